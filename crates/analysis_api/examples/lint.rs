@@ -0,0 +1,33 @@
+//! Prints the diagnostics of rust-analyzer for a Cargo project, like a small linter.
+//!
+//! Run it with `cargo run -p analysis_api --example lint -- path/to/project`.
+
+use std::{env, path::PathBuf};
+
+use analysis_api::Workspace;
+
+fn main() -> anyhow::Result<()> {
+    let path = env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+    let path = path.canonicalize()?;
+    let workspace = Workspace::load_cargo(&path)?;
+    let analysis = workspace.analysis();
+
+    let mut total = 0;
+    for file_id in workspace.local_files() {
+        let line_index = analysis.file_line_index(file_id)?;
+        for diagnostic in workspace.diagnostics(file_id)? {
+            let start = line_index.line_col(diagnostic.range.start());
+            println!(
+                "{}:{}:{}: {:?}: {}",
+                workspace.file_path(file_id),
+                start.line + 1,
+                start.col + 1,
+                diagnostic.severity,
+                diagnostic.message
+            );
+            total += 1;
+        }
+    }
+    println!("{} diagnostic(s)", total);
+    Ok(())
+}
@@ -0,0 +1,323 @@
+//! A stable entry point for tools embedding rust-analyzer's analysis, like code generators,
+//! custom linters or educational tools.
+//!
+//! The other crates of rust-analyzer are internal and change their API in every release. This
+//! crate is the exception: once published as `ra_ap_analysis_api`, the items defined here, and
+//! the re-exported `ide` types they use, are only changed in backwards compatible ways, and are
+//! deprecated for at least one release before being removed. The semantic model is re-exported
+//! as [`hir`] and the full analysis as [`Analysis`] for tools which need them, but their API is
+//! not covered by this guarantee.
+//!
+//! [`Workspace`] is where to start: it loads a Cargo project or in-memory sources, and computes
+//! diagnostics and assists. See `examples/lint.rs` for a small embedder.
+//!
+//! The crate also builds for `wasm32-unknown-unknown`, for tools running in the browser. There,
+//! Cargo projects can't be loaded, and the standard library has to be bundled with the tool and
+//! passed to [`Workspace::from_files_with_sysroot`].
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use std::sync::Arc;
+
+use cfg::CfgOptions;
+use ide::{AssistConfig, Change, CrateGraph, CrateId, DiagnosticsConfig, Edition, SourceRoot};
+use ide_db::{
+    base_db::{CrateDisplayName, CrateName, Env, FileSet, SourceDatabaseExt, VfsPath},
+    helpers::insert_use::{InsertUseConfig, MergeBehavior},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use load_cargo::{load_workspace_at, LoadCargoConfig};
+#[cfg(not(target_arch = "wasm32"))]
+use project_model::CargoConfig;
+
+pub use hir;
+pub use ide::{
+    Analysis, AnalysisHost, Assist, AssistId, AssistKind, Cancelable, Canceled, FileId,
+    FilePosition, FileRange, RootDatabase, SourceChange, TextRange, TextSize,
+};
+
+/// The semantic model of the analyzed code, see [`Workspace::semantics`].
+pub type Semantics<'db> = hir::Semantics<'db, RootDatabase>;
+
+/// The analyzed code and the files it consists of.
+pub struct Workspace {
+    host: AnalysisHost,
+    vfs: vfs::Vfs,
+}
+
+impl Workspace {
+    /// Loads the Cargo project at `path`, a directory with a `Cargo.toml` or the manifest
+    /// itself, together with its dependencies and the standard library.
+    ///
+    /// This runs `cargo metadata`, so it isn't available on `wasm32`. Build scripts aren't run
+    /// and procedural macros aren't expanded.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_cargo(path: &Path) -> anyhow::Result<Workspace> {
+        let config = LoadCargoConfig { load_out_dirs_from_check: false, with_proc_macro: false };
+        let (host, vfs) = load_workspace_at(path, &CargoConfig::default(), &config, &|_| {})?;
+        Ok(Workspace { host, vfs })
+    }
+
+    /// Creates a workspace of a single crate without dependencies from files given as pairs of
+    /// a path like `/lib.rs` and the text of the file. The first file is the crate root.
+    pub fn from_files<P, T>(files: impl IntoIterator<Item = (P, T)>) -> Workspace
+    where
+        P: Into<String>,
+        T: Into<String>,
+    {
+        Workspace::from_files_with_sysroot(files, Vec::<(String, String)>::new())
+    }
+
+    /// Like [`Workspace::from_files`], but the crate depends on the standard library, whose
+    /// sources are given instead of being read from the sysroot. This is how tools without a
+    /// file system, like ones running on `wasm32`, get `core`, `alloc` and `std`.
+    ///
+    /// The paths of the standard library files are relative to the `library` directory of the
+    /// `rust-src` component, like `core/src/lib.rs`.
+    pub fn from_files_with_sysroot<P, T, SP, ST>(
+        files: impl IntoIterator<Item = (P, T)>,
+        sysroot: impl IntoIterator<Item = (SP, ST)>,
+    ) -> Workspace
+    where
+        P: Into<String>,
+        T: Into<String>,
+        SP: Into<String>,
+        ST: Into<String>,
+    {
+        let mut vfs = vfs::Vfs::default();
+        let mut change = Change::new();
+        let files = files.into_iter().map(|(path, text)| (path.into(), text.into()));
+        let (local_files, root) = add_files(&mut vfs, &mut change, files);
+        let sysroot = sysroot.into_iter().map(|(path, text)| {
+            (format!("{}/{}", SYSROOT_DIR, path.into().trim_start_matches('/')), text.into())
+        });
+        let (sysroot_files, _) = add_files(&mut vfs, &mut change, sysroot);
+        change.set_roots(vec![
+            SourceRoot::new_local(local_files),
+            SourceRoot::new_library(sysroot_files),
+        ]);
+
+        let mut crate_graph = CrateGraph::default();
+        let mut sysroot_crates: Vec<(&str, CrateId)> = Vec::new();
+        for &(name, deps) in SYSROOT_CRATES {
+            let path = VfsPath::new_virtual_path(format!("{}/{}/src/lib.rs", SYSROOT_DIR, name));
+            let file_id = match vfs.file_id(&path) {
+                Some(it) => it,
+                None => continue,
+            };
+            let krate = crate_graph.add_crate_root(
+                file_id,
+                Edition::Edition2018,
+                Some(CrateDisplayName::from_canonical_name(name.to_string())),
+                CfgOptions::default(),
+                Env::default(),
+                Vec::new(),
+            );
+            for &(dep_name, dep) in sysroot_crates.iter().filter(|(it, _)| deps.contains(it)) {
+                let _ = crate_graph.add_dep(krate, CrateName::new(dep_name).unwrap(), dep);
+            }
+            sysroot_crates.push((name, krate));
+        }
+        if let Some(root) = root {
+            let krate = crate_graph.add_crate_root(
+                root,
+                Edition::Edition2018,
+                None,
+                CfgOptions::default(),
+                Env::default(),
+                Vec::new(),
+            );
+            for &(name, dep) in &sysroot_crates {
+                let _ = crate_graph.add_dep(krate, CrateName::new(name).unwrap(), dep);
+            }
+        }
+        change.set_crate_graph(crate_graph);
+
+        let mut host = AnalysisHost::default();
+        host.apply_change(change);
+        Workspace { host, vfs }
+    }
+
+    /// Returns an immutable snapshot of the analysis, which can be sent to other threads.
+    pub fn analysis(&self) -> Analysis {
+        self.host.analysis()
+    }
+
+    /// Returns the semantic model, to resolve names and compute types.
+    pub fn semantics(&self) -> Semantics<'_> {
+        hir::Semantics::new(self.host.raw_database())
+    }
+
+    /// Replaces the text of a file, like an editor does when the file is edited.
+    pub fn set_file_text(&mut self, file_id: FileId, text: String) {
+        let mut change = Change::new();
+        change.change_file(file_id, Some(Arc::new(text)));
+        self.host.apply_change(change);
+    }
+
+    /// Returns the files of the workspace members, without the files of dependencies.
+    pub fn local_files(&self) -> Vec<FileId> {
+        let db = self.host.raw_database();
+        self.vfs
+            .iter()
+            .map(|(file_id, _)| file_id)
+            .filter(|&file_id| !db.source_root(db.file_source_root(file_id)).is_library)
+            .collect()
+    }
+
+    /// Finds the file with the given path, either an absolute path for Cargo projects or the
+    /// path given to [`Workspace::from_files`].
+    pub fn file_id(&self, path: &str) -> Option<FileId> {
+        self.vfs.iter().find(|(_, it)| it.to_string() == path).map(|(file_id, _)| file_id)
+    }
+
+    pub fn file_path(&self, file_id: FileId) -> String {
+        self.vfs.file_path(file_id).to_string()
+    }
+
+    /// Computes the diagnostics of rust-analyzer for the file, including experimental ones.
+    pub fn diagnostics(&self, file_id: FileId) -> Cancelable<Vec<Diagnostic>> {
+        let diagnostics = self.analysis().diagnostics(&DiagnosticsConfig::default(), file_id)?;
+        Ok(diagnostics.into_iter().map(Diagnostic::new).collect())
+    }
+
+    /// Computes the assists applicable to the range, with the changes they make.
+    pub fn assists(&self, frange: FileRange) -> Cancelable<Vec<Assist>> {
+        let config = AssistConfig {
+            snippet_cap: None,
+            allowed: None,
+            insert_use: InsertUseConfig {
+                merge: Some(MergeBehavior::Full),
+                prefix_kind: hir::PrefixKind::Plain,
+            },
+        };
+        self.analysis().assists(&config, true, frange)
+    }
+}
+
+/// A diagnostic of rust-analyzer, see [`Workspace::diagnostics`].
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub range: TextRange,
+    pub severity: Severity,
+    /// The name of the diagnostic, like `unresolved-import`, which can be used to filter them.
+    pub code: Option<String>,
+    /// Whether the range is unused code, which editors usually render faded out.
+    pub unused: bool,
+    /// The fixes of the diagnostic, with a label to show to the user for each.
+    pub fixes: Vec<(String, SourceChange)>,
+}
+
+impl Diagnostic {
+    fn new(diagnostic: ide::Diagnostic) -> Diagnostic {
+        Diagnostic {
+            message: diagnostic.message,
+            range: diagnostic.range,
+            severity: match diagnostic.severity {
+                ide::Severity::Error => Severity::Error,
+                ide::Severity::WeakWarning => Severity::Warning,
+            },
+            code: diagnostic.code.map(|it| it.as_str().to_string()),
+            unused: diagnostic.unused,
+            fixes: diagnostic
+                .fixes
+                .unwrap_or_default()
+                .into_iter()
+                .map(|fix| (fix.label.to_string(), fix.source_change))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// The directory the files of the standard library passed to
+/// [`Workspace::from_files_with_sysroot`] are put in.
+const SYSROOT_DIR: &str = "/sysroot/library";
+
+/// The crates of the standard library, each with the ones it depends on, dependencies first.
+const SYSROOT_CRATES: &[(&str, &[&str])] =
+    &[("core", &[]), ("alloc", &["core"]), ("std", &["core", "alloc"])];
+
+/// Adds the files to the VFS and the change, returning them as a file set together with the
+/// first one.
+fn add_files(
+    vfs: &mut vfs::Vfs,
+    change: &mut Change,
+    files: impl Iterator<Item = (String, String)>,
+) -> (FileSet, Option<FileId>) {
+    let mut file_set = FileSet::default();
+    let mut first = None;
+    for (path, text) in files {
+        let path = VfsPath::new_virtual_path(path);
+        vfs.set_file_contents(path.clone(), Some(text.clone().into_bytes()));
+        let file_id = vfs.file_id(&path).unwrap();
+        file_set.insert(file_id, path);
+        change.change_file(file_id, Some(Arc::new(text)));
+        first.get_or_insert(file_id);
+    }
+    (file_set, first)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FileRange, Severity, TextRange, Workspace};
+
+    #[test]
+    fn in_memory_workspace() {
+        let mut workspace = Workspace::from_files(vec![
+            ("/lib.rs", "mod util;\nfn main() { let x = util::Answer; }\n"),
+            ("/util.rs", "pub struct Answer;\n"),
+        ]);
+        let lib = workspace.file_id("/lib.rs").unwrap();
+        assert_eq!(workspace.local_files().len(), 2);
+        assert_eq!(workspace.file_path(lib), "/lib.rs");
+
+        let diagnostics = workspace.diagnostics(lib).unwrap();
+        let messages = diagnostics.iter().map(|it| it.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, vec!["unused variable: `x`"]);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("unused-variable"));
+        assert_eq!(diagnostics[0].fixes[0].0, "Rename to `_x`");
+
+        let sema = workspace.semantics();
+        let module = sema.to_module_def(lib).unwrap();
+        let children = module.children(sema.db).filter_map(|it| it.name(sema.db));
+        assert_eq!(children.map(|it| it.to_string()).collect::<Vec<_>>(), vec!["util"]);
+
+        let range = TextRange::new(26.into(), 26.into());
+        let assists = workspace.assists(FileRange { file_id: lib, range }).unwrap();
+        assert!(assists.iter().any(|it| it.id.0 == "add_explicit_type"));
+
+        workspace.set_file_text(lib, "mod util;\nfn main() { let _x = util::Answer; }\n".into());
+        assert!(workspace.diagnostics(lib).unwrap().is_empty());
+    }
+
+    #[test]
+    fn bundled_sysroot() {
+        let workspace = Workspace::from_files_with_sysroot(
+            vec![("/main.rs", "fn main() { let _s: String = 92; }\n")],
+            vec![
+                ("core/src/lib.rs", "pub mod marker { pub trait Sized {} }\n"),
+                ("alloc/src/lib.rs", "pub mod string { pub struct String; }\n"),
+                (
+                    "std/src/lib.rs",
+                    "#[prelude_import]\nuse prelude::v1::*;\npub use alloc::string;\n\
+                     pub mod prelude { pub mod v1 { pub use crate::string::String; } }\n",
+                ),
+            ],
+        );
+        let main = workspace.file_id("/main.rs").unwrap();
+        assert_eq!(workspace.local_files(), vec![main]);
+        assert!(workspace.file_id("/sysroot/library/std/src/lib.rs").is_some());
+
+        let diagnostics = workspace.diagnostics(main).unwrap();
+        let messages = diagnostics.iter().map(|it| it.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, vec!["expected `String`, found `i32`"]);
+    }
+}
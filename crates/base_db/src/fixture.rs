@@ -64,6 +64,8 @@ use rustc_hash::FxHashMap;
 use test_utils::{
     extract_range_or_offset, Fixture, RangeOrOffset, CURSOR_MARKER, ESCAPED_CURSOR_MARKER,
 };
+
+pub use test_utils::extract_annotations;
 use vfs::{file_set::FileSet, VfsPath};
 
 use crate::{
@@ -146,6 +148,7 @@ impl ChangeFixture {
         let mut default_cfg = CfgOptions::default();
 
         let mut file_set = FileSet::default();
+        let mut library_file_set = FileSet::default();
         let source_root_prefix = "/".to_string();
         let mut file_id = FileId(0);
 
@@ -192,7 +195,11 @@ impl ChangeFixture {
 
             change.change_file(file_id, Some(Arc::new(text)));
             let path = VfsPath::new_virtual_path(meta.path);
-            file_set.insert(file_id, path.into());
+            if meta.library {
+                library_file_set.insert(file_id, path);
+            } else {
+                file_set.insert(file_id, path);
+            }
             files.push(file_id);
             file_id.0 += 1;
         }
@@ -215,7 +222,11 @@ impl ChangeFixture {
             }
         }
 
-        change.set_roots(vec![SourceRoot::new_local(file_set)]);
+        let mut roots = vec![SourceRoot::new_local(file_set)];
+        if library_file_set.iter().next().is_some() {
+            roots.push(SourceRoot::new_library(library_file_set));
+        }
+        change.set_roots(roots);
         change.set_crate_graph(crate_graph);
 
         ChangeFixture { file_position, files, change }
@@ -229,6 +240,7 @@ struct FileMeta {
     cfg: CfgOptions,
     edition: Edition,
     env: Env,
+    library: bool,
 }
 
 impl From<Fixture> for FileMeta {
@@ -247,6 +259,7 @@ impl From<Fixture> for FileMeta {
                 .as_ref()
                 .map_or(Edition::Edition2018, |v| Edition::from_str(&v).unwrap()),
             env: f.env.into_iter().collect(),
+            library: f.library,
         }
     }
 }
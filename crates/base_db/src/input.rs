@@ -309,21 +309,33 @@ impl CrateGraph {
         Some(crate_id)
     }
 
-    /// Extends this crate graph by adding a complete disjoint second crate
-    /// graph.
-    ///
-    /// The ids of the crates in the `other` graph are shifted by the return
-    /// amount.
-    pub fn extend(&mut self, other: CrateGraph) -> u32 {
-        let start = self.arena.len() as u32;
-        self.arena.extend(other.arena.into_iter().map(|(id, mut data)| {
-            let new_id = id.shift(start);
+    /// Adds the crates of `other` to the graph. The crates which are already in the graph with the
+    /// same data, like the dependencies shared by several workspaces, are reused instead of being
+    /// added a second time.
+    pub fn extend(&mut self, mut other: CrateGraph) {
+        let mut by_root: FxHashMap<FileId, Vec<CrateId>> = FxHashMap::default();
+        for (&id, data) in self.arena.iter() {
+            by_root.entry(data.root_file_id).or_default().push(id);
+        }
+
+        let mut mapping = FxHashMap::default();
+        for id in other.crates_in_topological_order() {
+            let mut data = other.arena.remove(&id).unwrap();
             for dep in &mut data.dependencies {
-                dep.crate_id = dep.crate_id.shift(start);
+                dep.crate_id = mapping[&dep.crate_id];
             }
-            (new_id, data)
-        }));
-        start
+            let candidates = by_root.entry(data.root_file_id).or_default();
+            let new_id = match candidates.iter().find(|it| self.arena[it] == data) {
+                Some(&it) => it,
+                None => {
+                    let new_id = CrateId(self.arena.len() as u32);
+                    candidates.push(new_id);
+                    self.arena.insert(new_id, data);
+                    new_id
+                }
+            };
+            mapping.insert(id, new_id);
+        }
     }
 
     fn dfs_find(&self, target: CrateId, from: CrateId, visited: &mut FxHashSet<CrateId>) -> bool {
@@ -375,12 +387,6 @@ impl ops::Index<CrateId> for CrateGraph {
     }
 }
 
-impl CrateId {
-    pub fn shift(self, amount: u32) -> CrateId {
-        CrateId(self.0 + amount)
-    }
-}
-
 impl CrateData {
     fn add_dep(&mut self, name: CrateName, crate_id: CrateId) {
         self.dependencies.push(Dependency { name, crate_id })
@@ -550,6 +556,41 @@ mod tests {
         assert!(graph.add_dep(crate2, CrateName::new("crate3").unwrap(), crate3).is_ok());
     }
 
+    #[test]
+    fn extend_reuses_shared_crates() {
+        fn workspace(member: u32) -> CrateGraph {
+            let mut graph = CrateGraph::default();
+            let member = graph.add_crate_root(
+                FileId(member),
+                Edition2018,
+                None,
+                CfgOptions::default(),
+                Env::default(),
+                Default::default(),
+            );
+            let dep = graph.add_crate_root(
+                FileId(10u32),
+                Edition2018,
+                None,
+                CfgOptions::default(),
+                Env::default(),
+                Default::default(),
+            );
+            graph.add_dep(member, CrateName::new("dep").unwrap(), dep).unwrap();
+            graph
+        }
+
+        let mut graph = CrateGraph::default();
+        graph.extend(workspace(1));
+        graph.extend(workspace(2));
+        assert_eq!(graph.iter().count(), 3);
+        let dep = graph.crate_id_for_crate_root(FileId(10u32)).unwrap();
+        for root in [FileId(1u32), FileId(2u32)].iter() {
+            let krate = graph.crate_id_for_crate_root(*root).unwrap();
+            assert_eq!(graph[krate].dependencies[0].crate_id, dep);
+        }
+    }
+
     #[test]
     fn dashes_are_normalized() {
         let mut graph = CrateGraph::default();
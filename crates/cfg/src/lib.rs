@@ -63,6 +63,16 @@ impl CfgDiff {
     pub fn len(&self) -> usize {
         self.enable.len() + self.disable.len()
     }
+
+    /// Returns the atoms enabled by this diff.
+    pub fn enabled(&self) -> &[CfgAtom] {
+        &self.enable
+    }
+
+    /// Returns the atoms disabled by this diff.
+    pub fn disabled(&self) -> &[CfgAtom] {
+        &self.disable
+    }
 }
 
 impl fmt::Display for CfgDiff {
@@ -6,7 +6,7 @@ use base_db::{CrateDisplayName, CrateId, Edition, FileId};
 use either::Either;
 use hir_def::{
     adt::{ReprKind, StructKind, VariantData},
-    expr::{BindingAnnotation, LabelId, Pat, PatId},
+    expr::{BindingAnnotation, ExprId, LabelId, Pat, PatId},
     import_map,
     item_tree::ItemTreeNode,
     lang_item::LangItemTarget,
@@ -28,6 +28,7 @@ use hir_expand::{
 };
 use hir_ty::{
     autoderef,
+    closure::{closure_captures, CaptureKind},
     display::{write_bounds_like_dyn_trait_with_prefix, HirDisplayError, HirFormatter},
     method_resolution,
     traits::{FnTrait, Solution, SolutionVariables},
@@ -39,14 +40,14 @@ use rustc_hash::FxHashSet;
 use stdx::{format_to, impl_from};
 use syntax::{
     ast::{self, AttrsOwner, NameOwner},
-    AstNode, SmolStr,
+    AstNode, AstPtr, SmolStr,
 };
 use tt::{Ident, Leaf, Literal, TokenTree};
 
 use crate::{
     db::{DefDatabase, HirDatabase},
     has_source::HasSource,
-    HirDisplay, InFile, Name,
+    HirDisplay, InFile, Name, PanicKind,
 };
 
 /// hir::Crate describes a single crate. It's the main interface with which
@@ -93,6 +94,11 @@ impl Crate {
         Module { id: def_map.module_id(def_map.root()) }
     }
 
+    /// The module whose items are in scope everywhere in the crate, like `std::prelude::v1`.
+    pub fn prelude(self, db: &dyn HirDatabase) -> Option<Module> {
+        db.crate_def_map(self.id).prelude().map(|id| Module { id })
+    }
+
     pub fn root_file(self, db: &dyn HirDatabase) -> FileId {
         db.crate_graph()[self.id].root_file_id
     }
@@ -357,6 +363,20 @@ impl Module {
             .collect()
     }
 
+    /// Returns the names in the module's scope which are visible from other crates, including
+    /// public re-exports.
+    pub fn exported_scope(self, db: &dyn HirDatabase) -> Vec<(Name, ScopeDef)> {
+        self.id.def_map(db.upcast())[self.id.local_id]
+            .scope
+            .entries()
+            .map(|(name, def)| (name, def.filter_visibility(|vis| vis == Visibility::Public)))
+            .filter(|(_, def)| !def.is_none())
+            .flat_map(|(name, def)| {
+                ScopeDef::all_items(def).into_iter().map(move |item| (name.clone(), item))
+            })
+            .collect()
+    }
+
     pub fn visibility_of(self, db: &dyn HirDatabase, def: &ModuleDef) -> Option<Visibility> {
         self.id.def_map(db.upcast())[self.id.local_id].scope.visibility_of(def.clone().into())
     }
@@ -383,6 +403,7 @@ impl Module {
         }
 
         for impl_def in self.impl_defs(db) {
+            hir_ty::diagnostics::validate_impl(db, impl_def.id, sink);
             for item in impl_def.items(db) {
                 if let AssocItem::Function(f) = item {
                     f.diagnostics(db, sink);
@@ -727,6 +748,18 @@ impl DefWithBody {
             DefWithBody::Const(c) => c.name(db),
         }
     }
+
+    /// Returns the expressions of this body which may panic at runtime.
+    pub fn possible_panics(
+        self,
+        db: &dyn HirDatabase,
+    ) -> Vec<(InFile<AstPtr<ast::Expr>>, PanicKind)> {
+        let (_, source_map) = db.body_with_source_map(self.into());
+        hir_ty::panics::possible_panics(db, self.into())
+            .into_iter()
+            .filter_map(|(id, kind)| Some((source_map.expr_syntax(id).ok()?, kind)))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -897,6 +930,14 @@ impl Const {
     pub fn name(self, db: &dyn HirDatabase) -> Option<Name> {
         db.const_data(self.id).name.clone()
     }
+
+    pub fn ty(self, db: &dyn HirDatabase) -> Type {
+        let resolver = self.id.resolver(db.upcast());
+        let ctx = hir_ty::TyLoweringContext::new(db, &resolver);
+        let environment = TraitEnvironment::lower(db, &resolver);
+        let ty = Ty::from_hir(&ctx, &db.const_data(self.id).type_ref);
+        Type { krate: self.module(db).id.krate(), ty: InEnvironment { value: ty, environment } }
+    }
 }
 
 impl HasVisibility for Const {
@@ -928,6 +969,14 @@ impl Static {
     pub fn is_mut(self, db: &dyn HirDatabase) -> bool {
         db.static_data(self.id).mutable
     }
+
+    pub fn ty(self, db: &dyn HirDatabase) -> Type {
+        let resolver = self.id.resolver(db.upcast());
+        let ctx = hir_ty::TyLoweringContext::new(db, &resolver);
+        let environment = TraitEnvironment::lower(db, &resolver);
+        let ty = Ty::from_hir(&ctx, &db.static_data(self.id).type_ref);
+        Type { krate: self.module(db).id.krate(), ty: InEnvironment { value: ty, environment } }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1294,6 +1343,56 @@ impl Label {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Closure {
+    pub(crate) parent: DefWithBodyId,
+    pub(crate) expr_id: ExprId,
+}
+
+impl Closure {
+    pub fn parent(self, _db: &dyn HirDatabase) -> DefWithBody {
+        self.parent.into()
+    }
+
+    /// The locals of the enclosing body the closure uses, in declaration order.
+    pub fn captures(self, db: &dyn HirDatabase) -> Vec<ClosureCapture> {
+        closure_captures(db, self.parent, self.expr_id)
+            .into_iter()
+            .map(|it| ClosureCapture {
+                local: Local { parent: self.parent, pat_id: it.pat },
+                kind: it.kind,
+            })
+            .collect()
+    }
+
+    pub fn source(self, db: &dyn HirDatabase) -> Option<InFile<ast::ClosureExpr>> {
+        let (_body, source_map) = db.body_with_source_map(self.parent);
+        let src = source_map.expr_syntax(self.expr_id).ok()?;
+        let root = src.file_syntax(db.upcast());
+        src.map(|ast| match ast.to_node(&root) {
+            ast::Expr::ClosureExpr(it) => Some(it),
+            _ => None,
+        })
+        .transpose()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClosureCapture {
+    local: Local,
+    kind: CaptureKind,
+}
+
+impl ClosureCapture {
+    pub fn local(&self) -> Local {
+        self.local
+    }
+
+    pub fn kind(&self) -> CaptureKind {
+        self.kind
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum GenericParam {
     TypeParam(TypeParam),
@@ -1454,6 +1553,12 @@ impl Impl {
         db.impl_data(self.id).target_trait.clone()
     }
 
+    /// Returns the implemented trait, if this is a trait impl.
+    pub fn trait_(self, db: &dyn HirDatabase) -> Option<Trait> {
+        let trait_ref = db.impl_trait(self.id)?;
+        Some(Trait { id: trait_ref.value.trait_ })
+    }
+
     pub fn target_ty(self, db: &dyn HirDatabase) -> Type {
         let impl_data = db.impl_data(self.id);
         let resolver = self.id.resolver(db.upcast());
@@ -1569,6 +1674,16 @@ impl Type {
         matches!(self.ty.value, Ty::Unknown)
     }
 
+    pub fn is_never(&self) -> bool {
+        matches!(self.ty.value, Ty::Never)
+    }
+
+    /// Checks whether `self` and `other` are the same type, regardless of the environments in
+    /// which they were found.
+    pub fn is_same_type(&self, other: &Type) -> bool {
+        self.ty.value == other.ty.value
+    }
+
     /// Checks that particular type `ty` implements `std::future::Future`.
     /// This function is used in `.await` syntax completion.
     pub fn impls_future(&self, db: &dyn HirDatabase) -> bool {
@@ -1712,6 +1827,22 @@ impl Type {
         matches!(&self.ty.value, Ty::RawPtr(..))
     }
 
+    pub fn is_mutable_raw_ptr(&self) -> bool {
+        matches!(&self.ty.value, Ty::RawPtr(Mutability::Mut, ..))
+    }
+
+    pub fn is_tuple(&self) -> bool {
+        matches!(&self.ty.value, Ty::Tuple(..))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(&self.ty.value, Ty::Array(..))
+    }
+
+    pub fn is_slice(&self) -> bool {
+        matches!(&self.ty.value, Ty::Slice(..))
+    }
+
     pub fn contains_unknown(&self) -> bool {
         return go(&self.ty.value);
 
@@ -1855,6 +1986,13 @@ impl Type {
         Some(adt.into())
     }
 
+    pub fn as_closure(&self) -> Option<Closure> {
+        match &self.ty.value {
+            Ty::Closure(parent, expr_id, _) => Some(Closure { parent: *parent, expr_id: *expr_id }),
+            _ => None,
+        }
+    }
+
     pub fn as_dyn_trait(&self) -> Option<Trait> {
         self.ty.value.dyn_trait().map(Into::into)
     }
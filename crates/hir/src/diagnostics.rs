@@ -1,11 +1,20 @@
 //! FIXME: write short doc here
 pub use hir_def::diagnostics::{
-    InactiveCode, UnresolvedMacroCall, UnresolvedModule, UnresolvedProcMacro,
+    DuplicateDefinition, InactiveCode, UnresolvedImport, UnresolvedMacroCall, UnresolvedModule,
+    UnresolvedProcMacro,
 };
 pub use hir_expand::diagnostics::{
     Diagnostic, DiagnosticCode, DiagnosticSink, DiagnosticSinkBuilder,
 };
-pub use hir_ty::diagnostics::{
-    IncorrectCase, MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkOrSomeInTailExpr,
-    NoSuchField, RemoveThisSemicolon, ReplaceFilterMapNextWithFindMap,
+pub use hir_ty::{
+    conversion::Conversion,
+    diagnostics::{
+        AwaitOutsideOfAsync, IncorrectCase, MismatchedArgCount, MismatchedTupleStructPatArgCount,
+        MissingErrorConversion, MissingFields, MissingImplItems, MissingMatchArms,
+        MissingOkOrSomeInTailExpr, MissingUnsafe, NoSuchField, NonExhaustiveRecordLiteral,
+        OrphanImpl, PrivateItem, PrivateItemKind, RecursiveType, RemoveThisSemicolon,
+        ReplaceFilterMapNextWithFindMap, ShadowedLabel, TryInNonTryFn, TypeMismatch,
+        UnnecessaryUnsafe, UnreachableMatchArm, UnresolvedField, UnresolvedMethodCall,
+        UnusedVariable,
+    },
 };
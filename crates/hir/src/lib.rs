@@ -33,11 +33,11 @@ mod has_source;
 pub use crate::{
     attrs::{HasAttrs, Namespace},
     code_model::{
-        Access, Adt, AsAssocItem, AssocItem, AssocItemContainer, Callable, CallableKind, Const,
-        ConstParam, Crate, CrateDependency, DefWithBody, Enum, Field, FieldSource, Function,
-        GenericDef, GenericParam, HasVisibility, Impl, Label, LifetimeParam, Local, MacroDef,
-        Module, ModuleDef, ScopeDef, Static, Struct, Trait, Type, TypeAlias, TypeParam, Union,
-        Variant, VariantDef,
+        Access, Adt, AsAssocItem, AssocItem, AssocItemContainer, Callable, CallableKind, Closure,
+        ClosureCapture, Const, ConstParam, Crate, CrateDependency, DefWithBody, Enum, Field,
+        FieldSource, Function, GenericDef, GenericParam, HasVisibility, Impl, Label, LifetimeParam,
+        Local, MacroDef, Module, ModuleDef, ScopeDef, Static, Struct, Trait, Type, TypeAlias,
+        TypeParam, Union, Variant, VariantDef,
     },
     has_source::HasSource,
     semantics::{PathResolution, Semantics, SemanticsScope},
@@ -45,7 +45,7 @@ pub use crate::{
 
 pub use hir_def::{
     adt::StructKind,
-    attr::{Attrs, Documentation},
+    attr::{Attrs, Documentation, LintLevel},
     body::scope::ExprScopes,
     builtin_type::BuiltinType,
     find_path::PrefixKind,
@@ -61,7 +61,11 @@ pub use hir_expand::{
     ExpandResult, HirFileId, InFile, MacroCallId, MacroCallLoc, /* FIXME */ MacroDefId,
     MacroFile, Origin,
 };
-pub use hir_ty::display::HirDisplay;
+pub use hir_ty::{
+    closure::CaptureKind,
+    display::{ClosureStyle, HirDisplay},
+    panics::PanicKind,
+};
 
 // These are negative re-exports: pub using these names is forbidden, they
 // should remain private to hir internals.
@@ -5,18 +5,19 @@ mod source_to_def;
 use std::{cell::RefCell, fmt, iter::successors};
 
 use base_db::{FileId, FileRange};
+use cfg::DnfExpr;
 use hir_def::{
     resolver::{self, HasResolver, Resolver, TypeNs},
     AsMacroCall, FunctionId, TraitId, VariantId,
 };
-use hir_expand::{hygiene::Hygiene, name::AsName, ExpansionInfo};
+use hir_expand::{hygiene::Hygiene, name::AsName, ExpansionInfo, Origin};
 use hir_ty::associated_type_shorthand_candidates;
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::{
     algo::find_node_at_offset,
     ast::{self, GenericParamsOwner, LoopBodyOwner},
-    match_ast, AstNode, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextSize,
+    match_ast, AstNode, SyntaxKind, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextRange, TextSize,
 };
 
 use crate::{
@@ -24,7 +25,7 @@ use crate::{
     db::HirDatabase,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
-    AssocItem, Callable, ConstParam, Crate, Field, Function, HirFileId, Impl, InFile, Label,
+    AssocItem, Attrs, Callable, ConstParam, Crate, Field, Function, HirFileId, Impl, InFile, Label,
     LifetimeParam, Local, MacroDef, Module, ModuleDef, Name, Path, ScopeDef, Trait, Type,
     TypeAlias, TypeParam, VariantDef,
 };
@@ -128,6 +129,17 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.descend_into_macros(token)
     }
 
+    /// Maps a range of the definition of the `macro_rules!` macro called by `macro_call` to the
+    /// arguments of the call which the expansion substitutes within the range. Returns `None`
+    /// if no argument ends up there.
+    pub fn macro_call_range_from_def(
+        &self,
+        macro_call: &ast::MacroCall,
+        def_range: FileRange,
+    ) -> Option<TextRange> {
+        self.imp.macro_call_range_from_def(macro_call, def_range)
+    }
+
     pub fn descend_node_at_offset<N: ast::AstNode>(
         &self,
         node: &SyntaxNode,
@@ -290,6 +302,12 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
     pub fn is_unsafe_ident_pat(&self, ident_pat: &ast::IdentPat) -> bool {
         self.imp.is_unsafe_ident_pat(ident_pat)
     }
+
+    /// If the `#[cfg]` attributes of `node` disable it in the crate it belongs to, returns a
+    /// description of the failing condition. Attributes of the ancestors are not considered.
+    pub fn inactive_cfg(&self, node: &SyntaxNode) -> Option<String> {
+        self.imp.inactive_cfg(node)
+    }
 }
 
 impl<'db> SemanticsImpl<'db> {
@@ -367,6 +385,59 @@ impl<'db> SemanticsImpl<'db> {
         token.value
     }
 
+    fn macro_call_range_from_def(
+        &self,
+        macro_call: &ast::MacroCall,
+        def_range: FileRange,
+    ) -> Option<TextRange> {
+        let sa = self.analyze(macro_call.syntax());
+        let file_id = sa.expand(self.db, InFile::new(sa.file_id, macro_call))?;
+        let expanded = self.db.parse_or_expand(file_id)?;
+        let mut cache = self.expansion_info_cache.borrow_mut();
+        let info = cache
+            .entry(file_id)
+            .or_insert_with(|| file_id.expansion_info(self.db.upcast()))
+            .as_ref()?;
+
+        // The tokens of the expansion, in order, with the range they come from, either in the
+        // definition or in the arguments.
+        let origins = expanded
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter_map(|token| info.map_token_up(InFile::new(file_id, &token)))
+            .map(|(token, origin)| {
+                let file = token.file_id.original_file(self.db.upcast());
+                (file, token.value.text_range(), origin)
+            })
+            .collect::<Vec<_>>();
+        let in_def_range = |(file, range, origin): &(FileId, TextRange, Origin)| {
+            *origin == Origin::Def
+                && *file == def_range.file_id
+                && def_range.range.contains_range(*range)
+        };
+        let mut first = origins.iter().position(in_def_range)?;
+        let mut last = origins.iter().rposition(in_def_range)?;
+        // Metavariables at the start or the end of the range are substituted by the arguments
+        // right before the first or after the last token of the definition.
+        if def_range.range.start() < origins[first].1.start() {
+            while first > 0 && origins[first - 1].2 == Origin::Call {
+                first -= 1;
+            }
+        }
+        if origins[last].1.end() < def_range.range.end() {
+            while last + 1 < origins.len() && origins[last + 1].2 == Origin::Call {
+                last += 1;
+            }
+        }
+        origins[first..=last]
+            .iter()
+            .filter(|(_, _, origin)| *origin == Origin::Call)
+            .map(|(_, range, _)| *range)
+            .fold(None, |acc: Option<TextRange>, range| {
+                Some(acc.map_or(range, |acc| acc.cover(range)))
+            })
+    }
+
     fn descend_node_at_offset(
         &self,
         node: &SyntaxNode,
@@ -710,6 +781,27 @@ impl<'db> SemanticsImpl<'db> {
             .map(|ty| ty.is_packed(self.db))
             .unwrap_or(false)
     }
+
+    fn inactive_cfg(&self, node: &SyntaxNode) -> Option<String> {
+        // Avoid looking up the crate for the vast majority of nodes without attributes.
+        if !node.children().any(|it| it.kind() == SyntaxKind::ATTR) {
+            return None;
+        }
+        let file = self.find_file(node.clone());
+        let krate = self.to_module_def(file.file_id.original_file(self.db.upcast()))?.krate();
+        let attrs = Attrs::from_syntax(self.db.upcast(), file.as_ref(), krate.id);
+        let cfg = attrs.cfg()?;
+        let cfg_options = &self.db.crate_graph()[krate.id].cfg_options;
+        if cfg_options.check(&cfg) != Some(false) {
+            return None;
+        }
+        let cfg = DnfExpr::new(cfg);
+        let reason = match cfg.why_inactive(cfg_options) {
+            Some(reason) => reason.to_string(),
+            None => cfg.to_string(),
+        };
+        Some(reason)
+    }
 }
 
 pub trait ToDef: AstNode + Clone {
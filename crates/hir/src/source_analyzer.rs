@@ -19,6 +19,7 @@ use hir_def::{
 };
 use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
 use hir_ty::{
+    associated_type_shorthand_candidates,
     diagnostics::{record_literal_missing_fields, record_pattern_missing_fields},
     InferenceResult, Substs,
 };
@@ -479,6 +480,27 @@ fn resolve_hir_path_(
         })
     };
 
+    // `T::Item` or `<S as Trait>::Item`, where the last segment names an associated type of the
+    // trait or of the bounds of the type the qualifier resolves to.
+    let assoc_types = || {
+        if path.type_anchor().is_some() {
+            return None;
+        }
+        let (ty, unresolved) = resolver.resolve_path_in_type_ns(db.upcast(), path.mod_path())?;
+        let segments = path.mod_path().segments();
+        if unresolved? + 1 != segments.len() {
+            return None;
+        }
+        let name = segments.last()?;
+        let type_alias = match ty {
+            TypeNs::TraitId(it) => db.trait_data(it).associated_type_by_name(name),
+            ty => associated_type_shorthand_candidates(db, ty, |candidate, _, id| {
+                (candidate == name).then(|| id)
+            }),
+        }?;
+        Some(PathResolution::Def(TypeAlias::from(type_alias).into()))
+    };
+
     let body_owner = resolver.body_owner();
     let values = || {
         resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path()).and_then(|val| {
@@ -513,6 +535,7 @@ fn resolve_hir_path_(
     };
 
     if prefer_value_ns { values().or_else(types) } else { types().or_else(values) }
+        .or_else(assoc_types)
         .or_else(items)
         .or_else(macros)
 }
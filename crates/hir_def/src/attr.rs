@@ -304,6 +304,25 @@ impl Attrs {
         Arc::new(res)
     }
 
+    /// Lowers the attributes written directly on `node`, expanding `cfg_attr`s for `krate`.
+    ///
+    /// This is meant for syntax which doesn't correspond to a definition, like statements or
+    /// expressions. Doc comments are not included.
+    pub fn from_syntax(db: &dyn DefDatabase, node: InFile<&SyntaxNode>, krate: CrateId) -> Attrs {
+        let hygiene = Hygiene::new(db.upcast(), node.file_id);
+        let attrs: Vec<_> = node
+            .value
+            .children()
+            .filter_map(ast::Attr::cast)
+            .enumerate()
+            .filter_map(|(i, attr)| {
+                Attr::from_src(attr, &hygiene).map(|attr| Attr { index: i as u32, ..attr })
+            })
+            .collect();
+        let entries = if attrs.is_empty() { None } else { Some(attrs.into()) };
+        RawAttrs { entries }.filter(db, krate)
+    }
+
     pub fn by_key(&self, key: &'static str) -> AttrQuery<'_> {
         AttrQuery { attrs: self, key }
     }
@@ -323,6 +342,32 @@ impl Attrs {
         }
     }
 
+    /// The level the lint level attributes among these, like `#[allow(..)]`, set for `lint`. A
+    /// later attribute overrides an earlier one, unless the earlier one forbids the lint.
+    pub fn lint_level(&self, lint: &str) -> Option<LintLevel> {
+        self.iter()
+            .filter_map(|attr| {
+                let name = attr.path.as_ident()?.to_string();
+                match attr.input.as_ref()? {
+                    AttrInput::TokenTree(tt) => LintLevel::of_attr(&name, &tt.to_string(), lint),
+                    AttrInput::Literal(_) => None,
+                }
+            })
+            .fold(None, |acc, level| match acc {
+                Some(LintLevel::Forbid) => acc,
+                _ => Some(level),
+            })
+    }
+
+    /// Whether the item is marked `#[doc(hidden)]`.
+    pub fn is_doc_hidden(&self) -> bool {
+        self.by_key("doc").tt_values().any(|tt| {
+            tt.token_trees.iter().any(|tree| {
+                matches!(tree, tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) if ident.text == "hidden")
+            })
+        })
+    }
+
     pub fn docs(&self) -> Option<Documentation> {
         let docs = self.by_key("doc").attrs().flat_map(|attr| match attr.input.as_ref()? {
             AttrInput::Literal(s) => Some(s),
@@ -376,6 +421,51 @@ fn inner_attributes(
     Some((attrs, docs))
 }
 
+/// Lint groups and the lints of native diagnostics they contain. All lints are
+/// also part of the `warnings` group.
+const LINT_GROUPS: &[(&str, &[&str])] = &[
+    ("unused", &["dead_code", "unreachable_patterns", "unused_unsafe", "unused_variables"]),
+    ("nonstandard_style", &["non_camel_case_types", "non_snake_case", "non_upper_case_globals"]),
+    ("clippy::all", &["clippy::redundant_field_names"]),
+    ("clippy::pedantic", &["clippy::filter_map_next"]),
+];
+
+/// The level a lint level attribute, like `#[allow(..)]`, sets for the lints it mentions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+}
+
+impl LintLevel {
+    /// Returns the level the attribute `#[name args]` sets for `lint`, if it is a lint level
+    /// attribute mentioning the lint or a group containing it. `args` is the text of the token
+    /// tree, like `(unused, clippy::all)`.
+    pub fn of_attr(name: &str, args: &str, lint: &str) -> Option<LintLevel> {
+        let level = match name {
+            "allow" => LintLevel::Allow,
+            "warn" => LintLevel::Warn,
+            "deny" => LintLevel::Deny,
+            "forbid" => LintLevel::Forbid,
+            _ => return None,
+        };
+        let args = args.replace(char::is_whitespace, "");
+        let args = args.trim_start_matches('(').trim_end_matches(')');
+        let mentions_lint = args.split(',').any(|name| {
+            name == lint
+                || name == "warnings"
+                || LINT_GROUPS.iter().any(|(group, lints)| *group == name && lints.contains(&lint))
+        });
+        if mentions_lint {
+            Some(level)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Attr {
     index: u32,
@@ -1,7 +1,7 @@
 //! Transforms `ast::Expr` into an equivalent `hir_def::expr::Expr`
 //! representation.
 
-use std::{any::type_name, mem, sync::Arc};
+use std::{any::type_name, convert::TryFrom, mem, sync::Arc};
 
 use either::Either;
 use hir_expand::{
@@ -486,7 +486,11 @@ impl ExprCollector<'_> {
                 let ret_type =
                     e.ret_type().and_then(|r| r.ty()).map(|it| TypeRef::from_ast(&self.ctx(), it));
                 let body = self.collect_expr_opt(e.body());
-                self.alloc_expr(Expr::Lambda { args, arg_types, ret_type, body }, syntax_ptr)
+                let is_move = e.move_token().is_some();
+                self.alloc_expr(
+                    Expr::Lambda { args, arg_types, ret_type, body, is_move },
+                    syntax_ptr,
+                )
             }
             ast::Expr::BinExpr(e) => {
                 let lhs = self.collect_expr_opt(e.lhs());
@@ -502,6 +506,9 @@ impl ExprCollector<'_> {
                 let expr = self.collect_expr_opt(e.expr());
                 self.alloc_expr(Expr::Box { expr }, syntax_ptr)
             }
+            // `_` only has a meaning as the target of a destructuring assignment, which isn't
+            // supported yet.
+            ast::Expr::UnderscoreExpr(_) => self.alloc_expr(Expr::Missing, syntax_ptr),
 
             ast::Expr::ArrayExpr(e) => {
                 let kind = e.kind();
@@ -1065,15 +1072,16 @@ impl From<ast::LiteralKind> for Literal {
     fn from(ast_lit_kind: ast::LiteralKind) -> Self {
         match ast_lit_kind {
             LiteralKind::IntNumber(lit) => {
+                let value = lit.value().map_or(0, |it| u64::try_from(it).unwrap_or(u64::MAX));
                 if let builtin @ Some(_) = lit.suffix().and_then(BuiltinFloat::from_suffix) {
                     return Literal::Float(Default::default(), builtin);
                 } else if let builtin @ Some(_) =
                     lit.suffix().and_then(|it| BuiltinInt::from_suffix(&it))
                 {
-                    Literal::Int(Default::default(), builtin)
+                    Literal::Int(value, builtin)
                 } else {
                     let builtin = lit.suffix().and_then(|it| BuiltinUint::from_suffix(&it));
-                    Literal::Uint(Default::default(), builtin)
+                    Literal::Uint(value, builtin)
                 }
             }
             LiteralKind::FloatNumber(lit) => {
@@ -27,6 +27,7 @@ pub struct FunctionData {
     pub has_self_param: bool,
     pub has_body: bool,
     pub is_unsafe: bool,
+    pub is_async: bool,
     pub is_varargs: bool,
     pub is_extern: bool,
     pub visibility: RawVisibility,
@@ -47,6 +48,7 @@ impl FunctionData {
             has_self_param: func.has_self_param,
             has_body: func.has_body,
             is_unsafe: func.is_unsafe,
+            is_async: func.is_async,
             is_varargs: func.is_varargs,
             is_extern: func.is_extern,
             visibility: item_tree[func.visibility].clone(),
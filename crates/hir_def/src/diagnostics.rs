@@ -3,7 +3,7 @@
 use std::any::Any;
 use stdx::format_to;
 
-use cfg::{CfgExpr, CfgOptions, DnfExpr};
+use cfg::{CfgAtom, CfgExpr, CfgOptions, DnfExpr};
 use hir_expand::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSink};
 use hir_expand::{HirFileId, InFile};
 use syntax::{ast, AstPtr, SyntaxNodePtr, TextRange};
@@ -134,10 +134,37 @@ pub struct InactiveCode {
     pub opts: CfgOptions,
 }
 
+impl InactiveCode {
+    /// Returns the Cargo features whose enabling would make the code active, if enabling
+    /// features is all it takes.
+    pub fn features_to_enable(&self) -> Vec<String> {
+        let dnf = DnfExpr::new(self.cfg.clone());
+        dnf.compute_enable_hints(&self.opts)
+            .filter(|diff| diff.disabled().is_empty())
+            .filter_map(|diff| {
+                diff.enabled()
+                    .iter()
+                    .map(|atom| match atom {
+                        CfgAtom::KeyValue { key, value } if key == "feature" => {
+                            Some(value.to_string())
+                        }
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+            })
+            .min_by_key(|features| features.len())
+            .unwrap_or_default()
+    }
+}
+
 impl Diagnostic for InactiveCode {
     fn code(&self) -> DiagnosticCode {
         DiagnosticCode("inactive-code")
     }
+    fn lint(&self) -> Option<&'static str> {
+        // There's no lint for code disabled by `#[cfg]`, but it is dead code to the compiler.
+        Some("dead_code")
+    }
     fn message(&self) -> String {
         let inactive = DnfExpr::new(self.cfg.clone()).why_inactive(&self.opts);
         let mut buf = "code is inactive due to #[cfg] directives".to_string();
@@ -224,3 +251,42 @@ impl Diagnostic for MacroError {
         true
     }
 }
+
+// Diagnostic: duplicate-definition
+//
+// This diagnostic is triggered if an item has the same name as another item of the same
+// namespace in the module, or if a field or a variant has the same name as another one of the
+// type.
+//
+// ```rust
+// struct S;
+// fn S() {}
+// ```
+#[derive(Debug)]
+pub struct DuplicateDefinition {
+    pub file: HirFileId,
+    pub name: AstPtr<ast::Name>,
+    /// The name of the first definition.
+    pub first: InFile<AstPtr<ast::Name>>,
+    pub text: String,
+    pub is_field: bool,
+}
+
+impl Diagnostic for DuplicateDefinition {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("duplicate-definition")
+    }
+    fn message(&self) -> String {
+        if self.is_field {
+            format!("field `{}` is already declared", self.text)
+        } else {
+            format!("the name `{}` is defined multiple times", self.text)
+        }
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.name.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
@@ -164,6 +164,7 @@ pub enum Expr {
         arg_types: Vec<Option<TypeRef>>,
         ret_type: Option<TypeRef>,
         body: ExprId,
+        is_move: bool,
     },
     Tuple {
         exprs: Vec<ExprId>,
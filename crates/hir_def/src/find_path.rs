@@ -285,10 +285,17 @@ fn find_local_import_locations(
     let data = &def_map[from.local_id];
     let mut worklist =
         data.children.values().map(|child| def_map.module_id(*child)).collect::<Vec<_>>();
-    let mut parent = data.parent;
-    while let Some(p) = parent {
-        worklist.push(def_map.module_id(p));
-        parent = def_map[p].parent;
+    // A block can also refer to anything the module containing it can.
+    let mut current = Some(from);
+    while let Some(module) = current {
+        let def_map = module.def_map(db);
+        let mut parent = def_map[module.local_id].parent;
+        while let Some(p) = parent {
+            worklist.push(def_map.module_id(p));
+            parent = def_map[p].parent;
+        }
+        current = def_map.parent();
+        worklist.extend(current);
     }
 
     let mut seen: FxHashSet<_> = FxHashSet::default();
@@ -300,7 +307,7 @@ fn find_local_import_locations(
         }
 
         let ext_def_map;
-        let data = if module.krate == from.krate {
+        let data = if module.krate == from.krate && module.block == from.block {
             &def_map[module.local_id]
         } else {
             // The crate might reexport a module defined in another crate.
@@ -846,6 +853,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn outer_items_from_inner_scope() {
+        check_found_path(
+            r#"
+            mod module {
+                pub struct Struct {}
+            }
+            fn main() {
+                struct Inner {}
+                $0
+            }
+        "#,
+            "module::Struct",
+            "module::Struct",
+            "module::Struct",
+            "module::Struct",
+        );
+    }
+
     #[test]
     #[ignore]
     fn inner_items_from_parent_module() {
@@ -1,6 +1,6 @@
 //! A map of all publicly exported items in a crate.
 
-use std::{cmp::Ordering, fmt, hash::BuildHasherDefault, sync::Arc};
+use std::{cmp::Ordering, fmt, hash::BuildHasherDefault, sync::Arc, time::Instant};
 
 use base_db::CrateId;
 use fst::{self, Streamer};
@@ -276,6 +276,9 @@ pub enum SearchMode {
     /// Import map entry should contain all letters from the query string,
     /// in the same order, but not necessary adjacent.
     Fuzzy,
+    /// Import map entry should differ from the query string by a few characters at most, like
+    /// a correction of a typo in it.
+    Typo,
 }
 
 #[derive(Debug)]
@@ -288,6 +291,7 @@ pub struct Query {
     case_sensitive: bool,
     limit: usize,
     exclude_import_kinds: FxHashSet<ImportKind>,
+    deadline: Option<Instant>,
 }
 
 impl Query {
@@ -302,6 +306,7 @@ impl Query {
             case_sensitive: false,
             limit: usize::max_value(),
             exclude_import_kinds: FxHashSet::default(),
+            deadline: None,
         }
     }
 
@@ -338,6 +343,11 @@ impl Query {
         self
     }
 
+    /// Stops the search once `deadline` has passed, returning the items found so far.
+    pub fn deadline(self, deadline: Instant) -> Self {
+        Self { deadline: Some(deadline), ..self }
+    }
+
     fn import_matches(&self, import: &ImportInfo, enforce_lowercase: bool) -> bool {
         if import.is_trait_assoc_item {
             if self.exclude_import_kinds.contains(&ImportKind::AssociatedItem) {
@@ -377,6 +387,7 @@ impl Query {
                 }
                 mismatching_query_char.is_none()
             }
+            SearchMode::Typo => stdx::is_typo_of(&input, query_string),
         }
     }
 }
@@ -399,12 +410,21 @@ pub fn search_dependencies<'a>(
 
     let mut op = fst::map::OpBuilder::new();
     for map in &import_maps {
-        op = op.add(map.fst.search(&automaton));
+        op = match query.search_mode {
+            // A typo might have dropped or replaced any character, so there is nothing to narrow
+            // the search with.
+            SearchMode::Typo => op.add(map.fst.search(fst::automaton::AlwaysMatch)),
+            _ => op.add(map.fst.search(&automaton)),
+        };
     }
 
     let mut stream = op.union();
     let mut res = Vec::new();
     while let Some((_, indexed_values)) = stream.next() {
+        if query.deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            mark::hit!(search_dependencies_past_deadline);
+            break;
+        }
         for indexed_value in indexed_values {
             let import_map = &import_maps[indexed_value.index];
             let importables = &import_map.importables[indexed_value.value as usize..];
@@ -946,6 +966,19 @@ mod tests {
                 dep::fmt::Display::fmt (a)
             "#]],
         );
+
+        check_search(
+            ra_fixture,
+            "main",
+            Query::new("fnt".to_string()).name_only().search_mode(SearchMode::Typo),
+            expect![[r#"
+                dep::fmt (t)
+                dep::Fmt (t)
+                dep::Fmt (v)
+                dep::Fmt (m)
+                dep::fmt::Display::fmt (a)
+            "#]],
+        );
     }
 
     #[test]
@@ -1093,4 +1126,22 @@ mod tests {
             expect![[r#""#]],
         );
     }
+
+    #[test]
+    fn search_past_deadline() {
+        mark::check!(search_dependencies_past_deadline);
+        let ra_fixture = r#"
+            //- /main.rs crate:main deps:dep
+            //- /dep.rs crate:dep
+
+            pub struct fmt;
+        "#;
+
+        check_search(
+            ra_fixture,
+            "main",
+            Query::new("fmt".to_string()).deadline(Instant::now()),
+            expect![[r#""#]],
+        );
+    }
 }
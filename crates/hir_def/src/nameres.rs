@@ -276,7 +276,7 @@ impl DefMap {
         self.block.as_ref().map(|block| block.block)
     }
 
-    pub(crate) fn prelude(&self) -> Option<ModuleId> {
+    pub fn prelude(&self) -> Option<ModuleId> {
         self.prelude
     }
 
@@ -399,7 +399,7 @@ mod diagnostics {
     use hir_expand::diagnostics::DiagnosticSink;
     use hir_expand::hygiene::Hygiene;
     use hir_expand::{InFile, MacroCallKind};
-    use syntax::ast::AttrsOwner;
+    use syntax::ast::{AttrsOwner, NameOwner};
     use syntax::{ast, AstNode, AstPtr, SyntaxKind, SyntaxNodePtr};
 
     use crate::path::ModPath;
@@ -420,6 +420,8 @@ mod diagnostics {
         UnresolvedMacroCall { ast: AstId<ast::MacroCall> },
 
         MacroError { ast: MacroCallKind, message: String },
+
+        DuplicateDefinition { first: AstId<ast::Item>, second: AstId<ast::Item> },
     }
 
     #[derive(Debug, PartialEq, Eq)]
@@ -486,6 +488,17 @@ mod diagnostics {
             Self { in_module: container, kind: DiagnosticKind::UnresolvedMacroCall { ast } }
         }
 
+        pub(super) fn duplicate_definition(
+            container: LocalModuleId,
+            first: AstId<ast::Item>,
+            second: AstId<ast::Item>,
+        ) -> Self {
+            Self {
+                in_module: container,
+                kind: DiagnosticKind::DuplicateDefinition { first, second },
+            }
+        }
+
         pub(super) fn add_to(
             &self,
             db: &dyn DefDatabase,
@@ -616,7 +629,42 @@ mod diagnostics {
                     };
                     sink.push(MacroError { file, node: ast, message: message.clone() });
                 }
+
+                DiagnosticKind::DuplicateDefinition { first, second } => {
+                    let first_name = item_name(&first.to_node(db.upcast()));
+                    let second_name = item_name(&second.to_node(db.upcast()));
+                    if let (Some(first_name), Some(second_name)) = (first_name, second_name) {
+                        sink.push(DuplicateDefinition {
+                            file: second.file_id,
+                            name: AstPtr::new(&second_name),
+                            first: InFile::new(first.file_id, AstPtr::new(&first_name)),
+                            text: second_name.text().to_string(),
+                            is_field: false,
+                        });
+                    }
+                }
             }
         }
     }
+
+    fn item_name(item: &ast::Item) -> Option<ast::Name> {
+        match item {
+            ast::Item::Const(it) => it.name(),
+            ast::Item::Enum(it) => it.name(),
+            ast::Item::Fn(it) => it.name(),
+            ast::Item::Module(it) => it.name(),
+            ast::Item::Static(it) => it.name(),
+            ast::Item::Struct(it) => it.name(),
+            ast::Item::Trait(it) => it.name(),
+            ast::Item::TypeAlias(it) => it.name(),
+            ast::Item::Union(it) => it.name(),
+            ast::Item::ExternBlock(_)
+            | ast::Item::ExternCrate(_)
+            | ast::Item::Impl(_)
+            | ast::Item::MacroCall(_)
+            | ast::Item::MacroRules(_)
+            | ast::Item::MacroDef(_)
+            | ast::Item::Use(_) => None,
+        }
+    }
 }
@@ -97,6 +97,7 @@ pub(super) fn collect_defs(
         proc_macros,
         exports_proc_macros: false,
         from_glob_import: Default::default(),
+        defined_names: FxHashMap::default(),
     };
     match block {
         Some(block) => {
@@ -238,6 +239,15 @@ struct DefCollector<'a> {
     proc_macros: Vec<(Name, ProcMacroExpander)>,
     exports_proc_macros: bool,
     from_glob_import: PerNsGlobImports,
+    /// The items defined in each module, in the type and the value namespaces, to report the
+    /// names defined several times.
+    defined_names: FxHashMap<(LocalModuleId, Name), DefinedNames>,
+}
+
+#[derive(Default)]
+struct DefinedNames {
+    types: Option<AstId<ast::Item>>,
+    values: Option<AstId<ast::Item>>,
 }
 
 impl DefCollector<'_> {
@@ -680,6 +690,36 @@ impl DefCollector<'_> {
         }
     }
 
+    /// Records the definition of an item, reporting it if an item with the same name is already
+    /// defined in the module in the same namespace.
+    fn define_name(
+        &mut self,
+        module_id: LocalModuleId,
+        name: &Name,
+        def: &PerNs,
+        ast_id: AstId<ast::Item>,
+    ) {
+        let defined = self.defined_names.entry((module_id, name.clone())).or_default();
+        let mut first = None;
+        if def.types.is_some() {
+            match defined.types {
+                Some(it) => first = Some(it),
+                None => defined.types = Some(ast_id),
+            }
+        }
+        if def.values.is_some() {
+            match defined.values {
+                Some(it) => first = first.or(Some(it)),
+                None => defined.values = Some(ast_id),
+            }
+        }
+        if let Some(first) = first {
+            self.def_map
+                .diagnostics
+                .push(DefDiagnostic::duplicate_definition(module_id, first, ast_id));
+        }
+    }
+
     fn update(
         &mut self,
         module_id: LocalModuleId,
@@ -1248,9 +1288,12 @@ impl ModCollector<'_, '_> {
                     .def_map
                     .resolve_visibility(self.def_collector.db, self.module_id, visibility)
                     .unwrap_or(Visibility::Public);
+                let per_ns = PerNs::from_def(id, vis, has_constructor);
+                let ast_id = AstId::new(self.file_id, item.ast_id(self.item_tree));
+                self.def_collector.define_name(self.module_id, name, &per_ns, ast_id);
                 self.def_collector.update(
                     self.module_id,
-                    &[(Some(name.clone()), PerNs::from_def(id, vis, has_constructor))],
+                    &[(Some(name.clone()), per_ns)],
                     vis,
                     ImportType::Named,
                 )
@@ -1356,12 +1399,10 @@ impl ModCollector<'_, '_> {
         let module = self.def_collector.def_map.module_id(res);
         let def: ModuleDefId = module.into();
         self.def_collector.def_map.modules[self.module_id].scope.define_def(def);
-        self.def_collector.update(
-            self.module_id,
-            &[(Some(name), PerNs::from_def(def, vis, false))],
-            vis,
-            ImportType::Named,
-        );
+        let per_ns = PerNs::from_def(def, vis, false);
+        let ast_id = AstId::new(declaration.file_id, declaration.value.upcast());
+        self.def_collector.define_name(self.module_id, &name, &per_ns, ast_id);
+        self.def_collector.update(self.module_id, &[(Some(name), per_ns)], vis, ImportType::Named);
         res
     }
 
@@ -1541,6 +1582,7 @@ mod tests {
             proc_macros: Default::default(),
             exports_proc_macros: false,
             from_glob_import: Default::default(),
+            defined_names: FxHashMap::default(),
         };
         collector.seed_with_top_level();
         collector.collect();
@@ -153,3 +153,29 @@ fn inactive_via_cfg_attr() {
         "#,
     );
 }
+
+#[test]
+fn duplicate_definitions() {
+    check_diagnostics(
+        r"
+        struct S;
+        fn S() {}
+         //^ the name `S` is defined multiple times
+
+        struct R {}
+        fn R() {}
+
+        mod m {}
+        enum m {}
+           //^ the name `m` is defined multiple times
+
+        const C: u8 = 0;
+        const C: u8 = 1;
+            //^ the name `C` is defined multiple times
+
+        #[cfg(never)] fn f() {}
+      //^^^^^^^^^^^^^^^^^^^^^^^ code is inactive due to #[cfg] directives: never is disabled
+        fn f() {}
+        ",
+    );
+}
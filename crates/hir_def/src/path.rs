@@ -336,6 +336,13 @@ macro_rules! __known_path {
     (core::ops::RangeInclusive) => {};
     (core::future::Future) => {};
     (core::ops::Try) => {};
+    (core::convert::Into) => {};
+    (core::convert::From) => {};
+    (core::convert::AsRef) => {};
+    (alloc::string::String) => {};
+    (alloc::string::ToString) => {};
+    (std::string::String) => {};
+    (std::string::ToString) => {};
     ($path:path) => {
         compile_error!("Please register your known path in the path module")
     };
@@ -43,6 +43,11 @@ pub trait Diagnostic: Any + Send + Sync + fmt::Debug + 'static {
     fn is_experimental(&self) -> bool {
         false
     }
+    /// The rustc or clippy lint controlling this diagnostic, if it isn't an error. Lint level
+    /// attributes like `#[allow(..)]` for the lint apply to the diagnostic.
+    fn lint(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 pub struct DiagnosticSink<'a> {
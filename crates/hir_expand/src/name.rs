@@ -2,7 +2,8 @@
 
 use std::fmt;
 
-use syntax::{ast, SmolStr};
+use base_db::Edition;
+use syntax::{ast, SmolStr, SyntaxKind, T};
 
 /// `Name` is a wrapper around string, which is used in hir for both references
 /// and declarations. In theory, names should also carry hygiene info, but we are
@@ -66,6 +67,29 @@ impl Name {
             _ => None,
         }
     }
+
+    /// Returns the name as it has to be written in the code of the given edition, that is as a
+    /// raw identifier if it's a keyword there, like an `async` function of a 2015 crate called
+    /// from a 2018 one.
+    pub fn escaped(&self, edition: Edition) -> String {
+        let text = match &self.0 {
+            Repr::Text(text) => text,
+            Repr::TupleField(idx) => return idx.to_string(),
+        };
+        let is_keyword = match SyntaxKind::from_keyword(text) {
+            // These can't be raw identifiers.
+            Some(T![self]) | Some(T![super]) | Some(T![crate]) | None => false,
+            Some(T![async]) | Some(T![await]) | Some(T![dyn]) | Some(T![try]) => {
+                edition != Edition::Edition2015
+            }
+            Some(_) => true,
+        };
+        if is_keyword {
+            format!("r#{}", text)
+        } else {
+            text.to_string()
+        }
+    }
 }
 
 pub trait AsName {
@@ -165,6 +189,8 @@ pub mod known {
         result,
         boxed,
         option,
+        convert,
+        string,
         // Components of known path (type name)
         Iterator,
         IntoIterator,
@@ -177,6 +203,11 @@ pub mod known {
         Output,
         Target,
         Box,
+        Into,
+        From,
+        AsRef,
+        String,
+        ToString,
         RangeFrom,
         RangeFull,
         RangeInclusive,
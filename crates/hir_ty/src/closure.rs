@@ -0,0 +1,329 @@
+//! Finds the local variables a closure captures and how, which decides the
+//! `Fn*` trait the closure implements.
+//!
+//! Like rustc, a closure captures each local in the least restrictive way its
+//! body allows: by reference, by mutable reference when the body assigns to it
+//! or borrows it mutably, and by value when the body moves out of it. `move`
+//! closures capture everything by value. A closure moving out of a captured
+//! local is only `FnOnce`, one mutating a captured local is `FnMut`.
+//!
+//! Only whole locals are tracked, not the fields of locals a closure uses.
+
+use std::sync::Arc;
+
+use hir_def::{
+    body::Body,
+    expr::{BinaryOp, BindingAnnotation, Expr, ExprId, Pat, PatId, Statement, UnaryOp},
+    lang_item::LangItemTarget,
+    resolver::HasResolver,
+    type_ref::{Mutability, TypeRef},
+    DefWithBodyId, HasModule,
+};
+
+use crate::{
+    db::HirDatabase, traits::FnTrait, Canonical, InEnvironment, InferenceResult, Obligation,
+    Substs, TraitEnvironment, TraitRef, Ty,
+};
+
+/// How a closure captures a local.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CaptureKind {
+    ByRef,
+    ByMutRef,
+    ByValue,
+}
+
+/// A local variable of the enclosing body used inside a closure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapturedLocal {
+    pub pat: PatId,
+    pub kind: CaptureKind,
+}
+
+/// Returns the locals captured by the closure expression `closure` of `owner`,
+/// in the order they were declared in.
+pub fn closure_captures(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    closure: ExprId,
+) -> Vec<CapturedLocal> {
+    let is_move = matches!(db.body(owner)[closure], Expr::Lambda { is_move: true, .. });
+    uses(db, owner, closure)
+        .into_iter()
+        .map(|(pat, usage)| CapturedLocal {
+            pat,
+            kind: if is_move { CaptureKind::ByValue } else { usage },
+        })
+        .collect()
+}
+
+/// Returns the most general `Fn*` trait the closure expression `closure` of
+/// `owner` implements.
+pub fn closure_kind(db: &dyn HirDatabase, owner: DefWithBodyId, closure: ExprId) -> FnTrait {
+    // Capturing by value because of `move` doesn't matter here, only what the body does.
+    match uses(db, owner, closure).into_iter().map(|(_, usage)| usage).max() {
+        Some(CaptureKind::ByValue) => FnTrait::FnOnce,
+        Some(CaptureKind::ByMutRef) => FnTrait::FnMut,
+        Some(CaptureKind::ByRef) | None => FnTrait::Fn,
+    }
+}
+
+/// The locals of the enclosing body the closure's body uses, and the way it needs to capture
+/// them for that.
+fn uses(db: &dyn HirDatabase, owner: DefWithBodyId, closure: ExprId) -> Vec<(PatId, CaptureKind)> {
+    let body = db.body(owner);
+    let closure_body = match &body[closure] {
+        Expr::Lambda { body, .. } => *body,
+        _ => return Vec::new(),
+    };
+    let mut ctx = Ctx {
+        db,
+        owner,
+        closure,
+        body: &body,
+        infer: db.infer(owner),
+        env: TraitEnvironment::lower(db, &owner.resolver(db.upcast())),
+        res: Vec::new(),
+    };
+    ctx.walk(closure_body, CaptureKind::ByValue);
+    let mut res = ctx.res;
+    res.sort_by_key(|(pat, _)| u32::from(pat.into_raw()));
+    res
+}
+
+struct Ctx<'a> {
+    db: &'a dyn HirDatabase,
+    owner: DefWithBodyId,
+    closure: ExprId,
+    body: &'a Body,
+    infer: Arc<InferenceResult>,
+    env: Arc<TraitEnvironment>,
+    res: Vec<(PatId, CaptureKind)>,
+}
+
+impl Ctx<'_> {
+    /// Walks `expr`, which the closure uses in the way `usage` says: an expression used as a
+    /// value is moved, unless its type is `Copy`.
+    fn walk(&mut self, expr: ExprId, usage: CaptureKind) {
+        let usage = if usage == CaptureKind::ByValue && self.is_copy(expr) {
+            CaptureKind::ByRef
+        } else {
+            usage
+        };
+        let body = self.body;
+        match &body[expr] {
+            Expr::Path(_) => {
+                if let Some(pat) = self.captured(expr) {
+                    self.record(pat, usage);
+                }
+            }
+            // Places: the base is used the same way as the place.
+            Expr::Field { expr, .. } => self.walk(*expr, usage),
+            Expr::Index { base, index } => {
+                // Indexing goes through `Index`, which can't move out of the base.
+                self.walk(*base, usage.min(CaptureKind::ByMutRef));
+                self.walk(*index, CaptureKind::ByValue);
+            }
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
+                self.walk(*expr, usage.min(CaptureKind::ByMutRef))
+            }
+            Expr::Ref { expr, mutability, .. } => {
+                let usage = match mutability {
+                    Mutability::Mut => CaptureKind::ByMutRef,
+                    Mutability::Shared => CaptureKind::ByRef,
+                };
+                self.walk(*expr, usage)
+            }
+            Expr::BinaryOp { lhs, rhs, op } => {
+                let lhs_usage = match op {
+                    Some(BinaryOp::Assignment { .. }) => CaptureKind::ByMutRef,
+                    // Comparisons take their operands by reference.
+                    Some(BinaryOp::CmpOp(_)) => CaptureKind::ByRef,
+                    _ => CaptureKind::ByValue,
+                };
+                let rhs_usage = match op {
+                    Some(BinaryOp::CmpOp(_)) => CaptureKind::ByRef,
+                    _ => CaptureKind::ByValue,
+                };
+                self.walk(*lhs, lhs_usage);
+                self.walk(*rhs, rhs_usage);
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                let usage = self.receiver_usage(expr);
+                self.walk(*receiver, usage);
+                for arg in args {
+                    self.walk(*arg, CaptureKind::ByValue);
+                }
+            }
+            Expr::Call { callee, args } => {
+                // Calling a closure needs the access its `Fn*` trait asks for.
+                let usage = match &self.infer[*callee] {
+                    Ty::Closure(owner, closure, _) => match closure_kind(self.db, *owner, *closure)
+                    {
+                        FnTrait::FnOnce => CaptureKind::ByValue,
+                        FnTrait::FnMut => CaptureKind::ByMutRef,
+                        FnTrait::Fn => CaptureKind::ByRef,
+                    },
+                    _ => CaptureKind::ByRef,
+                };
+                self.walk(*callee, usage);
+                for arg in args {
+                    self.walk(*arg, CaptureKind::ByValue);
+                }
+            }
+            Expr::Match { expr: scrutinee, arms } => {
+                let usage = arms
+                    .iter()
+                    .map(|arm| self.pat_usage(arm.pat))
+                    .max()
+                    .unwrap_or(CaptureKind::ByRef);
+                self.walk(*scrutinee, usage);
+                for arm in arms {
+                    if let Some(guard) = arm.guard {
+                        self.walk(guard, CaptureKind::ByValue);
+                    }
+                    self.walk(arm.expr, CaptureKind::ByValue);
+                }
+            }
+            Expr::Block { statements, tail, .. } => {
+                for stmt in statements {
+                    match stmt {
+                        Statement::Let { pat, initializer: Some(init), .. } => {
+                            let usage = self.pat_usage(*pat);
+                            self.walk(*init, usage);
+                        }
+                        Statement::Let { initializer: None, .. } => {}
+                        Statement::Expr(expr) => self.walk(*expr, CaptureKind::ByValue),
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.walk(*tail, usage);
+                }
+            }
+            Expr::If { condition, then_branch, else_branch } => {
+                self.walk(*condition, CaptureKind::ByValue);
+                self.walk(*then_branch, usage);
+                if let Some(it) = else_branch {
+                    self.walk(*it, usage);
+                }
+            }
+            Expr::Lambda { body: inner, is_move, .. } => {
+                // The uses of an inner closure are uses of the outer one. A `move` closure takes
+                // the locals it uses, which moves them unless they are `Copy`.
+                let mut inner_ctx = Ctx {
+                    db: self.db,
+                    owner: self.owner,
+                    closure: self.closure,
+                    body: self.body,
+                    infer: self.infer.clone(),
+                    env: self.env.clone(),
+                    res: Vec::new(),
+                };
+                inner_ctx.walk(*inner, CaptureKind::ByValue);
+                for (pat, usage) in inner_ctx.res {
+                    let usage = if *is_move && !self.is_copy_pat(pat) {
+                        CaptureKind::ByValue
+                    } else {
+                        usage
+                    };
+                    self.record(pat, usage);
+                }
+            }
+            it => it.walk_child_exprs(|child| self.walk(child, CaptureKind::ByValue)),
+        }
+    }
+
+    fn record(&mut self, pat: PatId, usage: CaptureKind) {
+        match self.res.iter_mut().find(|(it, _)| *it == pat) {
+            Some((_, it)) => *it = (*it).max(usage),
+            None => self.res.push((pat, usage)),
+        }
+    }
+
+    /// A name refers to a captured local if it resolves to the same binding inside the closure
+    /// and where the closure is defined.
+    fn captured(&self, expr: ExprId) -> Option<PatId> {
+        let scopes = self.db.expr_scopes(self.owner);
+        let name = match &self.body[expr] {
+            Expr::Path(path) => path.mod_path().as_ident()?,
+            _ => return None,
+        };
+        let inner = scopes.resolve_name_in_scope(scopes.scope_for(expr)?, name)?.pat();
+        let outer = scopes.resolve_name_in_scope(scopes.scope_for(self.closure)?, name)?.pat();
+        if inner == outer {
+            Some(inner)
+        } else {
+            None
+        }
+    }
+
+    /// How matching the pattern uses the matched value: binding parts of it by value moves
+    /// them, unless they are `Copy`.
+    fn pat_usage(&self, pat: PatId) -> CaptureKind {
+        let mut res = CaptureKind::ByRef;
+        let mut stack = vec![pat];
+        while let Some(pat) = stack.pop() {
+            let body = self.body;
+            match &body[pat] {
+                Pat::Bind { mode: BindingAnnotation::RefMut, .. } => {
+                    res = res.max(CaptureKind::ByMutRef)
+                }
+                Pat::Bind { mode: BindingAnnotation::Ref, .. } => {}
+                Pat::Bind { .. } if !self.is_copy_pat(pat) => res = res.max(CaptureKind::ByValue),
+                _ => {}
+            }
+            body[pat].walk_child_pats(|it| stack.push(it));
+        }
+        res
+    }
+
+    /// How a method call uses its receiver, according to its `self` parameter.
+    fn receiver_usage(&self, call: ExprId) -> CaptureKind {
+        let func = match self.infer.method_resolution(call) {
+            Some(it) => it,
+            None => return CaptureKind::ByRef,
+        };
+        let data = self.db.function_data(func);
+        match data.params.first() {
+            Some(TypeRef::Reference(_, _, Mutability::Mut)) => CaptureKind::ByMutRef,
+            Some(TypeRef::Reference(_, _, Mutability::Shared)) => CaptureKind::ByRef,
+            Some(_) if data.has_self_param => CaptureKind::ByValue,
+            _ => CaptureKind::ByRef,
+        }
+    }
+
+    fn is_copy(&self, expr: ExprId) -> bool {
+        self.implements_copy(&self.infer[expr])
+    }
+
+    fn is_copy_pat(&self, pat: PatId) -> bool {
+        self.implements_copy(&self.infer[pat])
+    }
+
+    fn implements_copy(&self, ty: &Ty) -> bool {
+        match ty {
+            // Don't claim anything is moved when we don't know its type.
+            Ty::Unknown => return true,
+            Ty::Scalar(_)
+            | Ty::Ref(Mutability::Shared, _)
+            | Ty::RawPtr(..)
+            | Ty::FnDef(..)
+            | Ty::Function(_)
+            | Ty::Never => return true,
+            Ty::Ref(Mutability::Mut, _) | Ty::Str | Ty::Slice(_) | Ty::Dyn(_) => return false,
+            _ => {}
+        }
+        let krate = self.owner.module(self.db.upcast()).krate();
+        let copy = match self.db.lang_item(krate, "copy".into()) {
+            Some(LangItemTarget::TraitId(it)) => it,
+            _ => return false,
+        };
+        let substs = Substs::build_for_def(self.db, copy).push(ty.clone()).build();
+        let trait_ref = TraitRef { trait_: copy, substs };
+        let goal = Canonical::new(
+            InEnvironment::new(self.env.clone(), Obligation::Trait(trait_ref)),
+            None,
+        );
+        self.db.trait_solve(krate, goal).is_some()
+    }
+}
@@ -0,0 +1,183 @@
+//! Searches for simple conversions which turn a value of one type into a
+//! value of another, like wrapping it in `Some` or calling `.into()` on it.
+//!
+//! This is used to suggest fixes for type mismatches: each conversion is
+//! checked against the actual coercion and trait rules, so only conversions
+//! which would make the types line up are returned.
+
+use std::sync::Arc;
+
+use base_db::CrateId;
+use hir_def::{
+    lang_item::LangItemTarget,
+    path::{path, ModPath},
+    resolver::HasResolver,
+    type_ref::Mutability,
+    AdtId, DefWithBodyId, HasModule, TraitId,
+};
+
+use crate::{
+    autoderef, db::HirDatabase, utils::generics, Canonical, InEnvironment, Obligation, Substs,
+    TraitEnvironment, TraitRef, Ty, TypeWalk,
+};
+
+/// A way to convert an expression to a different type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    /// `Some(expr)`
+    WrapInSome,
+    /// `Ok(expr)`
+    WrapInOk,
+    /// `&expr` or `&mut expr`
+    Borrow(Mutability),
+    /// `*expr`
+    Deref,
+    /// `expr.into()`
+    Into,
+    /// `expr.as_ref()`
+    AsRef,
+    /// `expr.to_string()`
+    ToString,
+}
+
+/// Returns the conversions which turn an expression of type `actual` inside
+/// of `owner` into one of type `expected`, most specific first.
+pub fn find_conversions(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    actual: &Ty,
+    expected: &Ty,
+) -> Vec<Conversion> {
+    let mut res = Vec::new();
+    if actual == expected || !is_known(actual) || !is_known(expected) {
+        return res;
+    }
+    let ctx = Ctx::new(db, owner);
+
+    if let Some(inner) = ctx.enum_param(expected, &path![core::option::Option]) {
+        if inner == actual {
+            res.push(Conversion::WrapInSome);
+        }
+    }
+    if let Some(inner) = ctx.enum_param(expected, &path![core::result::Result]) {
+        if inner == actual {
+            res.push(Conversion::WrapInOk);
+        }
+    }
+
+    if let Some((pointee, mutability)) = expected.as_reference() {
+        if ctx.derefs_to(actual, pointee) {
+            res.push(Conversion::Borrow(mutability));
+        } else if ctx.implements(actual, &[path![core::convert::AsRef]], Some(pointee)) {
+            res.push(Conversion::AsRef);
+        }
+    }
+    if let Some((pointee, _)) = actual.as_reference() {
+        if pointee == expected && ctx.is_copy(pointee) {
+            res.push(Conversion::Deref);
+        }
+    }
+
+    if ctx.is_string(expected)
+        && ctx.implements(
+            actual,
+            &[path![alloc::string::ToString], path![std::string::ToString]],
+            None,
+        )
+    {
+        res.push(Conversion::ToString);
+    }
+    if ctx.implements(actual, &[path![core::convert::Into]], Some(expected)) {
+        res.push(Conversion::Into);
+    }
+
+    res
+}
+
+/// Whether `ty` was inferred completely.
+pub(crate) fn is_known(ty: &Ty) -> bool {
+    let mut res = true;
+    ty.walk(&mut |ty| {
+        if matches!(ty, Ty::Unknown | Ty::Infer(_)) {
+            res = false;
+        }
+    });
+    res
+}
+
+struct Ctx<'a> {
+    db: &'a dyn HirDatabase,
+    owner: DefWithBodyId,
+    krate: CrateId,
+    env: Arc<TraitEnvironment>,
+}
+
+impl<'a> Ctx<'a> {
+    fn new(db: &'a dyn HirDatabase, owner: DefWithBodyId) -> Ctx<'a> {
+        let env = TraitEnvironment::lower(db, &owner.resolver(db.upcast()));
+        let krate = owner.module(db.upcast()).krate();
+        Ctx { db, owner, krate, env }
+    }
+
+    /// Returns the first type parameter of `ty` if it is the enum at `path`.
+    fn enum_param<'t>(&self, ty: &'t Ty, path: &ModPath) -> Option<&'t Ty> {
+        let resolver = self.owner.resolver(self.db.upcast());
+        let enum_ = resolver.resolve_known_enum(self.db.upcast(), path)?;
+        match ty {
+            Ty::Adt(AdtId::EnumId(it), params) if *it == enum_ => params.iter().next(),
+            _ => None,
+        }
+    }
+
+    fn is_string(&self, ty: &Ty) -> bool {
+        let resolver = self.owner.resolver(self.db.upcast());
+        // `alloc` is only nameable as `std` in most crates.
+        let string = [path![alloc::string::String], path![std::string::String]]
+            .iter()
+            .find_map(|path| resolver.resolve_known_struct(self.db.upcast(), path));
+        match (ty, string) {
+            (Ty::Adt(AdtId::StructId(it), _), Some(string)) => *it == string,
+            _ => false,
+        }
+    }
+
+    /// Whether `&ty` can be coerced to `&target`, possibly through auto-deref.
+    fn derefs_to(&self, ty: &Ty, target: &Ty) -> bool {
+        let ty = InEnvironment::new(self.env.clone(), Canonical::new(ty.clone(), None));
+        autoderef(self.db, Some(self.krate), ty).any(|derefed| &derefed.value == target)
+    }
+
+    fn is_copy(&self, ty: &Ty) -> bool {
+        match self.db.lang_item(self.krate, "copy".into()) {
+            Some(LangItemTarget::TraitId(copy)) => self.solve(copy, ty, None),
+            _ => false,
+        }
+    }
+
+    /// Whether `ty` implements the first of the traits at `paths` which
+    /// resolves. The trait may take one type parameter besides `Self`.
+    fn implements(&self, ty: &Ty, paths: &[ModPath], param: Option<&Ty>) -> bool {
+        let resolver = self.owner.resolver(self.db.upcast());
+        match paths.iter().find_map(|path| resolver.resolve_known_trait(self.db.upcast(), path)) {
+            Some(trait_) => self.solve(trait_, ty, param),
+            None => false,
+        }
+    }
+
+    fn solve(&self, trait_: TraitId, ty: &Ty, param: Option<&Ty>) -> bool {
+        let expected_params = 1 + param.iter().count();
+        if generics(self.db.upcast(), trait_.into()).len() != expected_params {
+            return false;
+        }
+        let mut substs = Substs::build_for_def(self.db, trait_).push(ty.clone());
+        if let Some(param) = param {
+            substs = substs.push(param.clone());
+        }
+        let trait_ref = TraitRef { trait_, substs: substs.build() };
+        let goal = Canonical::new(
+            InEnvironment::new(self.env.clone(), Obligation::Trait(trait_ref)),
+            None,
+        );
+        self.db.trait_solve(self.krate, goal).is_some()
+    }
+}
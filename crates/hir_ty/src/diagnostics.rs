@@ -1,19 +1,25 @@
 //! FIXME: write short doc here
 mod expr;
 mod match_check;
+mod privacy_check;
 mod unsafe_check;
+mod unused_check;
+mod unresolved_check;
 mod decl_check;
+mod impl_check;
+mod adt_check;
+mod loop_check;
 
 use std::{any::Any, fmt};
 
 use base_db::CrateId;
-use hir_def::{DefWithBodyId, ModuleDefId};
+use hir_def::{DefWithBodyId, ImplId, ModuleDefId, TraitId};
 use hir_expand::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSink};
 use hir_expand::{name::Name, HirFileId, InFile};
 use stdx::format_to;
 use syntax::{ast, AstPtr, SyntaxNodePtr};
 
-use crate::db::HirDatabase;
+use crate::{conversion::Conversion, db::HirDatabase};
 
 pub use crate::diagnostics::expr::{record_literal_missing_fields, record_pattern_missing_fields};
 
@@ -26,6 +32,14 @@ pub fn validate_module_item(
     let _p = profile::span("validate_module_item");
     let mut validator = decl_check::DeclValidator::new(db, krate, sink);
     validator.validate_item(owner);
+    if let ModuleDefId::AdtId(adt) = owner {
+        adt_check::validate_adt(db, adt, sink);
+    }
+}
+
+pub fn validate_impl(db: &dyn HirDatabase, impl_id: ImplId, sink: &mut DiagnosticSink<'_>) {
+    let _p = profile::span("validate_impl");
+    impl_check::validate_impl(db, impl_id, sink);
 }
 
 pub fn validate_body(db: &dyn HirDatabase, owner: DefWithBodyId, sink: &mut DiagnosticSink<'_>) {
@@ -34,7 +48,15 @@ pub fn validate_body(db: &dyn HirDatabase, owner: DefWithBodyId, sink: &mut Diag
     infer.add_diagnostics(db, owner, sink);
     let mut validator = expr::ExprValidator::new(owner, infer.clone(), sink);
     validator.validate_body(db);
-    let mut validator = unsafe_check::UnsafeValidator::new(owner, infer, sink);
+    let mut validator = unsafe_check::UnsafeValidator::new(owner, infer.clone(), sink);
+    validator.validate_body(db);
+    let mut validator = privacy_check::PrivacyValidator::new(owner, infer.clone(), sink);
+    validator.validate_body(db);
+    let mut validator = unused_check::UnusedValidator::new(owner, sink);
+    validator.validate_body(db);
+    let mut validator = loop_check::LoopValidator::new(owner, sink);
+    validator.validate_body(db);
+    let mut validator = unresolved_check::UnresolvedValidator::new(owner, infer, sink);
     validator.validate_body(db);
 }
 
@@ -161,6 +183,50 @@ impl Diagnostic for MissingPatFields {
     }
 }
 
+// Diagnostic: non-exhaustive-struct-literal
+//
+// This diagnostic is triggered if a struct or enum variant marked `#[non_exhaustive]` in another
+// crate is constructed with a struct literal.
+//
+// Example:
+//
+// ```rust
+// // in crate `dep`
+// #[non_exhaustive]
+// pub struct Config { pub verbose: bool }
+//
+// // in a crate depending on `dep`
+// let config = dep::Config { verbose: true };
+// ```
+#[derive(Debug)]
+pub struct NonExhaustiveRecordLiteral {
+    pub file: HirFileId,
+    pub record_expr: AstPtr<ast::RecordExpr>,
+    pub record_expr_path: Option<AstPtr<ast::Path>>,
+}
+
+impl Diagnostic for NonExhaustiveRecordLiteral {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("non-exhaustive-struct-literal")
+    }
+    fn message(&self) -> String {
+        "Cannot create a non-exhaustive struct from another crate with a struct literal".to_string()
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile {
+            file_id: self.file,
+            value: self
+                .record_expr_path
+                .clone()
+                .map(SyntaxNodePtr::from)
+                .unwrap_or_else(|| self.record_expr.clone().into()),
+        }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 // Diagnostic: missing-match-arm
 //
 // This diagnostic is triggered if `match` block is missing one or more match arms.
@@ -169,6 +235,8 @@ pub struct MissingMatchArms {
     pub file: HirFileId,
     pub match_expr: AstPtr<ast::Expr>,
     pub arms: AstPtr<ast::MatchArmList>,
+    /// Patterns which would make the match exhaustive when added as arms.
+    pub missing_patterns: Vec<String>,
 }
 
 impl Diagnostic for MissingMatchArms {
@@ -186,6 +254,47 @@ impl Diagnostic for MissingMatchArms {
     }
 }
 
+// Diagnostic: unreachable-match-arm
+//
+// This diagnostic is triggered if a match arm can never be reached, because all values matched by
+// its pattern are matched by earlier arms, or because its pattern matches a value of an
+// uninhabited type.
+#[derive(Debug)]
+pub struct UnreachableMatchArm {
+    pub file: HirFileId,
+    pub arm: AstPtr<ast::MatchArm>,
+    /// Whether the arm has a guard itself, and is reachable when the guard of an
+    /// earlier arm doesn't hold.
+    pub after_guard: bool,
+    /// Whether the arm only matches values of uninhabited types. Such arms are
+    /// required by older compilers, which don't take uninhabitedness into account.
+    pub uninhabited: bool,
+}
+
+impl Diagnostic for UnreachableMatchArm {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("unreachable-match-arm")
+    }
+    fn lint(&self) -> Option<&'static str> {
+        Some("unreachable_patterns")
+    }
+    fn message(&self) -> String {
+        if self.uninhabited {
+            String::from("Match arm matches a value of an uninhabited type")
+        } else if self.after_guard {
+            String::from("Match arm is only reachable if the guard of an earlier arm fails")
+        } else {
+            String::from("Unreachable match arm")
+        }
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.arm.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 // Diagnostic: missing-ok-or-some-in-tail-expr
 //
 // This diagnostic is triggered if a block that should return `Result` returns a value not wrapped in `Ok`,
@@ -247,11 +356,13 @@ impl Diagnostic for RemoveThisSemicolon {
 
 // Diagnostic: break-outside-of-loop
 //
-// This diagnostic is triggered if the `break` keyword is used outside of a loop.
+// This diagnostic is triggered if the `break` or `continue` keyword is used outside of a loop,
+// including inside a closure or an async block in a loop.
 #[derive(Debug)]
 pub struct BreakOutsideOfLoop {
     pub file: HirFileId,
     pub expr: AstPtr<ast::Expr>,
+    pub is_break: bool,
 }
 
 impl Diagnostic for BreakOutsideOfLoop {
@@ -259,7 +370,177 @@ impl Diagnostic for BreakOutsideOfLoop {
         DiagnosticCode("break-outside-of-loop")
     }
     fn message(&self) -> String {
-        "break outside of loop".to_string()
+        let keyword = if self.is_break { "break" } else { "continue" };
+        format!("{} outside of loop", keyword)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: break-with-value
+//
+// This diagnostic is triggered if a `break` with a value exits a `for` or a `while` loop, which
+// always evaluate to `()`.
+#[derive(Debug)]
+pub struct BreakWithValue {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    /// `for` or `while`.
+    pub loop_kind: &'static str,
+}
+
+impl Diagnostic for BreakWithValue {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("break-with-value")
+    }
+    fn message(&self) -> String {
+        format!("`break` with value from a `{}` loop", self.loop_kind)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: unresolved-label
+//
+// This diagnostic is triggered if `break` or `continue` refer to a label of no enclosing loop or
+// block.
+#[derive(Debug)]
+pub struct UnresolvedLabel {
+    pub file: HirFileId,
+    pub lifetime: AstPtr<ast::Lifetime>,
+    pub name: Name,
+}
+
+impl Diagnostic for UnresolvedLabel {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("unresolved-label")
+    }
+    fn message(&self) -> String {
+        format!("use of undeclared label `{}`", self.name)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.lifetime.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: shadowed-label
+//
+// This diagnostic is triggered if a label has the same name as the label of an enclosing loop or
+// block, which makes the outer label unusable inside.
+#[derive(Debug)]
+pub struct ShadowedLabel {
+    pub file: HirFileId,
+    pub lifetime: AstPtr<ast::Lifetime>,
+    /// The lifetime of the shadowed label.
+    pub outer: InFile<AstPtr<ast::Lifetime>>,
+    pub name: Name,
+}
+
+impl Diagnostic for ShadowedLabel {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("shadowed-label")
+    }
+    fn message(&self) -> String {
+        format!("label name `{}` shadows a label name that is already in scope", self.name)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.lifetime.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: await-outside-of-async
+//
+// This diagnostic is triggered if `.await` is used outside of an async function or block,
+// including in a closure inside of those.
+#[derive(Debug)]
+pub struct AwaitOutsideOfAsync {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+}
+
+impl Diagnostic for AwaitOutsideOfAsync {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("await-outside-of-async")
+    }
+    fn message(&self) -> String {
+        "`await` is only allowed inside `async` functions and blocks".to_string()
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: try-in-non-try-fn
+//
+// This diagnostic is triggered if the `?` operator is used in a function or a closure whose return
+// type doesn't implement `Try`, like `()`.
+#[derive(Debug)]
+pub struct TryInNonTryFn {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub return_ty: String,
+    /// The return type which would propagate the error of the operand, like `Result<(), E>` for
+    /// an operand of type `Result<T, E>`.
+    pub suggested_return_ty: Option<String>,
+}
+
+impl Diagnostic for TryInNonTryFn {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("try-in-non-try-fn")
+    }
+    fn message(&self) -> String {
+        format!(
+            "the `?` operator can only be used in a function that returns `Result` or `Option`, \
+             not `{}`",
+            self.return_ty
+        )
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: missing-error-conversion
+//
+// This diagnostic is triggered if the `?` operator is used on a `Result` whose error type doesn't
+// convert into the error type of the `Result` the function returns, as it doesn't implement `From`.
+#[derive(Debug)]
+pub struct MissingErrorConversion {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub from_ty: String,
+    pub to_ty: String,
+}
+
+impl Diagnostic for MissingErrorConversion {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("missing-error-conversion")
+    }
+    fn message(&self) -> String {
+        format!(
+            "`?` couldn't convert the error to `{}`, the trait `From<{}>` is not implemented for `{}`",
+            self.to_ty, self.from_ty, self.to_ty
+        )
     }
     fn display_source(&self) -> InFile<SyntaxNodePtr> {
         InFile { file_id: self.file, value: self.expr.clone().into() }
@@ -293,15 +574,105 @@ impl Diagnostic for MissingUnsafe {
     }
 }
 
+// Diagnostic: unnecessary-unsafe
+//
+// This diagnostic is triggered if an `unsafe` block doesn't contain any unsafe operations, or is
+// nested inside another `unsafe` block or `unsafe` function.
+#[derive(Debug)]
+pub struct UnnecessaryUnsafe {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub nested: bool,
+}
+
+impl Diagnostic for UnnecessaryUnsafe {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("unnecessary-unsafe")
+    }
+    fn lint(&self) -> Option<&'static str> {
+        Some("unused_unsafe")
+    }
+    fn message(&self) -> String {
+        if self.nested {
+            "unnecessary `unsafe` block: already inside an unsafe context".to_string()
+        } else {
+            "unnecessary `unsafe` block".to_string()
+        }
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: private-item
+//
+// This diagnostic is triggered if a field, function, constant, static or module is used outside
+// of the modules it is visible in.
+#[derive(Debug)]
+pub struct PrivateItem {
+    pub file: HirFileId,
+    pub name_ref: AstPtr<ast::NameRef>,
+    pub name: String,
+    pub kind: PrivateItemKind,
+    /// The definition of the item, whose visibility a fix could change.
+    pub target: Option<InFile<SyntaxNodePtr>>,
+    pub target_in_same_crate: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivateItemKind {
+    Field,
+    Method,
+    Function,
+    Const,
+    Static,
+    Module,
+}
+
+impl fmt::Display for PrivateItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            PrivateItemKind::Field => "field",
+            PrivateItemKind::Method => "method",
+            PrivateItemKind::Function => "function",
+            PrivateItemKind::Const => "constant",
+            PrivateItemKind::Static => "static",
+            PrivateItemKind::Module => "module",
+        };
+        f.write_str(kind)
+    }
+}
+
+impl Diagnostic for PrivateItem {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("private-item")
+    }
+    fn message(&self) -> String {
+        format!("{} `{}` is private", self.kind, self.name)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.name_ref.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 // Diagnostic: mismatched-arg-count
 //
 // This diagnostic is triggered if a function is invoked with an incorrect amount of arguments.
+// The message shows the signature of the function.
 #[derive(Debug)]
 pub struct MismatchedArgCount {
     pub file: HirFileId,
     pub call_expr: AstPtr<ast::Expr>,
     pub expected: usize,
     pub found: usize,
+    /// The signature of the callee, like `fn foo(u32, &str) -> bool`.
+    pub signature: String,
 }
 
 impl Diagnostic for MismatchedArgCount {
@@ -310,7 +681,10 @@ impl Diagnostic for MismatchedArgCount {
     }
     fn message(&self) -> String {
         let s = if self.expected == 1 { "" } else { "s" };
-        format!("Expected {} argument{}, found {}", self.expected, s, self.found)
+        format!(
+            "Expected {} argument{}, found {}: `{}`",
+            self.expected, s, self.found, self.signature
+        )
     }
     fn display_source(&self) -> InFile<SyntaxNodePtr> {
         InFile { file_id: self.file, value: self.call_expr.clone().into() }
@@ -323,6 +697,116 @@ impl Diagnostic for MismatchedArgCount {
     }
 }
 
+// Diagnostic: mismatched-tuple-struct-pat-arg-count
+//
+// This diagnostic is triggered if a tuple struct pattern doesn't have as many fields as the
+// struct or variant, or more of them if it uses `..`.
+#[derive(Debug)]
+pub struct MismatchedTupleStructPatArgCount {
+    pub file: HirFileId,
+    pub pat: AstPtr<ast::Pat>,
+    pub expected: usize,
+    pub found: usize,
+    pub has_ellipsis: bool,
+    /// The signature of the struct or variant, like `Some(T)`.
+    pub signature: String,
+}
+
+impl Diagnostic for MismatchedTupleStructPatArgCount {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("mismatched-tuple-struct-pat-arg-count")
+    }
+    fn message(&self) -> String {
+        let s = if self.expected == 1 { "" } else { "s" };
+        let at_most = if self.has_ellipsis { "at most " } else { "" };
+        format!(
+            "Expected {}{} field{}, found {}: `{}`",
+            at_most, self.expected, s, self.found, self.signature
+        )
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.pat.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: type-mismatch
+//
+// This diagnostic is triggered when the type of an expression does not match
+// the expected type. Where a simple conversion would make the types line up,
+// it is offered as a fix.
+//
+// Example:
+//
+// ```rust
+// fn foo(x: Option<i32>) {}
+// fn bar() { foo(92) }
+// ```
+#[derive(Debug)]
+pub struct TypeMismatch {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub expected: String,
+    pub actual: String,
+    pub conversions: Vec<Conversion>,
+}
+
+impl Diagnostic for TypeMismatch {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("type-mismatch")
+    }
+    fn message(&self) -> String {
+        format!("expected `{}`, found `{}`", self.expected, self.actual)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+// Diagnostic: unused-variable
+//
+// This diagnostic is triggered if a local binding is never used.
+//
+// Example:
+//
+// ```rust
+// fn foo() {
+//     let x = 92;
+// }
+// ```
+#[derive(Debug)]
+pub struct UnusedVariable {
+    pub file: HirFileId,
+    pub ident: AstPtr<ast::Name>,
+    pub name: Name,
+}
+
+impl Diagnostic for UnusedVariable {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("unused-variable")
+    }
+    fn lint(&self) -> Option<&'static str> {
+        Some("unused_variables")
+    }
+    fn message(&self) -> String {
+        format!("unused variable: `{}`", self.name)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.ident.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub enum CaseType {
     // `some_var`
@@ -393,6 +877,13 @@ impl Diagnostic for IncorrectCase {
     fn code(&self) -> DiagnosticCode {
         DiagnosticCode("incorrect-ident-case")
     }
+    fn lint(&self) -> Option<&'static str> {
+        Some(match self.expected_case {
+            CaseType::LowerSnakeCase => "non_snake_case",
+            CaseType::UpperSnakeCase => "non_upper_case_globals",
+            CaseType::UpperCamelCase => "non_camel_case_types",
+        })
+    }
 
     fn message(&self) -> String {
         format!(
@@ -431,6 +922,9 @@ impl Diagnostic for ReplaceFilterMapNextWithFindMap {
     fn code(&self) -> DiagnosticCode {
         DiagnosticCode("replace-filter-map-next-with-find-map")
     }
+    fn lint(&self) -> Option<&'static str> {
+        Some("clippy::filter_map_next")
+    }
     fn message(&self) -> String {
         "replace filter_map(..).next() with find_map(..)".to_string()
     }
@@ -442,6 +936,199 @@ impl Diagnostic for ReplaceFilterMapNextWithFindMap {
     }
 }
 
+// Diagnostic: unresolved-method
+//
+// This diagnostic is triggered if the type of the receiver has no method with
+// the called name. Methods with a similar name, and traits providing the method
+// which aren't imported, are offered as fixes.
+#[derive(Debug)]
+pub struct UnresolvedMethodCall {
+    pub file: HirFileId,
+    pub name_ref: AstPtr<ast::NameRef>,
+    pub name: Name,
+    pub receiver: String,
+    /// Methods of the receiver with a similar name, most similar first.
+    pub similar: Vec<Name>,
+    /// Traits which aren't in scope, but would provide the method.
+    pub traits_to_import: Vec<TraitId>,
+}
+
+impl Diagnostic for UnresolvedMethodCall {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("unresolved-method")
+    }
+    fn message(&self) -> String {
+        format!("no method named `{}` found for `{}`", self.name, self.receiver)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.name_ref.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+// Diagnostic: unresolved-field
+//
+// This diagnostic is triggered if the type of the receiver has no field with
+// the accessed name. Fields with a similar name are offered as fixes.
+#[derive(Debug)]
+pub struct UnresolvedField {
+    pub file: HirFileId,
+    pub name_ref: AstPtr<ast::NameRef>,
+    pub name: Name,
+    pub receiver: String,
+    /// Fields of the receiver with a similar name, most similar first.
+    pub similar: Vec<Name>,
+}
+
+impl Diagnostic for UnresolvedField {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("unresolved-field")
+    }
+    fn message(&self) -> String {
+        format!("no field `{}` on type `{}`", self.name, self.receiver)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.name_ref.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+// Diagnostic: missing-impl-items
+//
+// This diagnostic is triggered if a trait impl doesn't implement all the items of the
+// trait which have no default.
+//
+// Example:
+//
+// ```rust
+// trait Shape { fn area(&self) -> f64; }
+//
+// struct Square;
+//
+// impl Shape for Square {}
+// ```
+#[derive(Debug)]
+pub struct MissingImplItems {
+    pub file: HirFileId,
+    pub impl_: AstPtr<ast::Impl>,
+    pub trait_: Option<AstPtr<ast::Type>>,
+    pub missing: Vec<Name>,
+}
+
+impl Diagnostic for MissingImplItems {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("missing-impl-items")
+    }
+    fn message(&self) -> String {
+        let missing = self.missing.iter().map(|it| format!("`{}`", it)).collect::<Vec<_>>();
+        format!("not all trait items implemented, missing: {}", missing.join(", "))
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile {
+            file_id: self.file,
+            value: self
+                .trait_
+                .clone()
+                .map(SyntaxNodePtr::from)
+                .unwrap_or_else(|| self.impl_.clone().into()),
+        }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: recursive-type
+//
+// This diagnostic is triggered if a struct, enum or union contains itself by value, directly or
+// through other types, so that it would have an infinite size. A field pointing to the type
+// instead, like a `Box`, fixes it.
+//
+// Example:
+//
+// ```rust
+// enum List { Cons(i32, List), Nil }
+// ```
+#[derive(Debug)]
+pub struct RecursiveType {
+    pub file: HirFileId,
+    pub name: AstPtr<ast::Name>,
+    pub adt_name: String,
+    /// The types of the fields containing the ADT.
+    pub fields: Vec<AstPtr<ast::Type>>,
+}
+
+impl Diagnostic for RecursiveType {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("recursive-type")
+    }
+    fn message(&self) -> String {
+        format!("recursive type `{}` has infinite size", self.adt_name)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.name.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+// Diagnostic: orphan-impl
+//
+// This diagnostic is triggered if a trait defined in another crate is implemented for a type
+// which is defined in another crate too. The orphan rule forbids this, so that two crates can't
+// provide conflicting implementations. The usual way around it is the newtype pattern: wrapping
+// the type in a struct of the current crate, and implementing the trait for that struct.
+//
+// Example:
+//
+// ```rust
+// impl std::fmt::Display for Vec<u8> {}
+// ```
+#[derive(Debug)]
+pub struct OrphanImpl {
+    pub file: HirFileId,
+    pub impl_: AstPtr<ast::Impl>,
+    pub self_ty: Option<AstPtr<ast::Type>>,
+    pub self_ty_name: String,
+}
+
+impl Diagnostic for OrphanImpl {
+    fn code(&self) -> DiagnosticCode {
+        DiagnosticCode("orphan-impl")
+    }
+    fn message(&self) -> String {
+        format!(
+            "only traits defined in the current crate can be implemented for types defined \
+             outside of it; consider wrapping `{}` in a struct of this crate",
+            self.self_ty_name
+        )
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile {
+            file_id: self.file,
+            value: self
+                .self_ty
+                .clone()
+                .map(SyntaxNodePtr::from)
+                .unwrap_or_else(|| self.impl_.clone().into()),
+        }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use base_db::{fixture::WithFixture, FileId, SourceDatabase, SourceDatabaseExt};
@@ -454,7 +1141,7 @@ mod tests {
     use syntax::{TextRange, TextSize};
 
     use crate::{
-        diagnostics::{validate_body, validate_module_item},
+        diagnostics::{validate_body, validate_impl, validate_module_item},
         test_db::TestDB,
     };
 
@@ -476,6 +1163,11 @@ mod tests {
                     }
 
                     for impl_id in crate_def_map[module_id].scope.impls() {
+                        validate_impl(
+                            self,
+                            impl_id,
+                            &mut DiagnosticSinkBuilder::new().build(&mut cb),
+                        );
                         let impl_data = self.impl_data(impl_id);
                         for item in impl_data.items.iter() {
                             if let AssocItemId::FunctionId(f) = item {
@@ -501,11 +1193,35 @@ mod tests {
     }
 
     pub(crate) fn check_diagnostics(ra_fixture: &str) {
+        // Test fixtures are full of unused bindings and calls of methods their minimal std
+        // doesn't define, so those are checked separately.
+        check_diagnostics_filtered(ra_fixture, |d| {
+            !matches!(
+                d.code().as_str(),
+                "unused-variable" | "unresolved-method" | "unresolved-field"
+            )
+        });
+    }
+
+    pub(crate) fn check_unused_diagnostics(ra_fixture: &str) {
+        check_diagnostics_filtered(ra_fixture, |d| d.code().as_str() == "unused-variable");
+    }
+
+    pub(crate) fn check_unresolved_diagnostics(ra_fixture: &str) {
+        check_diagnostics_filtered(ra_fixture, |d| {
+            matches!(d.code().as_str(), "unresolved-method" | "unresolved-field")
+        });
+    }
+
+    fn check_diagnostics_filtered(ra_fixture: &str, filter: impl Fn(&dyn Diagnostic) -> bool) {
         let db = TestDB::with_files(ra_fixture);
         let annotations = db.extract_annotations();
 
         let mut actual: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
         db.diagnostics(|d| {
+            if !filter(d) {
+                return;
+            }
             let src = d.display_source();
             let root = db.parse_or_expand(src.file_id).unwrap();
             // FIXME: macros...
@@ -705,6 +1421,34 @@ fn x(a: S) {
         )
     }
 
+    #[test]
+    fn non_exhaustive_struct_literal() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:dep
+#[non_exhaustive]
+struct Local { a: u32 }
+
+fn f() {
+    let _ = Local { a: 0 };
+    let _ = dep::Config { verbose: true };
+          //^^^^^^^^^^^ Cannot create a non-exhaustive struct from another crate with a struct literal
+    let _ = dep::Mode::Fast { level: 1 };
+    let _ = dep::Mode::Slow {};
+          //^^^^^^^^^^^^^^^ Cannot create a non-exhaustive struct from another crate with a struct literal
+}
+//- /lib.rs crate:dep
+#[non_exhaustive]
+pub struct Config { pub verbose: bool, pub level: u32 }
+pub enum Mode {
+    Fast { level: u32 },
+    #[non_exhaustive]
+    Slow { level: u32 },
+}
+"#,
+        );
+    }
+
     #[test]
     fn break_outside_of_loop() {
         check_diagnostics(
@@ -715,6 +1459,106 @@ fn foo() { break; }
         );
     }
 
+    #[test]
+    fn await_outside_of_async() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:core
+use core::future::Future;
+async fn ready() {}
+fn f() {
+    ready().await;
+  //^^^^^^^^^^^^^ `await` is only allowed inside `async` functions and blocks
+    async { ready().await };
+}
+async fn g() {
+    ready().await;
+    let _ = || ready().await;
+             //^^^^^^^^^^^^^ `await` is only allowed inside `async` functions and blocks
+}
+//- /core.rs crate:core
+pub mod future {
+    #[lang = "future_trait"]
+    pub trait Future { type Output; }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn try_in_non_try_fn() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:core
+use core::{option::Option, result::Result};
+fn f(x: Result<u32, u8>) {
+    x?;
+  //^^ the `?` operator can only be used in a function that returns `Result` or `Option`, not `()`
+}
+fn g(x: Option<u32>) -> u32 {
+    x?
+  //^^ the `?` operator can only be used in a function that returns `Result` or `Option`, not `u32`
+}
+fn h(x: Option<u32>) -> Option<u32> {
+    let _ = || -> u32 { x? };
+                      //^^ the `?` operator can only be used in a function that returns `Result` or `Option`, not `u32`
+    let _ = || x?;
+    let _ = async { x? };
+    Some(x?)
+}
+//- /core.rs crate:core
+pub mod ops {
+    pub trait Try { type Ok; type Error; }
+    impl<T> Try for crate::option::Option<T> { type Ok = T; type Error = (); }
+    impl<T, E> Try for crate::result::Result<T, E> { type Ok = T; type Error = E; }
+}
+pub mod option {
+    pub enum Option<T> { Some(T), None }
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn missing_error_conversion() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:core
+use core::{convert::From, result::Result};
+struct IoError;
+struct ParseError;
+enum Error { Io(IoError) }
+impl From<IoError> for Error {
+    fn from(err: IoError) -> Error { Error::Io(err) }
+}
+fn f(x: Result<u32, ParseError>, y: Result<u32, IoError>) -> Result<u32, Error> {
+    x?;
+  //^^ `?` couldn't convert the error to `Error`, the trait `From<ParseError>` is not implemented for `Error`
+    y?;
+    let _ = || -> Result<u32, IoError> { Ok(y?) };
+    let _ = || -> Result<u32, IoError> { Ok(x?) };
+                                          //^^ `?` couldn't convert the error to `IoError`, the trait `From<ParseError>` is not implemented for `IoError`
+    Ok(0)
+}
+//- /core.rs crate:core
+pub mod ops {
+    pub trait Try { type Ok; type Error; }
+    impl<T, E> Try for crate::result::Result<T, E> { type Ok = T; type Error = E; }
+}
+pub mod convert {
+    pub trait From<T> { fn from(t: T) -> Self; }
+    impl<T> From<T> for T { fn from(t: T) -> T { t } }
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+"#,
+        );
+    }
+
     #[test]
     fn missing_semicolon() {
         check_diagnostics(
@@ -725,6 +1569,25 @@ fn foo() { break; }
         );
     }
 
+    #[test]
+    fn type_mismatch() {
+        check_diagnostics(
+            r#"
+struct S;
+fn takes_ref(s: &S) {}
+fn takes_unit(u: ()) {}
+fn test(s: S) -> i32 {
+    takes_ref(s);
+            //^ expected `&S`, found `S`
+    takes_unit(unknown());
+    let x: u32 = true;
+               //^^^^ expected `u32`, found `bool`
+    123
+}
+"#,
+        );
+    }
+
     // Register the required standard library types to make the tests work
     fn add_filter_map_with_find_next_boilerplate(body: &str) -> String {
         let prefix = r#"
@@ -0,0 +1,219 @@
+//! Checks ADTs: that their variants and fields have distinct names, and that they don't contain
+//! themselves by value, which would give them an infinite size.
+
+use hir_def::{
+    diagnostics::DuplicateDefinition,
+    src::{HasChildSource, HasSource},
+    AdtId, EnumVariantId, Lookup, VariantId,
+};
+use hir_expand::{diagnostics::DiagnosticSink, name::Name, HirFileId, InFile};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    ast::{self, NameOwner},
+    AstPtr,
+};
+
+use crate::{db::HirDatabase, diagnostics::RecursiveType, utils::variant_data, Ty};
+
+/// How many types containing the ADT are looked into, which bounds the search through generic
+/// types which grow with each level, like `struct S<T>(Option<S<Vec<T>>>)`.
+const MAX_EXPANSIONS: usize = 64;
+
+pub(super) fn validate_adt(db: &dyn HirDatabase, adt: AdtId, sink: &mut DiagnosticSink<'_>) {
+    validate_names(db, adt, sink);
+
+    let mut file = None;
+    let mut fields = Vec::new();
+    for variant in variants(db, adt) {
+        let field_types = db.field_types(variant);
+        let sources = variant.child_source(db.upcast());
+        for (field, ty) in field_types.iter() {
+            let mut visited = FxHashSet::default();
+            if !contains_by_value(db, adt, &ty.value, &mut visited) {
+                continue;
+            }
+            let ty = sources
+                .value
+                .get(field)
+                .and_then(|it| it.as_ref().either(|it| it.ty(), |it| it.ty()));
+            if let Some(ty) = ty {
+                file = Some(sources.file_id);
+                fields.push(AstPtr::new(&ty));
+            }
+        }
+    }
+    let file = match file {
+        Some(it) => it,
+        None => return,
+    };
+
+    let name = match adt {
+        AdtId::StructId(it) => it.lookup(db.upcast()).source(db.upcast()).value.name(),
+        AdtId::UnionId(it) => it.lookup(db.upcast()).source(db.upcast()).value.name(),
+        AdtId::EnumId(it) => it.lookup(db.upcast()).source(db.upcast()).value.name(),
+    };
+    let name = match name {
+        Some(it) => it,
+        None => return,
+    };
+    sink.push(RecursiveType {
+        file,
+        name: AstPtr::new(&name),
+        adt_name: name.text().to_string(),
+        fields,
+    });
+}
+
+fn validate_names(db: &dyn HirDatabase, adt: AdtId, sink: &mut DiagnosticSink<'_>) {
+    if let AdtId::EnumId(it) = adt {
+        let sources = it.child_source(db.upcast());
+        let enum_data = db.enum_data(it);
+        let names = enum_data.variants.iter().map(|(local_id, data)| {
+            (data.name.clone(), sources.value.get(local_id).and_then(|it| it.name()))
+        });
+        report_duplicates(sources.file_id, names, false, sink);
+    }
+    for variant in variants(db, adt) {
+        let sources = variant.child_source(db.upcast());
+        let variant_data = variant_data(db.upcast(), variant);
+        let names = variant_data.fields().iter().map(|(local_id, data)| {
+            let source = sources.value.get(local_id);
+            (data.name.clone(), source.and_then(|it| it.as_ref().right()?.name()))
+        });
+        report_duplicates(sources.file_id, names, true, sink);
+    }
+}
+
+fn report_duplicates(
+    file: HirFileId,
+    names: impl Iterator<Item = (Name, Option<ast::Name>)>,
+    is_field: bool,
+    sink: &mut DiagnosticSink<'_>,
+) {
+    let mut first_names = FxHashMap::default();
+    for (name, node) in names {
+        let node = match node {
+            Some(it) => it,
+            None => continue,
+        };
+        match first_names.get(&name) {
+            Some(first) => sink.push(DuplicateDefinition {
+                file,
+                name: AstPtr::new(&node),
+                first: InFile::new(file, AstPtr::new(first)),
+                text: node.text().to_string(),
+                is_field,
+            }),
+            None => {
+                first_names.insert(name, node);
+            }
+        }
+    }
+}
+
+fn variants(db: &dyn HirDatabase, adt: AdtId) -> Vec<VariantId> {
+    match adt {
+        AdtId::StructId(it) => vec![it.into()],
+        AdtId::UnionId(it) => vec![it.into()],
+        AdtId::EnumId(it) => db
+            .enum_data(it)
+            .variants
+            .iter()
+            .map(|(local_id, _)| EnumVariantId { parent: it, local_id }.into())
+            .collect(),
+    }
+}
+
+/// Whether a value of type `ty` contains a value of `target` rather than a pointer to it,
+/// through the fields of ADTs, tuples and arrays.
+fn contains_by_value(
+    db: &dyn HirDatabase,
+    target: AdtId,
+    ty: &Ty,
+    visited: &mut FxHashSet<Ty>,
+) -> bool {
+    match ty {
+        Ty::Adt(adt, substs) => {
+            if *adt == target {
+                return true;
+            }
+            if visited.len() >= MAX_EXPANSIONS || !visited.insert(ty.clone()) {
+                return false;
+            }
+            variants(db, *adt).into_iter().any(|variant| {
+                db.field_types(variant).iter().any(|(_, field_ty)| {
+                    let field_ty = field_ty.clone().subst(substs);
+                    contains_by_value(db, target, &field_ty, visited)
+                })
+            })
+        }
+        Ty::Tuple(_, substs) | Ty::Array(substs) => {
+            substs.iter().any(|ty| contains_by_value(db, target, ty, visited))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::check_diagnostics;
+
+    #[test]
+    fn recursive_types() {
+        check_diagnostics(
+            r#"
+struct Node { value: u32, next: Node }
+     //^^^^ recursive type `Node` has infinite size
+enum List { Cons(u32, List), Nil }
+   //^^^^ recursive type `List` has infinite size
+enum Option<T> { Some(T), None }
+struct Wrapper<T>(T);
+struct Tree { children: (Wrapper<Option<Tree>>, u8) }
+     //^^^^ recursive type `Tree` has infinite size
+struct Generic<T> { inner: [Generic<T>; 2], value: T }
+     //^^^^^^^ recursive type `Generic` has infinite size
+struct Grows<T>(Option<Grows<Option<T>>>);
+     //^^^^^ recursive type `Grows` has infinite size
+struct UsesGrows(Grows<u8>);
+struct A { b: B }
+     //^ recursive type `A` has infinite size
+struct B { a: Option<A> }
+     //^ recursive type `B` has infinite size
+"#,
+        );
+    }
+
+    #[test]
+    fn duplicate_fields_and_variants() {
+        check_diagnostics(
+            r#"
+struct S { a: u8, b: u8, a: u16 }
+                       //^ field `a` is already declared
+enum E {
+    A,
+    B { x: u8, x: u8 },
+             //^ field `x` is already declared
+    A(u8),
+  //^ the name `A` is defined multiple times
+}
+union U { f: u8, g: u8 }
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_with_indirection() {
+        check_diagnostics(
+            r#"
+struct Box<T>(*const T);
+struct Vec<T> { ptr: *mut T, len: usize }
+struct PhantomData<T>;
+enum Option<T> { Some(T), None }
+struct Node { next: Option<Box<Node>>, children: Vec<Node>, parent: *const Node }
+struct Ref<'a> { next: &'a Ref<'a>, marker: PhantomData<Ref<'a>> }
+struct Uses { node: Node, list: Option<Node> }
+struct Growing<T> { next: Option<Box<Growing<Option<T>>>> }
+"#,
+        );
+    }
+}
@@ -3,21 +3,33 @@
 use std::sync::Arc;
 
 use hir_def::{
-    expr::Statement, path::path, resolver::HasResolver, AdtId, AssocItemId, DefWithBodyId,
+    adt::StructKind,
+    expr::Statement,
+    path::path,
+    resolver::HasResolver,
+    src::{HasChildSource, HasSource},
+    AdtId, AssocItemId, AttrDefId, DefWithBodyId, HasModule, Lookup,
 };
 use hir_expand::{diagnostics::DiagnosticSink, name};
+use itertools::Itertools;
 use rustc_hash::FxHashSet;
-use syntax::{ast, AstPtr};
+use syntax::{ast, AstNode, AstPtr};
 
 use crate::{
+    conversion::{find_conversions, is_known},
     db::HirDatabase,
     diagnostics::{
-        match_check::{is_useful, MatchCheckCtx, Matrix, PatStack, Usefulness},
-        MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkOrSomeInTailExpr,
-        MissingPatFields, RemoveThisSemicolon,
+        match_check::{
+            is_impossible, is_uninhabited, is_useful, missing_patterns, MatchCheckCtx, Matrix,
+            PatStack, Usefulness,
+        },
+        MismatchedArgCount, MismatchedTupleStructPatArgCount, MissingFields, MissingMatchArms,
+        MissingOkOrSomeInTailExpr, MissingPatFields, NonExhaustiveRecordLiteral,
+        RemoveThisSemicolon, TypeMismatch, UnreachableMatchArm,
     },
+    display::HirDisplay,
     utils::variant_data,
-    InferenceResult, Ty,
+    CallableDefId, InferenceResult, Substs, Ty, TypeWalk,
 };
 
 pub(crate) use hir_def::{
@@ -49,8 +61,11 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         let body = db.body(self.owner.into());
 
         for (id, expr) in body.exprs.iter() {
-            if let Some((variant_def, missed_fields, true)) =
-                record_literal_missing_fields(db, &self.infer, id, expr)
+            // Listing the fields of a literal that can't be written would suggest a fix that
+            // doesn't compile.
+            let non_exhaustive = self.validate_non_exhaustive_record_literal(db, id, expr);
+            if let (false, Some((variant_def, missed_fields, true))) =
+                (non_exhaustive, record_literal_missing_fields(db, &self.infer, id, expr))
             {
                 self.create_record_literal_missing_fields_diagnostic(
                     id,
@@ -71,6 +86,9 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             }
         }
         for (id, pat) in body.pats.iter() {
+            if let Pat::TupleStruct { args, ellipsis, .. } = pat {
+                self.validate_tuple_struct_pat(db, id, args.len(), ellipsis.is_some());
+            }
             if let Some((variant_def, missed_fields, true)) =
                 record_pattern_missing_fields(db, &self.infer, id, pat)
             {
@@ -82,14 +100,56 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
                 );
             }
         }
+        // Mismatches of the whole body are handled by the tail expression checks.
+        let mut reported_mismatches = vec![body.body_expr];
         let body_expr = &body[body.body_expr];
         if let Expr::Block { statements, tail, .. } = body_expr {
             if let Some(t) = tail {
-                self.validate_results_in_tail_expr(body.body_expr, *t, db);
+                if self.validate_results_in_tail_expr(body.body_expr, *t, db) {
+                    reported_mismatches.push(*t);
+                }
             } else if let Some(Statement::Expr(id)) = statements.last() {
                 self.validate_missing_tail_expr(body.body_expr, *id, db);
             }
         }
+        self.validate_type_mismatches(db, &reported_mismatches);
+    }
+
+    /// Reports struct literals of `#[non_exhaustive]` structs and variants from other crates,
+    /// returning whether `expr` is one.
+    fn validate_non_exhaustive_record_literal(
+        &mut self,
+        db: &dyn HirDatabase,
+        id: ExprId,
+        expr: &Expr,
+    ) -> bool {
+        if !matches!(expr, Expr::RecordLit { .. }) {
+            return false;
+        }
+        let attr_owner: AttrDefId = match self.infer.variant_resolution_for_expr(id) {
+            Some(VariantId::StructId(it)) => AdtId::from(it).into(),
+            Some(VariantId::EnumVariantId(it)) => it.into(),
+            Some(VariantId::UnionId(_)) | None => return false,
+        };
+        let krate = self.owner.module(db.upcast()).krate();
+        if attr_owner.krate(db.upcast()) == krate
+            || !db.attrs(attr_owner).by_key("non_exhaustive").exists()
+        {
+            return false;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.owner.into());
+        if let Ok(source_ptr) = source_map.expr_syntax(id) {
+            let root = source_ptr.file_syntax(db.upcast());
+            if let ast::Expr::RecordExpr(record_expr) = &source_ptr.value.to_node(&root) {
+                self.sink.push(NonExhaustiveRecordLiteral {
+                    file: source_ptr.file_id,
+                    record_expr: AstPtr::new(record_expr),
+                    record_expr_path: record_expr.path().map(|path| AstPtr::new(&path)),
+                });
+            }
+        }
+        true
     }
 
     fn create_record_literal_missing_fields_diagnostic(
@@ -219,14 +279,18 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         }
 
         let is_method_call = matches!(expr, Expr::MethodCall { .. });
-        let (sig, args) = match expr {
+        let (sig, args, callee_def) = match expr {
             Expr::Call { callee, args } => {
                 let callee = &self.infer.type_of_expr[*callee];
                 let sig = match callee.callable_sig(db) {
                     Some(sig) => sig,
                     None => return,
                 };
-                (sig, args.clone())
+                let callee_def = match callee {
+                    Ty::FnDef(def, _) => Some(*def),
+                    _ => None,
+                };
+                (sig, args.clone(), callee_def)
             }
             Expr::MethodCall { receiver, args, .. } => {
                 let mut args = args.clone();
@@ -247,9 +311,11 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
                     Some(callee) => callee,
                     None => return,
                 };
-                let sig = db.callable_item_signature(callee.into()).value;
+                let sig = db
+                    .callable_item_signature(callee.into())
+                    .subst(&Substs::type_params(db, callee));
 
-                (sig, args)
+                (sig, args, Some(callee.into()))
             }
             _ => return,
         };
@@ -266,20 +332,75 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         if arg_count != param_count {
             let (_, source_map) = db.body_with_source_map(self.owner.into());
             if let Ok(source_ptr) = source_map.expr_syntax(call_id) {
+                let mut params = params;
                 if is_method_call {
                     param_count -= 1;
                     arg_count -= 1;
+                    params = &params[1..];
                 }
                 self.sink.push(MismatchedArgCount {
                     file: source_ptr.file_id,
                     call_expr: source_ptr.value,
                     expected: param_count,
                     found: arg_count,
+                    signature: callable_signature(db, callee_def, params, sig.ret()),
                 });
             }
         }
     }
 
+    fn validate_tuple_struct_pat(
+        &mut self,
+        db: &dyn HirDatabase,
+        id: PatId,
+        found: usize,
+        has_ellipsis: bool,
+    ) {
+        let variant = match self.infer.variant_resolution_for_pat(id) {
+            Some(it) => it,
+            None => return,
+        };
+        let variant_data = variant_data(db.upcast(), variant);
+        if variant_data.kind() != StructKind::Tuple {
+            return;
+        }
+        let expected = variant_data.fields().len();
+        if found == expected || (has_ellipsis && found < expected) {
+            return;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.owner);
+        let source_ptr = match source_map.pat_syntax(id) {
+            Ok(it) => it,
+            Err(_) => return,
+        };
+        let pat = match source_ptr.value.left() {
+            Some(it) => it,
+            None => return,
+        };
+
+        let substs = self.infer[id].substs().cloned().unwrap_or_else(Substs::empty);
+        let field_types = db.field_types(variant);
+        let fields = variant_data
+            .fields()
+            .iter()
+            .map(|(field, _)| field_types[field].clone().subst(&substs))
+            .collect::<Vec<_>>();
+        let def = match variant {
+            VariantId::StructId(it) => CallableDefId::StructId(it),
+            VariantId::EnumVariantId(it) => CallableDefId::EnumVariantId(it),
+            VariantId::UnionId(_) => return,
+        };
+        self.sink.push(MismatchedTupleStructPatArgCount {
+            file: source_ptr.file_id,
+            pat,
+            expected,
+            found,
+            has_ellipsis,
+            signature: callable_signature(db, Some(def), &fields, &Ty::unit()),
+        });
+    }
+
     fn validate_match(
         &mut self,
         id: ExprId,
@@ -298,11 +419,17 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             Some(ty) => ty,
         };
 
-        let cx = MatchCheckCtx { match_expr, body, infer: infer.clone(), db };
-        let pats = arms.iter().map(|arm| arm.pat);
+        let krate = self.owner.module(db.upcast()).krate();
+        let cx = MatchCheckCtx { match_expr, body, infer: infer.clone(), db, krate };
+        // Every value matches an arm of a match on an uninhabited type, even a wildcard.
+        let check_reachability = !is_uninhabited(db, match_expr_ty);
 
         let mut seen = Matrix::empty();
-        for pat in pats {
+        // Arms with a guard don't cover their pattern for the arms after them.
+        let mut seen_unguarded = Matrix::empty();
+        let mut unreachable_arms = Vec::new();
+        for arm in arms {
+            let pat = arm.pat;
             if let Some(pat_ty) = infer.type_of_pat.get(pat) {
                 // We only include patterns whose type matches the type
                 // of the match expression. If we had a InvalidMatchArmPattern
@@ -320,11 +447,24 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
                         .map(|(match_expr_ty, _)| match_expr_ty == pat_ty)
                         .unwrap_or(false)
                 {
-                    // If we had a NotUsefulMatchArm diagnostic, we could
-                    // check the usefulness of each pattern as we added it
-                    // to the matrix here.
-                    let v = PatStack::from_pattern(pat);
-                    seen.push(&cx, v);
+                    if check_reachability {
+                        let v = PatStack::from_pattern(pat);
+                        if is_useful(&cx, &seen_unguarded, &v) == Ok(Usefulness::NotUseful) {
+                            unreachable_arms.push((pat, false, false));
+                        } else if arm.guard.is_some()
+                            && is_useful(&cx, &seen, &v) == Ok(Usefulness::NotUseful)
+                        {
+                            // Falling back to an arm without a guard is expected, but a
+                            // guarded arm being covered by other guarded arms is suspicious.
+                            unreachable_arms.push((pat, true, false));
+                        } else if is_impossible(&cx, pat) {
+                            unreachable_arms.push((pat, false, true));
+                        }
+                    }
+                    seen.push(&cx, PatStack::from_pattern(pat));
+                    if arm.guard.is_none() {
+                        seen_unguarded.push(&cx, PatStack::from_pattern(pat));
+                    }
                     continue;
                 }
             }
@@ -336,6 +476,26 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             return;
         }
 
+        for (pat, after_guard, uninhabited) in unreachable_arms {
+            let source_ptr = match source_map.pat_syntax(pat) {
+                Ok(it) => it,
+                Err(_) => continue,
+            };
+            let root = source_ptr.file_syntax(db.upcast());
+            let pat_node = source_ptr.value.either(
+                |it| it.to_node(&root).syntax().clone(),
+                |it| it.to_node(&root).syntax().clone(),
+            );
+            if let Some(arm) = pat_node.ancestors().find_map(ast::MatchArm::cast) {
+                self.sink.push(UnreachableMatchArm {
+                    file: source_ptr.file_id,
+                    arm: AstPtr::new(&arm),
+                    after_guard,
+                    uninhabited,
+                });
+            }
+        }
+
         match is_useful(&cx, &seen, &PatStack::from_wild()) {
             Ok(Usefulness::Useful) => (),
             // if a wildcard pattern is not useful, then all patterns are covered
@@ -351,21 +511,52 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
                 if let (Some(match_expr), Some(arms)) =
                     (match_expr.expr(), match_expr.match_arm_list())
                 {
+                    let module = self.owner.module(db.upcast());
                     self.sink.push(MissingMatchArms {
                         file: source_ptr.file_id,
                         match_expr: AstPtr::new(&match_expr),
                         arms: AstPtr::new(&arms),
+                        missing_patterns: missing_patterns(&cx, &seen, module).unwrap_or_default(),
                     })
                 }
             }
         }
     }
 
-    fn validate_results_in_tail_expr(&mut self, body_id: ExprId, id: ExprId, db: &dyn HirDatabase) {
+    fn validate_type_mismatches(&mut self, db: &dyn HirDatabase, reported: &[ExprId]) {
+        let (_, source_map) = db.body_with_source_map(self.owner);
+        for (id, mismatch) in self.infer.type_mismatches.iter() {
+            if reported.contains(&id) {
+                continue;
+            }
+            if !is_known(&mismatch.expected) || !is_known(&mismatch.actual) {
+                continue;
+            }
+            let source_ptr = match source_map.expr_syntax(id) {
+                Ok(it) => it,
+                Err(_) => continue,
+            };
+            self.sink.push(TypeMismatch {
+                file: source_ptr.file_id,
+                expr: source_ptr.value,
+                expected: mismatch.expected.display(db).to_string(),
+                actual: mismatch.actual.display(db).to_string(),
+                conversions: find_conversions(db, self.owner, &mismatch.actual, &mismatch.expected),
+            });
+        }
+    }
+
+    /// Returns whether a diagnostic was reported for the tail expression.
+    fn validate_results_in_tail_expr(
+        &mut self,
+        body_id: ExprId,
+        id: ExprId,
+        db: &dyn HirDatabase,
+    ) -> bool {
         // the mismatch will be on the whole block currently
         let mismatch = match self.infer.type_mismatch_for_expr(body_id) {
             Some(m) => m,
-            None => return,
+            None => return false,
         };
 
         let core_result_path = path![core::result::Result];
@@ -374,11 +565,11 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         let resolver = self.owner.resolver(db.upcast());
         let core_result_enum = match resolver.resolve_known_enum(db.upcast(), &core_result_path) {
             Some(it) => it,
-            _ => return,
+            _ => return false,
         };
         let core_option_enum = match resolver.resolve_known_enum(db.upcast(), &core_option_path) {
             Some(it) => it,
-            _ => return,
+            _ => return false,
         };
 
         let (params, required) = match mismatch.expected {
@@ -388,7 +579,7 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             Ty::Adt(AdtId::EnumId(enum_id), ref parameters) if enum_id == core_option_enum => {
                 (parameters, "Some".to_string())
             }
-            _ => return,
+            _ => return false,
         };
 
         if params.len() > 0 && params[0] == mismatch.actual {
@@ -400,8 +591,10 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
                     expr: source_ptr.value,
                     required,
                 });
+                return true;
             }
         }
+        false
     }
 
     fn validate_missing_tail_expr(
@@ -433,6 +626,90 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
     }
 }
 
+/// Renders the signature of a function, or the constructor of a tuple struct or variant, with the
+/// given parameter types. Types which failed to infer are shown as written in the source.
+fn callable_signature(
+    db: &dyn HirDatabase,
+    def: Option<CallableDefId>,
+    params: &[Ty],
+    ret: &Ty,
+) -> String {
+    let (declared_params, declared_ret) = match def {
+        Some(def) => declared_types(db, def),
+        None => (Vec::new(), None),
+    };
+    let render = |ty: &Ty, declared: Option<&Option<String>>| match declared {
+        Some(Some(declared)) if contains_unknown(ty) => declared.clone(),
+        _ => ty.display(db).to_string(),
+    };
+    // `params` leaves out the receiver of a method call, so the declared types are aligned from
+    // the end.
+    let skipped = declared_params.len().saturating_sub(params.len());
+    let params = params
+        .iter()
+        .enumerate()
+        .map(|(i, it)| render(it, declared_params.get(skipped + i)))
+        .join(", ");
+    let ret = if *ret == Ty::unit() {
+        String::new()
+    } else {
+        format!(" -> {}", render(ret, Some(&declared_ret)))
+    };
+    match def {
+        Some(CallableDefId::FunctionId(it)) => {
+            format!("fn {}({}){}", db.function_data(it).name, params, ret)
+        }
+        Some(CallableDefId::StructId(it)) => format!("{}({})", db.struct_data(it).name, params),
+        Some(CallableDefId::EnumVariantId(it)) => {
+            let enum_data = db.enum_data(it.parent);
+            format!("{}::{}({})", enum_data.name, enum_data.variants[it.local_id].name, params)
+        }
+        None => format!("fn({}){}", params, ret),
+    }
+}
+
+/// The source text of the parameter types and of the return type of a callable. The receiver of
+/// a method has no type in the source.
+fn declared_types(
+    db: &dyn HirDatabase,
+    def: CallableDefId,
+) -> (Vec<Option<String>>, Option<String>) {
+    let text = |ty: Option<ast::Type>| ty.map(|it| it.syntax().text().to_string());
+    let variant_fields = |variant: VariantId| {
+        let sources = variant.child_source(db.upcast());
+        variant_data(db.upcast(), variant)
+            .fields()
+            .iter()
+            .map(|(field, _)| {
+                // Callables only have tuple fields. Their type is the last node, which is a macro
+                // call rather than a type for type macros like `Type![]`.
+                let field = sources.value.get(field)?.as_ref().left()?;
+                Some(field.syntax().last_child()?.text().to_string())
+            })
+            .collect()
+    };
+    match def {
+        CallableDefId::FunctionId(it) => {
+            let source = it.lookup(db.upcast()).source(db.upcast()).value;
+            let param_list = source.param_list();
+            let self_param = param_list.as_ref().and_then(|it| it.self_param()).map(|_| None);
+            let params = param_list.iter().flat_map(|it| it.params()).map(|it| text(it.ty()));
+            (
+                self_param.into_iter().chain(params).collect(),
+                text(source.ret_type().and_then(|it| it.ty())),
+            )
+        }
+        CallableDefId::StructId(it) => (variant_fields(it.into()), None),
+        CallableDefId::EnumVariantId(it) => (variant_fields(it.into()), None),
+    }
+}
+
+fn contains_unknown(ty: &Ty) -> bool {
+    let mut res = false;
+    ty.walk(&mut |it| res |= *it == Ty::Unknown);
+    res
+}
+
 pub fn record_literal_missing_fields(
     db: &dyn HirDatabase,
     infer: &InferenceResult,
@@ -503,7 +780,7 @@ mod tests {
             r#"
 fn zero() {}
 fn f() { zero(1); }
-       //^^^^^^^ Expected 0 arguments, found 1
+       //^^^^^^^ Expected 0 arguments, found 1: `fn zero()`
 "#,
         );
 
@@ -521,7 +798,7 @@ fn f() { zero(); }
             r#"
 fn one(arg: u8) {}
 fn f() { one(); }
-       //^^^^^ Expected 1 argument, found 0
+       //^^^^^ Expected 1 argument, found 0: `fn one(u8)`
 "#,
         );
 
@@ -542,7 +819,7 @@ impl S { fn method(&self) {} }
 
 fn f() {
     S::method();
-} //^^^^^^^^^^^ Expected 1 argument, found 0
+} //^^^^^^^^^^^ Expected 1 argument, found 0: `fn method(&S)`
 "#,
         );
 
@@ -568,7 +845,7 @@ impl S { fn method(&self, arg: u8) {} }
 
             fn f() {
                 S.method();
-            } //^^^^^^^^^^ Expected 1 argument, found 0
+            } //^^^^^^^^^^ Expected 1 argument, found 0: `fn method(u8)`
             "#,
         );
 
@@ -608,7 +885,7 @@ fn f() {
 struct Tup(u8, u16);
 fn f() {
     Tup(0);
-} //^^^^^^ Expected 2 arguments, found 1
+} //^^^^^^ Expected 2 arguments, found 1: `Tup(u8, u16)`
 "#,
         )
     }
@@ -620,7 +897,20 @@ fn f() {
 enum En { Variant(u8, u16), }
 fn f() {
     En::Variant(0);
-} //^^^^^^^^^^^^^^ Expected 2 arguments, found 1
+} //^^^^^^^^^^^^^^ Expected 2 arguments, found 1: `En::Variant(u8, u16)`
+"#,
+        )
+    }
+
+    #[test]
+    fn unknown_types_are_shown_as_written() {
+        check_diagnostics(
+            r#"
+struct S;
+impl S { fn method(&self, a: Unknown, b: u8) -> Vec<Unknown> { loop {} } }
+fn f() {
+    S.method(0);
+} //^^^^^^^^^^^ Expected 2 arguments, found 1: `fn method(Unknown, u8) -> Vec<Unknown>`
 "#,
         )
     }
@@ -639,9 +929,9 @@ impl Foo {
     fn new() {
         Foo::Bar(0);
         Foo::Bar(0, 1);
-      //^^^^^^^^^^^^^^ Expected 1 argument, found 2
+      //^^^^^^^^^^^^^^ Expected 1 argument, found 2: `Foo::Bar(Type![])`
         Foo::Bar();
-      //^^^^^^^^^^ Expected 1 argument, found 0
+      //^^^^^^^^^^ Expected 1 argument, found 0: `Foo::Bar(Type![])`
     }
 }
         "#,
@@ -662,7 +952,7 @@ fn f() {
     unsafe {
         fixed(0);
         fixed(0, 1);
-      //^^^^^^^^^^^ Expected 1 argument, found 2
+      //^^^^^^^^^^^ Expected 1 argument, found 2: `fn fixed(u8)`
         varargs(0);
         varargs(0, 1);
         varargs2();
@@ -674,6 +964,32 @@ fn f() {
         )
     }
 
+    #[test]
+    fn tuple_struct_pat() {
+        check_diagnostics(
+            r#"
+struct S(u32, &'static str);
+enum E<T> { V(T), W }
+fn f(s: S, e: E<bool>) {
+    let S(a, b) = s;
+    let S(a) = s;
+      //^^^^ Expected 2 fields, found 1: `S(u32, &str)`
+    let S(a, b, c) = s;
+      //^^^^^^^^^^ Expected 2 fields, found 3: `S(u32, &str)`
+    let S(..) = s;
+    let S(a, .., b) = s;
+    let S(.., a, b, c) = s;
+      //^^^^^^^^^^^^^^ Expected at most 2 fields, found 3: `S(u32, &str)`
+    match e {
+        E::V(a, b) => {}
+      //^^^^^^^^^^ Expected 1 field, found 2: `E::V(bool)`
+        E::W => {}
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn arg_count_lambda() {
         check_diagnostics(
@@ -681,10 +997,10 @@ fn f() {
 fn main() {
     let f = |()| ();
     f();
-  //^^^ Expected 1 argument, found 0
+  //^^^ Expected 1 argument, found 0: `fn(())`
     f(());
     f((), ());
-  //^^^^^^^^^ Expected 1 argument, found 2
+  //^^^^^^^^^ Expected 1 argument, found 2: `fn(())`
 }
 "#,
         )
@@ -0,0 +1,280 @@
+//! Checks trait impls: that they implement all the items of the trait which have no default, and
+//! that they respect the orphan rule.
+
+use base_db::CrateId;
+use hir_def::{src::HasSource, AdtId, AssocItemId, HasModule, ImplId, Lookup, TraitId};
+use hir_expand::{diagnostics::DiagnosticSink, name::Name};
+use syntax::AstPtr;
+
+use crate::{
+    db::HirDatabase,
+    diagnostics::{MissingImplItems, OrphanImpl},
+    GenericPredicate, Ty,
+};
+
+pub(super) fn validate_impl(db: &dyn HirDatabase, impl_id: ImplId, sink: &mut DiagnosticSink<'_>) {
+    let impl_data = db.impl_data(impl_id);
+    let trait_ref = match db.impl_trait(impl_id) {
+        Some(it) => it.value,
+        None => return,
+    };
+    let trait_ = trait_ref.trait_;
+
+    let source = impl_id.lookup(db.upcast()).source(db.upcast());
+    let krate = impl_id.lookup(db.upcast()).container.module(db.upcast()).krate();
+    if violates_orphan_rule(db, krate, trait_, &trait_ref.substs) {
+        sink.push(OrphanImpl {
+            file: source.file_id,
+            impl_: AstPtr::new(&source.value),
+            self_ty: source.value.self_ty().map(|it| AstPtr::new(&it)),
+            self_ty_name: source.value.self_ty().map_or_else(String::new, |it| it.to_string()),
+        });
+    }
+
+    if impl_data.is_negative {
+        return;
+    }
+    let implemented =
+        impl_data.items.iter().filter_map(|&item| item_name(db, item)).collect::<Vec<_>>();
+    let missing = db
+        .trait_data(trait_)
+        .items
+        .iter()
+        .filter(|(name, item)| {
+            is_required(db, *item)
+                && !implemented
+                    .iter()
+                    .any(|(it, is_type)| it == name && *is_type == is_type_item(*item))
+        })
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return;
+    }
+
+    sink.push(MissingImplItems {
+        file: source.file_id,
+        impl_: AstPtr::new(&source.value),
+        trait_: source.value.trait_().map(|it| AstPtr::new(&it)),
+        missing,
+    });
+}
+
+/// Where a type of an impl header is defined, for the orphan rule.
+enum Origin {
+    Local,
+    Foreign,
+    /// A type parameter which isn't covered by a local type, like `T` but unlike `Local<T>`.
+    Param,
+    /// The type isn't known well enough to tell.
+    Unknown,
+}
+
+/// Whether the impl of a foreign trait has no local type among the self type and the trait
+/// arguments, that is the orphan rule of RFC 2451 forbids it. Impls whose types aren't all known
+/// and impls with uncovered type parameters, which rustc rejects with a different error, aren't
+/// reported.
+fn violates_orphan_rule(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    trait_: TraitId,
+    substs: &[Ty],
+) -> bool {
+    if trait_.lookup(db.upcast()).container.module(db.upcast()).krate() == krate {
+        return false;
+    }
+    for ty in substs {
+        match origin(db, krate, ty) {
+            Origin::Local | Origin::Param | Origin::Unknown => return false,
+            Origin::Foreign => (),
+        }
+    }
+    true
+}
+
+fn origin(db: &dyn HirDatabase, krate: CrateId, ty: &Ty) -> Origin {
+    match ty {
+        Ty::Adt(adt, substs) => {
+            if adt.module(db.upcast()).krate() == krate {
+                return Origin::Local;
+            }
+            // `Box<T>` and other fundamental types are local if `T` is.
+            let is_fundamental = match adt {
+                AdtId::StructId(it) => db.attrs((*it).into()).by_key("fundamental").exists(),
+                _ => false,
+            };
+            match substs.first() {
+                Some(ty) if is_fundamental => origin(db, krate, ty),
+                _ => Origin::Foreign,
+            }
+        }
+        Ty::Ref(_, substs) => match substs.first() {
+            Some(ty) => origin(db, krate, ty),
+            None => Origin::Unknown,
+        },
+        Ty::Dyn(predicates) => {
+            let principal = predicates.iter().find_map(|it| match it {
+                GenericPredicate::Implemented(trait_ref) => Some(trait_ref.trait_),
+                _ => None,
+            });
+            match principal {
+                Some(it)
+                    if it.lookup(db.upcast()).container.module(db.upcast()).krate() == krate =>
+                {
+                    Origin::Local
+                }
+                Some(_) => Origin::Foreign,
+                None => Origin::Unknown,
+            }
+        }
+        Ty::Bound(_) | Ty::Placeholder(_) => Origin::Param,
+        Ty::Scalar(_)
+        | Ty::Tuple(..)
+        | Ty::Array(_)
+        | Ty::Slice(_)
+        | Ty::RawPtr(..)
+        | Ty::Str
+        | Ty::Never
+        | Ty::Function(_)
+        | Ty::FnDef(..) => Origin::Foreign,
+        Ty::AssociatedType(..)
+        | Ty::OpaqueType(..)
+        | Ty::Closure(..)
+        | Ty::ForeignType(_)
+        | Ty::Projection(_)
+        | Ty::Opaque(_)
+        | Ty::Infer(_)
+        | Ty::Unknown => Origin::Unknown,
+    }
+}
+
+/// Whether the trait item has no default, and so has to be implemented.
+fn is_required(db: &dyn HirDatabase, item: AssocItemId) -> bool {
+    match item {
+        AssocItemId::FunctionId(it) => !db.function_data(it).has_body,
+        AssocItemId::TypeAliasId(it) => db.type_alias_data(it).type_ref.is_none(),
+        AssocItemId::ConstId(it) => {
+            it.lookup(db.upcast()).source(db.upcast()).value.body().is_none()
+        }
+    }
+}
+
+fn is_type_item(item: AssocItemId) -> bool {
+    matches!(item, AssocItemId::TypeAliasId(_))
+}
+
+fn item_name(db: &dyn HirDatabase, item: AssocItemId) -> Option<(Name, bool)> {
+    let name = match item {
+        AssocItemId::FunctionId(it) => db.function_data(it).name.clone(),
+        AssocItemId::TypeAliasId(it) => db.type_alias_data(it).name.clone(),
+        AssocItemId::ConstId(it) => db.const_data(it).name.clone()?,
+    };
+    Some((name, is_type_item(item)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::check_diagnostics;
+
+    #[test]
+    fn missing_impl_items() {
+        check_diagnostics(
+            r#"
+trait Tr {
+    type Ty;
+    const C: u32;
+    const D: u32 = 0;
+    fn required(&self);
+    fn provided(&self) {}
+}
+struct S;
+impl Tr for S {}
+   //^^ not all trait items implemented, missing: `Ty`, `C`, `required`
+struct T;
+impl Tr for T {
+   //^^ not all trait items implemented, missing: `Ty`
+    const C: u32 = 1;
+    fn required(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_complete_impls() {
+        check_diagnostics(
+            r#"
+trait Tr {
+    type Ty;
+    fn required(&self);
+}
+struct S;
+impl Tr for S {
+    type Ty = ();
+    fn required(&self) {}
+}
+impl S {}
+impl !Tr for u32 {}
+impl Unresolved for S {}
+macro_rules! items { () => { type Ty = u8; fn required(&self) {} } }
+impl Tr for u8 { items!(); }
+"#,
+        );
+    }
+
+    #[test]
+    fn orphan_impls() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:core
+use core::{Box, Foreign, ForeignTrait, GenericTrait, Vec};
+struct Local;
+trait LocalTrait {}
+impl ForeignTrait for Foreign {}
+                    //^^^^^^^ only traits defined in the current crate can be implemented for types defined outside of it; consider wrapping `Foreign` in a struct of this crate
+impl ForeignTrait for (Local, u32) {}
+                    //^^^^^^^^^^^^ only traits defined in the current crate can be implemented for types defined outside of it; consider wrapping `(Local, u32)` in a struct of this crate
+impl<T> ForeignTrait for Vec<T> {}
+                       //^^^^^^ only traits defined in the current crate can be implemented for types defined outside of it; consider wrapping `Vec<T>` in a struct of this crate
+impl GenericTrait<u32> for Foreign {}
+                         //^^^^^^^ only traits defined in the current crate can be implemented for types defined outside of it; consider wrapping `Foreign` in a struct of this crate
+impl !ForeignTrait for &'static Foreign {}
+                     //^^^^^^^^^^^^^^^^ only traits defined in the current crate can be implemented for types defined outside of it; consider wrapping `&'static Foreign` in a struct of this crate
+//- /core.rs crate:core
+pub struct Foreign;
+pub struct Vec<T>(T);
+#[fundamental]
+pub struct Box<T>(T);
+pub trait ForeignTrait {}
+pub trait GenericTrait<T> {}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_local_impls() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:core
+use core::{Box, Foreign, ForeignTrait, GenericTrait, Vec};
+struct Local;
+trait LocalTrait {}
+impl ForeignTrait for Local {}
+impl ForeignTrait for &Local {}
+impl ForeignTrait for Box<Local> {}
+impl ForeignTrait for dyn LocalTrait {}
+impl LocalTrait for Foreign {}
+impl GenericTrait<Local> for Foreign {}
+impl<T> ForeignTrait for T {}
+impl ForeignTrait for Unresolved {}
+//- /core.rs crate:core
+pub struct Foreign;
+pub struct Vec<T>(T);
+#[fundamental]
+pub struct Box<T>(T);
+pub trait ForeignTrait {}
+pub trait GenericTrait<T> {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Checks the control flow of bodies: that `break` and `continue` are used inside loops, that
+//! `break` has no value in `for` and `while` loops, and that labels are declared and don't shadow
+//! each other.
+
+use hir_def::{
+    body::{Body, BodySourceMap},
+    expr::{Expr, ExprId, LabelId},
+    DefWithBodyId,
+};
+use hir_expand::{diagnostics::DiagnosticSink, name::Name, HirFileId, InFile};
+use syntax::{ast, AstPtr};
+
+use crate::{
+    db::HirDatabase,
+    diagnostics::{BreakOutsideOfLoop, BreakWithValue, ShadowedLabel, UnresolvedLabel},
+};
+
+pub(super) struct LoopValidator<'a, 'b: 'a> {
+    owner: DefWithBodyId,
+    sink: &'a mut DiagnosticSink<'b>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Loop,
+    /// `for` and `while` loops, which can't be broken out of with a value.
+    ForOrWhile(&'static str),
+    /// Labeled blocks, which only labeled `break`s can exit.
+    Block,
+}
+
+struct Breakable {
+    kind: Kind,
+    label: Option<LabelId>,
+}
+
+struct Walker<'a> {
+    body: &'a Body,
+    source_map: &'a BodySourceMap,
+    /// The breakables the walked expression is in, innermost last. Closures and async blocks
+    /// start with an empty stack, as control flow can't leave them.
+    breakables: Vec<Breakable>,
+    diagnostics: Vec<LoopDiagnostic>,
+}
+
+enum LoopDiagnostic {
+    OutsideOfLoop { expr: ExprId, is_break: bool },
+    WithValue { expr: ExprId, loop_kind: &'static str },
+    UnresolvedLabel { expr: ExprId },
+    ShadowedLabel { label: LabelId, outer: LabelId },
+}
+
+impl<'a, 'b> LoopValidator<'a, 'b> {
+    pub(super) fn new(
+        owner: DefWithBodyId,
+        sink: &'a mut DiagnosticSink<'b>,
+    ) -> LoopValidator<'a, 'b> {
+        LoopValidator { owner, sink }
+    }
+
+    pub(super) fn validate_body(&mut self, db: &dyn HirDatabase) {
+        let (body, source_map) = db.body_with_source_map(self.owner);
+        let mut walker = Walker {
+            body: &body,
+            source_map: &source_map,
+            breakables: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+        walker.walk(body.body_expr);
+
+        for diagnostic in walker.diagnostics {
+            match diagnostic {
+                LoopDiagnostic::OutsideOfLoop { expr, is_break } => {
+                    if let Ok(source) = source_map.expr_syntax(expr) {
+                        self.sink.push(BreakOutsideOfLoop {
+                            file: source.file_id,
+                            expr: source.value,
+                            is_break,
+                        });
+                    }
+                }
+                LoopDiagnostic::WithValue { expr, loop_kind } => {
+                    if let Ok(source) = source_map.expr_syntax(expr) {
+                        self.sink.push(BreakWithValue {
+                            file: source.file_id,
+                            expr: source.value,
+                            loop_kind,
+                        });
+                    }
+                }
+                LoopDiagnostic::UnresolvedLabel { expr } => {
+                    if let Some((file, lifetime, name)) = jump_label(db, &source_map, expr) {
+                        self.sink.push(UnresolvedLabel { file, lifetime, name });
+                    }
+                }
+                LoopDiagnostic::ShadowedLabel { label, outer } => {
+                    let lifetime = label_lifetime(db, &source_map, label);
+                    let outer = label_lifetime(db, &source_map, outer);
+                    if let (Some((file, lifetime)), Some((outer_file, outer))) = (lifetime, outer) {
+                        let outer = InFile::new(outer_file, outer);
+                        let name = body[label].name.clone();
+                        self.sink.push(ShadowedLabel { file, lifetime, outer, name });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Walker<'_> {
+    fn walk(&mut self, expr: ExprId) {
+        let body = self.body;
+        match &body[expr] {
+            Expr::Loop { body: loop_body, label } => {
+                let kind = match self.source_map.expr_syntax(expr) {
+                    // `while let` loops are desugared to `loop`s.
+                    Ok(source)
+                        if source.value.syntax_node_ptr().cast::<ast::WhileExpr>().is_some() =>
+                    {
+                        Kind::ForOrWhile("while")
+                    }
+                    _ => Kind::Loop,
+                };
+                self.walk_breakable(kind, *label, *loop_body);
+            }
+            Expr::While { condition, body: loop_body, label } => {
+                self.walk(*condition);
+                self.walk_breakable(Kind::ForOrWhile("while"), *label, *loop_body);
+            }
+            Expr::For { iterable, body: loop_body, label, .. } => {
+                self.walk(*iterable);
+                self.walk_breakable(Kind::ForOrWhile("for"), *label, *loop_body);
+            }
+            Expr::Block { label: Some(label), .. } => {
+                self.check_shadowing(*label);
+                self.breakables.push(Breakable { kind: Kind::Block, label: Some(*label) });
+                body[expr].walk_child_exprs(|it| self.walk(it));
+                self.breakables.pop();
+            }
+            Expr::Break { expr: value, label } => {
+                match self.find_target(expr, label.as_ref()) {
+                    Some(Kind::ForOrWhile(loop_kind)) if value.is_some() => {
+                        self.diagnostics.push(LoopDiagnostic::WithValue { expr, loop_kind })
+                    }
+                    Some(_) => (),
+                    None if label.is_none() => self
+                        .diagnostics
+                        .push(LoopDiagnostic::OutsideOfLoop { expr, is_break: true }),
+                    None => (),
+                }
+                if let Some(value) = value {
+                    self.walk(*value);
+                }
+            }
+            Expr::Continue { label } => {
+                if self.find_target(expr, label.as_ref()).is_none() && label.is_none() {
+                    self.diagnostics.push(LoopDiagnostic::OutsideOfLoop { expr, is_break: false });
+                }
+            }
+            Expr::Lambda { .. } | Expr::Async { .. } | Expr::Const { .. } => {
+                let outer = std::mem::take(&mut self.breakables);
+                body[expr].walk_child_exprs(|it| self.walk(it));
+                self.breakables = outer;
+            }
+            _ => body[expr].walk_child_exprs(|it| self.walk(it)),
+        }
+    }
+
+    fn walk_breakable(&mut self, kind: Kind, label: Option<LabelId>, loop_body: ExprId) {
+        if let Some(label) = label {
+            self.check_shadowing(label);
+        }
+        self.breakables.push(Breakable { kind, label });
+        self.walk(loop_body);
+        self.breakables.pop();
+    }
+
+    fn check_shadowing(&mut self, label: LabelId) {
+        let name = &self.body[label].name;
+        if *name == Name::missing() {
+            return;
+        }
+        let outer = self
+            .breakables
+            .iter()
+            .rev()
+            .filter_map(|it| it.label)
+            .find(|it| self.body[*it].name == *name);
+        if let Some(outer) = outer {
+            self.diagnostics.push(LoopDiagnostic::ShadowedLabel { label, outer });
+        }
+    }
+
+    /// Returns the kind of the breakable the `break` or `continue` jumps out of, reporting the
+    /// labels which aren't declared. Unlabeled jumps exit the innermost loop.
+    fn find_target(&mut self, expr: ExprId, label: Option<&Name>) -> Option<Kind> {
+        let body = self.body;
+        let target = match label {
+            Some(name) => self
+                .breakables
+                .iter()
+                .rev()
+                .find(|it| matches!(it.label, Some(label) if body[label].name == *name)),
+            None => self.breakables.iter().rev().find(|it| it.kind != Kind::Block),
+        };
+        if target.is_none() && label.is_some() {
+            self.diagnostics.push(LoopDiagnostic::UnresolvedLabel { expr });
+        }
+        target.map(|it| it.kind)
+    }
+}
+
+/// The lifetime of a `break 'label` or `continue 'label` expression.
+fn jump_label(
+    db: &dyn HirDatabase,
+    source_map: &BodySourceMap,
+    expr: ExprId,
+) -> Option<(HirFileId, AstPtr<ast::Lifetime>, Name)> {
+    let source = source_map.expr_syntax(expr).ok()?;
+    let root = db.parse_or_expand(source.file_id)?;
+    let lifetime = match source.value.to_node(&root) {
+        ast::Expr::BreakExpr(it) => it.lifetime()?,
+        ast::Expr::ContinueExpr(it) => it.lifetime()?,
+        _ => return None,
+    };
+    let name = Name::new_lifetime(&lifetime);
+    Some((source.file_id, AstPtr::new(&lifetime), name))
+}
+
+fn label_lifetime(
+    db: &dyn HirDatabase,
+    source_map: &BodySourceMap,
+    label: LabelId,
+) -> Option<(HirFileId, AstPtr<ast::Lifetime>)> {
+    let source = source_map.label_syntax(label);
+    let root = db.parse_or_expand(source.file_id)?;
+    let lifetime = source.value.to_node(&root).lifetime()?;
+    Some((source.file_id, AstPtr::new(&lifetime)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::check_diagnostics;
+
+    #[test]
+    fn break_and_continue_outside_of_loops() {
+        check_diagnostics(
+            r#"
+fn f() {
+    continue;
+  //^^^^^^^^ continue outside of loop
+    'a: {
+        break;
+      //^^^^^ break outside of loop
+    }
+    loop {
+        let _ = || break;
+                 //^^^^^ break outside of loop
+        let _ = async { continue };
+                      //^^^^^^^^ continue outside of loop
+        'b: { break; }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn break_with_value_from_for_and_while() {
+        check_diagnostics(
+            r#"
+fn f(b: bool) {
+    for _ in 0..1 { break 1; }
+                  //^^^^^^^ `break` with value from a `for` loop
+    while b { break 1; }
+            //^^^^^^^ `break` with value from a `while` loop
+    while let true = b { break 1; }
+                       //^^^^^^^ `break` with value from a `while` loop
+    'a: for _ in 0..1 {
+        loop { break 'a 1; }
+             //^^^^^^^^^^ `break` with value from a `for` loop
+    }
+    let _ = loop { break 1; };
+    let _ = 'b: { break 'b 1; };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unresolved_labels() {
+        check_diagnostics(
+            r#"
+fn f() {
+    loop { break 'a; }
+               //^^ use of undeclared label `'a`
+    'b: loop {
+        continue 'c;
+               //^^ use of undeclared label `'c`
+        let _ = || loop { break 'b; };
+                              //^^ use of undeclared label `'b`
+        continue 'b;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn shadowed_labels() {
+        check_diagnostics(
+            r#"
+fn f() {
+    'a: loop {
+        'a: loop { break 'a; }
+      //^^ label name `'a` shadows a label name that is already in scope
+        'b: { break 'b; }
+        'b: for _ in 0..1 {}
+        let _ = || 'a: loop {};
+    }
+}
+"#,
+        );
+    }
+}
@@ -222,12 +222,16 @@ use hir_def::{
     adt::VariantData,
     body::Body,
     expr::{Expr, Literal, Pat, PatId},
-    AdtId, EnumVariantId, StructId, VariantId,
+    find_path::find_path,
+    item_scope::ItemInNs,
+    AdtId, AttrDefId, EnumId, EnumVariantId, ModuleDefId, ModuleId, StructId, VariantId,
 };
 use la_arena::Idx;
 use smallvec::{smallvec, SmallVec};
 
-use crate::{db::HirDatabase, InferenceResult, Ty};
+use base_db::CrateId;
+
+use crate::{db::HirDatabase, InferenceResult, Scalar, Ty};
 
 #[derive(Debug, Clone, Copy)]
 /// Either a pattern from the source code being analyzed, represented as
@@ -539,7 +543,7 @@ impl Matrix {
         if let Some(Pat::Or(pat_ids)) = row.get_head().map(|pat_id| pat_id.as_pat(cx)) {
             // Or patterns are expanded here
             for pat_id in pat_ids {
-                self.0.push(PatStack::from_pattern(pat_id));
+                self.0.push(row.replace_head_with(iter::once(pat_id)));
             }
         } else {
             self.0.push(row);
@@ -607,6 +611,18 @@ pub(super) struct MatchCheckCtx<'a> {
     pub(super) body: Arc<Body>,
     pub(super) infer: Arc<InferenceResult>,
     pub(super) db: &'a dyn HirDatabase,
+    /// The crate of the match, outside of which `#[non_exhaustive]` enums can't be matched
+    /// exhaustively.
+    pub(super) krate: CrateId,
+}
+
+impl MatchCheckCtx<'_> {
+    /// Whether `enum_id` may get more variants that the match has to cover already.
+    fn is_foreign_non_exhaustive(&self, enum_id: EnumId) -> bool {
+        let def = AttrDefId::AdtId(AdtId::EnumId(enum_id));
+        def.krate(self.db.upcast()) != self.krate
+            && self.db.attrs(def).by_key("non_exhaustive").exists()
+    }
 }
 
 /// Given a set of patterns `matrix`, and pattern to consider `v`, determines
@@ -628,7 +644,9 @@ pub(super) fn is_useful(
     // In those cases, no match arm is useful.
     match cx.infer[cx.match_expr].strip_references() {
         Ty::Adt(AdtId::EnumId(enum_id), ..) => {
-            if cx.db.enum_data(*enum_id).variants.is_empty() {
+            if cx.db.enum_data(*enum_id).variants.is_empty()
+                && !cx.is_foreign_non_exhaustive(*enum_id)
+            {
                 return Ok(Usefulness::NotUseful);
             }
         }
@@ -648,7 +666,7 @@ pub(super) fn is_useful(
     if let Pat::Or(pat_ids) = head.as_pat(cx) {
         let mut found_unimplemented = false;
         let any_useful = pat_ids.iter().any(|&pat_id| {
-            let v = PatStack::from_pattern(pat_id);
+            let v = v.replace_head_with(iter::once(pat_id));
 
             match is_useful(cx, matrix, &v) {
                 Ok(Usefulness::Useful) => true,
@@ -734,7 +752,7 @@ pub(super) fn is_useful(
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 /// Similar to TypeCtor, but includes additional information about the specific
 /// value being instantiated. For example, TypeCtor::Bool doesn't contain the
 /// boolean value.
@@ -841,6 +859,7 @@ fn all_constructors_covered(
 
             covers_true && covers_false
         }
+        Constructor::Enum(e) if cx.is_foreign_non_exhaustive(e.parent) => false,
         Constructor::Enum(e) => cx.db.enum_data(e.parent).variants.iter().all(|(id, _)| {
             for constructor in used_constructors {
                 if let Constructor::Enum(e) = constructor {
@@ -859,6 +878,203 @@ fn all_constructors_covered(
     }
 }
 
+/// Whether there are no values of type `ty`, like for empty enums.
+pub(super) fn is_uninhabited(db: &dyn HirDatabase, ty: &Ty) -> bool {
+    match ty.strip_references() {
+        Ty::Never => true,
+        Ty::Adt(AdtId::EnumId(enum_id), _) => db.enum_data(*enum_id).variants.is_empty(),
+        _ => false,
+    }
+}
+
+/// Whether `pat` can never match, because it contains a subpattern of an
+/// uninhabited type.
+pub(super) fn is_impossible(cx: &MatchCheckCtx, pat: PatId) -> bool {
+    if cx.infer.type_of_pat.get(pat).map_or(false, |ty| is_uninhabited(cx.db, ty)) {
+        return true;
+    }
+    match &cx.body.pats[pat] {
+        Pat::Or(alternatives) => alternatives.iter().all(|&it| is_impossible(cx, it)),
+        other => {
+            let mut res = false;
+            other.walk_child_pats(|it| res |= is_impossible(cx, it));
+            res
+        }
+    }
+}
+
+/// A pattern matching values which aren't matched by any row of a matrix.
+#[derive(Debug, Clone)]
+enum Witness {
+    Wild,
+    Constructor(Constructor, Vec<Witness>),
+}
+
+/// Upper bound on the number of witnesses, as their number can grow
+/// exponentially with the width of the match.
+const MAX_WITNESSES: usize = 32;
+
+/// Returns patterns which together cover all the values not covered by
+/// `matrix`, rendered as paths visible from `module`.
+///
+/// Unlike [`is_useful`], this doesn't stop at the first uncovered constructor,
+/// so that the patterns can be inserted as arms to make the match exhaustive.
+pub(super) fn missing_patterns(
+    cx: &MatchCheckCtx,
+    matrix: &Matrix,
+    module: ModuleId,
+) -> MatchCheckResult<Vec<String>> {
+    let witnesses = if matrix.is_empty() {
+        // Without any arms, there are no constructors to start from, so list
+        // the ones of the matched type.
+        match root_constructors(cx) {
+            Some(constructors) => constructors
+                .into_iter()
+                .map(|constructor| {
+                    let arity = constructor.arity(cx)?;
+                    Ok(vec![Witness::Constructor(constructor, vec![Witness::Wild; arity])])
+                })
+                .collect::<MatchCheckResult<Vec<_>>>()?
+                .into_iter()
+                .chain(root_is_foreign_non_exhaustive(cx).then(|| vec![Witness::Wild]))
+                .collect(),
+            None => vec![vec![Witness::Wild]],
+        }
+    } else {
+        missing_witnesses(cx, matrix, 1)?
+    };
+
+    Ok(witnesses
+        .iter()
+        .filter_map(|witness| render_witness(cx, module, witness.first()?))
+        .collect())
+}
+
+fn root_is_foreign_non_exhaustive(cx: &MatchCheckCtx) -> bool {
+    match cx.infer[cx.match_expr].strip_references() {
+        Ty::Adt(AdtId::EnumId(enum_id), _) => cx.is_foreign_non_exhaustive(*enum_id),
+        _ => false,
+    }
+}
+
+fn root_constructors(cx: &MatchCheckCtx) -> Option<Vec<Constructor>> {
+    let constructor = match cx.infer[cx.match_expr].strip_references() {
+        Ty::Scalar(Scalar::Bool) => Constructor::Bool(true),
+        Ty::Adt(AdtId::EnumId(enum_id), _) => {
+            let local_id = cx.db.enum_data(*enum_id).variants.iter().next()?.0;
+            Constructor::Enum(EnumVariantId { parent: *enum_id, local_id })
+        }
+        _ => return None,
+    };
+    Some(constructor.all_constructors(cx))
+}
+
+/// Returns rows of `width` patterns, which together match all the values not
+/// matched by `matrix`.
+fn missing_witnesses(
+    cx: &MatchCheckCtx,
+    matrix: &Matrix,
+    width: usize,
+) -> MatchCheckResult<Vec<Vec<Witness>>> {
+    if width == 0 {
+        return Ok(if matrix.is_empty() { vec![vec![]] } else { vec![] });
+    }
+
+    let mut used_constructors: Vec<Constructor> = vec![];
+    for pat in matrix.heads() {
+        if let Some(constructor) = pat_constructor(cx, pat)? {
+            used_constructors.push(constructor);
+        }
+    }
+    let constructor = match used_constructors.first() {
+        Some(it) => it,
+        None => {
+            let mut res = missing_witnesses(cx, &matrix.specialize_wildcard(cx), width - 1)?;
+            res.iter_mut().for_each(|row| row.insert(0, Witness::Wild));
+            return Ok(res);
+        }
+    };
+
+    let mut res = vec![];
+    let mut missing_rest = None;
+    for constructor in constructor.all_constructors(cx) {
+        if used_constructors.contains(&constructor) {
+            let arity = constructor.arity(cx)?;
+            let matrix = matrix.specialize_constructor(cx, &constructor)?;
+            for mut row in missing_witnesses(cx, &matrix, arity + width - 1)? {
+                let rest = row.split_off(arity);
+                res.push(iter::once(Witness::Constructor(constructor, row)).chain(rest).collect());
+            }
+        } else {
+            // Rows starting with a wildcard are the only ones matching an unused constructor.
+            let rest = match &missing_rest {
+                Some(it) => it,
+                None => missing_rest.get_or_insert(missing_witnesses(
+                    cx,
+                    &matrix.specialize_wildcard(cx),
+                    width - 1,
+                )?),
+            };
+            let wildcards = vec![Witness::Wild; constructor.arity(cx)?];
+            for row in rest {
+                let head = Witness::Constructor(constructor, wildcards.clone());
+                res.push(iter::once(head).chain(row.iter().cloned()).collect());
+            }
+        }
+        if res.len() >= MAX_WITNESSES {
+            res.truncate(MAX_WITNESSES);
+            return Ok(res);
+        }
+    }
+    if let Constructor::Enum(e) = constructor {
+        // The variants the enum may get in the future are only matched by wildcards.
+        if cx.is_foreign_non_exhaustive(e.parent) {
+            let rest = missing_witnesses(cx, &matrix.specialize_wildcard(cx), width - 1)?;
+            res.extend(rest.into_iter().map(|row| iter::once(Witness::Wild).chain(row).collect()));
+            res.truncate(MAX_WITNESSES);
+        }
+    }
+    Ok(res)
+}
+
+fn render_witness(cx: &MatchCheckCtx, module: ModuleId, witness: &Witness) -> Option<String> {
+    let (constructor, args) = match witness {
+        Witness::Wild => return Some("_".to_string()),
+        Witness::Constructor(constructor, args) => (constructor, args),
+    };
+    let args =
+        args.iter().map(|arg| render_witness(cx, module, arg)).collect::<Option<Vec<_>>>()?;
+    let (def, variant_data): (ModuleDefId, _) = match *constructor {
+        Constructor::Bool(value) => return Some(value.to_string()),
+        Constructor::Tuple { arity: 1 } => return Some(format!("({},)", args[0])),
+        Constructor::Tuple { .. } => return Some(format!("({})", args.join(", "))),
+        Constructor::Enum(e) => {
+            (e.into(), cx.db.enum_data(e.parent).variants[e.local_id].variant_data.clone())
+        }
+        Constructor::Struct(s) => {
+            (AdtId::from(s).into(), cx.db.struct_data(s).variant_data.clone())
+        }
+    };
+    let path = find_path(cx.db.upcast(), ItemInNs::Types(def), module)?;
+    let res = match variant_data.as_ref() {
+        VariantData::Unit => path.to_string(),
+        VariantData::Tuple(_) => format!("{}({})", path, args.join(", ")),
+        VariantData::Record(fields) => {
+            let mut fields = fields
+                .iter()
+                .zip(&args)
+                .filter(|(_, arg)| *arg != "_")
+                .map(|((_, field), arg)| format!("{}: {}", field.name, arg))
+                .collect::<Vec<_>>();
+            if fields.len() < args.len() {
+                fields.push("..".to_string());
+            }
+            format!("{} {{ {} }}", path, fields.join(", "))
+        }
+    };
+    Some(res)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::diagnostics::tests::check_diagnostics;
@@ -1101,6 +1317,7 @@ fn main() {
     match (true, false) {
         (true, false, true) => (),
         (true) => (),
+       //^^^^ expected `(bool, bool)`, found `bool`
     }
     match (0) { () => () }
     match Unresolved::Bar { Unresolved::Baz => () }
@@ -1121,6 +1338,7 @@ fn main() {
     match Either::A {
         Either::A => (),
         Either::B() => (),
+      //^^^^^^^^^^^ Expected 1 field, found 0: `Either::B(u32)`
     }
 }
 "#,
@@ -1164,6 +1382,7 @@ fn foo() -> Either<!> { Either::B }
 fn main() -> u32 {
     match foo() {
         Either::A(val) => val,
+      //^^^^^^^^^^^^^^^^^^^^^ Match arm matches a value of an uninhabited type
         Either::B => 0,
     }
 }
@@ -1345,12 +1564,11 @@ fn panic(a: Category, b: Category) {
     match (a, b) {
         (Category::Zero | Category::Infinity, _) => (),
         (_, Category::Zero | Category::Infinity) => (),
+      //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ Unreachable match arm
     }
 
-    // FIXME: This is a false positive, but the code used to cause a panic in the match checker,
-    // so this acts as a regression test for that.
+    // This used to be a false positive, and before that, caused a panic in the match checker.
     match (a, b) {
-        //^^^^^^ Missing match arm
         (Category::Infinity, Category::Infinity) | (Category::Zero, Category::Zero) => (),
         (Category::Infinity | Category::Zero, _) => (),
     }
@@ -1359,6 +1577,71 @@ fn panic(a: Category, b: Category) {
         );
     }
 
+    #[test]
+    fn unreachable_arms() {
+        check_diagnostics(
+            r#"
+enum Void {}
+enum Either<T, U> { A(T), B(U) }
+
+fn main(b: bool, e: Either<bool, Void>) {
+    match b {
+        _ => (),
+        true => (),
+      //^^^^^^^^^^ Unreachable match arm
+    }
+    match b {
+        false => (),
+        true => (),
+        false | true => (),
+      //^^^^^^^^^^^^^^^^^^ Unreachable match arm
+    }
+    match (b, b) {
+        (true, _) => (),
+        (_, true) => (),
+        (false, false) => (),
+        (true, true) => (),
+      //^^^^^^^^^^^^^^^^^^ Unreachable match arm
+    }
+    match e {
+        Either::A(_) => (),
+        Either::B(_) => (),
+      //^^^^^^^^^^^^^^^^^^ Match arm matches a value of an uninhabited type
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unreachable_arms_after_guards() {
+        check_diagnostics(
+            r#"
+fn main(b: bool) {
+    match b {
+        true if b => (),
+        true => (),
+        false => (),
+        false if b => (),
+      //^^^^^^^^^^^^^^^^ Unreachable match arm
+    }
+    match b {
+        _ if b => (),
+        false | true => (),
+        true => (),
+      //^^^^^^^^^^ Unreachable match arm
+    }
+    match b {
+        true if b => (),
+        true if !b => (),
+      //^^^^^^^^^^^^^^^^ Match arm is only reachable if the guard of an earlier arm fails
+        _ => (),
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn unknown_type() {
         check_diagnostics(
@@ -1507,6 +1790,40 @@ fn main() {
 "#,
         );
     }
+
+    #[test]
+    fn foreign_non_exhaustive_enum() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:lib
+#[non_exhaustive]
+pub enum Foreign { A, B }
+#[non_exhaustive]
+pub enum Empty {}
+//- /main.rs crate:main deps:lib
+#[non_exhaustive]
+enum Local { A, B }
+fn main(empty: lib::Empty) {
+    match lib::Foreign::A {
+        //^^^^^^^^^^^^^^^ Missing match arm
+        lib::Foreign::A => (),
+        lib::Foreign::B => (),
+    }
+    match lib::Foreign::A {
+        lib::Foreign::A => (),
+        _ => (),
+    }
+    match empty {}
+        //^^^^^ Missing match arm
+    match Local::A {
+        Local::A => (),
+        Local::B => (),
+    }
+}
+"#,
+        );
+    }
+
     mod false_negatives {
         //! The implementation of match checking here is a work in progress. As we roll this out, we
         //! prefer false negatives to false positives (ideally there would be no false positives). This
@@ -0,0 +1,361 @@
+//! Finds fields, functions, constants, statics and modules which are used outside of the
+//! modules they are visible in.
+
+use std::sync::Arc;
+
+use hir_def::{
+    body::BodySourceMap,
+    expr::{Expr, ExprId},
+    path::{ModPath, Path, PathKind},
+    resolver::{resolver_for_expr, HasResolver},
+    src::{HasChildSource, HasSource},
+    visibility::Visibility,
+    AssocContainerId, AssocItemId, DefWithBodyId, FieldId, FunctionId, HasModule, Lookup,
+    ModuleDefId, ModuleId,
+};
+use hir_expand::{diagnostics::DiagnosticSink, InFile};
+use syntax::{ast, AstNode, AstPtr, SyntaxNodePtr};
+
+use crate::{
+    db::HirDatabase,
+    diagnostics::{PrivateItem, PrivateItemKind},
+    utils::variant_data,
+    InferenceResult,
+};
+
+pub(super) struct PrivacyValidator<'a, 'b: 'a> {
+    owner: DefWithBodyId,
+    infer: Arc<InferenceResult>,
+    sink: &'a mut DiagnosticSink<'b>,
+}
+
+/// An item whose visibility is checked.
+struct Target {
+    kind: PrivateItemKind,
+    visibility: Visibility,
+    source: Option<InFile<SyntaxNodePtr>>,
+    module: ModuleId,
+}
+
+impl<'a, 'b> PrivacyValidator<'a, 'b> {
+    pub(super) fn new(
+        owner: DefWithBodyId,
+        infer: Arc<InferenceResult>,
+        sink: &'a mut DiagnosticSink<'b>,
+    ) -> PrivacyValidator<'a, 'b> {
+        PrivacyValidator { owner, infer, sink }
+    }
+
+    pub(super) fn validate_body(&mut self, db: &dyn HirDatabase) {
+        let (body, source_map) = db.body_with_source_map(self.owner);
+        let module = self.owner.module(db.upcast());
+
+        for (id, expr) in body.exprs.iter() {
+            let mut targets = Vec::new();
+            match expr {
+                Expr::Field { .. } => {
+                    if let Some(field) = self.infer.field_resolution(id) {
+                        targets.push((field_target(db, field), UseSite::Expr));
+                    }
+                }
+                Expr::MethodCall { .. } => {
+                    if let Some(func) = self.infer.method_resolution(id) {
+                        let target = function_target(db, func, PrivateItemKind::Method);
+                        targets.extend(target.map(|it| (it, UseSite::Expr)));
+                    }
+                }
+                Expr::RecordLit { fields, .. } => {
+                    for (idx, field) in fields.iter().enumerate() {
+                        if let Some(field) = self.infer.record_field_resolution(field.expr) {
+                            targets.push((field_target(db, field), UseSite::RecordField(idx)));
+                        }
+                    }
+                }
+                Expr::Path(path) => targets.extend(self.path_targets(db, id, path)),
+                _ => {}
+            }
+
+            for (target, site) in targets {
+                if target.visibility.is_visible_from(db.upcast(), module) {
+                    continue;
+                }
+                let name_ref = match use_site_name(db, &source_map, id, site) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                self.sink.push(PrivateItem {
+                    file: name_ref.file_id,
+                    name: name_ref.value.text().to_string(),
+                    name_ref: AstPtr::new(&name_ref.value),
+                    kind: target.kind,
+                    target: target.source,
+                    target_in_same_crate: target.module.krate() == module.krate(),
+                });
+            }
+        }
+    }
+
+    /// Collects the modules and the item a path goes through, ignoring its first segment,
+    /// which is always visible from the scope it was found in.
+    fn path_targets(
+        &self,
+        db: &dyn HirDatabase,
+        id: ExprId,
+        path: &Path,
+    ) -> Vec<(Target, UseSite)> {
+        let mut res = Vec::new();
+        if let Some(AssocItemId::FunctionId(func)) = self.infer.assoc_resolutions_for_expr(id) {
+            let target = function_target(db, func, PrivateItemKind::Function);
+            let last = path.mod_path().segments().len().saturating_sub(1);
+            res.extend(target.map(|it| (it, UseSite::PathSegment(last))));
+        }
+        if path.type_anchor().is_some() {
+            return res;
+        }
+
+        let resolver = resolver_for_expr(db.upcast(), self.owner, id);
+        let mod_path = path.mod_path();
+        let segments = mod_path.segments();
+        let first_checked = if mod_path.kind == PathKind::Plain { 2 } else { 1 };
+        for len in first_checked..=segments.len() {
+            let prefix = ModPath::from_segments(mod_path.kind.clone(), segments[..len].to_vec());
+            let per_ns = resolver.resolve_module_path_in_items(db.upcast(), &prefix);
+            let segment = UseSite::PathSegment(len - 1);
+            if len < segments.len() {
+                match per_ns.types {
+                    Some((ModuleDefId::ModuleId(module), vis)) => {
+                        res.push((module_target(db, module, vis), segment))
+                    }
+                    // The rest of the path are associated items, which inference handles.
+                    _ => break,
+                }
+                continue;
+            }
+            let (def, vis) = match per_ns.values {
+                Some(it) => it,
+                None => break,
+            };
+            let (kind, source, module) = match def {
+                ModuleDefId::FunctionId(it) => {
+                    let loc = it.lookup(db.upcast());
+                    (
+                        PrivateItemKind::Function,
+                        ptr(loc.source(db.upcast())),
+                        loc.module(db.upcast()),
+                    )
+                }
+                ModuleDefId::ConstId(it) => {
+                    let loc = it.lookup(db.upcast());
+                    (PrivateItemKind::Const, ptr(loc.source(db.upcast())), loc.module(db.upcast()))
+                }
+                ModuleDefId::StaticId(it) => {
+                    let loc = it.lookup(db.upcast());
+                    (PrivateItemKind::Static, ptr(loc.source(db.upcast())), loc.module(db.upcast()))
+                }
+                _ => break,
+            };
+            res.push((Target { kind, visibility: vis, source: Some(source), module }, segment));
+        }
+        res
+    }
+}
+
+/// Where in the expression the item is named.
+#[derive(Clone, Copy)]
+enum UseSite {
+    /// The field or method name of a field access or method call.
+    Expr,
+    /// The field name of the record literal field with this index.
+    RecordField(usize),
+    /// The path segment with this index.
+    PathSegment(usize),
+}
+
+fn use_site_name(
+    db: &dyn HirDatabase,
+    source_map: &BodySourceMap,
+    id: ExprId,
+    site: UseSite,
+) -> Option<InFile<ast::NameRef>> {
+    let node: InFile<SyntaxNodePtr> = match site {
+        UseSite::RecordField(idx) => {
+            let field = source_map.field_syntax(id, idx);
+            InFile::new(field.file_id, field.value.into())
+        }
+        _ => {
+            let expr = source_map.expr_syntax(id).ok()?;
+            InFile::new(expr.file_id, expr.value.into())
+        }
+    };
+    // Macros may use whatever is visible from where they are defined.
+    if node.file_id.call_node(db.upcast()).is_some() {
+        return None;
+    }
+    let file_id = node.file_id;
+    let root = db.parse_or_expand(file_id)?;
+    let node = node.value.to_node(&root);
+    let name_ref = match site {
+        UseSite::Expr => match ast::Expr::cast(node)? {
+            ast::Expr::FieldExpr(it) => it.name_ref(),
+            ast::Expr::MethodCallExpr(it) => it.name_ref(),
+            _ => None,
+        },
+        UseSite::RecordField(_) => ast::RecordExprField::cast(node)?.field_name(),
+        UseSite::PathSegment(idx) => {
+            let path = match ast::Expr::cast(node)? {
+                ast::Expr::PathExpr(it) => it.path()?,
+                _ => return None,
+            };
+            let segments = path_segments(path)?;
+            segments.get(idx)?.name_ref()
+        }
+    }?;
+    Some(InFile::new(file_id, name_ref))
+}
+
+/// Returns the segments of the path, from the first one, skipping `crate`, `self` and `super`
+/// the same way `ModPath` does.
+fn path_segments(path: ast::Path) -> Option<Vec<ast::PathSegment>> {
+    let mut res = Vec::new();
+    let mut path = Some(path);
+    while let Some(it) = path {
+        if let Some(segment) = it.segment() {
+            if let ast::PathSegmentKind::Name(_) = segment.kind()? {
+                res.push(segment);
+            }
+        }
+        path = it.qualifier();
+    }
+    res.reverse();
+    Some(res)
+}
+
+fn field_target(db: &dyn HirDatabase, field: FieldId) -> Target {
+    let variant_data = variant_data(db.upcast(), field.parent);
+    let visibility = variant_data.fields()[field.local_id]
+        .visibility
+        .resolve(db.upcast(), &field.parent.resolver(db.upcast()));
+    let sources = field.parent.child_source(db.upcast());
+    let source = sources.value.get(field.local_id).map(|it| {
+        let node = it.as_ref().either(|it| it.syntax().clone(), |it| it.syntax().clone());
+        InFile::new(sources.file_id, SyntaxNodePtr::new(&node))
+    });
+    Target {
+        kind: PrivateItemKind::Field,
+        visibility,
+        source,
+        module: field.parent.module(db.upcast()),
+    }
+}
+
+fn function_target(
+    db: &dyn HirDatabase,
+    func: FunctionId,
+    kind: PrivateItemKind,
+) -> Option<Target> {
+    let loc = func.lookup(db.upcast());
+    // Items of trait impls are as visible as the trait itself.
+    if let AssocContainerId::ImplId(impl_id) = loc.container {
+        if db.impl_data(impl_id).target_trait.is_some() {
+            return None;
+        }
+    }
+    let visibility =
+        db.function_data(func).visibility.resolve(db.upcast(), &func.resolver(db.upcast()));
+    Some(Target {
+        kind,
+        visibility,
+        source: Some(ptr(loc.source(db.upcast()))),
+        module: loc.module(db.upcast()),
+    })
+}
+
+fn module_target(db: &dyn HirDatabase, module: ModuleId, visibility: Visibility) -> Target {
+    let def_map = module.def_map(db.upcast());
+    let source = def_map[module.local_id]
+        .declaration_source(db.upcast())
+        .map(|it| InFile::new(it.file_id, SyntaxNodePtr::new(it.value.syntax())));
+    Target { kind: PrivateItemKind::Module, visibility, source, module }
+}
+
+fn ptr<N: AstNode>(source: InFile<N>) -> InFile<SyntaxNodePtr> {
+    InFile::new(source.file_id, SyntaxNodePtr::new(source.value.syntax()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::check_diagnostics;
+
+    #[test]
+    fn private_field() {
+        check_diagnostics(
+            r#"
+mod m {
+    pub struct S { pub a: u32, b: u32 }
+    pub fn new() -> S { S { a: 0, b: 0 } }
+    fn inside(s: S) -> u32 { s.b }
+}
+fn main() {
+    let s = m::new();
+    s.a;
+    s.b;
+    //^ field `b` is private
+    m::S { a: 0, b: 1 };
+               //^ field `b` is private
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn private_functions_and_methods() {
+        check_diagnostics(
+            r#"
+mod m {
+    pub struct S;
+    impl S {
+        fn new() -> S { S }
+        pub fn get(&self) {}
+        fn secret(&self) {}
+    }
+    trait Tr { fn tr(&self); }
+    impl Tr for S { fn tr(&self) {} }
+    fn helper() {}
+    pub(crate) fn visible() {}
+    mod inner { fn f() { super::helper(); } }
+}
+fn main() {
+    m::helper();
+     //^^^^^^ function `helper` is private
+    m::visible();
+    let s = m::S::new();
+                //^^^ function `new` is private
+    s.get();
+    s.secret();
+    //^^^^^^ method `secret` is private
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn private_module() {
+        check_diagnostics(
+            r#"
+mod a {
+    mod b { pub fn f() {} pub const C: u32 = 0; }
+    pub mod c { static S: u32 = 0; }
+    fn g() { b::f(); }
+}
+fn main() {
+    a::b::f();
+     //^ module `b` is private
+    crate::a::b::C;
+            //^ module `b` is private
+    a::c::S;
+        //^ static `S` is private
+}
+"#,
+        );
+    }
+}
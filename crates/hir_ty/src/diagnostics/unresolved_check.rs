@@ -0,0 +1,393 @@
+//! Finds method calls and field accesses which don't resolve, and looks for
+//! what the user might have meant instead: similarly named methods and fields,
+//! and traits which would provide the method if they were imported.
+
+use std::sync::Arc;
+
+use base_db::CrateId;
+use hir_def::{
+    expr::{Expr, ExprId},
+    lang_item::LangItemTarget,
+    resolver::resolver_for_expr,
+    AdtId, AssocItemId, DefWithBodyId, HasModule, ModuleDefId, TraitId,
+};
+use hir_expand::{diagnostics::DiagnosticSink, name::Name};
+use rustc_hash::FxHashSet;
+use stdx::{edit_distance, is_typo_of};
+use syntax::{ast, AstPtr};
+
+use crate::{
+    autoderef,
+    conversion::is_known,
+    db::HirDatabase,
+    diagnostics::{UnresolvedField, UnresolvedMethodCall},
+    display::HirDisplay,
+    method_resolution::{iterate_method_candidates, LookupMode, TyFingerprint},
+    Canonical, InEnvironment, InferenceResult, TraitEnvironment, Ty, TypeWalk,
+};
+
+/// At most this many similar names are suggested.
+const MAX_SIMILAR: usize = 3;
+
+pub(super) struct UnresolvedValidator<'a, 'b: 'a> {
+    owner: DefWithBodyId,
+    infer: Arc<InferenceResult>,
+    sink: &'a mut DiagnosticSink<'b>,
+}
+
+impl<'a, 'b> UnresolvedValidator<'a, 'b> {
+    pub(super) fn new(
+        owner: DefWithBodyId,
+        infer: Arc<InferenceResult>,
+        sink: &'a mut DiagnosticSink<'b>,
+    ) -> UnresolvedValidator<'a, 'b> {
+        UnresolvedValidator { owner, infer, sink }
+    }
+
+    pub(super) fn validate_body(&mut self, db: &dyn HirDatabase) {
+        let (body, source_map) = db.body_with_source_map(self.owner);
+        let krate = self.owner.module(db.upcast()).krate();
+
+        for (id, expr) in body.exprs.iter() {
+            match expr {
+                Expr::MethodCall { receiver, method_name, .. } => {
+                    if self.infer.method_resolution(id).is_some() {
+                        continue;
+                    }
+                    let receiver_ty = &self.infer[*receiver];
+                    if !is_resolvable(receiver_ty) {
+                        continue;
+                    }
+                    let source_ptr = match source_map.expr_syntax(id) {
+                        Ok(it) => it,
+                        Err(_) => continue,
+                    };
+                    let root = source_ptr.file_syntax(db.upcast());
+                    let name_ref = match source_ptr.value.to_node(&root) {
+                        ast::Expr::MethodCallExpr(it) => it.name_ref(),
+                        _ => None,
+                    };
+                    let name_ref = match name_ref {
+                        Some(it) => it,
+                        None => continue,
+                    };
+
+                    let cx = Ctx::new(db, self.owner, id, krate, receiver_ty);
+                    if !cx.lookup_is_reliable(true) {
+                        continue;
+                    }
+                    self.sink.push(UnresolvedMethodCall {
+                        file: source_ptr.file_id,
+                        name_ref: AstPtr::new(&name_ref),
+                        name: method_name.clone(),
+                        receiver: receiver_ty.display(db).to_string(),
+                        similar: cx.similar_methods(method_name),
+                        traits_to_import: cx.traits_to_import(method_name),
+                    });
+                }
+                Expr::Field { expr, name } => {
+                    // The type of an unresolved field is inferred from the context, so it doesn't
+                    // tell whether the field resolved.
+                    if self.infer.field_resolution(id).is_some() {
+                        continue;
+                    }
+                    let receiver_ty = &self.infer[*expr];
+                    if !is_resolvable(receiver_ty) {
+                        continue;
+                    }
+                    let source_ptr = match source_map.expr_syntax(id) {
+                        Ok(it) => it,
+                        Err(_) => continue,
+                    };
+                    let root = source_ptr.file_syntax(db.upcast());
+                    let name_ref = match source_ptr.value.to_node(&root) {
+                        ast::Expr::FieldExpr(it) => it.name_ref(),
+                        _ => None,
+                    };
+                    let name_ref = match name_ref {
+                        Some(it) => it,
+                        None => continue,
+                    };
+
+                    let cx = Ctx::new(db, self.owner, id, krate, receiver_ty);
+                    if cx.has_tuple_field(name) || !cx.lookup_is_reliable(false) {
+                        continue;
+                    }
+                    self.sink.push(UnresolvedField {
+                        file: source_ptr.file_id,
+                        name_ref: AstPtr::new(&name_ref),
+                        name: name.clone(),
+                        receiver: receiver_ty.display(db).to_string(),
+                        similar: cx.similar_fields(name),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Whether method and field lookup on `ty` are reliable enough to report
+/// their failure: the type must be known, and not depend on trait bounds,
+/// which aren't always lowered completely.
+fn is_resolvable(ty: &Ty) -> bool {
+    let mut res = is_known(ty);
+    ty.walk(&mut |ty| {
+        if matches!(
+            ty,
+            Ty::Placeholder(_)
+                | Ty::Dyn(_)
+                | Ty::Opaque(_)
+                | Ty::OpaqueType(..)
+                | Ty::Projection(_)
+                | Ty::AssociatedType(..)
+                | Ty::Bound(_)
+                | Ty::ForeignType(_)
+        ) {
+            res = false;
+        }
+    });
+    res
+}
+
+struct Ctx<'a> {
+    db: &'a dyn HirDatabase,
+    krate: CrateId,
+    env: Arc<TraitEnvironment>,
+    traits_in_scope: FxHashSet<TraitId>,
+    receiver: Canonical<Ty>,
+}
+
+impl<'a> Ctx<'a> {
+    fn new(
+        db: &'a dyn HirDatabase,
+        owner: DefWithBodyId,
+        expr: ExprId,
+        krate: CrateId,
+        receiver: &Ty,
+    ) -> Ctx<'a> {
+        let resolver = resolver_for_expr(db.upcast(), owner, expr);
+        let env = TraitEnvironment::lower(db, &resolver);
+        let traits_in_scope = resolver.traits_in_scope(db.upcast());
+        Ctx { db, krate, env, traits_in_scope, receiver: Canonical::new(receiver.clone(), None) }
+    }
+
+    /// Whether the receiver derefs to a tuple with a field `name`, which has no field resolution.
+    fn has_tuple_field(&self, name: &Name) -> bool {
+        let idx = match name.as_tuple_index() {
+            Some(it) => it,
+            None => return false,
+        };
+        let ty = InEnvironment::new(self.env.clone(), self.receiver.clone());
+        autoderef(self.db, Some(self.krate), ty).any(|it| match &it.value {
+            Ty::Tuple(_, substs) => idx < substs.len(),
+            _ => false,
+        })
+    }
+
+    /// Whether autoderef of the receiver stopped because the type can't be
+    /// dereferenced any further, rather than because `Deref` or its impl for
+    /// the type couldn't be resolved. Methods of builtin types are defined by
+    /// lang item impls which might not be found either, so they are only
+    /// trusted for ADTs.
+    fn lookup_is_reliable(&self, is_method: bool) -> bool {
+        let ty = InEnvironment::new(self.env.clone(), self.receiver.clone());
+        let last = match autoderef(self.db, Some(self.krate), ty).last() {
+            Some(it) => it.value,
+            None => return false,
+        };
+        if is_method && !matches!(last, Ty::Adt(..)) {
+            return false;
+        }
+        let deref_trait = match self.db.lang_item(self.krate, "deref".into()) {
+            Some(LangItemTarget::TraitId(it)) => it,
+            _ => return false,
+        };
+        match TyFingerprint::for_impl(&last) {
+            Some(fingerprint) => self
+                .db
+                .trait_impls_in_deps(self.krate)
+                .for_trait_and_self_ty(deref_trait, fingerprint)
+                .next()
+                .is_none(),
+            None => true,
+        }
+    }
+
+    fn similar_methods(&self, name: &Name) -> Vec<Name> {
+        let mut candidates = Vec::new();
+        iterate_method_candidates(
+            &self.receiver,
+            self.db,
+            self.env.clone(),
+            self.krate,
+            &self.traits_in_scope,
+            None,
+            LookupMode::MethodCall,
+            |_, item| {
+                if let AssocItemId::FunctionId(func) = item {
+                    candidates.push(self.db.function_data(func).name.clone());
+                }
+                None::<()>
+            },
+        );
+        most_similar(candidates, name)
+    }
+
+    fn similar_fields(&self, name: &Name) -> Vec<Name> {
+        let ty = InEnvironment::new(self.env.clone(), self.receiver.clone());
+        let candidates = autoderef(self.db, Some(self.krate), ty)
+            .flat_map(|derefed| {
+                let variant_data = match derefed.value {
+                    Ty::Adt(AdtId::StructId(it), _) => self.db.struct_data(it).variant_data.clone(),
+                    Ty::Adt(AdtId::UnionId(it), _) => self.db.union_data(it).variant_data.clone(),
+                    _ => return Vec::new(),
+                };
+                variant_data.fields().iter().map(|(_, field)| field.name.clone()).collect()
+            })
+            .collect();
+        most_similar(candidates, name)
+    }
+
+    /// Returns the traits of the crate and its dependencies which provide a
+    /// method called `name` for the receiver, but aren't in scope.
+    fn traits_to_import(&self, name: &Name) -> Vec<TraitId> {
+        let crate_graph = self.db.crate_graph();
+        let mut crates = crate_graph.transitive_deps(self.krate).collect::<Vec<_>>();
+        crates.sort();
+        crates.insert(0, self.krate);
+
+        let mut res = Vec::new();
+        for krate in crates {
+            let def_map = self.db.crate_def_map(krate);
+            for (_, module) in def_map.modules() {
+                for decl in module.scope.declarations() {
+                    let trait_ = match decl {
+                        ModuleDefId::TraitId(it) => it,
+                        _ => continue,
+                    };
+                    if res.contains(&trait_)
+                        || self.traits_in_scope.contains(&trait_)
+                        || !self.db.trait_data(trait_).items.iter().any(|(it, _)| it == name)
+                    {
+                        continue;
+                    }
+                    let mut traits = FxHashSet::default();
+                    traits.insert(trait_);
+                    let provides_method = iterate_method_candidates(
+                        &self.receiver,
+                        self.db,
+                        self.env.clone(),
+                        self.krate,
+                        &traits,
+                        Some(name),
+                        LookupMode::MethodCall,
+                        |_, _| Some(()),
+                    );
+                    if provides_method.is_some() {
+                        res.push(trait_);
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+fn most_similar(mut candidates: Vec<Name>, name: &Name) -> Vec<Name> {
+    let name = name.to_string();
+    candidates.retain(|it| {
+        let candidate = it.to_string();
+        candidate != name && is_typo_of(&candidate, &name)
+    });
+    candidates.sort_by_key(|it| {
+        let candidate = it.to_string();
+        (edit_distance(&candidate, &name), candidate)
+    });
+    candidates.dedup();
+    candidates.truncate(MAX_SIMILAR);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::check_unresolved_diagnostics;
+
+    #[test]
+    fn unresolved_method_call() {
+        check_unresolved_diagnostics(
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct S;
+impl S { fn foo_bar(&self) {} }
+trait Tr { fn provided(&self) {} }
+impl Tr for S {}
+fn main() {
+    S.foo_bar();
+    S.provided();
+    S.foo_baz();
+    //^^^^^^^ no method named `foo_baz` found for `S`
+    (&&S).frobnicate();
+        //^^^^^^^^^^ no method named `frobnicate` found for `&&S`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unresolved_field() {
+        check_unresolved_diagnostics(
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct S { field: u32 }
+fn main() {
+    let s = S { field: 0 };
+    let r = &s;
+    r.field;
+    r.feild;
+    //^^^^^ no field `feild` on type `&S`
+    let n: u32 = r.count;
+                 //^^^^^ no field `count` on type `&S`
+    let t = (1, 2);
+    t.1;
+    t.2;
+    //^ no field `2` on type `(i32, i32)`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_unknown_or_generic_receivers() {
+        check_unresolved_diagnostics(
+            r#"
+#[lang = "deref"]
+trait Deref {}
+trait Tr { fn method(&self); }
+fn f<T: Tr>(t: T, d: &dyn Tr, i: impl Tr) {
+    t.unknown();
+    d.unknown();
+    i.unknown();
+    t.field;
+    Unresolved.unknown();
+    Unresolved.field;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_without_deref() {
+        check_unresolved_diagnostics(
+            r#"
+struct S;
+fn main() {
+    S.unknown();
+    S.field;
+}
+"#,
+        );
+    }
+}
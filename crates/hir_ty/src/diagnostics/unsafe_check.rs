@@ -1,17 +1,24 @@
-//! Provides validations for unsafe code. Currently checks if unsafe functions are missing
-//! unsafe blocks.
+//! Provides validations for unsafe code. Currently checks if unsafe functions, raw pointer
+//! dereferences, union field reads and mutable statics are used outside of unsafe blocks, and
+//! if unsafe blocks don't contain any unsafe operations.
 
 use std::sync::Arc;
 
+use base_db::Edition;
 use hir_def::{
+    attr::Attrs,
     body::Body,
-    expr::{Expr, ExprId, UnaryOp},
+    expr::{BinaryOp, Expr, ExprId, UnaryOp},
     resolver::{resolver_for_expr, ResolveValueResult, ValueNs},
-    DefWithBodyId,
+    DefWithBodyId, FunctionId, HasModule, Lookup, VariantId,
 };
 use hir_expand::diagnostics::DiagnosticSink;
 
-use crate::{db::HirDatabase, diagnostics::MissingUnsafe, InferenceResult, Ty};
+use crate::{
+    db::HirDatabase,
+    diagnostics::{MissingUnsafe, UnnecessaryUnsafe},
+    InferenceResult, Ty,
+};
 
 pub(super) struct UnsafeValidator<'a, 'b: 'a> {
     owner: DefWithBodyId,
@@ -29,100 +36,185 @@ impl<'a, 'b> UnsafeValidator<'a, 'b> {
     }
 
     pub(super) fn validate_body(&mut self, db: &dyn HirDatabase) {
-        let def = self.owner.into();
-        let unsafe_expressions = unsafe_expressions(db, self.infer.as_ref(), def);
+        let def = self.owner;
         let is_unsafe = match self.owner {
             DefWithBodyId::FunctionId(it) => db.function_data(it).is_unsafe,
             DefWithBodyId::StaticId(_) | DefWithBodyId::ConstId(_) => false,
         };
-        if is_unsafe
-            || unsafe_expressions
-                .iter()
-                .filter(|unsafe_expr| !unsafe_expr.inside_unsafe_block)
-                .count()
-                == 0
-        {
-            return;
-        }
+        // With `unsafe_op_in_unsafe_fn`, the body of an unsafe function needs unsafe blocks too.
+        let body_is_unsafe_context = match self.owner {
+            DefWithBodyId::FunctionId(it) if is_unsafe => !unsafe_op_in_unsafe_fn(db, it),
+            _ => false,
+        };
+        let (unsafe_expressions, unsafe_blocks) =
+            walk_body(db, self.infer.as_ref(), def, body_is_unsafe_context);
 
         let (_, body_source) = db.body_with_source_map(def);
-        for unsafe_expr in unsafe_expressions {
-            if !unsafe_expr.inside_unsafe_block {
-                if let Ok(in_file) = body_source.as_ref().expr_syntax(unsafe_expr.expr) {
-                    self.sink.push(MissingUnsafe { file: in_file.file_id, expr: in_file.value })
+        if !is_unsafe {
+            for unsafe_expr in unsafe_expressions {
+                if !unsafe_expr.inside_unsafe_block {
+                    if let Ok(in_file) = body_source.as_ref().expr_syntax(unsafe_expr.expr) {
+                        self.sink.push(MissingUnsafe { file: in_file.file_id, expr: in_file.value })
+                    }
+                }
+            }
+        }
+
+        for block in unsafe_blocks.into_iter().filter(|block| !block.is_used) {
+            if let Ok(in_file) = body_source.as_ref().expr_syntax(block.expr) {
+                // Blocks coming from macros can't be removed by the user.
+                if in_file.file_id.call_node(db.upcast()).is_some() {
+                    continue;
                 }
+                self.sink.push(UnnecessaryUnsafe {
+                    file: in_file.file_id,
+                    expr: in_file.value,
+                    nested: block.nested,
+                })
             }
         }
     }
 }
 
-pub(crate) struct UnsafeExpr {
-    pub(crate) expr: ExprId,
-    pub(crate) inside_unsafe_block: bool,
+struct UnsafeExpr {
+    expr: ExprId,
+    inside_unsafe_block: bool,
+}
+
+struct UnsafeBlock {
+    expr: ExprId,
+    /// Whether the block is inside another unsafe block or an unsafe function body.
+    nested: bool,
+    is_used: bool,
 }
 
-pub(crate) fn unsafe_expressions(
+fn walk_body(
     db: &dyn HirDatabase,
     infer: &InferenceResult,
     def: DefWithBodyId,
-) -> Vec<UnsafeExpr> {
-    let mut unsafe_exprs = vec![];
+    inside_unsafe_block: bool,
+) -> (Vec<UnsafeExpr>, Vec<UnsafeBlock>) {
     let body = db.body(def);
-    walk_unsafe(&mut unsafe_exprs, db, infer, def, &body, body.body_expr, false);
-
-    unsafe_exprs
+    let mut walker =
+        UnsafeWalker { db, infer, def, body: &body, unsafe_exprs: vec![], unsafe_blocks: vec![] };
+    walker.walk(body.body_expr, inside_unsafe_block, None);
+    (walker.unsafe_exprs, walker.unsafe_blocks)
 }
 
-fn walk_unsafe(
-    unsafe_exprs: &mut Vec<UnsafeExpr>,
-    db: &dyn HirDatabase,
-    infer: &InferenceResult,
+struct UnsafeWalker<'a> {
+    db: &'a dyn HirDatabase,
+    infer: &'a InferenceResult,
     def: DefWithBodyId,
-    body: &Body,
-    current: ExprId,
-    inside_unsafe_block: bool,
-) {
-    let expr = &body.exprs[current];
-    match expr {
-        &Expr::Call { callee, .. } => {
-            if let Some(func) = infer[callee].as_fn_def() {
-                if db.function_data(func).is_unsafe {
-                    unsafe_exprs.push(UnsafeExpr { expr: current, inside_unsafe_block });
-                }
+    body: &'a Body,
+    unsafe_exprs: Vec<UnsafeExpr>,
+    unsafe_blocks: Vec<UnsafeBlock>,
+}
+
+impl UnsafeWalker<'_> {
+    /// `unsafe_block` is the index of the outermost unsafe block containing `current`, which is
+    /// the one that an unsafe operation in `current` needs.
+    fn walk(&mut self, current: ExprId, inside_unsafe_block: bool, unsafe_block: Option<usize>) {
+        let db = self.db;
+        let infer = self.infer;
+        let body = self.body;
+        let expr = &body.exprs[current];
+        let is_unsafe_op = match expr {
+            &Expr::Call { callee, .. } => {
+                infer[callee].as_fn_def().map_or(false, |func| db.function_data(func).is_unsafe)
             }
-        }
-        Expr::Path(path) => {
-            let resolver = resolver_for_expr(db.upcast(), def, current);
-            let value_or_partial = resolver.resolve_path_in_value_ns(db.upcast(), path.mod_path());
-            if let Some(ResolveValueResult::ValueNs(ValueNs::StaticId(id))) = value_or_partial {
-                if db.static_data(id).mutable {
-                    unsafe_exprs.push(UnsafeExpr { expr: current, inside_unsafe_block });
+            Expr::Path(path) => {
+                let resolver = resolver_for_expr(db.upcast(), self.def, current);
+                let value_or_partial =
+                    resolver.resolve_path_in_value_ns(db.upcast(), path.mod_path());
+                match value_or_partial {
+                    Some(ResolveValueResult::ValueNs(ValueNs::StaticId(id))) => {
+                        db.static_data(id).mutable
+                    }
+                    _ => false,
                 }
             }
-        }
-        Expr::MethodCall { .. } => {
-            if infer
+            Expr::MethodCall { .. } => infer
                 .method_resolution(current)
                 .map(|func| db.function_data(func).is_unsafe)
-                .unwrap_or(false)
+                .unwrap_or(false),
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
+                matches!(&infer[*expr], Ty::RawPtr(..))
+            }
+            Expr::Field { .. } => is_union_field(infer, current),
+            &Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::Assignment { op: None }) }
+                if is_union_field(infer, lhs) =>
             {
-                unsafe_exprs.push(UnsafeExpr { expr: current, inside_unsafe_block });
+                // Writing to a union field is safe, only the receiver has to be checked.
+                body.exprs[lhs].walk_child_exprs(|child| {
+                    self.walk(child, inside_unsafe_block, unsafe_block);
+                });
+                return self.walk(rhs, inside_unsafe_block, unsafe_block);
             }
-        }
-        Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
-            if let Ty::RawPtr(..) = &infer[*expr] {
-                unsafe_exprs.push(UnsafeExpr { expr: current, inside_unsafe_block });
+            Expr::Unsafe { body: child } => {
+                let nested = inside_unsafe_block;
+                self.unsafe_blocks.push(UnsafeBlock { expr: current, nested, is_used: false });
+                let unsafe_block = match unsafe_block {
+                    Some(outer) => Some(outer),
+                    None if nested => None,
+                    None => Some(self.unsafe_blocks.len() - 1),
+                };
+                return self.walk(*child, true, unsafe_block);
+            }
+            _ => false,
+        };
+        if is_unsafe_op {
+            self.unsafe_exprs.push(UnsafeExpr { expr: current, inside_unsafe_block });
+            if let Some(idx) = unsafe_block {
+                self.unsafe_blocks[idx].is_used = true;
             }
         }
-        Expr::Unsafe { body: child } => {
-            return walk_unsafe(unsafe_exprs, db, infer, def, body, *child, true);
+
+        expr.walk_child_exprs(|child| self.walk(child, inside_unsafe_block, unsafe_block));
+    }
+}
+
+/// Whether the `unsafe_op_in_unsafe_fn` lint is enabled for `func`, in which case the body of an
+/// unsafe function is not an unsafe context.
+fn unsafe_op_in_unsafe_fn(db: &dyn HirDatabase, func: FunctionId) -> bool {
+    const LINT: &str = "unsafe_op_in_unsafe_fn";
+    if let Some(enabled) = lint_enabled(&db.attrs(func.into()), LINT) {
+        return enabled;
+    }
+    let module = func.lookup(db.upcast()).module(db.upcast());
+    let def_map = module.def_map(db.upcast());
+    let mut local_id = Some(module.local_id);
+    while let Some(id) = local_id {
+        if let Some(enabled) = lint_enabled(&db.attrs(def_map.module_id(id).into()), LINT) {
+            return enabled;
         }
-        _ => {}
+        local_id = def_map[id].parent;
+    }
+    match db.crate_graph()[module.krate()].edition {
+        // The lint is only warn-by-default starting with the 2024 edition.
+        Edition::Edition2015 | Edition::Edition2018 | Edition::Edition2021 => false,
     }
+}
 
-    expr.walk_child_exprs(|child| {
-        walk_unsafe(unsafe_exprs, db, infer, def, body, child, inside_unsafe_block);
-    });
+/// Looks for `lint` in the lint level attributes in `attrs`.
+fn lint_enabled(attrs: &Attrs, lint: &str) -> Option<bool> {
+    let mentions_lint = |level| {
+        attrs.by_key(level).tt_values().any(|tt| {
+            tt.token_trees.iter().any(|tt| {
+                matches!(tt, tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) if ident.text == lint)
+            })
+        })
+    };
+    if ["warn", "deny", "forbid"].iter().any(|level| mentions_lint(level)) {
+        Some(true)
+    } else if mentions_lint("allow") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn is_union_field(infer: &InferenceResult, expr: ExprId) -> bool {
+    matches!(infer.field_resolution(expr), Some(field) if matches!(field.parent, VariantId::UnionId(_)))
 }
 
 #[cfg(test)]
@@ -209,6 +301,87 @@ fn main() {
     let _ = floorf32(12.0);
           //^^^^^^^^^^^^^^ This operation is unsafe and requires an unsafe function or block
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn missing_unsafe_diagnostic_with_union_field_read() {
+        check_diagnostics(
+            r#"
+union Union {
+    a: u32,
+    b: f32,
+}
+
+fn main() {
+    let mut u = Union { a: 0 };
+    u.b = 1.0;
+    let x = u.a;
+          //^^^ This operation is unsafe and requires an unsafe function or block
+    let y = unsafe { u.b };
+}
+"#,
+        );
+    }
+    #[test]
+    fn unnecessary_unsafe_block() {
+        check_diagnostics(
+            r#"
+unsafe fn unsafe_fn() {}
+
+macro_rules! call_unsafe {
+    ($e:expr) => { unsafe { $e } };
+}
+
+fn main() {
+    unsafe { unsafe_fn() };
+    unsafe { 92 };
+  //^^^^^^^^^^^^^ unnecessary `unsafe` block
+    unsafe {
+        unsafe { unsafe_fn() };
+      //^^^^^^^^^^^^^^^^^^^^^^ unnecessary `unsafe` block: already inside an unsafe context
+    }
+    call_unsafe!(92);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unnecessary_unsafe_block_in_unsafe_fn() {
+        check_diagnostics(
+            r#"
+unsafe fn unsafe_fn() {}
+
+unsafe fn allowed() {
+    unsafe { unsafe_fn() };
+  //^^^^^^^^^^^^^^^^^^^^^^ unnecessary `unsafe` block: already inside an unsafe context
+}
+
+#[deny(unsafe_op_in_unsafe_fn)]
+unsafe fn denied() {
+    unsafe { unsafe_fn() };
+}
+"#,
+        );
+        check_diagnostics(
+            r#"
+#![warn(unsafe_op_in_unsafe_fn)]
+
+unsafe fn unsafe_fn() {}
+
+mod inner {
+    unsafe fn warned() {
+        unsafe { super::unsafe_fn() };
+    }
+
+    #[allow(unsafe_op_in_unsafe_fn)]
+    unsafe fn allowed() {
+        unsafe { super::unsafe_fn() };
+      //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ unnecessary `unsafe` block: already inside an unsafe context
+    }
+}
 "#,
         );
     }
@@ -0,0 +1,282 @@
+//! Finds local bindings (`let` bindings, function parameters, match arm and
+//! closure bindings) which are never used in the body they are declared in.
+
+use hir_def::{
+    attr::LintLevel,
+    body::Body,
+    expr::{Expr, Pat, PatId},
+    AttrDefId, DefWithBodyId,
+};
+use hir_expand::{diagnostics::DiagnosticSink, name::Name};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    ast::{self, NameOwner},
+    AstNode, AstPtr, SyntaxKind,
+};
+
+use crate::{db::HirDatabase, diagnostics::UnusedVariable};
+
+pub(super) struct UnusedValidator<'a, 'b: 'a> {
+    owner: DefWithBodyId,
+    sink: &'a mut DiagnosticSink<'b>,
+}
+
+impl<'a, 'b> UnusedValidator<'a, 'b> {
+    pub(super) fn new(
+        owner: DefWithBodyId,
+        sink: &'a mut DiagnosticSink<'b>,
+    ) -> UnusedValidator<'a, 'b> {
+        UnusedValidator { owner, sink }
+    }
+
+    pub(super) fn validate_body(&mut self, db: &dyn HirDatabase) {
+        let attr_owner: AttrDefId = match self.owner {
+            DefWithBodyId::FunctionId(it) => {
+                // Required trait methods have parameters, but nothing to use them in.
+                if !db.function_data(it).has_body {
+                    return;
+                }
+                it.into()
+            }
+            DefWithBodyId::StaticId(it) => it.into(),
+            DefWithBodyId::ConstId(it) => it.into(),
+        };
+        if db.attrs(attr_owner).lint_level("unused_variables") == Some(LintLevel::Allow) {
+            return;
+        }
+
+        let (body, source_map) = db.body_with_source_map(self.owner);
+        let used = used_bindings(db, self.owner, &body);
+        let mentioned_in_macros = names_in_unexpanded_macros(db, self.owner, &body);
+
+        for (id, pat) in body.pats.iter() {
+            let name = match pat {
+                Pat::Bind { name, .. } => name,
+                _ => continue,
+            };
+            if used.contains(&id) || !is_checked_name(name) {
+                continue;
+            }
+            if mentioned_in_macros.contains(&name.to_string()) {
+                continue;
+            }
+            let source = match source_map.pat_syntax(id) {
+                Ok(it) => it,
+                Err(_) => continue,
+            };
+            // Bindings introduced by macros are not the user's to rename.
+            if source.file_id.call_node(db.upcast()).is_some() {
+                continue;
+            }
+            // `self` parameters are never reported.
+            let ptr = match source.value.clone().left() {
+                Some(it) => it,
+                None => continue,
+            };
+            let root = source.file_syntax(db.upcast());
+            let ident = match ptr.to_node(&root) {
+                ast::Pat::IdentPat(it) => it.name(),
+                _ => None,
+            };
+            if let Some(ident) = ident {
+                self.sink.push(UnusedVariable {
+                    file: source.file_id,
+                    ident: AstPtr::new(&ident),
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn is_checked_name(name: &Name) -> bool {
+    let text = name.to_string();
+    !text.starts_with('_') && text != "self" && !text.is_empty()
+}
+
+/// Collects all bindings referred to by a path expression somewhere in the body.
+fn used_bindings(db: &dyn HirDatabase, owner: DefWithBodyId, body: &Body) -> FxHashSet<PatId> {
+    let scopes = db.expr_scopes(owner);
+    let mut used = FxHashSet::default();
+    for (id, expr) in body.exprs.iter() {
+        let name = match expr {
+            Expr::Path(path) => match path.mod_path().as_ident() {
+                Some(it) => it,
+                None => continue,
+            },
+            _ => continue,
+        };
+        let scope = match scopes.scope_for(id) {
+            Some(it) => it,
+            None => continue,
+        };
+        if let Some(entry) = scopes.resolve_name_in_scope(scope, name) {
+            used.insert(entry.pat());
+        }
+    }
+
+    // The alternatives of an or-pattern bind the same names, but only the first
+    // alternative's bindings are resolved to. Using one of them uses them all.
+    for (_, pat) in body.pats.iter() {
+        let alternatives = match pat {
+            Pat::Or(it) => it,
+            _ => continue,
+        };
+        let mut by_name: FxHashMap<Name, Vec<PatId>> = FxHashMap::default();
+        for &alt in alternatives {
+            collect_bindings(body, alt, &mut by_name);
+        }
+        for pats in by_name.values() {
+            if pats.iter().any(|pat| used.contains(pat)) {
+                used.extend(pats.iter().copied());
+            }
+        }
+    }
+
+    used
+}
+
+fn collect_bindings(body: &Body, pat: PatId, acc: &mut FxHashMap<Name, Vec<PatId>>) {
+    if let Pat::Bind { name, .. } = &body[pat] {
+        acc.entry(name.clone()).or_default().push(pat);
+    }
+    body[pat].walk_child_pats(|child| collect_bindings(body, child, acc));
+}
+
+/// Macro calls which failed to expand are lowered to `Expr::Missing`. We can't
+/// know what they do with their input, so every identifier passed to them
+/// counts as a potential use.
+fn names_in_unexpanded_macros(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    body: &Body,
+) -> FxHashSet<String> {
+    let (_, source_map) = db.body_with_source_map(owner);
+    let mut res = FxHashSet::default();
+    for (id, expr) in body.exprs.iter() {
+        if !matches!(expr, Expr::Missing) {
+            continue;
+        }
+        let source = match source_map.expr_syntax(id) {
+            Ok(it) => it,
+            Err(_) => continue,
+        };
+        let root = source.file_syntax(db.upcast());
+        let macro_call = match source.value.to_node(&root) {
+            ast::Expr::MacroCall(it) => it,
+            _ => continue,
+        };
+        if let Some(tt) = macro_call.token_tree() {
+            res.extend(
+                tt.syntax()
+                    .descendants_with_tokens()
+                    .filter_map(|it| it.into_token())
+                    .filter(|it| it.kind() == SyntaxKind::IDENT)
+                    .map(|it| it.text().to_string()),
+            );
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::check_unused_diagnostics;
+
+    #[test]
+    fn unused_let_binding() {
+        check_unused_diagnostics(
+            r#"
+fn main() {
+    let x = 92;
+      //^ unused variable: `x`
+    let y = 62;
+    let _z = y;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unused_param() {
+        check_unused_diagnostics(
+            r#"
+struct S;
+impl S {
+    fn method(&self, unused: i32, used: i32) -> i32 { used }
+                   //^^^^^^ unused variable: `unused`
+}
+trait Tr {
+    fn required(x: i32);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn used_in_closure_and_shadowed() {
+        check_unused_diagnostics(
+            r#"
+fn main() {
+    let x = 1;
+      //^ unused variable: `x`
+    let x = 2;
+    let f = |a: i32| a + x;
+    f(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn or_pattern_bindings() {
+        check_unused_diagnostics(
+            r#"
+enum E { A(i32), B(i32) }
+fn main(e: E) -> i32 {
+    match e {
+        E::A(v) | E::B(v) => v,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn record_patterns_and_shorthand() {
+        check_unused_diagnostics(
+            r#"
+struct S { a: i32, b: i32 }
+fn main(s: S) -> S {
+    let S { a, b } = s;
+             //^ unused variable: `b`
+    S { a, b: 0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn allow_unused() {
+        check_unused_diagnostics(
+            r#"
+#[allow(unused)]
+fn foo(x: i32) { let y = 92; }
+#[allow(dead_code, unused_variables)]
+fn bar(x: i32) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn names_in_unresolved_macros_are_used() {
+        check_unused_diagnostics(
+            r#"
+fn main() {
+    let x = 92;
+    unknown!("{}", x);
+}
+"#,
+        );
+    }
+}
@@ -3,8 +3,9 @@
 use std::{borrow::Cow, fmt};
 
 use crate::{
-    db::HirDatabase, primitive, utils::generics, CallableDefId, CallableSig, GenericPredicate,
-    Lifetime, Obligation, OpaqueTy, OpaqueTyId, ProjectionTy, Scalar, Substs, TraitRef, Ty,
+    closure::closure_kind, db::HirDatabase, primitive, utils::generics, CallableDefId, CallableSig,
+    GenericPredicate, Lifetime, Obligation, OpaqueTy, OpaqueTyId, ProjectionTy, Scalar, Substs,
+    TraitRef, Ty,
 };
 use arrayvec::ArrayVec;
 use hir_def::{
@@ -20,6 +21,7 @@ pub struct HirFormatter<'a> {
     curr_size: usize,
     pub(crate) max_size: Option<usize>,
     omit_verbose_types: bool,
+    closure_style: ClosureStyle,
     display_target: DisplayTarget,
 }
 
@@ -37,7 +39,14 @@ pub trait HirDisplay {
     where
         Self: Sized,
     {
-        HirDisplayWrapper { db, t: self, max_size, omit_verbose_types, display_target }
+        HirDisplayWrapper {
+            db,
+            t: self,
+            max_size,
+            omit_verbose_types,
+            closure_style: ClosureStyle::ClosureSyntax,
+            display_target,
+        }
     }
 
     /// Returns a `Display`able type that is human-readable.
@@ -51,6 +60,7 @@ pub trait HirDisplay {
             t: self,
             max_size: None,
             omit_verbose_types: false,
+            closure_style: ClosureStyle::ClosureSyntax,
             display_target: DisplayTarget::Diagnostics,
         }
     }
@@ -70,6 +80,7 @@ pub trait HirDisplay {
             t: self,
             max_size,
             omit_verbose_types: true,
+            closure_style: ClosureStyle::ClosureSyntax,
             display_target: DisplayTarget::Diagnostics,
         }
     }
@@ -89,6 +100,7 @@ pub trait HirDisplay {
             curr_size: 0,
             max_size: None,
             omit_verbose_types: false,
+            closure_style: ClosureStyle::ClosureSyntax,
             display_target: DisplayTarget::SourceCode { module_id },
         }) {
             Ok(()) => {}
@@ -108,6 +120,7 @@ pub trait HirDisplay {
             t: self,
             max_size: None,
             omit_verbose_types: false,
+            closure_style: ClosureStyle::ClosureSyntax,
             display_target: DisplayTarget::Test,
         }
     }
@@ -154,6 +167,16 @@ impl<'a> HirFormatter<'a> {
     }
 }
 
+/// How closure types are shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClosureStyle {
+    /// `|i32| -> i32`
+    ClosureSyntax,
+    /// `impl Fn(i32) -> i32`, with the `Fn*` trait the closure implements. Finding that trait
+    /// needs the closure's body, so this is not for anything computing types itself.
+    ImplFn,
+}
+
 #[derive(Clone, Copy)]
 pub enum DisplayTarget {
     /// Display types for inlays, doc popups, autocompletion, etc...
@@ -199,9 +222,17 @@ pub struct HirDisplayWrapper<'a, T> {
     t: &'a T,
     max_size: Option<usize>,
     omit_verbose_types: bool,
+    closure_style: ClosureStyle,
     display_target: DisplayTarget,
 }
 
+impl<'a, T> HirDisplayWrapper<'a, T> {
+    pub fn with_closure_style(mut self, closure_style: ClosureStyle) -> Self {
+        self.closure_style = closure_style;
+        self
+    }
+}
+
 impl<'a, T> fmt::Display for HirDisplayWrapper<'a, T>
 where
     T: HirDisplay,
@@ -214,6 +245,7 @@ where
             curr_size: 0,
             max_size: self.max_size,
             omit_verbose_types: self.omit_verbose_types,
+            closure_style: self.closure_style,
             display_target: self.display_target,
         }) {
             Ok(()) => Ok(()),
@@ -241,12 +273,9 @@ impl HirDisplay for ProjectionTy {
         }
 
         let trait_ = f.db.trait_data(self.trait_(f.db));
-        let first_parameter = self.parameters[0].into_displayable(
-            f.db,
-            f.max_size,
-            f.omit_verbose_types,
-            f.display_target,
-        );
+        let first_parameter = self.parameters[0]
+            .into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target)
+            .with_closure_style(f.closure_style);
         write!(f, "<{} as {}", first_parameter, trait_.name)?;
         if self.parameters.len() > 1 {
             write!(f, "<")?;
@@ -286,8 +315,9 @@ impl HirDisplay for Ty {
             }
             Ty::RawPtr(m, parameters) | Ty::Ref(m, parameters) => {
                 let t = parameters.as_single();
-                let ty_display =
-                    t.into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target);
+                let ty_display = t
+                    .into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target)
+                    .with_closure_style(f.closure_style);
 
                 if matches!(self, Ty::RawPtr(..)) {
                     write!(f, "*{}", m.as_keyword_for_ptr())?;
@@ -374,12 +404,9 @@ impl HirDisplay for Ty {
                 write!(f, ")")?;
                 let ret = sig.ret();
                 if *ret != Ty::unit() {
-                    let ret_display = ret.into_displayable(
-                        f.db,
-                        f.max_size,
-                        f.omit_verbose_types,
-                        f.display_target,
-                    );
+                    let ret_display = ret
+                        .into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target)
+                        .with_closure_style(f.closure_style);
 
                     write!(f, " -> {}", ret_display)?;
                 }
@@ -494,9 +521,22 @@ impl HirDisplay for Ty {
                     }
                 }
             }
-            Ty::Closure(.., substs) => {
+            Ty::Closure(def, expr, substs) => {
                 let sig = substs[0].callable_sig(f.db);
-                if let Some(sig) = sig {
+                if let (Some(sig), ClosureStyle::ImplFn) = (&sig, f.closure_style) {
+                    let kind = closure_kind(f.db, *def, *expr);
+                    write!(f, "impl {:?}(", kind)?;
+                    if !sig.params().is_empty() && f.omit_verbose_types() {
+                        write!(f, "{}", TYPE_HINT_TRUNCATION)?;
+                    } else {
+                        f.write_joined(sig.params(), ", ")?;
+                    }
+                    write!(f, ")")?;
+                    if *sig.ret() != Ty::unit() {
+                        write!(f, " -> ")?;
+                        sig.ret().hir_fmt(f)?;
+                    }
+                } else if let Some(sig) = sig {
                     if sig.params().is_empty() {
                         write!(f, "||")?;
                     } else if f.omit_verbose_types() {
@@ -507,12 +547,10 @@ impl HirDisplay for Ty {
                         write!(f, "|")?;
                     };
 
-                    let ret_display = sig.ret().into_displayable(
-                        f.db,
-                        f.max_size,
-                        f.omit_verbose_types,
-                        f.display_target,
-                    );
+                    let ret_display = sig
+                        .ret()
+                        .into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target)
+                        .with_closure_style(f.closure_style);
                     write!(f, " -> {}", ret_display)?;
                 } else {
                     write!(f, "{{closure}}")?;
@@ -585,8 +623,9 @@ impl HirDisplay for CallableSig {
         write!(f, ")")?;
         let ret = self.ret();
         if *ret != Ty::unit() {
-            let ret_display =
-                ret.into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target);
+            let ret_display = ret
+                .into_displayable(f.db, f.max_size, f.omit_verbose_types, f.display_target)
+                .with_closure_style(f.closure_style);
             write!(f, " -> {}", ret_display)?;
         }
         Ok(())
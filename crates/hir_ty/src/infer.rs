@@ -191,6 +191,11 @@ struct InferenceContext<'a> {
     table: unify::InferenceTable,
     trait_env: Arc<TraitEnvironment>,
     obligations: Vec<Obligation>,
+    /// Obligations which were ambiguous when we last tried to solve them,
+    /// together with their canonicalized form at that point. They are only
+    /// tried again once one of the variables in them got unified, since
+    /// solving the same canonical obligation again gives the same answer.
+    stalled_obligations: Vec<(Obligation, unify::Canonicalized<InEnvironment<Obligation>>)>,
     result: InferenceResult,
     /// The return type of the function being inferred, or the closure if we're
     /// currently within one.
@@ -199,6 +204,12 @@ struct InferenceContext<'a> {
     /// closures, but currently this is the only field that will change there,
     /// so it doesn't make sense.
     return_ty: Ty,
+    /// Whether `.await` can be used in the expression being inferred, that is whether it's in an
+    /// async function or block, and not in a closure inside of those.
+    is_async: bool,
+    /// The `?` expressions with the types of their operand and of the return type their error is
+    /// converted into, which are checked once the body is inferred.
+    try_conversions: Vec<(ExprId, Ty, Ty)>,
     diverges: Diverges,
     breakables: Vec<BreakableContext>,
 }
@@ -220,13 +231,22 @@ fn find_breakable<'c>(
     }
 }
 
+fn contains_unknown(ty: &Ty) -> bool {
+    let mut res = false;
+    ty.walk(&mut |it| res |= matches!(it, Ty::Unknown));
+    res
+}
+
 impl<'a> InferenceContext<'a> {
     fn new(db: &'a dyn HirDatabase, owner: DefWithBodyId, resolver: Resolver) -> Self {
         InferenceContext {
             result: InferenceResult::default(),
             table: unify::InferenceTable::new(),
             obligations: Vec::default(),
+            stalled_obligations: Vec::default(),
             return_ty: Ty::Unknown, // set in collect_fn_signature
+            is_async: false,
+            try_conversions: Vec::new(),
             trait_env: TraitEnvironment::lower(db, &resolver),
             db,
             owner,
@@ -238,6 +258,7 @@ impl<'a> InferenceContext<'a> {
     }
 
     fn resolve_all(mut self) -> InferenceResult {
+        self.check_try_conversions();
         // FIXME resolve obligations as well (use Guidance if necessary)
         let mut result = std::mem::take(&mut self.result);
         for ty in result.type_of_expr.values_mut() {
@@ -248,9 +269,61 @@ impl<'a> InferenceContext<'a> {
             let resolved = self.table.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
             *ty = resolved;
         }
+        for mismatch in result.type_mismatches.values_mut() {
+            mismatch.expected =
+                self.table.resolve_ty_completely(mem::replace(&mut mismatch.expected, Ty::Unknown));
+            mismatch.actual =
+                self.table.resolve_ty_completely(mem::replace(&mut mismatch.actual, Ty::Unknown));
+        }
         result
     }
 
+    /// Reports the `?` expressions whose error type doesn't implement `From` the error type of the
+    /// `Result` they return.
+    fn check_try_conversions(&mut self) {
+        let try_conversions = mem::take(&mut self.try_conversions);
+        let result_enum =
+            self.resolver.resolve_known_enum(self.db.upcast(), &path![core::result::Result]);
+        let from_trait =
+            self.resolver.resolve_known_trait(self.db.upcast(), &path![core::convert::From]);
+        let (result_enum, from_trait, krate) =
+            match (result_enum, from_trait, self.resolver.krate()) {
+                (Some(result_enum), Some(from_trait), Some(krate)) => {
+                    (result_enum, from_trait, krate)
+                }
+                _ => return,
+            };
+        for (expr, operand_ty, return_ty) in try_conversions {
+            let mut error_ty = |ty| match self.table.resolve_ty_completely(ty) {
+                Ty::Adt(AdtId::EnumId(it), substs) if it == result_enum => substs.0.get(1).cloned(),
+                _ => None,
+            };
+            let (from_ty, to_ty) = match (error_ty(operand_ty), error_ty(return_ty)) {
+                (Some(from_ty), Some(to_ty)) => (from_ty, to_ty),
+                _ => continue,
+            };
+            if from_ty == to_ty || contains_unknown(&from_ty) || contains_unknown(&to_ty) {
+                continue;
+            }
+            let substs = Substs::build_for_def(self.db, from_trait)
+                .push(to_ty.clone())
+                .push(from_ty.clone())
+                .build();
+            let implements_from = InEnvironment::new(
+                self.trait_env.clone(),
+                Obligation::Trait(TraitRef { trait_: from_trait, substs }),
+            );
+            let goal = self.canonicalizer().canonicalize_obligation(implements_from);
+            if self.db.trait_solve(krate, goal.value).is_none() {
+                self.push_diagnostic(InferenceDiagnostic::MissingErrorConversion {
+                    expr,
+                    from_ty,
+                    to_ty,
+                });
+            }
+        }
+    }
+
     fn write_expr_ty(&mut self, expr: ExprId, ty: Ty) {
         self.result.type_of_expr.insert(expr, ty);
     }
@@ -309,7 +382,14 @@ impl<'a> InferenceContext<'a> {
     }
 
     fn resolve_obligations_as_possible(&mut self) {
-        let obligations = mem::replace(&mut self.obligations, Vec::new());
+        let mut obligations = mem::replace(&mut self.obligations, Vec::new());
+        for (obligation, canonicalized) in mem::take(&mut self.stalled_obligations) {
+            if canonicalized.is_unchanged(&mut self.table) {
+                self.stalled_obligations.push((obligation, canonicalized));
+            } else {
+                obligations.push(obligation);
+            }
+        }
         for obligation in obligations {
             let in_env = InEnvironment::new(self.trait_env.clone(), obligation.clone());
             let canonicalized = self.canonicalizer().canonicalize_obligation(in_env);
@@ -326,7 +406,7 @@ impl<'a> InferenceContext<'a> {
                 }
                 Some(_) => {
                     // FIXME use this when trying to resolve everything at the end
-                    self.obligations.push(obligation);
+                    self.stalled_obligations.push((obligation, canonicalized));
                 }
                 None => {
                     // FIXME obligation cannot be fulfilled => diagnostic
@@ -538,6 +618,7 @@ impl<'a> InferenceContext<'a> {
         }
         let return_ty = self.make_ty_with_mode(&data.ret_type, ImplTraitLoweringMode::Disallowed); // FIXME implement RPIT
         self.return_ty = return_ty;
+        self.is_async = data.is_async;
     }
 
     fn infer_body(&mut self) {
@@ -775,18 +856,41 @@ impl std::ops::BitOrAssign for Diverges {
 }
 
 mod diagnostics {
-    use hir_def::{expr::ExprId, DefWithBodyId};
+    use hir_def::{
+        expr::ExprId, path::path, resolver::HasResolver, AdtId, DefWithBodyId, HasModule,
+    };
     use hir_expand::diagnostics::DiagnosticSink;
 
     use crate::{
         db::HirDatabase,
-        diagnostics::{BreakOutsideOfLoop, NoSuchField},
+        diagnostics::{AwaitOutsideOfAsync, MissingErrorConversion, NoSuchField, TryInNonTryFn},
+        display::HirDisplay,
+        Ty,
     };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(super) enum InferenceDiagnostic {
-        NoSuchField { expr: ExprId, field: usize },
-        BreakOutsideOfLoop { expr: ExprId },
+        NoSuchField {
+            expr: ExprId,
+            field: usize,
+        },
+        AwaitOutsideOfAsync {
+            expr: ExprId,
+        },
+        /// `expr` is the `?` expression, and `return_ty` the return type which doesn't implement
+        /// `Try`.
+        TryInNonTryFn {
+            expr: ExprId,
+            return_ty: Ty,
+            operand_ty: Ty,
+        },
+        /// `expr` is the `?` expression, whose error type `from_ty` doesn't convert into the error
+        /// type `to_ty` of the return type.
+        MissingErrorConversion {
+            expr: ExprId,
+            from_ty: Ty,
+            to_ty: Ty,
+        },
     }
 
     impl InferenceDiagnostic {
@@ -802,14 +906,60 @@ mod diagnostics {
                     let field = source_map.field_syntax(*expr, *field);
                     sink.push(NoSuchField { file: field.file_id, field: field.value })
                 }
-                InferenceDiagnostic::BreakOutsideOfLoop { expr } => {
+                InferenceDiagnostic::AwaitOutsideOfAsync { expr } => {
+                    let (_, source_map) = db.body_with_source_map(owner);
+                    if let Ok(source) = source_map.expr_syntax(*expr) {
+                        sink.push(AwaitOutsideOfAsync { file: source.file_id, expr: source.value })
+                    }
+                }
+                InferenceDiagnostic::TryInNonTryFn { expr, return_ty, operand_ty } => {
                     let (_, source_map) = db.body_with_source_map(owner);
-                    let ptr = source_map
-                        .expr_syntax(*expr)
-                        .expect("break outside of loop in synthetic syntax");
-                    sink.push(BreakOutsideOfLoop { file: ptr.file_id, expr: ptr.value })
+                    if let Ok(source) = source_map.expr_syntax(*expr) {
+                        sink.push(TryInNonTryFn {
+                            file: source.file_id,
+                            expr: source.value,
+                            return_ty: return_ty.display(db).to_string(),
+                            suggested_return_ty: suggest_return_ty(
+                                db, owner, return_ty, operand_ty,
+                            ),
+                        })
+                    }
+                }
+                InferenceDiagnostic::MissingErrorConversion { expr, from_ty, to_ty } => {
+                    let (_, source_map) = db.body_with_source_map(owner);
+                    if let Ok(source) = source_map.expr_syntax(*expr) {
+                        sink.push(MissingErrorConversion {
+                            file: source.file_id,
+                            expr: source.value,
+                            from_ty: from_ty.display(db).to_string(),
+                            to_ty: to_ty.display(db).to_string(),
+                        })
+                    }
                 }
             }
         }
     }
+
+    /// The return type wrapping `return_ty` the operand of `?` could be propagated into.
+    fn suggest_return_ty(
+        db: &dyn HirDatabase,
+        owner: DefWithBodyId,
+        return_ty: &Ty,
+        operand_ty: &Ty,
+    ) -> Option<String> {
+        let resolver = owner.resolver(db.upcast());
+        let (enum_, substs) = match operand_ty {
+            Ty::Adt(AdtId::EnumId(enum_), substs) => (*enum_, substs),
+            _ => return None,
+        };
+        let return_ty = return_ty.display_source_code(db, owner.module(db.upcast())).ok()?;
+        if Some(enum_) == resolver.resolve_known_enum(db.upcast(), &path![core::option::Option]) {
+            return Some(format!("Option<{}>", return_ty));
+        }
+        if Some(enum_) != resolver.resolve_known_enum(db.upcast(), &path![core::result::Result]) {
+            return None;
+        }
+        let error_ty = substs.0.get(1)?.display_source_code(db, owner.module(db.upcast())).ok()?;
+        Some(format!("Result<{}, {}>", return_ty, error_ty))
+    }
 }
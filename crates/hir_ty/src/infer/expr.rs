@@ -5,7 +5,7 @@ use std::{mem, sync::Arc};
 
 use hir_def::{
     expr::{Array, BinaryOp, Expr, ExprId, Literal, Statement, UnaryOp},
-    path::{GenericArg, GenericArgs},
+    path::{path, GenericArg, GenericArgs},
     resolver::resolver_for_expr,
     AdtId, AssocContainerId, FieldId, Lookup,
 };
@@ -168,9 +168,14 @@ impl<'a> InferenceContext<'a> {
                 Ty::Unknown
             }
             Expr::Async { body } => {
+                // `return` and `?` exit the block rather than the function.
+                let prev_ret_ty = mem::replace(&mut self.return_ty, Ty::Unknown);
+                let prev_is_async = mem::replace(&mut self.is_async, true);
                 // Use the first type parameter as the output type of future.
                 // existenail type AsyncBlockImplTrait<InnerType>: Future<Output = InnerType>
                 let inner_ty = self.infer_expr(*body, &Expectation::none());
+                self.return_ty = prev_ret_ty;
+                self.is_async = prev_is_async;
                 let opaque_ty_id = OpaqueTyId::AsyncBlockTypeImplTrait(self.owner, *body);
                 Ty::OpaqueType(opaque_ty_id, Substs::single(inner_ty))
             }
@@ -226,7 +231,7 @@ impl<'a> InferenceContext<'a> {
                 self.diverges = Diverges::Maybe;
                 Ty::unit()
             }
-            Expr::Lambda { body, args, ret_type, arg_types } => {
+            Expr::Lambda { body, args, ret_type, arg_types, .. } => {
                 assert_eq!(args.len(), arg_types.len());
 
                 let mut sig_tys = Vec::new();
@@ -267,11 +272,13 @@ impl<'a> InferenceContext<'a> {
 
                 let prev_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
                 let prev_ret_ty = mem::replace(&mut self.return_ty, ret_ty.clone());
+                let prev_is_async = mem::replace(&mut self.is_async, false);
 
                 self.infer_expr_coerce(*body, &Expectation::has_type(ret_ty));
 
                 self.diverges = prev_diverges;
                 self.return_ty = prev_ret_ty;
+                self.is_async = prev_is_async;
 
                 closure_ty
             }
@@ -353,10 +360,6 @@ impl<'a> InferenceContext<'a> {
                 if let Some(ctxt) = find_breakable(&mut self.breakables, label.as_ref()) {
                     ctxt.break_ty = merged_type;
                     ctxt.may_break = true;
-                } else {
-                    self.push_diagnostic(InferenceDiagnostic::BreakOutsideOfLoop {
-                        expr: tgt_expr,
-                    });
                 }
                 Ty::Never
             }
@@ -447,11 +450,17 @@ impl<'a> InferenceContext<'a> {
                 self.normalize_associated_types_in(ty)
             }
             Expr::Await { expr } => {
+                if !self.is_async {
+                    self.push_diagnostic(InferenceDiagnostic::AwaitOutsideOfAsync {
+                        expr: tgt_expr,
+                    });
+                }
                 let inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
                 self.resolve_associated_type(inner_ty, self.resolve_future_future_output())
             }
             Expr::Try { expr } => {
                 let inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
+                self.check_try_return_ty(tgt_expr, &inner_ty);
                 self.resolve_associated_type(inner_ty, self.resolve_ops_try_ok())
             }
             Expr::Cast { expr, type_ref } => {
@@ -770,6 +779,33 @@ impl<'a> InferenceContext<'a> {
         ty
     }
 
+    /// Reports `?` used in a function or closure whose return type doesn't implement `Try`. The
+    /// return types which aren't known yet, and the crates without `Try`, aren't checked.
+    fn check_try_return_ty(&mut self, expr: ExprId, operand_ty: &Ty) {
+        let return_ty = self.resolve_ty_as_possible(self.return_ty.clone());
+        if matches!(return_ty, Ty::Unknown | Ty::Infer(_)) {
+            return;
+        }
+        let try_trait = self.resolver.resolve_known_trait(self.db.upcast(), &path![core::ops::Try]);
+        let (try_trait, krate) = match (try_trait, self.resolver.krate()) {
+            (Some(try_trait), Some(krate)) => (try_trait, krate),
+            _ => return,
+        };
+        let canonicalized = self.canonicalizer().canonicalize_ty(return_ty.clone());
+        if method_resolution::implements_trait(
+            &canonicalized.value,
+            self.db,
+            self.trait_env.clone(),
+            krate,
+            try_trait,
+        ) {
+            self.try_conversions.push((expr, operand_ty.clone(), return_ty));
+            return;
+        }
+        let operand_ty = self.resolve_ty_as_possible(operand_ty.clone());
+        self.push_diagnostic(InferenceDiagnostic::TryInNonTryFn { expr, return_ty, operand_ty });
+    }
+
     fn infer_method_call(
         &mut self,
         tgt_expr: ExprId,
@@ -33,7 +33,7 @@ where
     var_stack: Vec<TypeVarId>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(super) struct Canonicalized<T> {
     pub(super) value: Canonical<T>,
     free_vars: Vec<InferTy>,
@@ -127,6 +127,17 @@ where
 }
 
 impl<T> Canonicalized<T> {
+    /// Whether none of the free variables of the canonicalized value has been
+    /// unified with anything since, so canonicalizing the value again would
+    /// give the same result.
+    pub(super) fn is_unchanged(&self, table: &mut InferenceTable) -> bool {
+        self.free_vars.iter().all(|var| {
+            let inner = var.to_inner();
+            table.var_unification_table.inlined_probe_value(inner).known().is_none()
+                && table.var_unification_table.find(inner) == inner
+        })
+    }
+
     pub(super) fn decanonicalize_ty(&self, mut ty: Ty) -> Ty {
         ty.walk_mut_binders(
             &mut |ty, binders| {
@@ -226,10 +237,16 @@ impl InferenceTable {
     }
 
     pub(crate) fn resolve_ty_completely(&mut self, ty: Ty) -> Ty {
+        if !has_infer_vars(&ty) {
+            return ty;
+        }
         self.resolve_ty_completely_inner(&mut Vec::new(), ty)
     }
 
     pub(crate) fn resolve_ty_as_possible(&mut self, ty: Ty) -> Ty {
+        if !has_infer_vars(&ty) {
+            return ty;
+        }
         self.resolve_ty_as_possible_inner(&mut Vec::new(), ty)
     }
 
@@ -428,6 +445,12 @@ impl InferenceTable {
     }
 }
 
+fn has_infer_vars(ty: &Ty) -> bool {
+    let mut has_vars = false;
+    ty.walk(&mut |ty| has_vars |= matches!(ty, Ty::Infer(_)));
+    has_vars
+}
+
 /// The ID of a type variable.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct TypeVarId(pub(super) u32);
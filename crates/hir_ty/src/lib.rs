@@ -17,6 +17,9 @@ pub(crate) mod utils;
 pub mod display;
 pub mod db;
 pub mod diagnostics;
+pub mod closure;
+pub mod conversion;
+pub mod panics;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,108 @@
+//! Finds expressions in a body which can panic at runtime.
+//!
+//! This is a best-effort check working on the inferred body: it knows about
+//! the panicking operations built into the language and the most common
+//! panicking library methods, but doesn't look into called functions.
+
+use hir_def::{
+    expr::{ArithOp, BinaryOp, Expr, ExprId, Literal, UnaryOp},
+    path::path,
+    resolver::HasResolver,
+    AdtId, DefWithBodyId,
+};
+
+use crate::{db::HirDatabase, Scalar, Ty};
+
+/// The reason an expression may panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PanicKind {
+    /// `unwrap` and friends on an `Option` or `Result`.
+    Unwrap,
+    /// Indexing, which panics when out of bounds.
+    Index,
+    /// Integer division or remainder by a possibly zero divisor.
+    Division,
+    /// Integer arithmetic, which panics on overflow in debug builds.
+    Overflow,
+    /// Calls to diverging functions, like the ones `panic!` expands to.
+    Explicit,
+}
+
+const UNWRAP_METHODS: &[&str] = &["unwrap", "expect", "unwrap_err", "expect_err"];
+
+/// Returns all expressions of `owner` which may panic, in body order.
+pub fn possible_panics(db: &dyn HirDatabase, owner: DefWithBodyId) -> Vec<(ExprId, PanicKind)> {
+    let body = db.body(owner);
+    let infer = db.infer(owner);
+    let resolver = owner.resolver(db.upcast());
+    let fallible_enums = [path![core::option::Option], path![core::result::Result]]
+        .iter()
+        .filter_map(|path| resolver.resolve_known_enum(db.upcast(), path))
+        .collect::<Vec<_>>();
+
+    let mut res = Vec::new();
+    for (id, expr) in body.exprs.iter() {
+        let kind = match expr {
+            Expr::MethodCall { receiver, method_name, .. } => {
+                let is_fallible = match infer[*receiver].strip_references() {
+                    Ty::Adt(AdtId::EnumId(it), _) => fallible_enums.contains(it),
+                    _ => false,
+                };
+                let is_unwrap = UNWRAP_METHODS.contains(&method_name.to_string().as_str());
+                if is_fallible && is_unwrap && infer.method_resolution(id).is_some() {
+                    PanicKind::Unwrap
+                } else if infer[id] == Ty::Never {
+                    PanicKind::Explicit
+                } else {
+                    continue;
+                }
+            }
+            Expr::Call { .. } if infer[id] == Ty::Never => PanicKind::Explicit,
+            Expr::Index { .. } => PanicKind::Index,
+            Expr::BinaryOp { lhs, rhs, op: Some(op) } => {
+                let op = match op {
+                    BinaryOp::ArithOp(op) | BinaryOp::Assignment { op: Some(op) } => op,
+                    _ => continue,
+                };
+                if !is_integer(&infer[*lhs]) {
+                    continue;
+                }
+                match op {
+                    ArithOp::Div | ArithOp::Rem => {
+                        let divisor = &body.exprs[*rhs];
+                        // Dividing by a positive constant can't panic.
+                        if matches!(
+                            divisor,
+                            Expr::Literal(Literal::Int(1..=u64::MAX, _))
+                                | Expr::Literal(Literal::Uint(1..=u64::MAX, _))
+                        ) {
+                            continue;
+                        }
+                        PanicKind::Division
+                    }
+                    ArithOp::Add | ArithOp::Sub | ArithOp::Mul | ArithOp::Shl | ArithOp::Shr => {
+                        PanicKind::Overflow
+                    }
+                    ArithOp::BitXor | ArithOp::BitOr | ArithOp::BitAnd => continue,
+                }
+            }
+            Expr::UnaryOp { expr, op: UnaryOp::Neg } => {
+                // `-1` is a constant, not a negation that can overflow.
+                if matches!(infer[*expr], Ty::Scalar(Scalar::Int(_)))
+                    && !matches!(body.exprs[*expr], Expr::Literal(_))
+                {
+                    PanicKind::Overflow
+                } else {
+                    continue;
+                }
+            }
+            _ => continue,
+        };
+        res.push((id, kind));
+    }
+    res
+}
+
+fn is_integer(ty: &Ty) -> bool {
+    matches!(ty, Ty::Scalar(Scalar::Int(_)) | Ty::Scalar(Scalar::Uint(_)))
+}
@@ -29,11 +29,13 @@ use syntax::{
     ast::{self, AstNode, NameOwner},
     SyntaxNode,
 };
+use test_utils::{bench, bench_fixture, skip_slow_tests};
 use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Registry};
 use tracing_tree::HierarchicalLayer;
 
 use crate::{
-    db::HirDatabase, display::HirDisplay, infer::TypeMismatch, test_db::TestDB, InferenceResult, Ty,
+    db::HirDatabase, display::HirDisplay, infer::TypeMismatch, test_db::TestDB, InferenceResult,
+    Ty, TypeWalk,
 };
 
 // These tests compare the inference results for all expressions in a file
@@ -363,3 +365,33 @@ fn check_infer_with_mismatches(ra_fixture: &str, expect: Expect) {
     actual.push('\n');
     expect.assert_eq(&actual);
 }
+
+#[test]
+fn benchmark_deep_generic_chains() {
+    if skip_slow_tests() {
+        return;
+    }
+    let fixture = bench_fixture::deep_generic_chains();
+    let (db, file_id) = TestDB::with_single_file(&fixture);
+    let module = db.module_for_file(file_id);
+    let def_map = module.def_map(&db);
+    let mut defs = Vec::new();
+    visit_module(&db, &def_map, module.local_id, &mut |it| defs.push(it));
+
+    let hash: usize = {
+        let _pt = bench("deep generic chains inference");
+        defs.into_iter()
+            .map(|def| {
+                let infer = db.infer(def);
+                infer.type_of_expr.iter().filter(|(_, ty)| !contains_unknown(ty)).count()
+            })
+            .sum()
+    };
+    assert_eq!(hash, 1246);
+}
+
+fn contains_unknown(ty: &Ty) -> bool {
+    let mut res = false;
+    ty.walk(&mut |it| res |= matches!(it, Ty::Unknown));
+    res
+}
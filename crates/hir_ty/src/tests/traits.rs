@@ -1,6 +1,9 @@
 use expect_test::expect;
+use stdx::format_to;
 use test_utils::mark;
 
+use crate::traits::CHALK_SOLVER_FUEL;
+
 use super::{check_infer, check_infer_with_mismatches, check_types};
 
 #[test]
@@ -3205,3 +3208,47 @@ fn f() {
         "#,
     );
 }
+
+/// A fixture where `S: Tr` holds through an impl with `width` bounds, each of which is proven
+/// through a chain of `depth` blanket impls, and `Tr::Out` is `u8`.
+fn trait_chain_fixture(width: i32, depth: i32, expected: &str) -> String {
+    let mut res = String::from("struct S;\n");
+    let mut bounds = Vec::new();
+    for i in 0..width {
+        for j in 0..depth {
+            format_to!(
+                res,
+                "trait A{}_{} {{}}\nimpl<T: A{}_{}> A{}_{} for T {{}}\n",
+                i,
+                j,
+                i,
+                j + 1,
+                i,
+                j
+            );
+        }
+        format_to!(res, "trait A{}_{} {{}}\nimpl A{}_{} for S {{}}\n", i, depth, i, depth);
+        bounds.push(format!("A{}_0", i));
+    }
+    format_to!(
+        res,
+        "trait Tr {{ type Out; }}\nimpl<T: {}> Tr for T {{ type Out = u8; }}\n",
+        bounds.join(" + ")
+    );
+    format_to!(res, "fn get<T: Tr>(t: T) -> T::Out {{ loop {{}} }}\n");
+    format_to!(res, "fn f() {{\n    get(S);\n  //^^^^^^ {}\n}}\n", expected);
+    res
+}
+
+#[test]
+fn trait_solving_out_of_fuel() {
+    mark::check!(trait_solver_out_of_fuel);
+    // Every bound takes one impl lookup, so the solver runs out of fuel before proving all of
+    // them, and gives up on the goal instead of guessing.
+    check_types(&trait_chain_fixture(CHALK_SOLVER_FUEL + 1, 0, "{unknown}"));
+}
+
+#[test]
+fn trait_solving_deep_chains_within_fuel() {
+    check_types(&trait_chain_fixture(10, 40, "u8"));
+}
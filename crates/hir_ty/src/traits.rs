@@ -1,4 +1,5 @@
 //! Trait solving using Chalk.
+use std::cell::Cell;
 use std::env::var;
 use std::sync::Arc;
 
@@ -7,6 +8,7 @@ use chalk_ir::cast::Cast;
 use chalk_solve::{logging_db::LoggingRustIrDatabase, Solver};
 use hir_def::{lang_item::LangItemTarget, TraitId};
 use stdx::panic_context;
+use test_utils::mark;
 
 use crate::{db::HirDatabase, DebruijnIndex, Substs};
 
@@ -16,13 +18,50 @@ use self::chalk::{from_chalk, Interner, ToChalk};
 
 pub(crate) mod chalk;
 
-/// This controls how much 'time' we give the Chalk solver before giving up.
-const CHALK_SOLVER_FUEL: i32 = 100;
+/// This controls how much 'time' we give the Chalk solver before giving up: the
+/// number of times it may look for the impls of a trait while solving a goal.
+/// Unlike a deadline, this keeps the result of solving a goal deterministic.
+pub(crate) const CHALK_SOLVER_FUEL: i32 = 1000;
 
 #[derive(Debug, Copy, Clone)]
 struct ChalkContext<'a> {
     db: &'a dyn HirDatabase,
     krate: CrateId,
+    /// The limits of the goal being solved, if any.
+    limits: Option<&'a SolverLimits>,
+}
+
+/// Limits how long the solver may work on a goal, since goals in deeply
+/// recursive trait hierarchies can take practically forever to solve.
+///
+/// The recursive solver doesn't support `should_continue`, so once the fuel is
+/// exhausted, the solver is starved instead: it doesn't find any more impls,
+/// which makes it bottom out quickly. Its result is then discarded.
+#[derive(Debug)]
+struct SolverLimits {
+    fuel: Cell<i32>,
+}
+
+impl SolverLimits {
+    fn new() -> SolverLimits {
+        let fuel = var("CHALK_SOLVER_FUEL").ok().and_then(|s| s.parse().ok());
+        SolverLimits { fuel: Cell::new(fuel.unwrap_or(CHALK_SOLVER_FUEL)) }
+    }
+
+    /// Whether the solver was refused an impl lookup.
+    fn is_exceeded(&self) -> bool {
+        self.fuel.get() < 0
+    }
+
+    /// Consumes one unit of fuel, returning whether the solver may go on.
+    fn consume(&self) -> bool {
+        let remaining = self.fuel.get();
+        self.fuel.set(remaining - 1);
+        if remaining == 0 {
+            log::debug!("fuel exhausted");
+        }
+        remaining > 0
+    }
 }
 
 fn create_chalk_solver() -> chalk_recursive::RecursiveSolver<Interner> {
@@ -126,6 +165,8 @@ pub(crate) fn trait_solve_query(
         Obligation::Trait(it) => db.trait_data(it.trait_).name.to_string(),
         Obligation::Projection(_) => "projection".to_string(),
     });
+    // Displaying closure types needs the inference result of their body, which may be the one
+    // being inferred right now, so only the debug representation can be logged here.
     log::info!("trait_solve_query({})", goal.value.value.display(db));
 
     if let Obligation::Projection(pred) = &goal.value.value {
@@ -149,20 +190,14 @@ fn solve(
     krate: CrateId,
     goal: &chalk_ir::UCanonical<chalk_ir::InEnvironment<chalk_ir::Goal<Interner>>>,
 ) -> Option<chalk_solve::Solution<Interner>> {
-    let context = ChalkContext { db, krate };
+    let limits = SolverLimits::new();
+    let context = ChalkContext { db, krate, limits: Some(&limits) };
     log::debug!("solve goal: {:?}", goal);
     let mut solver = create_chalk_solver();
 
-    let fuel = std::cell::Cell::new(CHALK_SOLVER_FUEL);
-
     let should_continue = || {
         context.db.check_canceled();
-        let remaining = fuel.get();
-        fuel.set(remaining - 1);
-        if remaining == 0 {
-            log::debug!("fuel exhausted");
-        }
-        remaining > 0
+        !limits.is_exceeded()
     };
 
     let mut solve = || {
@@ -190,6 +225,12 @@ fn solve(
     let solution =
         if is_chalk_debug() { chalk::tls::set_current_program(db, solve) } else { solve() };
 
+    if limits.is_exceeded() {
+        // The solver was starved of impls, so its solution can't be trusted.
+        mark::hit!(trait_solver_out_of_fuel);
+        log::warn!("trait solver ran out of fuel solving {:?}", goal);
+        return Some(chalk_solve::Solution::Ambig(chalk_solve::Guidance::Unknown));
+    }
     solution
 }
 
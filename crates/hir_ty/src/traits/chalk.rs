@@ -82,6 +82,11 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
         binders: &CanonicalVarKinds<Interner>,
     ) -> Vec<ImplId> {
         debug!("impls_for_trait {:?}", trait_id);
+        if let Some(limits) = self.limits {
+            if !limits.consume() {
+                return Vec::new();
+            }
+        }
         let trait_: hir_def::TraitId = from_chalk(self.db, trait_id);
 
         let ty: Ty = from_chalk(self.db, parameters[0].assert_ty_ref(&Interner).clone());
@@ -371,7 +376,7 @@ pub(crate) fn program_clauses_for_chalk_env_query(
     krate: CrateId,
     environment: chalk_ir::Environment<Interner>,
 ) -> chalk_ir::ProgramClauses<Interner> {
-    chalk_solve::program_clauses_for_env(&ChalkContext { db, krate }, &environment)
+    chalk_solve::program_clauses_for_env(&ChalkContext { db, krate, limits: None }, &environment)
 }
 
 pub(crate) fn associated_ty_data_query(
@@ -1,11 +1,13 @@
 use hir::Semantics;
 use ide_db::{
     base_db::{FileId, FilePosition, FileRange, SourceDatabase},
+    helpers::wildcard_arm::absorbed_variants,
     RootDatabase, SymbolKind,
 };
-use syntax::TextRange;
+use syntax::{algo::find_node_at_offset, ast, AstNode, TextRange};
 
 use crate::{
+    display::TryToNav,
     file_structure::file_structure,
     fn_references::find_all_methods,
     goto_implementation::goto_implementation,
@@ -17,7 +19,8 @@ use crate::{
 // Feature: Annotations
 //
 // Provides user with annotations above items for looking up references or impl blocks
-// and running/debugging binaries.
+// and running/debugging binaries, and above `_` arms of matches on enums for listing the
+// variants they handle.
 #[derive(Debug)]
 pub struct Annotation {
     pub range: TextRange,
@@ -29,6 +32,7 @@ pub enum AnnotationKind {
     Runnable { debug: bool, runnable: Runnable },
     HasImpls { position: FilePosition, data: Option<Vec<NavigationTarget>> },
     HasReferences { position: FilePosition, data: Option<Vec<FileRange>> },
+    AbsorbedVariants { position: FilePosition, data: Option<Vec<NavigationTarget>> },
 }
 
 pub struct AnnotationConfig {
@@ -37,6 +41,7 @@ pub struct AnnotationConfig {
     pub annotate_impls: bool,
     pub annotate_references: bool,
     pub annotate_method_references: bool,
+    pub annotate_wildcard_arms: bool,
     pub run: bool,
     pub debug: bool,
 }
@@ -119,6 +124,24 @@ pub(crate) fn annotations(
         }));
     }
 
+    if config.annotate_wildcard_arms {
+        let sema = Semantics::new(db);
+        let file = sema.parse(file_id);
+        for arm in file.syntax().descendants().filter_map(ast::MatchArm::cast) {
+            let variants = match absorbed_variants(&sema, &arm) {
+                Some(it) if !it.is_empty() => it,
+                _ => continue,
+            };
+            annotations.push(Annotation {
+                range: arm.syntax().text_range(),
+                kind: AnnotationKind::AbsorbedVariants {
+                    position: FilePosition { file_id, offset: arm.syntax().text_range().start() },
+                    data: Some(variants.into_iter().filter_map(|it| it.try_to_nav(db)).collect()),
+                },
+            });
+        }
+    }
+
     annotations
 }
 
@@ -139,6 +162,13 @@ pub(crate) fn resolve_annotation(db: &RootDatabase, mut annotation: Annotation)
                     .collect()
             });
         }
+        AnnotationKind::AbsorbedVariants { position, ref mut data } if data.is_none() => {
+            let sema = Semantics::new(db);
+            let file = sema.parse(position.file_id);
+            *data = find_node_at_offset::<ast::MatchArm>(file.syntax(), position.offset)
+                .and_then(|arm| absorbed_variants(&sema, &arm))
+                .map(|variants| variants.into_iter().filter_map(|it| it.try_to_nav(db)).collect());
+        }
         _ => {}
     };
 
@@ -170,6 +200,7 @@ mod tests {
                     annotate_impls: true,
                     annotate_references: true,
                     annotate_method_references: true,
+                    annotate_wildcard_arms: true,
                     run: true,
                     debug: true,
                 },
@@ -934,4 +965,117 @@ mod tests {
             "#]],
         );
     }
+
+    #[test]
+    fn wildcard_arm_annotations() {
+        check(
+            r#"
+enum E { A, B(u8), C { x: u8 } }
+
+fn f(e: &E) {
+    match e {
+        E::A | E::B(_) => (),
+        E::C { x: 0 } => (),
+        _ => (),
+    }
+}
+            "#,
+            expect![[r#"
+                [
+                    Annotation {
+                        range: 0..32,
+                        kind: HasImpls {
+                            position: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 5,
+                            },
+                            data: Some(
+                                [],
+                            ),
+                        },
+                    },
+                    Annotation {
+                        range: 0..32,
+                        kind: HasReferences {
+                            position: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 5,
+                            },
+                            data: Some(
+                                [
+                                    FileRange {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        range: 43..44,
+                                    },
+                                    FileRange {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        range: 70..71,
+                                    },
+                                    FileRange {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        range: 77..78,
+                                    },
+                                    FileRange {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        range: 100..101,
+                                    },
+                                ],
+                            ),
+                        },
+                    },
+                    Annotation {
+                        range: 37..38,
+                        kind: HasReferences {
+                            position: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 37,
+                            },
+                            data: Some(
+                                [],
+                            ),
+                        },
+                    },
+                    Annotation {
+                        range: 129..136,
+                        kind: AbsorbedVariants {
+                            position: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 129,
+                            },
+                            data: Some(
+                                [
+                                    NavigationTarget {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        full_range: 19..30,
+                                        focus_range: 19..20,
+                                        name: "C",
+                                        kind: Variant,
+                                        description: "C",
+                                    },
+                                ],
+                            ),
+                        },
+                    },
+                ]
+            "#]],
+        );
+    }
 }
@@ -6,6 +6,17 @@
 
 mod fixes;
 mod field_shorthand;
+mod blocking_in_async;
+mod ffi_safety;
+mod dead_code;
+mod unused_imports;
+mod glob_imports;
+mod cyclic_imports;
+mod missing_lifetime;
+mod custom_lints;
+mod lint_levels;
+mod ssr;
+mod typed_hole;
 
 use std::cell::RefCell;
 
@@ -14,7 +25,9 @@ use hir::{
     diagnostics::{Diagnostic as _, DiagnosticCode, DiagnosticSinkBuilder},
     InFile, Semantics,
 };
-use ide_db::{base_db::SourceDatabase, RootDatabase};
+use ide_db::{
+    base_db::SourceDatabase, helpers::SnippetCap, symbol_index::SymbolsDatabase, RootDatabase,
+};
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
 use syntax::{
@@ -23,9 +36,9 @@ use syntax::{
 };
 use text_edit::TextEdit;
 
-use crate::{FileId, Label, SourceChange};
+use crate::{FileId, FileRange, Label, SourceChange, SsrRule};
 
-use self::fixes::DiagnosticWithFix;
+use self::fixes::DiagnosticWithFixes;
 
 #[derive(Debug)]
 pub struct Diagnostic {
@@ -33,14 +46,31 @@ pub struct Diagnostic {
     pub message: String,
     pub range: TextRange,
     pub severity: Severity,
-    pub fix: Option<Fix>,
+    pub fixes: Option<Vec<Fix>>,
     pub unused: bool,
     pub code: Option<DiagnosticCode>,
+    /// The rustc or clippy lint controlling the diagnostic, which `#[allow(..)]` and the like apply to.
+    pub lint: Option<&'static str>,
+    /// For code disabled by `#[cfg(feature = "..")]`, the Cargo features which would enable it.
+    pub features_to_enable: Vec<String>,
+    /// Other locations relevant to the diagnostic, like the first definition of a name defined
+    /// several times.
+    pub related_information: Vec<(FileRange, String)>,
 }
 
 impl Diagnostic {
     fn error(range: TextRange, message: String) -> Self {
-        Self { message, range, severity: Severity::Error, fix: None, unused: false, code: None }
+        Self {
+            message,
+            range,
+            severity: Severity::Error,
+            fixes: None,
+            unused: false,
+            code: None,
+            lint: None,
+            features_to_enable: Vec::new(),
+            related_information: Vec::new(),
+        }
     }
 
     fn hint(range: TextRange, message: String) -> Self {
@@ -48,14 +78,21 @@ impl Diagnostic {
             message,
             range,
             severity: Severity::WeakWarning,
-            fix: None,
+            fixes: None,
             unused: false,
             code: None,
+            lint: None,
+            features_to_enable: Vec::new(),
+            related_information: Vec::new(),
         }
     }
 
     fn with_fix(self, fix: Option<Fix>) -> Self {
-        Self { fix, ..self }
+        Self { fixes: fix.map(|fix| vec![fix]), ..self }
+    }
+
+    fn with_fixes(self, fixes: Option<Vec<Fix>>) -> Self {
+        Self { fixes, ..self }
     }
 
     fn with_unused(self, unused: bool) -> Self {
@@ -65,6 +102,18 @@ impl Diagnostic {
     fn with_code(self, code: Option<DiagnosticCode>) -> Self {
         Self { code, ..self }
     }
+
+    fn with_lint(self, lint: Option<&'static str>) -> Self {
+        Self { lint, ..self }
+    }
+
+    fn with_features_to_enable(self, features_to_enable: Vec<String>) -> Self {
+        Self { features_to_enable, ..self }
+    }
+
+    fn with_related_information(self, related_information: Vec<(FileRange, String)>) -> Self {
+        Self { related_information, ..self }
+    }
 }
 
 #[derive(Debug)]
@@ -92,6 +141,19 @@ pub enum Severity {
 pub struct DiagnosticsConfig {
     pub disable_experimental: bool,
     pub disabled: FxHashSet<String>,
+    /// Structural search replace rules whose matches are reported as diagnostics, together with
+    /// their text.
+    pub ssr_rules: Vec<(String, SsrRule)>,
+    /// Custom lint rules, like `call crate::blocking in async`.
+    pub custom_lints: Vec<String>,
+    /// Blocking calls which shouldn't be reported in async code.
+    pub allowed_blocking_calls: Vec<String>,
+    /// Types which are taken to be FFI-safe, like the opaque types generated by bindgen.
+    pub ffi_safe_types: Vec<String>,
+    /// Whether fixes may leave placeholders to fill in as snippets.
+    pub snippet_cap: Option<SnippetCap>,
+    /// Whether to report unused items, which needs to search for the usages of every item.
+    pub dead_code: bool,
 }
 
 pub(crate) fn diagnostics(
@@ -100,6 +162,9 @@ pub(crate) fn diagnostics(
     file_id: FileId,
 ) -> Vec<Diagnostic> {
     let _p = profile::span("diagnostics");
+    if db.is_excluded_file(file_id) {
+        return Vec::new();
+    }
     let sema = Semantics::new(db);
     let parse = db.parse(file_id);
     let mut res = Vec::new();
@@ -113,32 +178,128 @@ pub(crate) fn diagnostics(
             .map(|err| Diagnostic::error(err.range(), format!("Syntax Error: {}", err))),
     );
 
+    if !config.disabled.contains("unused-import") {
+        unused_imports::check(&mut res, &sema, file_id);
+    }
+    glob_imports::check(&mut res, &sema, config, file_id);
+    cyclic_imports::check(&mut res, &sema, config, file_id);
+    if !config.disabled.contains("undeclared-lifetime") {
+        missing_lifetime::check(&mut res, &sema, file_id);
+    }
     for node in parse.tree().syntax().descendants() {
         check_unnecessary_braces_in_use_statement(&mut res, file_id, &node);
         field_shorthand::check(&mut res, file_id, &node);
     }
+    if !config.disabled.contains("ssr-rule") {
+        ssr::check(&mut res, db, &config.ssr_rules, file_id);
+    }
+    if !config.disabled.contains("blocking-in-async") {
+        blocking_in_async::check(&mut res, &sema, &config.allowed_blocking_calls, file_id);
+    }
+    ffi_safety::check(&mut res, &sema, config, file_id);
+    if !config.disabled.contains("custom-lint") {
+        custom_lints::check(&mut res, &sema, &config.custom_lints, file_id);
+    }
+    if !config.disabled.contains("typed-hole") {
+        typed_hole::check(&mut res, &sema, file_id);
+    }
+    if config.dead_code && !config.disabled.contains("dead-code") {
+        dead_code::check(&mut res, &sema, file_id);
+    }
     let res = RefCell::new(res);
     let sink_builder = DiagnosticSinkBuilder::new()
         .on::<hir::diagnostics::UnresolvedModule, _>(|d| {
-            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::UnresolvedImport, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
         })
         .on::<hir::diagnostics::MissingFields, _>(|d| {
-            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::MissingImplItems, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::MissingMatchArms, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
         })
         .on::<hir::diagnostics::MissingOkOrSomeInTailExpr, _>(|d| {
-            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::MissingUnsafe, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
         })
         .on::<hir::diagnostics::NoSuchField, _>(|d| {
-            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::PrivateItem, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::DuplicateDefinition, _>(|d| {
+            let first = sema.diagnostics_display_range(d.first.clone().map(|it| it.into()));
+            let related = vec![(first, format!("first definition of `{}` here", d.text))];
+            res.borrow_mut()
+                .push(diagnostic_with_fixes(d, &sema).with_related_information(related));
+        })
+        .on::<hir::diagnostics::AwaitOutsideOfAsync, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::TryInNonTryFn, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::MissingErrorConversion, _>(|d| {
+            let fixes = fixes::missing_error_conversion_fixes(d, &sema, config.snippet_cap);
+            res.borrow_mut().push(
+                Diagnostic::error(
+                    sema.diagnostics_display_range(d.display_source()).range,
+                    d.message(),
+                )
+                .with_fixes(fixes)
+                .with_code(Some(d.code()))
+                .with_lint(d.lint()),
+            );
+        })
+        .on::<hir::diagnostics::RecursiveType, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
         })
         .on::<hir::diagnostics::RemoveThisSemicolon, _>(|d| {
-            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::TypeMismatch, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::UnresolvedMethodCall, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::UnresolvedField, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fixes(d, &sema));
         })
         .on::<hir::diagnostics::IncorrectCase, _>(|d| {
-            res.borrow_mut().push(warning_with_fix(d, &sema));
+            res.borrow_mut().push(warning_with_fixes(d, &sema));
         })
         .on::<hir::diagnostics::ReplaceFilterMapNextWithFindMap, _>(|d| {
-            res.borrow_mut().push(warning_with_fix(d, &sema));
+            res.borrow_mut().push(warning_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::UnnecessaryUnsafe, _>(|d| {
+            res.borrow_mut().push(warning_with_fixes(d, &sema));
+        })
+        .on::<hir::diagnostics::UnreachableMatchArm, _>(|d| {
+            res.borrow_mut().push(warning_with_fixes(d, &sema).with_unused(true));
+        })
+        .on::<hir::diagnostics::ShadowedLabel, _>(|d| {
+            let outer = sema.diagnostics_display_range(d.outer.clone().map(|it| it.into()));
+            let related = vec![(outer, "first declared here".to_string())];
+            res.borrow_mut().push(
+                Diagnostic::hint(
+                    sema.diagnostics_display_range(d.display_source()).range,
+                    d.message(),
+                )
+                .with_code(Some(d.code()))
+                .with_related_information(related),
+            );
+        })
+        .on::<hir::diagnostics::UnusedVariable, _>(|d| {
+            res.borrow_mut().push(warning_with_fixes(d, &sema).with_unused(true));
         })
         .on::<hir::diagnostics::InactiveCode, _>(|d| {
             // If there's inactive code somewhere in a macro, don't propagate to the call-site.
@@ -153,7 +314,9 @@ pub(crate) fn diagnostics(
                     d.message(),
                 )
                 .with_unused(true)
-                .with_code(Some(d.code())),
+                .with_code(Some(d.code()))
+                .with_lint(d.lint())
+                .with_features_to_enable(d.features_to_enable()),
             );
         })
         .on::<hir::diagnostics::UnresolvedProcMacro, _>(|d| {
@@ -193,7 +356,8 @@ pub(crate) fn diagnostics(
                     sema.diagnostics_display_range(d.display_source()).range,
                     d.message(),
                 )
-                .with_code(Some(d.code())),
+                .with_code(Some(d.code()))
+                .with_lint(d.lint()),
             );
         });
 
@@ -201,19 +365,26 @@ pub(crate) fn diagnostics(
         m.diagnostics(db, &mut sink);
     };
     drop(sink);
-    res.into_inner()
+    let mut res = res.into_inner();
+    lint_levels::apply(&sema, file_id, &mut res);
+    res
 }
 
-fn diagnostic_with_fix<D: DiagnosticWithFix>(d: &D, sema: &Semantics<RootDatabase>) -> Diagnostic {
+fn diagnostic_with_fixes<D: DiagnosticWithFixes>(
+    d: &D,
+    sema: &Semantics<RootDatabase>,
+) -> Diagnostic {
     Diagnostic::error(sema.diagnostics_display_range(d.display_source()).range, d.message())
-        .with_fix(d.fix(&sema))
+        .with_fixes(d.fixes(&sema))
         .with_code(Some(d.code()))
+        .with_lint(d.lint())
 }
 
-fn warning_with_fix<D: DiagnosticWithFix>(d: &D, sema: &Semantics<RootDatabase>) -> Diagnostic {
+fn warning_with_fixes<D: DiagnosticWithFixes>(d: &D, sema: &Semantics<RootDatabase>) -> Diagnostic {
     Diagnostic::hint(sema.diagnostics_display_range(d.display_source()).range, d.message())
-        .with_fix(d.fix(&sema))
+        .with_fixes(d.fixes(&sema))
         .with_code(Some(d.code()))
+        .with_lint(d.lint())
 }
 
 fn check_unnecessary_braces_in_use_statement(
@@ -246,7 +417,8 @@ fn check_unnecessary_braces_in_use_statement(
                     "Remove unnecessary braces",
                     SourceChange::from_text_edit(file_id, edit),
                     use_range,
-                ))),
+                )))
+                .with_lint(Some("unused_import_braces")),
         );
     }
 
@@ -271,7 +443,12 @@ mod tests {
     use stdx::trim_indent;
     use test_utils::assert_eq_text;
 
-    use crate::{fixture, DiagnosticsConfig};
+    use ide_db::{
+        base_db::{fixture::ChangeFixture, VfsPath},
+        helpers::SnippetCap,
+    };
+
+    use crate::{fixture, AnalysisHost, DiagnosticsConfig, ExcludedCode, Query};
 
     /// Takes a multi-file input fixture with annotated cursor positions,
     /// and checks that:
@@ -279,15 +456,28 @@ mod tests {
     ///  * this diagnostic fix trigger range touches the input cursor position
     ///  * that the contents of the file containing the cursor match `after` after the diagnostic fix is applied
     pub(crate) fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_nth_fix(0, ra_fixture_before, ra_fixture_after);
+    }
+
+    /// Like [`check_fix`], but checks the `nth` fix of the diagnostic.
+    pub(crate) fn check_nth_fix(nth: usize, ra_fixture_before: &str, ra_fixture_after: &str) {
         let after = trim_indent(ra_fixture_after);
 
         let (analysis, file_position) = fixture::position(ra_fixture_before);
+        let config = DiagnosticsConfig { snippet_cap: SnippetCap::new(true), ..Default::default() };
         let diagnostic = analysis
-            .diagnostics(&DiagnosticsConfig::default(), file_position.file_id)
+            .diagnostics(&config, file_position.file_id)
             .unwrap()
-            .pop()
-            .unwrap();
-        let fix = diagnostic.fix.unwrap();
+            .into_iter()
+            .rev()
+            .find(|d| {
+                d.fixes
+                    .iter()
+                    .flatten()
+                    .any(|fix| fix.fix_trigger_range.contains_inclusive(file_position.offset))
+            })
+            .expect("no diagnostic with a fix at the cursor position");
+        let fix = diagnostic.fixes.unwrap().into_iter().nth(nth).unwrap();
         let actual = {
             let file_id = *fix.source_change.source_file_edits.keys().next().unwrap();
             let mut actual = analysis.file_text(file_id).unwrap().to_string();
@@ -307,22 +497,31 @@ mod tests {
         );
     }
 
+    /// Test fixtures are full of unused bindings and sloppily typed code, so
+    /// tests which don't check for these explicitly use this config.
+    fn test_config() -> DiagnosticsConfig {
+        let mut config = DiagnosticsConfig::default();
+        config.disabled.insert("unused-variable".to_string());
+        config.disabled.insert("unused-import".to_string());
+        config.disabled.insert("type-mismatch".to_string());
+        config
+    }
+
     /// Takes a multi-file input fixture with annotated cursor position and checks that no diagnostics
     /// apply to the file containing the cursor.
     pub(crate) fn check_no_diagnostics(ra_fixture: &str) {
         let (analysis, files) = fixture::files(ra_fixture);
+        let config = test_config();
         let diagnostics = files
             .into_iter()
-            .flat_map(|file_id| {
-                analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap()
-            })
+            .flat_map(|file_id| analysis.diagnostics(&config, file_id).unwrap())
             .collect::<Vec<_>>();
         assert_eq!(diagnostics.len(), 0, "unexpected diagnostics:\n{:#?}", diagnostics);
     }
 
     fn check_expect(ra_fixture: &str, expect: Expect) {
         let (analysis, file_id) = fixture::file(ra_fixture);
-        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&test_config(), file_id).unwrap();
         expect.assert_debug_eq(&diagnostics)
     }
 
@@ -360,6 +559,190 @@ fn div(x: i32, y: i32) -> Option<i32> {
         );
     }
 
+    #[test]
+    fn test_make_fn_async() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:core
+async fn ready() {}
+pub unsafe fn f() {
+    ready().await$0;
+}
+//- /core.rs crate:core
+pub mod future {
+    #[lang = "future_trait"]
+    pub trait Future { type Output; }
+}
+"#,
+            r#"
+async fn ready() {}
+pub async unsafe fn f() {
+    ready().await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_change_return_type_for_try() {
+        let core = r#"//- /core.rs crate:core
+pub mod ops {
+    pub trait Try { type Ok; type Error; }
+    impl<T> Try for crate::option::Option<T> { type Ok = T; type Error = (); }
+    impl<T, E> Try for crate::result::Result<T, E> { type Ok = T; type Error = E; }
+}
+pub mod option {
+    pub enum Option<T> { Some(T), None }
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+"#;
+        check_fix(
+            &format!(
+                r#"
+//- /main.rs crate:main deps:core
+use core::result::Result;
+struct Error;
+fn f(x: Result<u32, Error>) {{
+    x?$0;
+}}
+{}"#,
+                core
+            ),
+            r#"
+use core::result::Result;
+struct Error;
+fn f(x: Result<u32, Error>) -> Result<(), Error> {
+    x?;
+}
+"#,
+        );
+        check_fix(
+            &format!(
+                r#"
+//- /main.rs crate:main deps:core
+use core::option::Option;
+fn f(x: Option<u32>) -> u32 {{
+    x?$0
+}}
+{}"#,
+                core
+            ),
+            r#"
+use core::option::Option;
+fn f(x: Option<u32>) -> Option<u32> {
+    x?
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_fixes_for_missing_error_conversion() {
+        let core = r#"//- /core.rs crate:core
+pub mod ops {
+    pub trait Try { type Ok; type Error; }
+    impl<T, E> Try for crate::result::Result<T, E> { type Ok = T; type Error = E; }
+}
+pub mod convert {
+    pub trait From<T> { fn from(t: T) -> Self; }
+    impl<T> From<T> for T { fn from(t: T) -> T { t } }
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+"#;
+        let before = format!(
+            r#"
+//- /main.rs crate:main deps:core
+use core::result::Result::{{self, Ok}};
+struct IoError;
+enum Error {{
+    Io(IoError),
+}}
+fn f(x: Result<u32, IoError>) -> Result<u32, Error> {{
+    Ok(x?$0)
+}}
+{}"#,
+            core
+        );
+        check_nth_fix(
+            0,
+            &before,
+            r#"
+use core::result::Result::{self, Ok};
+struct IoError;
+enum Error {
+    Io(IoError),
+}
+fn f(x: Result<u32, IoError>) -> Result<u32, Error> {
+    Ok(x.map_err(Error::Io)?)
+}
+"#,
+        );
+        check_nth_fix(
+            1,
+            &before,
+            r#"
+use core::result::Result::{self, Ok};
+struct IoError;
+enum Error {
+    Io(IoError),
+}
+fn f(x: Result<u32, IoError>) -> Result<u32, IoError> {
+    Ok(x?)
+}
+"#,
+        );
+        check_nth_fix(
+            2,
+            &before,
+            r#"
+use core::result::Result::{self, Ok};
+struct IoError;
+enum Error {
+    Io(IoError),
+}
+
+impl From<IoError> for Error {
+    fn from(err: IoError) -> Self {
+        ${0:todo!()}
+    }
+}
+fn f(x: Result<u32, IoError>) -> Result<u32, Error> {
+    Ok(x?)
+}
+"#,
+        );
+        check_fix(
+            &format!(
+                r#"
+//- /main.rs crate:main deps:core
+use core::result::Result::{{self, Ok}};
+mod error {{
+    pub struct Error;
+}}
+struct ParseError;
+fn f(x: Result<u32, ParseError>) -> Result<u32, error::Error> {{
+    Ok(x?$0)
+}}
+{}"#,
+                core
+            ),
+            r#"
+use core::result::Result::{self, Ok};
+mod error {
+    pub struct Error;
+}
+struct ParseError;
+fn f(x: Result<u32, ParseError>) -> Result<u32, error::Error> {
+    Ok(x.map_err(|_| ${0:todo!()})?)
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_wrap_return_type() {
         check_fix(
@@ -634,26 +1017,28 @@ fn test_fn() {
                         message: "unresolved module",
                         range: 0..8,
                         severity: Error,
-                        fix: Some(
-                            Fix {
-                                label: "Create module",
-                                source_change: SourceChange {
-                                    source_file_edits: {},
-                                    file_system_edits: [
-                                        CreateFile {
-                                            dst: AnchoredPathBuf {
-                                                anchor: FileId(
-                                                    0,
-                                                ),
-                                                path: "foo.rs",
+                        fixes: Some(
+                            [
+                                Fix {
+                                    label: "Create module",
+                                    source_change: SourceChange {
+                                        source_file_edits: {},
+                                        file_system_edits: [
+                                            CreateFile {
+                                                dst: AnchoredPathBuf {
+                                                    anchor: FileId(
+                                                        0,
+                                                    ),
+                                                    path: "foo.rs",
+                                                },
+                                                initial_contents: "",
                                             },
-                                            initial_contents: "",
-                                        },
-                                    ],
-                                    is_snippet: false,
+                                        ],
+                                        is_snippet: false,
+                                    },
+                                    fix_trigger_range: 0..8,
                                 },
-                                fix_trigger_range: 0..8,
-                            },
+                            ],
                         ),
                         unused: false,
                         code: Some(
@@ -661,12 +1046,131 @@ fn test_fn() {
                                 "unresolved-module",
                             ),
                         ),
+                        lint: None,
+                        features_to_enable: [],
+                        related_information: [],
                     },
                 ]
             "#]],
         );
     }
 
+    #[test]
+    fn test_remove_unresolved_import() {
+        check_fix(
+            r#"
+mod foo { pub struct Bar; }
+use foo::{Bar, Quux$0};
+fn main() {}
+"#,
+            r#"
+mod foo { pub struct Bar; }
+use foo::{Bar};
+fn main() {}
+"#,
+        );
+        check_fix(
+            r#"
+mod foo { pub mod bar {} }
+use foo::{bar::{Qux$0}};
+use foo::bar;
+"#,
+            r#"
+mod foo { pub mod bar {} }
+use foo::bar;
+"#,
+        );
+    }
+
+    #[test]
+    fn test_correct_unresolved_import_from_module_scope() {
+        check_fix(
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    struct Bra;
+}
+use foo::{Baz, Bzr$0};
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    struct Bra;
+}
+use foo::{Baz, Bar};
+"#,
+        );
+    }
+
+    #[test]
+    fn test_correct_unresolved_import_from_import_map() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:std
+use std::HashMpa$0;
+//- /std/lib.rs crate:std
+pub mod collections {
+    pub struct HashMap;
+    pub struct HashSet;
+}
+"#,
+            r#"
+use std::collections::HashMap;
+"#,
+        );
+    }
+
+    #[test]
+    fn test_change_visibility_of_private_item() {
+        check_fix(
+            r#"
+mod m {
+    pub struct S {
+        /// Doc.
+        x: u32,
+    }
+    pub(super) fn f() {}
+}
+fn main() {
+    let s: m::S = loop {};
+    s.x$0;
+}
+"#,
+            r#"
+mod m {
+    pub struct S {
+        /// Doc.
+        pub(crate) x: u32,
+    }
+    pub(super) fn f() {}
+}
+fn main() {
+    let s: m::S = loop {};
+    s.x;
+}
+"#,
+        );
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() {
+    dep::m::f$0();
+}
+//- /dep.rs crate:dep
+pub mod m {
+    pub(crate) fn f() {}
+}
+"#,
+            r#"
+pub mod m {
+    pub fn f() {}
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_unresolved_macro_range() {
         check_expect(
@@ -677,13 +1181,16 @@ fn test_fn() {
                         message: "unresolved macro call",
                         range: 5..8,
                         severity: Error,
-                        fix: None,
+                        fixes: None,
                         unused: false,
                         code: Some(
                             DiagnosticCode(
                                 "unresolved-macro-call",
                             ),
                         ),
+                        lint: None,
+                        features_to_enable: [],
+                        related_information: [],
                     },
                 ]
             "#]],
@@ -963,16 +1470,787 @@ impl TestStruct {
         );
     }
 
+    #[test]
+    fn test_prefix_unused_variable() {
+        check_fix(
+            r#"
+fn main() {
+    let x$0 = 92;
+}
+"#,
+            r#"
+fn main() {
+    let _x = 92;
+}
+"#,
+        );
+        check_fix(
+            r#"
+struct S { x: i32 }
+fn foo(s: S) {
+    let S { mut x$0 } = s;
+}
+"#,
+            r#"
+struct S { x: i32 }
+fn foo(s: S) {
+    let S { x: mut _x } = s;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_remove_unused_variable() {
+        check_nth_fix(
+            1,
+            r#"
+fn main() {
+    let y = 1;
+    let x$0 = y;
+    y;
+}
+"#,
+            r#"
+fn main() {
+    let y = 1;
+    y;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unused_variable_with_side_effects_is_not_removed() {
+        let (analysis, file_position) = fixture::position(
+            r#"
+fn f() -> i32 { 92 }
+fn main() {
+    let x$0 = f();
+}
+"#,
+        );
+        let diagnostics =
+            analysis.diagnostics(&DiagnosticsConfig::default(), file_position.file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let fixes = diagnostics[0].fixes.as_ref().unwrap();
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].label.to_string(), "Rename to `_x`");
+    }
+
     #[test]
     fn test_single_incorrect_case_diagnostic_in_function_name_issue_6970() {
         let input = r#"fn FOO$0() {}"#;
         let expected = r#"fn foo() {}"#;
 
         let (analysis, file_position) = fixture::position(input);
-        let diagnostics =
-            analysis.diagnostics(&DiagnosticsConfig::default(), file_position.file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&test_config(), file_position.file_id).unwrap();
         assert_eq!(diagnostics.len(), 1);
 
         check_fix(input, expected);
     }
+
+    const CONVERSION_FIXTURE: &str = r#"//- /core/lib.rs crate:core
+pub mod option {
+    pub enum Option<T> { Some(T), None }
+}
+pub mod marker {
+    #[lang = "copy"]
+    pub trait Copy {}
+    impl Copy for i32 {}
+}
+pub mod convert {
+    pub trait From<T> { fn from(t: T) -> Self; }
+    pub trait Into<T> { fn into(self) -> T; }
+    impl<T, U: From<T>> Into<U> for T { fn into(self) -> U { U::from(self) } }
+    pub trait AsRef<T> { fn as_ref(&self) -> &T; }
+}
+//- /alloc/lib.rs crate:alloc deps:core
+pub mod string {
+    pub struct String;
+    pub trait ToString { fn to_string(&self) -> String; }
+    impl<'a> ToString for &'a str { fn to_string(&self) -> String { String } }
+}
+"#;
+
+    fn check_conversion_fix(nth: usize, ra_fixture_before: &str, ra_fixture_after: &str) {
+        let before = format!(
+            "//- /main.rs crate:main deps:core,alloc\n{}{}",
+            trim_indent(ra_fixture_before),
+            CONVERSION_FIXTURE
+        );
+        check_nth_fix(nth, &before, ra_fixture_after);
+    }
+
+    #[test]
+    fn test_type_mismatch_wrap_in_some() {
+        check_conversion_fix(
+            0,
+            r#"
+use core::option::Option;
+fn f(x: Option<i32>) {}
+fn main() { f(92$0); }
+"#,
+            r#"
+use core::option::Option;
+fn f(x: Option<i32>) {}
+fn main() { f(Some(92)); }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_borrow_and_deref() {
+        check_conversion_fix(
+            0,
+            r#"
+struct S;
+fn f(s: &mut S) {}
+fn main() { let mut s = S; f(s$0); }
+"#,
+            r#"
+struct S;
+fn f(s: &mut S) {}
+fn main() { let mut s = S; f(&mut s); }
+"#,
+        );
+        check_conversion_fix(
+            0,
+            r#"
+fn f(x: i32) {}
+fn main(x: &i32) { f(x$0); }
+"#,
+            r#"
+fn f(x: i32) {}
+fn main(x: &i32) { f(*x); }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_conversion_traits() {
+        check_conversion_fix(
+            0,
+            r#"
+use core::convert::From;
+struct A;
+struct B;
+impl From<A> for B { fn from(a: A) -> B { B } }
+fn f(b: B) {}
+fn main() { f(A$0); }
+"#,
+            r#"
+use core::convert::From;
+struct A;
+struct B;
+impl From<A> for B { fn from(a: A) -> B { B } }
+fn f(b: B) {}
+fn main() { f(A.into()); }
+"#,
+        );
+        check_conversion_fix(
+            0,
+            r#"
+use core::convert::AsRef;
+struct A;
+struct B;
+impl AsRef<B> for A { fn as_ref(&self) -> &B { &B } }
+fn f(b: &B) {}
+fn main() { f(A$0); }
+"#,
+            r#"
+use core::convert::AsRef;
+struct A;
+struct B;
+impl AsRef<B> for A { fn as_ref(&self) -> &B { &B } }
+fn f(b: &B) {}
+fn main() { f(A.as_ref()); }
+"#,
+        );
+        check_conversion_fix(
+            0,
+            r#"
+use alloc::string::String;
+fn f(s: String) {}
+fn main() { f("hello"$0); }
+"#,
+            r#"
+use alloc::string::String;
+fn f(s: String) {}
+fn main() { f("hello".to_string()); }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_method_similar_name() {
+        check_fix(
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct S;
+impl S { fn frobnicate(&self) {} }
+fn main() { S.frobnicat$0(); }
+"#,
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct S;
+impl S { fn frobnicate(&self) {} }
+fn main() { S.frobnicate(); }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_method_import_trait() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:dep
+#[lang = "deref"]
+trait Deref {}
+struct S;
+impl dep::Frobnicate for S {}
+fn main() { S.frob$0nicate(); }
+//- /dep.rs crate:dep
+pub mod traits {
+    pub trait Frobnicate { fn frobnicate(&self) {} }
+}
+pub use traits::Frobnicate;
+"#,
+            r#"
+use dep::Frobnicate;
+
+#[lang = "deref"]
+trait Deref {}
+struct S;
+impl dep::Frobnicate for S {}
+fn main() { S.frobnicate(); }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_field_similar_name() {
+        check_fix(
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct S { field: u32 }
+fn main(s: S) { s.fild$0; }
+"#,
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct S { field: u32 }
+fn main(s: S) { s.field; }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_field_add_field() {
+        check_fix(
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct Counter {
+    count: u32,
+}
+impl Counter {
+    fn reset(&mut self) {
+        self.count = 0;
+        self.resets$0 = self.count + 1;
+    }
+}
+"#,
+            r#"
+#[lang = "deref"]
+trait Deref {}
+struct Counter {
+    count: u32,
+    resets: u32,
+}
+impl Counter {
+    fn reset(&mut self) {
+        self.count = 0;
+        self.resets = self.count + 1;
+    }
+}
+"#,
+        );
+        check_fix(
+            r#"
+//- /main.rs
+mod config;
+fn limit(c: &config::Config) -> u64 {
+    c.limit$0
+}
+//- /config.rs
+#[lang = "deref"]
+trait Deref {}
+pub struct Config;
+"#,
+            r#"
+#[lang = "deref"]
+trait Deref {}
+pub struct Config { pub(crate) limit: u64 }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_fill_match_arms_with_nested_patterns() {
+        check_fix(
+            r#"
+enum Inner { A, B }
+enum E { X(Inner), Y { b: bool, i: Inner } }
+fn main(e: E) {
+    match e {$0
+        E::X(Inner::A) => {}
+        E::Y { b: true, .. } => (),
+    }
+}
+"#,
+            r#"
+enum Inner { A, B }
+enum E { X(Inner), Y { b: bool, i: Inner } }
+fn main(e: E) {
+    match e {
+        E::X(Inner::A) => {}
+        E::Y { b: true, .. } => (),
+        E::X(Inner::B) => {}
+        E::Y { b: false, .. } => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_fill_match_arms_with_tuples() {
+        check_fix(
+            r#"
+enum E { A, B }
+fn main(t: (bool, E)) {
+    match t {$0
+        (true, E::A) => (),
+        (false, _) => ()
+    }
+}
+"#,
+            r#"
+enum E { A, B }
+fn main(t: (bool, E)) {
+    match t {
+        (true, E::A) => (),
+        (false, _) => (),
+        (true, E::B) => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_fill_empty_match() {
+        check_fix(
+            r#"
+mod m { pub enum E { A(i32, i32), B } }
+fn main(e: m::E) {
+    match e {$0}
+}
+"#,
+            r#"
+mod m { pub enum E { A(i32, i32), B } }
+fn main(e: m::E) {
+    match e {
+        m::E::A(_, _) => {}
+        m::E::B => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_remove_unreachable_arm() {
+        check_fix(
+            r#"
+fn main(b: bool) {
+    match b {
+        true => (),
+        _ => (),
+        false$0 => {}
+    }
+}
+"#,
+            r#"
+fn main(b: bool) {
+    match b {
+        true => (),
+        _ => (),
+    }
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn main(b: bool) {
+    match b {
+        _ => {}
+        true$0 => (),
+        false => ()
+    }
+}
+"#,
+            r#"
+fn main(b: bool) {
+    match b {
+        _ => {}
+        false => ()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unreachable_arm_after_guard_is_a_hint() {
+        check_expect(
+            r#"
+fn main(b: bool) {
+    match b {
+        true if b => (),
+        true if !b => (),
+        _ => (),
+    }
+}
+"#,
+            expect![[r#"
+                [
+                    Diagnostic {
+                        message: "Match arm is only reachable if the guard of an earlier arm fails",
+                        range: 66..82,
+                        severity: WeakWarning,
+                        fixes: Some(
+                            [
+                                Fix {
+                                    label: "Remove unreachable arm",
+                                    source_change: SourceChange {
+                                        source_file_edits: {
+                                            FileId(
+                                                0,
+                                            ): TextEdit {
+                                                indels: [
+                                                    Indel {
+                                                        insert: "",
+                                                        delete: 57..83,
+                                                    },
+                                                ],
+                                            },
+                                        },
+                                        file_system_edits: [],
+                                        is_snippet: false,
+                                    },
+                                    fix_trigger_range: 66..82,
+                                },
+                            ],
+                        ),
+                        unused: true,
+                        code: Some(
+                            DiagnosticCode(
+                                "unreachable-match-arm",
+                            ),
+                        ),
+                        lint: Some(
+                            "unreachable_patterns",
+                        ),
+                        features_to_enable: [],
+                        related_information: [],
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_add_unsafe_block() {
+        check_fix(
+            r#"
+unsafe fn unsafe_fn() {}
+
+fn main() {
+    unsafe_fn$0();
+}
+"#,
+            r#"
+unsafe fn unsafe_fn() {}
+
+fn main() {
+    unsafe { unsafe_fn(); }
+}
+"#,
+        );
+        check_fix(
+            r#"
+struct S { field: u32 }
+static mut STATIC_MUT: S = S { field: 0 };
+
+fn main() {
+    let x = STATIC_MUT$0.field + 1;
+}
+"#,
+            r#"
+struct S { field: u32 }
+static mut STATIC_MUT: S = S { field: 0 };
+
+fn main() {
+    let x = unsafe { STATIC_MUT.field + 1 };
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn main() {
+    let x = &5 as *const usize;
+    if *x$0 > 0 {}
+}
+"#,
+            r#"
+fn main() {
+    let x = &5 as *const usize;
+    if unsafe { *x > 0 } {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_remove_unnecessary_unsafe_block() {
+        check_fix(
+            r#"
+fn main() {
+    let x = unsafe$0 { 92 };
+}
+"#,
+            r#"
+fn main() {
+    let x = 92;
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn foo() {}
+
+fn main() {
+    unsafe$0 {
+        foo();
+        foo();
+    }
+    foo();
+}
+"#,
+            r#"
+fn foo() {}
+
+fn main() {
+    foo();
+    foo();
+    foo();
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn main() {
+    foo();
+    unsafe$0 {}
+}
+fn foo() {}
+"#,
+            r#"
+fn main() {
+    foo();
+}
+fn foo() {}
+"#,
+        );
+        check_fix(
+            r#"
+fn main() {
+    let x = unsafe$0 { let y = 1; y }.to_string();
+}
+"#,
+            r#"
+fn main() {
+    let x = { let y = 1; y }.to_string();
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn main() {
+    let y = 1;
+    unsafe$0 {
+        let y = 2;
+        foo(y);
+    }
+    foo(y);
+}
+fn foo(_: i32) {}
+"#,
+            r#"
+fn main() {
+    let y = 1;
+    {
+        let y = 2;
+        foo(y);
+    }
+    foo(y);
+}
+fn foo(_: i32) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inactive_code_features_to_enable() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- /lib.rs crate:foo cfg:feature=std
+#[cfg(all(feature = "std", feature = "serde"))]
+fn with_serde() {}
+#[cfg(any(feature = "b", all(feature = "a", feature = "c")))]
+fn with_b() {}
+#[cfg(not(feature = "std"))]
+fn without_std() {}
+"#,
+        );
+        let diagnostics = analysis.diagnostics(&test_config(), file_id).unwrap();
+        let features =
+            diagnostics.iter().map(|it| it.features_to_enable.clone()).collect::<Vec<_>>();
+        assert_eq!(features, vec![vec!["serde".to_string()], vec!["b".to_string()], Vec::new()]);
+    }
+
+    #[test]
+    fn test_fill_missing_impl_items() {
+        check_fix(
+            r#"
+trait Shape {
+    type Unit;
+    const SIDES: u32;
+    fn name(&self) -> String { String::new() }
+}
+struct Square;
+impl Sha$0pe for Square {
+    const SIDES: u32 = 4;
+}
+"#,
+            r#"
+trait Shape {
+    type Unit;
+    const SIDES: u32;
+    fn name(&self) -> String { String::new() }
+}
+struct Square;
+impl Shape for Square {
+    const SIDES: u32 = 4;
+
+    type Unit;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_wrap_recursive_fields() {
+        check_fix(
+            r#"
+enum Option<T> { Some(T), None }
+struct No$0de { next: Option<Node>, prev: Node }
+"#,
+            r#"
+enum Option<T> { Some(T), None }
+struct Node { next: Box<Option<Node>>, prev: Box<Node> }
+"#,
+        );
+        check_nth_fix(
+            1,
+            r#"
+enum Option<T> { Some(T), None }
+struct No$0de { next: Option<Node>, prev: Node }
+"#,
+            r#"
+enum Option<T> { Some(T), None }
+struct Node { next: Option<Box<Node>>, prev: Option<Box<Node>> }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_duplicate_definition() {
+        check_fix(
+            r#"
+fn foo() {}
+fn main() { foo(); }
+fn fo$0o() {}
+"#,
+            r#"
+fn foo() {}
+fn main() { foo(); }
+fn foo2() {}
+"#,
+        );
+        check_fix(
+            r#"
+struct S { field: u8, fie$0ld: u16 }
+fn f(s: S) -> u8 { s.field }
+"#,
+            r#"
+struct S { field: u8, field2: u16 }
+fn f(s: S) -> u8 { s.field }
+"#,
+        );
+    }
+
+    #[test]
+    fn excluded_code_is_not_analyzed() {
+        let mut host = AnalysisHost::default();
+        let change_fixture = ChangeFixture::parse(
+            r#"
+//- /main.rs crate:main
+mod gen;
+mod bindings;
+fn main() { let _: gen::Generated = gen::Generated; 1 + }
+//- /gen/mod.rs
+pub struct Generated;
+fn f() { 1 + }
+//- /bindings.rs
+pub struct FfiType;
+fn g() { 1 + }
+"#,
+        );
+        host.raw_database_mut().apply_change(change_fixture.change);
+        host.update_excluded_code(ExcludedCode {
+            dirs: vec![VfsPath::new_virtual_path("/gen".to_string())],
+            modules: vec!["main::bindings".to_string()],
+        });
+        let analysis = host.analysis();
+
+        let diagnostic_counts = change_fixture
+            .files
+            .iter()
+            .map(|&file_id| analysis.diagnostics(&test_config(), file_id).unwrap().len())
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostic_counts, vec![1, 0, 0]);
+
+        let symbols = analysis.symbol_search(Query::new("main".to_string())).unwrap();
+        assert_eq!(symbols.len(), 1);
+        let symbols = analysis.symbol_search(Query::new("Generated".to_string())).unwrap();
+        assert!(symbols.is_empty());
+        let symbols = analysis.symbol_search(Query::new("FfiType".to_string())).unwrap();
+        assert!(symbols.is_empty());
+    }
 }
@@ -0,0 +1,130 @@
+//! Reports calls to blocking standard library APIs inside of `async` code,
+//! where they stall the executor thread instead of yielding to it.
+
+use hir::{diagnostics::DiagnosticCode, Semantics};
+use ide_db::{base_db::FileId, RootDatabase};
+
+use crate::Diagnostic;
+
+use super::custom_lints::{self, LintContext, LintRule};
+
+const BLOCKING_CALLS: &[&str] = &[
+    "std::thread::sleep",
+    "std::thread::JoinHandle::join",
+    "std::fs::read",
+    "std::fs::read_to_string",
+    "std::fs::read_dir",
+    "std::fs::write",
+    "std::fs::copy",
+    "std::fs::rename",
+    "std::fs::metadata",
+    "std::fs::create_dir",
+    "std::fs::create_dir_all",
+    "std::fs::remove_file",
+    "std::fs::remove_dir",
+    "std::fs::remove_dir_all",
+    "std::fs::File::open",
+    "std::fs::File::create",
+    "std::sync::Mutex::lock",
+    "std::sync::RwLock::read",
+    "std::sync::RwLock::write",
+    "std::sync::mpsc::Receiver::recv",
+    "std::net::TcpStream::connect",
+    "std::net::TcpListener::accept",
+    "std::process::Command::output",
+    "std::process::Command::status",
+];
+
+pub(super) fn check(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    allowed: &[String],
+    file_id: FileId,
+) {
+    let rules = BLOCKING_CALLS
+        .iter()
+        .filter(|path| !allowed.iter().any(|it| it == *path))
+        .map(|path| LintRule {
+            call: path.to_string(),
+            context: Some(LintContext::Async),
+            message: Some(format!("`{}` blocks the current thread in async code", path)),
+        })
+        .collect::<Vec<_>>();
+    custom_lints::check_rules(acc, sema, &rules, file_id, DiagnosticCode("blocking-in-async"));
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{fixture, DiagnosticsConfig};
+
+    const STD: &str = r#"
+//- /std/lib.rs crate:std
+pub mod thread {
+    pub fn sleep(ms: u64) {}
+}
+pub mod sync {
+    pub struct Mutex<T>(T);
+    impl<T> Mutex<T> {
+        pub fn lock(&self) -> &T { &self.0 }
+    }
+}
+"#;
+
+    fn check(allowed: &[&str], ra_fixture: &str, expect: Expect) {
+        let config = DiagnosticsConfig {
+            allowed_blocking_calls: allowed.iter().map(|it| it.to_string()).collect(),
+            ..DiagnosticsConfig::default()
+        };
+        let fixture = format!("//- /main.rs crate:main deps:std{}{}", ra_fixture, STD);
+        let (analysis, file_id) = fixture::file(&fixture);
+        let actual = analysis
+            .diagnostics(&config, file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.code.map_or(false, |it| it.as_str() == "blocking-in-async"))
+            .map(|d| format!("{:?} {}\n", d.range, d.message))
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn reports_blocking_calls_in_async_code() {
+        check(
+            &[],
+            r#"
+use std::{sync::Mutex, thread};
+async fn f(m: Mutex<i32>) {
+    thread::sleep(10);
+    m.lock();
+}
+fn g(m: Mutex<i32>) {
+    let _ = async move { m.lock() };
+    thread::sleep(10);
+}
+"#,
+            expect![[r#"
+                64..81 `std::thread::sleep` blocks the current thread in async code
+                87..95 `std::sync::Mutex::lock` blocks the current thread in async code
+                146..154 `std::sync::Mutex::lock` blocks the current thread in async code
+            "#]],
+        );
+    }
+
+    #[test]
+    fn allowed_calls_are_not_reported() {
+        check(
+            &["std::sync::Mutex::lock"],
+            r#"
+async fn f(m: std::sync::Mutex<i32>) {
+    m.lock();
+    std::thread::sleep(10);
+}
+"#,
+            expect![[r#"
+                57..79 `std::thread::sleep` blocks the current thread in async code
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! User-defined lints, written in a small rule language and configured per
+//! workspace. Unlike SSR rules, which match syntax, these match on resolved
+//! items:
+//!
+//! ```text
+//! call crate::db::blocking_query in async => "use `query` instead"
+//! call std::process::exit in fn crate::lib_entry
+//! ```
+//!
+//! A rule is `call <path>`, optionally followed by `in <context>` and by
+//! `=> <message>`, where the context is one of `async`, `unsafe`, `loop` or
+//! `fn <path>`. The path names a function or a method (`Type::method`), and
+//! is resolved from the root of the checked crate.
+
+use std::str::FromStr;
+
+use hir::{
+    diagnostics::DiagnosticCode, AsAssocItem, AssocItemContainer, PathResolution, Semantics,
+    SemanticsScope,
+};
+use ide_db::{base_db::FileId, RootDatabase};
+use syntax::{
+    ast::{self, AstNode},
+    match_ast, SyntaxNode,
+};
+
+use crate::Diagnostic;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LintRule {
+    pub(super) call: String,
+    pub(super) context: Option<LintContext>,
+    pub(super) message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum LintContext {
+    Async,
+    Unsafe,
+    Loop,
+    Fn(String),
+}
+
+impl FromStr for LintRule {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<LintRule, String> {
+        let (matcher, message) = match text.find("=>") {
+            Some(idx) => {
+                let message = text[idx + 2..].trim();
+                let message = message.strip_prefix('"').unwrap_or(message);
+                let message = message.strip_suffix('"').unwrap_or(message);
+                (&text[..idx], Some(message.to_string()))
+            }
+            None => (text, None),
+        };
+
+        let mut words = matcher.split_whitespace();
+        if words.next() != Some("call") {
+            return Err("Lint rules must start with `call`".to_string());
+        }
+        let call = words.next().ok_or_else(|| "Missing path after `call`".to_string())?;
+        let context = match words.next() {
+            None => None,
+            Some("in") => Some(match words.next() {
+                Some("async") => LintContext::Async,
+                Some("unsafe") => LintContext::Unsafe,
+                Some("loop") => LintContext::Loop,
+                Some("fn") => {
+                    let path = words.next().ok_or_else(|| "Missing path after `fn`".to_string())?;
+                    LintContext::Fn(path.to_string())
+                }
+                _ => return Err("Expected one of `async`, `unsafe`, `loop` or `fn`".to_string()),
+            }),
+            Some(word) => return Err(format!("Unexpected `{}`", word)),
+        };
+        if let Some(word) = words.next() {
+            return Err(format!("Unexpected `{}`", word));
+        }
+        Ok(LintRule { call: call.to_string(), context, message })
+    }
+}
+
+impl LintRule {
+    fn default_message(&self) -> String {
+        let context = match &self.context {
+            None => String::new(),
+            Some(LintContext::Async) => " in async context".to_string(),
+            Some(LintContext::Unsafe) => " in unsafe code".to_string(),
+            Some(LintContext::Loop) => " in a loop".to_string(),
+            Some(LintContext::Fn(path)) => format!(" in `{}`", path),
+        };
+        format!("call to `{}`{}", self.call, context)
+    }
+}
+
+pub(super) fn check(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    rules: &[String],
+    file_id: FileId,
+) {
+    let rules = rules.iter().filter_map(|it| it.parse().ok()).collect::<Vec<LintRule>>();
+    check_rules(acc, sema, &rules, file_id, DiagnosticCode("custom-lint"));
+}
+
+/// Reports all calls in `file_id` matched by one of `rules`. Rules whose
+/// paths don't resolve in the file's crate are skipped.
+pub(super) fn check_rules(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    rules: &[LintRule],
+    file_id: FileId,
+    code: DiagnosticCode,
+) {
+    if rules.is_empty() {
+        return;
+    }
+    let source_file = sema.parse(file_id);
+    let scope = sema.scope(source_file.syntax());
+    let rules = rules
+        .iter()
+        .filter_map(|rule| {
+            let call = resolve_function(&scope, &rule.call)?;
+            let context = match &rule.context {
+                Some(LintContext::Fn(path)) => Some(Context::Fn(resolve_function(&scope, path)?)),
+                Some(LintContext::Async) => Some(Context::Async),
+                Some(LintContext::Unsafe) => Some(Context::Unsafe),
+                Some(LintContext::Loop) => Some(Context::Loop),
+                None => None,
+            };
+            Some((rule, call, context))
+        })
+        .collect::<Vec<_>>();
+    if rules.is_empty() {
+        return;
+    }
+
+    for node in source_file.syntax().descendants() {
+        let callee = match_ast! {
+            match node {
+                ast::MethodCallExpr(it) => sema.resolve_method_call(&it),
+                ast::CallExpr(it) => match it.expr() {
+                    Some(ast::Expr::PathExpr(it)) => it.path().and_then(|it| resolve_call_path(sema, &it)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        };
+        let callee = match callee {
+            Some(it) => it,
+            None => continue,
+        };
+        for (rule, call, context) in &rules {
+            if !calls(sema.db, callee, *call) {
+                continue;
+            }
+            if let Some(context) = context {
+                if !is_in_context(sema, &node, context) {
+                    continue;
+                }
+            }
+            let message = rule.message.clone().unwrap_or_else(|| rule.default_message());
+            acc.push(Diagnostic::hint(node.text_range(), message).with_code(Some(code)));
+        }
+    }
+}
+
+enum Context {
+    Async,
+    Unsafe,
+    Loop,
+    Fn(hir::Function),
+}
+
+fn resolve_function(scope: &SemanticsScope, path: &str) -> Option<hir::Function> {
+    let path = ast::Path::parse(path).ok()?;
+    match scope.speculative_resolve(&path) {
+        Some(PathResolution::Def(hir::ModuleDef::Function(it))) => return Some(it),
+        Some(PathResolution::AssocItem(hir::AssocItem::Function(it))) => return Some(it),
+        Some(_) => return None,
+        None => (),
+    }
+    // Methods can't be resolved as paths, look them up on their type or trait.
+    let name = path.segment()?.name_ref()?.text().to_string();
+    let items = match scope.speculative_resolve(&path.qualifier()?)? {
+        PathResolution::Def(hir::ModuleDef::Trait(it)) => it.items(scope.db),
+        PathResolution::Def(hir::ModuleDef::Adt(adt)) => {
+            let mut res = Vec::new();
+            adt.ty(scope.db).iterate_path_candidates(
+                scope.db,
+                scope.module()?.krate(),
+                &scope.traits_in_scope(),
+                None,
+                |_, item| {
+                    res.push(item);
+                    None::<()>
+                },
+            );
+            res
+        }
+        _ => return None,
+    };
+    items.into_iter().find_map(|item| match item {
+        hir::AssocItem::Function(it) if it.name(scope.db).to_string() == name => Some(it),
+        _ => None,
+    })
+}
+
+fn resolve_call_path(sema: &Semantics<RootDatabase>, path: &ast::Path) -> Option<hir::Function> {
+    match sema.resolve_path(path)? {
+        PathResolution::Def(hir::ModuleDef::Function(it)) => Some(it),
+        PathResolution::AssocItem(hir::AssocItem::Function(it)) => Some(it),
+        _ => None,
+    }
+}
+
+/// Whether calling `callee` is a call to `target`. Calls to trait methods
+/// resolve to the implementation where it is known, so a rule for a trait
+/// method matches all of its implementations.
+fn calls(db: &RootDatabase, callee: hir::Function, target: hir::Function) -> bool {
+    if callee == target {
+        return true;
+    }
+    let target_trait = match target.as_assoc_item(db).and_then(|it| it.containing_trait(db)) {
+        Some(it) => it,
+        None => return false,
+    };
+    let callee_trait = callee.as_assoc_item(db).and_then(|it| match it.container(db) {
+        AssocItemContainer::Trait(it) => Some(it),
+        AssocItemContainer::Impl(it) => it.trait_(db),
+    });
+    callee_trait == Some(target_trait) && callee.name(db) == target.name(db)
+}
+
+fn is_in_context(sema: &Semantics<RootDatabase>, node: &SyntaxNode, context: &Context) -> bool {
+    for node in node.ancestors().skip(1) {
+        match_ast! {
+            match node {
+                ast::EffectExpr(it) => match context {
+                    Context::Async if it.async_token().is_some() => return true,
+                    Context::Unsafe if it.unsafe_token().is_some() => return true,
+                    _ => (),
+                },
+                ast::LoopExpr(_it) => if let Context::Loop = context { return true },
+                ast::WhileExpr(_it) => if let Context::Loop = context { return true },
+                ast::ForExpr(_it) => if let Context::Loop = context { return true },
+                // Closures are executed later, outside of the surrounding context.
+                ast::ClosureExpr(_it) => return false,
+                ast::Fn(it) => return match context {
+                    Context::Async => it.async_token().is_some(),
+                    Context::Unsafe => it.unsafe_token().is_some(),
+                    Context::Loop => false,
+                    Context::Fn(target) => sema.to_def(&it) == Some(*target),
+                },
+                _ => (),
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::{LintContext, LintRule};
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn check(rule: &str, ra_fixture: &str, expect: Expect) {
+        let config = DiagnosticsConfig {
+            custom_lints: vec![rule.to_string()],
+            ..DiagnosticsConfig::default()
+        };
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let actual = analysis
+            .diagnostics(&config, file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.code.map_or(false, |it| it.as_str() == "custom-lint"))
+            .map(|d| format!("{:?} {}\n", d.range, d.message))
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!(
+            "call foo::bar in fn crate::baz => \"don't\"".parse::<LintRule>(),
+            Ok(LintRule {
+                call: "foo::bar".to_string(),
+                context: Some(LintContext::Fn("crate::baz".to_string())),
+                message: Some("don't".to_string()),
+            })
+        );
+        assert_eq!(
+            "call foo".parse::<LintRule>(),
+            Ok(LintRule { call: "foo".to_string(), context: None, message: None })
+        );
+        assert!("foo::bar".parse::<LintRule>().is_err());
+        assert!("call foo in space".parse::<LintRule>().is_err());
+        assert!("call foo bar".parse::<LintRule>().is_err());
+    }
+
+    #[test]
+    fn matches_calls_in_context() {
+        check(
+            "call crate::blocking in async",
+            r#"
+fn blocking() {}
+async fn f() {
+    blocking();
+    let _ = || blocking();
+    async { crate::blocking() };
+}
+fn g() { blocking() }
+"#,
+            expect![[r#"
+                36..46 call to `crate::blocking` in async context
+                87..104 call to `crate::blocking` in async context
+            "#]],
+        );
+    }
+
+    #[test]
+    fn matches_methods_and_trait_impls() {
+        check(
+            "call crate::Tr::run in loop => \"don't run in loops\"",
+            r#"
+trait Tr { fn run(&self); }
+struct S;
+impl Tr for S { fn run(&self) {} }
+fn f(s: S) {
+    s.run();
+    loop { s.run(); S::run(&s); }
+}
+"#,
+            expect![[r#"
+                110..117 don't run in loops
+                119..129 don't run in loops
+            "#]],
+        );
+        check(
+            "call crate::S::new in fn crate::f",
+            r#"
+struct S;
+impl S { fn new() -> S { S } }
+fn f() { S::new(); }
+fn g() { S::new(); }
+"#,
+            expect![[r#"
+                50..58 call to `crate::S::new` in `crate::f`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unresolved_rules_are_ignored() {
+        check("call crate::missing", "fn missing2() {} fn f() { missing2() }", expect![[""]]);
+    }
+}
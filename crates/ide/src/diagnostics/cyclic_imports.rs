@@ -0,0 +1,295 @@
+//! Reports imports which lead back to the module containing them:
+//!
+//! * imports of the module itself, like `use super::foo;` in `foo`, which are
+//!   harmless but almost always a mistake;
+//! * imports forming a trivial cycle, like `pub use crate::b::X;` in `a` and
+//!   `pub use crate::a::X;` in `b`. Neither import can resolve, and rustc only
+//!   reports them as unresolved, without pointing at the cycle.
+//!
+//! Both come with a fix removing the import.
+
+use hir::{
+    diagnostics::DiagnosticCode, Module, ModuleDef, ModuleSource, PathResolution, Semantics,
+};
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, AstNode, ModuleItemOwner, NameOwner},
+    SyntaxNode,
+};
+
+use crate::{
+    diagnostics::unused_imports::removal_edit, Diagnostic, DiagnosticsConfig, FileRange, Fix,
+};
+
+pub(super) fn check(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    config: &DiagnosticsConfig,
+    file_id: FileId,
+) {
+    let file_module = match sema.to_module_def(file_id) {
+        Some(it) => it,
+        None => return,
+    };
+    let source_file = sema.parse(file_id);
+    for use_item in source_file.syntax().descendants().filter_map(ast::Use::cast) {
+        let module = match module_of(sema, file_module, use_item.syntax()) {
+            Some(it) => it,
+            None => continue,
+        };
+        let trees = use_item
+            .syntax()
+            .descendants()
+            .filter_map(ast::UseTree::cast)
+            .filter(|it| it.use_tree_list().is_none() && it.star_token().is_none());
+        for tree in trees {
+            let diagnostic = match imported_path(&tree) {
+                Some(path) => match sema.resolve_path(&path) {
+                    Some(PathResolution::Def(ModuleDef::Module(it))) if it == module => {
+                        if config.disabled.contains("self-import") {
+                            continue;
+                        }
+                        let name = imported_name(&tree).unwrap_or_else(|| path.to_string());
+                        Diagnostic::hint(
+                            tree.syntax().text_range(),
+                            format!("import of `{}` refers to the module containing it", name),
+                        )
+                        .with_code(Some(DiagnosticCode("self-import")))
+                    }
+                    Some(_) => continue,
+                    None => {
+                        if config.disabled.contains("cyclic-import") {
+                            continue;
+                        }
+                        match cyclic_import(sema, module, &tree, &path) {
+                            Some(it) => it,
+                            None => continue,
+                        }
+                    }
+                },
+                None => continue,
+            };
+            let fix = Fix::new(
+                "Remove import",
+                SourceChange::from_text_edit(file_id, removal_edit(&source_file, &[tree.clone()])),
+                tree.syntax().text_range(),
+            );
+            acc.push(diagnostic.with_fix(Some(fix)));
+        }
+    }
+}
+
+/// Reports `tree` if the module it imports from imports the same name back from `module`.
+fn cyclic_import(
+    sema: &Semantics<RootDatabase>,
+    module: Module,
+    tree: &ast::UseTree,
+    path: &ast::Path,
+) -> Option<Diagnostic> {
+    let db = sema.db;
+    let name = imported_name(tree)?;
+    let (source, source_name) = import_source(sema, path)?;
+    let range = tree.syntax().text_range();
+    if source == module && source_name == name {
+        return Some(
+            Diagnostic::error(
+                range,
+                format!("cyclic import: `{}` is imported from this module itself", name),
+            )
+            .with_code(Some(DiagnosticCode("cyclic-import"))),
+        );
+    }
+
+    let (source_file_id, items) = module_items(sema, source)?;
+    let back = items
+        .iter()
+        .filter_map(|it| match it {
+            ast::Item::Use(it) => it.use_tree(),
+            _ => None,
+        })
+        .flat_map(|it| it.syntax().descendants().filter_map(ast::UseTree::cast).collect::<Vec<_>>())
+        .filter(|it| it.use_tree_list().is_none() && it.star_token().is_none())
+        .find(|it| {
+            imported_name(it).as_deref() == Some(source_name.as_str())
+                && imported_path(it).and_then(|path| import_source(sema, &path))
+                    == Some((module, name.clone()))
+        })?;
+    let source_path = source.name(db).map_or_else(|| "crate".to_string(), |it| it.to_string());
+    let related = vec![(
+        FileRange { file_id: source_file_id, range: back.syntax().text_range() },
+        format!("`{}` imports `{}` back here", source_path, source_name),
+    )];
+    Some(
+        Diagnostic::error(
+            range,
+            format!(
+                "cyclic import: `{}` is imported from `{}`, which imports it from this module",
+                name, source_path
+            ),
+        )
+        .with_code(Some(DiagnosticCode("cyclic-import")))
+        .with_related_information(related),
+    )
+}
+
+/// The path of the item a non-glob `use` tree imports. `use foo::{self}` imports `foo`.
+fn imported_path(tree: &ast::UseTree) -> Option<ast::Path> {
+    let path = tree.path()?;
+    if path.qualifier().is_none() && path.segment()?.self_token().is_some() {
+        let list = tree.syntax().parent().and_then(ast::UseTreeList::cast)?;
+        return list.parent_use_tree().path();
+    }
+    Some(path)
+}
+
+fn imported_name(tree: &ast::UseTree) -> Option<String> {
+    match tree.rename() {
+        Some(rename) => Some(rename.name()?.text().to_string()),
+        None => Some(imported_path(tree)?.segment()?.name_ref()?.text().to_string()),
+    }
+}
+
+/// The module an imported path is looked up in, and the name looked up.
+fn import_source(sema: &Semantics<RootDatabase>, path: &ast::Path) -> Option<(Module, String)> {
+    let name = path.segment()?.name_ref()?.text().to_string();
+    let qualifier = match path.qualifier() {
+        Some(it) => it,
+        None => {
+            path.syntax().ancestors().find_map(ast::UseTreeList::cast)?.parent_use_tree().path()?
+        }
+    };
+    match sema.resolve_path(&qualifier)? {
+        PathResolution::Def(ModuleDef::Module(it)) => Some((it, name)),
+        _ => None,
+    }
+}
+
+fn module_items(
+    sema: &Semantics<RootDatabase>,
+    module: Module,
+) -> Option<(FileId, Vec<ast::Item>)> {
+    let src = module.definition_source(sema.db);
+    if src.file_id.call_node(sema.db).is_some() {
+        return None;
+    }
+    let file_id = src.file_id.original_file(sema.db);
+    let file = sema.parse(file_id);
+    let items = match src.value {
+        ModuleSource::SourceFile(_) => file.items().collect(),
+        ModuleSource::Module(it) => {
+            find_node_at_range::<ast::Module>(file.syntax(), it.syntax().text_range())?
+                .item_list()?
+                .items()
+                .collect()
+        }
+        ModuleSource::BlockExpr(_) => return None,
+    };
+    Some((file_id, items))
+}
+
+fn module_of(
+    sema: &Semantics<RootDatabase>,
+    file_module: Module,
+    node: &SyntaxNode,
+) -> Option<Module> {
+    match node.ancestors().skip(1).find_map(ast::Module::cast) {
+        Some(module) => sema.to_def(&module),
+        None => Some(file_module),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{diagnostics::tests::check_fix, fixture, DiagnosticsConfig};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let actual = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| {
+                d.code.map_or(false, |it| {
+                    it.as_str() == "self-import" || it.as_str() == "cyclic-import"
+                })
+            })
+            .map(|d| {
+                let related = d
+                    .related_information
+                    .iter()
+                    .map(|(range, message)| format!("    {:?} {}\n", range.range, message))
+                    .collect::<String>();
+                format!("{:?} {}\n{}", d.range, d.message, related)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn reports_imports_of_the_containing_module() {
+        check(
+            r#"
+mod foo {
+    use super::foo;
+    use crate::{foo::{self}, bar};
+    use super::bar as baz;
+}
+mod bar {}
+"#,
+            expect![[r#"
+                18..28 import of `foo` refers to the module containing it
+                52..56 import of `foo` refers to the module containing it
+            "#]],
+        );
+    }
+
+    #[test]
+    fn reports_trivially_cyclic_reexports() {
+        check(
+            r#"
+mod a { pub use crate::b::X; }
+mod b { pub use super::a::{X}; }
+use self::Y;
+"#,
+            expect![[r#"
+                16..27 cyclic import: `X` is imported from `b`, which imports it from this module
+                    58..59 `b` imports `X` back here
+                58..59 cyclic import: `X` is imported from `a`, which imports it from this module
+                    16..27 `a` imports `X` back here
+                68..75 cyclic import: `Y` is imported from this module itself
+            "#]],
+        );
+    }
+
+    #[test]
+    fn resolved_reexports_are_not_cyclic() {
+        check(
+            r#"
+mod a { pub use crate::b::X; pub struct Y; }
+mod b { pub use super::a::Y; pub struct X; }
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn removes_self_import() {
+        check_fix(
+            r#"
+mod foo {
+    use super::{f$0oo, bar};
+}
+mod bar {}
+"#,
+            r#"
+mod foo {
+    use super::bar;
+}
+mod bar {}
+"#,
+        );
+    }
+}
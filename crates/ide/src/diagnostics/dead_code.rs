@@ -0,0 +1,352 @@
+//! Reports items which can't be reached from outside of their crate and which
+//! aren't referred to from anywhere inside of it, with a fix deleting them.
+//!
+//! References are found with the regular find-usages machinery. As usages in
+//! `#[cfg]`-disabled code and macro definitions can't be resolved, textual
+//! mentions of the name there count as uses as well. The identifiers in such
+//! code are collected once per file by `SymbolsDatabase::unresolved_idents`.
+//!
+//! Searching for the usages of every item of a file is slow in large crates,
+//! so this is only enabled by `DiagnosticsConfig::dead_code`.
+
+use hir::{
+    diagnostics::DiagnosticCode, HasSource, InFile, Module, ModuleDef, ModuleSource, ScopeDef,
+    Semantics, Visibility,
+};
+use ide_db::{
+    base_db::FileId, defs::Definition, source_change::SourceChange, symbol_index::SymbolsDatabase,
+    RootDatabase,
+};
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, AstNode},
+    SyntaxKind, SyntaxNode, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{Diagnostic, Fix};
+
+pub(super) fn check(acc: &mut Vec<Diagnostic>, sema: &Semantics<RootDatabase>, file_id: FileId) {
+    let db = sema.db;
+    let module = match sema.to_module_def(file_id) {
+        Some(it) => it,
+        None => return,
+    };
+    let crate_root = module.crate_root(db);
+    let exported = exported_items(db, crate_root);
+    let crate_files = crate_files(db, crate_root);
+
+    let mut modules = vec![module];
+    while let Some(module) = modules.pop() {
+        // Inline submodules are part of this file, out-of-line ones are checked separately.
+        modules.extend(
+            module.children(db).filter(|child| {
+                matches!(child.definition_source(db).value, ModuleSource::Module(_))
+            }),
+        );
+        for def in module.declarations(db) {
+            if exported.contains(&def) {
+                continue;
+            }
+            check_item(acc, sema, file_id, &crate_files, module, def);
+        }
+    }
+}
+
+fn check_item(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    crate_files: &[FileId],
+    module: Module,
+    def: ModuleDef,
+) -> Option<()> {
+    let db = sema.db;
+    let kind = item_kind(def)?;
+    let name = def.name(db)?.to_string();
+    if name.starts_with('_') {
+        return None;
+    }
+    if matches!(def, ModuleDef::Function(_)) && name == "main" && module.parent(db).is_none() {
+        return None;
+    }
+
+    let source = item_source(db, def)?;
+    // Items produced by macros can't be deleted by the user.
+    if source.file_id != file_id.into() {
+        return None;
+    }
+    let item = source.value;
+    if is_used_externally(&item) {
+        return None;
+    }
+    let item_range = item.syntax().text_range();
+    let name_range = item.syntax().children().find_map(ast::Name::cast)?.syntax().text_range();
+
+    let definition = Definition::ModuleDef(def);
+    let usages = definition.usages(sema).all();
+    let is_used = usages.file_ranges().any(|reference| {
+        reference.file_id != file_id || !item_range.contains_range(reference.range)
+    });
+    if is_used || is_mentioned_in_unresolved_code(db, crate_files, &name, file_id, item_range) {
+        return None;
+    }
+
+    let fix = Fix::new(
+        &format!("Safe delete `{}`", name),
+        SourceChange::from_text_edit(file_id, delete_item(&item)),
+        name_range,
+    );
+    acc.push(
+        Diagnostic::hint(name_range, format!("{} `{}` is never used", kind, name))
+            .with_fix(Some(fix))
+            .with_unused(true)
+            .with_code(Some(DiagnosticCode("dead-code")))
+            .with_lint(Some("dead_code")),
+    );
+    Some(())
+}
+
+/// The files of the modules of a crate. Items which aren't exported can only be
+/// used there.
+fn crate_files(db: &RootDatabase, crate_root: Module) -> Vec<FileId> {
+    let mut res = Vec::new();
+    let mut to_visit = vec![crate_root];
+    while let Some(module) = to_visit.pop() {
+        let file_id = module.definition_source(db).file_id.original_file(db);
+        if !res.contains(&file_id) {
+            res.push(file_id);
+        }
+        to_visit.extend(module.children(db));
+    }
+    res
+}
+
+/// Collects items which can be named from other crates, either directly or
+/// through `pub use` re-exports.
+fn exported_items(db: &RootDatabase, crate_root: Module) -> FxHashSet<ModuleDef> {
+    let mut res = FxHashSet::default();
+    let mut to_visit = vec![crate_root];
+    while let Some(module) = to_visit.pop() {
+        for (_, def) in module.scope(db, None) {
+            let def = match def {
+                ScopeDef::ModuleDef(it) => it,
+                _ => continue,
+            };
+            if module.visibility_of(db, &def) != Some(Visibility::Public) {
+                continue;
+            }
+            if !res.insert(def) {
+                continue;
+            }
+            if let ModuleDef::Module(it) = def {
+                if it.krate() == crate_root.krate() {
+                    to_visit.push(it);
+                }
+            }
+        }
+    }
+    res
+}
+
+fn item_kind(def: ModuleDef) -> Option<&'static str> {
+    let res = match def {
+        ModuleDef::Function(_) => "function",
+        ModuleDef::Adt(hir::Adt::Struct(_)) => "struct",
+        ModuleDef::Adt(hir::Adt::Union(_)) => "union",
+        ModuleDef::Adt(hir::Adt::Enum(_)) => "enum",
+        ModuleDef::Const(_) => "constant",
+        ModuleDef::Static(_) => "static",
+        ModuleDef::Trait(_) => "trait",
+        ModuleDef::TypeAlias(_) => "type alias",
+        ModuleDef::Module(_) | ModuleDef::Variant(_) | ModuleDef::BuiltinType(_) => return None,
+    };
+    Some(res)
+}
+
+fn item_source(db: &RootDatabase, def: ModuleDef) -> Option<InFile<ast::Item>> {
+    let res = match def {
+        ModuleDef::Function(it) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::Adt(hir::Adt::Struct(it)) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::Adt(hir::Adt::Union(it)) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::Adt(hir::Adt::Enum(it)) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::Const(it) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::Static(it) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::Trait(it) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::TypeAlias(it) => it.source(db)?.map(ast::Item::from),
+        ModuleDef::Module(_) | ModuleDef::Variant(_) | ModuleDef::BuiltinType(_) => return None,
+    };
+    Some(res)
+}
+
+/// Tests, and symbols which are exported to the linker, are used without
+/// being referred to by name.
+fn is_used_externally(item: &ast::Item) -> bool {
+    attrs(item.syntax()).any(|attr| {
+        matches!(
+            attr.simple_name().as_deref(),
+            Some("test") | Some("bench") | Some("no_mangle") | Some("export_name") | Some("used")
+        )
+    })
+}
+
+fn attrs(node: &SyntaxNode) -> impl Iterator<Item = ast::Attr> {
+    node.children().filter_map(ast::Attr::cast)
+}
+
+/// Code disabled by `#[cfg]` (like `#[cfg(test)]` modules) and macro token
+/// trees are not resolved, so a mention of the name there is a potential use.
+fn is_mentioned_in_unresolved_code(
+    db: &RootDatabase,
+    crate_files: &[FileId],
+    name: &str,
+    file_id: FileId,
+    item_range: TextRange,
+) -> bool {
+    crate_files.iter().any(|&scope_file_id| {
+        db.unresolved_idents(scope_file_id).get(name).map_or(false, |offsets| {
+            offsets.iter().any(|&offset| scope_file_id != file_id || !item_range.contains(offset))
+        })
+    })
+}
+
+fn delete_item(item: &ast::Item) -> TextEdit {
+    let mut range = item.syntax().text_range();
+    if let Some(ws) = item
+        .syntax()
+        .prev_sibling_or_token()
+        .and_then(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+    {
+        range = TextRange::new(ws.text_range().start(), range.end());
+    }
+    TextEdit::delete(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig { dead_code: true, ..DiagnosticsConfig::default() }
+    }
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let actual = analysis
+            .diagnostics(&config(), file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.code.map_or(false, |it| it.as_str() == "dead-code"))
+            .map(|d| format!("{:?} {}\n", d.range, d.message))
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let diagnostic = analysis
+            .diagnostics(&config(), position.file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.code.map_or(false, |it| it.as_str() == "dead-code"))
+            .find(|d| d.range.contains_inclusive(position.offset))
+            .unwrap();
+        let fix = diagnostic.fixes.unwrap().pop().unwrap();
+        let mut actual = analysis.file_text(position.file_id).unwrap().to_string();
+        for edit in fix.source_change.source_file_edits.values() {
+            edit.apply(&mut actual);
+        }
+        assert_eq_text!(&trim_indent(ra_fixture_after), &actual);
+    }
+
+    #[test]
+    fn reports_unused_private_items() {
+        check(
+            r#"
+fn used() {}
+fn unused() {}
+fn recursive() { recursive() }
+struct S;
+const C: () = ();
+pub fn api() { used() }
+"#,
+            expect![[r#"
+                16..22 function `unused` is never used
+                31..40 function `recursive` is never used
+                66..67 struct `S` is never used
+                75..76 constant `C` is never used
+            "#]],
+        );
+    }
+
+    #[test]
+    fn pub_items_in_private_modules() {
+        check(
+            r#"
+mod inner {
+    pub fn reexported() {}
+    pub fn glob() {}
+    pub fn unused() {}
+}
+mod glob {
+    pub fn via_glob() {}
+}
+pub use inner::reexported;
+pub use glob::*;
+"#,
+            expect![[r#"
+                50..54 function `glob` is never used
+                71..77 function `unused` is never used
+            "#]],
+        );
+    }
+
+    #[test]
+    fn usages_in_cfg_and_macros() {
+        check(
+            r#"
+fn in_test() {}
+fn in_macro() {}
+fn skipped_test() {}
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn t() { super::in_test() }
+}
+macro_rules! m { () => { $crate::in_macro() } }
+#[test]
+fn skipped_test_itself() {}
+#[allow(dead_code)]
+fn allowed() {}
+fn main() {}
+"#,
+            expect![[r#"
+                36..48 function `skipped_test` is never used
+            "#]],
+        );
+    }
+
+    #[test]
+    fn safe_delete() {
+        check_fix(
+            r#"
+fn main() {}
+
+/// Docs.
+fn unused$0() {
+    main()
+}
+fn other() {}
+"#,
+            r#"
+fn main() {}
+fn other() {}
+"#,
+        );
+    }
+}
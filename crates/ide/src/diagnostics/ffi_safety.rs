@@ -0,0 +1,456 @@
+//! Reports types which can't be passed across an FFI boundary in the signatures of items of
+//! `extern` blocks, of `extern "C"` functions and of `#[no_mangle]` statics.
+//!
+//! Like rustc's `improper_ctypes` lints, this looks at the types in the signature, but not
+//! through pointers: a pointer to a Rust type is the usual way to pass an opaque handle.
+
+use hir::{
+    diagnostics::DiagnosticCode, Adt, HasAttrs, HasSource, ModuleDef, PathResolution, Semantics,
+};
+use ide_db::{base_db::FileId, helpers::FamousDefs, source_change::SourceChange, RootDatabase};
+use syntax::{
+    ast::{self, edit::IndentLevel, AttrsOwner, GenericParamsOwner},
+    AstNode,
+};
+use text_edit::TextEdit;
+
+use crate::{Diagnostic, DiagnosticsConfig, Fix};
+
+pub(super) fn check(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    config: &DiagnosticsConfig,
+    file_id: FileId,
+) {
+    let source_file = sema.parse(file_id);
+    let krate = match sema.to_module_def(file_id) {
+        Some(module) => module.krate(),
+        None => return,
+    };
+    let checker =
+        Checker { sema, config, krate, option: FamousDefs(sema, Some(krate)).core_option_Option() };
+    for node in source_file.syntax().descendants() {
+        if let Some(block) = ast::ExternBlock::cast(node.clone()) {
+            if !is_foreign_abi(block.abi()) {
+                continue;
+            }
+            for item in block.extern_item_list().into_iter().flat_map(|it| it.extern_items()) {
+                match item {
+                    ast::ExternItem::Fn(it) => checker.check_fn(acc, &it, "improper_ctypes"),
+                    ast::ExternItem::Static(it) => {
+                        if let Some(ty) = it.ty() {
+                            checker.check_type(acc, &ty, false, "improper_ctypes");
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        } else if let Some(func) = ast::Fn::cast(node.clone()) {
+            if func.abi().is_some() && is_foreign_abi(func.abi()) && func.body().is_some() {
+                checker.check_fn(acc, &func, "improper_ctypes_definitions");
+            }
+        } else if let Some(statik) = ast::Static::cast(node) {
+            let no_mangle =
+                statik.attrs().any(|attr| attr.as_simple_atom().as_deref() == Some("no_mangle"));
+            if let (true, Some(ty)) = (no_mangle, statik.ty()) {
+                checker.check_type(acc, &ty, false, "improper_ctypes");
+            }
+        }
+    }
+}
+
+fn is_foreign_abi(abi: Option<ast::Abi>) -> bool {
+    let abi = abi.and_then(|it| it.abi_string());
+    !matches!(abi.as_deref(), Some("Rust") | Some("rust-call") | Some("rust-intrinsic"))
+}
+
+struct Checker<'a> {
+    sema: &'a Semantics<'a, RootDatabase>,
+    config: &'a DiagnosticsConfig,
+    krate: hir::Crate,
+    option: Option<hir::Enum>,
+}
+
+impl Checker<'_> {
+    fn check_fn(&self, acc: &mut Vec<Diagnostic>, func: &ast::Fn, lint: &'static str) {
+        // Generic functions can't be called from C; their instances are checked by rustc.
+        if func.generic_param_list().is_some() {
+            return;
+        }
+        let params = func.param_list().into_iter().flat_map(|it| it.params());
+        for ty in params.filter_map(|it| it.ty()) {
+            self.check_type(acc, &ty, true, lint);
+        }
+        if let Some(ty) = func.ret_type().and_then(|it| it.ty()) {
+            self.check_type(acc, &ty, true, lint);
+        }
+    }
+
+    fn check_type(
+        &self,
+        acc: &mut Vec<Diagnostic>,
+        ty: &ast::Type,
+        by_value: bool,
+        lint: &'static str,
+    ) {
+        let reason = match ty {
+            ast::Type::ParenType(it) => {
+                if let Some(ty) = it.ty() {
+                    self.check_type(acc, &ty, by_value, lint);
+                }
+                return;
+            }
+            ast::Type::RefType(it) => it.ty().and_then(|it| self.wide_pointer(&it)),
+            ast::Type::PtrType(it) => it.ty().and_then(|it| self.wide_pointer(&it)),
+            ast::Type::SliceType(_) => Some("slices have no C equivalent".to_string()),
+            ast::Type::DynTraitType(_) | ast::Type::ImplTraitType(_) => {
+                Some("trait objects have no C equivalent".to_string())
+            }
+            ast::Type::TupleType(it) if it.fields().next().is_some() => {
+                Some("tuples have unspecified layout".to_string())
+            }
+            ast::Type::ArrayType(it) => {
+                if let Some(ty) = it.ty() {
+                    self.check_type(acc, &ty, false, lint);
+                }
+                if !by_value {
+                    return;
+                }
+                Some("arrays can't be passed by value, pass a pointer instead".to_string())
+            }
+            ast::Type::FnPtrType(it) if !is_foreign_abi_fn_ptr(it) => {
+                Some("function pointers must use a foreign ABI, like `extern \"C\"`".to_string())
+            }
+            ast::Type::PathType(it) => match it.path() {
+                Some(path) => return self.check_path(acc, &path, lint),
+                None => None,
+            },
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            self.push_unsafe_type(acc, ty.syntax(), reason, lint);
+        }
+    }
+
+    fn check_path(&self, acc: &mut Vec<Diagnostic>, path: &ast::Path, lint: &'static str) {
+        let db = self.sema.db;
+        let reason = match self.sema.resolve_path(path) {
+            Some(PathResolution::Def(ModuleDef::BuiltinType(builtin))) => {
+                match builtin.name().to_string().as_str() {
+                    "char" => Some("`char` has no C equivalent, use `u32` instead".to_string()),
+                    "i128" | "u128" => Some("128-bit integers have no stable ABI".to_string()),
+                    "str" => Some("string slices have no C equivalent".to_string()),
+                    _ => None,
+                }
+            }
+            Some(PathResolution::Def(ModuleDef::Adt(adt))) => {
+                let name = adt.name(db).to_string();
+                let allowed = self.config.ffi_safe_types.iter().any(|it| {
+                    *it == name || *it == path.to_string() || it.ends_with(&format!("::{}", name))
+                });
+                if allowed || is_ffi_safe_repr(db, adt) {
+                    None
+                } else if self.option.map(Adt::from) == Some(adt) {
+                    match first_type_arg(path) {
+                        Some(arg) if is_non_nullable(self.sema, &arg) => None,
+                        _ => Some(
+                            "`Option` is only FFI-safe around references, `Box` and function pointers"
+                                .to_string(),
+                        ),
+                    }
+                } else if name == "Box" && adt.module(db).krate() != self.krate {
+                    first_type_arg(path).and_then(|it| self.wide_pointer(&it))
+                } else if adt.module(db).krate() == self.krate {
+                    self.push_missing_repr(acc, path, adt, lint);
+                    None
+                } else {
+                    Some(format!("`{}` has unspecified layout", name))
+                }
+            }
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            self.push_unsafe_type(acc, path.syntax(), reason, lint);
+        }
+    }
+
+    /// Why a pointer to `pointee` is twice the size of a C pointer, if it is.
+    fn wide_pointer(&self, pointee: &ast::Type) -> Option<String> {
+        let kind = match pointee {
+            ast::Type::SliceType(_) => "slice",
+            ast::Type::DynTraitType(_) => "trait object",
+            ast::Type::PathType(it) => match self.sema.resolve_path(&it.path()?)? {
+                PathResolution::Def(ModuleDef::BuiltinType(it))
+                    if it.name().to_string() == "str" =>
+                {
+                    "string slice"
+                }
+                PathResolution::Def(ModuleDef::Trait(_)) => "trait object",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(format!("pointers to a {} carry metadata and have no C equivalent", kind))
+    }
+
+    fn push_unsafe_type(
+        &self,
+        acc: &mut Vec<Diagnostic>,
+        node: &syntax::SyntaxNode,
+        reason: String,
+        lint: &'static str,
+    ) {
+        if self.config.disabled.contains("ffi-unsafe-type") {
+            return;
+        }
+        acc.push(
+            Diagnostic::hint(node.text_range(), format!("`{}` is not FFI-safe: {}", node, reason))
+                .with_code(Some(DiagnosticCode("ffi-unsafe-type")))
+                .with_lint(Some(lint)),
+        );
+    }
+
+    fn push_missing_repr(
+        &self,
+        acc: &mut Vec<Diagnostic>,
+        path: &ast::Path,
+        adt: Adt,
+        lint: &'static str,
+    ) {
+        if self.config.disabled.contains("ffi-missing-repr-c") {
+            return;
+        }
+        let db = self.sema.db;
+        let kind = match adt {
+            Adt::Struct(_) => "struct",
+            Adt::Union(_) => "union",
+            Adt::Enum(_) => "enum",
+        };
+        let range = path.syntax().text_range();
+        let message = format!(
+            "{} `{}` is used across an FFI boundary but has no `#[repr(C)]`",
+            kind,
+            adt.name(db)
+        );
+        acc.push(
+            Diagnostic::hint(range, message)
+                .with_fix(repr_c_fix(self.sema, adt, range))
+                .with_code(Some(DiagnosticCode("ffi-missing-repr-c")))
+                .with_lint(Some(lint)),
+        );
+    }
+}
+
+fn repr_c_fix(sema: &Semantics<RootDatabase>, adt: Adt, range: syntax::TextRange) -> Option<Fix> {
+    let db = sema.db;
+    let (file_id, node) = match adt {
+        Adt::Struct(it) => it.source(db).map(|it| (it.file_id, it.value.syntax().clone())),
+        Adt::Union(it) => it.source(db).map(|it| (it.file_id, it.value.syntax().clone())),
+        Adt::Enum(it) => it.source(db).map(|it| (it.file_id, it.value.syntax().clone())),
+    }?;
+    if file_id.call_node(db).is_some() {
+        return None;
+    }
+    let adt_node = ast::Adt::cast(node)?;
+    // Keep doc comments first, the attribute goes before any other attributes.
+    let offset = adt_node
+        .syntax()
+        .children_with_tokens()
+        .find(|it| !it.kind().is_trivia())?
+        .text_range()
+        .start();
+    let indent = IndentLevel::from_node(adt_node.syntax());
+    let edit = TextEdit::insert(offset, format!("#[repr(C)]\n{}", indent));
+    let file_id = file_id.original_file(db);
+    Some(Fix::new("Add `#[repr(C)]`", SourceChange::from_text_edit(file_id, edit), range))
+}
+
+fn is_ffi_safe_repr(db: &RootDatabase, adt: Adt) -> bool {
+    adt.attrs(db).by_key("repr").tt_values().any(|tt| {
+        let text = tt.to_string();
+        text.split(|c: char| !c.is_alphanumeric() && c != '_').any(|repr| match repr {
+            "C" | "transparent" => true,
+            "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+                matches!(adt, Adt::Enum(_))
+            }
+            _ => false,
+        })
+    })
+}
+
+fn is_foreign_abi_fn_ptr(fn_ptr: &ast::FnPtrType) -> bool {
+    fn_ptr.abi().is_some() && is_foreign_abi(fn_ptr.abi())
+}
+
+fn first_type_arg(path: &ast::Path) -> Option<ast::Type> {
+    path.segment()?.generic_arg_list()?.generic_args().find_map(|arg| match arg {
+        ast::GenericArg::TypeArg(it) => it.ty(),
+        _ => None,
+    })
+}
+
+/// Whether `ty` is a pointer which can't be null, so that `Option<ty>` is the C pointer.
+fn is_non_nullable(sema: &Semantics<RootDatabase>, ty: &ast::Type) -> bool {
+    match ty {
+        ast::Type::RefType(_) => true,
+        ast::Type::FnPtrType(it) => is_foreign_abi_fn_ptr(it),
+        ast::Type::PathType(it) => match it.path().and_then(|path| sema.resolve_path(&path)) {
+            Some(PathResolution::Def(ModuleDef::Adt(adt))) => {
+                let name = adt.name(sema.db).to_string();
+                name == "Box" || name == "NonNull"
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{diagnostics::tests::check_fix, fixture, DiagnosticsConfig};
+
+    const STD: &str = r#"
+//- /core/lib.rs crate:core
+pub mod string {
+    pub struct String;
+}
+pub mod boxed {
+    pub struct Box<T: ?Sized>(*mut T);
+}
+pub mod option {
+    pub enum Option<T> { None, Some(T) }
+}
+"#;
+
+    fn check(safe_types: &[&str], ra_fixture: &str, expect: Expect) {
+        let config = DiagnosticsConfig {
+            ffi_safe_types: safe_types.iter().map(|it| it.to_string()).collect(),
+            ..DiagnosticsConfig::default()
+        };
+        let fixture = format!("//- /main.rs crate:main deps:core{}{}", ra_fixture, STD);
+        let (analysis, file_id) = fixture::file(&fixture);
+        let actual = analysis
+            .diagnostics(&config, file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.code.map_or(false, |it| it.as_str().starts_with("ffi-")))
+            .map(|d| format!("{:?} {}\n", d.range, d.message))
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn reports_unsafe_types_in_extern_blocks() {
+        check(
+            &[],
+            r#"
+use core::string::String;
+trait Tr {}
+extern "C" {
+    fn takes(s: String, c: char, b: &[u8], t: &dyn Tr, f: fn(), p: *const String);
+    fn returns() -> (i32, i32);
+    static NAME: &str;
+}
+"#,
+            expect![[r#"
+                67..73 `String` is not FFI-safe: `String` has unspecified layout
+                78..82 `char` is not FFI-safe: `char` has no C equivalent, use `u32` instead
+                87..92 `&[u8]` is not FFI-safe: pointers to a slice carry metadata and have no C equivalent
+                97..104 `&dyn Tr` is not FFI-safe: pointers to a trait object carry metadata and have no C equivalent
+                109..113 `fn()` is not FFI-safe: function pointers must use a foreign ABI, like `extern "C"`
+                154..164 `(i32, i32)` is not FFI-safe: tuples have unspecified layout
+                183..187 `&str` is not FFI-safe: pointers to a string slice carry metadata and have no C equivalent
+            "#]],
+        );
+    }
+
+    #[test]
+    fn reports_unsafe_types_in_extern_fns_and_no_mangle_statics() {
+        check(
+            &[],
+            r#"
+use core::{boxed::Box, option::Option};
+#[no_mangle]
+static TABLE: [u128; 4] = [0; 4];
+extern "C" fn ok(x: Option<&i32>, y: Option<Box<i32>>, z: Box<[u8]>) {}
+extern fn bad(x: Option<i32>, xs: [u8; 4]) {}
+extern "Rust" fn rust(s: &str) {}
+fn plain(s: &str) {}
+"#,
+            expect![[r#"
+                68..72 `u128` is not FFI-safe: 128-bit integers have no stable ABI
+                145..154 `Box<[u8]>` is not FFI-safe: pointers to a slice carry metadata and have no C equivalent
+                176..187 `Option<i32>` is not FFI-safe: `Option` is only FFI-safe around references, `Box` and function pointers
+                193..200 `[u8; 4]` is not FFI-safe: arrays can't be passed by value, pass a pointer instead
+            "#]],
+        );
+    }
+
+    #[test]
+    fn reports_missing_repr_c() {
+        check(
+            &[],
+            r#"
+struct Plain { x: i32 }
+#[repr(C)]
+struct WithReprC { x: i32 }
+#[repr(transparent)]
+struct Wrapper(i32);
+#[repr(u8)]
+enum Tag { A, B }
+enum Untagged { A, B }
+extern "C" {
+    fn f(a: Plain, b: WithReprC, c: Wrapper, d: Tag, e: Untagged, f: *mut Plain);
+}
+"#,
+            expect![[r##"
+                183..188 struct `Plain` is used across an FFI boundary but has no `#[repr(C)]`
+                227..235 enum `Untagged` is used across an FFI boundary but has no `#[repr(C)]`
+            "##]],
+        );
+    }
+
+    #[test]
+    fn respects_configured_safe_types() {
+        check(
+            &["core::string::String", "bindings::Opaque"],
+            r#"
+use core::string::String;
+mod bindings {
+    pub struct Opaque { _private: [u8; 0] }
+}
+extern "C" {
+    fn f(s: String, o: bindings::Opaque);
+}
+"#,
+            expect![[""]],
+        );
+    }
+
+    #[test]
+    fn adds_repr_c() {
+        check_fix(
+            r#"
+mod ffi {
+    /// A point.
+    pub struct Point { x: f64, y: f64 }
+}
+extern "C" {
+    fn draw(p: ffi::Po$0int);
+}
+"#,
+            r#"
+mod ffi {
+    /// A point.
+    #[repr(C)]
+    pub struct Point { x: f64, y: f64 }
+}
+extern "C" {
+    fn draw(p: ffi::Point);
+}
+"#,
+        );
+    }
+}
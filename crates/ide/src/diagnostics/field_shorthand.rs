@@ -7,6 +7,8 @@ use text_edit::TextEdit;
 
 use crate::{Diagnostic, Fix};
 
+const REDUNDANT_FIELD_NAMES: &str = "clippy::redundant_field_names";
+
 pub(super) fn check(acc: &mut Vec<Diagnostic>, file_id: FileId, node: &SyntaxNode) {
     match_ast! {
         match node {
@@ -46,13 +48,13 @@ fn check_expr_field_shorthand(
 
         let field_range = record_field.syntax().text_range();
         acc.push(
-            Diagnostic::hint(field_range, "Shorthand struct initialization".to_string()).with_fix(
-                Some(Fix::new(
+            Diagnostic::hint(field_range, "Shorthand struct initialization".to_string())
+                .with_fix(Some(Fix::new(
                     "Use struct shorthand initialization",
                     SourceChange::from_text_edit(file_id, edit),
                     field_range,
-                )),
-            ),
+                )))
+                .with_lint(Some(REDUNDANT_FIELD_NAMES)),
         );
     }
 }
@@ -85,13 +87,15 @@ fn check_pat_field_shorthand(
         let edit = edit_builder.finish();
 
         let field_range = record_pat_field.syntax().text_range();
-        acc.push(Diagnostic::hint(field_range, "Shorthand struct pattern".to_string()).with_fix(
-            Some(Fix::new(
-                "Use struct field shorthand",
-                SourceChange::from_text_edit(file_id, edit),
-                field_range,
-            )),
-        ));
+        acc.push(
+            Diagnostic::hint(field_range, "Shorthand struct pattern".to_string())
+                .with_fix(Some(Fix::new(
+                    "Use struct field shorthand",
+                    SourceChange::from_text_edit(file_id, edit),
+                    field_range,
+                )))
+                .with_lint(Some(REDUNDANT_FIELD_NAMES)),
+        );
     }
 }
 
@@ -3,37 +3,57 @@
 use hir::{
     db::AstDatabase,
     diagnostics::{
-        Diagnostic, IncorrectCase, MissingFields, MissingOkOrSomeInTailExpr, NoSuchField,
-        RemoveThisSemicolon, ReplaceFilterMapNextWithFindMap, UnresolvedModule,
+        AwaitOutsideOfAsync, Conversion, Diagnostic, DuplicateDefinition, IncorrectCase,
+        MissingErrorConversion, MissingFields, MissingImplItems, MissingMatchArms,
+        MissingOkOrSomeInTailExpr, MissingUnsafe, NoSuchField, PrivateItem, RecursiveType,
+        RemoveThisSemicolon, ReplaceFilterMapNextWithFindMap, TryInNonTryFn, TypeMismatch,
+        UnnecessaryUnsafe, UnreachableMatchArm, UnresolvedField, UnresolvedImport,
+        UnresolvedMethodCall, UnresolvedModule, UnusedVariable,
     },
-    HasSource, HirDisplay, InFile, Semantics, VariantDef,
+    import_map::{self, ImportKind},
+    HasSource, HirDisplay, InFile, ModuleDef, Mutability, Name, PathResolution, ScopeDef,
+    Semantics, VariantDef,
+};
+use ide_assists::utils::{
+    add_trait_assoc_items_to_impl, append_to_braced_list, filter_assoc_items, DefaultMethods,
 };
 use ide_db::{
-    base_db::{AnchoredPathBuf, FileId},
+    base_db::{AnchoredPathBuf, FileId, SourceDatabaseExt},
+    helpers::{
+        expected_type,
+        insert_use::{insert_use, ImportScope, MergeBehavior},
+        mod_path_to_ast, SnippetCap,
+    },
     source_change::{FileSystemEdit, SourceChange},
+    traits::{get_missing_assoc_items, resolve_target_trait},
     RootDatabase,
 };
+use stdx::edit_distance;
 use syntax::{
     algo,
-    ast::{self, edit::IndentLevel, make, ArgListOwner},
-    AstNode, TextRange,
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, ArgListOwner, GenericParamsOwner, NameOwner,
+    },
+    AstNode, Direction, SyntaxKind, SyntaxNode, TextRange, T,
 };
 use text_edit::TextEdit;
 
 use crate::{diagnostics::Fix, references::rename::rename_with_semantics, FilePosition};
 
-/// A [Diagnostic] that potentially has a fix available.
+/// A [Diagnostic] that potentially has some fixes available.
 ///
 /// [Diagnostic]: hir::diagnostics::Diagnostic
-pub(crate) trait DiagnosticWithFix: Diagnostic {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix>;
+pub(crate) trait DiagnosticWithFixes: Diagnostic {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>>;
 }
 
-impl DiagnosticWithFix for UnresolvedModule {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+impl DiagnosticWithFixes for UnresolvedModule {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
         let root = sema.db.parse_or_expand(self.file)?;
         let unresolved_module = self.decl.to_node(&root);
-        Some(Fix::new(
+        Some(vec![Fix::new(
             "Create module",
             FileSystemEdit::CreateFile {
                 dst: AnchoredPathBuf {
@@ -44,23 +64,210 @@ impl DiagnosticWithFix for UnresolvedModule {
             }
             .into(),
             unresolved_module.syntax().text_range(),
-        ))
+        )])
+    }
+}
+
+impl DiagnosticWithFixes for UnresolvedImport {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let use_tree = self.node.to_node(&root);
+        let file_id = self.file.original_file(sema.db);
+        let trigger_range = use_tree.syntax().text_range();
+
+        let mut fixes = Vec::new();
+        if let Some((range, replacement)) = corrected_import(sema, &use_tree) {
+            fixes.push(Fix::new(
+                &format!("Replace with `{}`", replacement),
+                SourceChange::from_text_edit(file_id, TextEdit::replace(range, replacement)),
+                trigger_range,
+            ));
+        }
+
+        let mut builder = TextEdit::builder();
+        let new_root = remove_use_tree(use_tree).rewrite(&root);
+        algo::diff(&root, &new_root).into_text_edit(&mut builder);
+        fixes.push(Fix::new(
+            "Remove import",
+            SourceChange::from_text_edit(file_id, builder.finish()),
+            trigger_range,
+        ));
+        Some(fixes)
+    }
+}
+
+/// Removes the tree together with the enclosing `{}`, or the whole `use` item, if nothing else is
+/// imported with them.
+fn remove_use_tree(mut use_tree: ast::UseTree) -> algo::SyntaxRewriter<'static> {
+    loop {
+        let parent = match use_tree.syntax().parent() {
+            Some(it) => it,
+            None => return use_tree.remove(),
+        };
+        if let Some(use_item) = ast::Use::cast(parent.clone()) {
+            return use_item.remove();
+        }
+        match ast::UseTreeList::cast(parent) {
+            Some(list) if list.use_trees().count() == 1 => use_tree = list.parent_use_tree(),
+            _ => return use_tree.remove(),
+        }
+    }
+}
+
+/// Looks for the item the user most likely meant to import, returning the range to replace and
+/// the replacement.
+fn corrected_import(
+    sema: &Semantics<RootDatabase>,
+    use_tree: &ast::UseTree,
+) -> Option<(TextRange, String)> {
+    if use_tree.star_token().is_some() || use_tree.use_tree_list().is_some() {
+        return None;
+    }
+    let path = use_tree.path()?;
+    let name_ref = path.segment()?.name_ref()?;
+    let name = name_ref.text().to_string();
+    let current_module = sema.scope(use_tree.syntax()).module()?;
+
+    // If the module exists, the name is the only thing that can be wrong.
+    let qualifier = path.qualifier().or_else(|| {
+        let list = ast::UseTreeList::cast(use_tree.syntax().parent()?)?;
+        list.parent_use_tree().path()
+    });
+    if let Some(PathResolution::Def(ModuleDef::Module(module))) =
+        qualifier.and_then(|it| sema.resolve_path(&it))
+    {
+        let candidate = module
+            .scope(sema.db, Some(current_module))
+            .into_iter()
+            .filter(|(_, def)| !matches!(def, ScopeDef::Unknown))
+            .map(|(candidate, _)| candidate.to_string())
+            .filter(|candidate| *candidate != name && stdx::is_typo_of(candidate, &name))
+            .map(|candidate| (edit_distance(&candidate, &name), candidate))
+            .min();
+        if let Some((_, candidate)) = candidate {
+            return Some((name_ref.syntax().text_range(), candidate));
+        }
+    }
+
+    // Otherwise, look for a similarly named item in the dependencies and replace the whole path.
+    ast::Use::cast(use_tree.syntax().parent()?)?;
+    let query = import_map::Query::new(name.clone())
+        .name_only()
+        .search_mode(import_map::SearchMode::Typo)
+        .exclude_import_kind(ImportKind::AssociatedItem)
+        .limit(40);
+    let written = path.syntax().text().to_string();
+    current_module
+        .krate()
+        .query_external_importables(sema.db, query)
+        .filter_map(|item| {
+            let item: hir::ItemInNs = item.either(Into::into, Into::into);
+            current_module.find_use_path(sema.db, item)
+        })
+        .map(|found| found.to_string())
+        .filter(|found| *found != written)
+        .map(|found| (edit_distance(&found, &written), found))
+        .min()
+        .map(|(_, found)| (path.syntax().text_range(), found))
+}
+
+impl DiagnosticWithFixes for PrivateItem {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        let target = self.target.as_ref()?;
+        if target.file_id.call_node(sema.db).is_some() {
+            return None;
+        }
+        // Only items in the workspace can be edited.
+        let target_file = target.file_id.original_file(sema.db);
+        if sema.db.source_root(sema.db.file_source_root(target_file)).is_library {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(target.file_id)?;
+        let node = target.value.to_node(&root);
+
+        let missing_visibility = if self.target_in_same_crate { "pub(crate)" } else { "pub" };
+        let edit = match node.children().find_map(ast::Visibility::cast) {
+            Some(current) => {
+                TextEdit::replace(current.syntax().text_range(), missing_visibility.into())
+            }
+            None => {
+                let offset = node
+                    .children_with_tokens()
+                    .find(|it| {
+                        !matches!(
+                            it.kind(),
+                            SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::ATTR
+                        )
+                    })
+                    .map_or(node.text_range().start(), |it| it.text_range().start());
+                TextEdit::insert(offset, format!("{} ", missing_visibility))
+            }
+        };
+
+        let root = sema.db.parse_or_expand(self.file)?;
+        let trigger_range = self.name_ref.to_node(&root).syntax().text_range();
+        Some(vec![Fix::new(
+            &format!("Change visibility of `{}` to {}", self.name, missing_visibility),
+            SourceChange::from_text_edit(target_file, edit),
+            trigger_range,
+        )])
+    }
+}
+
+impl DiagnosticWithFixes for MissingUnsafe {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let unsafe_expr = self.expr.to_node(&root);
+
+        // Wrap the whole expression the operation is part of, stopping at the statement or
+        // block boundary, so that e.g. `STATIC_MUT.field` isn't turned into a move out of a static.
+        let target = unsafe_expr
+            .syntax()
+            .ancestors()
+            .take_while(|node| {
+                !matches!(
+                    node.kind(),
+                    SyntaxKind::EXPR_STMT
+                        | SyntaxKind::LET_STMT
+                        | SyntaxKind::CONDITION
+                        | SyntaxKind::MATCH_ARM
+                        | SyntaxKind::PARAM_LIST
+                ) && (node == unsafe_expr.syntax()
+                    || !matches!(node.kind(), SyntaxKind::BLOCK_EXPR | SyntaxKind::CLOSURE_EXPR))
+            })
+            .filter_map(ast::Expr::cast)
+            .last()?;
+
+        let (range, replacement) = match target.syntax().parent().and_then(ast::ExprStmt::cast) {
+            Some(stmt) => (stmt.syntax().text_range(), format!("unsafe {{ {} }}", stmt)),
+            None => (target.syntax().text_range(), format!("unsafe {{ {} }}", target)),
+        };
+        let edit = TextEdit::replace(range, replacement);
+        let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
+        Some(vec![Fix::new("Add unsafe block", source_change, unsafe_expr.syntax().text_range())])
     }
 }
 
-impl DiagnosticWithFix for NoSuchField {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+impl DiagnosticWithFixes for NoSuchField {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
         let root = sema.db.parse_or_expand(self.file)?;
         missing_record_expr_field_fix(
             &sema,
             self.file.original_file(sema.db),
             &self.field.to_node(&root),
         )
+        .map(|fix| vec![fix])
     }
 }
 
-impl DiagnosticWithFix for MissingFields {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+impl DiagnosticWithFixes for MissingFields {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
         // Note that although we could add a diagnostics to
         // fill the missing tuple field, e.g :
         // `struct A(usize);`
@@ -86,16 +293,131 @@ impl DiagnosticWithFix for MissingFields {
                 .into_text_edit(&mut builder);
             builder.finish()
         };
-        Some(Fix::new(
+        Some(vec![Fix::new(
             "Fill struct fields",
             SourceChange::from_text_edit(self.file.original_file(sema.db), edit),
             sema.original_range(&field_list_parent.syntax()).range,
-        ))
+        )])
     }
 }
 
-impl DiagnosticWithFix for MissingOkOrSomeInTailExpr {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+impl DiagnosticWithFixes for MissingImplItems {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let impl_def = self.impl_.to_node(&root);
+        let trait_ = resolve_target_trait(sema, &impl_def)?;
+        let missing_items = filter_assoc_items(
+            sema.db,
+            &get_missing_assoc_items(sema, &impl_def),
+            DefaultMethods::No,
+        );
+        if missing_items.is_empty() {
+            return None;
+        }
+
+        let target_scope = sema.scope(impl_def.syntax());
+        let (new_impl_def, _) = add_trait_assoc_items_to_impl(
+            sema,
+            missing_items,
+            trait_,
+            impl_def.clone(),
+            target_scope,
+        );
+        let range = impl_def.syntax().text_range();
+        let edit = TextEdit::replace(range, new_impl_def.to_string());
+        Some(vec![Fix::new(
+            "Implement missing members",
+            SourceChange::from_text_edit(self.file.original_file(sema.db), edit),
+            range,
+        )])
+    }
+}
+
+impl DiagnosticWithFixes for RecursiveType {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let fields = self.fields.iter().map(|it| it.to_node(&root)).collect::<Vec<_>>();
+        let file_id = self.file.original_file(sema.db);
+        let range = self.name.to_node(&root).syntax().text_range();
+
+        let wrap = |label: &str, wrap: &dyn Fn(&ast::Type) -> String| {
+            let mut builder = TextEdit::builder();
+            for ty in &fields {
+                builder.replace(ty.syntax().text_range(), wrap(ty));
+            }
+            Fix::new(label, SourceChange::from_text_edit(file_id, builder.finish()), range)
+        };
+        Some(vec![
+            wrap("Wrap in `Box`", &|ty| format!("Box<{}>", ty)),
+            wrap("Wrap in `Option<Box<_>>`", &|ty| match option_arg(ty) {
+                Some(inner) => format!("Option<Box<{}>>", inner),
+                None => format!("Option<Box<{}>>", ty),
+            }),
+        ])
+    }
+}
+
+/// Returns `T` if the type is `Option<T>`.
+fn option_arg(ty: &ast::Type) -> Option<ast::Type> {
+    let segment = match ty {
+        ast::Type::PathType(it) => it.path()?.segment()?,
+        _ => return None,
+    };
+    if segment.name_ref()?.text() != "Option" {
+        return None;
+    }
+    match segment.generic_arg_list()?.generic_args().next()? {
+        ast::GenericArg::TypeArg(it) => it.ty(),
+        _ => None,
+    }
+}
+
+impl DiagnosticWithFixes for MissingMatchArms {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.missing_patterns.is_empty() || self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let arm_list = self.arms.to_node(&root);
+        let r_curly = arm_list.r_curly_token()?;
+
+        let indent = IndentLevel::from_node(arm_list.syntax());
+        let mut new_arms = String::new();
+        // The last arm needs a comma to be followed by another unless it is a block.
+        let (start, prefix) = match arm_list.arms().last() {
+            Some(arm) if arm.comma_token().is_none() && !is_block_like(arm.expr()) => {
+                (arm.syntax().text_range().end(), ",")
+            }
+            Some(arm) => (
+                arm.comma_token().map_or(arm.syntax().text_range(), |it| it.text_range()).end(),
+                "",
+            ),
+            None => (arm_list.l_curly_token()?.text_range().end(), ""),
+        };
+        new_arms.push_str(prefix);
+        for pat in &self.missing_patterns {
+            new_arms.push_str(&format!("\n{}{} => {{}}", indent + 1, pat));
+        }
+        new_arms.push_str(&format!("\n{}", indent));
+
+        let edit = TextEdit::replace(TextRange::new(start, r_curly.text_range().start()), new_arms);
+        let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
+        Some(vec![Fix::new("Fill match arms", source_change, arm_list.syntax().text_range())])
+    }
+}
+
+fn is_block_like(expr: Option<ast::Expr>) -> bool {
+    matches!(expr, Some(ast::Expr::BlockExpr(_)) | Some(ast::Expr::EffectExpr(_)))
+}
+
+impl DiagnosticWithFixes for MissingOkOrSomeInTailExpr {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
         let root = sema.db.parse_or_expand(self.file)?;
         let tail_expr = self.expr.to_node(&root);
         let tail_expr_range = tail_expr.syntax().text_range();
@@ -103,12 +425,423 @@ impl DiagnosticWithFix for MissingOkOrSomeInTailExpr {
         let edit = TextEdit::replace(tail_expr_range, replacement);
         let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
         let name = if self.required == "Ok" { "Wrap with Ok" } else { "Wrap with Some" };
-        Some(Fix::new(name, source_change, tail_expr_range))
+        Some(vec![Fix::new(name, source_change, tail_expr_range)])
     }
 }
 
-impl DiagnosticWithFix for RemoveThisSemicolon {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+impl DiagnosticWithFixes for AwaitOutsideOfAsync {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let await_expr = self.expr.to_node(&root);
+        let func = enclosing_fn(&await_expr)?;
+        if func.const_token().is_some() {
+            return None;
+        }
+        // `async` goes before `unsafe` and `extern`.
+        let keyword = func.unsafe_token().or_else(|| func.abi()?.syntax().first_token());
+        let offset = keyword.or_else(|| func.fn_token())?.text_range().start();
+        let edit = TextEdit::insert(offset, "async ".to_string());
+        let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
+        Some(vec![Fix::new(
+            &format!("Make `{}` async", func.name()?),
+            source_change,
+            await_expr.syntax().text_range(),
+        )])
+    }
+}
+
+impl DiagnosticWithFixes for TryInNonTryFn {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let return_ty = self.suggested_return_ty.as_ref()?;
+        let root = sema.db.parse_or_expand(self.file)?;
+        let try_expr = self.expr.to_node(&root);
+        let func = enclosing_fn(&try_expr)?;
+        let edit = match func.ret_type().and_then(|it| it.ty()) {
+            Some(ty) => TextEdit::replace(ty.syntax().text_range(), return_ty.clone()),
+            None => TextEdit::insert(
+                func.param_list()?.syntax().text_range().end(),
+                format!(" -> {}", return_ty),
+            ),
+        };
+        let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
+        Some(vec![Fix::new(
+            &format!("Change the return type to `{}`", return_ty),
+            source_change,
+            try_expr.syntax().text_range(),
+        )])
+    }
+}
+
+/// The fixes of a [`MissingErrorConversion`], which leave a placeholder for the conversion
+/// they can't write, as a snippet if `snippet_cap` allows.
+pub(super) fn missing_error_conversion_fixes(
+    d: &MissingErrorConversion,
+    sema: &Semantics<RootDatabase>,
+    snippet_cap: Option<SnippetCap>,
+) -> Option<Vec<Fix>> {
+    if d.file.call_node(sema.db).is_some() {
+        return None;
+    }
+    let root = sema.db.parse_or_expand(d.file)?;
+    let try_expr = match d.expr.to_node(&root) {
+        ast::Expr::TryExpr(it) => it,
+        _ => return None,
+    };
+    let operand = try_expr.expr()?;
+    let module = sema.scope(try_expr.syntax()).module()?;
+    let from_ty = sema.type_of_expr(&operand)?.type_parameters().nth(1)?;
+    let from_ty_text = from_ty.display_source_code(sema.db, module.into()).ok()?;
+    let file_id = d.file.original_file(sema.db);
+    let range = try_expr.syntax().text_range();
+
+    // The error type as written in the return type of the function or the closure.
+    let error_ty = try_expr
+        .syntax()
+        .ancestors()
+        .find(|it| matches!(it.kind(), SyntaxKind::FN | SyntaxKind::CLOSURE_EXPR))
+        .and_then(|it| match ast::Fn::cast(it.clone()) {
+            Some(func) => func.ret_type(),
+            None => ast::ClosureExpr::cast(it)?.ret_type(),
+        })
+        .and_then(|it| result_error_ty(&it.ty()?));
+    let error_adt = error_ty.as_ref().and_then(|ty| match ty {
+        ast::Type::PathType(it) => match sema.resolve_path(&it.path()?)? {
+            PathResolution::Def(ModuleDef::Adt(adt)) => Some(adt),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    let placeholder = match snippet_cap {
+        Some(_) => format!("${{0:{}}}", make::expr_todo()),
+        None => make::expr_todo().to_string(),
+    };
+    let snippet = |file_id, edit| {
+        let mut source_change = SourceChange::from_text_edit(file_id, edit);
+        source_change.is_snippet = snippet_cap.is_some();
+        source_change
+    };
+    let mut fixes = Vec::new();
+
+    // A variant of the error enum wrapping the error, like `Error::Io(io::Error)`.
+    let variant = match (error_adt, &error_ty) {
+        (Some(hir::Adt::Enum(enum_)), Some(error_ty)) => enum_
+            .variants(sema.db)
+            .into_iter()
+            .find(|variant| match variant.fields(sema.db).as_slice() {
+                [field] => {
+                    variant.kind(sema.db) == hir::StructKind::Tuple
+                        && field.signature_ty(sema.db).display(sema.db).to_string()
+                            == from_ty.display(sema.db).to_string()
+                }
+                _ => false,
+            })
+            .map(|variant| format!("{}::{}", error_ty, variant.name(sema.db))),
+        _ => None,
+    };
+    let conversion = variant.unwrap_or_else(|| format!("|_| {}", make::expr_todo()));
+    let map_err = format!(".map_err({})", conversion);
+    let edit = TextEdit::insert(
+        operand.syntax().text_range().end(),
+        map_err.replace(&make::expr_todo().to_string(), &placeholder),
+    );
+    fixes.push(Fix::new(
+        &format!("Convert the error with `{}`", map_err),
+        snippet(file_id, edit),
+        range,
+    ));
+
+    if let Some(error_ty) = &error_ty {
+        let edit = TextEdit::replace(error_ty.syntax().text_range(), from_ty_text.clone());
+        fixes.push(Fix::new(
+            &format!("Change the error type to `{}`", from_ty_text),
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        ));
+    }
+
+    // `From` is implemented next to the error type, if it's defined in the crate.
+    let error_adt = error_adt.filter(|it| it.module(sema.db).krate() == module.krate());
+    if let Some(adt) = error_adt {
+        let source = match adt {
+            hir::Adt::Struct(it) => it.source(sema.db)?.map(ast::Adt::from),
+            hir::Adt::Enum(it) => it.source(sema.db)?.map(ast::Adt::from),
+            hir::Adt::Union(it) => it.source(sema.db)?.map(ast::Adt::from),
+        };
+        if source.file_id.call_node(sema.db).is_none()
+            && source.value.generic_param_list().is_none()
+        {
+            let from_ty = from_ty.display_source_code(sema.db, adt.module(sema.db).into()).ok()?;
+            let name = adt.name(sema.db);
+            let indent = IndentLevel::from_node(source.value.syntax());
+            let impl_text = format!(
+                "\n\n{0}impl From<{1}> for {2} {{\n{0}    fn from(err: {1}) -> Self {{\n{0}        {3}\n{0}    }}\n{0}}}",
+                indent, from_ty, name, placeholder
+            );
+            let edit = TextEdit::insert(source.value.syntax().text_range().end(), impl_text);
+            fixes.push(Fix::new(
+                &format!("Implement `From<{}>` for `{}`", from_ty, name),
+                snippet(source.file_id.original_file(sema.db), edit),
+                range,
+            ));
+        }
+    }
+
+    Some(fixes)
+}
+
+/// The error type of a `Result<T, E>` type.
+fn result_error_ty(ty: &ast::Type) -> Option<ast::Type> {
+    let path = match ty {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if segment.name_ref()?.text() != "Result" {
+        return None;
+    }
+    segment
+        .generic_arg_list()?
+        .generic_args()
+        .filter_map(|arg| match arg {
+            ast::GenericArg::TypeArg(it) => it.ty(),
+            _ => None,
+        })
+        .nth(1)
+}
+
+/// The function the expression is in, unless it's in a closure.
+fn enclosing_fn(expr: &ast::Expr) -> Option<ast::Fn> {
+    expr.syntax()
+        .ancestors()
+        .find(|it| matches!(it.kind(), SyntaxKind::FN | SyntaxKind::CLOSURE_EXPR))
+        .and_then(ast::Fn::cast)
+}
+
+impl DiagnosticWithFixes for TypeMismatch {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        // Don't try to edit the expansion of a macro call.
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let expr = self.expr.to_node(&root);
+        let expr_range = expr.syntax().text_range();
+        let file_id = self.file.original_file(sema.db);
+
+        let fixes = self
+            .conversions
+            .iter()
+            .map(|conversion| {
+                let (label, replacement) = match conversion {
+                    Conversion::WrapInSome => ("Wrap in `Some`", format!("Some({})", expr)),
+                    Conversion::WrapInOk => ("Wrap in `Ok`", format!("Ok({})", expr)),
+                    Conversion::Borrow(Mutability::Shared) => {
+                        ("Borrow with `&`", format!("&{}", prefix_operand(&expr)))
+                    }
+                    Conversion::Borrow(Mutability::Mut) => {
+                        ("Borrow with `&mut`", format!("&mut {}", prefix_operand(&expr)))
+                    }
+                    Conversion::Deref => {
+                        ("Dereference with `*`", format!("*{}", prefix_operand(&expr)))
+                    }
+                    Conversion::Into => {
+                        ("Convert with `.into()`", format!("{}.into()", receiver(&expr)))
+                    }
+                    Conversion::AsRef => {
+                        ("Convert with `.as_ref()`", format!("{}.as_ref()", receiver(&expr)))
+                    }
+                    Conversion::ToString => {
+                        ("Convert with `.to_string()`", format!("{}.to_string()", receiver(&expr)))
+                    }
+                };
+                let edit = TextEdit::replace(expr_range, replacement);
+                Fix::new(label, SourceChange::from_text_edit(file_id, edit), expr_range)
+            })
+            .collect::<Vec<_>>();
+        if fixes.is_empty() {
+            None
+        } else {
+            Some(fixes)
+        }
+    }
+}
+
+/// Renders `expr` so that a prefix operator applies to all of it.
+fn prefix_operand(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::BinExpr(_) | ast::Expr::CastExpr(_) | ast::Expr::RangeExpr(_) => {
+            format!("({})", expr)
+        }
+        _ => expr.to_string(),
+    }
+}
+
+/// Renders `expr` so that a method can be called on it.
+fn receiver(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::Literal(_)
+        | ast::Expr::PathExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::TupleExpr(_)
+        | ast::Expr::ArrayExpr(_)
+        | ast::Expr::MacroCall(_)
+        | ast::Expr::AwaitExpr(_)
+        | ast::Expr::TryExpr(_) => expr.to_string(),
+        _ => format!("({})", expr),
+    }
+}
+
+impl DiagnosticWithFixes for UnresolvedMethodCall {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let name_ref = self.name_ref.to_node(&root);
+        let file_id = self.file.original_file(sema.db);
+        let trigger_range = name_ref.syntax().text_range();
+
+        let mut fixes = replace_name_fixes(file_id, trigger_range, &self.similar);
+        let module = sema.scope(name_ref.syntax()).module()?;
+        let scope = ImportScope::find_insert_use_container(name_ref.syntax(), sema)?;
+        for &trait_ in &self.traits_to_import {
+            let trait_ = hir::Trait::from(trait_);
+            let path = match module.find_use_path(sema.db, ModuleDef::from(trait_)) {
+                Some(it) => it,
+                None => continue,
+            };
+            let rewriter = insert_use(&scope, mod_path_to_ast(&path), Some(MergeBehavior::Full));
+            let old_root = match rewriter.rewrite_root() {
+                Some(it) => it,
+                None => continue,
+            };
+            let mut builder = TextEdit::builder();
+            algo::diff(&old_root, &rewriter.rewrite(&old_root)).into_text_edit(&mut builder);
+            fixes.push(Fix::new(
+                &format!("Import `{}`", path),
+                SourceChange::from_text_edit(file_id, builder.finish()),
+                trigger_range,
+            ));
+        }
+        if fixes.is_empty() {
+            None
+        } else {
+            Some(fixes)
+        }
+    }
+}
+
+impl DiagnosticWithFixes for UnresolvedField {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let name_ref = self.name_ref.to_node(&root);
+        let range = name_ref.syntax().text_range();
+        let mut fixes = replace_name_fixes(self.file.original_file(sema.db), range, &self.similar);
+        fixes.extend(add_field_fix(sema, self.file.original_file(sema.db), range));
+        if fixes.is_empty() {
+            None
+        } else {
+            Some(fixes)
+        }
+    }
+}
+
+/// Offers to add the field `name_ref` accesses to the definition of the struct, with the type the
+/// access is expected to have.
+fn add_field_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    trigger_range: TextRange,
+) -> Option<Fix> {
+    let db = sema.db;
+    let name_ref: ast::NameRef =
+        algo::find_node_at_range(sema.parse(file_id).syntax(), trigger_range)?;
+    let field_expr = name_ref.syntax().parent().and_then(ast::FieldExpr::cast)?;
+    let name = name_ref.ident_token()?;
+    let mut receiver_ty = sema.type_of_expr(&field_expr.expr()?)?;
+    while let Some(ty) = receiver_ty.remove_ref() {
+        receiver_ty = ty;
+    }
+    let strukt = match receiver_ty.as_adt()? {
+        hir::Adt::Struct(it) => it,
+        _ => return None,
+    };
+    let module = sema.scope(name_ref.syntax()).module()?;
+    let target_module = strukt.module(db);
+    if target_module.krate() != module.krate() {
+        return None;
+    }
+    let src = strukt.source(db)?;
+    if src.file_id.call_node(db).is_some() {
+        return None;
+    }
+
+    let field_expr = ast::Expr::FieldExpr(field_expr);
+    let ty = match field_expr.syntax().parent().and_then(ast::BinExpr::cast) {
+        Some(assignment)
+            if assignment.op_kind() == Some(ast::BinOp::Assignment)
+                && assignment.lhs().as_ref() == Some(&field_expr) =>
+        {
+            sema.type_of_expr(&assignment.rhs()?)
+        }
+        _ => expected_type(sema, &field_expr),
+    };
+    let ty = ty
+        .filter(|ty| !ty.is_unknown())
+        .and_then(|ty| ty.display_source_code(db, target_module.into()).ok())
+        .unwrap_or_else(|| "()".to_string());
+    let vis = if module != target_module { "pub(crate) " } else { "" };
+    let field = format!("{}{}: {}", vis, name, ty);
+    let edit = match src.value.field_list() {
+        Some(ast::FieldList::RecordFieldList(it)) => {
+            let (offset, text) = append_to_braced_list(it.syntax(), &field)?;
+            TextEdit::insert(offset, text)
+        }
+        Some(ast::FieldList::TupleFieldList(_)) => return None,
+        None => {
+            let semicolon = src.value.semicolon_token()?;
+            TextEdit::replace(semicolon.text_range(), format!(" {{ {} }}", field))
+        }
+    };
+    let target_file = src.file_id.original_file(db);
+    Some(Fix::new(
+        &format!("Add field `{}` to `{}`", name, strukt.name(db)),
+        SourceChange::from_text_edit(target_file, edit),
+        trigger_range,
+    ))
+}
+
+/// Offers to replace the name at `range` with each of the `similar` names.
+fn replace_name_fixes(file_id: FileId, range: TextRange, similar: &[Name]) -> Vec<Fix> {
+    similar
+        .iter()
+        .map(|name| {
+            let edit = TextEdit::replace(range, name.to_string());
+            Fix::new(
+                &format!("Change to `{}`", name),
+                SourceChange::from_text_edit(file_id, edit),
+                range,
+            )
+        })
+        .collect()
+}
+
+impl DiagnosticWithFixes for RemoveThisSemicolon {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
         let root = sema.db.parse_or_expand(self.file)?;
 
         let semicolon = self
@@ -123,12 +856,12 @@ impl DiagnosticWithFix for RemoveThisSemicolon {
         let edit = TextEdit::delete(semicolon);
         let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
 
-        Some(Fix::new("Remove this semicolon", source_change, semicolon))
+        Some(vec![Fix::new("Remove this semicolon", source_change, semicolon)])
     }
 }
 
-impl DiagnosticWithFix for IncorrectCase {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+impl DiagnosticWithFixes for IncorrectCase {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
         let root = sema.db.parse_or_expand(self.file)?;
         let name_node = self.ident.to_node(&root);
 
@@ -140,12 +873,29 @@ impl DiagnosticWithFix for IncorrectCase {
             rename_with_semantics(sema, file_position, &self.suggested_text).ok()?;
 
         let label = format!("Rename to {}", self.suggested_text);
-        Some(Fix::new(&label, rename_changes, frange.range))
+        Some(vec![Fix::new(&label, rename_changes, frange.range)])
+    }
+}
+
+impl DiagnosticWithFixes for DuplicateDefinition {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        let root = sema.db.parse_or_expand(self.file)?;
+        let name_node = self.name.to_node(&root);
+
+        let name_node = InFile::new(self.file, name_node.syntax());
+        let frange = name_node.original_file_range(sema.db);
+        let file_position = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
+
+        let new_name = format!("{}2", self.text);
+        let rename_changes = rename_with_semantics(sema, file_position, &new_name).ok()?;
+
+        let label = format!("Rename to {}", new_name);
+        Some(vec![Fix::new(&label, rename_changes, frange.range)])
     }
 }
 
-impl DiagnosticWithFix for ReplaceFilterMapNextWithFindMap {
-    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+impl DiagnosticWithFixes for ReplaceFilterMapNextWithFindMap {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
         let root = sema.db.parse_or_expand(self.file)?;
         let next_expr = self.next_expr.to_node(&root);
         let next_call = ast::MethodCallExpr::cast(next_expr.syntax().clone())?;
@@ -163,14 +913,162 @@ impl DiagnosticWithFix for ReplaceFilterMapNextWithFindMap {
 
         let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
 
-        Some(Fix::new(
+        Some(vec![Fix::new(
             "Replace filter_map(..).next() with find_map()",
             source_change,
             trigger_range,
-        ))
+        )])
     }
 }
 
+impl DiagnosticWithFixes for UnreachableMatchArm {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let arm = self.arm.to_node(&root);
+        let arm_range = arm.syntax().text_range();
+
+        let start = match arm.syntax().prev_sibling_or_token() {
+            Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+            _ => arm_range.start(),
+        };
+        // The comma separating arms is part of the arm list, not of the arm.
+        let end = match algo::non_trivia_sibling(arm.syntax().clone().into(), Direction::Next) {
+            Some(comma) if comma.kind() == T![,] => comma.text_range().end(),
+            _ => arm_range.end(),
+        };
+        let edit = TextEdit::delete(TextRange::new(start, end));
+        let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
+        Some(vec![Fix::new("Remove unreachable arm", source_change, arm_range)])
+    }
+}
+
+impl DiagnosticWithFixes for UnnecessaryUnsafe {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        if self.file.call_node(sema.db).is_some() {
+            return None;
+        }
+        let root = sema.db.parse_or_expand(self.file)?;
+        let effect = match self.expr.to_node(&root) {
+            ast::Expr::EffectExpr(it) => it,
+            _ => return None,
+        };
+        let block = effect.block_expr()?;
+        let parent = effect.syntax().parent()?;
+        let mut range = effect.syntax().text_range();
+
+        let replacement = match (block.statements().next(), block.tail_expr()) {
+            (None, None)
+                if matches!(parent.kind(), SyntaxKind::EXPR_STMT | SyntaxKind::BLOCK_EXPR) =>
+            {
+                // Remove the whole statement, together with its indentation.
+                let stmt = if parent.kind() == SyntaxKind::EXPR_STMT {
+                    parent
+                } else {
+                    effect.syntax().clone()
+                };
+                range = match stmt.prev_sibling_or_token() {
+                    Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+                        TextRange::new(ws.text_range().start(), stmt.text_range().end())
+                    }
+                    _ => stmt.text_range(),
+                };
+                String::new()
+            }
+            (None, None) => "()".to_string(),
+            (None, Some(tail)) if can_inline_block(&parent) => tail.syntax().text().to_string(),
+            (Some(_), _)
+                if matches!(parent.kind(), SyntaxKind::EXPR_STMT | SyntaxKind::BLOCK_EXPR)
+                    && block.statements().all(|it| matches!(it, ast::Stmt::ExprStmt(_))) =>
+            {
+                // In statement position the contents can be moved into the surrounding block,
+                // unless that brings the locals and items of the block into its scope.
+                let block = block.dedent(IndentLevel(1));
+                let l_curly = block.l_curly_token()?.text_range().end();
+                let r_curly = block.r_curly_token()?.text_range().start();
+                let inner = TextRange::new(l_curly, r_curly) - block.syntax().text_range().start();
+                block.syntax().text().to_string()[inner].trim().to_string()
+            }
+            _ => block.syntax().text().to_string(),
+        };
+        let edit = TextEdit::replace(range, replacement);
+        let source_change = SourceChange::from_text_edit(self.file.original_file(sema.db), edit);
+        Some(vec![Fix::new("Remove unsafe block", source_change, effect.syntax().text_range())])
+    }
+}
+
+/// Whether a block in `parent` can be replaced by its tail expression without changing
+/// precedence.
+fn can_inline_block(parent: &SyntaxNode) -> bool {
+    matches!(
+        parent.kind(),
+        SyntaxKind::EXPR_STMT
+            | SyntaxKind::BLOCK_EXPR
+            | SyntaxKind::LET_STMT
+            | SyntaxKind::ARG_LIST
+            | SyntaxKind::PAREN_EXPR
+            | SyntaxKind::MATCH_ARM
+            | SyntaxKind::RETURN_EXPR
+    )
+}
+
+impl DiagnosticWithFixes for UnusedVariable {
+    fn fixes(&self, sema: &Semantics<RootDatabase>) -> Option<Vec<Fix>> {
+        let root = sema.db.parse_or_expand(self.file)?;
+        let name = self.ident.to_node(&root);
+        let ident_pat = ast::IdentPat::cast(name.syntax().parent()?)?;
+        let file_id = self.file.original_file(sema.db);
+        let trigger_range = name.syntax().text_range();
+
+        let mut builder = TextEdit::builder();
+        if ast::RecordPatField::for_field_name(&name).is_some() {
+            // `S { x }` has to become `S { x: _x }`.
+            builder.insert(ident_pat.syntax().text_range().start(), format!("{}: ", name));
+        }
+        builder.insert(trigger_range.start(), "_".to_string());
+        let mut fixes = vec![Fix::new(
+            &format!("Rename to `_{}`", name),
+            SourceChange::from_text_edit(file_id, builder.finish()),
+            trigger_range,
+        )];
+
+        if let Some(fix) = remove_unused_let_fix(&ident_pat, file_id, trigger_range) {
+            fixes.push(fix);
+        }
+        Some(fixes)
+    }
+}
+
+/// Removing a binding is only safe if evaluating its initializer has no side
+/// effects.
+fn remove_unused_let_fix(
+    ident_pat: &ast::IdentPat,
+    file_id: FileId,
+    trigger_range: TextRange,
+) -> Option<Fix> {
+    let let_stmt = ast::LetStmt::cast(ident_pat.syntax().parent()?)?;
+    match let_stmt.initializer() {
+        None
+        | Some(ast::Expr::Literal(_))
+        | Some(ast::Expr::PathExpr(_))
+        | Some(ast::Expr::ClosureExpr(_)) => (),
+        Some(_) => return None,
+    }
+    let stmt_range = let_stmt.syntax().text_range();
+    let start = match let_stmt.syntax().prev_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+        _ => stmt_range.start(),
+    };
+    let edit = TextEdit::delete(TextRange::new(start, stmt_range.end()));
+    Some(Fix::new(
+        "Remove unused variable",
+        SourceChange::from_text_edit(file_id, edit),
+        trigger_range,
+    ))
+}
+
 fn missing_record_expr_field_fix(
     sema: &Semantics<RootDatabase>,
     usage_file_id: FileId,
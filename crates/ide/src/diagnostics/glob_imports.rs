@@ -0,0 +1,435 @@
+//! Reports names brought into scope by glob imports which clash with other
+//! names:
+//!
+//! * a name imported by two glob imports of a module, which refer to different
+//!   items, is ambiguous. rustc accepts the imports and only rejects uses of the
+//!   name, without pointing at the imports involved;
+//! * a name imported by a glob import shadows the item of the same name from the
+//!   prelude, which is allowed but rarely intended.
+//!
+//! Items declared in the module and names imported explicitly take precedence
+//! over glob imports, so they never clash. Both clashes are only reported when
+//! the name is actually used in the module.
+
+use hir::{
+    diagnostics::DiagnosticCode, Adt, MacroDef, Module, ModuleDef, PathResolution, ScopeDef,
+    Semantics,
+};
+use ide_db::{base_db::FileId, RootDatabase};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    ast::{self, AstNode, ModuleItemOwner, NameOwner},
+    SyntaxNode,
+};
+
+use crate::{Diagnostic, DiagnosticsConfig, FileRange};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Def(ModuleDef),
+    Macro(MacroDef),
+}
+
+#[derive(PartialEq, Eq)]
+enum Namespace {
+    Types,
+    Values,
+    Macros,
+}
+
+impl Item {
+    fn from_scope_def(def: ScopeDef) -> Option<Item> {
+        match def {
+            ScopeDef::ModuleDef(it) => Some(Item::Def(it)),
+            ScopeDef::MacroDef(it) => Some(Item::Macro(it)),
+            _ => None,
+        }
+    }
+
+    fn from_resolution(resolution: PathResolution) -> Option<Item> {
+        match resolution {
+            PathResolution::Def(it) => Some(Item::Def(it)),
+            PathResolution::Macro(it) => Some(Item::Macro(it)),
+            _ => None,
+        }
+    }
+
+    fn namespace(self) -> Namespace {
+        match self {
+            Item::Def(ModuleDef::Function(_))
+            | Item::Def(ModuleDef::Const(_))
+            | Item::Def(ModuleDef::Static(_))
+            | Item::Def(ModuleDef::Variant(_)) => Namespace::Values,
+            Item::Def(_) => Namespace::Types,
+            Item::Macro(_) => Namespace::Macros,
+        }
+    }
+
+    fn display(self, db: &RootDatabase, name: &str) -> String {
+        match self {
+            Item::Def(it) => it.canonical_path(db).unwrap_or_else(|| name.to_string()),
+            Item::Macro(_) => name.to_string(),
+        }
+    }
+}
+
+struct Glob {
+    tree: ast::UseTree,
+    items: Vec<(String, Item)>,
+}
+
+pub(super) fn check(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    config: &DiagnosticsConfig,
+    file_id: FileId,
+) {
+    let file_module = match sema.to_module_def(file_id) {
+        Some(it) => it,
+        None => return,
+    };
+    let source_file = sema.parse(file_id);
+    let mut modules = vec![(file_module, source_file.items().collect::<Vec<_>>())];
+    for module in source_file.syntax().descendants().filter_map(ast::Module::cast) {
+        if let (Some(def), Some(items)) = (sema.to_def(&module), module.item_list()) {
+            modules.push((def, items.items().collect()));
+        }
+    }
+
+    // The paths each module starts with a name, which is where glob-imported names are used.
+    let mut uses = FxHashMap::<Module, Vec<ast::Path>>::default();
+    for path in source_file.syntax().descendants().filter_map(ast::Path::cast) {
+        if path.qualifier().is_some()
+            || path.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind()))
+        {
+            continue;
+        }
+        if let Some(module) = module_of(sema, file_module, path.syntax()) {
+            uses.entry(module).or_default().push(path);
+        }
+    }
+
+    for (module, items) in modules {
+        let uses = uses.get(&module).map_or(&[][..], |it| it.as_slice());
+        check_module(acc, sema, config, file_id, module, &items, uses);
+    }
+}
+
+fn check_module(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    config: &DiagnosticsConfig,
+    file_id: FileId,
+    module: Module,
+    items: &[ast::Item],
+    uses: &[ast::Path],
+) {
+    let db = sema.db;
+    let mut globs = Vec::new();
+    let mut explicit = module
+        .declarations(db)
+        .into_iter()
+        .filter_map(|it| it.name(db))
+        .map(|it| it.to_string())
+        .collect::<FxHashSet<_>>();
+    let trees = items
+        .iter()
+        .filter_map(|it| match it {
+            ast::Item::Use(it) => it.use_tree(),
+            _ => None,
+        })
+        .flat_map(|it| it.syntax().descendants().filter_map(ast::UseTree::cast).collect_vec())
+        .filter(|it| it.use_tree_list().is_none());
+    for tree in trees {
+        if tree.star_token().is_some() {
+            if let Some(items) = glob_items(sema, module, &tree) {
+                globs.push(Glob { tree, items });
+            }
+        } else if let Some(name) = imported_name(&tree) {
+            explicit.insert(name);
+        }
+    }
+    if globs.is_empty() {
+        return;
+    }
+
+    let used = |name: &str, item: Item| {
+        uses.iter().find(|path| {
+            path.segment().and_then(|it| it.name_ref()).map_or(false, |it| it.text() == name)
+                && sema.resolve_path(path).and_then(Item::from_resolution) == Some(item)
+        })
+    };
+
+    if !config.disabled.contains("ambiguous-glob-import") {
+        let mut candidates = FxHashMap::<&str, Vec<(&Glob, Item)>>::default();
+        for glob in &globs {
+            for (name, item) in &glob.items {
+                if !explicit.contains(name) {
+                    candidates.entry(name).or_default().push((glob, *item));
+                }
+            }
+        }
+        for path in uses {
+            let name_ref = match path.segment().and_then(|it| it.name_ref()) {
+                Some(it) => it,
+                None => continue,
+            };
+            let name = name_ref.text().to_string();
+            let candidates = match candidates.get(name.as_str()) {
+                Some(it) => it,
+                None => continue,
+            };
+            let resolved = match sema.resolve_path(path).and_then(Item::from_resolution) {
+                Some(it) if candidates.iter().any(|(_, item)| *item == it) => it,
+                _ => continue,
+            };
+            let clashing = candidates
+                .iter()
+                .filter(|(_, item)| item.namespace() == resolved.namespace())
+                .collect_vec();
+            let mut distinct = Vec::new();
+            for (_, item) in &clashing {
+                if !distinct.contains(item) {
+                    distinct.push(*item);
+                }
+            }
+            if distinct.len() < 2 {
+                continue;
+            }
+
+            let imports = clashing.iter().map(|(glob, _)| format!("`{}`", glob.tree)).join(", ");
+            let related = clashing
+                .iter()
+                .map(|(glob, item)| {
+                    let range = FileRange { file_id, range: glob.tree.syntax().text_range() };
+                    (range, format!("`{}` could refer to `{}`", name, item.display(db, &name)))
+                })
+                .collect();
+            acc.push(
+                Diagnostic::error(
+                    name_ref.syntax().text_range(),
+                    format!(
+                        "`{}` is ambiguous: it is imported by the glob imports {}",
+                        name, imports
+                    ),
+                )
+                .with_code(Some(DiagnosticCode("ambiguous-glob-import")))
+                .with_related_information(related),
+            );
+        }
+    }
+
+    if !config.disabled.contains("glob-shadows-prelude") {
+        let prelude = match module.krate().prelude(db) {
+            Some(it) if it != module => it,
+            _ => return,
+        };
+        let prelude_items = prelude
+            .scope(db, None)
+            .into_iter()
+            .filter_map(|(name, def)| Some((name.to_string(), Item::from_scope_def(def)?)))
+            .collect_vec();
+        for glob in &globs {
+            for (name, item) in &glob.items {
+                if explicit.contains(name) {
+                    continue;
+                }
+                let shadowed = prelude_items.iter().find(|(it, prelude_item)| {
+                    it == name
+                        && prelude_item != item
+                        && prelude_item.namespace() == item.namespace()
+                });
+                let shadowed = match shadowed {
+                    Some((_, it)) => it,
+                    None => continue,
+                };
+                let use_site = match used(name, *item) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let related = vec![(
+                    FileRange { file_id, range: use_site.syntax().text_range() },
+                    format!("`{}` refers to `{}` here", name, item.display(db, name)),
+                )];
+                acc.push(
+                    Diagnostic::hint(
+                        glob.tree.syntax().text_range(),
+                        format!(
+                            "`{}` imported by this glob shadows `{}` from the prelude",
+                            name,
+                            shadowed.display(db, name)
+                        ),
+                    )
+                    .with_code(Some(DiagnosticCode("glob-shadows-prelude")))
+                    .with_related_information(related),
+                );
+            }
+        }
+    }
+}
+
+/// The items a glob import brings into `module`.
+fn glob_items(
+    sema: &Semantics<RootDatabase>,
+    module: Module,
+    tree: &ast::UseTree,
+) -> Option<Vec<(String, Item)>> {
+    let db = sema.db;
+    let path = match tree.path() {
+        Some(it) => it,
+        None => {
+            tree.syntax().ancestors().find_map(ast::UseTreeList::cast)?.parent_use_tree().path()?
+        }
+    };
+    let res = match sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::Module(it)) => it
+            .scope(db, Some(module))
+            .into_iter()
+            .filter_map(|(name, def)| Some((name.to_string(), Item::from_scope_def(def)?)))
+            .collect(),
+        PathResolution::Def(ModuleDef::Adt(Adt::Enum(it))) => it
+            .variants(db)
+            .into_iter()
+            .map(|it| (it.name(db).to_string(), Item::Def(ModuleDef::Variant(it))))
+            .collect(),
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// The name a non-glob `use` tree brings into scope, `None` for `use Trait as _`.
+fn imported_name(tree: &ast::UseTree) -> Option<String> {
+    match tree.rename() {
+        Some(rename) => rename.name().map(|it| it.text().to_string()),
+        None => {
+            let segment = tree.path()?.segment()?;
+            match segment.kind()? {
+                ast::PathSegmentKind::Name(it) => Some(it.text().to_string()),
+                // `use foo::{self}` imports `foo`.
+                ast::PathSegmentKind::SelfKw => {
+                    let parent = tree.syntax().ancestors().find_map(ast::UseTreeList::cast)?;
+                    let segment = parent.parent_use_tree().path()?.segment()?;
+                    segment.name_ref().map(|it| it.text().to_string())
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+fn module_of(
+    sema: &Semantics<RootDatabase>,
+    file_module: Module,
+    node: &SyntaxNode,
+) -> Option<Module> {
+    match node.ancestors().skip(1).find_map(ast::Module::cast) {
+        Some(module) => sema.to_def(&module),
+        None => Some(file_module),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let actual = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| {
+                d.code.map_or(false, |it| {
+                    it.as_str() == "ambiguous-glob-import" || it.as_str() == "glob-shadows-prelude"
+                })
+            })
+            .map(|d| {
+                let related = d
+                    .related_information
+                    .iter()
+                    .map(|(range, message)| format!("    {:?} {}\n", range.range, message))
+                    .collect::<String>();
+                format!("{:?} {}\n{}", d.range, d.message, related)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn reports_uses_of_ambiguous_names() {
+        check(
+            r#"
+mod a { pub struct Foo; pub fn f() {} }
+mod b { pub struct Foo; pub fn f() {} }
+use a::*;
+use b::*;
+fn main() {
+    let _: Foo;
+}
+"#,
+            expect![[r#"
+                123..126 `Foo` is ambiguous: it is imported by the glob imports `a::*`, `b::*`
+                    84..88 `Foo` could refer to `a::Foo`
+                    94..98 `Foo` could refer to `b::Foo`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn explicit_names_take_precedence_over_globs() {
+        check(
+            r#"
+mod a { pub struct Foo; pub struct Bar; }
+mod b { pub struct Foo; pub struct Bar; }
+use a::*;
+use b::{*, Foo};
+struct Bar;
+fn main() {
+    let _: (Foo, Bar);
+}
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn same_item_through_two_globs_is_not_ambiguous() {
+        check(
+            r#"
+mod a { pub struct Foo; }
+mod b { pub use crate::a::Foo; }
+use a::*;
+use b::*;
+fn main() {
+    let _: Foo;
+}
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn reports_globs_shadowing_the_prelude() {
+        check(
+            r#"
+//- /main.rs crate:main deps:std
+mod io { pub enum Result { Ok } pub struct Error; }
+use io::*;
+fn f() -> Result {
+    Result::Ok
+}
+//- /std/lib.rs crate:std
+#[prelude_import]
+use prelude::v1::*;
+pub mod prelude { pub mod v1 { pub enum Result { Ok, Err } pub struct Error; } }
+"#,
+            expect![[r#"
+                56..61 `Result` imported by this glob shadows `prelude::v1::Result` from the prelude
+                    73..79 `Result` refers to `io::Result` here
+            "#]],
+        );
+    }
+}
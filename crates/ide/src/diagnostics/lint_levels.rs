@@ -0,0 +1,166 @@
+//! Applies lint level attributes like `#[allow(unused_variables)]` to the
+//! diagnostics which correspond to a rustc or clippy lint.
+//!
+//! Like rustc, the attributes of the enclosing items, statements and modules,
+//! up to the crate root, are considered, and the innermost one mentioning the
+//! lint or one of its groups wins, unless an outer one forbids the lint.
+
+use hir::{LintLevel, Semantics};
+use ide_db::{base_db::FileId, RootDatabase};
+use syntax::{ast, AstNode, NodeOrToken, SyntaxNode};
+
+use crate::{Diagnostic, Severity};
+
+pub(super) fn apply(sema: &Semantics<RootDatabase>, file_id: FileId, acc: &mut Vec<Diagnostic>) {
+    if acc.iter().all(|it| it.lint.is_none()) {
+        return;
+    }
+    let root = sema.parse(file_id);
+    let module_attrs = enclosing_module_attrs(sema, file_id);
+
+    let diagnostics = std::mem::take(acc);
+    for mut diagnostic in diagnostics {
+        let lint = match diagnostic.lint {
+            Some(it) => it,
+            None => {
+                acc.push(diagnostic);
+                continue;
+            }
+        };
+        let node = match root.syntax().covering_element(diagnostic.range) {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent(),
+        };
+        let attrs = attrs_of_ancestors(&node).chain(module_attrs.iter().cloned());
+        match lint_level(attrs, lint) {
+            Some(LintLevel::Allow) => continue,
+            Some(LintLevel::Deny) | Some(LintLevel::Forbid) => {
+                diagnostic.severity = Severity::Error
+            }
+            Some(LintLevel::Warn) | None => {}
+        }
+        acc.push(diagnostic);
+    }
+}
+
+/// Returns the attributes applying to the module of `file_id` which are outside of the file,
+/// from the innermost to the outermost.
+fn enclosing_module_attrs(sema: &Semantics<RootDatabase>, file_id: FileId) -> Vec<ast::Attr> {
+    let mut res = Vec::new();
+    let mut file_id = file_id;
+    let mut module = sema.to_module_def(file_id);
+    while let Some(decl) = module.and_then(|it| it.declaration_source(sema.db)) {
+        res.extend(attrs_of_ancestors(decl.value.syntax()));
+        let parent_file = decl.file_id.original_file(sema.db);
+        if parent_file == file_id {
+            break;
+        }
+        file_id = parent_file;
+        module = sema.to_module_def(file_id);
+    }
+    res
+}
+
+/// Both the outer attributes of the ancestors and the inner attributes of the blocks and item
+/// lists containing the node are direct children of an ancestor.
+fn attrs_of_ancestors(node: &SyntaxNode) -> impl Iterator<Item = ast::Attr> {
+    node.ancestors().flat_map(|it| it.children().filter_map(ast::Attr::cast).collect::<Vec<_>>())
+}
+
+fn lint_level(attrs: impl Iterator<Item = ast::Attr>, lint: &str) -> Option<LintLevel> {
+    let mut levels = attrs.filter_map(|attr| attr_level(&attr, lint));
+    let innermost = levels.next()?;
+    if innermost != LintLevel::Forbid && levels.any(|it| it == LintLevel::Forbid) {
+        return Some(LintLevel::Forbid);
+    }
+    Some(innermost)
+}
+
+/// Returns the level the attribute sets for the lint, if it is a lint level attribute mentioning
+/// the lint or a group containing it.
+fn attr_level(attr: &ast::Attr, lint: &str) -> Option<LintLevel> {
+    let (name, tt) = attr.as_simple_call()?;
+    LintLevel::of_attr(&name, &tt.syntax().text().to_string(), lint)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn check_severities(ra_fixture: &str, expected: &[&str]) {
+        let (analysis, files) = fixture::files(ra_fixture);
+        let actual = files
+            .into_iter()
+            .flat_map(|file_id| {
+                analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap()
+            })
+            .map(|it| format!("{:?}: {}", it.severity, it.message))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn allow_on_items_and_statements() {
+        check_severities(
+            r#"
+#[allow(unused_variables)]
+fn f() { let x = 92; }
+fn g() {
+    #[allow(unused)]
+    let y = 92;
+}
+mod m {
+    #![allow(non_snake_case)]
+    fn Foo() {}
+}
+"#,
+            &[],
+        );
+    }
+
+    #[test]
+    fn allow_on_modules_and_crate() {
+        check_severities(
+            r#"
+//- /main.rs
+#![allow(non_camel_case_types)]
+#[allow(warnings)]
+mod a;
+mod b;
+struct foo;
+//- /a.rs
+fn f() { let x = 92; }
+//- /b.rs
+struct bar;
+"#,
+            &[],
+        );
+    }
+
+    #[test]
+    fn deny_raises_severity() {
+        check_severities(
+            r#"
+#![deny(unused)]
+pub fn f() { let x = 92; }
+#[warn(unused_variables)]
+pub fn g() { let y = 92; }
+"#,
+            &["Error: unused variable: `x`", "WeakWarning: unused variable: `y`"],
+        );
+    }
+
+    #[test]
+    fn forbid_overrides_inner_allow() {
+        check_severities(
+            r#"
+#![forbid(unused)]
+pub fn f() {
+    #[allow(unused_variables)]
+    let x = 92;
+}
+"#,
+            &["Error: unused variable: `x`"],
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! Reports lifetimes which no enclosing item or `for<..>` binder declares, which
+//! commonly happens after pasting code, with a fix declaring the lifetime on the
+//! closest item.
+//!
+//! When the item is a struct, enum or union, the fix also threads the new
+//! lifetime through the paths referring to the type: the type itself uses the
+//! declared name, functions and impls the anonymous `'_`. Other type
+//! definitions, consts and statics can't use `'_`, so the fix isn't offered
+//! when they refer to the type.
+
+use hir::{diagnostics::DiagnosticCode, Adt, Semantics};
+use ide_db::{base_db::FileId, defs::Definition, source_change::SourceChange, RootDatabase};
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast::{self, AstNode, GenericParamsOwner, NameOwner},
+    match_ast, SyntaxNode, TextSize,
+};
+use text_edit::TextEditBuilder;
+
+use crate::{Diagnostic, Fix};
+
+pub(super) fn check(acc: &mut Vec<Diagnostic>, sema: &Semantics<RootDatabase>, file_id: FileId) {
+    let source_file = sema.parse(file_id);
+    for lifetime in source_file.syntax().descendants().filter_map(ast::Lifetime::cast) {
+        if !is_lifetime_use(&lifetime)
+            || is_bound_by_binder(&lifetime)
+            || sema.resolve_lifetime_param(&lifetime).is_some()
+        {
+            continue;
+        }
+        let name = lifetime.text().to_string();
+        let range = lifetime.syntax().text_range();
+        let fix = declare_lifetime(sema, file_id, &lifetime, &name);
+        acc.push(
+            Diagnostic::error(range, format!("use of undeclared lifetime name `{}`", name))
+                .with_fix(fix)
+                .with_code(Some(DiagnosticCode("undeclared-lifetime"))),
+        );
+    }
+}
+
+/// Whether the lifetime refers to a lifetime parameter, rather than declaring one or being a
+/// label.
+fn is_lifetime_use(lifetime: &ast::Lifetime) -> bool {
+    if matches!(lifetime.text(), "'static" | "'_") {
+        return false;
+    }
+    match lifetime.syntax().parent() {
+        Some(parent) => {
+            !(ast::LifetimeParam::can_cast(parent.kind())
+                || ast::Label::can_cast(parent.kind())
+                || ast::BreakExpr::can_cast(parent.kind())
+                || ast::ContinueExpr::can_cast(parent.kind()))
+        }
+        None => false,
+    }
+}
+
+/// Whether a `for<..>` binder declares the lifetime. These aren't generic parameters of an item,
+/// so they aren't resolved to one.
+fn is_bound_by_binder(lifetime: &ast::Lifetime) -> bool {
+    let text = lifetime.text();
+    lifetime.syntax().ancestors().any(|node| {
+        let generic_param_list = match_ast! {
+            match node {
+                ast::ForType(it) => it.generic_param_list(),
+                ast::WherePred(it) => it.generic_param_list(),
+                _ => None,
+            }
+        };
+        generic_param_list.map_or(false, |list| {
+            list.lifetime_params().any(|it| it.lifetime().map_or(false, |it| it.text() == text))
+        })
+    })
+}
+
+fn declare_lifetime(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    lifetime: &ast::Lifetime,
+    name: &str,
+) -> Option<Fix> {
+    let owner = lifetime.syntax().ancestors().find(is_generic_owner)?;
+    let mut builders: FxHashMap<FileId, TextEditBuilder> = FxHashMap::default();
+    let (offset, text) = declaration_insertion(&owner, name)?;
+    builders.entry(file_id).or_default().insert(offset, text);
+
+    let adt = match_ast! {
+        match owner {
+            ast::Struct(it) => sema.to_def(&it).map(Adt::from),
+            ast::Enum(it) => sema.to_def(&it).map(Adt::from),
+            ast::Union(it) => sema.to_def(&it).map(Adt::from),
+            _ => None,
+        }
+    };
+    if let Some(adt) = adt {
+        let usages = Definition::ModuleDef(adt.into()).usages(sema).all();
+        for (usage_file_id, references) in usages.references {
+            for reference in references {
+                let name_ref = match reference.name {
+                    ast::NameLike::NameRef(it) => it,
+                    _ => continue,
+                };
+                let in_owner =
+                    usage_file_id == file_id && owner.text_range().contains_range(reference.range);
+                let arg = if in_owner { name } else { "'_" };
+                if let Some((offset, text)) = lifetime_arg_insertion(&name_ref, arg) {
+                    if !in_owner && !allows_anonymous_lifetime(&name_ref) {
+                        return None;
+                    }
+                    builders.entry(usage_file_id).or_default().insert(offset, text);
+                }
+            }
+        }
+    }
+
+    let edits = builders
+        .into_iter()
+        .map(|(file_id, builder)| (file_id, builder.finish()))
+        .collect::<FxHashMap<_, _>>();
+    let label = format!("Declare lifetime `{}`", name);
+    Some(Fix::new(&label, SourceChange::from(edits), lifetime.syntax().text_range()))
+}
+
+fn is_generic_owner(node: &SyntaxNode) -> bool {
+    ast::Fn::can_cast(node.kind())
+        || ast::Struct::can_cast(node.kind())
+        || ast::Enum::can_cast(node.kind())
+        || ast::Union::can_cast(node.kind())
+        || ast::Trait::can_cast(node.kind())
+        || ast::Impl::can_cast(node.kind())
+        || ast::TypeAlias::can_cast(node.kind())
+}
+
+/// Where and what to insert to declare `name` on `owner`. Lifetime parameters go before any
+/// other generic parameter.
+fn declaration_insertion(owner: &SyntaxNode, name: &str) -> Option<(TextSize, String)> {
+    let (generic_param_list, new_list_offset) = match_ast! {
+        match owner {
+            ast::Impl(it) => (it.generic_param_list(), it.impl_token()?.text_range().end()),
+            ast::Fn(it) => (it.generic_param_list(), name_end(&it)?),
+            ast::Struct(it) => (it.generic_param_list(), name_end(&it)?),
+            ast::Enum(it) => (it.generic_param_list(), name_end(&it)?),
+            ast::Union(it) => (it.generic_param_list(), name_end(&it)?),
+            ast::Trait(it) => (it.generic_param_list(), name_end(&it)?),
+            ast::TypeAlias(it) => (it.generic_param_list(), name_end(&it)?),
+            _ => return None,
+        }
+    };
+    let res = match generic_param_list {
+        Some(list) => {
+            let offset = list.l_angle_token()?.text_range().end();
+            match list.generic_params().next() {
+                Some(_) => (offset, format!("{}, ", name)),
+                None => (offset, name.to_string()),
+            }
+        }
+        None => (new_list_offset, format!("<{}>", name)),
+    };
+    Some(res)
+}
+
+fn name_end(owner: &impl NameOwner) -> Option<TextSize> {
+    Some(owner.name()?.syntax().text_range().end())
+}
+
+/// Where and what to insert to pass `lifetime` to the type `name_ref` refers to, if it is used
+/// as a type which doesn't pass a lifetime already.
+fn lifetime_arg_insertion(name_ref: &ast::NameRef, lifetime: &str) -> Option<(TextSize, String)> {
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+    let path = segment.parent_path();
+    if path.segment()? != segment {
+        return None;
+    }
+    ast::PathType::cast(path.syntax().parent()?)?;
+    let res = match segment.generic_arg_list() {
+        Some(list) => {
+            if list.generic_args().any(|it| matches!(it, ast::GenericArg::LifetimeArg(_))) {
+                return None;
+            }
+            let offset = list.l_angle_token()?.text_range().end();
+            match list.generic_args().next() {
+                Some(_) => (offset, format!("{}, ", lifetime)),
+                None => (offset, lifetime.to_string()),
+            }
+        }
+        None => (name_ref.syntax().text_range().end(), format!("<{}>", lifetime)),
+    };
+    Some(res)
+}
+
+/// Whether `'_` may stand for a lifetime at `name_ref`, which is the case in functions and impl
+/// headers but not in type definitions, consts and statics.
+fn allows_anonymous_lifetime(name_ref: &ast::NameRef) -> bool {
+    name_ref
+        .syntax()
+        .ancestors()
+        .find_map(ast::Item::cast)
+        .map_or(false, |item| matches!(item, ast::Item::Fn(_) | ast::Item::Impl(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::{check_fix, check_no_diagnostics};
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn check(ra_fixture: &str, expected: &[&str]) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let messages = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|it| it.code.as_ref().map(|it| it.as_str()) == Some("undeclared-lifetime"))
+            .map(|it| it.message)
+            .collect::<Vec<_>>();
+        let expected = expected
+            .iter()
+            .map(|it| format!("use of undeclared lifetime name `{}`", it))
+            .collect::<Vec<_>>();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn undeclared_lifetimes() {
+        check(
+            r#"
+struct S { s: &'a str }
+impl T for S<'b> {}
+fn f(x: &'c u8) -> &'static u8 { loop { 'l: loop { break 'l; } } }
+const C: &'d str = "";
+"#,
+            &["'a", "'b", "'c", "'d"],
+        );
+    }
+
+    #[test]
+    fn declared_lifetimes() {
+        check_no_diagnostics(
+            r#"
+struct S<'a> { s: &'a str, f: for<'b> fn(&'b u8) }
+impl<'a> S<'a> {
+    fn get(&self) -> &'a str { let _: &'a str = self.s; self.s }
+}
+fn f<'a, F>(x: &'a u8, _: &'_ u8, _: F) -> &'static u8
+where
+    F: for<'b> Fn(&'b u8),
+    for<'c> &'c F: Copy,
+{
+    'outer: loop { continue 'outer; }
+}
+trait Tr<'a> { type Item<'b>; fn f(&'a self); }
+"#,
+        );
+    }
+
+    #[test]
+    fn declare_on_struct() {
+        check_fix(
+            r#"
+struct Wrapper<T> { inner: T, name: &'a$0 str }
+"#,
+            r#"
+struct Wrapper<'a, T> { inner: T, name: &'a str }
+"#,
+        );
+    }
+
+    #[test]
+    fn declare_on_impl() {
+        check_fix(
+            r#"
+struct S<'a>(&'a str);
+impl S<'a$0> {}
+"#,
+            r#"
+struct S<'a>(&'a str);
+impl<'a> S<'a> {}
+"#,
+        );
+    }
+
+    #[test]
+    fn declare_on_fn_with_empty_generics() {
+        check_fix(
+            r#"
+fn f<>(x: &'a$0 u8) {}
+"#,
+            r#"
+fn f<'a>(x: &'a u8) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn threads_lifetime_through_uses() {
+        check_fix(
+            r#"
+struct Node { name: &'a$0 str, next: Option<Box<Node>> }
+impl Node {
+    fn new(name: &str) -> Node { loop {} }
+}
+fn first(nodes: &[Node], _: Node<>) -> Option<&Node> { nodes.first() }
+"#,
+            r#"
+struct Node<'a> { name: &'a str, next: Option<Box<Node<'a>>> }
+impl Node<'_> {
+    fn new(name: &str) -> Node<'_> { loop {} }
+}
+fn first(nodes: &[Node<'_>], _: Node<'_>) -> Option<&Node<'_>> { nodes.first() }
+"#,
+        );
+    }
+
+    #[test]
+    fn no_fix_when_used_in_type_definitions() {
+        let (analysis, position) = fixture::position(
+            r#"
+struct Node { name: &'a$0 str }
+enum E { A(Node) }
+"#,
+        );
+        let diagnostics =
+            analysis.diagnostics(&DiagnosticsConfig::default(), position.file_id).unwrap();
+        let diagnostic = diagnostics
+            .iter()
+            .find(|it| it.code.as_ref().map(|it| it.as_str()) == Some("undeclared-lifetime"))
+            .unwrap();
+        assert!(diagnostic.fixes.is_none());
+    }
+}
@@ -0,0 +1,139 @@
+//! Reports matches of the structural search replace rules of the project, like
+//! `old_util($x) ==>> new_util($x)`, with a fix applying the replacement.
+//!
+//! This makes it possible to write lightweight, project-specific lints (e.g.
+//! for deprecated internal APIs) without writing a clippy lint.
+
+use hir::diagnostics::DiagnosticCode;
+use ide_db::{
+    base_db::{FileId, FilePosition, FileRange, SourceDatabaseExt},
+    source_change::SourceChange,
+    RootDatabase,
+};
+use ide_ssr::{MatchFinder, SsrRule};
+use syntax::{TextRange, TextSize};
+
+use crate::{Diagnostic, Fix};
+
+pub(super) fn check(
+    acc: &mut Vec<Diagnostic>,
+    db: &RootDatabase,
+    rules: &[(String, SsrRule)],
+    file_id: FileId,
+) {
+    if rules.is_empty() {
+        return;
+    }
+    let file_range =
+        FileRange { file_id, range: TextRange::up_to(TextSize::of(&*db.file_text(file_id))) };
+    for (rule_text, rule) in rules {
+        let mut match_finder = MatchFinder::in_context(
+            db,
+            FilePosition { file_id, offset: 0.into() },
+            vec![file_range],
+        );
+        // Rules whose paths don't resolve from this file are simply not applicable here.
+        if match_finder.add_rule(rule.clone()).is_err() {
+            continue;
+        }
+        for (range, edit) in match_finder.match_edits() {
+            if range.file_id != file_id {
+                continue;
+            }
+            acc.push(
+                Diagnostic::hint(range.range, format!("Matches SSR rule `{}`", rule_text))
+                    .with_fix(Some(Fix::new(
+                        "Apply SSR rule",
+                        SourceChange::from_text_edit(file_id, edit),
+                        range.range,
+                    )))
+                    .with_code(Some(DiagnosticCode("ssr-rule"))),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn config(rule: &str) -> DiagnosticsConfig {
+        let ssr_rules = vec![(rule.to_string(), rule.parse().unwrap())];
+        DiagnosticsConfig { ssr_rules, ..DiagnosticsConfig::default() }
+    }
+
+    fn check_messages(rule: &str, ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let actual = analysis
+            .diagnostics(&config(rule), file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.code.map_or(false, |it| it.as_str() == "ssr-rule"))
+            .map(|d| format!("{:?} {}\n", d.range, d.message))
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    fn check_fix(rule: &str, ra_fixture_before: &str, ra_fixture_after: &str) {
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let diagnostic = analysis
+            .diagnostics(&config(rule), position.file_id)
+            .unwrap()
+            .into_iter()
+            .find(|d| d.range.contains_inclusive(position.offset))
+            .unwrap();
+        let fix = diagnostic.fixes.unwrap().pop().unwrap();
+        let mut actual = analysis.file_text(position.file_id).unwrap().to_string();
+        for edit in fix.source_change.source_file_edits.values() {
+            edit.apply(&mut actual);
+        }
+        assert_eq_text!(&trim_indent(ra_fixture_after), &actual);
+    }
+
+    #[test]
+    fn reports_rule_matches() {
+        check_messages(
+            "crate::old_util($x) ==>> crate::new_util($x)",
+            r#"
+fn old_util(x: i32) -> i32 { x }
+fn new_util(x: i32) -> i32 { x }
+mod inner {
+    fn f() -> i32 { super::old_util(1) + crate::new_util(2) }
+}
+"#,
+            expect![[r#"
+                98..116 Matches SSR rule `crate::old_util($x) ==>> crate::new_util($x)`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unresolved_rules_are_ignored() {
+        check_messages("missing($x) ==>> other($x)", "fn old_util(x: i32) {}", expect![[""]]);
+    }
+
+    #[test]
+    fn applies_rule() {
+        check_fix(
+            "crate::old_util($x) ==>> crate::new_util($x)",
+            r#"
+fn old_util(x: i32) -> i32 { x }
+fn new_util(x: i32) -> i32 { x }
+mod inner {
+    fn f() -> i32 { super::old_util$0(1 + 2) }
+}
+"#,
+            r#"
+fn old_util(x: i32) -> i32 { x }
+fn new_util(x: i32) -> i32 { x }
+mod inner {
+    fn f() -> i32 { crate::new_util(1 + 2) }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! Reports `todo!()` and `_` expressions as typed holes: the diagnostic names the
+//! type the expression needs to have, and lists the locals, constants, statics
+//! and functions in scope which have that type, with a fix inserting each of
+//! them.
+//!
+//! The expected type comes from the context of the hole, like the type of the
+//! `let` binding, the parameter or the field it initializes, or the return type
+//! of the function. Holes of the unit type aren't reported, as nearly anything
+//! could fill them, and neither is `_` as the target of an assignment.
+
+use hir::{diagnostics::DiagnosticCode, HirDisplay, ModuleDef, ScopeDef, Semantics, Type};
+use ide_db::{base_db::FileId, helpers, source_change::SourceChange, RootDatabase};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, make, AstNode},
+    SyntaxKind, T,
+};
+use text_edit::TextEdit;
+
+use crate::{Diagnostic, Fix};
+
+pub(super) fn check(acc: &mut Vec<Diagnostic>, sema: &Semantics<RootDatabase>, file_id: FileId) {
+    let source_file = sema.parse(file_id);
+    for hole in source_file.syntax().descendants().filter_map(ast::Expr::cast) {
+        let is_hole = match &hole {
+            ast::Expr::MacroCall(call) => is_todo(sema, call),
+            ast::Expr::UnderscoreExpr(_) => !is_assignee(&hole),
+            _ => false,
+        };
+        if is_hole {
+            check_hole(acc, sema, file_id, hole);
+        }
+    }
+}
+
+fn check_hole(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    hole: ast::Expr,
+) -> Option<()> {
+    let db = sema.db;
+    let ty = expected_type(sema, &hole)?;
+
+    // Calling the function the hole is in would recurse endlessly.
+    let enclosing_fn = hole.syntax().ancestors().find_map(ast::Fn::cast);
+    let enclosing_fn = enclosing_fn.and_then(|it| sema.to_def(&it));
+    let mut fillers = Vec::new();
+    sema.scope(hole.syntax()).process_all_names(&mut |name, def| {
+        let filler = match def {
+            ScopeDef::Local(it) if it.ty(db).is_same_type(&ty) => (0, name.to_string()),
+            ScopeDef::ModuleDef(ModuleDef::Const(it)) if it.ty(db).is_same_type(&ty) => {
+                (1, name.to_string())
+            }
+            ScopeDef::ModuleDef(ModuleDef::Static(it)) if it.ty(db).is_same_type(&ty) => {
+                (1, name.to_string())
+            }
+            ScopeDef::ModuleDef(ModuleDef::Function(it))
+                if Some(it) != enclosing_fn
+                    && it.self_param(db).is_none()
+                    && it.ret_type(db).is_same_type(&ty) =>
+            {
+                let args = it.assoc_fn_params(db).iter().map(|_| make::expr_todo()).join(", ");
+                (2, format!("{}({})", name, args))
+            }
+            _ => return,
+        };
+        fillers.push(filler);
+    });
+    fillers.sort();
+    fillers.dedup();
+
+    let range = hole.syntax().text_range();
+    let mut message = format!("typed hole of type `{}`", ty.display(db));
+    if !fillers.is_empty() {
+        let names = fillers.iter().map(|(_, it)| format!("`{}`", it)).join(", ");
+        message = format!("{}, could be filled with {}", message, names);
+    }
+    let fixes = fillers
+        .into_iter()
+        .map(|(_, filler)| {
+            Fix::new(
+                &format!("Fill with `{}`", filler),
+                SourceChange::from_text_edit(file_id, TextEdit::replace(range, filler)),
+                range,
+            )
+        })
+        .collect::<Vec<_>>();
+    acc.push(
+        Diagnostic::hint(range, message)
+            .with_fixes(Some(fixes).filter(|it| !it.is_empty()))
+            .with_code(Some(DiagnosticCode("typed-hole"))),
+    );
+    Some(())
+}
+
+/// Whether `call` is `todo!()` of `core` or `std`, without a message.
+fn is_todo(sema: &Semantics<RootDatabase>, call: &ast::MacroCall) -> bool {
+    let db = sema.db;
+    let is_empty = call.token_tree().map_or(false, |tt| {
+        tt.syntax()
+            .children_with_tokens()
+            .all(|it| matches!(it.kind(), T!['('] | T![')'] | SyntaxKind::WHITESPACE))
+    });
+    if !is_empty {
+        return false;
+    }
+    let mac = match sema.resolve_macro_call(call) {
+        Some(it) => it,
+        None => return false,
+    };
+    let krate = mac.module(db).and_then(|it| it.krate().display_name(db));
+    mac.name(db).map_or(false, |it| it.to_string() == "todo")
+        && krate.map_or(false, |it| matches!(&*it.to_string(), "core" | "std"))
+}
+
+/// Whether `expr` is assigned to, like the `_` in `(a, _) = pair`.
+fn is_assignee(expr: &ast::Expr) -> bool {
+    let mut node = expr.syntax().clone();
+    while let Some(parent) = node.parent() {
+        match parent.kind() {
+            SyntaxKind::TUPLE_EXPR
+            | SyntaxKind::PAREN_EXPR
+            | SyntaxKind::ARRAY_EXPR
+            | SyntaxKind::CALL_EXPR
+            | SyntaxKind::ARG_LIST
+            | SyntaxKind::RECORD_EXPR
+            | SyntaxKind::RECORD_EXPR_FIELD_LIST
+            | SyntaxKind::RECORD_EXPR_FIELD => node = parent,
+            _ => {
+                return ast::BinExpr::cast(parent).map_or(false, |it| {
+                    it.op_kind() == Some(ast::BinOp::Assignment)
+                        && it.lhs().map_or(false, |lhs| lhs.syntax() == &node)
+                })
+            }
+        }
+    }
+    false
+}
+
+/// The type `expr` has to have in its context, if it's a useful one.
+fn expected_type(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> Option<Type> {
+    let ty = helpers::expected_type(sema, expr)?;
+    if ty.is_unknown() || ty.is_never() || ty.is_unit() {
+        return None;
+    }
+    Some(ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let actual = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.code.map_or(false, |it| it.as_str() == "typed-hole"))
+            .map(|d| {
+                let fixes = d.fixes.unwrap_or_default();
+                let fixes = fixes.iter().map(|it| it.label.to_string()).collect::<Vec<_>>();
+                format!("{:?} {} {:?}\n", d.range, d.message, fixes)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    /// A `std` crate providing `todo!`, to be appended to fixtures depending on it.
+    const STD: &str = r#"
+//- /std.rs crate:std
+#[prelude_import]
+use self::prelude::*;
+
+mod prelude {
+    #[macro_export]
+    macro_rules! todo {
+        () => { loop {} };
+        ($($arg:tt)+) => { loop {} };
+    }
+}
+"#;
+
+    #[test]
+    fn lists_fillers_of_the_expected_type() {
+        check(
+            &format!(
+                "{}{}",
+                r#"
+//- /main.rs crate:main deps:std
+const LIMIT: u32 = 10;
+static NAME: &str = "";
+const TITLE: &str = "";
+fn next_id(seed: u32) -> u32 { seed }
+fn id() -> u32 { todo!() }
+
+struct Config { limit: u32, name: &'static str }
+
+fn f(count: u32, flag: bool) -> Config {
+    let first: u32 = todo!();
+    next_id(_);
+    Config { limit: count, name: todo!() }
+}
+"#,
+                STD
+            ),
+            expect![[r#"
+                126..133 typed hole of type `u32`, could be filled with `LIMIT`, `next_id(todo!())` ["Fill with `LIMIT`", "Fill with `next_id(todo!())`"]
+                249..256 typed hole of type `u32`, could be filled with `count`, `LIMIT`, `id()`, `next_id(todo!())` ["Fill with `count`", "Fill with `LIMIT`", "Fill with `id()`", "Fill with `next_id(todo!())`"]
+                270..271 typed hole of type `u32`, could be filled with `count`, `first`, `LIMIT`, `id()`, `next_id(todo!())` ["Fill with `count`", "Fill with `first`", "Fill with `LIMIT`", "Fill with `id()`", "Fill with `next_id(todo!())`"]
+                307..314 typed hole of type `&str`, could be filled with `NAME`, `TITLE` ["Fill with `NAME`", "Fill with `TITLE`"]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn skips_holes_without_a_useful_type() {
+        check(
+            &format!(
+                "{}{}",
+                r#"
+//- /main.rs crate:main deps:std
+fn f() {
+    todo!()
+}
+fn g() -> u8 {
+    todo!("later")
+}
+"#,
+                STD
+            ),
+            expect![[""]],
+        );
+    }
+
+    #[test]
+    fn skips_assigned_underscores() {
+        check(
+            r#"
+struct S { x: u32 }
+struct T(u32, u32);
+fn f() -> u32 { 0 }
+fn g(mut a: u32) {
+    _ = f();
+    (a, _) = (1, 2);
+    [_, a] = [1, 2];
+    T(_, a) = T(1, 2);
+    S { x: _ } = S { x: 1 };
+    let x: u32 = _;
+}
+"#,
+            expect![[r#"
+                203..204 typed hole of type `u32`, could be filled with `a`, `f()` ["Fill with `a`", "Fill with `f()`"]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn skips_other_todo_macros() {
+        check(
+            r#"
+macro_rules! todo {
+    () => { 0 };
+}
+fn f() -> u32 { todo!() }
+"#,
+            expect![[""]],
+        );
+    }
+}
@@ -0,0 +1,677 @@
+//! Reports private imports which nothing in their module refers to, with fixes
+//! removing them one by one or all at once.
+//!
+//! Every path of the file is resolved, and an import counts as used when a path
+//! of its module starts with a name resolving to what it imports. Traits are
+//! used by resolved method calls and associated items too. As macro token trees
+//! and unresolved paths can't be told apart from uses, mentioning the imported
+//! name there counts as a use.
+//!
+//! Private imports are visible in the child modules as well, so the paths of the
+//! files of out-of-line child modules are resolved too. A child module uses an
+//! import of its parent through `super::` paths, or through a glob import like
+//! `use super::*`.
+
+use std::iter;
+
+use hir::{
+    diagnostics::DiagnosticCode, AsAssocItem, MacroDef, Module, ModuleDef, ModuleSource,
+    PathResolution, Semantics,
+};
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, AstNode, AstToken, NameOwner, PathSegmentKind, VisibilityOwner},
+    SyntaxKind, SyntaxNode, TextRange, TextSize,
+};
+use text_edit::TextEdit;
+
+use crate::{Diagnostic, Fix};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Item {
+    Def(ModuleDef),
+    Macro(MacroDef),
+}
+
+impl Item {
+    fn from_resolution(resolution: PathResolution) -> Option<Item> {
+        match resolution {
+            PathResolution::Def(it) => Some(Item::Def(it)),
+            PathResolution::Macro(it) => Some(Item::Macro(it)),
+            _ => None,
+        }
+    }
+}
+
+/// What the paths of the file refer to.
+#[derive(Default)]
+struct Uses {
+    items: FxHashSet<(Module, Item)>,
+    /// Modules with method calls which couldn't be resolved, so any trait imported there might
+    /// be used.
+    unresolved_methods: FxHashSet<Module>,
+    /// Names mentioned in macro token trees and in paths which couldn't be resolved.
+    mentioned_names: FxHashSet<String>,
+    /// Modules with glob imports, which may bring the imports of their parent into scope.
+    glob_modules: FxHashSet<Module>,
+}
+
+struct Import {
+    tree: ast::UseTree,
+    module: Module,
+    item: Item,
+    /// `None` for `use Trait as _`.
+    name: Option<String>,
+}
+
+pub(super) fn check(acc: &mut Vec<Diagnostic>, sema: &Semantics<RootDatabase>, file_id: FileId) {
+    let file_module = match sema.to_module_def(file_id) {
+        Some(it) => it,
+        None => return,
+    };
+    let source_file = sema.parse(file_id);
+    let mut uses = Uses::default();
+    collect_uses(sema, &mut uses, file_module, &source_file);
+    for (module, file) in child_files(sema, file_module, file_id) {
+        collect_uses(sema, &mut uses, module, &file);
+    }
+    let unused = source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::Use::cast)
+        .filter(|it| it.visibility().is_none())
+        .flat_map(|it| imports(sema, file_module, &it))
+        .filter(|it| !is_used(sema, &uses, it))
+        .map(|it| it.tree)
+        .collect::<Vec<_>>();
+    if unused.is_empty() {
+        return;
+    }
+    let remove_all = match unused.len() {
+        1 => None,
+        _ => Some(removal_edit(&source_file, &unused)),
+    };
+
+    for tree in &unused {
+        let range = tree.syntax().text_range();
+        let mut fixes = vec![Fix::new(
+            "Remove unused import",
+            SourceChange::from_text_edit(file_id, removal_edit(&source_file, &[tree.clone()])),
+            range,
+        )];
+        if let Some(edit) = &remove_all {
+            fixes.push(Fix::new(
+                "Remove all unused imports",
+                SourceChange::from_text_edit(file_id, edit.clone()),
+                range,
+            ));
+        }
+        acc.push(
+            Diagnostic::hint(range, format!("unused import: `{}`", tree))
+                .with_fixes(Some(fixes))
+                .with_unused(true)
+                .with_code(Some(DiagnosticCode("unused-import")))
+                .with_lint(Some("unused_imports")),
+        );
+    }
+}
+
+/// The files of the out-of-line modules nested in the file, with the module each file defines.
+fn child_files(
+    sema: &Semantics<RootDatabase>,
+    file_module: Module,
+    file_id: FileId,
+) -> Vec<(Module, ast::SourceFile)> {
+    let db = sema.db;
+    let mut res = Vec::new();
+    let mut stack = file_module.children(db).collect::<Vec<_>>();
+    while let Some(module) = stack.pop() {
+        stack.extend(module.children(db));
+        let src = module.definition_source(db);
+        if let ModuleSource::SourceFile(_) = src.value {
+            let child_file_id = src.file_id.original_file(db);
+            if child_file_id != file_id {
+                res.push((module, sema.parse(child_file_id)));
+            }
+        }
+    }
+    res
+}
+
+fn collect_uses(
+    sema: &Semantics<RootDatabase>,
+    res: &mut Uses,
+    file_module: Module,
+    source_file: &ast::SourceFile,
+) {
+    let db = sema.db;
+    for node in source_file.syntax().descendants() {
+        if let Some(call) = ast::MethodCallExpr::cast(node.clone()) {
+            let module = match module_of(sema, file_module, call.syntax()) {
+                Some(it) => it,
+                None => continue,
+            };
+            match sema.resolve_method_call(&call) {
+                Some(func) => {
+                    if let Some(trait_) =
+                        func.as_assoc_item(db).and_then(|it| it.containing_trait(db))
+                    {
+                        res.items.insert((module, Item::Def(ModuleDef::Trait(trait_))));
+                    }
+                }
+                None => {
+                    res.unresolved_methods.insert(module);
+                }
+            }
+        } else if let Some(tt) = ast::TokenTree::cast(node.clone()) {
+            res.mentioned_names.extend(
+                tt.syntax()
+                    .children_with_tokens()
+                    .filter_map(|it| it.into_token())
+                    .filter(|it| it.kind() == SyntaxKind::IDENT)
+                    .map(|it| it.text().to_string()),
+            );
+        } else if let Some(tree) = ast::UseTree::cast(node.clone()) {
+            if tree.star_token().is_some() {
+                if let Some(module) = module_of(sema, file_module, tree.syntax()) {
+                    res.glob_modules.insert(module);
+                }
+            }
+        } else if let Some(path) = ast::Path::cast(node) {
+            collect_path_use(sema, res, file_module, &path);
+        }
+    }
+}
+
+fn collect_path_use(
+    sema: &Semantics<RootDatabase>,
+    uses: &mut Uses,
+    file_module: Module,
+    path: &ast::Path,
+) {
+    let db = sema.db;
+    let module = match module_of(sema, file_module, path.syntax()) {
+        Some(it) => it,
+        None => return,
+    };
+    if let Some(use_item) = path.syntax().ancestors().find_map(ast::Use::cast) {
+        // An import of an import, like `use super::Import;`, uses it.
+        if let Some(tree) = path.syntax().parent().and_then(ast::UseTree::cast) {
+            if tree.use_tree_list().is_none() && tree.star_token().is_none() {
+                credit_source_module(sema, uses, path, import_qualifier(&tree));
+            }
+        }
+        // The other paths of imports only refer to other imports through their first segment,
+        // as in `use Enum::*`.
+        let top_path = match use_item.use_tree().and_then(|it| it.path()) {
+            Some(it) => it,
+            None => return,
+        };
+        if *path != first_segment(&top_path) || (*path == top_path && is_leaf_import(&use_item)) {
+            return;
+        }
+    }
+
+    let segment = match path.segment() {
+        Some(it) => it,
+        None => return,
+    };
+    if path.qualifier().is_some() {
+        // Associated items of traits use the trait.
+        let assoc_item = match sema.resolve_path(path) {
+            Some(PathResolution::AssocItem(it)) => Some(it),
+            Some(PathResolution::Def(ModuleDef::Function(it))) => it.as_assoc_item(db),
+            Some(PathResolution::Def(ModuleDef::Const(it))) => it.as_assoc_item(db),
+            Some(PathResolution::Def(ModuleDef::TypeAlias(it))) => it.as_assoc_item(db),
+            _ => None,
+        };
+        if let Some(trait_) = assoc_item.and_then(|it| it.containing_trait(db)) {
+            uses.items.insert((module, Item::Def(ModuleDef::Trait(trait_))));
+        }
+        // `self::Import` and `super::Import` use the import of the module they name.
+        credit_source_module(sema, uses, path, path.qualifier());
+        // `self::Import` and `super::Import` can refer to imports of other modules.
+        let root = first_segment(path);
+        let is_relative = matches!(
+            root.segment().and_then(|it| it.kind()),
+            Some(PathSegmentKind::SelfKw)
+                | Some(PathSegmentKind::SuperKw)
+                | Some(PathSegmentKind::CrateKw)
+        );
+        if is_relative {
+            if let Some(name_ref) = segment.name_ref() {
+                uses.mentioned_names.insert(name_ref.text().to_string());
+            }
+        }
+        return;
+    }
+
+    let name_ref = match segment.kind() {
+        Some(PathSegmentKind::Name(it)) => it,
+        _ => return,
+    };
+    match sema.resolve_path(path).and_then(Item::from_resolution) {
+        Some(item) => {
+            uses.items.insert((module, item));
+        }
+        None => {
+            uses.mentioned_names.insert(name_ref.text().to_string());
+        }
+    }
+}
+
+/// Records a use of the item `path` resolves to in the module `qualifier` resolves to, where
+/// it might be imported.
+fn credit_source_module(
+    sema: &Semantics<RootDatabase>,
+    uses: &mut Uses,
+    path: &ast::Path,
+    qualifier: Option<ast::Path>,
+) {
+    let source = match qualifier.and_then(|it| sema.resolve_path(&it)) {
+        Some(PathResolution::Def(ModuleDef::Module(it))) => it,
+        _ => return,
+    };
+    if let Some(item) = sema.resolve_path(path).and_then(Item::from_resolution) {
+        uses.items.insert((source, item));
+    }
+}
+
+/// The path of the module a non-glob `use` tree imports from, including the prefix of the tree
+/// it is nested in.
+fn import_qualifier(tree: &ast::UseTree) -> Option<ast::Path> {
+    match tree.path()?.qualifier() {
+        Some(it) => Some(it),
+        None => tree.syntax().parent().and_then(ast::UseTreeList::cast)?.parent_use_tree().path(),
+    }
+}
+
+/// The module the node is in. Imports and paths in blocks are attributed to the module containing
+/// the block.
+fn module_of(
+    sema: &Semantics<RootDatabase>,
+    file_module: Module,
+    node: &SyntaxNode,
+) -> Option<Module> {
+    match node.ancestors().skip(1).find_map(ast::Module::cast) {
+        Some(module) => sema.to_def(&module),
+        None => Some(file_module),
+    }
+}
+
+/// Whether the import is a single name, like `use foo;`, whose path denotes the import itself.
+fn is_leaf_import(use_item: &ast::Use) -> bool {
+    match use_item.use_tree() {
+        Some(tree) => tree.use_tree_list().is_none() && tree.star_token().is_none(),
+        None => false,
+    }
+}
+
+fn first_segment(path: &ast::Path) -> ast::Path {
+    iter::successors(Some(path.clone()), |it| it.qualifier()).last().unwrap_or_else(|| path.clone())
+}
+
+/// The names imported by the use item, except for glob imports.
+fn imports(
+    sema: &Semantics<RootDatabase>,
+    file_module: Module,
+    use_item: &ast::Use,
+) -> Vec<Import> {
+    let module = match module_of(sema, file_module, use_item.syntax()) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    use_item
+        .syntax()
+        .descendants()
+        .filter_map(ast::UseTree::cast)
+        .filter(|it| it.use_tree_list().is_none() && it.star_token().is_none())
+        .filter_map(|it| import(sema, module, it))
+        .collect()
+}
+
+fn import(sema: &Semantics<RootDatabase>, module: Module, tree: ast::UseTree) -> Option<Import> {
+    let mut path = tree.path()?;
+    // `use foo::{self}` imports `foo`.
+    if path.qualifier().is_none() && path.segment()?.self_token().is_some() {
+        let list = tree.syntax().parent().and_then(ast::UseTreeList::cast)?;
+        path = list.parent_use_tree().path()?;
+    }
+    let item = Item::from_resolution(sema.resolve_path(&path)?)?;
+    let name = match tree.rename() {
+        Some(rename) if rename.underscore_token().is_some() => None,
+        Some(rename) => Some(rename.name()?.text().to_string()),
+        None => match path.segment()?.kind()? {
+            PathSegmentKind::Name(name_ref) => Some(name_ref.text().to_string()),
+            _ => return None,
+        },
+    };
+    Some(Import { tree, module, item, name })
+}
+
+fn is_used(sema: &Semantics<RootDatabase>, uses: &Uses, import: &Import) -> bool {
+    let is_trait = matches!(import.item, Item::Def(ModuleDef::Trait(_)));
+    if is_trait && uses.unresolved_methods.contains(&import.module) {
+        return true;
+    }
+    if uses.items.contains(&(import.module, import.item)) {
+        return true;
+    }
+    // Child modules with glob imports may see the import through them.
+    let used_through_glob = uses.items.iter().any(|&(module, item)| {
+        item == import.item
+            && uses.glob_modules.contains(&module)
+            && is_strict_descendant(sema, module, import.module)
+    });
+    if used_through_glob {
+        return true;
+    }
+    match &import.name {
+        Some(name) => uses.mentioned_names.contains(name),
+        // Only traits are imported as `_`, the others are reported by rustc.
+        None => !is_trait,
+    }
+}
+
+fn is_strict_descendant(sema: &Semantics<RootDatabase>, module: Module, ancestor: Module) -> bool {
+    iter::successors(module.parent(sema.db), |it| it.parent(sema.db)).any(|it| it == ancestor)
+}
+
+/// Removes the given use trees, along with the lists and use items they leave empty.
+pub(super) fn removal_edit(source_file: &ast::SourceFile, unused: &[ast::UseTree]) -> TextEdit {
+    let unused = unused.iter().cloned().collect::<FxHashSet<_>>();
+    let mut builder = TextEdit::builder();
+    for use_item in source_file.syntax().descendants().filter_map(ast::Use::cast) {
+        let tree = match use_item.use_tree() {
+            Some(it) => it,
+            None => continue,
+        };
+        if !tree
+            .syntax()
+            .descendants()
+            .filter_map(ast::UseTree::cast)
+            .any(|it| unused.contains(&it))
+        {
+            continue;
+        }
+        match render(&tree, &unused) {
+            Some(text) => builder.replace(tree.syntax().text_range(), text),
+            None => builder.delete(use_item_range(&use_item)),
+        }
+    }
+    builder.finish()
+}
+
+/// The use item with the line break following it.
+fn use_item_range(use_item: &ast::Use) -> TextRange {
+    let range = use_item.syntax().text_range();
+    let next_ws = use_item
+        .syntax()
+        .next_sibling_or_token()
+        .and_then(|it| it.into_token())
+        .and_then(ast::Whitespace::cast);
+    match next_ws {
+        Some(ws) if ws.text().starts_with('\n') => {
+            TextRange::new(range.start(), range.end() + TextSize::of('\n'))
+        }
+        _ => range,
+    }
+}
+
+/// The text of the use tree without the unused trees, or `None` if nothing is left of it.
+fn render(tree: &ast::UseTree, unused: &FxHashSet<ast::UseTree>) -> Option<String> {
+    let list = match tree.use_tree_list() {
+        Some(it) => it,
+        None if unused.contains(tree) => return None,
+        None => return Some(tree.to_string()),
+    };
+    let children = list.use_trees().collect::<Vec<_>>();
+    if children.is_empty() {
+        return Some(tree.to_string());
+    }
+    let kept = children
+        .iter()
+        .filter_map(|child| render(child, unused).map(|text| (child, text)))
+        .collect::<Vec<_>>();
+    if kept.is_empty() {
+        return None;
+    }
+    if kept.len() == children.len() && kept.iter().all(|(child, text)| child.to_string() == *text) {
+        return Some(tree.to_string());
+    }
+
+    let prefix = match tree.path() {
+        Some(path) => format!("{}::", path),
+        None if tree.coloncolon_token().is_some() => "::".to_string(),
+        None => String::new(),
+    };
+    match kept.as_slice() {
+        [(child, text)] if tree.path().is_some() && !is_self(child) => {
+            Some(format!("{}{}", prefix, text))
+        }
+        _ => {
+            let texts = kept.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>();
+            Some(format!("{}{{{}}}", prefix, texts.join(", ")))
+        }
+    }
+}
+
+fn is_self(tree: &ast::UseTree) -> bool {
+    let segment = tree.path().filter(|it| it.qualifier().is_none()).and_then(|it| it.segment());
+    matches!(segment, Some(segment) if segment.self_token().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::{check_fix, check_nth_fix};
+    use crate::{fixture, DiagnosticsConfig};
+
+    fn check(ra_fixture: &str, expected: &[&str]) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let mut config = DiagnosticsConfig::default();
+        config.disabled.insert("unused-variable".to_string());
+        let messages = analysis
+            .diagnostics(&config, file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|it| it.code.as_ref().map(|it| it.as_str()) == Some("unused-import"))
+            .map(|it| it.message)
+            .collect::<Vec<_>>();
+        let expected =
+            expected.iter().map(|it| format!("unused import: `{}`", it)).collect::<Vec<_>>();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn unused_imports() {
+        check(
+            r#"
+mod m {
+    pub struct S;
+    pub struct T;
+    pub fn f() {}
+    pub mod n { pub fn g() {} }
+    pub trait Tr { fn method(&self) {} }
+    impl Tr for S {}
+}
+use m::{S, T as Renamed};
+use m::f;
+use m::n;
+use m::Tr;
+mod inner {
+    use super::m::n;
+    fn h() { n::g() }
+}
+fn main() {
+    let _ = S;
+}
+"#,
+            &["T as Renamed", "m::f", "m::n", "m::Tr"],
+        );
+    }
+
+    #[test]
+    fn used_imports() {
+        check(
+            r#"
+mod m {
+    pub struct S;
+    pub enum E { A, B }
+    pub fn f() {}
+    pub mod n { pub fn g() {} }
+    pub trait Tr { fn method(&self) {} fn assoc() {} }
+    pub trait Tr2 { fn method2(&self) {} }
+    pub trait Tr3 { fn method3(&self) {} }
+    impl Tr for S {}
+    impl Tr2 for S {}
+    impl Tr3 for S {}
+    pub struct Derived;
+}
+use m::{self, f, n, Tr, Tr2 as _, E};
+use E::*;
+use m::S as Alias;
+use m::Tr3;
+use m::Derived;
+pub use m::S;
+macro_rules! mac { ($e:expr) => { $e } }
+#[derive(Derived)]
+struct Uses;
+fn main() {
+    f();
+    n::g();
+    let _ = A;
+    let s: m::S = Alias;
+    s.method2();
+    <m::S as m::Tr>::assoc();
+    s.method();
+    mac!(Tr3::method3(&s));
+}
+"#,
+            &[],
+        );
+    }
+
+    #[test]
+    fn imports_used_through_glob_imports_of_children() {
+        check(
+            r#"
+mod m { pub struct S; pub struct T; }
+use m::{S, T};
+mod child {
+    use super::*;
+    fn f(_: S) {}
+}
+"#,
+            &["T"],
+        );
+    }
+
+    #[test]
+    fn imports_used_through_super_paths() {
+        check(
+            r#"
+mod m { pub struct S; pub struct T; pub struct U; }
+use m::{S, T, U};
+mod child {
+    use super::T;
+    fn f(_: super::S, _: T) {}
+    mod grandchild {
+        use super::super::{U};
+        fn g(_: U) {}
+    }
+}
+"#,
+            &[],
+        );
+    }
+
+    #[test]
+    fn imports_used_in_out_of_line_children() {
+        check(
+            r#"
+//- /main.rs
+mod m { pub struct S; pub struct T; pub struct U; }
+use m::{S, T, U};
+mod child;
+//- /child.rs
+use super::*;
+fn f(_: S, _: super::T) {}
+"#,
+            &["U"],
+        );
+    }
+
+    #[test]
+    fn traits_used_by_unresolved_methods() {
+        check(
+            r#"
+mod m {
+    pub trait Tr { fn method(&self) {} }
+}
+use m::Tr;
+fn f(x: Unknown) {
+    x.method();
+}
+"#,
+            &[],
+        );
+    }
+
+    #[test]
+    fn remove_unused_import() {
+        check_fix(
+            r#"
+mod m { pub struct S; pub struct T; pub struct U; }
+use m::{S, T$0, U};
+fn f(_: S, _: U) {}
+"#,
+            r#"
+mod m { pub struct S; pub struct T; pub struct U; }
+use m::{S, U};
+fn f(_: S, _: U) {}
+"#,
+        );
+        check_fix(
+            r#"
+mod m { pub mod n { pub struct S; pub struct T; } }
+use m::{n::{S, T$0}, self};
+fn f(_: S, _: m::n::S) {}
+"#,
+            r#"
+mod m { pub mod n { pub struct S; pub struct T; } }
+use m::{n::S, self};
+fn f(_: S, _: m::n::S) {}
+"#,
+        );
+        check_fix(
+            r#"
+mod m { pub struct S; }
+use m::S$0;
+fn f() {}
+"#,
+            r#"
+mod m { pub struct S; }
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_all_unused_imports() {
+        check_nth_fix(
+            1,
+            r#"
+mod m { pub struct S; pub struct T; pub struct U; }
+use m::{S, T$0};
+use m::U;
+fn f(_: S) {}
+"#,
+            r#"
+mod m { pub struct S; pub struct T; pub struct U; }
+use m::S;
+fn f(_: S) {}
+"#,
+        );
+    }
+}
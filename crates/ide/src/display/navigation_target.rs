@@ -435,6 +435,27 @@ impl ToNav for hir::Label {
     }
 }
 
+impl TryToNav for hir::Closure {
+    fn try_to_nav(&self, db: &RootDatabase) -> Option<NavigationTarget> {
+        let src = self.source(db)?;
+        let full_range = src.with_value(src.value.syntax()).original_file_range(db);
+        let focus_range = src
+            .value
+            .param_list()
+            .map(|it| src.with_value(it.syntax()).original_file_range(db).range);
+        Some(NavigationTarget {
+            file_id: full_range.file_id,
+            name: "{closure}".into(),
+            kind: None,
+            full_range: full_range.range,
+            focus_range,
+            container_name: None,
+            description: None,
+            docs: None,
+        })
+    }
+}
+
 impl TryToNav for hir::TypeParam {
     fn try_to_nav(&self, db: &RootDatabase) -> Option<NavigationTarget> {
         let src = self.source(db)?;
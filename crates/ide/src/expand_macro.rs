@@ -1,10 +1,10 @@
 use hir::Semantics;
-use ide_db::RootDatabase;
+use ide_db::{base_db::FileRange, RootDatabase};
 use syntax::{
     algo::{find_node_at_offset, SyntaxRewriter},
     ast, AstNode, NodeOrToken, SyntaxKind,
     SyntaxKind::*,
-    SyntaxNode, WalkEvent, T,
+    SyntaxNode, TextRange, WalkEvent, T,
 };
 
 use crate::FilePosition;
@@ -38,6 +38,28 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
     Some(ExpandedMacro { name: name_ref.text().to_string(), expansion })
 }
 
+/// Maps a range of the definition of a `macro_rules!` macro, like the span of a `cargo check`
+/// diagnostic, to the arguments of the macro call at `call` which were expanded within it.
+pub(crate) fn macro_call_range_from_def(
+    db: &RootDatabase,
+    call: FileRange,
+    def: FileRange,
+) -> Option<TextRange> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(call.file_id);
+    let node = match file.syntax().covering_element(call.range) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    // The range may include the semicolon of a macro call statement.
+    let mac = node.ancestors().find_map(ast::MacroCall::cast).or_else(|| {
+        node.descendants()
+            .filter_map(ast::MacroCall::cast)
+            .find(|it| call.range.contains_range(it.syntax().text_range()))
+    })?;
+    sema.macro_call_range_from_def(&mac, def)
+}
+
 fn expand_macro_recur(
     sema: &Semantics<RootDatabase>,
     macro_call: &ast::MacroCall,
@@ -121,9 +143,26 @@ fn insert_whitespaces(syn: SyntaxNode) -> String {
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
+    use ide_db::base_db::FileRange;
+    use syntax::TextRange;
+    use test_utils::extract_annotations;
 
     use crate::fixture;
 
+    fn check_call_range_from_def(ra_fixture: &str) {
+        let (analysis, def) = fixture::range(ra_fixture);
+        let text = analysis.file_text(def.file_id).unwrap();
+        let call_start = text.find("m!(").unwrap();
+        let call_end = call_start + text[call_start..].find(')').unwrap() + 1;
+        let call = FileRange {
+            file_id: def.file_id,
+            range: TextRange::new((call_start as u32).into(), (call_end as u32).into()),
+        };
+        let expected = extract_annotations(&text).into_iter().map(|(range, _)| range).next();
+        let actual = analysis.macro_call_range_from_def(call, def).unwrap();
+        assert_eq!(actual, expected);
+    }
+
     fn check(ra_fixture: &str, expect: Expect) {
         let (analysis, pos) = fixture::position(ra_fixture);
         let expansion = analysis.expand_macro(pos).unwrap().unwrap();
@@ -280,4 +319,33 @@ fn main() {
                 0 "#]],
         );
     }
+
+    #[test]
+    fn maps_def_range_to_call_arguments() {
+        check_call_range_from_def(
+            r#"
+macro_rules! m {
+    ($a:expr, $b:expr) => { $0$a + $b$0; $a };
+}
+fn f() {
+    m!(1 + 2, "");
+     //^^^^^^^^^
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn def_range_without_arguments() {
+        check_call_range_from_def(
+            r#"
+macro_rules! m {
+    ($a:expr) => { $0let x = 1;$0 $a };
+}
+fn f() {
+    m!(92);
+}
+"#,
+        );
+    }
 }
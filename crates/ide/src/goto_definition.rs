@@ -1,5 +1,7 @@
 use either::Either;
-use hir::{HasAttrs, ModuleDef, Semantics};
+use hir::{
+    AsAssocItem, AssocItem, Crate, HasAttrs, Impl, ModuleDef, PathResolution, Semantics, TypeAlias,
+};
 use ide_db::{
     defs::{Definition, NameClass, NameRefClass},
     RootDatabase,
@@ -17,6 +19,9 @@ use crate::{
 //
 // Navigates to the definition of an identifier.
 //
+// On an associated type of a trait, like `T::Item` or `<Foo as Iterator>::Item`, the `type Item`
+// of the implementation is offered as well when it is the only one which applies.
+//
 // |===
 // | Editor  | Shortcut
 //
@@ -39,7 +44,12 @@ pub(crate) fn goto_definition(
     let nav = match_ast! {
         match parent {
             ast::NameRef(name_ref) => {
-                reference_definition(&sema, Either::Right(&name_ref))
+                let nav = reference_definition(&sema, Either::Right(&name_ref));
+                let impl_nav = impl_assoc_type(&sema, &name_ref).and_then(|it| it.try_to_nav(db));
+                return Some(RangeInfo::new(
+                    original_token.text_range(),
+                    nav.into_iter().chain(impl_nav).collect(),
+                ));
             },
             ast::Name(name) => {
                 let def = NameClass::classify(&sema, &name)?.referenced_or_defined(sema.db);
@@ -58,6 +68,52 @@ pub(crate) fn goto_definition(
     Some(RangeInfo::new(original_token.text_range(), nav.into_iter().collect()))
 }
 
+/// For an associated type of a trait, like `T::Item` or `<Foo as Iterator>::Item`, finds its
+/// definition in the implementation of the trait for the type, if there is only one.
+fn impl_assoc_type(sema: &Semantics<RootDatabase>, name_ref: &ast::NameRef) -> Option<TypeAlias> {
+    let db = sema.db;
+    let alias = match NameRefClass::classify(sema, name_ref)?.referenced(db) {
+        Definition::ModuleDef(ModuleDef::TypeAlias(it)) => it,
+        _ => return None,
+    };
+    let trait_ = alias.as_assoc_item(db)?.containing_trait(db)?;
+    let qualifier =
+        name_ref.syntax().parent().and_then(ast::PathSegment::cast)?.parent_path().qualifier()?;
+    let segment = qualifier.segment()?;
+    let self_path = match segment.path_type() {
+        // `<Foo as Trait>::Item`
+        Some(self_type) => self_type.path()?,
+        None => qualifier,
+    };
+    let self_ty = match sema.resolve_path(&self_path)? {
+        PathResolution::Def(ModuleDef::Adt(it)) => Some(it.ty(db)),
+        PathResolution::Def(ModuleDef::TypeAlias(it)) if it.as_assoc_item(db).is_none() => {
+            Some(it.ty(db))
+        }
+        PathResolution::SelfType(it) => Some(it.target_ty(db)),
+        PathResolution::TypeParam(_) | PathResolution::AssocItem(_) => None,
+        _ => return None,
+    };
+
+    let impls = Crate::all(db)
+        .into_iter()
+        .flat_map(|krate| Impl::for_trait(db, krate, trait_))
+        .filter(|imp| match &self_ty {
+            Some(ty) => ty.is_equal_for_find_impls(&imp.target_ty(db)),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+    let imp = match impls.as_slice() {
+        [it] => *it,
+        _ => return None,
+    };
+    let name = alias.name(db);
+    imp.items(db).into_iter().find_map(|it| match it {
+        AssocItem::TypeAlias(it) if it.name(db) == name => Some(it),
+        _ => None,
+    })
+}
+
 fn def_for_doc_comment(
     sema: &Semantics<RootDatabase>,
     position: FilePosition,
@@ -147,6 +203,17 @@ mod tests {
         assert_eq!(expected, FileRange { file_id: nav.file_id, range: nav.focus_or_full_range() });
     }
 
+    fn check_all(ra_fixture: &str) {
+        let (analysis, position, annotations) = fixture::annotations(ra_fixture);
+        let navs = analysis.goto_definition(position).unwrap().expect("no definition found").info;
+        let actual = navs
+            .into_iter()
+            .map(|nav| FileRange { file_id: nav.file_id, range: nav.focus_or_full_range() })
+            .collect::<Vec<_>>();
+        let expected = annotations.into_iter().map(|(range, _)| range).collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn goto_def_for_extern_crate() {
         check(
@@ -417,6 +484,26 @@ fn bar(foo: &Foo) {
         );
     }
 
+    #[test]
+    fn goto_def_for_raw_identifiers() {
+        check(
+            r#"
+struct Foo { r#type: u32 }
+           //^^^^^^
+fn bar(foo: &Foo) {
+    foo.r#type$0;
+}
+"#,
+        );
+        check(
+            r#"
+mod r#type { pub fn r#fn() {} }
+                  //^^^^
+fn main() { r#type::r#fn$0(); }
+"#,
+        );
+    }
+
     #[test]
     fn goto_def_for_record_fields() {
         check(
@@ -1161,4 +1248,49 @@ struct S;
 "#,
         )
     }
+
+    #[test]
+    fn goto_def_for_assoc_type_of_qualified_path() {
+        check_all(
+            r#"
+trait Iterator { type Item; }
+                    //^^^^
+struct Counter;
+impl Iterator for Counter { type Item = u32; }
+                               //^^^^
+struct Other;
+impl Iterator for Other { type Item = u8; }
+fn next() -> <Counter as Iterator>::Item$0 { 0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_for_assoc_type_of_type_param_with_single_impl() {
+        check_all(
+            r#"
+trait Shape { type Unit; }
+                 //^^^^
+struct Square;
+impl Shape for Square { type Unit = f32; }
+                           //^^^^
+fn area<S: Shape>() -> S::Unit$0 { loop {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_for_assoc_type_of_type_param_with_many_impls() {
+        check(
+            r#"
+trait Shape { type Unit; }
+                 //^^^^
+struct Square;
+impl Shape for Square { type Unit = f32; }
+struct Circle;
+impl Shape for Circle { type Unit = f64; }
+fn area<S: Shape>() -> S::Unit$0 { loop {} }
+"#,
+        );
+    }
 }
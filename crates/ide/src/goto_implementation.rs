@@ -1,5 +1,10 @@
-use hir::{Crate, Impl, Semantics};
-use ide_db::RootDatabase;
+use hir::{Crate, Impl, ModuleDef, Semantics, Trait};
+use ide_db::{
+    base_db::SourceDatabaseExt,
+    defs::{Definition, NameRefClass},
+    RootDatabase,
+};
+use rustc_hash::FxHashSet;
 use syntax::{algo::find_node_at_offset, ast, AstNode};
 
 use crate::{display::TryToNav, FilePosition, NavigationTarget, RangeInfo};
@@ -8,6 +13,9 @@ use crate::{display::TryToNav, FilePosition, NavigationTarget, RangeInfo};
 //
 // Navigates to the impl block of structs, enums or traits. Also implemented as a code lens.
 //
+// On a trait bound in a where clause or a generic parameter list, like `T: Display`, navigates
+// to the types of the workspace implementing the trait instead.
+//
 // |===
 // | Editor  | Shortcut
 //
@@ -21,6 +29,15 @@ pub(crate) fn goto_implementation(
     let source_file = sema.parse(position.file_id);
     let syntax = source_file.syntax().clone();
 
+    if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(&syntax, position.offset) {
+        if let Some(tr) = bound_trait(&sema, &name_ref) {
+            return Some(RangeInfo::new(
+                name_ref.syntax().text_range(),
+                workspace_implementors(db, tr),
+            ));
+        }
+    }
+
     let krate = sema.to_module_def(position.file_id)?.krate();
 
     if let Some(nominal_def) = find_node_at_offset::<ast::Adt>(&syntax, position.offset) {
@@ -72,6 +89,42 @@ fn impls_for_trait(
     Some(impls.into_iter().filter_map(|imp| imp.try_to_nav(sema.db)).collect())
 }
 
+/// Returns the trait named by `name_ref` if it is a bound of a where clause predicate or of a
+/// generic parameter.
+pub(crate) fn bound_trait(
+    sema: &Semantics<RootDatabase>,
+    name_ref: &ast::NameRef,
+) -> Option<Trait> {
+    let path = ast::PathSegment::cast(name_ref.syntax().parent()?)?.parent_path();
+    let bound =
+        ast::TypeBound::cast(ast::PathType::cast(path.syntax().parent()?)?.syntax().parent()?)?;
+    let bounded = bound.syntax().parent()?.parent()?;
+    if !ast::WherePred::can_cast(bounded.kind()) && !ast::TypeParam::can_cast(bounded.kind()) {
+        return None;
+    }
+    match NameRefClass::classify(sema, name_ref)?.referenced(sema.db) {
+        Definition::ModuleDef(ModuleDef::Trait(it)) => Some(it),
+        _ => None,
+    }
+}
+
+/// Finds the types of the workspace which implement the trait. Impls for types which aren't
+/// ADTs, like blanket impls, are returned as the impl itself.
+pub(crate) fn workspace_implementors(db: &RootDatabase, tr: Trait) -> Vec<NavigationTarget> {
+    let mut seen = FxHashSet::default();
+    Crate::all(db)
+        .into_iter()
+        .filter(|krate| !db.source_root(db.file_source_root(krate.root_file(db))).is_library)
+        .flat_map(|krate| Impl::for_trait(db, krate, tr))
+        .filter(|imp| !imp.is_negative(db))
+        .filter_map(|imp| match imp.target_ty(db).as_adt() {
+            Some(adt) => adt.try_to_nav(db),
+            None => imp.try_to_nav(db),
+        })
+        .filter(|nav| seen.insert((nav.file_id, nav.full_range)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use ide_db::base_db::FileRange;
@@ -223,6 +276,44 @@ mod marker {
 }
 #[rustc_builtin_macro]
 macro Copy {}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_implementation_from_where_clause_bound() {
+        check(
+            r#"
+//- /lib.rs
+trait Tr {}
+struct Foo;
+     //^^^
+enum Bar {}
+   //^^^
+struct Baz;
+impl Tr for Foo {}
+impl Tr for &Foo {}
+          //^^^^
+impl Tr for Bar {}
+impl<T: Copy> Tr for [T] {}
+                   //^^^
+trait Copy {}
+fn f<T>(t: T) where T: T$0r {}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_implementation_from_generic_param_bound() {
+        check(
+            r#"
+//- /lib.rs
+trait Tr {}
+struct Foo;
+     //^^^
+impl Tr for Foo {}
+fn f<T: Copy + T$0r>(t: T) {}
+trait Copy {}
 "#,
         );
     }
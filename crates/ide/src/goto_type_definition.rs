@@ -5,7 +5,7 @@ use crate::{display::TryToNav, FilePosition, NavigationTarget, RangeInfo};
 
 // Feature: Go to Type Definition
 //
-// Navigates to the type of an identifier.
+// Navigates to the type of an identifier. For closures, navigates to the closure expression.
 //
 // |===
 // | Editor  | Action Name
@@ -35,9 +35,10 @@ pub(crate) fn goto_type_definition(
         Some((ty, node))
     })?;
 
-    let adt_def = ty.autoderef(db).filter_map(|ty| ty.as_adt()).last()?;
-
-    let nav = adt_def.try_to_nav(db)?;
+    let nav = match ty.autoderef(db).last().and_then(|ty| ty.as_closure()) {
+        Some(closure) => closure.try_to_nav(db)?,
+        None => ty.autoderef(db).filter_map(|ty| ty.as_adt()).last()?.try_to_nav(db)?,
+    };
     Some(RangeInfo::new(node.text_range(), vec![nav]))
 }
 
@@ -144,6 +145,19 @@ struct Foo;
 impl Foo {
     fn f(&self$0) {}
 }
+"#,
+        )
+    }
+
+    #[test]
+    fn goto_type_definition_for_closure() {
+        check(
+            r#"
+fn foo() {
+    let add = |a: i32, b: i32| a + b;
+            //^^^^^^^^^^^^^^^^
+    let sum = &ad$0d;
+}
 "#,
         )
     }
@@ -1,6 +1,6 @@
 use hir::{
-    Adt, AsAssocItem, AssocItemContainer, FieldSource, GenericParam, HasAttrs, HasSource,
-    HirDisplay, Module, ModuleDef, ModuleSource, Semantics,
+    Adt, AsAssocItem, AssocItemContainer, CaptureKind, ClosureStyle, FieldSource, GenericParam,
+    HasAttrs, HasSource, HirDisplay, Module, ModuleDef, ModuleSource, Semantics,
 };
 use ide_db::{
     base_db::SourceDatabase,
@@ -9,12 +9,19 @@ use ide_db::{
 };
 use itertools::Itertools;
 use stdx::format_to;
-use syntax::{ast, match_ast, AstNode, SyntaxKind::*, SyntaxToken, TokenAtOffset, T};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, ModuleItemOwner, NameOwner, VisibilityOwner},
+    match_ast, AstNode,
+    SyntaxKind::*,
+    SyntaxToken, TokenAtOffset, T,
+};
 use test_utils::mark;
 
 use crate::{
     display::{macro_label, ShortLabel, TryToNav},
     doc_links::{remove_links, rewrite_links},
+    goto_implementation::{bound_trait, workspace_implementors},
     markdown_remove::remove_markdown,
     markup::Markup,
     runnables::{runnable_fn, runnable_mod},
@@ -78,6 +85,10 @@ pub struct HoverResult {
 //
 // Shows additional information, like type of an expression or documentation for definition when "focusing" code.
 // Focusing is usually hovering with a mouse, but can also be triggered with a shortcut.
+// On a trait bound in a where clause or a generic parameter list, also shows how many types of the
+// workspace implement the trait.
+// On a segment of a `use` path, also shows the chain of re-exports the item travels through and
+// whether the import is itself re-exported.
 pub(crate) fn hover(
     db: &RootDatabase,
     position: FilePosition,
@@ -116,6 +127,26 @@ pub(crate) fn hover(
             } else {
                 remove_links(markup)
             };
+            let mut markup = markup;
+            let summary = match definition {
+                Definition::Local(local) => captures_summary(db, &local.ty(db)),
+                Definition::ModuleDef(ModuleDef::Function(it)) => {
+                    it.source(db).and_then(|src| abi_summary(db, it, &src.value))
+                }
+                Definition::ModuleDef(ModuleDef::Static(it)) => {
+                    it.source(db).and_then(|src| abi_summary(db, it, &src.value))
+                }
+                _ => ast::NameRef::cast(node.clone())
+                    .and_then(|it| bound_trait(&sema, &it))
+                    .map(|tr| implementors_summary(db, tr)),
+            };
+            let import_summary = ast::NameRef::cast(node.clone())
+                .and_then(|it| import_summary(&sema, &it, definition));
+            for summary in summary.into_iter().chain(import_summary) {
+                let summary = if markdown { summary } else { remove_markdown(&summary) };
+                let separator = if markdown { "\n\n---\n\n" } else { "\n\n" };
+                format_to!(markup, "{}{}", separator, summary);
+            }
             res.markup = Markup::from(markup);
             if let Some(action) = show_implementations_action(db, definition) {
                 res.actions.push(action);
@@ -134,6 +165,14 @@ pub(crate) fn hover(
         }
     }
 
+    if let Some(reason) = token.ancestors().find_map(|it| sema.inactive_cfg(&it)) {
+        let markup = format!("code is inactive due to `#[cfg]` directives: {}", reason);
+        let markup = if markdown { markup } else { remove_markdown(&markup) };
+        res.markup = Markup::from(markup);
+        let range = sema.original_range(&node).range;
+        return Some(RangeInfo::new(range, res));
+    }
+
     if token.kind() == syntax::SyntaxKind::COMMENT {
         // don't highlight the entire parent node on comment hover
         return None;
@@ -154,15 +193,230 @@ pub(crate) fn hover(
         }
     };
 
-    res.markup = if markdown {
-        Markup::fenced_block(&ty.display(db))
+    let mut markup: String = if markdown {
+        Markup::fenced_block(&ty.display(db).with_closure_style(ClosureStyle::ImplFn)).into()
     } else {
-        ty.display(db).to_string().into()
+        ty.display(db).with_closure_style(ClosureStyle::ImplFn).to_string()
     };
+    if let Some(summary) = captures_summary(db, &ty) {
+        let summary = if markdown { summary } else { remove_markdown(&summary) };
+        let separator = if markdown { "\n\n---\n\n" } else { "\n\n" };
+        format_to!(markup, "{}{}", separator, summary);
+    }
+    res.markup = Markup::from(markup);
     let range = sema.original_range(&node).range;
     Some(RangeInfo::new(range, res))
 }
 
+fn implementors_summary(db: &RootDatabase, tr: hir::Trait) -> String {
+    let count = workspace_implementors(db, tr).len();
+    let types = if count == 1 { "type" } else { "types" };
+    format!("`{}` is implemented by {} {} in the workspace", tr.name(db), count, types)
+}
+
+/// Lists the locals a closure captures and how.
+fn captures_summary(db: &RootDatabase, ty: &hir::Type) -> Option<String> {
+    let captures = ty.as_closure()?.captures(db);
+    if captures.is_empty() {
+        return None;
+    }
+    let captures = captures
+        .iter()
+        .filter_map(|it| {
+            let name = it.local().name(db)?;
+            let by = match it.kind() {
+                CaptureKind::ByRef => "reference",
+                CaptureKind::ByMutRef => "mutable reference",
+                CaptureKind::ByValue => "value",
+            };
+            Some(format!("`{}` by {}", name, by))
+        })
+        .join(", ");
+    Some(format!("Captures {}", captures))
+}
+
+/// Describes how an FFI item is linked: the ABI it uses and the symbol it is known as.
+fn abi_summary(
+    db: &RootDatabase,
+    def: impl HasAttrs,
+    item: &(impl ast::AttrsOwner + ast::NameOwner),
+) -> Option<String> {
+    let attrs = def.attrs(db);
+    let abi_name = |abi: Option<ast::Abi>| {
+        abi.and_then(|it| it.abi_string()).unwrap_or_else(|| "C".to_string())
+    };
+    let name = item.name()?.text().to_string();
+    let block = item.syntax().ancestors().find_map(ast::ExternBlock::cast);
+    if let Some(block) = block {
+        let symbol = attrs.by_key("link_name").string_value().map_or(name, |it| it.to_string());
+        return Some(format!(
+            "Declared in an `extern \"{}\"` block, links to the symbol `{}`",
+            abi_name(block.abi()),
+            symbol
+        ));
+    }
+    let abi = ast::Fn::cast(item.syntax().clone()).and_then(|it| it.abi());
+    let abi = abi.map(|it| format!("Uses the `{}` ABI", abi_name(Some(it))));
+    let symbol = match attrs.by_key("export_name").string_value() {
+        Some(symbol) => format!("the symbol `{}`", symbol),
+        None if attrs.by_key("no_mangle").exists() => format!("the unmangled symbol `{}`", name),
+        None => return abi,
+    };
+    Some(match abi {
+        Some(abi) => format!("{} and is exported as {}", abi, symbol),
+        None => format!("Exported as {}", symbol),
+    })
+}
+
+/// Describes the re-exports an imported item travels through, from the module named in the `use`
+/// to the one defining it, and whether the import itself is re-exported.
+fn import_summary(
+    sema: &Semantics<RootDatabase>,
+    name_ref: &ast::NameRef,
+    def: Definition,
+) -> Option<String> {
+    let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+    let use_item = path.syntax().ancestors().find_map(ast::Use::cast)?;
+    let mut lines = Vec::new();
+
+    let chain = reexport_chain(sema, &path, def);
+    if chain.len() > 1 {
+        let chain = chain.iter().map(|it| format!("`{}`", it)).join(" → ");
+        lines.push(format!("Re-exported through {}", chain));
+    }
+    if let Some(vis) = use_item.visibility() {
+        lines.push(format!("This import is itself re-exported (`{} use`)", vis));
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    Some(lines.join("\n\n"))
+}
+
+/// The paths under which `def` is reachable, starting with the one written in the `use` tree and
+/// following the imports of each module until the module that defines it.
+fn reexport_chain(
+    sema: &Semantics<RootDatabase>,
+    path: &ast::Path,
+    def: Definition,
+) -> Vec<String> {
+    let db = sema.db;
+    let mut res = Vec::new();
+    let (mut module, mut name) = match import_qualifier(sema, path) {
+        Some(it) => it,
+        None => return res,
+    };
+    let mut visited = Vec::new();
+    while !visited.contains(&(module, name.clone())) {
+        res.push(render_path(db, module, Some(name.clone())));
+        visited.push((module, name.clone()));
+        match find_import(sema, module, &name, def) {
+            Some(next) => {
+                module = next.0;
+                name = next.1;
+            }
+            None => break,
+        }
+    }
+    res
+}
+
+/// Finds the `use` in `module` which brings `def` into scope as `name`, and returns the module
+/// and name it is imported from.
+fn find_import(
+    sema: &Semantics<RootDatabase>,
+    module: Module,
+    name: &str,
+    def: Definition,
+) -> Option<(Module, String)> {
+    let db = sema.db;
+    let trees = module_items(sema, module)
+        .into_iter()
+        .filter_map(|item| match item {
+            ast::Item::Use(it) => it.use_tree(),
+            _ => None,
+        })
+        .flat_map(|tree| tree.syntax().descendants().filter_map(ast::UseTree::cast).collect_vec())
+        .filter(|tree| tree.use_tree_list().is_none())
+        .collect_vec();
+
+    let named = trees.iter().filter(|tree| tree.star_token().is_none()).find_map(|tree| {
+        let path = tree.path()?;
+        let imported_as = match tree.rename() {
+            Some(rename) => rename.name()?.text().to_string(),
+            None => path.segment()?.name_ref()?.text().to_string(),
+        };
+        if imported_as != name || Definition::from(sema.resolve_path(&path)?) != def {
+            return None;
+        }
+        import_qualifier(sema, &path)
+    });
+    if named.is_some() {
+        return named;
+    }
+
+    trees.iter().filter(|tree| tree.star_token().is_some()).find_map(|tree| {
+        let path = match tree.path() {
+            Some(it) => it,
+            None => tree
+                .syntax()
+                .ancestors()
+                .find_map(ast::UseTreeList::cast)?
+                .parent_use_tree()
+                .path()?,
+        };
+        let glob = match sema.resolve_path(&path)? {
+            hir::PathResolution::Def(ModuleDef::Module(it)) => it,
+            _ => return None,
+        };
+        let found = glob.scope(db, Some(module)).into_iter().any(|(it, scope_def)| {
+            it.to_string() == name
+                && match (scope_def, def) {
+                    (hir::ScopeDef::ModuleDef(it), Definition::ModuleDef(def)) => it == def,
+                    (hir::ScopeDef::MacroDef(it), Definition::Macro(def)) => it == def,
+                    _ => false,
+                }
+        });
+        if found {
+            Some((glob, name.to_string()))
+        } else {
+            None
+        }
+    })
+}
+
+/// The module the last segment of a `use` path is looked up in, and the name of that segment.
+fn import_qualifier(sema: &Semantics<RootDatabase>, path: &ast::Path) -> Option<(Module, String)> {
+    let name = path.segment()?.name_ref()?.text().to_string();
+    let qualifier = match path.qualifier() {
+        Some(it) => it,
+        None => {
+            path.syntax().ancestors().find_map(ast::UseTreeList::cast)?.parent_use_tree().path()?
+        }
+    };
+    match sema.resolve_path(&qualifier)? {
+        hir::PathResolution::Def(ModuleDef::Module(it)) => Some((it, name)),
+        _ => None,
+    }
+}
+
+fn module_items(sema: &Semantics<RootDatabase>, module: Module) -> Vec<ast::Item> {
+    let src = module.definition_source(sema.db);
+    if src.file_id.call_node(sema.db).is_some() {
+        return Vec::new();
+    }
+    let file = sema.parse(src.file_id.original_file(sema.db));
+    match src.value {
+        ModuleSource::SourceFile(_) => file.items().collect(),
+        ModuleSource::Module(it) => {
+            find_node_at_range::<ast::Module>(file.syntax(), it.syntax().text_range())
+                .and_then(|it| it.item_list())
+                .map_or_else(Vec::new, |it| it.items().collect())
+        }
+        ModuleSource::BlockExpr(_) => Vec::new(),
+    }
+}
+
 fn show_implementations_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
     fn to_action(nav_target: NavigationTarget) -> HoverAction {
         HoverAction::Implementation(FilePosition {
@@ -212,6 +466,7 @@ fn goto_type_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
         }
     };
 
+    let mut closure = None;
     if let Definition::GenericParam(GenericParam::TypeParam(it)) = def {
         it.trait_bounds(db).into_iter().for_each(|it| push_new_def(it.into()));
     } else {
@@ -220,6 +475,10 @@ fn goto_type_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
             Definition::GenericParam(GenericParam::ConstParam(it)) => it.ty(db),
             _ => return None,
         };
+        if let Some(it) = ty.autoderef(db).last().and_then(|it| it.as_closure()) {
+            closure =
+                Some((ty.display(db).with_closure_style(ClosureStyle::ImplFn).to_string(), it));
+        }
 
         ty.walk(db, |t| {
             if let Some(adt) = t.as_adt() {
@@ -234,14 +493,16 @@ fn goto_type_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
         });
     }
 
-    let targets = targets
+    let closure = closure
+        .and_then(|(mod_path, it)| Some(HoverGotoTypeData { mod_path, nav: it.try_to_nav(db)? }));
+    let targets = closure
         .into_iter()
-        .filter_map(|it| {
+        .chain(targets.into_iter().filter_map(|it| {
             Some(HoverGotoTypeData {
                 mod_path: render_path(db, it.module(db)?, it.name(db).map(|name| name.to_string())),
                 nav: it.try_to_nav(db)?,
             })
-        })
+        }))
         .collect();
 
     Some(HoverAction::GoToType(targets))
@@ -341,7 +602,9 @@ fn hover_for_definition(db: &RootDatabase, def: Definition) -> Option<Markup> {
             ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path),
             ModuleDef::BuiltinType(it) => Some(Markup::fenced_block(&it.name())),
         },
-        Definition::Local(it) => Some(Markup::fenced_block(&it.ty(db).display(db))),
+        Definition::Local(it) => Some(Markup::fenced_block(
+            &it.ty(db).display(db).with_closure_style(ClosureStyle::ImplFn),
+        )),
         Definition::SelfType(impl_def) => {
             impl_def.target_ty(db).as_adt().and_then(|adt| match adt {
                 Adt::Struct(it) => from_def_source(db, it, mod_path),
@@ -508,7 +771,7 @@ fn main() {
                 *iter*
 
                 ```rust
-                Iter<Scan<OtherStruct<OtherStruct<i32>>, |&mut u32, &u32, &mut u32| -> Option<u32>, u32>>
+                Iter<Scan<OtherStruct<OtherStruct<i32>>, impl Fn(&mut u32, &u32, &mut u32) -> Option<u32>, u32>>
                 ```
             "#]],
         );
@@ -1363,6 +1626,10 @@ fn bar() { fo$0o(); }
                 ```rust
                 pub(crate) async unsafe extern "C" fn foo()
                 ```
+
+                ---
+
+                Uses the `C` ABI
             "#]],
         );
     }
@@ -1386,6 +1653,252 @@ fn bar() { fo$0o(); }
         );
     }
 
+    #[test]
+    fn hover_trait_bound_shows_implementors_count() {
+        check(
+            r#"
+trait Tr {}
+struct Foo;
+struct Bar;
+impl Tr for Foo {}
+impl Tr for Bar {}
+fn f<T>(t: T) where T: T$0r {}
+"#,
+            expect![[r#"
+                *Tr*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                trait Tr
+                ```
+
+                ---
+
+                `Tr` is implemented by 2 types in the workspace
+            "#]],
+        );
+        check(
+            r#"
+trait Tr {}
+struct Foo;
+impl Tr for Foo {}
+fn f<T: T$0r>(t: T) {}
+"#,
+            expect![[r#"
+                *Tr*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                trait Tr
+                ```
+
+                ---
+
+                `Tr` is implemented by 1 type in the workspace
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_abi_of_ffi_items() {
+        check(
+            r#"
+extern "C" {
+    #[link_name = "c_abs"]
+    fn abs(x: i32) -> i32;
+}
+fn main() { unsafe { abs$0(-1) }; }
+"#,
+            expect![[r#"
+                *abs*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                fn abs(x: i32) -> i32
+                ```
+
+                ---
+
+                Declared in an `extern "C"` block, links to the symbol `c_abs`
+            "#]],
+        );
+        check(
+            r#"
+#[no_mangle]
+pub extern fn callback$0() {}
+"#,
+            expect![[r#"
+                *callback*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                pub extern fn callback()
+                ```
+
+                ---
+
+                Uses the `C` ABI and is exported as the unmangled symbol `callback`
+            "#]],
+        );
+        check(
+            r#"
+#[export_name = "VERSION_STRING"]
+static VERSION$0: u32 = 1;
+"#,
+            expect![[r#"
+                *VERSION*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                static VERSION: u32
+                ```
+
+                ---
+
+                Exported as the symbol `VERSION_STRING`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_closure_shows_signature_and_captures() {
+        check(
+            r#"
+struct V;
+impl V { fn push(&mut self, x: i32) {} fn len(&self) -> usize { 0 } }
+fn main() {
+    let mut total = 0;
+    let mut v = V;
+    let x = 1;
+    let f = |y: i32| { total += y; v.push(x); v.len() };
+    f$0;
+}
+"#,
+            expect![[r#"
+                *f*
+
+                ```rust
+                impl FnMut(i32) -> usize
+                ```
+
+                ---
+
+                Captures `total` by mutable reference, `v` by mutable reference, `x` by reference
+            "#]],
+        );
+        check(
+            r#"
+fn main() {
+    let x = 1;
+    let f$0 = |y: i32| x + y;
+}
+"#,
+            expect![[r#"
+                *f*
+
+                ```rust
+                impl Fn(i32) -> i32
+                ```
+
+                ---
+
+                Captures `x` by reference
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_move_and_fn_once_closures() {
+        check(
+            r#"
+struct S;
+fn main() {
+    let s = S;
+    let x = 1;
+    let f$0 = move || { let _ = &s; x };
+}
+"#,
+            expect![[r#"
+                *f*
+
+                ```rust
+                impl Fn() -> i32
+                ```
+
+                ---
+
+                Captures `s` by value, `x` by value
+            "#]],
+        );
+        check(
+            r#"
+struct S;
+fn consume(s: S) {}
+fn main() {
+    let s = S;
+    let x = 1;
+    let f$0 = || { consume(s); x };
+}
+"#,
+            expect![[r#"
+                *f*
+
+                ```rust
+                impl FnOnce() -> i32
+                ```
+
+                ---
+
+                Captures `s` by value, `x` by reference
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_closure_goto_type_action() {
+        check_actions(
+            r#"
+fn main() {
+    let f$0 = || ();
+}
+"#,
+            expect![[r#"
+                [
+                    GoToType(
+                        [
+                            HoverGotoTypeData {
+                                mod_path: "impl Fn()",
+                                nav: NavigationTarget {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    full_range: 24..29,
+                                    focus_range: 24..26,
+                                    name: "{closure}",
+                                },
+                            },
+                        ],
+                    ),
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_hover_extern_crate() {
         check(
@@ -1426,6 +1939,83 @@ extern crate std as ab$0c;
         );
     }
 
+    #[test]
+    fn hover_use_shows_reexport_chain() {
+        check(
+            r#"
+//- /main.rs crate:main deps:lib
+pub use lib::prelude::{Fo$0o};
+//- /lib.rs crate:lib
+pub mod prelude { pub use crate::shapes::*; }
+pub mod shapes { pub use self::inner::Bar as Foo; mod inner { pub struct Bar; } }
+"#,
+            expect![[r#"
+                *Foo*
+
+                ```rust
+                lib::shapes::inner
+                ```
+
+                ```rust
+                pub struct Bar
+                ```
+
+                ---
+
+                Re-exported through `lib::prelude::Foo` → `lib::shapes::Foo` → `lib::shapes::inner::Bar`
+
+                This import is itself re-exported (`pub use`)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_use_of_module_shows_reexport_chain() {
+        check(
+            r#"
+use crate::a::fo$0o::Baz;
+mod a { pub(crate) use crate::b::foo; }
+mod b { pub mod foo { pub struct Baz; } }
+"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                test::b
+                ```
+
+                ```rust
+                pub mod foo
+                ```
+
+                ---
+
+                Re-exported through `test::a::foo` → `test::b::foo`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_use_without_reexports_has_no_chain() {
+        check(
+            r#"
+use crate::a::Fo$0o;
+mod a { pub struct Foo; }
+"#,
+            expect![[r#"
+                *Foo*
+
+                ```rust
+                test::a
+                ```
+
+                ```rust
+                pub struct Foo
+                ```
+            "#]],
+        );
+    }
+
     #[test]
     fn test_hover_mod_with_same_name_as_function() {
         check(
@@ -3496,4 +4086,32 @@ mod foo$0;
             "#]],
         );
     }
+
+    #[test]
+    fn hover_inactive_code() {
+        check(
+            r#"
+#[cfg(feature = "foo")]
+fn foo() {
+    let x$0 = 92;
+}
+"#,
+            expect![[r#"
+                *x*
+                code is inactive due to `#[cfg]` directives: feature = "foo" is disabled
+            "#]],
+        );
+        check(
+            r#"
+fn main() {
+    #[cfg(all(unix, not(unix)))]
+    let y = unr$0esolved();
+}
+"#,
+            expect![[r##"
+                *unresolved*
+                code is inactive due to `#[cfg]` directives: unix is disabled
+            "##]],
+        );
+    }
 }
@@ -1,7 +1,10 @@
+use std::convert::TryFrom;
+
 use either::Either;
-use hir::{known, Callable, HirDisplay, Semantics};
+use hir::{db::AstDatabase, known, Callable, HirDisplay, InFile, PanicKind, Semantics};
 use ide_db::helpers::FamousDefs;
 use ide_db::RootDatabase;
+use rustc_hash::FxHashSet;
 use stdx::to_lower_snake_case;
 use syntax::{
     ast::{self, ArgListOwner, AstNode, NameOwner},
@@ -15,6 +18,8 @@ pub struct InlayHintsConfig {
     pub type_hints: bool,
     pub parameter_hints: bool,
     pub chaining_hints: bool,
+    pub panic_hints: bool,
+    pub discriminant_hints: bool,
     pub max_length: Option<usize>,
 }
 
@@ -23,6 +28,8 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    PanicHint,
+    DiscriminantHint,
 }
 
 #[derive(Debug)]
@@ -42,6 +49,8 @@ pub struct InlayHint {
 // * types of local variables
 // * names of function arguments
 // * types of chained expressions
+// * expressions which may panic (disabled by default)
+// * discriminants of enum variants (disabled by default)
 //
 // **Note:** VS Code does not have native support for inlay hints https://github.com/microsoft/vscode/issues/16221[yet] and the hints are implemented using decorations.
 // This approach has limitations, the caret movement and bracket highlighting near the edges of the hint may be weird:
@@ -72,6 +81,10 @@ pub(crate) fn inlay_hints(
                 ast::CallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::MethodCallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::IdentPat(it) => { get_bind_pat_hints(&mut res, &sema, config, it); },
+                ast::Fn(it) => { get_panic_hints(&mut res, &sema, config, file_id, sema.to_def(&it).map(Into::into)); },
+                ast::Const(it) => { get_panic_hints(&mut res, &sema, config, file_id, sema.to_def(&it).map(Into::into)); },
+                ast::Static(it) => { get_panic_hints(&mut res, &sema, config, file_id, sema.to_def(&it).map(Into::into)); },
+                ast::Enum(it) => { get_discriminant_hints(&mut res, config, it); },
                 _ => (),
             }
         }
@@ -180,6 +193,117 @@ fn get_param_name_hints(
     Some(())
 }
 
+fn get_panic_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    file_id: FileId,
+    def: Option<hir::DefWithBody>,
+) -> Option<()> {
+    if !config.panic_hints {
+        return None;
+    }
+
+    let mut seen = FxHashSet::default();
+    for (ptr, kind) in def?.possible_panics(sema.db) {
+        let root = sema.db.parse_or_expand(ptr.file_id)?;
+        let expr = ptr.value.to_node(&root);
+        let range = InFile::new(ptr.file_id, expr.syntax()).original_file_range(sema.db);
+        // Macro expansions can map several expressions to the same call.
+        if range.file_id != file_id || !seen.insert((range.range, kind)) {
+            continue;
+        }
+        let label = match kind {
+            PanicKind::Unwrap => "may panic: unwrap",
+            PanicKind::Index => "may panic: index out of bounds",
+            PanicKind::Division => "may panic: division by zero",
+            PanicKind::Overflow => "may panic: overflow in debug builds",
+            PanicKind::Explicit => "panics",
+        };
+        acc.push(InlayHint { range: range.range, kind: InlayKind::PanicHint, label: label.into() });
+    }
+    Some(())
+}
+
+fn get_discriminant_hints(
+    acc: &mut Vec<InlayHint>,
+    config: &InlayHintsConfig,
+    enum_: ast::Enum,
+) -> Option<()> {
+    if !config.discriminant_hints {
+        return None;
+    }
+
+    let variants: Vec<ast::Variant> = enum_.variant_list()?.variants().collect();
+    // Discriminants of enums with fields can only be observed if they are given explicitly.
+    let fieldless = variants.iter().all(|it| it.field_list().is_none());
+    if !fieldless && variants.iter().all(|it| it.expr().is_none()) {
+        return None;
+    }
+
+    let mut next = Some(0);
+    for variant in variants {
+        let expr = variant.expr();
+        let value = match &expr {
+            Some(expr) => eval_discriminant(expr),
+            None => next,
+        };
+        next = value.and_then(|it| it.checked_add(1));
+
+        let is_literal = match &expr {
+            Some(ast::Expr::Literal(_)) => true,
+            Some(ast::Expr::PrefixExpr(it)) => {
+                matches!(it.expr(), Some(ast::Expr::Literal(_)))
+                    && it.op_kind() == Some(ast::PrefixOp::Neg)
+            }
+            _ => false,
+        };
+        if let (Some(value), false) = (value, is_literal) {
+            // Leave out attributes and comments attached to the variant.
+            let start = variant.name()?.syntax().text_range().start();
+            acc.push(InlayHint {
+                range: TextRange::new(start, variant.syntax().text_range().end()),
+                kind: InlayKind::DiscriminantHint,
+                label: format!("= {}", value).into(),
+            });
+        }
+    }
+    Some(())
+}
+
+/// Evaluates the integer expressions commonly used as discriminants, like `1 << 3`.
+fn eval_discriminant(expr: &ast::Expr) -> Option<i128> {
+    match expr {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::IntNumber(it) => i128::try_from(it.value()?).ok(),
+            _ => None,
+        },
+        ast::Expr::ParenExpr(it) => eval_discriminant(&it.expr()?),
+        ast::Expr::PrefixExpr(it) => match it.op_kind()? {
+            ast::PrefixOp::Neg => eval_discriminant(&it.expr()?)?.checked_neg(),
+            _ => None,
+        },
+        ast::Expr::BinExpr(it) => {
+            let lhs = eval_discriminant(&it.lhs()?)?;
+            let rhs = eval_discriminant(&it.rhs()?)?;
+            match it.op_kind()? {
+                ast::BinOp::Addition => lhs.checked_add(rhs),
+                ast::BinOp::Subtraction => lhs.checked_sub(rhs),
+                ast::BinOp::Multiplication => lhs.checked_mul(rhs),
+                ast::BinOp::Division => lhs.checked_div(rhs),
+                ast::BinOp::Remainder => lhs.checked_rem(rhs),
+                ast::BinOp::LeftShift => lhs.checked_shl(u32::try_from(rhs).ok()?),
+                ast::BinOp::RightShift => lhs.checked_shr(u32::try_from(rhs).ok()?),
+                ast::BinOp::BitwiseOr => Some(lhs | rhs),
+                ast::BinOp::BitwiseAnd => Some(lhs & rhs),
+                ast::BinOp::BitwiseXor => Some(lhs ^ rhs),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 fn get_bind_pat_hints(
     acc: &mut Vec<InlayHint>,
     sema: &Semantics<RootDatabase>,
@@ -455,6 +579,8 @@ mod tests {
         type_hints: true,
         parameter_hints: true,
         chaining_hints: true,
+        panic_hints: false,
+        discriminant_hints: false,
         max_length: None,
     };
 
@@ -488,6 +614,8 @@ mod tests {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -510,6 +638,8 @@ fn main() {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -532,6 +662,8 @@ fn main() {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -551,6 +683,8 @@ fn main() {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -570,6 +704,8 @@ fn main() {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -592,6 +728,8 @@ fn main() {
                 type_hints: false,
                 parameter_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -609,6 +747,8 @@ fn main() {
                 type_hints: true,
                 parameter_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -976,6 +1116,8 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1016,6 +1158,8 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1038,6 +1182,8 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1082,6 +1228,8 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1191,6 +1339,8 @@ fn main() {
                 type_hints: true,
                 parameter_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1224,6 +1374,8 @@ fn main() {
                 parameter_hints: false,
                 type_hints: true,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1261,6 +1413,8 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1317,6 +1471,8 @@ fn main() {
                 parameter_hints: false,
                 type_hints: true,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1354,6 +1510,8 @@ fn main() {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: false,
                 max_length: None,
             },
             r#"
@@ -1463,4 +1621,75 @@ fn main() {
 }"#,
         )
     }
+
+    #[test]
+    fn panic_hints() {
+        check_with_config(
+            InlayHintsConfig {
+                parameter_hints: false,
+                type_hints: false,
+                chaining_hints: false,
+                panic_hints: true,
+                discriminant_hints: false,
+                max_length: None,
+            },
+            r#"
+fn fail() -> ! { loop {} }
+fn f(v: &[i32], o: Option<i32>, d: i32) -> i32 {
+    let a = o.unwrap();
+          //^^^^^^^^^^ may panic: unwrap
+    let b = v[0];
+          //^^^^ may panic: index out of bounds
+    let c = d / a | d / 2;
+          //^^^^^ may panic: division by zero
+    let c = c - d;
+          //^^^^^ may panic: overflow in debug builds
+    if c < 0 {
+        fail();
+      //^^^^^^ panics
+    }
+    b
+}"#,
+        );
+    }
+
+    #[test]
+    fn discriminant_hints() {
+        check_with_config(
+            InlayHintsConfig {
+                parameter_hints: false,
+                type_hints: false,
+                chaining_hints: false,
+                panic_hints: false,
+                discriminant_hints: true,
+                max_length: None,
+            },
+            r#"
+const C: u8 = 1;
+#[repr(i8)]
+enum E {
+    A,
+  //^ = 0
+    B = -2,
+    C,
+  //^ = -1
+    D = 1 << 3,
+  //^^^^^^^^^^ = 8
+    F,
+  //^ = 9
+    G = C as i8,
+    H,
+}
+enum WithFields {
+    A(u32),
+    B { b: u32 },
+}
+#[repr(u8)]
+enum Explicit {
+    A(u32) = 0x10,
+    B { b: u32 },
+  //^^^^^^^^^^^^ = 17
+}"#,
+        );
+    }
 }
@@ -0,0 +1,49 @@
+//! Detects files which are too large for the expensive analyses, like diagnostics and inlay
+//! hints. Cheap features, like syntax highlighting and navigation, keep working for them.
+
+use ide_db::{base_db::SourceDatabaseExt, LineIndexDatabase, RootDatabase};
+
+use crate::FileId;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LargeFileConfig {
+    /// The size of a file in bytes above which it is considered large, or `0` for no limit.
+    pub max_file_size: usize,
+    /// The number of lines of a function above which its file is considered large, or `0` for
+    /// no limit.
+    pub max_fn_lines: usize,
+}
+
+pub(crate) fn is_large_file(db: &RootDatabase, config: &LargeFileConfig, file_id: FileId) -> bool {
+    if config.max_file_size != 0 && db.file_text(file_id).len() > config.max_file_size {
+        return true;
+    }
+    config.max_fn_lines != 0 && db.max_fn_body_lines(file_id) > config.max_fn_lines
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    use super::*;
+
+    fn check(ra_fixture: &str, config: LargeFileConfig, expected: bool) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        assert_eq!(analysis.is_large_file(file_id, &config).unwrap(), expected);
+    }
+
+    #[test]
+    fn large_files() {
+        let fixture = r#"
+fn small() {}
+fn large() {
+    let a = 1;
+    let b = 2;
+}
+"#;
+        check(fixture, LargeFileConfig { max_file_size: 0, max_fn_lines: 0 }, false);
+        check(fixture, LargeFileConfig { max_file_size: 40, max_fn_lines: 0 }, true);
+        check(fixture, LargeFileConfig { max_file_size: 0, max_fn_lines: 4 }, false);
+        check(fixture, LargeFileConfig { max_file_size: 0, max_fn_lines: 3 }, true);
+    }
+}
@@ -36,6 +36,7 @@ mod view_hir;
 mod hover;
 mod inlay_hints;
 mod join_lines;
+mod large_file;
 mod matching_brace;
 mod parent_module;
 mod references;
@@ -44,6 +45,7 @@ mod runnables;
 mod status;
 mod syntax_highlighting;
 mod syntax_tree;
+mod type_search;
 mod typing;
 mod markdown_remove;
 mod doc_links;
@@ -73,6 +75,7 @@ pub use crate::{
     folding_ranges::{Fold, FoldKind},
     hover::{HoverAction, HoverConfig, HoverGotoTypeData, HoverResult},
     inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
+    large_file::LargeFileConfig,
     markup::Markup,
     prime_caches::PrimeCachesProgress,
     references::{rename::RenameError, ReferenceSearchResult},
@@ -81,12 +84,13 @@ pub use crate::{
         tags::{Highlight, HlMod, HlMods, HlPunct, HlTag},
         HlRange,
     },
+    type_search::TypeSearchError,
 };
 pub use hir::{Documentation, Semantics};
 pub use ide_assists::{Assist, AssistConfig, AssistId, AssistKind};
 pub use ide_completion::{
-    CompletionConfig, CompletionItem, CompletionItemKind, CompletionScore, ImportEdit,
-    InsertTextFormat,
+    CompletionConfig, CompletionItem, CompletionItemKind, CompletionList, CompletionScore,
+    ImportEdit, InsertTextFormat,
 };
 pub use ide_db::{
     base_db::{
@@ -98,10 +102,10 @@ pub use ide_db::{
     line_index::{LineCol, LineColUtf16, LineIndex},
     search::{ReferenceAccess, SearchScope},
     source_change::{FileSystemEdit, SourceChange},
-    symbol_index::Query,
+    symbol_index::{ExcludedCode, Query},
     RootDatabase,
 };
-pub use ide_ssr::SsrError;
+pub use ide_ssr::{SsrError, SsrRule};
 pub use syntax::{TextRange, TextSize};
 pub use text_edit::{Indel, TextEdit};
 
@@ -135,6 +139,10 @@ impl AnalysisHost {
         self.db.update_lru_capacity(lru_capacity);
     }
 
+    pub fn update_excluded_code(&mut self, excluded_code: ExcludedCode) {
+        self.db.update_excluded_code(excluded_code);
+    }
+
     /// Returns a snapshot of the current state, which you can query for
     /// semantic information.
     pub fn analysis(&self) -> Analysis {
@@ -279,6 +287,16 @@ impl Analysis {
         self.with_db(|db| expand_macro::expand_macro(db, position))
     }
 
+    /// Maps a range of the definition of a `macro_rules!` macro to the arguments of the macro
+    /// call at `call` which the expansion puts within it.
+    pub fn macro_call_range_from_def(
+        &self,
+        call: FileRange,
+        def: FileRange,
+    ) -> Cancelable<Option<TextRange>> {
+        self.with_db(|db| expand_macro::macro_call_range_from_def(db, call, def))
+    }
+
     /// Returns an edit to remove all newlines in the range, cleaning up minor
     /// stuff like trailing commas.
     pub fn join_lines(&self, frange: FileRange) -> Cancelable<TextEdit> {
@@ -317,6 +335,12 @@ impl Analysis {
         self.with_db(|db| file_structure::file_structure(&db.parse(file_id).tree()))
     }
 
+    /// Returns whether the file is too large for expensive analyses, like diagnostics and inlay
+    /// hints.
+    pub fn is_large_file(&self, file_id: FileId, config: &LargeFileConfig) -> Cancelable<bool> {
+        self.with_db(|db| large_file::is_large_file(db, config, file_id))
+    }
+
     /// Returns a list of the places in the file where type hints can be displayed.
     pub fn inlay_hints(
         &self,
@@ -467,7 +491,7 @@ impl Analysis {
         &self,
         config: &CompletionConfig,
         position: FilePosition,
-    ) -> Cancelable<Option<Vec<CompletionItem>>> {
+    ) -> Cancelable<Option<CompletionList>> {
         self.with_db(|db| ide_completion::completions(db, config, position).map(Into::into))
     }
 
@@ -541,6 +565,16 @@ impl Analysis {
         self.with_db(|db| references::rename::will_rename_file(db, file_id, new_name_stem))
     }
 
+    /// Searches for functions matching a signature pattern like `fn(&str) -> usize`, or for
+    /// expressions in the given file matching a type pattern like `Option<_>`.
+    pub fn search_by_type(
+        &self,
+        query: &str,
+        file_id: Option<FileId>,
+    ) -> Cancelable<Result<Vec<FileRange>, TypeSearchError>> {
+        self.with_db(|db| type_search::search_by_type(db, query, file_id))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
@@ -556,6 +556,25 @@ fn main(s: Foo) {
         );
     }
 
+    #[test]
+    fn test_find_all_refs_raw_identifier() {
+        check(
+            r#"
+mod r#type { pub fn r#fn() {} }
+fn main() {
+    r#type::r#fn$0();
+    crate::r#type::r#fn();
+}
+"#,
+            expect![[r##"
+                r#fn Function FileId(0) 13..29 20..24
+
+                FileId(0) 56..60
+                FileId(0) 83..87
+            "##]],
+        );
+    }
+
     #[test]
     fn test_find_all_refs_impl_item_name() {
         check(
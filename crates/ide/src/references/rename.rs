@@ -2,16 +2,19 @@
 use std::fmt::{self, Display};
 
 use either::Either;
-use hir::{HasSource, InFile, Module, ModuleDef, ModuleSource, Semantics};
+use hir::{
+    Adt, AsAssocItem, AsName, AssocItem, AssocItemContainer, HasSource, HirDisplay, Impl, InFile,
+    Module, ModuleDef, ModuleSource, Name, ScopeDef, Semantics, StructKind,
+};
 use ide_db::{
-    base_db::{AnchoredPathBuf, FileId},
+    base_db::{AnchoredPathBuf, FileId, SourceDatabaseExt},
     defs::{Definition, NameClass, NameRefClass},
     search::FileReference,
     RootDatabase,
 };
 use stdx::never;
 use syntax::{
-    ast::{self, NameOwner},
+    ast::{self, ModuleItemOwner, NameOwner},
     lex_single_syntax_kind, AstNode, SyntaxKind, SyntaxNode, T,
 };
 use test_utils::mark;
@@ -77,10 +80,44 @@ pub(crate) fn rename_with_semantics(
     let syntax = source_file.syntax();
 
     let def = find_definition(sema, syntax, position)?;
-    match def {
+    let change = match def {
         Definition::ModuleDef(ModuleDef::Module(module)) => rename_mod(&sema, module, new_name),
         def => rename_reference(sema, def, new_name),
+    }?;
+    check_edits_are_local(sema.db, &change)?;
+    Ok(change)
+}
+
+/// Dependencies are read-only, so renames which would edit their sources are rejected, pointing
+/// to the external locations.
+fn check_edits_are_local(db: &RootDatabase, change: &SourceChange) -> RenameResult<()> {
+    let moved_files = change.file_system_edits.iter().map(|edit| match edit {
+        FileSystemEdit::MoveFile { src, .. } => *src,
+        FileSystemEdit::CreateFile { dst, .. } => dst.anchor,
+    });
+    let mut external = change
+        .source_file_edits
+        .keys()
+        .copied()
+        .chain(moved_files)
+        .filter_map(|file_id| {
+            let source_root = db.source_root(db.file_source_root(file_id));
+            if !source_root.is_library {
+                return None;
+            }
+            Some(source_root.path_for_file(&file_id)?.to_string())
+        })
+        .collect::<Vec<_>>();
+    if external.is_empty() {
+        return Ok(());
     }
+    external.sort();
+    external.dedup();
+    let locations = external.iter().map(|it| format!("`{}`", it)).collect::<Vec<_>>();
+    bail!(
+        "Cannot rename outside of the workspace, the rename would edit dependency sources: {}",
+        locations.join(", ")
+    )
 }
 
 pub(crate) fn will_rename_file(
@@ -92,6 +129,7 @@ pub(crate) fn will_rename_file(
     let module = sema.to_module_def(file_id)?;
     let mut change = rename_mod(&sema, module, new_name_stem).ok()?;
     change.file_system_edits.clear();
+    check_edits_are_local(db, &change).ok()?;
     Some(change)
 }
 
@@ -122,6 +160,15 @@ fn check_identifier(new_name: &str) -> RenameResult<IdentifierKind> {
     }
 }
 
+/// Returns the raw identifier for the keywords which can be used as one, like `r#type`.
+fn escape_keyword(new_name: &str) -> Option<String> {
+    match lex_single_syntax_kind(new_name)? {
+        (T![self], _) | (T![super], _) | (T![crate], _) => None,
+        (kind, None) if kind.is_keyword() => Some(format!("r#{}", new_name)),
+        _ => None,
+    }
+}
+
 fn find_definition(
     sema: &Semantics<RootDatabase>,
     syntax: &SyntaxNode,
@@ -271,9 +318,13 @@ fn rename_mod(
     module: Module,
     new_name: &str,
 ) -> RenameResult<SourceChange> {
+    let escaped = escape_keyword(new_name);
+    let new_name = escaped.as_deref().unwrap_or(new_name);
     if IdentifierKind::Ident != check_identifier(new_name)? {
         bail!("Invalid name `{0}`: cannot rename module to {0}", new_name);
     }
+    // The files of modules named with raw identifiers are named without the `r#`.
+    let file_stem = new_name.strip_prefix("r#").unwrap_or(new_name);
 
     let mut source_change = SourceChange::default();
 
@@ -282,9 +333,9 @@ fn rename_mod(
     if let ModuleSource::SourceFile(..) = def_source {
         // mod is defined in path/to/dir/mod.rs
         let path = if module.is_mod_rs(sema.db) {
-            format!("../{}/mod.rs", new_name)
+            format!("../{}/mod.rs", file_stem)
         } else {
-            format!("{}.rs", new_name)
+            format!("{}.rs", file_stem)
         };
         let dst = AnchoredPathBuf { anchor: file_id, path };
         let move_file = FileSystemEdit::MoveFile { src: file_id, dst };
@@ -442,6 +493,8 @@ fn rename_reference(
     def: Definition,
     new_name: &str,
 ) -> RenameResult<SourceChange> {
+    let escaped = escape_keyword(new_name);
+    let new_name = escaped.as_deref().unwrap_or(new_name);
     let ident_kind = check_identifier(new_name)?;
 
     let def_is_lbl_or_lt = matches!(
@@ -479,6 +532,10 @@ fn rename_reference(
         (IdentifierKind::Ident, _) | (IdentifierKind::Underscore, _) => mark::hit!(rename_ident),
     }
 
+    let qualifying_edits = match ident_kind {
+        IdentifierKind::Ident => check_conflicts(sema, def, new_name)?,
+        _ => Vec::new(),
+    };
     let usages = def.usages(sema).all();
     if !usages.is_empty() && ident_kind == IdentifierKind::Underscore {
         mark::hit!(rename_underscore_multiple);
@@ -489,11 +546,314 @@ fn rename_reference(
         source_edit_from_references(sema, file_id, &references, def, new_name)
     }));
 
+    source_change.extend(qualifying_edits);
+
     let (file_id, edit) = source_edit_from_def(sema, def, new_name)?;
     source_change.insert_source_edit(file_id, edit);
     Ok(source_change)
 }
 
+/// Checks that renaming `def` doesn't make it clash with another definition, or change what the
+/// existing names refer to. Returns the edits qualifying the paths which would otherwise refer to
+/// the renamed definition.
+fn check_conflicts(
+    sema: &Semantics<RootDatabase>,
+    def: Definition,
+    new_name: &str,
+) -> RenameResult<Vec<(FileId, TextEdit)>> {
+    let db = sema.db;
+    let old_name = match def.name(db) {
+        Some(it) => it,
+        None => return Ok(Vec::new()),
+    };
+    let new = ast::make::name(new_name).as_name();
+    let conflict =
+        |reason: String| format_err!("Cannot rename `{}` to `{}`: {}", old_name, new_name, reason);
+
+    match def {
+        Definition::Local(local) => {
+            check_local_conflicts(sema, local, &old_name, &new).map_err(conflict)?
+        }
+        Definition::Field(field) => {
+            if field.parent_def(db).fields(db).iter().any(|it| it.name(db) == new) {
+                return Err(conflict(format!("a field named `{}` already exists", new)));
+            }
+        }
+        Definition::ModuleDef(ModuleDef::Variant(variant)) => {
+            if variant.parent_enum(db).variants(db).iter().any(|it| it.name(db) == new) {
+                return Err(conflict(format!("a variant named `{}` already exists", new)));
+            }
+        }
+        Definition::ModuleDef(module_def) => match module_def.as_assoc_item(db) {
+            Some(assoc) => check_assoc_conflicts(db, assoc, &new).map_err(conflict)?,
+            None => return check_item_conflicts(sema, module_def, &new).map_err(conflict),
+        },
+        _ => (),
+    }
+    Ok(Vec::new())
+}
+
+fn check_local_conflicts(
+    sema: &Semantics<RootDatabase>,
+    local: hir::Local,
+    old_name: &Name,
+    new_name: &Name,
+) -> Result<(), String> {
+    let db = sema.db;
+    let declaration_start = |local: hir::Local| match local.source(db).value {
+        Either::Left(it) => it.syntax().text_range().start(),
+        Either::Right(it) => it.syntax().text_range().start(),
+    };
+    let start = declaration_start(local);
+
+    // A binding of the new name declared after the renamed one would shadow it.
+    let usages = Definition::Local(local).usages(sema).all();
+    for reference in usages.iter().flat_map(|(_, references)| references) {
+        let name_ref = match &reference.name {
+            ast::NameLike::NameRef(it) => it,
+            _ => continue,
+        };
+        if let Some(ScopeDef::Local(other)) = resolve_name(sema, name_ref.syntax(), new_name) {
+            if other != local && declaration_start(other) > start {
+                return Err(format!("the binding would be shadowed by another `{}`", new_name));
+            }
+        }
+    }
+
+    // Conversely, the renamed binding would shadow the outer bindings and items of the new name
+    // used in its scope.
+    let source = local.source(db).value;
+    let declaration = source.either(|it| it.syntax().clone(), |it| it.syntax().clone());
+    let body = declaration
+        .ancestors()
+        .find(|it| ast::Fn::can_cast(it.kind()) || ast::ClosureExpr::can_cast(it.kind()))
+        .unwrap_or_else(|| declaration.ancestors().last().unwrap());
+    for name_ref in body.descendants().filter_map(ast::NameRef::cast) {
+        if name_ref.as_name() != *new_name || !is_path_expr(&name_ref) {
+            continue;
+        }
+        let refers_to_outer = match resolve_name(sema, name_ref.syntax(), new_name) {
+            Some(ScopeDef::Local(other)) => other != local && declaration_start(other) < start,
+            Some(ScopeDef::ModuleDef(_)) => true,
+            _ => false,
+        };
+        let in_scope = matches!(
+            resolve_name(sema, name_ref.syntax(), old_name),
+            Some(ScopeDef::Local(it)) if it == local
+        );
+        if refers_to_outer && in_scope {
+            return Err(format!("the binding would shadow the `{}` used in its scope", new_name));
+        }
+    }
+    Ok(())
+}
+
+fn check_assoc_conflicts(
+    db: &RootDatabase,
+    assoc: AssocItem,
+    new_name: &Name,
+) -> Result<(), String> {
+    let is_type = matches!(assoc, AssocItem::TypeAlias(_));
+    let clashes = |it: &AssocItem| {
+        matches!(it, AssocItem::TypeAlias(_)) == is_type && it.name(db).as_ref() == Some(new_name)
+    };
+    let impl_ = match assoc.container(db) {
+        AssocItemContainer::Trait(trait_) => {
+            if trait_.items(db).iter().any(clashes) {
+                return Err(format!("the trait already has an item named `{}`", new_name));
+            }
+            return Ok(());
+        }
+        AssocItemContainer::Impl(it) => it,
+    };
+    if impl_.items(db).iter().any(clashes) {
+        return Err(format!("the impl already has an item named `{}`", new_name));
+    }
+    if impl_.trait_(db).is_some() {
+        return Ok(());
+    }
+
+    let ty = impl_.target_ty(db);
+    let krate = impl_.module(db).krate();
+    for other in Impl::all_in_crate(db, krate) {
+        if other == impl_ || other.target_ty(db) != ty {
+            continue;
+        }
+        match other.trait_(db) {
+            None if other.items(db).iter().any(clashes) => {
+                return Err(format!(
+                    "`{}` already has an item named `{}`",
+                    ty.display(db),
+                    new_name
+                ));
+            }
+            // Inherent methods take precedence over the trait ones, so the calls of the trait
+            // method would call the renamed one instead.
+            Some(trait_) if !is_type && trait_.items(db).iter().any(clashes) => {
+                return Err(format!(
+                    "the calls of `{}::{}` on `{}` would call the renamed method",
+                    trait_.name(db),
+                    new_name,
+                    ty.display(db)
+                ));
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+fn check_item_conflicts(
+    sema: &Semantics<RootDatabase>,
+    def: ModuleDef,
+    new_name: &Name,
+) -> Result<Vec<(FileId, TextEdit)>, String> {
+    let db = sema.db;
+    let module = match def.module(db) {
+        Some(it) => it,
+        None => return Ok(Vec::new()),
+    };
+    let namespaces = Namespaces::of(db, def);
+    for (name, scope_def) in module.scope(db, None) {
+        let other = match scope_def {
+            ScopeDef::ModuleDef(it) if name == *new_name => it,
+            _ => continue,
+        };
+        if !namespaces.overlap(Namespaces::of(db, other)) {
+            continue;
+        }
+        if other.module(db) == Some(module) {
+            return Err(format!("`{}` is already defined in the module", new_name));
+        }
+        if is_imported_by_name(db, module, new_name) {
+            return Err(format!("`{}` is already imported in the module", new_name));
+        }
+    }
+
+    // The definitions of a module shadow the names from glob imports and from the prelude, so
+    // the paths referring to those have to be qualified.
+    let source = module.definition_source(db);
+    if source.file_id.call_node(db).is_some() {
+        return Ok(Vec::new());
+    }
+    let syntax = match source.value {
+        ModuleSource::SourceFile(it) => it.syntax().clone(),
+        ModuleSource::Module(it) => it.syntax().clone(),
+        ModuleSource::BlockExpr(_) => return Ok(Vec::new()),
+    };
+    let file_id = source.file_id.original_file(db);
+    sema.parse(file_id);
+
+    let mut edit = TextEdit::builder();
+    for name_ref in syntax.descendants().filter_map(ast::NameRef::cast) {
+        if name_ref.as_name() != *new_name {
+            continue;
+        }
+        let path = match name_ref.syntax().parent().and_then(ast::PathSegment::cast) {
+            Some(segment) => segment.parent_path(),
+            None => continue,
+        };
+        if path.qualifier().is_some()
+            || path.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind()))
+            || sema.scope(name_ref.syntax()).module() != Some(module)
+        {
+            continue;
+        }
+        let referenced = match NameRefClass::classify(sema, &name_ref) {
+            Some(NameRefClass::Definition(Definition::ModuleDef(it))) => it,
+            _ => continue,
+        };
+        if referenced == def
+            || referenced.module(db) == Some(module)
+            || !namespaces.overlap(Namespaces::of(db, referenced))
+        {
+            continue;
+        }
+        let qualifier = referenced
+            .module(db)
+            .and_then(|parent| module.find_use_path(db, ModuleDef::Module(parent)))
+            .ok_or_else(|| format!("it would shadow `{}`", new_name))?;
+        edit.replace(name_ref.syntax().text_range(), format!("{}::{}", qualifier, name_ref));
+    }
+    Ok(vec![(file_id, edit.finish())])
+}
+
+/// Whether a `use` item of the module imports an item as `name`.
+fn is_imported_by_name(db: &RootDatabase, module: Module, name: &Name) -> bool {
+    let items = match module.definition_source(db).value {
+        ModuleSource::SourceFile(it) => it.items().collect::<Vec<_>>(),
+        ModuleSource::Module(it) => match it.item_list() {
+            Some(it) => it.items().collect(),
+            None => return false,
+        },
+        ModuleSource::BlockExpr(_) => return false,
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            ast::Item::Use(it) => Some(it),
+            _ => None,
+        })
+        .flat_map(|it| it.syntax().descendants().filter_map(ast::UseTree::cast))
+        .filter(|tree| tree.use_tree_list().is_none() && tree.star_token().is_none())
+        .filter_map(|tree| match tree.rename() {
+            Some(rename) => rename.name().map(|it| it.as_name()),
+            None => tree.path()?.segment()?.name_ref().map(|it| it.as_name()),
+        })
+        .any(|it| it == *name)
+}
+
+/// The namespaces a module level definition is in.
+#[derive(Clone, Copy)]
+struct Namespaces {
+    types: bool,
+    values: bool,
+}
+
+impl Namespaces {
+    fn of(db: &RootDatabase, def: ModuleDef) -> Namespaces {
+        let (types, values) = match def {
+            ModuleDef::Module(_)
+            | ModuleDef::Adt(Adt::Enum(_))
+            | ModuleDef::Adt(Adt::Union(_))
+            | ModuleDef::Trait(_)
+            | ModuleDef::TypeAlias(_)
+            | ModuleDef::BuiltinType(_) => (true, false),
+            ModuleDef::Adt(Adt::Struct(it)) => (true, it.kind(db) != StructKind::Record),
+            ModuleDef::Variant(it) => (true, it.kind(db) != StructKind::Record),
+            ModuleDef::Function(_) | ModuleDef::Const(_) | ModuleDef::Static(_) => (false, true),
+        };
+        Namespaces { types, values }
+    }
+
+    fn overlap(self, other: Namespaces) -> bool {
+        self.types && other.types || self.values && other.values
+    }
+}
+
+fn resolve_name(
+    sema: &Semantics<RootDatabase>,
+    node: &SyntaxNode,
+    name: &Name,
+) -> Option<ScopeDef> {
+    let mut res = None;
+    sema.scope(node).process_all_names(&mut |it, def| {
+        if res.is_none() && it == *name {
+            res = Some(def);
+        }
+    });
+    res
+}
+
+fn is_path_expr(name_ref: &ast::NameRef) -> bool {
+    let path = match name_ref.syntax().parent().and_then(ast::PathSegment::cast) {
+        Some(segment) => segment.parent_path(),
+        None => return false,
+    };
+    path.qualifier().is_none()
+        && path.syntax().parent().map_or(false, |it| ast::PathExpr::can_cast(it.kind()))
+}
+
 fn source_edit_from_def(
     sema: &Semantics<RootDatabase>,
     def: Definition,
@@ -589,6 +949,32 @@ mod tests {
         check("r#fn", r#"fn main() { let i$0 = 1; }"#, r#"fn main() { let r#fn = 1; }"#);
     }
 
+    #[test]
+    fn test_rename_in_dependency() {
+        check(
+            "bar",
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() { dep::foo$0(); }
+//- /dep.rs crate:dep library
+pub fn foo() {}
+"#,
+            "error: Cannot rename outside of the workspace, the rename would edit dependency sources: `/dep.rs`",
+        );
+        check(
+            "bar",
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() { let foo$0 = dep::Foo; }
+//- /dep.rs crate:dep library
+pub struct Foo;
+"#,
+            r#"
+fn main() { let bar = dep::Foo; }
+"#,
+        );
+    }
+
     #[test]
     fn test_rename_to_invalid_identifier1() {
         check(
@@ -610,9 +996,198 @@ mod tests {
     #[test]
     fn test_rename_to_invalid_identifier3() {
         check(
-            "let",
+            "super",
             r#"fn main() { let i$0 = 1; }"#,
-            "error: Invalid name `let`: not an identifier",
+            "error: Invalid name `super`: not an identifier",
+        );
+    }
+
+    #[test]
+    fn test_rename_to_keyword() {
+        check("let", r#"fn main() { let i$0 = 1; }"#, r#"fn main() { let r#let = 1; }"#);
+        check(
+            "type",
+            r#"
+struct Foo { ty$0: u32 }
+fn f(foo: Foo) -> u32 { foo.ty }
+"#,
+            r#"
+struct Foo { r#type: u32 }
+fn f(foo: Foo) -> u32 { foo.r#type }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_local_shadowed() {
+        check(
+            "y",
+            r#"
+fn main() {
+    let x$0 = 1;
+    let y = 2;
+    x + y;
+}
+"#,
+            "error: Cannot rename `x` to `y`: the binding would be shadowed by another `y`",
+        );
+    }
+
+    #[test]
+    fn test_rename_local_shadowing() {
+        check(
+            "x",
+            r#"
+fn main() {
+    let x = 1;
+    let y$0 = 2;
+    x + y;
+}
+"#,
+            "error: Cannot rename `y` to `x`: the binding would shadow the `x` used in its scope",
+        );
+        check(
+            "foo",
+            r#"
+fn foo() {}
+fn main() {
+    let y$0 = 2;
+    foo();
+}
+"#,
+            "error: Cannot rename `y` to `foo`: the binding would shadow the `foo` used in its scope",
+        );
+    }
+
+    #[test]
+    fn test_rename_local_without_conflicts() {
+        check(
+            "x",
+            r#"
+fn main() {
+    let x = 1;
+    x;
+    let y$0 = 2;
+    y;
+}
+"#,
+            r#"
+fn main() {
+    let x = 1;
+    x;
+    let x = 2;
+    x;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_field_conflict() {
+        check(
+            "bar",
+            r#"struct Foo { foo$0: u32, bar: u32 }"#,
+            "error: Cannot rename `foo` to `bar`: a field named `bar` already exists",
+        );
+        check(
+            "B",
+            r#"enum E { A$0, B }"#,
+            "error: Cannot rename `A` to `B`: a variant named `B` already exists",
+        );
+    }
+
+    #[test]
+    fn test_rename_item_conflict() {
+        check(
+            "bar",
+            r#"
+fn foo$0() {}
+fn bar() {}
+"#,
+            "error: Cannot rename `foo` to `bar`: `bar` is already defined in the module",
+        );
+        check(
+            "Bar",
+            r#"
+mod m { pub struct Bar; }
+use m::Bar;
+struct Foo$0;
+"#,
+            "error: Cannot rename `Foo` to `Bar`: `Bar` is already imported in the module",
+        );
+        check(
+            "bar",
+            r#"
+fn foo$0() {}
+struct bar {}
+"#,
+            r#"
+fn bar() {}
+struct bar {}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_item_qualifies_shadowed_paths() {
+        check(
+            "Bar",
+            r#"
+mod m { pub struct Bar; }
+use m::*;
+struct Foo$0;
+fn f(_: Foo) -> Bar { Bar }
+"#,
+            r#"
+mod m { pub struct Bar; }
+use m::*;
+struct Bar;
+fn f(_: Bar) -> m::Bar { m::Bar }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_assoc_item_conflict() {
+        check(
+            "bar",
+            r#"
+struct S;
+impl S {
+    fn foo$0(&self) {}
+    fn bar(&self) {}
+}
+"#,
+            "error: Cannot rename `foo` to `bar`: the impl already has an item named `bar`",
+        );
+        check(
+            "bar",
+            r#"
+struct S;
+impl S { fn foo$0(&self) {} }
+impl S { fn bar(&self) {} }
+"#,
+            "error: Cannot rename `foo` to `bar`: `S` already has an item named `bar`",
+        );
+        check(
+            "bar",
+            r#"
+trait Tr {
+    fn foo$0(&self);
+    fn bar(&self);
+}
+"#,
+            "error: Cannot rename `foo` to `bar`: the trait already has an item named `bar`",
+        );
+        check(
+            "bar",
+            r#"
+trait Tr { fn bar(&self); }
+struct S;
+impl S { fn foo$0(&self) {} }
+impl Tr for S { fn bar(&self) {} }
+"#,
+            "error: Cannot rename `foo` to `bar`: the calls of `Tr::bar` on `S` would call the renamed method",
         );
     }
 
@@ -973,6 +1548,75 @@ mod foo$0;
         );
     }
 
+    #[test]
+    fn test_rename_mod_to_keyword() {
+        check_expect(
+            "type",
+            r#"
+//- /lib.rs
+mod foo$0;
+use foo::S;
+//- /foo.rs
+pub struct S;
+"#,
+            expect![[r#"
+                SourceChange {
+                    source_file_edits: {
+                        FileId(
+                            0,
+                        ): TextEdit {
+                            indels: [
+                                Indel {
+                                    insert: "r#type",
+                                    delete: 4..7,
+                                },
+                                Indel {
+                                    insert: "r#type",
+                                    delete: 13..16,
+                                },
+                            ],
+                        },
+                    },
+                    file_system_edits: [
+                        MoveFile {
+                            src: FileId(
+                                1,
+                            ),
+                            dst: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "type.rs",
+                            },
+                        },
+                    ],
+                    is_snippet: false,
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_rename_from_raw_identifier() {
+        check(
+            "kind",
+            r#"
+struct Foo { r#type$0: u32 }
+fn f(foo: Foo) -> u32 {
+    let Foo { r#type } = foo;
+    foo.r#type + r#type
+}
+"#,
+            r#"
+struct Foo { kind: u32 }
+fn f(foo: Foo) -> u32 {
+    let Foo { kind: r#type } = foo;
+    foo.kind + r#type
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_rename_mod_in_use_tree() {
         check_expect(
@@ -40,10 +40,23 @@ impl fmt::Display for TestId {
 
 #[derive(Debug, Clone)]
 pub enum RunnableKind {
-    Test { test_id: TestId, attr: TestAttr },
-    TestMod { path: String },
-    Bench { test_id: TestId },
-    DocTest { test_id: TestId },
+    Test {
+        test_id: TestId,
+        attr: TestAttr,
+    },
+    TestMod {
+        path: String,
+    },
+    Bench {
+        test_id: TestId,
+    },
+    DocTest {
+        test_id: TestId,
+    },
+    /// All doctests of a module or impl, selected by the path prefix of their items.
+    DocTestMod {
+        path: String,
+    },
     Bin,
 }
 
@@ -67,6 +80,7 @@ impl Runnable {
             RunnableKind::TestMod { path } => format!("test-mod {}", path),
             RunnableKind::Bench { test_id } => format!("bench {}", test_id),
             RunnableKind::DocTest { test_id, .. } => format!("doctest {}", test_id),
+            RunnableKind::DocTestMod { path } => format!("doctest-mod {}", path),
             RunnableKind::Bin => {
                 target.map_or_else(|| "run binary".to_string(), |t| format!("run {}", t))
             }
@@ -76,7 +90,7 @@ impl Runnable {
     pub fn action(&self) -> &'static RunnableAction {
         match &self.kind {
             RunnableKind::Test { .. } | RunnableKind::TestMod { .. } => &TEST,
-            RunnableKind::DocTest { .. } => &DOCTEST,
+            RunnableKind::DocTest { .. } | RunnableKind::DocTestMod { .. } => &DOCTEST,
             RunnableKind::Bench { .. } => &BENCH,
             RunnableKind::Bin => &BIN,
         }
@@ -107,24 +121,46 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
 }
 
 fn runnables_mod(sema: &Semantics<RootDatabase>, acc: &mut Vec<Runnable>, module: hir::Module) {
-    acc.extend(module.declarations(sema.db).into_iter().filter_map(|def| {
+    let mut doctests = 0;
+    for def in module.declarations(sema.db) {
         let runnable = match def {
             hir::ModuleDef::Module(it) => runnable_mod(&sema, it),
             hir::ModuleDef::Function(it) => runnable_fn(&sema, it),
             _ => None,
         };
-        runnable.or_else(|| module_def_doctest(&sema, def))
-    }));
+        if let Some(runnable) = runnable.or_else(|| module_def_doctest(&sema, def)) {
+            doctests += matches!(runnable.kind, RunnableKind::DocTest { .. }) as usize;
+            acc.push(runnable);
+        }
+    }
 
-    acc.extend(module.impl_defs(sema.db).into_iter().flat_map(|it| it.items(sema.db)).filter_map(
-        |def| match def {
-            hir::AssocItem::Function(it) => {
-                runnable_fn(&sema, it).or_else(|| module_def_doctest(&sema, it.into()))
-            }
-            hir::AssocItem::Const(it) => module_def_doctest(&sema, it.into()),
-            hir::AssocItem::TypeAlias(it) => module_def_doctest(&sema, it.into()),
-        },
-    ));
+    for imp in module.impl_defs(sema.db) {
+        let impl_runnables = imp
+            .items(sema.db)
+            .into_iter()
+            .filter_map(|def| match def {
+                hir::AssocItem::Function(it) => {
+                    runnable_fn(&sema, it).or_else(|| module_def_doctest(&sema, it.into()))
+                }
+                hir::AssocItem::Const(it) => module_def_doctest(&sema, it.into()),
+                hir::AssocItem::TypeAlias(it) => module_def_doctest(&sema, it.into()),
+            })
+            .collect::<Vec<_>>();
+        let impl_doctest_count = impl_runnables
+            .iter()
+            .filter(|it| matches!(it.kind, RunnableKind::DocTest { .. }))
+            .count();
+        doctests += impl_doctest_count;
+        acc.extend(impl_runnables);
+        if impl_doctest_count > 1 {
+            acc.extend(runnable_impl_doctests(sema, module, imp));
+        }
+    }
+
+    // The doctests of the crate root are simply all of them.
+    if doctests > 1 && module.parent(sema.db).is_some() {
+        acc.push(runnable_mod_doctests(sema, module));
+    }
 
     for def in module.declarations(sema.db) {
         if let hir::ModuleDef::Module(submodule) = def {
@@ -173,8 +209,7 @@ pub(crate) fn runnable_mod(sema: &Semantics<RootDatabase>, def: hir::Module) ->
     if !has_test_function_or_multiple_test_submodules(sema, &def) {
         return None;
     }
-    let path =
-        def.path_to_root(sema.db).into_iter().rev().filter_map(|it| it.name(sema.db)).join("::");
+    let path = module_path(sema, def);
 
     let attrs = def.attrs(sema.db);
     let cfg = attrs.cfg();
@@ -182,6 +217,37 @@ pub(crate) fn runnable_mod(sema: &Semantics<RootDatabase>, def: hir::Module) ->
     Some(Runnable { nav, kind: RunnableKind::TestMod { path }, cfg })
 }
 
+/// Runs the doctests of all items in `def` and its submodules, as rustdoc filters doctests by
+/// their path.
+fn runnable_mod_doctests(sema: &Semantics<RootDatabase>, def: hir::Module) -> Runnable {
+    let path = module_path(sema, def);
+    let attrs = def.attrs(sema.db);
+    let nav = def.to_nav(sema.db);
+    Runnable { nav, kind: RunnableKind::DocTestMod { path }, cfg: attrs.cfg() }
+}
+
+/// Runs the doctests of the items of an impl. As they are selected by the path of the type, this
+/// includes the doctests of its other impls.
+fn runnable_impl_doctests(
+    sema: &Semantics<RootDatabase>,
+    module: hir::Module,
+    def: hir::Impl,
+) -> Option<Runnable> {
+    let adt = def.target_ty(sema.db).as_adt()?;
+    let name = adt.name(sema.db).to_string();
+    let path = match module_path(sema, module) {
+        module_path if module_path.is_empty() => name,
+        module_path => format!("{}::{}", module_path, name),
+    };
+    let mut nav = def.try_to_nav(sema.db)?;
+    nav.focus_range = None;
+    Some(Runnable { nav, kind: RunnableKind::DocTestMod { path }, cfg: None })
+}
+
+fn module_path(sema: &Semantics<RootDatabase>, def: hir::Module) -> String {
+    def.path_to_root(sema.db).into_iter().rev().filter_map(|it| it.name(sema.db)).join("::")
+}
+
 // FIXME: figure out a proper API here.
 pub(crate) fn doc_owner_to_def(
     sema: &Semantics<RootDatabase>,
@@ -645,6 +711,133 @@ impl Data {
         );
     }
 
+    #[test]
+    fn test_runnables_doc_tests_of_module_and_impl() {
+        check(
+            r#"
+//- /lib.rs
+$0
+mod foo {
+    /// ```
+    /// let x = 5;
+    /// ```
+    pub fn bar() {}
+
+    /// ```
+    /// let x = 5;
+    /// ```
+    pub struct Data;
+
+    impl Data {
+        /// ```
+        /// let x = 5;
+        /// ```
+        pub fn a() {}
+
+        /// ```
+        /// let x = 5;
+        /// ```
+        pub fn b() {}
+    }
+}
+"#,
+            &[&DOCTEST, &DOCTEST, &DOCTEST, &DOCTEST, &DOCTEST, &DOCTEST],
+            expect![[r#"
+                [
+                    Runnable {
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 15..73,
+                            name: "bar",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo::bar",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 79..138,
+                            name: "Data",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo::Data",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 164..232,
+                            name: "a",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo::Data::a",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 242..310,
+                            name: "b",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "foo::Data::b",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 144..316,
+                            name: "impl",
+                            kind: Impl,
+                        },
+                        kind: DocTestMod {
+                            path: "foo::Data",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 1..318,
+                            focus_range: 5..8,
+                            name: "foo",
+                            kind: Module,
+                        },
+                        kind: DocTestMod {
+                            path: "foo",
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_runnables_module() {
         check(
@@ -48,6 +48,7 @@ pub struct HlRange {
 //
 // The general rule is that a reference to an entity gets colored the same way as the entity itself.
 // We also give special modifier for `mut` and `&mut` local variables.
+// Code disabled by `#[cfg]` attributes gets the `inactive` modifier, so that it can be dimmed.
 pub(crate) fn highlight(
     db: &RootDatabase,
     file_id: FileId,
@@ -90,6 +91,9 @@ fn traverse(
     let mut current_macro_rules: Option<ast::MacroRules> = None;
     let mut macro_rules_highlighter = MacroRulesHighlighter::default();
     let mut inside_attribute = false;
+    // The outermost node disabled by `#[cfg]`, everything inside of it is inactive.
+    let mut inactive_node: Option<SyntaxNode> = None;
+    let root_is_inactive = root.ancestors().skip(1).any(|it| sema.inactive_cfg(&it).is_some());
 
     // Walk all nodes, keeping track of whether we are inside a macro or not.
     // If in macro, expand it first and highlight the expanded code.
@@ -103,6 +107,19 @@ fn traverse(
             continue;
         }
 
+        match &event {
+            WalkEvent::Enter(NodeOrToken::Node(node))
+                if inactive_node.is_none() && sema.inactive_cfg(node).is_some() =>
+            {
+                inactive_node = Some(node.clone())
+            }
+            WalkEvent::Leave(NodeOrToken::Node(node)) if inactive_node.as_ref() == Some(node) => {
+                inactive_node = None
+            }
+            _ => (),
+        }
+        let inactive = root_is_inactive || inactive_node.is_some();
+
         // Track "inside macro" state
         match event.clone().map(|it| it.into_node().and_then(ast::MacroCall::cast)) {
             WalkEvent::Enter(Some(mc)) => {
@@ -195,15 +212,26 @@ fn traverse(
             continue;
         }
 
-        if let Some((mut highlight, binding_hash)) = highlight::element(
+        let highlight = highlight::element(
             &sema,
             &mut bindings_shadow_count,
             syntactic_name_ref_highlighting,
             element_to_highlight.clone(),
-        ) {
+        );
+        let highlight = match highlight {
+            // Inactive tokens without a highlight of their own still have to be dimmed.
+            None if inactive && element.as_token().map_or(false, |it| it.kind() != WHITESPACE) => {
+                Some((HlTag::None.into(), None))
+            }
+            highlight => highlight,
+        };
+        if let Some((mut highlight, binding_hash)) = highlight {
             if inside_attribute {
                 highlight = highlight | HlMod::Attribute;
             }
+            if inactive {
+                highlight = highlight | HlMod::Inactive;
+            }
 
             hl.add(HlRange { range, highlight, binding_hash });
         }
@@ -56,6 +56,8 @@ pub enum HlMod {
     Static,
     /// Used for items in impls&traits.
     Associated,
+    /// Used for code disabled by `#[cfg]` attributes.
+    Inactive,
 
     /// Keep this last!
     Unsafe,
@@ -155,6 +157,7 @@ impl HlMod {
         HlMod::Callable,
         HlMod::Static,
         HlMod::Associated,
+        HlMod::Inactive,
         HlMod::Unsafe,
     ];
 
@@ -171,6 +174,7 @@ impl HlMod {
             HlMod::Callable => "callable",
             HlMod::Static => "static",
             HlMod::Associated => "associated",
+            HlMod::Inactive => "inactive",
         }
     }
 
@@ -565,6 +565,25 @@ fn test_extern_crate() {
     );
 }
 
+#[test]
+fn test_inactive_code_highlighting() {
+    check_highlighting(
+        r#"
+#[cfg(feature = "foo")]
+fn inactive(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {
+    #[cfg(test)]
+    let y = inactive(2);
+}
+"#,
+        expect_file!["./test_data/highlight_inactive.html"],
+        false,
+    );
+}
+
 #[test]
 fn test_associated_function() {
     check_highlighting(
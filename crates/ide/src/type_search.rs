@@ -0,0 +1,412 @@
+//! Finds the functions and expressions whose types match a type pattern. The pattern is
+//! compared with the inferred `hir::Type`s, resolving its paths in the scope of the searched
+//! file, so that different types with the same name aren't confused.
+
+use std::fmt;
+
+use hir::{HirDisplay, Module, ModuleDef, Name, PathResolution, Semantics, SemanticsScope};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    symbol_index::{self, FileSymbolKind, Query},
+    RootDatabase,
+};
+use syntax::ast::{self, AstNode};
+
+// Feature: Search By Type
+//
+// Searches the workspace for functions with a signature matching a pattern like
+// `fn(&str) -> Result<_, _>`, or the current file for expressions with a type matching a pattern
+// like `Vec<_>`.
+//
+// `_` matches any type, and a path without generic arguments matches the type with any arguments,
+// so `Option` matches `Option<i32>`. Paths are resolved in the current file. Paths which don't
+// resolve there match the types with the same name, in modules with the names of the path's
+// qualifier. Like in Rust, a function pattern without a return type only matches functions
+// returning `()`. Methods are matched with their `self` parameter as the first parameter.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: Search by type**
+// |===
+pub(crate) fn search_by_type(
+    db: &RootDatabase,
+    query: &str,
+    file_id: Option<FileId>,
+) -> Result<Vec<FileRange>, TypeSearchError> {
+    let pattern = ast::Type::parse(query.trim())
+        .map_err(|()| TypeSearchError(format!("`{}` is not a type pattern", query.trim())))?;
+    let sema = Semantics::new(db);
+    let scope = file_id.map(|file_id| sema.scope(sema.parse(file_id).syntax()));
+    let matcher = Matcher { sema: &sema, scope };
+    let mut res = match pattern {
+        ast::Type::FnPtrType(signature) => search_functions(&matcher, &signature),
+        pattern => {
+            let file_id = file_id.ok_or_else(|| {
+                TypeSearchError("searching for expressions requires a file".to_string())
+            })?;
+            search_exprs(&matcher, &pattern, file_id)
+        }
+    };
+    res.sort_by_key(|it| (it.file_id, it.range.start()));
+    Ok(res)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeSearchError(String);
+
+impl fmt::Display for TypeSearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for TypeSearchError {}
+
+fn search_functions(matcher: &Matcher, signature: &ast::FnPtrType) -> Vec<FileRange> {
+    let sema = matcher.sema;
+    let params = match signature.param_list() {
+        Some(it) => it.params().filter_map(|it| it.ty()).collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+    let ret_type = signature.ret_type().and_then(|it| it.ty());
+
+    // An empty query matches all symbols of the workspace.
+    symbol_index::world_symbols(sema.db, Query::new(String::new()))
+        .into_iter()
+        .filter(|symbol| symbol.kind == FileSymbolKind::Function)
+        .filter(|symbol| {
+            let root = sema.parse(symbol.file_id);
+            let func = match ast::Fn::cast(symbol.ptr.to_node(root.syntax())) {
+                Some(it) => it,
+                None => return false,
+            };
+            let func = match sema.to_def(&func) {
+                Some(it) => it,
+                None => return false,
+            };
+            let func_params = func.assoc_fn_params(sema.db);
+            if func_params.len() != params.len() {
+                return false;
+            }
+            params
+                .iter()
+                .zip(&func_params)
+                .all(|(pattern, param)| matcher.matches(pattern, param.ty()))
+                && matcher.ret_type_matches(ret_type.as_ref(), &func.ret_type(sema.db))
+        })
+        .map(|symbol| FileRange {
+            file_id: symbol.file_id,
+            range: symbol.name_range.unwrap_or(symbol.range),
+        })
+        .collect()
+}
+
+fn search_exprs(matcher: &Matcher, pattern: &ast::Type, file_id: FileId) -> Vec<FileRange> {
+    let sema = matcher.sema;
+    let root = sema.parse(file_id);
+    root.syntax()
+        .descendants()
+        .filter_map(ast::Expr::cast)
+        // Parentheses don't add anything to the expression inside.
+        .filter(|expr| !matches!(expr, ast::Expr::ParenExpr(_)))
+        .filter(|expr| sema.type_of_expr(expr).map_or(false, |ty| matcher.matches(pattern, &ty)))
+        .map(|expr| FileRange { file_id, range: expr.syntax().text_range() })
+        .collect()
+}
+
+struct Matcher<'a> {
+    sema: &'a Semantics<'a, RootDatabase>,
+    /// The scope the paths of the pattern are resolved in, if the search has a file.
+    scope: Option<SemanticsScope<'a>>,
+}
+
+impl Matcher<'_> {
+    /// Checks if the type matches the pattern. Types which can't be written down, like closures
+    /// or unknown types, only match `_`.
+    fn matches(&self, pattern: &ast::Type, ty: &hir::Type) -> bool {
+        let db = self.sema.db;
+        match pattern {
+            ast::Type::InferType(_) => true,
+            _ if ty.is_unknown() => false,
+            ast::Type::ParenType(pattern) => self.matches_inner(pattern.ty(), Some(ty.clone())),
+            ast::Type::PathType(pattern) => {
+                pattern.path().map_or(false, |path| self.path_matches(&path, ty))
+            }
+            ast::Type::RefType(pattern) => {
+                pattern.mut_token().is_some() == ty.is_mutable_reference()
+                    && self.matches_inner(pattern.ty(), ty.remove_ref())
+            }
+            ast::Type::PtrType(pattern) => {
+                ty.is_raw_ptr()
+                    && pattern.mut_token().is_some() == ty.is_mutable_raw_ptr()
+                    && self.matches_inner(pattern.ty(), ty.type_parameters().next())
+            }
+            ast::Type::SliceType(pattern) => {
+                ty.is_slice() && self.matches_inner(pattern.ty(), ty.type_parameters().next())
+            }
+            ast::Type::ArrayType(pattern) => {
+                ty.is_array() && self.matches_inner(pattern.ty(), ty.type_parameters().next())
+            }
+            ast::Type::TupleType(pattern) => {
+                ty.is_tuple() && self.all_match(pattern.fields(), ty.tuple_fields(db))
+            }
+            ast::Type::NeverType(_) => ty.is_never(),
+            ast::Type::FnPtrType(pattern) => {
+                let callable = match ty.as_callable(db) {
+                    Some(it) if ty.is_fn() => it,
+                    _ => return false,
+                };
+                let params = pattern.param_list().into_iter().flat_map(|it| it.params());
+                self.all_match(
+                    params.filter_map(|it| it.ty()),
+                    callable.params(db).into_iter().map(|(_, ty)| ty).collect(),
+                ) && self.ret_type_matches(
+                    pattern.ret_type().and_then(|it| it.ty()).as_ref(),
+                    &callable.return_type(),
+                )
+            }
+            ast::Type::ImplTraitType(pattern) => match ty.as_impl_traits(db) {
+                Some(traits) => self.bounds_match(pattern.type_bound_list(), &traits),
+                None => false,
+            },
+            ast::Type::DynTraitType(pattern) => match ty.as_dyn_trait() {
+                Some(trait_) => self.bounds_match(pattern.type_bound_list(), &[trait_]),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn matches_inner(&self, pattern: Option<ast::Type>, ty: Option<hir::Type>) -> bool {
+        match (pattern, ty) {
+            (Some(pattern), Some(ty)) => self.matches(&pattern, &ty),
+            _ => false,
+        }
+    }
+
+    /// Like in Rust, a missing return type stands for `()`.
+    fn ret_type_matches(&self, pattern: Option<&ast::Type>, ty: &hir::Type) -> bool {
+        match pattern {
+            Some(pattern) => self.matches(pattern, ty),
+            None => ty.is_unit(),
+        }
+    }
+
+    fn all_match(&self, patterns: impl Iterator<Item = ast::Type>, types: Vec<hir::Type>) -> bool {
+        let patterns = patterns.collect::<Vec<_>>();
+        patterns.len() == types.len()
+            && patterns.iter().zip(&types).all(|(pattern, ty)| self.matches(pattern, ty))
+    }
+
+    fn path_matches(&self, path: &ast::Path, ty: &hir::Type) -> bool {
+        let db = self.sema.db;
+        let segment = match path.segment() {
+            Some(it) => it,
+            None => return false,
+        };
+        let resolved = self.scope.as_ref().and_then(|scope| scope.speculative_resolve(path));
+        let def_matches = match (resolved, ty.as_adt()) {
+            (Some(PathResolution::Def(ModuleDef::BuiltinType(builtin))), _) => {
+                let module = self.scope.as_ref().and_then(|it| it.module());
+                return module.map_or(false, |module| builtin.ty(db, module).is_same_type(ty));
+            }
+            (Some(PathResolution::Def(def @ ModuleDef::Adt(_))), adt) => {
+                adt.map_or(false, |adt| def == adt.into())
+            }
+            // Builtin types are only known by their name if the path can't be resolved.
+            (_, None) => {
+                return segment.generic_arg_list().is_none()
+                    && ty.display(db).to_string() == path.syntax().text().to_string()
+            }
+            (_, Some(adt)) => self.names_match(path, adt.name(db), adt.module(db)),
+        };
+        if !def_matches {
+            return false;
+        }
+        // Without generic arguments, the pattern matches all instances of the type.
+        match segment.generic_arg_list() {
+            None => true,
+            Some(list) => {
+                let patterns = list.generic_args().filter_map(|arg| match arg {
+                    ast::GenericArg::TypeArg(it) => it.ty(),
+                    _ => None,
+                });
+                self.all_match(patterns, ty.type_parameters().collect())
+            }
+        }
+    }
+
+    /// Checks if each trait in the pattern's bounds is one of `traits`.
+    fn bounds_match(&self, pattern: Option<ast::TypeBoundList>, traits: &[hir::Trait]) -> bool {
+        let db = self.sema.db;
+        let trait_matches = |path: &ast::Path, trait_: hir::Trait| match self
+            .scope
+            .as_ref()
+            .and_then(|scope| scope.speculative_resolve(path))
+        {
+            Some(PathResolution::Def(def)) => def == trait_.into(),
+            _ => self.names_match(path, trait_.name(db), trait_.module(db)),
+        };
+        pattern.into_iter().flat_map(|it| it.bounds()).all(|bound| {
+            let path = match bound.ty() {
+                Some(ast::Type::PathType(it)) => it.path(),
+                _ => None,
+            };
+            path.map_or(false, |path| traits.iter().any(|&trait_| trait_matches(&path, trait_)))
+        })
+    }
+
+    /// For paths which don't resolve, checks that the path names the item `name` in `module`:
+    /// the last segment is the name, and the qualifier lists the names of the enclosing modules.
+    fn names_match(&self, path: &ast::Path, name: Name, module: Module) -> bool {
+        let db = self.sema.db;
+        let segment_name =
+            |it: &ast::Path| it.segment()?.name_ref().map(|it| it.text().to_string());
+        if segment_name(path) != Some(name.to_string()) {
+            return false;
+        }
+        let mut modules = module.path_to_root(db).into_iter();
+        let mut qualifier = path.qualifier();
+        while let Some(path) = qualifier {
+            // `crate`, `self` and `super` aren't compared.
+            let segment_name = match segment_name(&path) {
+                Some(it) => it,
+                None => break,
+            };
+            let module_name = match modules.next() {
+                Some(module) => match module.name(db) {
+                    Some(name) => name.to_string(),
+                    None => match module.krate().display_name(db) {
+                        Some(name) => name.to_string(),
+                        None => return false,
+                    },
+                },
+                None => return false,
+            };
+            if module_name != segment_name {
+                return false;
+            }
+            qualifier = path.qualifier();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::extract_annotations;
+
+    use crate::fixture;
+
+    fn check(query: &str, ra_fixture: &str) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let text = analysis.file_text(file_id).unwrap();
+        let mut expected =
+            extract_annotations(&text).into_iter().map(|(range, _)| range).collect::<Vec<_>>();
+        expected.sort_by_key(|it| it.start());
+        let actual = analysis
+            .search_by_type(query, Some(file_id))
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|it| it.range)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn search_functions_by_signature() {
+        check(
+            "fn(&str) -> Result<_, _>",
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+struct S;
+impl S {
+    fn parse(&self, s: &str) -> Result<u32, ()> { loop {} }
+}
+fn parse(s: &str) -> Result<u32, ()> { loop {} }
+ //^^^^^
+fn parse_n(s: &str, n: usize) -> Result<u32, ()> { loop {} }
+fn parse_mut(s: &mut str) -> Result<u32, ()> { loop {} }
+fn len(s: &str) -> usize { 0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn search_methods_and_unit_functions() {
+        check(
+            "fn(&S)",
+            r#"
+struct S;
+impl S {
+    fn clear(&self) {}
+     //^^^^^
+    fn len(&self) -> usize { 0 }
+}
+fn reset(s: &S) {}
+ //^^^^^
+"#,
+        );
+    }
+
+    #[test]
+    fn search_exprs_by_type() {
+        check(
+            "Option",
+            r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn main() {
+    let a = Some(1);
+          //^^^^^^^
+    let b: Option<()> = None;
+                      //^^^^
+    a;
+  //^
+    let c = 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn search_exprs_by_generic_arguments() {
+        check(
+            "(_, &[u8])",
+            r#"
+fn main() {
+    let s: &[u8] = &[];
+    let t = (1, s);
+          //^^^^^^
+    let u = (s, 1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn search_exprs_by_resolved_type() {
+        check(
+            "Foo",
+            r#"
+mod a { pub struct Foo; }
+mod b { pub struct Foo; }
+use a::Foo;
+fn main() {
+    let x = Foo;
+          //^^^
+    let y = b::Foo;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let (analysis, file_id) = fixture::file("");
+        let err = analysis.search_by_type("fn(", Some(file_id)).unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "`fn(` is not a type pattern");
+    }
+}
@@ -0,0 +1,201 @@
+use hir::HirDisplay;
+use syntax::{
+    ast::{self, AstNode},
+    TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: add_explicit_closure_types
+//
+// Specify the inferred parameter and return types of a closure.
+//
+// ```
+// fn main() {
+//     let double = |$0x| x * 2;
+//     double(21);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let double = |x: i32| -> i32 { x * 2 };
+//     double(21);
+// }
+// ```
+pub(crate) fn add_explicit_closure_types(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let closure = ctx.find_node_at_offset::<ast::ClosureExpr>()?;
+    let param_list = closure.param_list()?;
+    // Only offered on the closure head, so that it doesn't show up on every expression
+    // inside of the body.
+    let head = TextRange::new(
+        closure.syntax().text_range().start(),
+        param_list.syntax().text_range().end(),
+    );
+    if !head.contains_range(ctx.frange.range) {
+        return None;
+    }
+    let module = ctx.sema.scope(closure.syntax()).module()?;
+    let callable = ctx.sema.type_of_expr(&closure.clone().into())?.as_callable(ctx.db())?;
+    let display = |ty: &hir::Type| {
+        if ty.contains_unknown() {
+            return None;
+        }
+        ty.display_source_code(ctx.db(), module.into()).ok()
+    };
+
+    let mut param_types = Vec::new();
+    for (param, (_, ty)) in param_list.params().zip(callable.params(ctx.db())) {
+        let ascribed = param.ty();
+        // Types without placeholders are left as they are.
+        if let Some(ty) = &ascribed {
+            if ty.syntax().descendants().find_map(ast::InferType::cast).is_none() {
+                continue;
+            }
+        }
+        if let Some(inferred) = display(&ty) {
+            param_types.push((param, ascribed, inferred));
+        }
+    }
+    let ret_type = callable.return_type();
+    let ret_type = match closure.ret_type() {
+        Some(_) => None,
+        None if ret_type.is_unit() => None,
+        None => display(&ret_type),
+    };
+    if param_types.is_empty() && ret_type.is_none() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("add_explicit_closure_types", AssistKind::RefactorRewrite),
+        "Insert explicit closure types",
+        head,
+        |builder| {
+            for (param, ascribed, inferred) in param_types {
+                match (ascribed, param.pat()) {
+                    (Some(ascribed), _) => {
+                        builder.replace(ascribed.syntax().text_range(), inferred)
+                    }
+                    (None, Some(pat)) => {
+                        builder.insert(pat.syntax().text_range().end(), format!(": {}", inferred))
+                    }
+                    (None, None) => (),
+                }
+            }
+            if let (Some(ret_type), Some(body)) = (ret_type, closure.body()) {
+                builder.insert(param_list.syntax().text_range().end(), format!(" -> {}", ret_type));
+                // A closure with a return type needs a block body.
+                if !matches!(body, ast::Expr::BlockExpr(_)) {
+                    builder.replace(body.syntax().text_range(), format!("{{ {} }}", body));
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_explicit_closure_types_target() {
+        check_assist_target(
+            add_explicit_closure_types,
+            "fn f() { let c = move |$0x| x + 1; c(1); }",
+            "move |x|",
+        );
+    }
+
+    #[test]
+    fn adds_param_and_return_types() {
+        check_assist(
+            add_explicit_closure_types,
+            r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+struct S { name: u32 }
+fn f(s: &S) {
+    let by_name = |$0a, (b, _)| if a == b { Some(a) } else { None };
+    by_name(s.name, (2u32, s));
+}
+"#,
+            r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+struct S { name: u32 }
+fn f(s: &S) {
+    let by_name = |a: u32, (b, _): (u32, &S)| -> Option<u32> { if a == b { Some(a) } else { None } };
+    by_name(s.name, (2u32, s));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_block_body_and_replaces_placeholders() {
+        check_assist(
+            add_explicit_closure_types,
+            r#"
+struct W<T>(T);
+fn f() {
+    let c = |x: W<_>, y: u8|$0 {
+        x.0 + y as i64
+    };
+    c(W(1i64), 2);
+}
+"#,
+            r#"
+struct W<T>(T);
+fn f() {
+    let c = |x: W<i64>, y: u8| -> i64 {
+        x.0 + y as i64
+    };
+    c(W(1i64), 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_unit_return_type() {
+        check_assist(
+            add_explicit_closure_types,
+            r#"
+fn f() {
+    let c = |$0x| { let _ = x; };
+    c(1u8);
+}
+"#,
+            r#"
+fn f() {
+    let c = |x: u8| { let _ = x; };
+    c(1u8);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_everything_is_explicit() {
+        check_assist_not_applicable(
+            add_explicit_closure_types,
+            "fn f() { let c = |$0x: i32| -> i32 { x }; }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            add_explicit_closure_types,
+            "fn f() { let c = |x| x$0 + 1; c(1); }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_unknown_types() {
+        check_assist_not_applicable(add_explicit_closure_types, "fn f() { let c = |$0x| x; }");
+    }
+}
@@ -0,0 +1,413 @@
+use std::{convert::TryInto, fmt};
+
+use syntax::{
+    ast::{self, edit::IndentLevel, AttrsOwner, NameOwner},
+    AstNode, TextSize,
+};
+
+use crate::{utils::vis_offset, AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: add_explicit_enum_discriminants
+//
+// Writes out the discriminants of the variants of a C-like enum.
+//
+// ```
+// enum Color$0 {
+//     Red,
+//     Green = 4,
+//     Blue,
+// }
+// ```
+// ->
+// ```
+// enum Color {
+//     Red = 0,
+//     Green = 4,
+//     Blue = 5,
+// }
+// ```
+pub(crate) fn add_explicit_enum_discriminants(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let enum_ = enum_at_offset(ctx)?;
+    let discriminants = discriminants(&enum_)?;
+    if discriminants.iter().all(|(_, it)| it.is_explicit) {
+        return None;
+    }
+
+    let target = enum_.syntax().text_range();
+    acc.add(
+        AssistId("add_explicit_enum_discriminants", AssistKind::RefactorRewrite),
+        "Add explicit discriminants",
+        target,
+        |builder| {
+            for (offset, text) in implicit_discriminants(&discriminants) {
+                builder.insert(offset, text);
+            }
+        },
+    )
+}
+
+// Assist: add_enum_repr
+//
+// Adds a `#[repr]` attribute with an integer type to a C-like enum, and writes out the
+// discriminants of its variants.
+//
+// ```
+// enum Color$0 {
+//     Red,
+//     Green,
+// }
+// ```
+// ->
+// ```
+// #[repr(u8)]
+// enum Color {
+//     Red = 0,
+//     Green = 1,
+// }
+// ```
+pub(crate) fn add_enum_repr(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let enum_ = enum_at_offset(ctx)?;
+    if enum_.attrs().any(|attr| attr.simple_name().as_deref() == Some("repr")) {
+        return None;
+    }
+    let discriminants = discriminants(&enum_)?;
+    let all_explicit = discriminants.iter().all(|(_, it)| it.is_explicit);
+
+    let offset = vis_offset(enum_.syntax());
+    let indent = IndentLevel::from_node(enum_.syntax());
+    let target = enum_.syntax().text_range();
+    let group = GroupLabel("Add `#[repr]`".into());
+    for &repr in REPRS {
+        let fits = discriminants.iter().all(|(_, it)| match it.value {
+            Value::Known(value) => repr.fits(value),
+            Value::Offset(..) => true,
+        });
+        if !fits {
+            continue;
+        }
+        let label = match all_explicit {
+            true => format!("Add `#[repr({})]`", repr.name),
+            false => format!("Add `#[repr({})]` and explicit discriminants", repr.name),
+        };
+        acc.add_group(
+            &group,
+            AssistId("add_enum_repr", AssistKind::RefactorRewrite),
+            label,
+            target,
+            |builder| {
+                builder.insert(offset, format!("#[repr({})]\n{}", repr.name, indent));
+                for (offset, text) in implicit_discriminants(&discriminants) {
+                    builder.insert(offset, text);
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+#[derive(Clone, Copy)]
+struct Repr {
+    name: &'static str,
+    min: i128,
+    max: i128,
+}
+
+impl Repr {
+    fn fits(self, value: i128) -> bool {
+        self.min <= value && value <= self.max
+    }
+}
+
+const REPRS: &[Repr] = &[
+    Repr { name: "u8", min: 0, max: u8::MAX as i128 },
+    Repr { name: "u16", min: 0, max: u16::MAX as i128 },
+    Repr { name: "u32", min: 0, max: u32::MAX as i128 },
+    Repr { name: "u64", min: 0, max: u64::MAX as i128 },
+    Repr { name: "i32", min: i32::MIN as i128, max: i32::MAX as i128 },
+    Repr { name: "i64", min: i64::MIN as i128, max: i64::MAX as i128 },
+];
+
+/// The enum whose header is at the cursor, if it's a C-like enum.
+fn enum_at_offset(ctx: &AssistContext) -> Option<ast::Enum> {
+    let enum_: ast::Enum = ctx.find_node_at_offset()?;
+    let variant_list = enum_.variant_list()?;
+    // Only offered on the header of the enum, so that it doesn't get in the way in the variants.
+    if variant_list.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    enum_.name()?;
+    Some(enum_)
+}
+
+struct Discriminant {
+    value: Value,
+    is_explicit: bool,
+}
+
+/// The value of a discriminant.
+enum Value {
+    Known(i128),
+    /// An offset from an explicit discriminant which couldn't be evaluated, like `BASE + 1`.
+    Offset(String, i128),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Known(value) => write!(f, "{}", value),
+            Value::Offset(base, offset) => write!(f, "{} + {}", base, offset),
+        }
+    }
+}
+
+/// The discriminants of the variants of the enum with the offset where they would be written,
+/// or `None` if the enum isn't C-like.
+fn discriminants(enum_: &ast::Enum) -> Option<Vec<(TextSize, Discriminant)>> {
+    let mut res = Vec::new();
+    let mut next = Value::Known(0);
+    for variant in enum_.variant_list()?.variants() {
+        if variant.field_list().is_some() {
+            return None;
+        }
+        let offset = variant.name()?.syntax().text_range().end();
+        let discriminant = match variant.expr() {
+            Some(expr) => {
+                let value = match eval(&expr) {
+                    Some(value) => Value::Known(value),
+                    None => match expr {
+                        ast::Expr::PathExpr(_)
+                        | ast::Expr::Literal(_)
+                        | ast::Expr::ParenExpr(_) => Value::Offset(expr.to_string(), 0),
+                        _ => Value::Offset(format!("({})", expr), 0),
+                    },
+                };
+                Discriminant { value, is_explicit: true }
+            }
+            None => Discriminant { value: next, is_explicit: false },
+        };
+        next = match &discriminant.value {
+            Value::Known(value) => Value::Known(value.checked_add(1)?),
+            Value::Offset(base, offset) => Value::Offset(base.clone(), offset + 1),
+        };
+        res.push((offset, discriminant));
+    }
+    if res.is_empty() {
+        return None;
+    }
+    Some(res)
+}
+
+fn implicit_discriminants(
+    discriminants: &[(TextSize, Discriminant)],
+) -> impl Iterator<Item = (TextSize, String)> + '_ {
+    discriminants
+        .iter()
+        .filter(|(_, it)| !it.is_explicit)
+        .map(|(offset, it)| (*offset, format!(" = {}", it.value)))
+}
+
+/// Evaluates the discriminant expressions made of integer literals and arithmetic.
+fn eval(expr: &ast::Expr) -> Option<i128> {
+    match expr {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::IntNumber(it) => it.value()?.try_into().ok(),
+            _ => None,
+        },
+        ast::Expr::ParenExpr(it) => eval(&it.expr()?),
+        ast::Expr::PrefixExpr(it) => match it.op_kind()? {
+            ast::PrefixOp::Neg => eval(&it.expr()?)?.checked_neg(),
+            _ => None,
+        },
+        ast::Expr::BinExpr(it) => {
+            let lhs = eval(&it.lhs()?)?;
+            let rhs = eval(&it.rhs()?)?;
+            match it.op_kind()? {
+                ast::BinOp::Addition => lhs.checked_add(rhs),
+                ast::BinOp::Subtraction => lhs.checked_sub(rhs),
+                ast::BinOp::Multiplication => lhs.checked_mul(rhs),
+                ast::BinOp::LeftShift => lhs.checked_shl(rhs.try_into().ok()?),
+                ast::BinOp::BitwiseOr => Some(lhs | rhs),
+                ast::BinOp::BitwiseAnd => Some(lhs & rhs),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn sequential_discriminants() {
+        check_assist(
+            add_explicit_enum_discriminants,
+            r#"
+pub enum $0E {
+    A,
+    B,
+    C = 1 << 3,
+    D,
+    E = -2,
+    F,
+}
+"#,
+            r#"
+pub enum E {
+    A = 0,
+    B = 1,
+    C = 1 << 3,
+    D = 9,
+    E = -2,
+    F = -1,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn discriminants_after_unevaluated_expressions() {
+        check_assist(
+            add_explicit_enum_discriminants,
+            r#"
+const BASE: u8 = 4;
+enum E$0 {
+    A = BASE,
+    B,
+    C = BASE * 2 + 1,
+    D,
+}
+"#,
+            r#"
+const BASE: u8 = 4;
+enum E {
+    A = BASE,
+    B = BASE + 1,
+    C = BASE * 2 + 1,
+    D = (BASE * 2 + 1) + 1,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            add_explicit_enum_discriminants,
+            r#"
+enum E$0 {
+    A = 0,
+    B = 1,
+}
+"#,
+        );
+        check_assist_not_applicable(
+            add_explicit_enum_discriminants,
+            r#"
+enum E$0 {
+    A,
+    B(u32),
+}
+"#,
+        );
+        check_assist_not_applicable(
+            add_explicit_enum_discriminants,
+            r#"
+enum E {
+    A$0,
+    B,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_repr_with_discriminants() {
+        check_assist_by_label(
+            add_enum_repr,
+            r#"
+mod m {
+    /// Docs.
+    #[derive(Clone, Copy)]
+    pub enum E$0 {
+        A,
+        B = 300,
+    }
+}
+"#,
+            r#"
+mod m {
+    /// Docs.
+    #[derive(Clone, Copy)]
+    #[repr(u32)]
+    pub enum E {
+        A = 0,
+        B = 300,
+    }
+}
+"#,
+            "Add `#[repr(u32)]` and explicit discriminants",
+        );
+        check_assist_by_label(
+            add_enum_repr,
+            r#"
+enum E$0 {
+    A = 0,
+    B = 1,
+}
+"#,
+            r#"
+#[repr(u8)]
+enum E {
+    A = 0,
+    B = 1,
+}
+"#,
+            "Add `#[repr(u8)]`",
+        );
+    }
+
+    #[test]
+    fn repr_types_fit_discriminants() {
+        check_assist_not_applicable(
+            add_enum_repr,
+            r#"
+#[repr(C)]
+enum E$0 {
+    A,
+}
+"#,
+        );
+        check_assist_by_label(
+            add_enum_repr,
+            r#"
+enum E$0 {
+    A = -1,
+    B = 300,
+}
+"#,
+            r#"
+#[repr(i32)]
+enum E {
+    A = -1,
+    B = 300,
+}
+"#,
+            "Add `#[repr(i32)]`",
+        );
+        check_assist_not_applicable(
+            add_enum_repr,
+            r#"
+enum E$0 {
+    A = 1 << 64,
+}
+"#,
+        );
+    }
+}
@@ -1,11 +1,11 @@
 use ide_db::traits::resolve_target_trait;
-use syntax::ast::{self, AstNode};
+use syntax::ast::{self, edit::IndentLevel, AstNode};
 
 use crate::{
     assist_context::{AssistContext, Assists},
     utils::add_trait_assoc_items_to_impl,
     utils::DefaultMethods,
-    utils::{filter_assoc_items, render_snippet, Cursor},
+    utils::{filter_assoc_items, pretty::reformat, render_snippet, Cursor},
     AssistId, AssistKind,
 };
 
@@ -116,10 +116,11 @@ fn add_missing_impl_members_inner(
     let target = impl_def.syntax().text_range();
     acc.add(AssistId(assist_id, AssistKind::QuickFix), label, target, |builder| {
         let target_scope = ctx.sema.scope(impl_def.syntax());
+        let indent = IndentLevel::from_node(impl_def.syntax());
         let (new_impl_def, first_new_item) =
             add_trait_assoc_items_to_impl(&ctx.sema, missing_items, trait_, impl_def, target_scope);
         match ctx.config.snippet_cap {
-            None => builder.replace(target, new_impl_def.to_string()),
+            None => builder.replace(target, reformat(&new_impl_def.to_string(), indent)),
             Some(cap) => {
                 let mut cursor = Cursor::Before(first_new_item.syntax());
                 let placeholder;
@@ -134,7 +135,7 @@ fn add_missing_impl_members_inner(
                 builder.replace_snippet(
                     cap,
                     target,
-                    render_snippet(cap, new_impl_def.syntax(), cursor),
+                    reformat(&render_snippet(cap, new_impl_def.syntax(), cursor), indent),
                 )
             }
         };
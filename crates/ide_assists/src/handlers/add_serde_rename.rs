@@ -0,0 +1,422 @@
+use syntax::{
+    ast::{self, edit::IndentLevel, AttrsOwner, NameOwner},
+    AstNode, SyntaxKind, SyntaxToken, TextRange, T,
+};
+
+use crate::{utils::vis_offset, AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: add_serde_rename_all
+//
+// Adds `#[serde(rename_all = "..")]` to a struct deriving `Serialize` or `Deserialize`, for the
+// naming conventions which agree with the fields already renamed.
+//
+// ```
+// #[derive(Serialize)]
+// struct Config$0 {
+//     #[serde(rename = "maxSize")]
+//     max_size: u32,
+//     min_size: u32,
+// }
+// ```
+// ->
+// ```
+// #[derive(Serialize)]
+// #[serde(rename_all = "camelCase")]
+// struct Config {
+//     max_size: u32,
+//     min_size: u32,
+// }
+// ```
+pub(crate) fn add_serde_rename_all(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (strukt, fields) = serde_struct_at_offset(ctx)?;
+    let has_renames = fields.iter().any(|it| matches!(it.rename, Some(Rename::Value { .. })));
+
+    let offset = vis_offset(strukt.syntax());
+    let indent = IndentLevel::from_node(strukt.syntax());
+    let target = strukt.syntax().text_range();
+    let group = GroupLabel("Add `#[serde(rename_all)]`".into());
+    for convention in consistent_conventions(&fields) {
+        let renames_any = fields.iter().any(|it| it.rename.is_none() && it.renamed(convention));
+        if !renames_any && !has_renames {
+            continue;
+        }
+        acc.add_group(
+            &group,
+            AssistId("add_serde_rename_all", AssistKind::RefactorRewrite),
+            format!("Add `#[serde(rename_all = \"{}\")]`", convention.name),
+            target,
+            |builder| {
+                builder.insert(
+                    offset,
+                    format!("#[serde(rename_all = \"{}\")]\n{}", convention.name, indent),
+                );
+                // The renames are now implied by `rename_all`.
+                for field in &fields {
+                    if let Some(Rename::Value { only_attr: Some(attr), .. }) = &field.rename {
+                        builder.delete(attr_range(attr));
+                    }
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+// Assist: add_serde_field_renames
+//
+// Adds `#[serde(rename = "..")]` to the fields of a struct deriving `Serialize` or `Deserialize`
+// which aren't renamed yet, following a naming convention.
+//
+// ```
+// #[derive(Deserialize)]
+// struct Config$0 {
+//     max_size: u32,
+//     #[serde(rename = "minSize")]
+//     min_size: u32,
+// }
+// ```
+// ->
+// ```
+// #[derive(Deserialize)]
+// struct Config {
+//     #[serde(rename = "maxSize")]
+//     max_size: u32,
+//     #[serde(rename = "minSize")]
+//     min_size: u32,
+// }
+// ```
+pub(crate) fn add_serde_field_renames(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (strukt, fields) = serde_struct_at_offset(ctx)?;
+
+    let target = strukt.syntax().text_range();
+    let group = GroupLabel("Add `#[serde(rename)]` to fields".into());
+    for convention in consistent_conventions(&fields) {
+        let to_rename = fields
+            .iter()
+            .filter(|it| it.rename.is_none() && it.renamed(convention))
+            .collect::<Vec<_>>();
+        if to_rename.is_empty() {
+            continue;
+        }
+        acc.add_group(
+            &group,
+            AssistId("add_serde_field_renames", AssistKind::RefactorRewrite),
+            format!("Rename fields to {} with `#[serde(rename)]`", convention.name),
+            target,
+            |builder| {
+                for field in to_rename {
+                    let indent = IndentLevel::from_node(field.field.syntax());
+                    builder.insert(
+                        vis_offset(field.field.syntax()),
+                        format!(
+                            "#[serde(rename = \"{}\")]\n{}",
+                            (convention.rename)(&field.name),
+                            indent
+                        ),
+                    );
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+/// A value of serde's `rename_all`.
+struct Convention {
+    name: &'static str,
+    rename: fn(&str) -> String,
+}
+
+/// The conventions changing snake case field names, renaming them the way serde does.
+const CONVENTIONS: &[Convention] = &[
+    Convention { name: "camelCase", rename: to_camel_case },
+    Convention { name: "PascalCase", rename: to_pascal_case },
+    Convention { name: "SCREAMING_SNAKE_CASE", rename: |it| it.to_ascii_uppercase() },
+    Convention { name: "kebab-case", rename: |it| it.replace('_', "-") },
+    Convention {
+        name: "SCREAMING-KEBAB-CASE",
+        rename: |it| it.to_ascii_uppercase().replace('_', "-"),
+    },
+];
+
+fn to_pascal_case(field: &str) -> String {
+    let mut res = String::new();
+    let mut capitalize = true;
+    for c in field.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            res.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+fn to_camel_case(field: &str) -> String {
+    let pascal = to_pascal_case(field);
+    match pascal.chars().next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + &pascal[first.len_utf8()..],
+        None => pascal,
+    }
+}
+
+struct Field {
+    field: ast::RecordField,
+    /// The name without the `r#` of raw identifiers, as serde sees it.
+    name: String,
+    rename: Option<Rename>,
+}
+
+impl Field {
+    fn renamed(&self, convention: &Convention) -> bool {
+        (convention.rename)(&self.name) != self.name
+    }
+}
+
+enum Rename {
+    /// `#[serde(rename = "value")]`, with the attribute if it contains nothing else.
+    Value { value: String, only_attr: Option<ast::Attr> },
+    /// Renames which differ between serialization and deserialization, like
+    /// `#[serde(rename(serialize = "a", deserialize = "b"))]`.
+    Other,
+}
+
+/// The conventions which give the fields with a `rename` their current name.
+fn consistent_conventions(fields: &[Field]) -> impl Iterator<Item = &'static Convention> + '_ {
+    CONVENTIONS.iter().filter(move |convention| {
+        fields.iter().all(|field| match &field.rename {
+            Some(Rename::Value { value, .. }) => (convention.rename)(&field.name) == *value,
+            Some(Rename::Other) | None => true,
+        })
+    })
+}
+
+/// The struct at the cursor with its fields, if it derives one of serde's traits and doesn't
+/// use `rename_all` yet.
+fn serde_struct_at_offset(ctx: &AssistContext) -> Option<(ast::Struct, Vec<Field>)> {
+    let strukt: ast::Struct = ctx.find_node_at_offset()?;
+    let field_list = match strukt.field_list()? {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+    // Only offered on the header of the struct, so that it doesn't get in the way in the fields.
+    if field_list.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    let derives_serde =
+        strukt.attrs().filter(|it| it.simple_name().as_deref() == Some("derive")).any(|attr| {
+            attr_tokens(&attr).iter().any(|it| matches!(it.text(), "Serialize" | "Deserialize"))
+        });
+    if !derives_serde {
+        return None;
+    }
+    let has_rename_all = serde_attrs(&strukt)
+        .any(|attr| attr_tokens(&attr).iter().any(|it| it.text() == "rename_all"));
+    if has_rename_all {
+        return None;
+    }
+
+    let fields = field_list
+        .fields()
+        .map(|field| {
+            let name = field.name()?.text().trim_start_matches("r#").to_string();
+            let rename = field_rename(&field);
+            Some(Field { field, name, rename })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fields.is_empty() {
+        return None;
+    }
+    Some((strukt, fields))
+}
+
+fn field_rename(field: &ast::RecordField) -> Option<Rename> {
+    serde_attrs(field).find_map(|attr| {
+        let tokens = attr_tokens(&attr);
+        let position = tokens.iter().position(|it| it.text() == "rename")?;
+        match &tokens[position + 1..] {
+            [eq, value, ..] if eq.kind() == T![=] && value.kind() == SyntaxKind::STRING => {
+                let only_attr = match tokens.len() {
+                    // `(`, `rename`, `=`, the value and `)`.
+                    5 => Some(attr.clone()),
+                    _ => None,
+                };
+                let value = value.text().trim_matches('"').to_string();
+                Some(Rename::Value { value, only_attr })
+            }
+            _ => Some(Rename::Other),
+        }
+    })
+}
+
+fn serde_attrs(node: &impl AttrsOwner) -> impl Iterator<Item = ast::Attr> {
+    node.attrs().filter(|it| it.simple_name().as_deref() == Some("serde"))
+}
+
+fn attr_tokens(attr: &ast::Attr) -> Vec<SyntaxToken> {
+    match attr.token_tree() {
+        Some(tt) => tt
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| !it.kind().is_trivia())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// The attribute with the whitespace following it.
+fn attr_range(attr: &ast::Attr) -> TextRange {
+    let range = attr.syntax().text_range();
+    match attr.syntax().next_sibling_or_token() {
+        Some(next) if next.kind() == SyntaxKind::WHITESPACE => range.cover(next.text_range()),
+        _ => range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn rename_all() {
+        check_assist_by_label(
+            add_serde_rename_all,
+            r#"
+#[derive(Debug, serde::Serialize)]
+pub struct Config$0 {
+    pub max_size: u32,
+    r#type: String,
+}
+"#,
+            r#"
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub struct Config {
+    pub max_size: u32,
+    r#type: String,
+}
+"#,
+            "Add `#[serde(rename_all = \"SCREAMING-KEBAB-CASE\")]`",
+        );
+    }
+
+    #[test]
+    fn rename_all_consistent_with_renames() {
+        check_assist_by_label(
+            add_serde_rename_all,
+            r#"
+mod config {
+    #[derive(Deserialize)]
+    struct Config$0 {
+        #[serde(rename = "MaxSize")]
+        max_size: u32,
+        #[serde(default, rename = "MinSize")]
+        min_size: u32,
+        #[serde(rename(serialize = "size"))]
+        size: u32,
+    }
+}
+"#,
+            r#"
+mod config {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Config {
+        max_size: u32,
+        #[serde(default, rename = "MinSize")]
+        min_size: u32,
+        #[serde(rename(serialize = "size"))]
+        size: u32,
+    }
+}
+"#,
+            "Add `#[serde(rename_all = \"PascalCase\")]`",
+        );
+        check_assist_not_applicable(
+            add_serde_rename_all,
+            r#"
+#[derive(Serialize)]
+struct Config$0 {
+    #[serde(rename = "maxSize")]
+    max_size: u32,
+    #[serde(rename = "MinSize")]
+    min_size: u32,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn field_renames() {
+        check_assist_by_label(
+            add_serde_field_renames,
+            r#"
+#[derive(Serialize)]
+struct Config$0 {
+    /// Docs.
+    pub max_size: u32,
+    #[serde(rename = "min-size")]
+    min_size: u32,
+    id: u32,
+}
+"#,
+            r#"
+#[derive(Serialize)]
+struct Config {
+    /// Docs.
+    #[serde(rename = "max-size")]
+    pub max_size: u32,
+    #[serde(rename = "min-size")]
+    min_size: u32,
+    id: u32,
+}
+"#,
+            "Rename fields to kebab-case with `#[serde(rename)]`",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            add_serde_rename_all,
+            r#"
+#[derive(Debug)]
+struct Config$0 {
+    max_size: u32,
+}
+"#,
+        );
+        check_assist_not_applicable(
+            add_serde_rename_all,
+            r#"
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Config$0 {
+    max_size: u32,
+}
+"#,
+        );
+        check_assist_not_applicable(
+            add_serde_field_renames,
+            r#"
+#[derive(Serialize)]
+struct Config {
+    max_size$0: u32,
+}
+"#,
+        );
+        check_assist_not_applicable(
+            add_serde_field_renames,
+            r#"
+#[derive(Serialize)]
+struct Config$0(u32);
+"#,
+        );
+    }
+}
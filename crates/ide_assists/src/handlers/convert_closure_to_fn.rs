@@ -0,0 +1,829 @@
+use hir::{AsAssocItem, HasSource, HirDisplay, Local, PathResolution};
+use ide_db::{
+    base_db::FileId,
+    defs::{Definition, NameRefClass},
+    search::{ReferenceAccess, SearchScope},
+};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    algo::{find_node_at_range, SyntaxRewriter},
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, ArgListOwner, GenericParamsOwner, NameOwner,
+    },
+    AstNode, Direction, SyntaxKind, SyntaxNode, TextRange, T,
+};
+use test_utils::mark;
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    utils::pretty::reformat,
+    AssistId, AssistKind,
+};
+
+// Assist: convert_closure_to_fn
+//
+// Converts a closure into a function, which takes the variables the closure captures as
+// parameters.
+//
+// ```
+// struct Counter(u32);
+//
+// fn main() {
+//     let counter = Counter(0);
+//     let add = |$0x: u32| counter.0 + x;
+//     add(1);
+// }
+// ```
+// ->
+// ```
+// struct Counter(u32);
+//
+// fn main() {
+//     let counter = Counter(0);
+//     add(1, &counter);
+// }
+//
+// fn add(x: u32, counter: &Counter) -> u32 {
+//     counter.0 + x
+// }
+// ```
+pub(crate) fn convert_closure_to_fn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let closure: ast::ClosureExpr = ctx.find_node_at_offset()?;
+    let body = closure.body()?;
+    // Only offered on the head of the closure, so that it doesn't get in the way in its body.
+    if body.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    if closure.async_token().is_some() || closure.static_token().is_some() {
+        return None;
+    }
+    let db = ctx.db();
+    let module = ctx.sema.scope(closure.syntax()).module()?;
+
+    let mut params = Vec::new();
+    for param in closure.param_list()?.params() {
+        let pat = param.pat()?;
+        let ty = match param.ty() {
+            Some(ty) => ty.to_string(),
+            None => ctx.sema.type_of_pat(&pat)?.display_source_code(db, module.into()).ok()?,
+        };
+        params.push((pat, ty));
+    }
+    let ret_ty = match closure.ret_type() {
+        Some(ret_ty) => Some(ret_ty.ty()?.to_string()),
+        None => {
+            let ty = ctx.sema.type_of_expr(&body)?;
+            match ty.is_unit() {
+                true => None,
+                false => Some(ty.display_source_code(db, module.into()).ok()?),
+            }
+        }
+    };
+
+    let mut captures = Vec::new();
+    let mut self_capture = None;
+    for local in captured_locals(ctx, &closure, &body) {
+        let capture = Capture::new(ctx, &closure, &body, local)?;
+        if local.is_self(db) {
+            self_capture = Some(capture);
+        } else {
+            captures.push(capture);
+        }
+    }
+
+    let method = enclosing_inherent_method(&closure);
+    let callee = match (&self_capture, &method) {
+        (Some(_), Some(_)) => Callee::Method,
+        (None, Some(_)) => Callee::Assoc,
+        (None, None) => Callee::Free,
+        (Some(_), None) => return None,
+    };
+    let insert_after = match method {
+        Some(method) => method.syntax().clone(),
+        None => closure.syntax().ancestors().find(|it| {
+            matches!(
+                it.parent().map(|parent| parent.kind()),
+                Some(SyntaxKind::SOURCE_FILE) | Some(SyntaxKind::ITEM_LIST)
+            )
+        })?,
+    };
+
+    // A closure bound to a variable becomes a function named like the variable, which replaces
+    // the calls of the closure.
+    let binding =
+        closure.syntax().parent().and_then(ast::LetStmt::cast).and_then(|stmt| {
+            match stmt.pat()? {
+                ast::Pat::IdentPat(pat)
+                    if pat.ref_token().is_none() && pat.at_token().is_none() =>
+                {
+                    Some((stmt, pat))
+                }
+                _ => None,
+            }
+        });
+    let (name, usages) = match &binding {
+        Some((_, pat)) => {
+            let local = ctx.sema.to_def(pat)?;
+            let usages = binding_usages(ctx, local);
+            if usages.iter().any(|(_, call)| call.is_none())
+                && (!captures.is_empty() || callee == Callee::Method)
+            {
+                mark::hit!(convert_closure_to_fn_used_as_value);
+                return None;
+            }
+            (pat.name()?.to_string(), usages)
+        }
+        None => ("fun_name".to_string(), Vec::new()),
+    };
+
+    let target = closure.syntax().text_range();
+    acc.add(
+        AssistId("convert_closure_to_fn", AssistKind::RefactorRewrite),
+        "Convert closure to function",
+        target,
+        |builder| {
+            let capture_args = captures.iter().map(Capture::to_arg).collect::<Vec<_>>();
+            match &binding {
+                Some((stmt, _)) => {
+                    builder.delete(range_with_whitespace(stmt.syntax(), Direction::Prev));
+                    for (path, call) in &usages {
+                        match call {
+                            Some(call) => {
+                                let args = call
+                                    .arg_list()
+                                    .into_iter()
+                                    .flat_map(|it| it.args())
+                                    .map(|it| it.to_string())
+                                    .chain(capture_args.iter().cloned());
+                                let call_text = callee.call(&name, args);
+                                builder.replace(call.syntax().text_range(), call_text);
+                            }
+                            None => builder.replace(path.syntax().text_range(), callee.path(&name)),
+                        }
+                    }
+                }
+                None if captures.is_empty() && callee != Callee::Method => {
+                    builder.replace(target, callee.path(&name));
+                }
+                None => {
+                    let names = params
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, (pat, _))| match pat {
+                            ast::Pat::IdentPat(pat)
+                                if pat.ref_token().is_none()
+                                    && pat.mut_token().is_none()
+                                    && pat.at_token().is_none() =>
+                            {
+                                pat.to_string()
+                            }
+                            _ => format!("arg{}", idx),
+                        })
+                        .collect::<Vec<_>>();
+                    let args = names.iter().cloned().chain(capture_args);
+                    let move_ = if closure.move_token().is_some() { "move " } else { "" };
+                    let closure_text =
+                        format!("{}|{}| {}", move_, names.join(", "), callee.call(&name, args));
+                    builder.replace(target, closure_text);
+                }
+            }
+
+            let mut fn_params = Vec::new();
+            if let Some(capture) = &self_capture {
+                fn_params.push(match capture.kind {
+                    CaptureKind::MutRef => "&mut self".to_string(),
+                    CaptureKind::Value | CaptureKind::SharedRef => "&self".to_string(),
+                });
+            }
+            fn_params.extend(params.iter().map(|(pat, ty)| format!("{}: {}", pat, ty)));
+            fn_params.extend(captures.iter().map(|it| it.to_param(ctx, module)));
+
+            let new_indent = IndentLevel::from_node(&insert_after);
+            let old_indent = IndentLevel::from_node(closure.syntax());
+            let body = rewrite_capture_usages(ctx, &captures, &body);
+            let body = match body {
+                ast::Expr::BlockExpr(block) => block.dedent(old_indent),
+                expr => {
+                    make::block_expr(None, Some(expr.dedent(old_indent).indent(IndentLevel(1))))
+                }
+            };
+
+            let mut fn_def = String::new();
+            let cursor = match (binding.is_none(), ctx.config.snippet_cap) {
+                (true, Some(_)) => "$0",
+                _ => "",
+            };
+            format_to!(fn_def, "\n\n{}fn {}{}({})", new_indent, cursor, name, fn_params.join(", "));
+            if let Some(ret_ty) = &ret_ty {
+                format_to!(fn_def, " -> {}", ret_ty);
+            }
+            format_to!(fn_def, " {}", body.indent(new_indent));
+            let fn_def = reformat(&fn_def, IndentLevel(0));
+
+            let offset = insert_after.text_range().end();
+            match ctx.config.snippet_cap {
+                Some(cap) if !cursor.is_empty() => builder.insert_snippet(cap, offset, fn_def),
+                _ => builder.insert(offset, fn_def),
+            }
+        },
+    )
+}
+
+// Assist: convert_fn_to_closure
+//
+// Inlines a function which is only passed as an argument once into a closure.
+//
+// ```
+// fn double(x: u32) -> u32 { x * 2 }
+//
+// fn apply(f: impl Fn(u32) -> u32) {}
+//
+// fn main() {
+//     apply(double$0);
+// }
+// ```
+// ->
+// ```
+// fn apply(f: impl Fn(u32) -> u32) {}
+//
+// fn main() {
+//     apply(|x| x * 2);
+// }
+// ```
+pub(crate) fn convert_fn_to_closure(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let path_expr: ast::PathExpr = ctx.find_node_at_offset()?;
+    path_expr.syntax().parent().and_then(ast::ArgList::cast)?;
+    let function = match ctx.sema.resolve_path(&path_expr.path()?)? {
+        PathResolution::Def(hir::ModuleDef::Function(it)) => it,
+        _ => return None,
+    };
+    let db = ctx.db();
+    if function.as_assoc_item(db).is_some() {
+        return None;
+    }
+    // The body of the function is resolved in its module, which has to be the one of the call for
+    // the names to keep resolving.
+    if ctx.sema.scope(path_expr.syntax()).module()? != function.module(db) {
+        return None;
+    }
+    let source = function.source(db)?;
+    if source.file_id != ctx.frange.file_id.into() {
+        return None;
+    }
+    let fn_ = source.value;
+    if fn_.generic_param_list().is_some()
+        || fn_.const_token().is_some()
+        || fn_.async_token().is_some()
+        || fn_.unsafe_token().is_some()
+        || fn_.abi().is_some()
+    {
+        return None;
+    }
+    let pats = fn_.param_list()?.params().map(|it| it.pat()).collect::<Option<Vec<_>>>()?;
+    let body = fn_.body()?;
+
+    let usages = Definition::ModuleDef(function.into()).usages(&ctx.sema).all();
+    if usages.file_ranges().count() != 1 {
+        mark::hit!(convert_fn_to_closure_used_more_than_once);
+        return None;
+    }
+
+    let target = path_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_fn_to_closure", AssistKind::RefactorInline),
+        format!("Inline `{}` into a closure", path_expr),
+        target,
+        |builder| {
+            let new_indent = IndentLevel::from_node(path_expr.syntax());
+            let body = match (body.statements().next(), body.tail_expr()) {
+                (None, Some(tail_expr)) => {
+                    tail_expr.dedent(IndentLevel::from_node(tail_expr.syntax())).indent(new_indent)
+                }
+                _ => ast::Expr::from(body.dedent(IndentLevel::from_node(fn_.syntax())))
+                    .indent(new_indent),
+            };
+            let closure = format!("|{}| {}", pats.iter().join(", "), body);
+            builder.replace(target, closure);
+            builder.delete(range_with_whitespace(fn_.syntax(), Direction::Next));
+        },
+    )
+}
+
+/// How the generated function is called.
+#[derive(Clone, Copy, PartialEq)]
+enum Callee {
+    Free,
+    /// An associated function of the impl of the method the closure is in.
+    Assoc,
+    /// A method, for closures capturing `self`.
+    Method,
+}
+
+impl Callee {
+    fn path(self, name: &str) -> String {
+        match self {
+            Callee::Free => name.to_string(),
+            Callee::Assoc => format!("Self::{}", name),
+            Callee::Method => format!("self.{}", name),
+        }
+    }
+
+    fn call(self, name: &str, args: impl Iterator<Item = String>) -> String {
+        format!("{}({})", self.path(name), args.format(", "))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CaptureKind {
+    Value,
+    SharedRef,
+    MutRef,
+}
+
+/// A variable from outside of the closure which is used in it.
+struct Capture {
+    local: Local,
+    name: String,
+    ty: hir::Type,
+    is_mutated: bool,
+    kind: CaptureKind,
+}
+
+impl Capture {
+    fn new(
+        ctx: &AssistContext,
+        closure: &ast::ClosureExpr,
+        body: &ast::Expr,
+        local: Local,
+    ) -> Option<Capture> {
+        let db = ctx.db();
+        let name = local.name(db)?.to_string();
+        let ty = local.ty(db);
+        let is_mutated = local_usages(ctx, local, body)
+            .iter()
+            .any(|(path, access)| *access == Some(ReferenceAccess::Write) || is_mut_use(ctx, path));
+        let kind = if closure.move_token().is_some() || (!is_mutated && ty.is_copy(db)) {
+            CaptureKind::Value
+        } else if is_mutated {
+            CaptureKind::MutRef
+        } else {
+            CaptureKind::SharedRef
+        };
+        Some(Capture { local, name, ty, is_mutated, kind })
+    }
+
+    fn to_arg(&self) -> String {
+        match self.kind {
+            CaptureKind::Value => self.name.clone(),
+            CaptureKind::SharedRef => format!("&{}", self.name),
+            CaptureKind::MutRef => format!("&mut {}", self.name),
+        }
+    }
+
+    fn to_param(&self, ctx: &AssistContext, module: hir::Module) -> String {
+        let ty = self
+            .ty
+            .display_source_code(ctx.db(), module.into())
+            .unwrap_or_else(|_| "_".to_string());
+        match self.kind {
+            CaptureKind::Value if self.is_mutated => format!("mut {}: {}", self.name, ty),
+            CaptureKind::Value => format!("{}: {}", self.name, ty),
+            CaptureKind::SharedRef => format!("{}: &{}", self.name, ty),
+            CaptureKind::MutRef => format!("{}: &mut {}", self.name, ty),
+        }
+    }
+}
+
+/// The locals defined outside of the closure which are used in its body.
+fn captured_locals(
+    ctx: &AssistContext,
+    closure: &ast::ClosureExpr,
+    body: &ast::Expr,
+) -> Vec<Local> {
+    let db = ctx.db();
+    body.syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .filter_map(|name_ref| NameRefClass::classify(&ctx.sema, &name_ref))
+        .filter_map(|class| match class.referenced(db) {
+            Definition::Local(local) => Some(local),
+            _ => None,
+        })
+        .filter(|local| {
+            let source = local.source(db);
+            let range = match &source.value {
+                either::Either::Left(pat) => pat.syntax().text_range(),
+                either::Either::Right(self_param) => self_param.syntax().text_range(),
+            };
+            source.file_id.original_file(db) == ctx.frange.file_id
+                && !closure.syntax().text_range().contains_range(range)
+        })
+        .unique()
+        .collect()
+}
+
+/// The path expressions referring to `local` in `node`, with their access.
+fn local_usages(
+    ctx: &AssistContext,
+    local: Local,
+    node: &ast::Expr,
+) -> Vec<(ast::PathExpr, Option<ReferenceAccess>)> {
+    let range = node.syntax().text_range();
+    Definition::Local(local)
+        .usages(&ctx.sema)
+        .in_scope(SearchScope::single_file(ctx.frange.file_id))
+        .all()
+        .into_iter()
+        .flat_map(|(_, references)| references)
+        .filter(|reference| range.contains_range(reference.range))
+        .filter_map(|reference| {
+            let path = find_node_at_range::<ast::PathExpr>(node.syntax(), reference.range)?;
+            Some((path, reference.access))
+        })
+        .collect()
+}
+
+/// Whether the use of the variable needs it to be mutable, like `&mut x` or `x.push(1)`.
+fn is_mut_use(ctx: &AssistContext, path: &ast::PathExpr) -> bool {
+    let mut expr = ast::Expr::from(path.clone());
+    loop {
+        let parent = match expr.syntax().parent().and_then(ast::Expr::cast) {
+            Some(it) => it,
+            None => return false,
+        };
+        match &parent {
+            ast::Expr::RefExpr(it) => return it.mut_token().is_some(),
+            ast::Expr::MethodCallExpr(call) => {
+                let access = ctx
+                    .sema
+                    .resolve_method_call(call)
+                    .and_then(|it| it.self_param(ctx.db()))
+                    .map(|it| it.access(ctx.db()));
+                return matches!(access, Some(hir::Access::Exclusive));
+            }
+            ast::Expr::FieldExpr(_) => expr = parent,
+            _ => return false,
+        }
+    }
+}
+
+/// Rewrites the uses of the variables captured by reference, which are references in the
+/// generated function.
+fn rewrite_capture_usages(
+    ctx: &AssistContext,
+    captures: &[Capture],
+    body: &ast::Expr,
+) -> ast::Expr {
+    let mut rewriter = SyntaxRewriter::default();
+    for capture in captures.iter().filter(|it| it.kind != CaptureKind::Value) {
+        for (path, _) in local_usages(ctx, capture.local, body) {
+            match path.syntax().parent().and_then(ast::Expr::cast) {
+                Some(ast::Expr::MethodCallExpr(_)) | Some(ast::Expr::FieldExpr(_)) => (),
+                Some(ast::Expr::RefExpr(ref_expr))
+                    if ref_expr.mut_token().is_some() == (capture.kind == CaptureKind::MutRef) =>
+                {
+                    rewriter.replace_ast(&ast::Expr::from(ref_expr), &path.into());
+                }
+                _ if capture.kind == CaptureKind::MutRef => {
+                    let deref = make::expr_prefix(T![*], path.clone().into());
+                    rewriter.replace_ast(&ast::Expr::from(path), &deref);
+                }
+                _ => (),
+            }
+        }
+    }
+    ast::Expr::cast(rewriter.rewrite(body.syntax())).unwrap_or_else(|| body.clone())
+}
+
+/// The method of an inherent impl the closure is in, next to which the generated function is
+/// put.
+fn enclosing_inherent_method(closure: &ast::ClosureExpr) -> Option<ast::Fn> {
+    let method = closure.syntax().ancestors().find_map(ast::Fn::cast)?;
+    let impl_ = method.syntax().parent()?.parent().and_then(ast::Impl::cast)?;
+    if impl_.trait_().is_some() {
+        return None;
+    }
+    Some(method)
+}
+
+/// The uses of the variable a closure is bound to, with the calls of the closure.
+fn binding_usages(
+    ctx: &AssistContext,
+    local: Local,
+) -> Vec<(ast::PathExpr, Option<ast::CallExpr>)> {
+    let file_id: FileId = ctx.frange.file_id;
+    let source_file = ctx.sema.parse(file_id);
+    Definition::Local(local)
+        .usages(&ctx.sema)
+        .in_scope(SearchScope::single_file(file_id))
+        .all()
+        .into_iter()
+        .flat_map(|(_, references)| references)
+        .filter_map(|reference| {
+            let path = find_node_at_range::<ast::PathExpr>(source_file.syntax(), reference.range)?;
+            let call =
+                path.syntax().parent().and_then(ast::CallExpr::cast).filter(|call| {
+                    call.expr().as_ref().map(AstNode::syntax) == Some(path.syntax())
+                });
+            Some((path, call))
+        })
+        .collect()
+}
+
+/// The range of the node with the whitespace separating it from its sibling in `direction`, or
+/// in the other direction if there is none.
+fn range_with_whitespace(node: &SyntaxNode, direction: Direction) -> TextRange {
+    let whitespace = |direction| {
+        let mut siblings = node.siblings_with_tokens(direction).skip(1);
+        let token =
+            siblings.next()?.into_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE)?;
+        // The whitespace at the start and the end of the file is kept.
+        siblings.next()?;
+        Some(token)
+    };
+    let other = match direction {
+        Direction::Next => Direction::Prev,
+        Direction::Prev => Direction::Next,
+    };
+    match whitespace(direction).or_else(|| whitespace(other)) {
+        Some(token) => node.text_range().cover(token.text_range()),
+        None => node.text_range(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn closure_bound_to_variable() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+#[lang = "copy"]
+pub trait Copy {}
+impl Copy for u32 {}
+fn main() {
+    let n = 2u32;
+    let add = |$0x: u32| x + n;
+    let m = add(1) + add(2);
+}
+"#,
+            r#"
+#[lang = "copy"]
+pub trait Copy {}
+impl Copy for u32 {}
+fn main() {
+    let n = 2u32;
+    let m = add(1, n) + add(2, n);
+}
+
+fn add(x: u32, n: u32) -> u32 {
+    x + n
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn closure_argument_without_captures() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+fn apply(f: impl Fn(u32) -> u32) {}
+fn main() {
+    apply($0|x: u32| {
+        let y = x * 2;
+        y + 1
+    });
+}
+"#,
+            r#"
+fn apply(f: impl Fn(u32) -> u32) {}
+fn main() {
+    apply(fun_name);
+}
+
+fn $0fun_name(x: u32) -> u32 {
+    let y = x * 2;
+    y + 1
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn captures_by_reference() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+struct Name;
+impl Name {
+    fn len(&self) -> u32 { 0 }
+}
+fn apply(f: impl FnMut(u32)) {}
+fn main() {
+    let mut count = 0u32;
+    let name = Name;
+    apply(|x: u32|$0 count += x + name.len());
+}
+"#,
+            r#"
+struct Name;
+impl Name {
+    fn len(&self) -> u32 { 0 }
+}
+fn apply(f: impl FnMut(u32)) {}
+fn main() {
+    let mut count = 0u32;
+    let name = Name;
+    apply(|x| fun_name(x, &mut count, &name));
+}
+
+fn $0fun_name(x: u32, count: &mut u32, name: &Name) {
+    *count += x + name.len()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_closure_captures_by_value() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+struct Name;
+fn apply(f: impl FnOnce() -> Name) {}
+fn main() {
+    let name = Name;
+    apply(move $0|| name);
+}
+"#,
+            r#"
+struct Name;
+fn apply(f: impl FnOnce() -> Name) {}
+fn main() {
+    let name = Name;
+    apply(move || fun_name(name));
+}
+
+fn $0fun_name(name: Name) -> Name {
+    name
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn closure_in_method() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+struct S(u32);
+impl S {
+    fn f(&self) -> u32 {
+        let get = $0|| self.0;
+        get()
+    }
+}
+"#,
+            r#"
+struct S(u32);
+impl S {
+    fn f(&self) -> u32 {
+        self.get()
+    }
+
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#,
+        );
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+struct S;
+impl S {
+    fn f() {
+        let double = $0|x: u32| x * 2;
+        let _ = [1].iter().cloned().map(double);
+    }
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn f() {
+        let _ = [1].iter().cloned().map(Self::double);
+    }
+
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_closure_body() {
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let f = |x: u32| x + 1$0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_capturing_closure_is_used_as_value() {
+        mark::check!(convert_closure_to_fn_used_as_value);
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn apply(f: impl Fn(u32) -> u32) {}
+fn main() {
+    let n = 1u32;
+    let f = $0|x: u32| x + n;
+    apply(f);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fn_with_statements_to_closure() {
+        check_assist(
+            convert_fn_to_closure,
+            r#"
+fn apply(f: impl Fn(u32, u32) -> u32) {}
+
+fn main() {
+    apply(add$0);
+}
+
+fn add(a: u32, b: u32) -> u32 {
+    let sum = a + b;
+    sum * 2
+}
+"#,
+            r#"
+fn apply(f: impl Fn(u32, u32) -> u32) {}
+
+fn main() {
+    apply(|a, b| {
+        let sum = a + b;
+        sum * 2
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_fn_used_more_than_once() {
+        mark::check!(convert_fn_to_closure_used_more_than_once);
+        check_assist_not_applicable(
+            convert_fn_to_closure,
+            r#"
+fn double(x: u32) -> u32 { x * 2 }
+fn apply(f: impl Fn(u32) -> u32) {}
+fn main() {
+    apply(double$0);
+    double(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_generic_or_non_argument_fns() {
+        check_assist_not_applicable(
+            convert_fn_to_closure,
+            r#"
+fn id<T>(x: T) -> T { x }
+fn apply(f: impl Fn(u32) -> u32) {}
+fn main() {
+    apply(id$0);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_fn_to_closure,
+            r#"
+fn double(x: u32) -> u32 { x * 2 }
+fn main() {
+    let f = double$0;
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,475 @@
+use stdx::to_lower_snake_case;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstToken, CommentShape, ModuleItemOwner, NameOwner},
+    AstNode, SyntaxElement, SyntaxKind, SyntaxNode, TextSize,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_doctest_to_test
+//
+// Adds a unit test running the code of the doctest under the cursor to the `tests` module next
+// to the documented item.
+//
+// ```
+// /// ```
+// /// let x = $0double(2);
+// /// assert_eq!(x, 4);
+// /// ```
+// fn double(x: i32) -> i32 { x * 2 }
+// ```
+// ->
+// ```
+// /// ```
+// /// let x = double(2);
+// /// assert_eq!(x, 4);
+// /// ```
+// fn double(x: i32) -> i32 { x * 2 }
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn double_doctest() {
+//         let x = double(2);
+//         assert_eq!(x, 4);
+//     }
+// }
+// ```
+pub(crate) fn convert_doctest_to_test(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let comment = ctx.find_token_at_offset::<ast::Comment>()?;
+    if comment.kind().shape != CommentShape::Line || comment.kind().doc.is_none() {
+        return None;
+    }
+    let comments = doc_comment_block(&comment);
+    let cursor_line = comments.iter().position(|it| *it == comment)?;
+    let lines = comments.iter().map(|it| it.doc_comment().unwrap_or("")).collect::<Vec<_>>();
+    let (doctest, fence_range) = find_doctest(&lines, cursor_line)?;
+
+    let owner = comment.syntax().parent();
+    let container = if comment.is_inner() { Some(owner.clone()) } else { owner.parent() }
+        .into_iter()
+        .flat_map(|it| it.ancestors())
+        .find(|it| matches!(it.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST))?;
+    let tests_module = container
+        .children()
+        .filter_map(ast::Module::cast)
+        .find(|it| it.name().map_or(false, |name| name.text() == "tests"));
+    let tests_item_list = tests_module.as_ref().and_then(|it| it.item_list());
+    if tests_module.is_some() && tests_item_list.is_none() {
+        // The tests live in another file.
+        return None;
+    }
+
+    let crate_name = ctx
+        .sema
+        .to_module_def(ctx.frange.file_id)
+        .and_then(|it| it.krate().display_name(ctx.db()))
+        .map(|it| it.to_string());
+    let test_name = unique_test_name(
+        &format!("{}_doctest", test_name_prefix(&comment, &owner, &container)),
+        tests_item_list.as_ref(),
+    );
+
+    let (offset, text) = match tests_item_list {
+        Some(item_list) => {
+            let indent = IndentLevel::from_node(tests_module?.syntax());
+            let test = doctest.to_test(&test_name, crate_name.as_deref(), indent + 1);
+            match item_list.items().last() {
+                Some(last) => (last.syntax().text_range().end(), format!("\n\n{}", test)),
+                None => (
+                    item_list.l_curly_token()?.text_range().end(),
+                    format!("\n{}\n{}", test, indent),
+                ),
+            }
+        }
+        None => {
+            let (offset, indent) = module_insertion(&container);
+            let test = doctest.to_test(&test_name, crate_name.as_deref(), indent + 1);
+            let module = format!(
+                "\n\n{indent}#[cfg(test)]\n{indent}mod tests {{\n{indent}    use super::*;\n\n{}\n{indent}}}",
+                test,
+                indent = indent,
+            );
+            (offset, module)
+        }
+    };
+
+    let target = comments[fence_range.0]
+        .syntax()
+        .text_range()
+        .cover(comments[fence_range.1].syntax().text_range());
+    acc.add(
+        AssistId("convert_doctest_to_test", AssistKind::RefactorExtract),
+        "Convert doctest to a unit test",
+        target,
+        |builder| builder.insert(offset, text),
+    )
+}
+
+/// The line doc comments next to each other and to `comment`, which make up a single doc string.
+fn doc_comment_block(comment: &ast::Comment) -> Vec<ast::Comment> {
+    let mut blocks = vec![Vec::new()];
+    for element in comment.syntax().parent().children_with_tokens() {
+        let block = blocks.last_mut().unwrap();
+        match element {
+            SyntaxElement::Token(token) if token.kind() == SyntaxKind::WHITESPACE => {
+                if token.text().matches('\n').count() > 1 {
+                    blocks.push(Vec::new());
+                }
+            }
+            SyntaxElement::Token(token) => match ast::Comment::cast(token) {
+                Some(it) if it.kind() == comment.kind() => block.push(it),
+                _ => blocks.push(Vec::new()),
+            },
+            SyntaxElement::Node(_) => blocks.push(Vec::new()),
+        }
+    }
+    blocks.into_iter().find(|it| it.contains(comment)).unwrap_or_else(|| vec![comment.clone()])
+}
+
+struct Doctest {
+    lines: Vec<String>,
+    should_panic: bool,
+}
+
+/// Finds the code block around `cursor_line` and the indices of its fences, if it is a doctest
+/// rustdoc would run.
+fn find_doctest(lines: &[&str], cursor_line: usize) -> Option<(Doctest, (usize, usize))> {
+    let mut start = None;
+    for (idx, line) in lines.iter().enumerate() {
+        let header = match line.trim_start().strip_prefix("```") {
+            Some(it) => it,
+            None => continue,
+        };
+        match start.take() {
+            None => start = Some((idx, header)),
+            Some((start_idx, header)) if (start_idx..=idx).contains(&cursor_line) => {
+                let doctest = Doctest::new(header, &lines[start_idx + 1..idx])?;
+                return Some((doctest, (start_idx, idx)));
+            }
+            Some(_) => {}
+        }
+    }
+    None
+}
+
+impl Doctest {
+    fn new(header: &str, lines: &[&str]) -> Option<Doctest> {
+        let mut should_panic = false;
+        for attr in header.split(',').map(|it| it.trim()) {
+            match attr {
+                "" | "rust" | "edition2015" | "edition2018" | "edition2021" => {}
+                "should_panic" => should_panic = true,
+                _ => return None,
+            }
+        }
+        let lines = lines
+            .iter()
+            .map(|line| {
+                // Lines hidden from the docs are part of the test, `##` escapes a `#`.
+                let trimmed = line.trim_start();
+                if trimmed == "#" {
+                    String::new()
+                } else if let Some(rest) = trimmed.strip_prefix("# ") {
+                    format!("{}{}", &line[..line.len() - trimmed.len()], rest)
+                } else if trimmed.starts_with("##") {
+                    line.replacen("##", "#", 1)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        Some(Doctest { lines, should_panic })
+    }
+
+    fn to_test(&self, name: &str, crate_name: Option<&str>, indent: IndentLevel) -> String {
+        let mut buf = String::new();
+        buf.push_str(&format!("{}#[test]\n", indent));
+        if self.should_panic {
+            buf.push_str(&format!("{}#[should_panic]\n", indent));
+        }
+        buf.push_str(&format!("{}fn {}() {{\n", indent, name));
+        let mut has_main = false;
+        for line in &self.lines {
+            if let Some(crate_name) = crate_name {
+                if line.trim() == format!("extern crate {};", crate_name) {
+                    continue;
+                }
+            }
+            has_main |= line.starts_with("fn main()");
+            let line = match crate_name {
+                Some(crate_name) => replace_crate_name(line, crate_name),
+                None => line.clone(),
+            };
+            match line.is_empty() {
+                true => buf.push('\n'),
+                false => buf.push_str(&format!("{}{}\n", indent + 1, line)),
+            }
+        }
+        // Rustdoc calls `main` if the doctest has one.
+        if has_main {
+            buf.push_str(&format!("{}main();\n", indent + 1));
+        }
+        buf.push_str(&format!("{}}}", indent));
+        buf
+    }
+}
+
+/// Replaces paths starting with the crate's name, which doctests use as they are compiled as
+/// separate crates.
+fn replace_crate_name(line: &str, crate_name: &str) -> String {
+    let pattern = format!("{}::", crate_name);
+    let mut res = String::new();
+    let mut rest = line;
+    while let Some(idx) = rest.find(&pattern) {
+        let preceded_by_ident = rest[..idx]
+            .chars()
+            .next_back()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_' || c == ':');
+        res.push_str(&rest[..idx]);
+        res.push_str(if preceded_by_ident { &pattern } else { "crate::" });
+        rest = &rest[idx + pattern.len()..];
+    }
+    res.push_str(rest);
+    res
+}
+
+fn test_name_prefix(comment: &ast::Comment, owner: &SyntaxNode, container: &SyntaxNode) -> String {
+    let module_name = || {
+        let module = container.parent().and_then(ast::Module::cast)?;
+        Some(module.name()?.text().to_string())
+    };
+    let name = if comment.is_inner() {
+        module_name().unwrap_or_else(|| "crate".to_string())
+    } else {
+        let name = owner.children().find_map(ast::Name::cast).map(|it| it.text().to_string());
+        // Functions of different impls are told apart by the type's name.
+        let impl_ty = owner
+            .parent()
+            .and_then(|it| it.parent())
+            .and_then(ast::Impl::cast)
+            .and_then(|it| it.self_ty())
+            .and_then(|it| match it {
+                ast::Type::PathType(it) => {
+                    Some(it.path()?.segment()?.name_ref()?.text().to_string())
+                }
+                _ => None,
+            });
+        match (impl_ty, name) {
+            (Some(ty), Some(name)) => format!("{}_{}", ty, name),
+            (None, Some(name)) => name,
+            (_, None) => return "doc".to_string(),
+        }
+    };
+    to_lower_snake_case(&name)
+}
+
+fn unique_test_name(name: &str, tests: Option<&ast::ItemList>) -> String {
+    let existing = tests
+        .into_iter()
+        .flat_map(|it| it.items())
+        .filter_map(|it| match it {
+            ast::Item::Fn(it) => Some(it.name()?.text().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut res = name.to_string();
+    let mut idx = 1;
+    while existing.contains(&res) {
+        idx += 1;
+        res = format!("{}_{}", name, idx);
+    }
+    res
+}
+
+/// Where to add a new `tests` module to `container`, and its indentation.
+fn module_insertion(container: &SyntaxNode) -> (TextSize, IndentLevel) {
+    match ast::ItemList::cast(container.clone()) {
+        Some(item_list) => match item_list.items().last() {
+            Some(last) => (last.syntax().text_range().end(), IndentLevel::from_node(last.syntax())),
+            None => (
+                item_list.syntax().text_range().start() + TextSize::of('{'),
+                IndentLevel::from_node(container) + 1,
+            ),
+        },
+        None => {
+            let end = container
+                .last_token()
+                .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                .map_or(container.text_range().end(), |it| it.text_range().start());
+            (end, IndentLevel(0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn creates_tests_module() {
+        check_assist(
+            convert_doctest_to_test,
+            r#"
+/// Doubles.
+///
+/// ```
+/// # use std::convert::identity;
+/// let x = identity(double(2));$0
+/// assert_eq!(x, 4);
+/// ```
+fn double(x: i32) -> i32 { x * 2 }
+"#,
+            r#"
+/// Doubles.
+///
+/// ```
+/// # use std::convert::identity;
+/// let x = identity(double(2));
+/// assert_eq!(x, 4);
+/// ```
+fn double(x: i32) -> i32 { x * 2 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn double_doctest() {
+        use std::convert::identity;
+        let x = identity(double(2));
+        assert_eq!(x, 4);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn appends_to_tests_module() {
+        check_assist(
+            convert_doctest_to_test,
+            r#"
+struct Counter;
+
+impl Counter {
+    /// ```should_panic
+    /// Counter::$0fail();
+    /// ```
+    fn fail() { panic!() }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn counter_fail_doctest() {}
+}
+"#,
+            r#"
+struct Counter;
+
+impl Counter {
+    /// ```should_panic
+    /// Counter::fail();
+    /// ```
+    fn fail() { panic!() }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn counter_fail_doctest() {}
+
+    #[test]
+    #[should_panic]
+    fn counter_fail_doctest_2() {
+        Counter::fail();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replaces_crate_name_and_calls_main() {
+        check_assist(
+            convert_doctest_to_test,
+            r#"
+//- /lib.rs crate:my_crate
+mod inner {
+    //! ```
+    //! extern crate my_crate;
+    //! use my_crate::inner::S;
+    //! fn main() {
+    //!     let _ = S;$0
+    //! }
+    //! ```
+    pub struct S;
+}
+"#,
+            r#"
+mod inner {
+    //! ```
+    //! extern crate my_crate;
+    //! use my_crate::inner::S;
+    //! fn main() {
+    //!     let _ = S;
+    //! }
+    //! ```
+    pub struct S;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn inner_doctest() {
+            use crate::inner::S;
+            fn main() {
+                let _ = S;
+            }
+            main();
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_of_doctests() {
+        check_assist_not_applicable(
+            convert_doctest_to_test,
+            r#"
+/// Not $0code.
+///
+/// ```
+/// let x = 1;
+/// ```
+fn f() {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_doctest_to_test,
+            r#"
+/// ```text
+/// plain $0text
+/// ```
+fn f() {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_doctest_to_test,
+            r#"
+// ```
+// let x = $01;
+// ```
+fn f() {}
+"#,
+        );
+    }
+}
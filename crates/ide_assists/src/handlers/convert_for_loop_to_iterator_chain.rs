@@ -0,0 +1,588 @@
+use hir::{HirDisplay, Local};
+use ide_db::{
+    defs::Definition,
+    search::{FileReference, ReferenceAccess},
+};
+use stdx::format_to;
+use syntax::{
+    ast::{self, ArgListOwner, LoopBodyOwner, NameOwner},
+    AstNode, SyntaxKind, SyntaxNode, TextRange,
+};
+use test_utils::mark;
+
+use crate::{
+    utils::{impls_core_iter, invert_boolean_expression, is_ref_and_impls_iter_method},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: convert_for_loop_to_iterator_chain
+//
+// Converts a `for` loop accumulating into the variable declared right before it into a chain of
+// iterator adapters: pushing into a vector becomes `collect()`, adding to a number becomes
+// `sum()`, and breaking out once an item is found becomes `find()`.
+//
+// ```
+// struct Vec<T>(T);
+// impl<T> Vec<T> {
+//     fn new() -> Vec<T> { loop {} }
+//     fn push(&mut self, item: T) {}
+// }
+// struct Order { price: u32 }
+// fn prices(orders: Vec<Order>) -> Vec<u32> {
+//     let mut prices = Vec::new();
+//     for$0 order in orders {
+//         prices.push(order.price);
+//     }
+//     prices
+// }
+// ```
+// ->
+// ```
+// struct Vec<T>(T);
+// impl<T> Vec<T> {
+//     fn new() -> Vec<T> { loop {} }
+//     fn push(&mut self, item: T) {}
+// }
+// struct Order { price: u32 }
+// fn prices(orders: Vec<Order>) -> Vec<u32> {
+//     let prices: Vec<_> = orders.into_iter().map(|order| order.price).collect();
+//     prices
+// }
+// ```
+pub(crate) fn convert_for_loop_to_iterator_chain(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let for_loop = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    let body = for_loop.loop_body()?;
+    if body.syntax().text_range().start() < ctx.offset() || for_loop.label().is_some() {
+        return None;
+    }
+    let pat = for_loop.pat()?;
+    let iterable = for_loop.iterable()?;
+
+    // The loop and the declaration of the accumulator right before it.
+    let loop_stmt = match for_loop.syntax().parent().and_then(ast::ExprStmt::cast) {
+        Some(stmt) => stmt.syntax().clone(),
+        None => for_loop.syntax().clone(),
+    };
+    let let_stmt = loop_stmt.prev_sibling().and_then(ast::LetStmt::cast)?;
+    let acc_pat = match let_stmt.pat()? {
+        ast::Pat::IdentPat(it) if it.ref_token().is_none() && it.at_token().is_none() => it,
+        _ => return None,
+    };
+    let acc_local = ctx.sema.to_def(&acc_pat)?;
+    let init = let_stmt.initializer()?;
+
+    let mut exprs = body_exprs(&body)?;
+    let last = exprs.pop()?;
+    // Guards skipping items, as `(condition, skipped_when_true)`.
+    let mut conditions = Vec::new();
+    for expr in exprs {
+        let (cond, then) = if_without_else(&expr)?;
+        match body_exprs(&then)?.as_slice() {
+            [ast::Expr::ContinueExpr(it)] if it.lifetime().is_none() => {
+                conditions.push((cond, true))
+            }
+            _ => return None,
+        }
+    }
+    let action = match if_without_else(&last) {
+        Some((cond, then)) => {
+            conditions.push((cond, false));
+            body_exprs(&then)?
+        }
+        None => vec![last],
+    };
+    let kind = Accumulation::new(ctx, &action, acc_local, &init, &pat)?;
+
+    let loop_range = for_loop.syntax().text_range();
+    let acc_uses = Definition::Local(acc_local).usages(&ctx.sema).all();
+    let mut mutated_after_loop = false;
+    let mut uses_in_loop = 0;
+    for FileReference { range, name, access } in acc_uses.references.values().flatten() {
+        if loop_range.contains_range(*range) {
+            uses_in_loop += 1;
+        } else if range.start() >= loop_range.end() {
+            mutated_after_loop |= *access == Some(ReferenceAccess::Write) || may_borrow_mut(name);
+        }
+    }
+    // The accumulator can only be used by the accumulation itself.
+    if uses_in_loop != 1 {
+        return None;
+    }
+    let mut modeled = conditions.iter().map(|(cond, _)| cond).chain(kind.operand());
+    if modeled.any(|it| has_control_flow(it.syntax())) {
+        mark::hit!(iterator_chain_control_flow);
+        return None;
+    }
+
+    let db = ctx.db();
+    let module = ctx.sema.scope(for_loop.syntax()).module()?;
+    if !conditions.is_empty() {
+        // The closures of `filter` and `find` get the items by reference, so the pattern only
+        // works for items which can be copied out of the reference.
+        let item_ty = ctx.sema.type_of_pat(&pat)?;
+        let copied = match &pat {
+            ast::Pat::IdentPat(it) if it.ref_token().is_none() && it.mut_token().is_none() => {
+                item_ty.remove_ref().is_some() || item_ty.is_copy(db)
+            }
+            ast::Pat::TuplePat(_) => item_ty.remove_ref().is_none() && item_ty.is_copy(db),
+            _ => false,
+        };
+        if !copied {
+            mark::hit!(iterator_chain_item_not_copy);
+            return None;
+        }
+    }
+    let acc_ty = acc_local.ty(db);
+    let declared_ty = match let_stmt.ty() {
+        Some(ty) => ty.to_string(),
+        None => match &kind {
+            Accumulation::Push(_) => "Vec<_>".to_string(),
+            Accumulation::Sum(_) => acc_ty.display_source_code(db, module.into()).ok()?,
+            Accumulation::Find => String::new(),
+        },
+    };
+
+    let mut chain = match is_ref_and_impls_iter_method(&ctx.sema, &iterable) {
+        Some((expr, method)) => format!("{}.{}()", expr, method),
+        None if impls_core_iter(&ctx.sema, &iterable) => iterable.to_string(),
+        None => match iterable {
+            ast::Expr::RefExpr(_) => format!("({}).into_iter()", iterable),
+            _ => format!("{}.into_iter()", iterable),
+        },
+    };
+    let find_cond = match kind {
+        Accumulation::Find => match conditions.pop()? {
+            (cond, false) => Some(cond),
+            _ => return None,
+        },
+        _ => None,
+    };
+    for (cond, skipped) in &conditions {
+        let cond = if *skipped {
+            invert_boolean_expression(&ctx.sema, cond.clone())
+        } else {
+            cond.clone()
+        };
+        format_to!(chain, ".filter(|&{}| {})", pat, cond);
+    }
+    match &kind {
+        Accumulation::Push(expr) | Accumulation::Sum(expr) => {
+            if expr.syntax().text() != pat.syntax().text() {
+                format_to!(chain, ".map(|{}| {})", pat, expr);
+            }
+            let method = if matches!(kind, Accumulation::Push(_)) { "collect" } else { "sum" };
+            format_to!(chain, ".{}()", method);
+        }
+        Accumulation::Find => format_to!(chain, ".find(|&{}| {})", pat, find_cond?),
+    }
+
+    let mut_token = if mutated_after_loop && acc_pat.mut_token().is_some() { "mut " } else { "" };
+    let name = acc_pat.name()?;
+    let ty = if declared_ty.is_empty() { String::new() } else { format!(": {}", declared_ty) };
+    let target = TextRange::new(let_stmt.syntax().text_range().start(), loop_range.end());
+    let range = TextRange::new(target.start(), loop_stmt.text_range().end());
+    acc.add(
+        AssistId("convert_for_loop_to_iterator_chain", AssistKind::RefactorRewrite),
+        "Convert to iterator chain",
+        target,
+        |builder| {
+            builder.replace(range, format!("let {}{}{} = {};", mut_token, name, ty, chain));
+        },
+    )
+}
+
+enum Accumulation {
+    /// `acc.push(expr)` into an empty vector.
+    Push(ast::Expr),
+    /// `acc += expr` onto zero.
+    Sum(ast::Expr),
+    /// `acc = Some(item); break;` from `None`.
+    Find,
+}
+
+impl Accumulation {
+    fn new(
+        ctx: &AssistContext,
+        action: &[ast::Expr],
+        acc: Local,
+        init: &ast::Expr,
+        pat: &ast::Pat,
+    ) -> Option<Accumulation> {
+        let db = ctx.db();
+        let is_acc = |expr: Option<ast::Expr>| {
+            match expr {
+            Some(ast::Expr::PathExpr(it)) => {
+                matches!(ctx.sema.resolve_path(&it.path()?)?, hir::PathResolution::Local(it) if it == acc)
+                    .then(|| ())
+            }
+            _ => None,
+        }
+        };
+        let acc_adt = acc.ty(db).as_adt().map(|it| it.name(db).to_string());
+        let kind = match action {
+            [ast::Expr::MethodCallExpr(call)] => {
+                is_acc(call.receiver())?;
+                let args = call.arg_list()?.args().collect::<Vec<_>>();
+                let arg = match args.as_slice() {
+                    [arg] => arg.clone(),
+                    _ => return None,
+                };
+                if call.name_ref()?.text() != "push"
+                    || acc_adt.as_deref() != Some("Vec")
+                    || !is_empty_vec(init)
+                {
+                    return None;
+                }
+                Accumulation::Push(arg)
+            }
+            [ast::Expr::BinExpr(bin)] if bin.op_kind()? == ast::BinOp::AddAssign => {
+                is_acc(bin.lhs())?;
+                let rhs = bin.rhs()?;
+                if !is_zero(init) {
+                    return None;
+                }
+                // `Sum` is implemented for the numbers and references to them.
+                let acc_ty = acc.ty(db);
+                if acc_ty.is_unknown() {
+                    return None;
+                }
+                match ctx.sema.type_of_expr(&rhs) {
+                    Some(ty) if ty.is_unknown() => {}
+                    Some(ty) if ty.is_same_type(&acc_ty) => {}
+                    Some(ty) if ty.remove_ref().map_or(false, |it| it.is_same_type(&acc_ty)) => {}
+                    _ => return None,
+                }
+                Accumulation::Sum(rhs)
+            }
+            [ast::Expr::BinExpr(assign), ast::Expr::BreakExpr(brk)]
+                if assign.op_kind()? == ast::BinOp::Assignment =>
+            {
+                is_acc(assign.lhs())?;
+                if brk.lifetime().is_some()
+                    || brk.expr().is_some()
+                    || acc_adt.as_deref() != Some("Option")
+                    || init.syntax().text() != "None"
+                {
+                    return None;
+                }
+                let call = match assign.rhs()? {
+                    ast::Expr::CallExpr(it) => it,
+                    _ => return None,
+                };
+                let args = call.arg_list()?.args().collect::<Vec<_>>();
+                match (call.expr()?, args.as_slice()) {
+                    (ast::Expr::PathExpr(callee), [item])
+                        if callee.syntax().text() == "Some"
+                            && item.syntax().text() == pat.syntax().text()
+                            && matches!(pat, ast::Pat::IdentPat(_)) => {}
+                    _ => return None,
+                }
+                Accumulation::Find
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// The expression the closure of `map` evaluates to.
+    fn operand(&self) -> Option<&ast::Expr> {
+        match self {
+            Accumulation::Push(it) | Accumulation::Sum(it) => Some(it),
+            Accumulation::Find => None,
+        }
+    }
+}
+
+/// The expressions of the statements and the tail of `block`, if it has no other statements.
+fn body_exprs(block: &ast::BlockExpr) -> Option<Vec<ast::Expr>> {
+    let mut exprs = block
+        .statements()
+        .map(|stmt| match stmt {
+            ast::Stmt::ExprStmt(it) => it.expr(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    exprs.extend(block.tail_expr());
+    Some(exprs)
+}
+
+fn if_without_else(expr: &ast::Expr) -> Option<(ast::Expr, ast::BlockExpr)> {
+    let if_expr = match expr {
+        ast::Expr::IfExpr(it) if it.else_branch().is_none() => it,
+        _ => return None,
+    };
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    Some((cond.expr()?, if_expr.then_branch()?))
+}
+
+/// Whether `node` changes the control flow, which the closures of the chain can't model.
+fn has_control_flow(node: &SyntaxNode) -> bool {
+    node.descendants().any(|it| {
+        matches!(
+            it.kind(),
+            SyntaxKind::BREAK_EXPR
+                | SyntaxKind::CONTINUE_EXPR
+                | SyntaxKind::RETURN_EXPR
+                | SyntaxKind::TRY_EXPR
+                | SyntaxKind::AWAIT_EXPR
+        )
+    })
+}
+
+/// Whether the local referred to by `name` may be borrowed mutably, by a method call or
+/// explicitly.
+fn may_borrow_mut(name: &ast::NameLike) -> bool {
+    let path_expr = name.syntax().ancestors().find_map(ast::PathExpr::cast);
+    let parent = path_expr.as_ref().and_then(|it| it.syntax().parent());
+    match parent.and_then(ast::Expr::cast) {
+        Some(ast::Expr::MethodCallExpr(call)) => {
+            call.receiver().map(|it| it.syntax().clone()) == path_expr.map(|it| it.syntax().clone())
+        }
+        Some(ast::Expr::RefExpr(it)) => it.mut_token().is_some(),
+        _ => false,
+    }
+}
+
+fn is_empty_vec(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::CallExpr(call) => {
+            call.arg_list().map_or(false, |it| it.args().next().is_none())
+                && call.expr().map_or(false, |callee| {
+                    let text = callee.syntax().text().to_string();
+                    text == "Vec::new" || text.ends_with("::Vec::new")
+                })
+        }
+        ast::Expr::MacroCall(call) => {
+            let is_vec = call
+                .path()
+                .and_then(|it| it.segment())
+                .and_then(|it| it.name_ref())
+                .map_or(false, |it| it.text() == "vec");
+            is_vec
+                && call
+                    .token_tree()
+                    .map_or(false, |it| it.syntax().children_with_tokens().count() == 2)
+        }
+        _ => false,
+    }
+}
+
+fn is_zero(expr: &ast::Expr) -> bool {
+    let literal = match expr {
+        ast::Expr::Literal(it) => it,
+        _ => return false,
+    };
+    let text = literal.token().text().to_string();
+    // Strip the suffix, but not the radix of `0x1`.
+    let value_len =
+        text.find(|c: char| !(c.is_ascii_digit() || c == '_' || c == '.')).unwrap_or(text.len());
+    let (value, suffix) = text.split_at(value_len);
+    !suffix.starts_with(|c| matches!(c, 'x' | 'o' | 'b'))
+        && value.contains('0')
+        && value.chars().all(|c| matches!(c, '0' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::helpers::FamousDefs;
+
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    fn with_core(fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:core{}{}", fixture, FamousDefs::FIXTURE)
+    }
+
+    #[test]
+    fn converts_push_with_filters() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            &with_core(
+                r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Vec<T> { loop {} }
+    fn push(&mut self, item: T) {}
+}
+pub struct Numbers;
+impl Iterator for Numbers {
+    type Item = &'static u32;
+    fn next(&mut self) -> Option<&'static u32> { None }
+}
+impl IntoIterator for Numbers {
+    type Item = &'static u32;
+}
+fn main(numbers: Numbers) {
+    let mut squares = Vec::new();
+    $0for n in numbers {
+        if *n == 0 {
+            continue;
+        }
+        if n % 2 == 1 {
+            squares.push(n * n);
+        }
+    }
+}
+"#,
+            ),
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Vec<T> { loop {} }
+    fn push(&mut self, item: T) {}
+}
+pub struct Numbers;
+impl Iterator for Numbers {
+    type Item = &'static u32;
+    fn next(&mut self) -> Option<&'static u32> { None }
+}
+impl IntoIterator for Numbers {
+    type Item = &'static u32;
+}
+fn main(numbers: Numbers) {
+    let squares: Vec<_> = numbers.filter(|&n| *n != 0).filter(|&n| n % 2 == 1).map(|n| n * n).collect();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_sum() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+struct Order { price: u64 }
+fn total(orders: Orders) -> u64 {
+    let mut total = 0;
+    $0for order in orders {
+        total += order.price;
+    }
+    total
+}
+"#,
+            r#"
+struct Order { price: u64 }
+fn total(orders: Orders) -> u64 {
+    let total: u64 = orders.into_iter().map(|order| order.price).sum();
+    total
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_find() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            &with_core(
+                r#"
+pub struct Names;
+impl Iterator for Names {
+    type Item = &'static str;
+    fn next(&mut self) -> Option<&'static str> { None }
+}
+impl IntoIterator for Names {
+    type Item = &'static str;
+}
+fn main(names: Names) {
+    let mut found = None;
+    $0for name in names {
+        if name.len() > 3 {
+            found = Some(name);
+            break;
+        }
+    }
+    found = None;
+}
+"#,
+            ),
+            r#"
+pub struct Names;
+impl Iterator for Names {
+    type Item = &'static str;
+    fn next(&mut self) -> Option<&'static str> { None }
+}
+impl IntoIterator for Names {
+    type Item = &'static str;
+}
+fn main(names: Names) {
+    let mut found = names.find(|&name| name.len() > 3);
+    found = None;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_unmodeled_control_flow() {
+        mark::check!(iterator_chain_control_flow);
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            r#"
+fn total(orders: Orders) -> Option<u64> {
+    let mut total = 0;
+    $0for order in orders {
+        total += order.price?;
+    }
+    Some(total)
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            r#"
+fn total(orders: Orders) -> u64 {
+    let mut total = 0;
+    $0for order in orders {
+        if order.price == 0 {
+            break;
+        }
+        total += order.price;
+    }
+    total
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_filter_would_move_items() {
+        mark::check!(iterator_chain_item_not_copy);
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            &with_core(
+                r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Vec<T> { loop {} }
+    fn push(&mut self, item: T) {}
+}
+pub struct Name;
+pub struct Names;
+impl Iterator for Names {
+    type Item = Name;
+    fn next(&mut self) -> Option<Name> { None }
+}
+impl IntoIterator for Names {
+    type Item = Name;
+}
+fn main(names: Names, keep: bool) {
+    let mut kept = Vec::new();
+    $0for name in names {
+        if keep {
+            kept.push(name);
+        }
+    }
+}
+"#,
+            ),
+        );
+    }
+}
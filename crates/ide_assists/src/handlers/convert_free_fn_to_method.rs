@@ -0,0 +1,488 @@
+use hir::{Adt, ModuleDef, PathResolution};
+use ide_db::{
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    search::{FileReference, UsageSearchResult},
+};
+use rustc_hash::FxHashSet;
+use stdx::format_to;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        ArgListOwner, NameOwner,
+    },
+    AstNode, SourceFile, SyntaxKind, TextRange,
+};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_free_fn_to_method
+//
+// Converts a function whose first parameter is a type of the current crate into a method of
+// that type, and its calls into method calls.
+//
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// fn $0norm(p: &Point) -> f64 {
+//     (p.x * p.x + p.y * p.y).sqrt()
+// }
+//
+// fn main() {
+//     let p = Point { x: 3.0, y: 4.0 };
+//     norm(&p);
+// }
+// ```
+// ->
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// impl Point {
+//     fn norm(&self) -> f64 {
+//         (self.x * self.x + self.y * self.y).sqrt()
+//     }
+// }
+//
+// fn main() {
+//     let p = Point { x: 3.0, y: 4.0 };
+//     p.norm();
+// }
+// ```
+pub(crate) fn convert_free_fn_to_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let func: ast::Fn = ctx.find_node_at_offset()?;
+    if let Some(body) = func.body() {
+        if body.syntax().text_range().contains_range(ctx.frange.range) {
+            return None;
+        }
+    }
+    let is_free = match func.syntax().parent()?.kind() {
+        SyntaxKind::SOURCE_FILE => true,
+        SyntaxKind::ITEM_LIST => {
+            func.syntax().parent()?.parent().map_or(false, |it| ast::Module::can_cast(it.kind()))
+        }
+        _ => false,
+    };
+    if !is_free {
+        return None;
+    }
+    let name = func.name()?;
+    let param = func.param_list()?.params().next()?;
+    let pat = match param.pat()? {
+        ast::Pat::IdentPat(it) if it.ref_token().is_none() && it.pat().is_none() => it,
+        _ => return None,
+    };
+
+    let (self_kind, path_ty) = match param.ty()? {
+        ast::Type::PathType(it) => (SelfKind::Value, it),
+        ast::Type::RefType(it) => match it.ty()? {
+            ast::Type::PathType(inner) => (SelfKind::Ref(it), inner),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let path = path_ty.path()?;
+    let adt = match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::Adt(it)) => it,
+        _ => return None,
+    };
+    // The generic parameters of the type would have to move onto the impl.
+    if path.segment()?.generic_arg_list().is_some() {
+        mark::hit!(free_fn_to_method_generic_type);
+        return None;
+    }
+    let db = ctx.db();
+    let krate = ctx.sema.scope(func.syntax()).module()?.krate();
+    if adt.module(db).krate() != krate {
+        return None;
+    }
+    if has_assoc_item(ctx, adt, &name.text()) {
+        mark::hit!(free_fn_to_method_name_taken);
+        return None;
+    }
+
+    let fn_def = ctx.sema.to_def(&func)?;
+    let fn_usages = Definition::ModuleDef(fn_def.into()).usages(&ctx.sema).all();
+    let imported = fn_usages.iter().flat_map(|(_, refs)| refs).any(|reference| {
+        reference.name.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind()))
+    });
+    if imported {
+        mark::hit!(free_fn_to_method_imported);
+        return None;
+    }
+    let local = ctx.sema.to_def(&pat)?;
+    let local_usages = Definition::Local(local).usages(&ctx.sema).all();
+
+    let target = func.syntax().text_range();
+    acc.add(
+        AssistId("convert_free_fn_to_method", AssistKind::RefactorRewrite),
+        format!("Convert to a method on `{}`", path),
+        target,
+        |builder| {
+            let file_id = ctx.frange.file_id;
+            let name = name.text();
+            // Edits inside the function are applied to its text, which then moves into the impl.
+            let mut fn_edits = vec![(param.syntax().text_range(), self_kind.self_param(&pat))];
+            fn_edits.extend(self_edits(&local_usages));
+            for (&file, refs) in fn_usages.iter() {
+                builder.edit_file(file);
+                for (range, text) in call_edits(ctx, refs, adt, &name, self_kind.is_ref()) {
+                    if file == file_id && target.contains_range(range) {
+                        fn_edits.push((range, text));
+                    } else {
+                        builder.replace(range, text);
+                    }
+                }
+            }
+
+            builder.edit_file(file_id);
+            let mut fn_text = func.syntax().to_string();
+            fn_edits.sort_by_key(|(range, _)| range.start());
+            for (range, text) in fn_edits.into_iter().rev() {
+                let range = range - target.start();
+                fn_text.replace_range(usize::from(range.start())..usize::from(range.end()), &text);
+            }
+            let method = SourceFile::parse(&fn_text)
+                .tree()
+                .syntax()
+                .descendants()
+                .find_map(ast::Fn::cast)
+                .map_or(fn_text, |it| it.indent(IndentLevel(1)).to_string());
+            let indent = IndentLevel::from_node(func.syntax());
+            let mut buf = String::new();
+            format_to!(buf, "impl {} {{\n{}{}\n{}}}", path, indent + 1, method, indent);
+            builder.replace(target, buf);
+        },
+    )
+}
+
+#[derive(Clone)]
+enum SelfKind {
+    Value,
+    Ref(ast::RefType),
+}
+
+impl SelfKind {
+    fn is_ref(&self) -> bool {
+        matches!(self, SelfKind::Ref(_))
+    }
+
+    fn self_param(&self, pat: &ast::IdentPat) -> String {
+        match self {
+            SelfKind::Value if pat.mut_token().is_some() => "mut self".to_string(),
+            SelfKind::Value => "self".to_string(),
+            SelfKind::Ref(ty) => {
+                let mut buf = String::from("&");
+                if let Some(lifetime) = ty.lifetime() {
+                    format_to!(buf, "{} ", lifetime);
+                }
+                if ty.mut_token().is_some() {
+                    buf.push_str("mut ");
+                }
+                buf.push_str("self");
+                buf
+            }
+        }
+    }
+}
+
+fn has_assoc_item(ctx: &AssistContext, adt: Adt, name: &str) -> bool {
+    let db = ctx.db();
+    adt.ty(db)
+        .iterate_path_candidates(
+            db,
+            adt.module(db).krate(),
+            &FxHashSet::default(),
+            None,
+            |_, item| match item.name(db) {
+                Some(it) if it.to_string() == name => Some(()),
+                _ => None,
+            },
+        )
+        .is_some()
+}
+
+/// Replaces the uses of the parameter with `self`.
+fn self_edits(usages: &UsageSearchResult) -> Vec<(TextRange, String)> {
+    usages
+        .iter()
+        .flat_map(|(_, refs)| refs)
+        .map(|FileReference { range, name, .. }| {
+            let shorthand = match name {
+                ast::NameLike::NameRef(name_ref) => ast::RecordExprField::for_field_name(name_ref)
+                    .map_or(false, |field| field.name_ref().is_none()),
+                _ => false,
+            };
+            let text = if shorthand { format!("{}: self", name.syntax()) } else { "self".into() };
+            (*range, text)
+        })
+        .collect()
+}
+
+/// Turns calls of the function into method calls, and other uses into paths through the type.
+fn call_edits(
+    ctx: &AssistContext,
+    refs: &[FileReference],
+    adt: Adt,
+    name: &str,
+    by_ref: bool,
+) -> Vec<(TextRange, String)> {
+    let mut edits = Vec::new();
+    for reference in refs {
+        let path_expr = match reference.name.syntax().ancestors().find_map(ast::PathExpr::cast) {
+            Some(it) => it,
+            None => continue,
+        };
+        let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast);
+        let (call, arg_list) = match call.and_then(|call| Some((call.arg_list()?, call))) {
+            Some((arg_list, call)) => (call, arg_list),
+            None => {
+                let module = ctx.sema.scope(path_expr.syntax()).module();
+                let ty_path = module.and_then(|it| it.find_use_path(ctx.db(), ModuleDef::Adt(adt)));
+                if let Some(ty_path) = ty_path {
+                    let text = format!("{}::{}", mod_path_to_ast(&ty_path), name);
+                    edits.push((path_expr.syntax().text_range(), text));
+                }
+                continue;
+            }
+        };
+        let mut args = arg_list.args();
+        let first = match args.next() {
+            Some(it) => it,
+            None => continue,
+        };
+        let receiver = match &first {
+            ast::Expr::RefExpr(it) if by_ref => it.expr().unwrap_or_else(|| first.clone()),
+            _ => first.clone(),
+        };
+        let rest_start = match args.next() {
+            Some(it) => it.syntax().text_range().start(),
+            None => match arg_list.r_paren_token() {
+                Some(it) => it.text_range().start(),
+                None => continue,
+            },
+        };
+        let parens = !is_simple_receiver(&receiver);
+        let receiver_range = receiver.syntax().text_range();
+        let call_start = call.syntax().text_range().start();
+        edits.push((
+            TextRange::new(call_start, receiver_range.start()),
+            if parens { "(".to_string() } else { String::new() },
+        ));
+        edits.push((
+            TextRange::new(receiver_range.end(), rest_start),
+            format!("{}.{}(", if parens { ")" } else { "" }, name),
+        ));
+    }
+    edits
+}
+
+/// Whether `expr` can be the receiver of a method call without parentheses.
+fn is_simple_receiver(expr: &ast::Expr) -> bool {
+    matches!(
+        expr,
+        ast::Expr::PathExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::TupleExpr(_)
+            | ast::Expr::ArrayExpr(_)
+            | ast::Expr::Literal(_)
+            | ast::Expr::MacroCall(_)
+            | ast::Expr::TryExpr(_)
+            | ast::Expr::AwaitExpr(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn converts_by_value_param_and_uses_as_value() {
+        check_assist(
+            convert_free_fn_to_method,
+            r#"
+mod shapes {
+    pub struct Circle { pub r: u32 }
+
+    pub fn $0scale(mut c: Circle, by: u32) -> Circle {
+        c.r *= by;
+        c
+    }
+}
+
+fn main() {
+    let c = shapes::scale(shapes::Circle { r: 1 }, 2);
+    let _ = shapes::scale(if true { c } else { shapes::Circle { r: 0 } }, 3);
+    let _ = Some(shapes::Circle { r: 2 }).map(|c| c).map(|c| (c, 1)).map(|(c, by)| shapes::scale(c, by));
+    let f = shapes::scale;
+}
+"#,
+            r#"
+mod shapes {
+    pub struct Circle { pub r: u32 }
+
+    impl Circle {
+        pub fn scale(mut self, by: u32) -> Circle {
+            self.r *= by;
+            self
+        }
+    }
+}
+
+fn main() {
+    let c = (shapes::Circle { r: 1 }).scale(2);
+    let _ = (if true { c } else { shapes::Circle { r: 0 } }).scale(3);
+    let _ = Some(shapes::Circle { r: 2 }).map(|c| c).map(|c| (c, 1)).map(|(c, by)| c.scale(by));
+    let f = shapes::Circle::scale;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_ref_params_and_recursive_calls() {
+        check_assist(
+            convert_free_fn_to_method,
+            r#"
+struct Node { depth: u32 }
+struct Wrapper<'a> { node: &'a Node }
+
+fn depth<'a>(node: &'a mut Node$0, limit: u32) -> Wrapper<'a> {
+    if node.depth < limit {
+        depth(&mut *node, limit - 1);
+        depth(node, 0);
+    }
+    Wrapper { node }
+}
+
+fn main(n: &mut Node) {
+    depth(
+        &mut *n,
+        3,
+    );
+}
+"#,
+            r#"
+struct Node { depth: u32 }
+struct Wrapper<'a> { node: &'a Node }
+
+impl Node {
+    fn depth<'a>(&'a mut self, limit: u32) -> Wrapper<'a> {
+        if self.depth < limit {
+            (*self).depth(limit - 1);
+            self.depth(0);
+        }
+        Wrapper { node: self }
+    }
+}
+
+fn main(n: &mut Node) {
+    (*n).depth(3,
+    );
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_calls_in_other_files() {
+        check_assist(
+            convert_free_fn_to_method,
+            r#"
+//- /main.rs
+mod counter;
+use counter::Counter;
+
+fn main() {
+    let c = Counter(0);
+    counter::get(&c);
+}
+//- /counter.rs
+pub struct Counter(pub u32);
+
+pub fn get$0(c: &Counter) -> u32 {
+    c.0
+}
+"#,
+            r#"
+//- /main.rs
+mod counter;
+use counter::Counter;
+
+fn main() {
+    let c = Counter(0);
+    c.get();
+}
+//- /counter.rs
+pub struct Counter(pub u32);
+
+impl Counter {
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_methods_and_foreign_types() {
+        check_assist_not_applicable(
+            convert_free_fn_to_method,
+            r#"
+struct S;
+impl S {
+    fn f$0(s: &S) {}
+}
+"#,
+        );
+        check_assist_not_applicable(convert_free_fn_to_method, "fn f$0(s: &str) {}");
+        check_assist_not_applicable(convert_free_fn_to_method, "struct S; fn f(s: S) { $0 }");
+    }
+
+    #[test]
+    fn not_applicable_to_generic_types() {
+        mark::check!(free_fn_to_method_generic_type);
+        check_assist_not_applicable(
+            convert_free_fn_to_method,
+            "struct S<T>(T); fn f$0<T>(s: S<T>) {}",
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_name_is_taken() {
+        mark::check!(free_fn_to_method_name_taken);
+        check_assist_not_applicable(
+            convert_free_fn_to_method,
+            r#"
+struct S;
+impl S {
+    fn f(&self) {}
+}
+fn f$0(s: &S) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_imported() {
+        mark::check!(free_fn_to_method_imported);
+        check_assist_not_applicable(
+            convert_free_fn_to_method,
+            r#"
+mod m {
+    pub struct S;
+    pub fn f$0(s: &S) {}
+}
+use m::f;
+"#,
+        );
+    }
+}
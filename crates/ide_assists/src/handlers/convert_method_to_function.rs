@@ -0,0 +1,586 @@
+use std::cmp::Reverse;
+
+use hir::{Adt, ModuleDef};
+use ide_db::{base_db::FileId, defs::Definition, helpers::mod_path_to_ast, search::FileReference};
+use stdx::to_lower_snake_case;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        ArgListOwner, GenericParamsOwner, NameOwner, SelfParamKind,
+    },
+    AstNode, SourceFile, SyntaxKind, SyntaxNode, TextRange, TextSize,
+};
+use test_utils::mark;
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_method_to_assoc_fn
+//
+// Converts a method into an associated function taking the receiver as its first parameter,
+// and its method calls into calls through the type.
+//
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// impl Point {
+//     fn $0dist(&self, other: &Point) -> f64 {
+//         ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+//     }
+// }
+//
+// fn main() {
+//     let p = Point { x: 0.0, y: 0.0 };
+//     p.dist(&p);
+// }
+// ```
+// ->
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// impl Point {
+//     fn dist(point: &Point, other: &Point) -> f64 {
+//         ((point.x - other.x).powi(2) + (point.y - other.y).powi(2)).sqrt()
+//     }
+// }
+//
+// fn main() {
+//     let p = Point { x: 0.0, y: 0.0 };
+//     Point::dist(&p, &p);
+// }
+// ```
+pub(crate) fn convert_method_to_assoc_fn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let method = Method::at_cursor(ctx)?;
+    acc.add(
+        AssistId("convert_method_to_assoc_fn", AssistKind::RefactorRewrite),
+        "Convert to an associated function",
+        method.func.syntax().text_range(),
+        |builder| {
+            let (fn_edits, mut other_edits) = method.edits(ctx, false);
+            let file_id = ctx.frange.file_id;
+            let fn_start = method.func.syntax().text_range().start();
+            let fn_edits =
+                fn_edits.into_iter().map(|edit| Edit { range: edit.range + fn_start, ..edit });
+            match other_edits.iter_mut().find(|(file, _)| *file == file_id) {
+                Some((_, edits)) => edits.extend(fn_edits),
+                None => other_edits.push((file_id, fn_edits.collect())),
+            }
+            for (file_id, edits) in other_edits {
+                apply_edits(builder, file_id, edits);
+            }
+        },
+    )
+}
+
+// Assist: convert_method_to_free_fn
+//
+// Converts a method into a free function taking the receiver as its first parameter, and its
+// method calls into function calls.
+//
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// impl Point {
+//     fn $0norm(&self) -> f64 {
+//         (self.x * self.x + self.y * self.y).sqrt()
+//     }
+// }
+//
+// fn main() {
+//     let p = Point { x: 3.0, y: 4.0 };
+//     p.norm();
+// }
+// ```
+// ->
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// impl Point {
+// }
+//
+// fn norm(point: &Point) -> f64 {
+//     (point.x * point.x + point.y * point.y).sqrt()
+// }
+//
+// fn main() {
+//     let p = Point { x: 3.0, y: 4.0 };
+//     norm(&p);
+// }
+// ```
+pub(crate) fn convert_method_to_free_fn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let method = Method::at_cursor(ctx)?;
+    // The parameters of the impl would have to move onto the function.
+    if method.impl_.generic_param_list().is_some() {
+        mark::hit!(method_to_free_fn_generic_impl);
+        return None;
+    }
+    acc.add(
+        AssistId("convert_method_to_free_fn", AssistKind::RefactorRewrite),
+        "Convert to a free function",
+        method.func.syntax().text_range(),
+        |builder| {
+            let (fn_edits, other_edits) = method.edits(ctx, true);
+            for (file_id, edits) in other_edits {
+                apply_edits(builder, file_id, edits);
+            }
+
+            let file_id = ctx.frange.file_id;
+            builder.edit_file(file_id);
+            let mut fn_text = method.func.syntax().to_string();
+            for edit in normalize(fn_edits).into_iter().rev() {
+                let range = usize::from(edit.range.start())..usize::from(edit.range.end());
+                fn_text.replace_range(range, &edit.text);
+            }
+            let fn_text = SourceFile::parse(&fn_text)
+                .tree()
+                .syntax()
+                .descendants()
+                .find_map(ast::Fn::cast)
+                .map_or(fn_text, |it| it.dedent(IndentLevel(1)).to_string());
+
+            let fn_range = method.func.syntax().text_range();
+            let delete_range = match method.func.syntax().prev_sibling_or_token() {
+                Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => fn_range.cover(ws.text_range()),
+                _ => fn_range,
+            };
+            builder.delete(delete_range);
+            let indent = IndentLevel::from_node(method.impl_.syntax());
+            builder.insert(
+                method.impl_.syntax().text_range().end(),
+                format!("\n\n{}{}", indent, fn_text),
+            );
+        },
+    )
+}
+
+struct Method {
+    func: ast::Fn,
+    impl_: ast::Impl,
+    self_param: ast::SelfParam,
+    adt: Adt,
+    def: hir::Function,
+    self_ty: String,
+    param_name: String,
+}
+
+struct Edit {
+    range: TextRange,
+    text: String,
+    /// Among insertions at the same offset, the ones with the lowest order go first.
+    order: Reverse<TextSize>,
+}
+
+impl Method {
+    fn at_cursor(ctx: &AssistContext) -> Option<Method> {
+        let func = ctx.find_node_at_offset::<ast::Fn>()?;
+        if let Some(body) = func.body() {
+            if body.syntax().text_range().contains_range(ctx.frange.range) {
+                return None;
+            }
+        }
+        let impl_ = func.syntax().parent()?.parent().and_then(ast::Impl::cast)?;
+        if impl_.trait_().is_some() {
+            return None;
+        }
+        let self_param = func.param_list()?.self_param()?;
+        if self_param.ty().is_some() {
+            return None;
+        }
+        let adt = ctx.sema.to_def(&impl_)?.target_ty(ctx.db()).as_adt()?;
+        let def = ctx.sema.to_def(&func)?;
+        let self_ty = impl_.self_ty()?.to_string();
+
+        let mut param_name = to_lower_snake_case(&adt.name(ctx.db()).to_string());
+        let taken = func.syntax().descendants_with_tokens().any(|it| {
+            it.as_token().map_or(false, |token| {
+                token.kind() == SyntaxKind::IDENT && token.text() == param_name.as_str()
+            })
+        });
+        if taken {
+            mark::hit!(method_to_fn_name_taken);
+            param_name = "this".to_string();
+        }
+        Some(Method { func, impl_, self_param, adt, def, self_ty, param_name })
+    }
+
+    /// Edits inside the method relative to its start, and edits of the other places in the
+    /// workspace.
+    fn edits(&self, ctx: &AssistContext, free: bool) -> (Vec<Edit>, Vec<(FileId, Vec<Edit>)>) {
+        let file_id = ctx.frange.file_id;
+        let fn_range = self.func.syntax().text_range();
+        let mut fn_edits = Vec::new();
+
+        let ty = match self.self_param.kind() {
+            SelfParamKind::Owned => self.self_ty.clone(),
+            SelfParamKind::Ref => format!("&{}", self.self_ty),
+            SelfParamKind::MutRef => format!("&mut {}", self.self_ty),
+        };
+        let ty = match self.self_param.lifetime() {
+            Some(lifetime) => ty.replacen('&', &format!("&{} ", lifetime), 1),
+            None => ty,
+        };
+        let mut_ = match self.self_param.kind() {
+            SelfParamKind::Owned if self.self_param.mut_token().is_some() => "mut ",
+            _ => "",
+        };
+        fn_edits.push(Edit::replace(
+            self.self_param.syntax().text_range(),
+            format!("{}{}: {}", mut_, self.param_name, ty),
+        ));
+
+        if let Some(local) = ctx.sema.to_def(&self.self_param) {
+            let usages = Definition::Local(local).usages(&ctx.sema).all();
+            for reference in usages.iter().flat_map(|(_, refs)| refs) {
+                fn_edits.push(Edit::replace(reference.range, self.param_name.clone()));
+            }
+        }
+        if free {
+            // `Self` means nothing outside of the impl.
+            for name_ref in self.func.syntax().descendants().filter_map(ast::NameRef::cast) {
+                if name_ref.text() == "Self" {
+                    fn_edits
+                        .push(Edit::replace(name_ref.syntax().text_range(), self.self_ty.clone()));
+                }
+            }
+        }
+
+        let usages = Definition::ModuleDef(self.def.into()).usages(&ctx.sema).all();
+        let mut other_edits = Vec::new();
+        for (&file, refs) in usages.iter() {
+            let mut edits = Vec::new();
+            for edit in self.call_edits(ctx, refs, free) {
+                if file == file_id && fn_range.contains_range(edit.range) {
+                    fn_edits.push(edit);
+                } else {
+                    edits.push(edit);
+                }
+            }
+            other_edits.push((file, edits));
+        }
+
+        let fn_start = fn_range.start();
+        let fn_edits = fn_edits
+            .into_iter()
+            .map(|edit| Edit { range: edit.range - fn_start, ..edit })
+            .collect();
+        (fn_edits, other_edits)
+    }
+
+    fn call_edits(&self, ctx: &AssistContext, refs: &[FileReference], free: bool) -> Vec<Edit> {
+        let mut edits = Vec::new();
+        for reference in refs {
+            let name_ref = match &reference.name {
+                ast::NameLike::NameRef(it) => it,
+                _ => continue,
+            };
+            let callee = match self.callee(ctx, name_ref.syntax(), free) {
+                Some(it) => it,
+                None => continue,
+            };
+            if let Some(call) = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+                let (receiver, l_paren) =
+                    match (call.receiver(), call.arg_list().and_then(|it| it.l_paren_token())) {
+                        (Some(receiver), Some(l_paren)) => (receiver, l_paren),
+                        _ => continue,
+                    };
+                let receiver_ty = ctx.sema.type_of_expr(&receiver);
+                let is_ref = receiver_ty.as_ref().map_or(false, |it| it.remove_ref().is_some());
+                let is_mut_ref = receiver_ty.as_ref().map_or(false, |it| it.is_mutable_reference());
+                let borrow = match self.self_param.kind() {
+                    SelfParamKind::Ref if !is_ref => "&",
+                    SelfParamKind::MutRef if !is_mut_ref => "&mut ",
+                    _ => "",
+                };
+                let turbofish = call.generic_arg_list().map_or(String::new(), |it| it.to_string());
+                let receiver_range = receiver.syntax().text_range();
+                edits.push(Edit {
+                    range: TextRange::empty(receiver_range.start()),
+                    text: format!("{}{}({}", callee, turbofish, borrow),
+                    order: Reverse(call.syntax().text_range().len()),
+                });
+                let has_args = call.arg_list().map_or(false, |it| it.args().next().is_some());
+                edits.push(Edit::replace(
+                    TextRange::new(receiver_range.end(), l_paren.text_range().end()),
+                    if has_args { ", ".to_string() } else { String::new() },
+                ));
+            } else if free {
+                let path = name_ref.syntax().ancestors().find_map(ast::Path::cast);
+                if let Some(path) = path.filter(|it| it.qualifier().is_some()) {
+                    let generic_args = path
+                        .segment()
+                        .and_then(|it| it.generic_arg_list())
+                        .map_or(String::new(), |it| it.to_string());
+                    edits.push(Edit::replace(
+                        path.syntax().text_range(),
+                        format!("{}{}", callee, generic_args),
+                    ));
+                }
+            }
+        }
+        edits
+    }
+
+    /// How to refer to the converted function from `node`.
+    fn callee(&self, ctx: &AssistContext, node: &SyntaxNode, free: bool) -> Option<String> {
+        let db = ctx.db();
+        let module = ctx.sema.scope(node).module()?;
+        let name = self.func.name()?;
+        if free {
+            let fn_module = self.def.module(db);
+            if fn_module == module {
+                return Some(name.to_string());
+            }
+            let path = module.find_use_path(db, ModuleDef::Module(fn_module))?;
+            Some(format!("{}::{}", mod_path_to_ast(&path), name))
+        } else {
+            let path = module.find_use_path(db, ModuleDef::Adt(self.adt))?;
+            Some(format!("{}::{}", mod_path_to_ast(&path), name))
+        }
+    }
+}
+
+impl Edit {
+    fn replace(range: TextRange, text: String) -> Edit {
+        Edit { range, text, order: Reverse(0.into()) }
+    }
+}
+
+/// Sorts `edits` and merges the insertions at the start of other edits into them.
+fn normalize(mut edits: Vec<Edit>) -> Vec<Edit> {
+    edits.sort_by_key(|it| (it.range.start(), !it.range.is_empty(), it.order));
+    let mut res: Vec<Edit> = Vec::new();
+    for edit in edits {
+        match res.last_mut() {
+            Some(last) if last.range.is_empty() && last.range.start() == edit.range.start() => {
+                last.text.push_str(&edit.text);
+                last.range = edit.range;
+            }
+            _ => res.push(edit),
+        }
+    }
+    res
+}
+
+fn apply_edits(builder: &mut AssistBuilder, file_id: FileId, edits: Vec<Edit>) {
+    builder.edit_file(file_id);
+    for edit in normalize(edits) {
+        builder.replace(edit.range, edit.text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn assoc_fn_rewrites_method_calls() {
+        check_assist(
+            convert_method_to_assoc_fn,
+            r#"
+struct Counter { n: u32 }
+
+impl Counter {
+    fn $0bump(&mut self, by: u32) -> &mut Counter {
+        self.n += by;
+        self
+    }
+}
+
+fn f(c: &mut Counter) {
+    let mut d = Counter { n: 0 };
+    d.bump(1).bump(2);
+    c.bump(3);
+}
+"#,
+            r#"
+struct Counter { n: u32 }
+
+impl Counter {
+    fn bump(counter: &mut Counter, by: u32) -> &mut Counter {
+        counter.n += by;
+        counter
+    }
+}
+
+fn f(c: &mut Counter) {
+    let mut d = Counter { n: 0 };
+    Counter::bump(Counter::bump(&mut d, 1), 2);
+    Counter::bump(c, 3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn assoc_fn_with_owned_receiver_across_modules() {
+        check_assist(
+            convert_method_to_assoc_fn,
+            r#"
+mod shapes {
+    pub struct Square(pub u32);
+
+    impl Square {
+        pub fn $0into_area(mut self) -> u32 {
+            self.0 *= self.0;
+            self.0
+        }
+
+        pub fn area(&self) -> u32 {
+            Square(self.0).into_area()
+        }
+    }
+}
+
+fn main() {
+    shapes::Square(2).into_area();
+}
+"#,
+            r#"
+mod shapes {
+    pub struct Square(pub u32);
+
+    impl Square {
+        pub fn into_area(mut square: Square) -> u32 {
+            square.0 *= square.0;
+            square.0
+        }
+
+        pub fn area(&self) -> u32 {
+            Square::into_area(Square(self.0))
+        }
+    }
+}
+
+fn main() {
+    shapes::Square::into_area(shapes::Square(2));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn assoc_fn_uses_this_when_name_is_taken() {
+        mark::check!(method_to_fn_name_taken);
+        check_assist(
+            convert_method_to_assoc_fn,
+            r#"
+struct Point(u32);
+
+impl Point {
+    fn $0offset(&self, point: u32) -> u32 { self.0 + point }
+}
+"#,
+            r#"
+struct Point(u32);
+
+impl Point {
+    fn offset(this: &Point, point: u32) -> u32 { this.0 + point }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn free_fn_moves_out_of_impl() {
+        check_assist(
+            convert_method_to_free_fn,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32 }
+
+    impl Point {
+        pub fn new(x: i32) -> Self { Point { x } }
+
+        pub fn $0moved(&self, dx: i32) -> Self {
+            Self::new(self.x + dx)
+        }
+    }
+}
+
+fn main() {
+    let p = geo::Point::new(1);
+    p.moved(2);
+    geo::Point::moved(&p, 3);
+}
+"#,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32 }
+
+    impl Point {
+        pub fn new(x: i32) -> Self { Point { x } }
+    }
+
+    pub fn moved(point: &Point, dx: i32) -> Point {
+        Point::new(point.x + dx)
+    }
+}
+
+fn main() {
+    let p = geo::Point::new(1);
+    geo::moved(&p, 2);
+    geo::moved(&p, 3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn free_fn_rewrites_calls_in_other_files() {
+        check_assist(
+            convert_method_to_free_fn,
+            r#"
+//- /main.rs
+mod foo;
+struct S;
+impl S {
+    fn $0get(&self) -> u32 { 0 }
+}
+//- /foo.rs
+fn f(s: &crate::S) -> u32 { s.get() }
+"#,
+            r#"
+//- /main.rs
+mod foo;
+struct S;
+impl S {
+}
+
+fn get(s: &S) -> u32 { 0 }
+//- /foo.rs
+fn f(s: &crate::S) -> u32 { crate::get(s) }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            convert_method_to_assoc_fn,
+            "struct S; impl S { fn new() -> S { S$0 } }",
+        );
+        check_assist_not_applicable(
+            convert_method_to_assoc_fn,
+            "struct S; impl S { fn f(&self) { $0 } }",
+        );
+        check_assist_not_applicable(
+            convert_method_to_assoc_fn,
+            "struct S; trait T { fn f(&self); } impl T for S { fn $0f(&self) {} }",
+        );
+        check_assist_not_applicable(
+            convert_method_to_assoc_fn,
+            "struct S; impl S { fn $0f(self: Box<Self>) {} }",
+        );
+    }
+
+    #[test]
+    fn free_fn_not_applicable_in_generic_impl() {
+        mark::check!(method_to_free_fn_generic_impl);
+        check_assist_not_applicable(
+            convert_method_to_free_fn,
+            "struct S<T>(T); impl<T> S<T> { fn $0get(&self) -> &T { &self.0 } }",
+        );
+    }
+}
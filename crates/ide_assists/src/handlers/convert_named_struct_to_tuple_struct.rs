@@ -0,0 +1,404 @@
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::format_to;
+use syntax::{
+    ast::{
+        self, edit::IndentLevel, AstNode, AttrsOwner, GenericParamsOwner, NameOwner,
+        VisibilityOwner,
+    },
+    SyntaxKind, SyntaxToken, TextRange, TextSize,
+};
+
+use crate::{
+    handlers::convert_tuple_struct_to_named_struct::apply_edits, AssistContext, AssistId,
+    AssistKind, Assists,
+};
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts a struct with named fields to a tuple struct, and updates its constructors, patterns
+// and field accesses.
+//
+// ```
+// struct Poin$0t { x: f32, y: f32 }
+//
+// fn origin() -> Point { Point { x: 0.0, y: 0.0 } }
+// fn x(point: &Point) -> f32 { point.x }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// fn origin() -> Point { Point(0.0, 0.0) }
+// fn x(point: &Point) -> f32 { point.0 }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.field_list()? {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+    // Only offer the assist on the header of the struct.
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let fields = field_list.fields().collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    let names = fields.iter().map(|it| Some(it.name()?.to_string())).collect::<Option<Vec<_>>>()?;
+    let def = ctx.sema.to_def(&strukt)?;
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_named_struct_to_tuple_struct", AssistKind::RefactorRewrite),
+        "Convert to tuple struct",
+        target,
+        |builder| {
+            let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+            if let Some(edit) = definition_edit(&strukt, &field_list, &fields) {
+                edits.entry(ctx.frange.file_id).or_default().push(edit);
+            }
+
+            let mut converted = FxHashSet::default();
+            for (idx, field) in def.fields(ctx.db()).into_iter().enumerate() {
+                for (file_id, references) in Definition::Field(field).usages(&ctx.sema).all() {
+                    let file_edits = edits.entry(file_id).or_default();
+                    for reference in references {
+                        file_edits.extend(field_usage_edits(
+                            &reference,
+                            idx,
+                            &names,
+                            &mut converted,
+                        ));
+                    }
+                }
+            }
+
+            apply_edits(builder, edits);
+        },
+    )
+}
+
+fn definition_edit(
+    strukt: &ast::Struct,
+    field_list: &ast::RecordFieldList,
+    fields: &[ast::RecordField],
+) -> Option<(TextRange, String)> {
+    let start = match strukt.generic_param_list() {
+        Some(it) => it.syntax().text_range().end(),
+        None => strukt.name()?.syntax().text_range().end(),
+    };
+    let fields = fields.iter().map(|field| {
+        let mut buf = String::new();
+        for attr in field.attrs() {
+            format_to!(buf, "{} ", attr);
+        }
+        if let Some(vis) = field.visibility() {
+            format_to!(buf, "{} ", vis);
+        }
+        if let Some(ty) = field.ty() {
+            format_to!(buf, "{}", ty);
+        }
+        buf
+    });
+
+    let mut buf = String::new();
+    if field_list.syntax().text().contains_char('\n') {
+        let indent = IndentLevel::from_node(strukt.syntax());
+        let field_indent = indent + 1;
+        let fields = fields.map(|it| format!("{}{},\n", field_indent, it));
+        format_to!(buf, "(\n{}{})", fields.collect::<String>(), indent);
+    } else {
+        format_to!(buf, "({})", fields.format(", "));
+    }
+    if let Some(where_clause) = strukt.where_clause() {
+        let range = TextRange::new(start, where_clause.syntax().text_range().start());
+        let separator = if strukt
+            .syntax()
+            .text()
+            .slice(range - strukt.syntax().text_range().start())
+            .contains_char('\n')
+        {
+            "\n"
+        } else {
+            " "
+        };
+        format_to!(buf, "{}{}", separator, where_clause);
+    }
+    buf.push(';');
+    Some((TextRange::new(start, strukt.syntax().text_range().end()), buf))
+}
+
+/// Renames a field access to the index of the field, or converts the record expression or
+/// pattern the field is used in.
+fn field_usage_edits(
+    reference: &FileReference,
+    idx: usize,
+    names: &[String],
+    converted: &mut FxHashSet<(TextRange, String)>,
+) -> Vec<(TextRange, String)> {
+    let record = match &reference.name {
+        ast::NameLike::NameRef(name_ref) => ast::RecordExprField::for_field_name(name_ref)
+            .and_then(|it| it.syntax().ancestors().find_map(ast::RecordExpr::cast))
+            .map(Record::Expr)
+            .or_else(|| {
+                ast::RecordPatField::for_field_name_ref(name_ref)
+                    .and_then(|it| it.syntax().ancestors().find_map(ast::RecordPat::cast))
+                    .map(Record::Pat)
+            }),
+        ast::NameLike::Name(name) => ast::RecordPatField::for_field_name(name)
+            .and_then(|it| it.syntax().ancestors().find_map(ast::RecordPat::cast))
+            .map(Record::Pat),
+        ast::NameLike::Lifetime(_) => return Vec::new(),
+    };
+    match record {
+        Some(record) => {
+            // Each record is converted once, for the first of its fields.
+            let key = (record.syntax().text_range(), record.syntax().to_string());
+            if !converted.insert(key) {
+                return Vec::new();
+            }
+            match record {
+                Record::Expr(it) => record_expr_edits(&it, names),
+                Record::Pat(it) => record_pat_edits(&it, names),
+            }
+        }
+        None => vec![(reference.range, idx.to_string())],
+    }
+}
+
+enum Record {
+    Expr(ast::RecordExpr),
+    Pat(ast::RecordPat),
+}
+
+impl Record {
+    fn syntax(&self) -> &syntax::SyntaxNode {
+        match self {
+            Record::Expr(it) => it.syntax(),
+            Record::Pat(it) => it.syntax(),
+        }
+    }
+}
+
+/// Turns `S { x: a, y: b }` into `S(a, b)`, or into `S { 0: a, 1: b }` when the fields aren't
+/// listed in order or the base of a struct update is given.
+fn record_expr_edits(expr: &ast::RecordExpr, names: &[String]) -> Vec<(TextRange, String)> {
+    let (path, list) = match (expr.path(), expr.record_expr_field_list()) {
+        (Some(path), Some(list)) => (path, list),
+        _ => return Vec::new(),
+    };
+    let fields = list
+        .fields()
+        .filter_map(|field| {
+            let name = field.field_name()?.to_string();
+            Some((names.iter().position(|it| *it == name)?, field))
+        })
+        .collect::<Vec<_>>();
+    let in_order = fields.iter().map(|(idx, _)| *idx).eq(0..names.len());
+
+    let mut edits = Vec::new();
+    if in_order && list.dotdot_token().is_none() {
+        let first = fields[0].1.syntax().text_range().start();
+        let last = fields[fields.len() - 1].1.syntax().text_range().end();
+        edits.extend(paren_edits(path.syntax().text_range().end(), &list, first, last));
+        for (_, field) in &fields {
+            if let (Some(name_ref), Some(expr)) = (field.name_ref(), field.expr()) {
+                let range = TextRange::new(
+                    name_ref.syntax().text_range().start(),
+                    expr.syntax().text_range().start(),
+                );
+                edits.push((range, String::new()));
+            }
+        }
+    } else {
+        for (idx, field) in &fields {
+            match field.name_ref() {
+                Some(name_ref) => edits.push((name_ref.syntax().text_range(), idx.to_string())),
+                None => edits.push((
+                    TextRange::empty(field.syntax().text_range().start()),
+                    format!("{}: ", idx),
+                )),
+            }
+        }
+    }
+    edits
+}
+
+/// Turns `S { x, y: b, .. }` into `S(x, b, ..)`, or into `S { 1: y, .. }` when the fields aren't
+/// listed in order.
+fn record_pat_edits(pat: &ast::RecordPat, names: &[String]) -> Vec<(TextRange, String)> {
+    let (path, list) = match (pat.path(), pat.record_pat_field_list()) {
+        (Some(path), Some(list)) => (path, list),
+        _ => return Vec::new(),
+    };
+    let fields = list
+        .fields()
+        .filter_map(|field| {
+            let name = field.field_name()?.to_string();
+            Some((names.iter().position(|it| *it == name)?, field))
+        })
+        .collect::<Vec<_>>();
+    let rest = list.dotdot_token();
+    let is_prefix = fields.iter().map(|(idx, _)| *idx).eq(0..fields.len());
+    let in_order = is_prefix && (rest.is_some() || fields.len() == names.len());
+
+    let mut edits = Vec::new();
+    if in_order && !fields.is_empty() {
+        let first = fields[0].1.syntax().text_range().start();
+        let last = match &rest {
+            Some(rest) => rest.text_range().end(),
+            None => fields[fields.len() - 1].1.syntax().text_range().end(),
+        };
+        edits.extend(paren_edits(path.syntax().text_range().end(), &list, first, last));
+        for (_, field) in &fields {
+            if let (Some(name_ref), Some(pat)) = (field.name_ref(), field.pat()) {
+                let range = TextRange::new(
+                    name_ref.syntax().text_range().start(),
+                    pat.syntax().text_range().start(),
+                );
+                edits.push((range, String::new()));
+            }
+        }
+    } else {
+        for (idx, field) in &fields {
+            match field.name_ref() {
+                Some(name_ref) => edits.push((name_ref.syntax().text_range(), idx.to_string())),
+                None => edits.push((
+                    TextRange::empty(field.syntax().text_range().start()),
+                    format!("{}: ", idx),
+                )),
+            }
+        }
+    }
+    edits
+}
+
+/// Replaces the braces of the field list after `path_end` with parentheses, dropping the
+/// whitespace and trailing comma of single-line lists.
+fn paren_edits(
+    path_end: TextSize,
+    list: &impl AstNode,
+    first: TextSize,
+    last: TextSize,
+) -> Vec<(TextRange, String)> {
+    let tokens = list.syntax().children_with_tokens().filter_map(|it| it.into_token());
+    let (l_curly, r_curly) = match tokens
+        .filter(|it| matches!(it.kind(), SyntaxKind::L_CURLY | SyntaxKind::R_CURLY))
+        .collect_tuple::<(SyntaxToken, SyntaxToken)>()
+    {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let is_newline = |token: Option<SyntaxToken>| {
+        token.map_or(false, |it| it.kind() == SyntaxKind::WHITESPACE && it.text().contains('\n'))
+    };
+    let l_range = if is_newline(l_curly.next_token()) {
+        TextRange::new(path_end, l_curly.text_range().end())
+    } else {
+        TextRange::new(path_end, first)
+    };
+    let r_range = if is_newline(r_curly.prev_token()) {
+        r_curly.text_range()
+    } else {
+        TextRange::new(last, r_curly.text_range().end())
+    };
+    vec![(l_range, "(".to_string()), (r_range, ")".to_string())]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn converts_constructors_patterns_and_accesses() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct $0Span<T> where T: Copy { pub start: T, end: T }
+
+impl<T: Copy> Span<T> {
+    fn new(start: T, end: T) -> Self { Span { start, end } }
+    fn flip(self) -> Self { Span { end: self.start, start: self.end } }
+    fn start(&self) -> T {
+        let Span { start, .. } = self;
+        *start
+    }
+    fn end(&self) -> T {
+        let Span { end: e, .. } = self;
+        *e
+    }
+    fn with_start(self, start: T) -> Self { Span { start, ..self } }
+}
+"#,
+            r#"
+struct Span<T>(pub T, T) where T: Copy;
+
+impl<T: Copy> Span<T> {
+    fn new(start: T, end: T) -> Self { Span(start, end) }
+    fn flip(self) -> Self { Span { 1: self.0, 0: self.1 } }
+    fn start(&self) -> T {
+        let Span(start, ..) = self;
+        *start
+    }
+    fn end(&self) -> T {
+        let Span { 1: e, .. } = self;
+        *e
+    }
+    fn with_start(self, start: T) -> Self { Span { 0: start, ..self } }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_multiline_struct() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+mod m {
+    pub struct $0Config {
+        #[allow(unused)]
+        name: String,
+        verbose: bool,
+    }
+
+    fn make() -> Config {
+        Config {
+            name: String::new(),
+            verbose: false,
+        }
+    }
+}
+"#,
+            r#"
+mod m {
+    pub struct Config(
+        #[allow(unused)] String,
+        bool,
+    );
+
+    fn make() -> Config {
+        Config(
+            String::new(),
+            false,
+        )
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_tuple_structs_or_in_field_list() {
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, "struct S$0(u8);");
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, "struct S { a$0: u8 }");
+    }
+}
@@ -0,0 +1,381 @@
+use hir::{PathResolution, Semantics};
+use ide_db::RootDatabase;
+use itertools::Itertools;
+use syntax::{
+    ast::{self, ArgListOwner, NameOwner},
+    AstNode, TextRange,
+};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_string_concat_to_format
+//
+// Converts a chain of string concatenations, or consecutive `push_str` calls, into a
+// single `format!` call.
+//
+// ```
+// #[lang = "add"]
+// trait Add<Rhs> { type Output; }
+// struct String;
+// impl Add<&str> for String { type Output = String; }
+// fn greet(name: String, punct: &str) -> String {
+//     name $0+ ", hello" + punct
+// }
+// ```
+// ->
+// ```
+// #[lang = "add"]
+// trait Add<Rhs> { type Output; }
+// struct String;
+// impl Add<&str> for String { type Output = String; }
+// fn greet(name: String, punct: &str) -> String {
+//     format!("{name}, hello{punct}")
+// }
+// ```
+pub(crate) fn convert_string_concat_to_format(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let (range, replacement) = concat_to_format(ctx).or_else(|| push_str_to_format(ctx))?;
+    acc.add(
+        AssistId("convert_string_concat_to_format", AssistKind::RefactorRewrite),
+        "Convert to `format!`",
+        range,
+        |builder| builder.replace(range, replacement),
+    )
+}
+
+fn concat_to_format(ctx: &AssistContext) -> Option<(TextRange, String)> {
+    let mut top = ctx.find_node_at_offset::<ast::BinExpr>().filter(is_addition)?;
+    while let Some(parent) = top.syntax().parent().and_then(ast::BinExpr::cast) {
+        if !is_addition(&parent) || parent.lhs()?.syntax() != top.syntax() {
+            break;
+        }
+        top = parent;
+    }
+    if !is_string(&ctx.sema, &ctx.sema.type_of_expr(&top.clone().into())?) {
+        return None;
+    }
+
+    let mut operands = Vec::new();
+    let mut expr: ast::Expr = top.clone().into();
+    while let ast::Expr::BinExpr(bin) = &expr {
+        if !is_addition(bin) {
+            break;
+        }
+        operands.push(bin.rhs()?);
+        expr = bin.lhs()?;
+    }
+    operands.push(into_string_arg(&expr).unwrap_or(expr));
+    operands.reverse();
+
+    let mut format = FormatBuilder::default();
+    for operand in operands {
+        match operand {
+            ast::Expr::RefExpr(it) if it.mut_token().is_none() => {
+                format.push(&ctx.sema, it.expr()?)
+            }
+            operand => format.push(&ctx.sema, operand),
+        }
+    }
+    Some((top.syntax().text_range(), format.build()))
+}
+
+fn push_str_to_format(ctx: &AssistContext) -> Option<(TextRange, String)> {
+    let stmt = ctx.find_node_at_offset::<ast::ExprStmt>()?;
+    let block = stmt.syntax().parent().and_then(ast::BlockExpr::cast)?;
+    let (target, _) = pushed_value(&stmt)?;
+    let stmts = block.statements().collect::<Vec<_>>();
+    let idx = stmts.iter().position(|it| it.syntax() == stmt.syntax())?;
+    let is_push = |stmt: &ast::Stmt| match stmt {
+        ast::Stmt::ExprStmt(it) => pushed_value(it).map_or(false, |(it, _)| it == target),
+        _ => false,
+    };
+    let start = (0..idx).rev().take_while(|&it| is_push(&stmts[it])).last().unwrap_or(idx);
+    let end = (idx..stmts.len()).take_while(|&it| is_push(&stmts[it])).last()?;
+    let pushes = stmts[start..=end].iter().filter_map(|it| match it {
+        ast::Stmt::ExprStmt(it) => pushed_value(it).map(|(_, value)| value),
+        _ => None,
+    });
+
+    let init = start.checked_sub(1).and_then(|it| match &stmts[it] {
+        ast::Stmt::LetStmt(let_stmt) => {
+            let name = match let_stmt.pat()? {
+                ast::Pat::IdentPat(it) => it.name()?,
+                _ => return None,
+            };
+            if name.text() != target.as_str() {
+                return None;
+            }
+            let init = let_stmt.initializer()?;
+            let value = if is_string_new(&init) { None } else { Some(into_string_arg(&init)?) };
+            Some((let_stmt.clone(), value))
+        }
+        _ => None,
+    });
+    if end - start + init.is_some() as usize == 0 {
+        mark::hit!(string_concat_single_push);
+        return None;
+    }
+
+    let mut format = FormatBuilder::default();
+    if let Some((_, Some(value))) = &init {
+        format.push(&ctx.sema, value.clone());
+    }
+    for value in pushes {
+        format.push(&ctx.sema, value);
+    }
+    let end = stmts[end].syntax().text_range().end();
+    let res = match init {
+        Some((let_stmt, _)) => {
+            let ty = let_stmt.ty().map(|it| format!(": {}", it)).unwrap_or_default();
+            let range = TextRange::new(let_stmt.syntax().text_range().start(), end);
+            (range, format!("let {}{} = {};", let_stmt.pat()?, ty, format.build()))
+        }
+        None => {
+            let range = TextRange::new(stmts[start].syntax().text_range().start(), end);
+            (range, format!("{}.push_str(&{});", target, format.build()))
+        }
+    };
+    Some(res)
+}
+
+/// For `s.push_str(value);` and `s.push(value);`, where `s` is a local, returns `s` and
+/// `value`.
+fn pushed_value(stmt: &ast::ExprStmt) -> Option<(String, ast::Expr)> {
+    let call = match stmt.expr()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    let method = call.name_ref()?;
+    if method.text() != "push_str" && method.text() != "push" {
+        return None;
+    }
+    let receiver = match call.receiver()? {
+        ast::Expr::PathExpr(it) => it.path()?.as_single_segment()?.name_ref()?.text().to_string(),
+        _ => return None,
+    };
+    let (value,) = call.arg_list()?.args().collect_tuple()?;
+    let value = match value {
+        ast::Expr::RefExpr(it) if it.mut_token().is_none() => it.expr()?,
+        it => it,
+    };
+    Some((receiver, value))
+}
+
+/// The value formatted by `value.to_string()`, `value.to_owned()` and `String::from(value)`.
+fn into_string_arg(expr: &ast::Expr) -> Option<ast::Expr> {
+    match expr {
+        ast::Expr::MethodCallExpr(call) => {
+            let method = call.name_ref()?;
+            if (method.text() == "to_string" || method.text() == "to_owned")
+                && call.arg_list()?.args().next().is_none()
+            {
+                return call.receiver();
+            }
+            None
+        }
+        ast::Expr::CallExpr(call) => match call.expr()? {
+            ast::Expr::PathExpr(path) if path.path()?.to_string() == "String::from" => {
+                call.arg_list()?.args().exactly_one().ok()
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_string_new(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::CallExpr(call) => {
+            call.arg_list().map_or(false, |it| it.args().next().is_none())
+                && call.expr().map_or(false, |it| it.to_string() == "String::new")
+        }
+        _ => false,
+    }
+}
+
+fn is_addition(bin: &ast::BinExpr) -> bool {
+    bin.op_kind() == Some(ast::BinOp::Addition)
+}
+
+fn is_string(sema: &Semantics<RootDatabase>, ty: &hir::Type) -> bool {
+    match ty.as_adt() {
+        Some(hir::Adt::Struct(it)) => it.name(sema.db).to_string() == "String",
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+struct FormatBuilder {
+    template: String,
+    args: Vec<String>,
+}
+
+impl FormatBuilder {
+    fn push(&mut self, sema: &Semantics<RootDatabase>, expr: ast::Expr) {
+        if let ast::Expr::Literal(lit) = &expr {
+            let text = lit.token().text().to_string();
+            let inline = match lit.kind() {
+                ast::LiteralKind::String(it) if !it.is_raw() => Some(&text[1..text.len() - 1]),
+                ast::LiteralKind::Char if text != "'\"'" => Some(&text[1..text.len() - 1]),
+                ast::LiteralKind::Char => Some("\\\""),
+                _ => None,
+            };
+            if let Some(inline) = inline {
+                self.template.push_str(&inline.replace('{', "{{").replace('}', "}}"));
+                return;
+            }
+        }
+        // Locals can be captured by the format string itself.
+        if let ast::Expr::PathExpr(path) = &expr {
+            if let Some(path) = path.path() {
+                if let Some(PathResolution::Local(_)) = sema.resolve_path(&path) {
+                    if path.as_single_segment().and_then(|it| it.name_ref()).is_some() {
+                        self.template.push_str(&format!("{{{}}}", path));
+                        return;
+                    }
+                }
+            }
+        }
+        self.template.push_str("{}");
+        self.args.push(expr.to_string());
+    }
+
+    fn build(self) -> String {
+        let args = self.args.iter().map(|it| format!(", {}", it)).join("");
+        format!("format!(\"{}\"{})", self.template, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const CORE: &str = r#"
+//- /core.rs crate:core
+pub mod ops {
+    #[lang = "add"]
+    pub trait Add<Rhs = Self> { type Output; fn add(self, rhs: Rhs) -> Self::Output; }
+}
+pub mod string {
+    pub struct String;
+    impl String {
+        pub fn new() -> String { String }
+        pub fn push_str(&mut self, s: &str) {}
+        pub fn push(&mut self, c: char) {}
+    }
+    impl crate::ops::Add<&str> for String {
+        type Output = String;
+        fn add(self, rhs: &str) -> String { self }
+    }
+}
+"#;
+
+    fn fixture(ra_fixture: &str) -> String {
+        format!(
+            "//- /main.rs crate:main deps:core\nuse core::string::String;{}{}",
+            ra_fixture, CORE
+        )
+    }
+
+    fn output(ra_fixture: &str) -> String {
+        format!("use core::string::String;{}\n", ra_fixture)
+    }
+
+    #[test]
+    fn concat_to_format() {
+        check_assist(
+            convert_string_concat_to_format,
+            &fixture(
+                r#"
+struct User { name: String }
+fn f(user: &User, id: u32, sep: &str) -> String {
+    id.to_string() + sep + "{" + &user.name +$0 "}"
+}
+"#,
+            ),
+            &output(
+                r#"
+struct User { name: String }
+fn f(user: &User, id: u32, sep: &str) -> String {
+    format!("{id}{sep}{{{}}}", user.name)
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn push_str_to_format_with_initializer() {
+        check_assist(
+            convert_string_concat_to_format,
+            &fixture(
+                r#"
+fn f(a: &str, n: u8) -> String {
+    let mut s = String::new();
+    s.push_str("a = ");
+    s.push_str$0(a);
+    s.push(',');
+    s.push_str(&n.to_string());
+    s
+}
+"#,
+            ),
+            &output(
+                r#"
+fn f(a: &str, n: u8) -> String {
+    let mut s = format!("a = {a},{}", n.to_string());
+    s
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn push_str_to_format_on_existing_string() {
+        check_assist(
+            convert_string_concat_to_format,
+            &fixture(
+                r#"
+fn f(s: &mut String, xs: &[&str]) {
+    s.len();
+    s.push_str(xs[0]);$0
+    s.push('"');
+}
+"#,
+            ),
+            &output(
+                r#"
+fn f(s: &mut String, xs: &[&str]) {
+    s.len();
+    s.push_str(&format!("{}\"", xs[0]));
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        mark::check!(string_concat_single_push);
+        check_assist_not_applicable(
+            convert_string_concat_to_format,
+            &fixture(
+                r#"
+fn f(s: &mut String) {
+    s.push_str$0("a");
+}
+"#,
+            ),
+        );
+        check_assist_not_applicable(
+            convert_string_concat_to_format,
+            &fixture("\nfn f() -> i32 { 1 +$0 2 }\n"),
+        );
+    }
+}
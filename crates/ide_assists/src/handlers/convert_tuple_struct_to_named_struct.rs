@@ -0,0 +1,358 @@
+use hir::{Adt, ModuleDef};
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::{format_to, to_lower_snake_case};
+use syntax::{
+    ast::{
+        self, edit::IndentLevel, ArgListOwner, AstNode, AttrsOwner, GenericParamsOwner,
+        VisibilityOwner,
+    },
+    SyntaxKind, SyntaxToken, TextRange, TextSize,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_tuple_struct_to_named_struct
+//
+// Converts a tuple struct to a struct with named fields, naming the fields after their types,
+// and updates its constructors, patterns and field accesses.
+//
+// ```
+// struct Poin$0t(Meters, f32);
+// struct Meters(f32);
+//
+// fn x(point: &Point) -> f32 {
+//     let Point(x, _) = point;
+//     (x.0 + point.1) / 2.0
+// }
+// ```
+// ->
+// ```
+// struct Point { meters: Meters, field2: f32 }
+// struct Meters(f32);
+//
+// fn x(point: &Point) -> f32 {
+//     let Point { meters: x, field2: _ } = point;
+//     (x.0 + point.field2) / 2.0
+// }
+// ```
+pub(crate) fn convert_tuple_struct_to_named_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.field_list()? {
+        ast::FieldList::TupleFieldList(it) => it,
+        ast::FieldList::RecordFieldList(_) => return None,
+    };
+    // Only offer the assist on the header of the struct.
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let fields = field_list.fields().collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    let def = ctx.sema.to_def(&strukt)?;
+    let names = field_names(&fields);
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_tuple_struct_to_named_struct", AssistKind::RefactorRewrite),
+        "Convert to named struct",
+        target,
+        |builder| {
+            let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+            edits.entry(ctx.frange.file_id).or_default().push(definition_edit(
+                &strukt,
+                &field_list,
+                &fields,
+                &names,
+            ));
+
+            let usages =
+                Definition::ModuleDef(ModuleDef::Adt(Adt::Struct(def))).usages(&ctx.sema).all();
+            for (file_id, references) in usages {
+                let file_edits = edits.entry(file_id).or_default();
+                for reference in references {
+                    file_edits.extend(struct_usage_edits(&reference, &names));
+                }
+            }
+
+            for (field, name) in def.fields(ctx.db()).into_iter().zip(names.iter()) {
+                for (file_id, references) in Definition::Field(field).usages(&ctx.sema).all() {
+                    let file_edits = edits.entry(file_id).or_default();
+                    for reference in references {
+                        file_edits.push((reference.range, name.clone()));
+                    }
+                }
+            }
+
+            apply_edits(builder, edits);
+        },
+    )
+}
+
+/// Applies the edits of the conversions, skipping edits found twice through different usages.
+pub(super) fn apply_edits(
+    builder: &mut AssistBuilder,
+    edits: FxHashMap<FileId, Vec<(TextRange, String)>>,
+) {
+    for (file_id, file_edits) in edits {
+        builder.edit_file(file_id);
+        let mut seen = FxHashSet::default();
+        for (range, text) in file_edits {
+            if seen.insert(range) {
+                builder.replace(range, text);
+            }
+        }
+    }
+}
+
+/// Names the fields after their types, like `meters` for `Meters`, or by their position.
+fn field_names(fields: &[ast::TupleField]) -> Vec<String> {
+    let mut taken = FxHashSet::default();
+    let derived = fields
+        .iter()
+        .map(|field| match field.ty()? {
+            ast::Type::PathType(it) => {
+                let name = it.path()?.segment()?.name_ref()?.to_string();
+                let is_type_name = name.chars().next().map_or(false, |it| it.is_uppercase());
+                is_type_name.then(|| to_lower_snake_case(&name))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let counts = derived.iter().flatten().cloned().counts();
+    derived
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| match name {
+            Some(name) if counts[&name] == 1 && taken.insert(name.clone()) => name,
+            _ => format!("field{}", idx + 1),
+        })
+        .collect()
+}
+
+fn definition_edit(
+    strukt: &ast::Struct,
+    field_list: &ast::TupleFieldList,
+    fields: &[ast::TupleField],
+    names: &[String],
+) -> (TextRange, String) {
+    let mut buf = String::new();
+    if let Some(where_clause) = strukt.where_clause() {
+        format_to!(buf, " {}", where_clause);
+    }
+    let fields = fields.iter().zip(names).map(|(field, name)| {
+        let mut prefix = String::new();
+        for attr in field.attrs() {
+            format_to!(prefix, "{} ", attr);
+        }
+        if let Some(vis) = field.visibility() {
+            format_to!(prefix, "{} ", vis);
+        }
+        let ty = field.ty().map_or(String::new(), |it| it.to_string());
+        format!("{}{}: {}", prefix, name, ty)
+    });
+    if field_list.syntax().text().contains_char('\n') {
+        let indent = IndentLevel::from_node(strukt.syntax());
+        let field_indent = indent + 1;
+        let fields = fields.map(|it| format!("{}{},\n", field_indent, it));
+        format_to!(buf, " {{\n{}{}}}", fields.collect::<String>(), indent);
+    } else {
+        format_to!(buf, " {{ {} }}", fields.format(", "));
+    }
+    let range = TextRange::new(
+        field_list.syntax().text_range().start(),
+        strukt.syntax().text_range().end(),
+    );
+    (range, buf)
+}
+
+/// Turns `S(a, b)` calls and patterns into `S { x: a, y: b }`.
+fn struct_usage_edits(reference: &FileReference, names: &[String]) -> Vec<(TextRange, String)> {
+    let path = match &reference.name {
+        ast::NameLike::NameRef(it) => match it.syntax().ancestors().find_map(ast::Path::cast) {
+            Some(it) => top_path(it),
+            None => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    let parent = match path.syntax().parent() {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    if let Some(pat) = ast::TupleStructPat::cast(parent.clone()) {
+        let fields = pat.fields().collect::<Vec<_>>();
+        let rest = fields.iter().position(|it| matches!(it, ast::Pat::RestPat(_)));
+        let mut edits =
+            brace_edits(path.syntax().text_range().end(), pat.l_paren_token(), pat.r_paren_token());
+        if edits.is_empty() {
+            return edits;
+        }
+        for (idx, field) in fields.iter().enumerate() {
+            let field_idx = match rest {
+                Some(rest) if idx == rest => continue,
+                Some(rest) if idx > rest => names.len() - (fields.len() - idx),
+                _ => idx,
+            };
+            if let Some(name) = names.get(field_idx) {
+                let offset = field.syntax().text_range().start();
+                edits.push((TextRange::empty(offset), format!("{}: ", name)));
+            }
+        }
+        // `..` has to come last in a record pattern.
+        if let Some(rest) = rest.filter(|&rest| rest + 1 < fields.len()) {
+            let start = fields[rest].syntax().text_range().start();
+            let end = fields[rest + 1].syntax().text_range().start();
+            edits.push((TextRange::new(start, end), String::new()));
+            if let Some((_, r_curly)) = edits.get_mut(1) {
+                *r_curly = format!(", ..{}", r_curly);
+            }
+        }
+        return edits;
+    }
+    if let Some(call) = parent.parent().and_then(ast::CallExpr::cast) {
+        if ast::PathExpr::can_cast(parent.kind()) {
+            let arg_list = match call.arg_list() {
+                Some(it) => it,
+                None => return Vec::new(),
+            };
+            let mut edits = brace_edits(
+                path.syntax().text_range().end(),
+                arg_list.l_paren_token(),
+                arg_list.r_paren_token(),
+            );
+            for (arg, name) in arg_list.args().zip(names) {
+                edits.push((
+                    TextRange::empty(arg.syntax().text_range().start()),
+                    format!("{}: ", name),
+                ));
+            }
+            return edits;
+        }
+    }
+    Vec::new()
+}
+
+fn top_path(path: ast::Path) -> ast::Path {
+    let mut path = path;
+    while let Some(parent) = path.syntax().parent().and_then(ast::Path::cast) {
+        path = parent;
+    }
+    path
+}
+
+/// Replaces the parentheses after `path_end` with braces.
+fn brace_edits(
+    path_end: TextSize,
+    l_paren: Option<SyntaxToken>,
+    r_paren: Option<SyntaxToken>,
+) -> Vec<(TextRange, String)> {
+    let (l_paren, r_paren) = match (l_paren, r_paren) {
+        (Some(l), Some(r)) => (l, r),
+        _ => return Vec::new(),
+    };
+    let is_newline = |token: Option<SyntaxToken>| {
+        token.map_or(false, |it| it.kind() == SyntaxKind::WHITESPACE && it.text().contains('\n'))
+    };
+    let l_curly = if is_newline(l_paren.next_token()) { " {" } else { " { " };
+    let r_curly = if is_newline(r_paren.prev_token()) { "}" } else { " }" };
+    vec![
+        (TextRange::new(path_end, l_paren.text_range().end()), l_curly.to_string()),
+        (r_paren.text_range(), r_curly.to_string()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn converts_constructors_patterns_and_accesses() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Inner;
+struct $0Wrapper<T>(pub Inner, T, T) where T: Copy;
+
+impl<T: Copy> Wrapper<T> {
+    fn new(a: T, b: T) -> Self { Wrapper(Inner, a, b) }
+    fn first(&self) -> T {
+        let Wrapper(_, first, ..) = self;
+        *first
+    }
+    fn last(&self) -> T {
+        let Wrapper(.., last) = self;
+        *last
+    }
+    fn second(&self) -> T { self.2 }
+}
+"#,
+            r#"
+struct Inner;
+struct Wrapper<T> where T: Copy { pub inner: Inner, field2: T, field3: T }
+
+impl<T: Copy> Wrapper<T> {
+    fn new(a: T, b: T) -> Self { Wrapper { inner: Inner, field2: a, field3: b } }
+    fn first(&self) -> T {
+        let Wrapper { inner: _, field2: first, .. } = self;
+        *first
+    }
+    fn last(&self) -> T {
+        let Wrapper { field3: last, .. } = self;
+        *last
+    }
+    fn second(&self) -> T { self.field3 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_usages_in_other_files() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+//- /main.rs
+mod ops;
+pub struct Id$0(
+    u32,
+);
+//- /ops.rs
+use crate::Id;
+
+fn next(id: Id) -> Id {
+    Id(
+        id.0 + 1,
+    )
+}
+"#,
+            r#"
+//- /main.rs
+mod ops;
+pub struct Id {
+    field1: u32,
+}
+//- /ops.rs
+use crate::Id;
+
+fn next(id: Id) -> Id {
+    Id {
+        field1: id.field1 + 1,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_named_structs_or_in_field_list() {
+        check_assist_not_applicable(convert_tuple_struct_to_named_struct, "struct S$0 { a: u8 }");
+        check_assist_not_applicable(convert_tuple_struct_to_named_struct, "struct S(u$08);");
+    }
+}
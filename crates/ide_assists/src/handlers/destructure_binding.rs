@@ -0,0 +1,259 @@
+use hir::{Adt, HasVisibility, ModuleDef, StructKind};
+use ide_db::{defs::Definition, helpers::mod_path_to_ast};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use syntax::ast::{self, AstNode, NameOwner};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: destructure_binding
+//
+// Destructures a local binding of a tuple or struct type, replacing the accesses to its fields
+// with the new bindings.
+//
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// fn norm(p: Point) -> f64 {
+//     let $0q = p;
+//     (q.x * q.x + q.y * q.y).sqrt()
+// }
+// ```
+// ->
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// fn norm(p: Point) -> f64 {
+//     let Point { x, y } = p;
+//     (x * x + y * y).sqrt()
+// }
+// ```
+pub(crate) fn destructure_binding(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let ident_pat = ctx.find_node_at_offset::<ast::IdentPat>()?;
+    let let_stmt = ident_pat.syntax().parent().and_then(ast::LetStmt::cast)?;
+    if ident_pat.ref_token().is_some() || ident_pat.at_token().is_some() {
+        return None;
+    }
+    let name = ident_pat.name()?;
+    let local = ctx.sema.to_def(&ident_pat)?;
+    let scope = match let_stmt.initializer() {
+        Some(it) => ctx.sema.scope(it.syntax()),
+        None => ctx.sema.scope(let_stmt.syntax()),
+    };
+    let module = scope.module()?;
+
+    let ty = local.ty(ctx.db());
+    let tuple_fields = ty.tuple_fields(ctx.db());
+    let (path, kind, field_names) = if !tuple_fields.is_empty() {
+        let names = (0..tuple_fields.len()).map(|idx| idx.to_string()).collect::<Vec<_>>();
+        (None, StructKind::Tuple, names)
+    } else {
+        let strukt = match ty.as_adt()? {
+            Adt::Struct(it) => it,
+            _ => return None,
+        };
+        let fields = strukt.fields(ctx.db());
+        if !fields.iter().all(|field| field.is_visible_from(ctx.db(), module)) {
+            return None;
+        }
+        let path = module.find_use_path(ctx.db(), ModuleDef::Adt(Adt::Struct(strukt)))?;
+        let names = fields.iter().map(|field| field.name(ctx.db()).to_string()).collect();
+        (Some(mod_path_to_ast(&path)), strukt.kind(ctx.db()), names)
+    };
+    if field_names.is_empty() {
+        return None;
+    }
+
+    // Rewrite the accesses to the fields, and give up if the binding is used as a whole.
+    let mut edits = Vec::new();
+    let usages = Definition::Local(local).usages(&ctx.sema).all();
+    for reference in usages.references.values().flatten() {
+        let field_expr = match &reference.name {
+            ast::NameLike::NameRef(name_ref) => name_ref
+                .syntax()
+                .ancestors()
+                .find_map(ast::PathExpr::cast)
+                .and_then(|it| it.syntax().parent())
+                .and_then(ast::FieldExpr::cast),
+            _ => None,
+        };
+        let field_expr = match field_expr {
+            Some(it) => it,
+            None => {
+                mark::hit!(destructure_binding_used_as_a_whole);
+                return None;
+            }
+        };
+        let field = field_expr.name_ref()?.to_string();
+        let idx = field_names.iter().position(|it| *it == field)?;
+        edits.push((field_expr.syntax().text_range(), idx));
+    }
+
+    let taken = {
+        let mut taken = FxHashSet::default();
+        scope.process_all_names(&mut |name, _| {
+            taken.insert(name.to_string());
+        });
+        taken
+    };
+    let bindings = field_names
+        .iter()
+        .map(|field| {
+            let binding = match kind {
+                StructKind::Record => field.clone(),
+                _ => format!("_{}", field),
+            };
+            if taken.contains(&binding) {
+                format!("{}_{}", name, field.trim_start_matches('_'))
+            } else {
+                binding
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let target = ident_pat.syntax().text_range();
+    acc.add(
+        AssistId("destructure_binding", AssistKind::RefactorRewrite),
+        "Destructure binding",
+        target,
+        |builder| {
+            let mut_prefix = if ident_pat.mut_token().is_some() { "mut " } else { "" };
+            let pats = bindings.iter().map(|it| format!("{}{}", mut_prefix, it));
+            let pat = match (&path, kind) {
+                (None, _) if bindings.len() == 1 => format!("({},)", pats.format("")),
+                (None, _) => format!("({})", pats.format(", ")),
+                (Some(path), StructKind::Record) => {
+                    let pats = field_names.iter().zip(&bindings).zip(pats).map(
+                        |((field, binding), pat)| {
+                            if field == binding {
+                                pat
+                            } else {
+                                format!("{}: {}", field, pat)
+                            }
+                        },
+                    );
+                    format!("{} {{ {} }}", path, pats.format(", "))
+                }
+                (Some(path), _) => format!("{}({})", path, pats.format(", ")),
+            };
+            builder.replace(target, pat);
+            for (range, idx) in edits {
+                builder.replace(range, bindings[idx].clone());
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn destructures_tuple() {
+        check_assist(
+            destructure_binding,
+            r#"
+fn main() {
+    let (a, b) = (1, 2);
+    let mut pai$0r = (a, "b", b);
+    pair.0 += pair.2;
+    println(pair.1);
+}
+"#,
+            r#"
+fn main() {
+    let (a, b) = (1, 2);
+    let (mut _0, mut _1, mut _2) = (a, "b", b);
+    _0 += _2;
+    println(_1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructures_structs() {
+        check_assist(
+            destructure_binding,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, pub y: i32 }
+    pub struct Meters(pub f64);
+}
+
+fn f(p: geo::Point, m: geo::Meters) -> f64 {
+    let y = 0;
+    let $0q = p;
+    let n = m;
+    (q.x + q.y + y) as f64 + n.0
+}
+"#,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, pub y: i32 }
+    pub struct Meters(pub f64);
+}
+
+fn f(p: geo::Point, m: geo::Meters) -> f64 {
+    let y = 0;
+    let geo::Point { x, y: q_y } = p;
+    let n = m;
+    (x + q_y + y) as f64 + n.0
+}
+"#,
+        );
+        check_assist(
+            destructure_binding,
+            r#"
+mod geo {
+    pub struct Meters(pub f64);
+}
+use geo::Meters;
+
+fn f(m: Meters) -> f64 {
+    let n$0 = m;
+    n.0 * 2.0
+}
+"#,
+            r#"
+mod geo {
+    pub struct Meters(pub f64);
+}
+use geo::Meters;
+
+fn f(m: Meters) -> f64 {
+    let Meters(_0) = m;
+    _0 * 2.0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_used_as_a_whole_or_on_private_fields() {
+        mark::check!(destructure_binding_used_as_a_whole);
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+fn consume(_: (u8, u8)) {}
+fn main() {
+    let $0pair = (1, 2);
+    let first = pair.0;
+    consume(pair);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+mod m { pub struct Secret { value: u32 } }
+fn f(s: m::Secret) {
+    let $0t = s;
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use hir::{Adt, HasVisibility, Local, Module, ModuleDef, PathResolution, StructKind, Type};
+use ide_db::helpers::mod_path_to_ast;
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, edit::IndentLevel, make},
+    AstNode, SyntaxNode,
+};
+
+use crate::{utils::placeholders_to_tabstops, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: destructure_local
+//
+// Turns a local standing on its own into a `let` or a `match` destructuring it, with a binding
+// for each field. Fields of newtypes and other single-variant types are destructured as well.
+//
+// ```
+// struct Meters(f64);
+// struct Segment { length: Meters, name: u32 }
+//
+// fn f(segment: Segment) {
+//     segment$0;
+// }
+// ```
+// ->
+// ```
+// struct Meters(f64);
+// struct Segment { length: Meters, name: u32 }
+//
+// fn f(segment: Segment) {
+//     let Segment { length: Meters(length), name } = segment;
+// }
+// ```
+pub(crate) fn destructure_local(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let path_expr = ctx.find_node_at_offset::<ast::PathExpr>()?;
+    let parent = path_expr.syntax().parent()?;
+    let target = replaced_node(&parent, &path_expr)?;
+    let local = match ctx.sema.resolve_path(&path_expr.path()?)? {
+        PathResolution::Local(it) => it,
+        _ => return None,
+    };
+    let db = ctx.db();
+    let module = ctx.sema.scope(path_expr.syntax()).module()?;
+    let mut ty = local.ty(db);
+    while let Some(it) = ty.remove_ref() {
+        ty = it;
+    }
+    let taken = taken_names(ctx, path_expr.syntax());
+    let patterns = Patterns { ctx, module, taken: &taken, local };
+
+    let indent = IndentLevel::from_node(&target);
+    let (label, text) = match ty.as_adt()? {
+        Adt::Struct(strukt) => {
+            let path = patterns.path(ModuleDef::Adt(Adt::Struct(strukt)))?;
+            let ctor = Constructor { path, kind: strukt.kind(db), fields: strukt.fields(db) };
+            let pat = patterns.constructor(&ctor, &mut FxHashSet::default())?;
+            ("let", format!("let {} = {};", pat, path_expr))
+        }
+        Adt::Enum(enum_) => {
+            let ctors = enum_
+                .variants(db)
+                .into_iter()
+                .map(|variant| {
+                    let path = patterns.path(ModuleDef::Variant(variant))?;
+                    Some(Constructor { path, kind: variant.kind(db), fields: variant.fields(db) })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            match ctors.as_slice() {
+                [] => return None,
+                [ctor] => {
+                    let pat = patterns.constructor(ctor, &mut FxHashSet::default())?;
+                    ("let", format!("let {} = {};", pat, path_expr))
+                }
+                _ => {
+                    let arms = ctors
+                        .iter()
+                        .map(|ctor| {
+                            let pat = patterns.constructor(ctor, &mut FxHashSet::default())?;
+                            Some(format!("{}{} => {},\n", indent + 1, pat, make::expr_todo()))
+                        })
+                        .collect::<Option<String>>()?;
+                    ("match", format!("match {} {{\n{}{}}}", path_expr, arms, indent))
+                }
+            }
+        }
+        Adt::Union(_) => return None,
+    };
+    let name = local.name(db)?;
+    acc.add(
+        AssistId("destructure_local", AssistKind::Generate),
+        format!("Destructure `{}` with `{}`", name, label),
+        path_expr.syntax().text_range(),
+        |builder| match ctx.config.snippet_cap {
+            Some(cap) => {
+                let text = placeholders_to_tabstops(cap, &text, &make::expr_todo().to_string());
+                builder.replace_snippet(cap, target.text_range(), text)
+            }
+            None => builder.replace(target.text_range(), text),
+        },
+    )
+}
+
+/// The node to replace: the statement made of the path, or the path if it's the tail of a
+/// block.
+fn replaced_node(parent: &SyntaxNode, path_expr: &ast::PathExpr) -> Option<SyntaxNode> {
+    if let Some(stmt) = ast::ExprStmt::cast(parent.clone()) {
+        return Some(stmt.syntax().clone());
+    }
+    let block = ast::BlockExpr::cast(parent.clone())?;
+    if block.tail_expr()?.syntax() != path_expr.syntax() {
+        return None;
+    }
+    Some(path_expr.syntax().clone())
+}
+
+fn taken_names(ctx: &AssistContext, node: &SyntaxNode) -> FxHashSet<String> {
+    let mut taken = FxHashSet::default();
+    ctx.sema.scope(node).process_all_names(&mut |name, _| {
+        taken.insert(name.to_string());
+    });
+    taken
+}
+
+struct Constructor {
+    path: String,
+    kind: StructKind,
+    fields: Vec<hir::Field>,
+}
+
+struct Patterns<'a> {
+    ctx: &'a AssistContext<'a>,
+    module: Module,
+    /// The names in scope, which the bindings must not shadow.
+    taken: &'a FxHashSet<String>,
+    local: Local,
+}
+
+impl Patterns<'_> {
+    fn path(&self, def: ModuleDef) -> Option<String> {
+        let path = self.module.find_use_path(self.ctx.db(), def)?;
+        Some(mod_path_to_ast(&path).to_string())
+    }
+
+    /// The pattern for `ctor`, with its fields destructured one level deeper where their type
+    /// has a single constructor.
+    fn constructor(&self, ctor: &Constructor, used: &mut FxHashSet<String>) -> Option<String> {
+        let db = self.ctx.db();
+        if !ctor.fields.iter().all(|it| it.is_visible_from(db, self.module)) {
+            return None;
+        }
+        let mut pats = Vec::new();
+        for (idx, field) in ctor.fields.iter().enumerate() {
+            let field_name = field.name(db).to_string();
+            let base = match ctor.kind {
+                StructKind::Record => field_name.clone(),
+                _ => format!("_{}", idx),
+            };
+            let pat = match self.single_constructor(&field.signature_ty(db)) {
+                Some(inner) => self.nested(&inner, &base, used),
+                None => self.binding(&base, used),
+            };
+            pats.push(match ctor.kind {
+                StructKind::Record if pat == field_name => pat,
+                StructKind::Record => format!("{}: {}", field_name, pat),
+                _ => pat,
+            });
+        }
+        Some(render(ctor, pats))
+    }
+
+    /// The pattern for a field of a type with a single constructor, with bindings named after
+    /// the field.
+    fn nested(&self, ctor: &Constructor, base: &str, used: &mut FxHashSet<String>) -> String {
+        let db = self.ctx.db();
+        let pats = ctor
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let field_name = field.name(db).to_string();
+                let binding = match ctor.kind {
+                    _ if ctor.fields.len() == 1 => self.binding(base, used),
+                    StructKind::Record => self.binding(&field_name, used),
+                    _ => self.binding(&format!("{}_{}", base, idx), used),
+                };
+                match ctor.kind {
+                    StructKind::Record if binding != field_name => {
+                        format!("{}: {}", field_name, binding)
+                    }
+                    _ => binding,
+                }
+            })
+            .collect();
+        render(ctor, pats)
+    }
+
+    /// The constructor of `ty`, if it's a newtype or a single-variant enum whose fields are all
+    /// visible.
+    fn single_constructor(&self, ty: &Type) -> Option<Constructor> {
+        let db = self.ctx.db();
+        let ctor = match ty.as_adt()? {
+            Adt::Struct(strukt) if strukt.kind(db) == StructKind::Tuple => {
+                let fields = strukt.fields(db);
+                if fields.len() != 1 {
+                    return None;
+                }
+                let path = self.path(ModuleDef::Adt(Adt::Struct(strukt)))?;
+                Constructor { path, kind: StructKind::Tuple, fields }
+            }
+            Adt::Enum(enum_) => match enum_.variants(db).as_slice() {
+                [variant] => {
+                    let path = self.path(ModuleDef::Variant(*variant))?;
+                    Constructor { path, kind: variant.kind(db), fields: variant.fields(db) }
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if !ctor.fields.iter().all(|it| it.is_visible_from(db, self.module)) {
+            return None;
+        }
+        Some(ctor)
+    }
+
+    /// A fresh name for a binding, prefixed with the name of the local if `base` is taken.
+    fn binding(&self, base: &str, used: &mut FxHashSet<String>) -> String {
+        let mut name = base.to_string();
+        if self.taken.contains(&name) || used.contains(&name) {
+            let local = self.local.name(self.ctx.db()).map(|it| it.to_string()).unwrap_or_default();
+            name = format!("{}_{}", local, base.trim_start_matches('_'));
+        }
+        used.insert(name.clone());
+        name
+    }
+}
+
+fn render(ctor: &Constructor, pats: Vec<String>) -> String {
+    match ctor.kind {
+        StructKind::Record if pats.is_empty() => format!("{} {{}}", ctor.path),
+        StructKind::Record => format!("{} {{ {} }}", ctor.path, pats.iter().format(", ")),
+        StructKind::Tuple => format!("{}({})", ctor.path, pats.iter().format(", ")),
+        StructKind::Unit => ctor.path.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn destructures_struct_with_let() {
+        check_assist(
+            destructure_local,
+            r#"
+mod geo {
+    pub struct Id(pub u32);
+    pub enum Tagged { Tag { id: Id, label: u8 } }
+    pub enum Wrapped { Only { value: u8 } }
+    pub struct Shape { pub id: Id, pub tag: Tagged, pub sides: u8, pub pair: (u8, u8), pub wrapped: Wrapped }
+}
+fn f(shape: &geo::Shape, sides: u8) {
+    let _ = 1;
+    shape$0
+}
+"#,
+            r#"
+mod geo {
+    pub struct Id(pub u32);
+    pub enum Tagged { Tag { id: Id, label: u8 } }
+    pub enum Wrapped { Only { value: u8 } }
+    pub struct Shape { pub id: Id, pub tag: Tagged, pub sides: u8, pub pair: (u8, u8), pub wrapped: Wrapped }
+}
+fn f(shape: &geo::Shape, sides: u8) {
+    let _ = 1;
+    let geo::Shape { id: geo::Id(id), tag: geo::Tagged::Tag { id: shape_id, label }, sides: shape_sides, pair, wrapped: geo::Wrapped::Only { value: wrapped } } = shape;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructures_enum_with_match() {
+        check_assist(
+            destructure_local,
+            r#"
+struct Meters(f64);
+enum Event {
+    Move { distance: Meters, run: bool },
+    Jump(Meters, u32),
+    Stop,
+}
+fn handle(event: Event) {
+    if true {
+        event$0;
+    }
+}
+"#,
+            r#"
+struct Meters(f64);
+enum Event {
+    Move { distance: Meters, run: bool },
+    Jump(Meters, u32),
+    Stop,
+}
+fn handle(event: Event) {
+    if true {
+        match event {
+            Event::Move { distance: Meters(distance), run } => ${1:todo!()},
+            Event::Jump(Meters(_0), _1) => ${2:todo!()},
+            Event::Stop => ${0:todo!()},
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_other_expressions() {
+        check_assist_not_applicable(
+            destructure_local,
+            r#"
+struct S { a: u8 }
+fn consume(s: S) {}
+fn f(s: S) {
+    consume(s$0);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            destructure_local,
+            r#"
+fn f(n: u32) {
+    n$0;
+}
+"#,
+        );
+        check_assist_not_applicable(
+            destructure_local,
+            r#"
+mod m { pub struct Secret { value: u32 } }
+fn f(s: m::Secret) {
+    s$0;
+}
+"#,
+        );
+    }
+}
@@ -1,15 +1,11 @@
-use std::{iter::once, ops::RangeInclusive};
-
+use stdx::format_to;
 use syntax::{
-    algo::replace_children,
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
-        make,
     },
     AstNode,
-    SyntaxKind::{FN, LOOP_EXPR, L_CURLY, R_CURLY, WHILE_EXPR, WHITESPACE},
-    SyntaxNode,
+    SyntaxKind::{FN, LOOP_EXPR, L_CURLY, R_CURLY, WHILE_EXPR},
 };
 
 use crate::{
@@ -42,30 +38,17 @@ use crate::{
 // ```
 pub(crate) fn convert_to_guarded_return(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let if_expr: ast::IfExpr = ctx.find_node_at_offset()?;
-    if if_expr.else_branch().is_some() {
-        return None;
+    match if_expr.else_branch() {
+        None => guard_tail_if(acc, ctx, if_expr),
+        Some(else_branch) => guard_if_else(acc, ctx, if_expr, else_branch),
     }
+}
 
-    let cond = if_expr.condition()?;
-
-    // Check if there is an IfLet that we can handle.
-    let if_let_pat = match cond.pat() {
-        None => None, // No IfLet, supported.
-        Some(ast::Pat::TupleStructPat(pat)) if pat.fields().count() == 1 => {
-            let path = pat.path()?;
-            match path.qualifier() {
-                None => {
-                    let bound_ident = pat.fields().next().unwrap();
-                    Some((path, bound_ident))
-                }
-                Some(_) => return None,
-            }
-        }
-        Some(_) => return None, // Unsupported IfLet.
-    };
-
-    let cond_expr = cond.expr()?;
-    let then_block = if_expr.then_branch()?;
+/// `if cond { body }` as the last expression of a function or a loop body. Directly nested
+/// `if`s become consecutive guards.
+fn guard_tail_if(acc: &mut Assists, ctx: &AssistContext, if_expr: ast::IfExpr) -> Option<()> {
+    let mut guards = vec![GuardCond::new(&if_expr)?];
+    let mut then_block = if_expr.then_branch()?;
 
     let parent_block = if_expr.syntax().parent()?.ancestors().find_map(ast::BlockExpr::cast)?;
 
@@ -86,9 +69,9 @@ pub(crate) fn convert_to_guarded_return(acc: &mut Assists, ctx: &AssistContext)
 
     let parent_container = parent_block.syntax().parent()?;
 
-    let early_expression: ast::Expr = match parent_container.kind() {
-        WHILE_EXPR | LOOP_EXPR => make::expr_continue(),
-        FN => make::expr_return(None),
+    let early_expression = match parent_container.kind() {
+        WHILE_EXPR | LOOP_EXPR => "continue",
+        FN => "return",
         _ => return None,
     };
 
@@ -98,95 +81,242 @@ pub(crate) fn convert_to_guarded_return(acc: &mut Assists, ctx: &AssistContext)
 
     then_block.syntax().last_child_or_token().filter(|t| t.kind() == R_CURLY)?;
 
+    while then_block.statements().next().is_none() {
+        let inner = match then_block.tail_expr() {
+            Some(ast::Expr::IfExpr(it)) if it.else_branch().is_none() => it,
+            _ => break,
+        };
+        match (GuardCond::new(&inner), inner.then_branch()) {
+            (Some(guard), Some(block)) => {
+                guards.push(guard);
+                then_block = block;
+            }
+            _ => break,
+        }
+    }
+
     let target = if_expr.syntax().text_range();
     acc.add(
         AssistId("convert_to_guarded_return", AssistKind::RefactorRewrite),
         "Convert to guarded return",
         target,
         |edit| {
-            let if_indent_level = IndentLevel::from_node(&if_expr.syntax());
-            let new_block = match if_let_pat {
-                None => {
-                    // If.
-                    let new_expr = {
-                        let then_branch =
-                            make::block_expr(once(make::expr_stmt(early_expression).into()), None);
-                        let cond = invert_boolean_expression(&ctx.sema, cond_expr);
-                        make::expr_if(make::condition(cond, None), then_branch, None)
-                            .indent(if_indent_level)
-                    };
-                    replace(new_expr.syntax(), &then_block, &parent_block, &if_expr)
-                }
-                Some((path, bound_ident)) => {
-                    // If-let.
-                    let match_expr = {
-                        let happy_arm = {
-                            let pat = make::tuple_struct_pat(
-                                path,
-                                once(make::ident_pat(make::name("it")).into()),
-                            );
-                            let expr = {
-                                let name_ref = make::name_ref("it");
-                                let segment = make::path_segment(name_ref);
-                                let path = make::path_unqualified(segment);
-                                make::expr_path(path)
-                            };
-                            make::match_arm(once(pat.into()), expr)
-                        };
-
-                        let sad_arm = make::match_arm(
-                            // FIXME: would be cool to use `None` or `Err(_)` if appropriate
-                            once(make::wildcard_pat().into()),
-                            early_expression,
+            let indent = IndentLevel::from_node(if_expr.syntax());
+            let mut buf = String::new();
+            for guard in &guards {
+                match guard {
+                    GuardCond::If(cond) => {
+                        let cond = invert_boolean_expression(&ctx.sema, cond.clone());
+                        format_to!(
+                            buf,
+                            "if {} {{\n{1}    {2};\n{1}}}",
+                            cond,
+                            indent,
+                            early_expression
+                        );
+                    }
+                    GuardCond::IfLet { path, bound_ident, expr } => {
+                        // FIXME: would be cool to use `None` or `Err(_)` if appropriate
+                        format_to!(
+                            buf,
+                            "let {0} = match {1} {{\n{3}    {2}(it) => it,\n{3}    _ => {4},\n{3}}};",
+                            bound_ident,
+                            expr,
+                            path,
+                            indent,
+                            early_expression
                         );
+                    }
+                }
+                format_to!(buf, "\n{}", indent);
+            }
+            buf.push_str(&block_contents(&then_block, guards.len() as u8));
+            edit.replace(target, buf);
+        },
+    )
+}
 
-                        make::expr_match(cond_expr, make::match_arm_list(vec![happy_arm, sad_arm]))
-                    };
+/// `if cond { body } else { .. }` where either branch leaves the enclosing block.
+fn guard_if_else(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+    if_expr: ast::IfExpr,
+    else_branch: ast::ElseBranch,
+) -> Option<()> {
+    // The value of the `if` must not be needed in the middle of an expression.
+    let stmt = if_expr.syntax().parent().and_then(ast::ExprStmt::cast);
+    let is_tail =
+        if_expr.syntax().parent().and_then(ast::BlockExpr::cast).map_or(false, |it| {
+            it.tail_expr().map_or(false, |tail| tail.syntax() == if_expr.syntax())
+        });
+    if stmt.is_none() && !is_tail {
+        return None;
+    }
+    let target = stmt.as_ref().map_or(if_expr.syntax(), |it| it.syntax()).text_range();
+    let indent = IndentLevel::from_node(if_expr.syntax());
+
+    let then_block = if_expr.then_branch()?;
+    if block_diverges(&then_block) {
+        // Leading branches of an `else if` chain which leave the block become separate `if`s.
+        let mut guards = Vec::new();
+        let mut current = if_expr.clone();
+        let rest = loop {
+            let cond = current.condition()?;
+            let then_block = current.then_branch()?;
+            if !block_diverges(&then_block) {
+                break current.to_string();
+            }
+            guards.push(format!("if {} {}", cond, then_block));
+            match current.else_branch() {
+                Some(ast::ElseBranch::IfExpr(it)) => current = it,
+                Some(ast::ElseBranch::Block(block)) => {
+                    break block_stmts(&block, stmt.is_some());
+                }
+                None => break String::new(),
+            }
+        };
+        return acc.add(
+            AssistId("convert_to_guarded_return", AssistKind::RefactorRewrite),
+            "Convert to guarded return",
+            target,
+            |edit| {
+                let mut buf = guards.join(&format!("\n{}", indent));
+                if !rest.is_empty() {
+                    format_to!(buf, "\n{}{}", indent, rest);
+                }
+                edit.replace(target, buf);
+            },
+        );
+    }
 
-                    let let_stmt = make::let_stmt(
-                        make::ident_pat(make::name(&bound_ident.syntax().to_string())).into(),
-                        Some(match_expr),
-                    );
-                    let let_stmt = let_stmt.indent(if_indent_level);
-                    replace(let_stmt.syntax(), &then_block, &parent_block, &if_expr)
+    if !else_diverges(&else_branch) {
+        return None;
+    }
+    let guard = GuardCond::new(&if_expr)?;
+    acc.add(
+        AssistId("convert_to_guarded_return", AssistKind::RefactorRewrite),
+        "Convert to guarded return",
+        target,
+        |edit| {
+            let else_block = match &else_branch {
+                ast::ElseBranch::Block(block) => block.to_string(),
+                ast::ElseBranch::IfExpr(else_if) => {
+                    format!("{{\n{0}    {1}\n{0}}}", indent, else_if.indent(IndentLevel(1)))
                 }
             };
-            edit.replace_ast(parent_block, ast::BlockExpr::cast(new_block).unwrap());
-
-            fn replace(
-                new_expr: &SyntaxNode,
-                then_block: &ast::BlockExpr,
-                parent_block: &ast::BlockExpr,
-                if_expr: &ast::IfExpr,
-            ) -> SyntaxNode {
-                let then_block_items = then_block.dedent(IndentLevel(1));
-                let end_of_then = then_block_items.syntax().last_child_or_token().unwrap();
-                let end_of_then =
-                    if end_of_then.prev_sibling_or_token().map(|n| n.kind()) == Some(WHITESPACE) {
-                        end_of_then.prev_sibling_or_token().unwrap()
-                    } else {
-                        end_of_then
-                    };
-                let mut then_statements = new_expr.children_with_tokens().chain(
-                    then_block_items
-                        .syntax()
-                        .children_with_tokens()
-                        .skip(1)
-                        .take_while(|i| *i != end_of_then),
-                );
-                replace_children(
-                    &parent_block.syntax(),
-                    RangeInclusive::new(
-                        if_expr.clone().syntax().clone().into(),
-                        if_expr.syntax().clone().into(),
-                    ),
-                    &mut then_statements,
-                )
+            let mut buf = match guard {
+                GuardCond::If(cond) => {
+                    format!("if {} {}", invert_boolean_expression(&ctx.sema, cond), else_block)
+                }
+                GuardCond::IfLet { path, bound_ident, expr } => format!(
+                    "let {0} = match {1} {{\n{3}    {2}(it) => it,\n{3}    _ => {4},\n{3}}};",
+                    bound_ident,
+                    expr,
+                    path,
+                    indent,
+                    match &else_branch {
+                        ast::ElseBranch::Block(block) => block.indent(IndentLevel(1)).to_string(),
+                        ast::ElseBranch::IfExpr(it) => it.indent(IndentLevel(1)).to_string(),
+                    }
+                ),
+            };
+            let body = block_stmts(&then_block, stmt.is_some());
+            if !body.is_empty() {
+                format_to!(buf, "\n{}{}", indent, body);
             }
+            edit.replace(target, buf);
         },
     )
 }
 
+enum GuardCond {
+    If(ast::Expr),
+    IfLet { path: ast::Path, bound_ident: ast::Pat, expr: ast::Expr },
+}
+
+impl GuardCond {
+    fn new(if_expr: &ast::IfExpr) -> Option<GuardCond> {
+        let cond = if_expr.condition()?;
+        let cond_expr = cond.expr()?;
+
+        // Check if there is an IfLet that we can handle.
+        match cond.pat() {
+            None => Some(GuardCond::If(cond_expr)), // No IfLet, supported.
+            Some(ast::Pat::TupleStructPat(pat)) if pat.fields().count() == 1 => {
+                let path = pat.path()?;
+                match path.qualifier() {
+                    None => {
+                        let bound_ident = pat.fields().next().unwrap();
+                        Some(GuardCond::IfLet { path, bound_ident, expr: cond_expr })
+                    }
+                    Some(_) => None,
+                }
+            }
+            Some(_) => None, // Unsupported IfLet.
+        }
+    }
+}
+
+/// The statements of `block`, moved out of `levels` blocks.
+fn block_contents(block: &ast::BlockExpr, levels: u8) -> String {
+    let text = block.dedent(IndentLevel(levels)).to_string();
+    let inner = text.strip_prefix('{').and_then(|it| it.strip_suffix('}')).unwrap_or(&text);
+    inner.trim().to_string()
+}
+
+/// Like `block_contents`, for a block in the place of a statement, whose tail expression has to
+/// become a statement as well.
+fn block_stmts(block: &ast::BlockExpr, is_stmt: bool) -> String {
+    let mut text = block_contents(block, 1);
+    let needs_semi = block.tail_expr().map_or(false, |tail| {
+        !matches!(
+            tail,
+            ast::Expr::IfExpr(_)
+                | ast::Expr::MatchExpr(_)
+                | ast::Expr::BlockExpr(_)
+                | ast::Expr::LoopExpr(_)
+                | ast::Expr::WhileExpr(_)
+                | ast::Expr::ForExpr(_)
+                | ast::Expr::EffectExpr(_)
+        )
+    });
+    if is_stmt && needs_semi {
+        text.push(';');
+    }
+    text
+}
+
+fn expr_diverges(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::ReturnExpr(_) | ast::Expr::ContinueExpr(_) | ast::Expr::BreakExpr(_) => true,
+        ast::Expr::MacroCall(call) => call
+            .path()
+            .and_then(|it| it.segment()?.name_ref())
+            .map_or(false, |it| it.text() == "panic" || it.text() == "unreachable"),
+        _ => false,
+    }
+}
+
+fn block_diverges(block: &ast::BlockExpr) -> bool {
+    match block.tail_expr() {
+        Some(tail) => expr_diverges(&tail),
+        None => match block.statements().last() {
+            Some(ast::Stmt::ExprStmt(stmt)) => stmt.expr().map_or(false, |it| expr_diverges(&it)),
+            _ => false,
+        },
+    }
+}
+
+fn else_diverges(else_branch: &ast::ElseBranch) -> bool {
+    match else_branch {
+        ast::ElseBranch::Block(block) => block_diverges(block),
+        ast::ElseBranch::IfExpr(if_expr) => {
+            if_expr.then_branch().map_or(false, |it| block_diverges(&it))
+                && if_expr.else_branch().map_or(false, |it| else_diverges(&it))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -512,4 +642,200 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn convert_nested_ifs() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main(n: Option<u32>) {
+    if$0 ready() {
+        if let Some(n) = n {
+            if n > 1 {
+                foo(n);
+                bar();
+            }
+        }
+    }
+}
+"#,
+            r#"
+fn main(n: Option<u32>) {
+    if !ready() {
+        return;
+    }
+    let n = match n {
+        Some(it) => it,
+        _ => return,
+    };
+    if !(n > 1) {
+        return;
+    }
+    foo(n);
+    bar();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_diverging_else() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main() {
+    for x in xs() {
+        if$0 x.is_valid() {
+            foo(x);
+            bar(x)
+        } else {
+            log();
+            continue;
+        }
+        baz();
+    }
+}
+"#,
+            r#"
+fn main() {
+    for x in xs() {
+        if !x.is_valid() {
+            log();
+            continue;
+        }
+        foo(x);
+        bar(x);
+        baz();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_if_let_with_diverging_else() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main(n: Option<u32>) -> u32 {
+    if$0 let Some(n) = n {
+        n + 1
+    } else {
+        return 0;
+    }
+}
+"#,
+            r#"
+fn main(n: Option<u32>) -> u32 {
+    let n = match n {
+        Some(it) => it,
+        _ => {
+            return 0;
+        },
+    };
+    n + 1
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_diverging_else_if_chain() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main(n: u32) {
+    if$0 n > 10 {
+        foo(n);
+    } else if n > 5 {
+        return;
+    } else {
+        panic!("too small");
+    }
+}
+"#,
+            r#"
+fn main(n: u32) {
+    if !(n > 10) {
+        if n > 5 {
+            return;
+        } else {
+            panic!("too small");
+        }
+    }
+    foo(n);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_leading_diverging_branches() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main(n: u32) -> u32 {
+    if$0 n == 0 {
+        return 0;
+    } else if n == 1 {
+        return 1;
+    } else {
+        let m = n - 1;
+        m * 2
+    }
+}
+"#,
+            r#"
+fn main(n: u32) -> u32 {
+    if n == 0 {
+        return 0;
+    }
+    if n == 1 {
+        return 1;
+    }
+    let m = n - 1;
+    m * 2
+}
+"#,
+        );
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main(n: u32) {
+    if$0 n == 0 {
+        return;
+    } else if n == 1 {
+        foo();
+    } else {
+        bar();
+    }
+}
+"#,
+            r#"
+fn main(n: u32) {
+    if n == 0 {
+        return;
+    }
+    if n == 1 {
+        foo();
+    } else {
+        bar();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_if_else_in_expression() {
+        check_assist_not_applicable(
+            convert_to_guarded_return,
+            r#"
+fn main(n: u32) -> u32 {
+    let x = if$0 n == 0 { 1 } else { return 0 };
+    x
+}
+"#,
+        );
+    }
 }
@@ -0,0 +1,347 @@
+use ide_db::helpers::wildcard_arm::absorbed_variants;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::ast::{self, edit::IndentLevel, AstNode};
+
+use crate::{utils::build_pat, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: expand_wildcard_arm
+//
+// Replaces the `_` arm of a `match` on an enum with the variants it handles.
+//
+// ```
+// enum Action { Move { distance: u32 }, Jump, Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Stop => {}
+//         $0_ => {}
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Jump, Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Stop => {}
+//         Action::Move { distance } | Action::Jump => {}
+//     }
+// }
+// ```
+pub(crate) fn expand_wildcard_arm(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (arm, pats) = wildcard_arm_variants(ctx)?;
+    let target = arm.pat()?.syntax().text_range();
+    acc.add(
+        AssistId("expand_wildcard_arm", AssistKind::RefactorRewrite),
+        "Expand `_` into the variants it handles",
+        target,
+        |builder| builder.replace(target, pats.iter().format(" | ").to_string()),
+    )
+}
+
+// Assist: replace_wildcard_with_variant_arms
+//
+// Replaces the `_` arm of a `match` on an enum with an arm for each variant it handles, all
+// with the body of the `_` arm.
+//
+// ```
+// enum Action { Move { distance: u32 }, Jump, Stop }
+//
+// fn handle(action: Action) -> u32 {
+//     match action {
+//         Action::Stop => 0,
+//         $0_ => 1,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Jump, Stop }
+//
+// fn handle(action: Action) -> u32 {
+//     match action {
+//         Action::Stop => 0,
+//         Action::Move { distance } => 1,
+//         Action::Jump => 1,
+//     }
+// }
+// ```
+pub(crate) fn replace_wildcard_with_variant_arms(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let (arm, pats) = wildcard_arm_variants(ctx)?;
+    let body = arm.expr()?;
+    let target = arm.syntax().text_range();
+    acc.add(
+        AssistId("replace_wildcard_with_variant_arms", AssistKind::RefactorRewrite),
+        "Replace `_` with an arm for each variant",
+        target,
+        |builder| {
+            // Arms other than the last need a comma unless their body is a block.
+            let sep = match body {
+                ast::Expr::BlockExpr(_) => "",
+                _ => ",",
+            };
+            let last = if arm.comma_token().is_some() { "," } else { "" };
+            let arms = pats
+                .iter()
+                .enumerate()
+                .map(|(idx, pat)| {
+                    let comma = if idx + 1 == pats.len() { last } else { sep };
+                    format!("{} => {}{}", pat, body, comma)
+                })
+                .join(&arm_separator(&arm));
+            builder.replace(target, arms);
+        },
+    )
+}
+
+// Assist: add_variant_arms_before_wildcard
+//
+// Adds an arm for each variant the `_` arm of a `match` on an enum handles, before it.
+//
+// ```
+// enum Action { Move { distance: u32 }, Jump, Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Stop => {}
+//         $0_ => {}
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Jump, Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Stop => {}
+//         Action::Move { distance } => {}
+//         Action::Jump => {}
+//         _ => {}
+//     }
+// }
+// ```
+pub(crate) fn add_variant_arms_before_wildcard(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let (arm, pats) = wildcard_arm_variants(ctx)?;
+    let target = arm.syntax().text_range();
+    acc.add(
+        AssistId("add_variant_arms_before_wildcard", AssistKind::Generate),
+        "Add an arm for each variant before `_`",
+        target,
+        |builder| {
+            let sep = arm_separator(&arm);
+            let mut buf = String::new();
+            for pat in &pats {
+                format_to!(buf, "{} => {{}}{}", pat, sep);
+            }
+            builder.insert(target.start(), buf);
+        },
+    )
+}
+
+/// The `_` arm at the cursor, with patterns for the variants it handles.
+fn wildcard_arm_variants(ctx: &AssistContext) -> Option<(ast::MatchArm, Vec<ast::Pat>)> {
+    let arm = ctx.find_node_at_offset::<ast::MatchArm>()?;
+    if !arm.pat()?.syntax().text_range().contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    let variants = absorbed_variants(&ctx.sema, &arm)?;
+    if variants.is_empty() {
+        return None;
+    }
+    let module = ctx.sema.scope(arm.syntax()).module()?;
+    let pats = variants
+        .into_iter()
+        .map(|variant| build_pat(ctx.db(), module, variant))
+        .collect::<Option<Vec<_>>>()?;
+    Some((arm, pats))
+}
+
+/// What goes between arms next to `arm`, which depends on whether they're on one line.
+fn arm_separator(arm: &ast::MatchArm) -> String {
+    let single_line =
+        arm.syntax().parent().map_or(false, |arm_list| !arm_list.text().contains_char('\n'));
+    if single_line {
+        " ".to_string()
+    } else {
+        format!("\n{}", IndentLevel::from_node(arm.syntax()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn keeps_partially_handled_variants() {
+        check_assist(
+            expand_wildcard_arm,
+            r#"
+mod shapes {
+    pub enum Shape { Circle(u32), Square(u32), Point }
+}
+use shapes::Shape;
+
+fn area(shape: &Shape) -> u32 {
+    match shape {
+        Shape::Circle(0) => 0,
+        Shape::Point | Shape::Square(_) if true => 0,
+        Shape::Square(side) => side * side,
+        _$0 => 1,
+    }
+}
+"#,
+            r#"
+mod shapes {
+    pub enum Shape { Circle(u32), Square(u32), Point }
+}
+use shapes::Shape;
+
+fn area(shape: &Shape) -> u32 {
+    match shape {
+        Shape::Circle(0) => 0,
+        Shape::Point | Shape::Square(_) if true => 0,
+        Shape::Square(side) => side * side,
+        Shape::Circle(_) | Shape::Point => 1,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_nothing_is_absorbed() {
+        check_assist_not_applicable(
+            expand_wildcard_arm,
+            r#"
+enum E { A, B }
+fn f(e: E) {
+    match e {
+        E::A => (),
+        E::B => (),
+        $0_ => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_guarded_or_non_enum_wildcards() {
+        check_assist_not_applicable(
+            expand_wildcard_arm,
+            r#"
+enum E { A, B }
+fn f(e: E) {
+    match e {
+        E::A => (),
+        $0_ if true => (),
+        _ => (),
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            expand_wildcard_arm,
+            r#"
+fn f(n: u32) {
+    match n {
+        0 => (),
+        $0_ => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replaces_wildcard_with_arms() {
+        check_assist(
+            replace_wildcard_with_variant_arms,
+            r#"
+enum E { A, B(u8), C { x: u8 } }
+fn f(e: E) -> u8 {
+    match e {
+        E::B(0) => 0,
+        _$0 => {
+            1
+        }
+    }
+}
+"#,
+            r#"
+enum E { A, B(u8), C { x: u8 } }
+fn f(e: E) -> u8 {
+    match e {
+        E::B(0) => 0,
+        E::A => {
+            1
+        }
+        E::B(_) => {
+            1
+        }
+        E::C { x } => {
+            1
+        }
+    }
+}
+"#,
+        );
+        check_assist(
+            replace_wildcard_with_variant_arms,
+            r#"
+enum E { A, B, C }
+fn f(e: E) -> u8 {
+    match e { E::A => 0, $0_ => 1 }
+}
+"#,
+            r#"
+enum E { A, B, C }
+fn f(e: E) -> u8 {
+    match e { E::A => 0, E::B => 1, E::C => 1 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn adds_arms_before_wildcard() {
+        check_assist(
+            add_variant_arms_before_wildcard,
+            r#"
+enum E { A, B(u8, u8), C }
+fn f(e: &E) {
+    if true {
+        match e {
+            E::C => (),
+            $0_ => (),
+        }
+    }
+}
+"#,
+            r#"
+enum E { A, B(u8, u8), C }
+fn f(e: &E) {
+    if true {
+        match e {
+            E::C => (),
+            E::A => {}
+            E::B(_, _) => {}
+            _ => (),
+        }
+    }
+}
+"#,
+        );
+    }
+}
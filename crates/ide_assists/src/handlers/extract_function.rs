@@ -2,19 +2,21 @@ use std::iter;
 
 use ast::make;
 use either::Either;
-use hir::{HirDisplay, Local};
+use hir::{HirDisplay, Local, ModuleDef};
 use ide_db::{
     defs::{Definition, NameRefClass},
+    helpers::{mod_path_to_ast, FamousDefs},
     search::{FileReference, ReferenceAccess, SearchScope},
 };
 use itertools::Itertools;
+use rustc_hash::FxHashSet;
 use stdx::format_to;
 use syntax::{
     algo::SyntaxRewriter,
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
-        AstNode,
+        AstNode, GenericParamsOwner, NameOwner,
     },
     SyntaxElement,
     SyntaxKind::{self, BLOCK_EXPR, BREAK_EXPR, COMMENT, PATH_EXPR, RETURN_EXPR},
@@ -24,6 +26,7 @@ use test_utils::mark;
 
 use crate::{
     assist_context::{AssistContext, Assists},
+    utils::pretty::reformat,
     AssistId,
 };
 
@@ -85,6 +88,7 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext) -> Option
         return None;
     }
     let control_flow = external_control_flow(ctx, &body)?;
+    let is_async = body_contains_await(&body);
 
     let target_range = body.text_range();
 
@@ -103,6 +107,7 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext) -> Option
                 ret_ty,
                 body,
                 vars_defined_in_body_and_outlive,
+                is_async,
             };
 
             let new_indent = IndentLevel::from_node(&insert_after);
@@ -181,28 +186,43 @@ fn external_control_flow(ctx: &AssistContext, body: &FunctionBody) -> Option<Con
     }
 
     let kind = match (try_expr, ret_expr, break_expr, continue_expr) {
-        (Some(e), None, None, None) => {
+        (Some(e), ret_expr, break_expr, continue_expr) => {
+            // Early exits besides `?` are reported through `ControlFlow`, in the `Ok`/`Some` case.
+            let exit = match (ret_expr, break_expr, continue_expr) {
+                (None, None, None) => None,
+                (Some(r), None, None) => match r.expr() {
+                    Some(expr) => match expr_err_kind(&expr, ctx) {
+                        Some(kind) => {
+                            return Some(ControlFlow {
+                                kind: Some(FlowKind::TryReturn { expr, kind }),
+                            })
+                        }
+                        None => Some(FlowKind::ReturnValue(expr)),
+                    },
+                    None => Some(FlowKind::Return),
+                },
+                (None, Some(b), None) => match b.expr() {
+                    Some(expr) => Some(FlowKind::BreakValue(expr)),
+                    None => Some(FlowKind::Break),
+                },
+                (None, None, Some(_)) => Some(FlowKind::Continue),
+                _ => {
+                    mark::hit!(external_control_flow_try_and_bc);
+                    return None;
+                }
+            };
             let func = e.syntax().ancestors().find_map(ast::Fn::cast)?;
             let def = ctx.sema.to_def(&func)?;
             let ret_ty = def.ret_type(ctx.db());
             let kind = try_kind_of_ty(ret_ty, ctx)?;
 
-            Some(FlowKind::Try { kind })
-        }
-        (Some(_), Some(r), None, None) => match r.expr() {
-            Some(expr) => {
-                if let Some(kind) = expr_err_kind(&expr, ctx) {
-                    Some(FlowKind::TryReturn { expr, kind })
-                } else {
-                    mark::hit!(external_control_flow_try_and_return_non_err);
-                    return None;
+            match exit {
+                Some(exit) => {
+                    let control_flow = control_flow_path(ctx, syntax)?;
+                    Some(FlowKind::TryExit { kind, exit: Box::new(exit), control_flow })
                 }
+                None => Some(FlowKind::Try { kind }),
             }
-            None => return None,
-        },
-        (Some(_), _, _, _) => {
-            mark::hit!(external_control_flow_try_and_bc);
-            return None;
         }
         (None, Some(r), None, None) => match r.expr() {
             Some(expr) => Some(FlowKind::ReturnValue(expr)),
@@ -227,6 +247,30 @@ fn external_control_flow(ctx: &AssistContext, body: &FunctionBody) -> Option<Con
     Some(ControlFlow { kind })
 }
 
+/// Path to `ControlFlow` from `node`, which is left unqualified without a `core` to find it in.
+fn control_flow_path(ctx: &AssistContext, node: &SyntaxNode) -> Option<ast::Path> {
+    let module = ctx.sema.scope(node).module()?;
+    let path = FamousDefs(&ctx.sema, Some(module.krate()))
+        .core_ops_ControlFlow()
+        .and_then(|it| module.find_use_path(ctx.db(), ModuleDef::from(it)))
+        .map(|it| mod_path_to_ast(&it));
+    Some(path.unwrap_or_else(|| make_path_from_text("ControlFlow")))
+}
+
+/// Whether `body` awaits futures itself, rather than in closures or async blocks inside it.
+fn body_contains_await(body: &FunctionBody) -> bool {
+    body.descendants().filter_map(ast::AwaitExpr::cast).any(|await_expr| {
+        await_expr.syntax().ancestors().take_while(|it| body.contains_node(it)).all(|it| {
+            match it.kind() {
+                SyntaxKind::CLOSURE_EXPR | SyntaxKind::FN => false,
+                _ => {
+                    ast::EffectExpr::cast(it).map_or(true, |effect| effect.async_token().is_none())
+                }
+            }
+        })
+    })
+}
+
 /// Checks is expr is `Err(_)` or `None`
 fn expr_err_kind(expr: &ast::Expr, ctx: &AssistContext) -> Option<TryKind> {
     let func_name = match expr {
@@ -254,6 +298,7 @@ struct Function {
     ret_ty: RetType,
     body: FunctionBody,
     vars_defined_in_body_and_outlive: Vec<Local>,
+    is_async: bool,
 }
 
 #[derive(Debug)]
@@ -373,6 +418,12 @@ enum FlowKind {
         expr: ast::Expr,
         kind: TryKind,
     },
+    /// `?` together with another early exit, which is returned as `ControlFlow::Break`
+    TryExit {
+        kind: TryKind,
+        exit: Box<FlowKind>,
+        control_flow: ast::Path,
+    },
     /// Break without value (`return;`)
     Break,
     /// Break with value (`break $expr;`)
@@ -392,7 +443,7 @@ impl FlowKind {
         match self {
             FlowKind::Return | FlowKind::ReturnValue(_) => make::expr_return(expr),
             FlowKind::Break | FlowKind::BreakValue(_) => make::expr_break(expr),
-            FlowKind::Try { .. } | FlowKind::TryReturn { .. } => {
+            FlowKind::Try { .. } | FlowKind::TryReturn { .. } | FlowKind::TryExit { .. } => {
                 stdx::never!("cannot have result handler with try");
                 expr.unwrap_or_else(|| make::expr_return(None))
             }
@@ -408,7 +459,7 @@ impl FlowKind {
             FlowKind::ReturnValue(expr)
             | FlowKind::BreakValue(expr)
             | FlowKind::TryReturn { expr, .. } => ctx.sema.type_of_expr(expr),
-            FlowKind::Try { .. } => {
+            FlowKind::Try { .. } | FlowKind::TryExit { .. } => {
                 stdx::never!("try does not have defined expr_ty");
                 None
             }
@@ -923,6 +974,7 @@ fn format_replacement(ctx: &AssistContext, fun: &Function, indent: IndentLevel)
         let func = make::expr_path(make_path_from_text(&fun.name));
         make::expr_call(func, args)
     };
+    let call_expr = if fun.is_async { make::expr_await(call_expr) } else { call_expr };
 
     let handler = FlowHandler::from_ret_ty(fun, &ret_ty);
 
@@ -953,6 +1005,7 @@ fn format_replacement(ctx: &AssistContext, fun: &Function, indent: IndentLevel)
 enum FlowHandler {
     None,
     Try { kind: TryKind },
+    TryControlFlow { kind: TryKind, exit: FlowKind, control_flow: ast::Path, unit: bool },
     If { action: FlowKind },
     IfOption { action: FlowKind },
     MatchOption { none: FlowKind },
@@ -963,6 +1016,12 @@ impl FlowHandler {
     fn from_ret_ty(fun: &Function, ret_ty: &FunType) -> FlowHandler {
         match &fun.control_flow.kind {
             None => FlowHandler::None,
+            Some(FlowKind::TryExit { kind, exit, control_flow }) => FlowHandler::TryControlFlow {
+                kind: kind.clone(),
+                exit: (**exit).clone(),
+                control_flow: control_flow.clone(),
+                unit: *ret_ty == FunType::Unit,
+            },
             Some(flow_kind) => {
                 let action = flow_kind.clone();
                 if *ret_ty == FunType::Unit {
@@ -973,9 +1032,9 @@ impl FlowHandler {
                         FlowKind::ReturnValue(_) | FlowKind::BreakValue(_) => {
                             FlowHandler::IfOption { action }
                         }
-                        FlowKind::Try { kind } | FlowKind::TryReturn { kind, .. } => {
-                            FlowHandler::Try { kind: kind.clone() }
-                        }
+                        FlowKind::Try { kind }
+                        | FlowKind::TryReturn { kind, .. }
+                        | FlowKind::TryExit { kind, .. } => FlowHandler::Try { kind: kind.clone() },
                     }
                 } else {
                     match flow_kind {
@@ -985,9 +1044,9 @@ impl FlowHandler {
                         FlowKind::ReturnValue(_) | FlowKind::BreakValue(_) => {
                             FlowHandler::MatchResult { err: action }
                         }
-                        FlowKind::Try { kind } | FlowKind::TryReturn { kind, .. } => {
-                            FlowHandler::Try { kind: kind.clone() }
-                        }
+                        FlowKind::Try { kind }
+                        | FlowKind::TryReturn { kind, .. }
+                        | FlowKind::TryExit { kind, .. } => FlowHandler::Try { kind: kind.clone() },
                     }
                 }
             }
@@ -998,6 +1057,40 @@ impl FlowHandler {
         match self {
             FlowHandler::None => call_expr,
             FlowHandler::Try { kind: _ } => make::expr_try(call_expr),
+            FlowHandler::TryControlFlow { kind: _, exit, control_flow, unit } => {
+                let value_name = "value";
+                let variant = |name| {
+                    make::path_qualified(
+                        control_flow.clone(),
+                        make::path_segment(make::name_ref(name)),
+                    )
+                };
+                let value_pat = || make::ident_pat(make::name(value_name)).into();
+                let unit_pat = || make::tuple_pat(iter::empty()).into();
+
+                let break_arm = {
+                    let has_value =
+                        matches!(exit, FlowKind::ReturnValue(_) | FlowKind::BreakValue(_));
+                    let (pat, value) = if has_value {
+                        (value_pat(), Some(make::expr_path(make_path_from_text(value_name))))
+                    } else {
+                        (unit_pat(), None)
+                    };
+                    let pat = make::tuple_struct_pat(variant("Break"), iter::once(pat));
+                    make::match_arm(iter::once(pat.into()), exit.make_result_handler(value))
+                };
+                let continue_arm = {
+                    let (pat, value) = if *unit {
+                        (unit_pat(), make::expr_empty_block())
+                    } else {
+                        (value_pat(), make::expr_path(make_path_from_text(value_name)))
+                    };
+                    let pat = make::tuple_struct_pat(variant("Continue"), iter::once(pat));
+                    make::match_arm(iter::once(pat.into()), value)
+                };
+                let arms = make::match_arm_list(vec![break_arm, continue_arm]);
+                make::expr_match(make::expr_try(call_expr), arms)
+            }
             FlowHandler::If { action } => {
                 let action = action.make_result_handler(None);
                 let stmt = make::expr_stmt(action);
@@ -1079,13 +1172,125 @@ fn format_function(
     let params = make_param_list(ctx, module, fun);
     let ret_ty = make_ret_ty(ctx, module, fun);
     let body = make_body(ctx, old_indent, new_indent, fun);
-    format_to!(fn_def, "\n\n{}fn $0{}{}", new_indent, fun.name, params);
+    let (generic_params, where_clause) = make_generics(fun, &params, ret_ty.as_ref());
+    let async_kw = if fun.is_async { "async " } else { "" };
+    format_to!(fn_def, "\n\n{}{}fn $0{}", new_indent, async_kw, fun.name);
+    if let Some(generic_params) = generic_params {
+        format_to!(fn_def, "{}", generic_params);
+    }
+    format_to!(fn_def, "{}", params);
     if let Some(ret_ty) = ret_ty {
         format_to!(fn_def, " {}", ret_ty);
     }
+    if let Some(where_clause) = where_clause {
+        format_to!(fn_def, " {}", where_clause);
+    }
     format_to!(fn_def, " {}", body);
 
-    fn_def
+    reformat(&fn_def, IndentLevel(0))
+}
+
+/// Generic parameters of the enclosing function, and of its impl if the extracted function goes
+/// outside of it, which the extracted function mentions, with the bounds on them.
+fn make_generics(
+    fun: &Function,
+    params: &ast::ParamList,
+    ret_ty: Option<&ast::RetType>,
+) -> (Option<String>, Option<String>) {
+    let anchor = match &fun.body {
+        FunctionBody::Expr(expr) => expr.syntax().clone(),
+        FunctionBody::Span { parent, .. } => parent.syntax().clone(),
+    };
+    let func = match anchor.ancestors().find_map(ast::Fn::cast) {
+        Some(it) => it,
+        None => return (None, None),
+    };
+    let mut owners: Vec<Box<dyn GenericParamsOwner>> = Vec::new();
+    if fun.self_param.is_none() {
+        if let Some(impl_) = func.syntax().ancestors().find_map(ast::Impl::cast) {
+            owners.push(Box::new(impl_));
+        }
+    }
+    owners.push(Box::new(func));
+    let generic_params: Vec<ast::GenericParam> = owners
+        .iter()
+        .filter_map(|it| it.generic_param_list())
+        .flat_map(|it| it.generic_params())
+        .collect();
+    if generic_params.is_empty() {
+        return (None, None);
+    }
+    let preds: Vec<ast::WherePred> =
+        owners.iter().filter_map(|it| it.where_clause()).flat_map(|it| it.predicates()).collect();
+
+    let mut used: FxHashSet<String> = idents(params.syntax()).collect();
+    used.extend(ret_ty.into_iter().flat_map(|it| idents(it.syntax())));
+    used.extend(fun.body.descendants().flat_map(|it| {
+        it.children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| matches!(it.kind(), SyntaxKind::IDENT | SyntaxKind::LIFETIME_IDENT))
+            .map(|it| it.text().to_string())
+            .collect::<Vec<_>>()
+    }));
+    let param_name = |param: &ast::GenericParam| -> Option<String> {
+        match param {
+            ast::GenericParam::TypeParam(it) => Some(it.name()?.to_string()),
+            ast::GenericParam::ConstParam(it) => Some(it.name()?.to_string()),
+            ast::GenericParam::LifetimeParam(it) => Some(it.lifetime()?.to_string()),
+        }
+    };
+    let mut needed: FxHashSet<String> =
+        generic_params.iter().filter_map(param_name).filter(|it| used.contains(it)).collect();
+    // The bounds on needed parameters may mention others.
+    loop {
+        let mut mentioned: Vec<String> = Vec::new();
+        for param in &generic_params {
+            if param_name(param).map_or(false, |it| needed.contains(&it)) {
+                mentioned.extend(idents(param.syntax()));
+            }
+        }
+        for pred in &preds {
+            if bounds_needed(pred, &needed) {
+                mentioned.extend(idents(pred.syntax()));
+            }
+        }
+        let len = needed.len();
+        needed.extend(
+            generic_params.iter().filter_map(param_name).filter(|it| mentioned.contains(it)),
+        );
+        if needed.len() == len {
+            break;
+        }
+    }
+    if needed.is_empty() {
+        return (None, None);
+    }
+
+    let generic_params = generic_params
+        .iter()
+        .filter(|it| param_name(it).map_or(false, |it| needed.contains(&it)))
+        .format(", ");
+    let preds = preds.iter().filter(|pred| bounds_needed(pred, &needed)).format(", ").to_string();
+    let where_clause = if preds.is_empty() { None } else { Some(format!("where {}", preds)) };
+    (Some(format!("<{}>", generic_params)), where_clause)
+}
+
+/// Whether `pred` bounds a type or lifetime which mentions one of the `needed` parameters.
+fn bounds_needed(pred: &ast::WherePred, needed: &FxHashSet<String>) -> bool {
+    let bounded = match (pred.ty(), pred.lifetime()) {
+        (Some(ty), _) => ty.syntax().clone(),
+        (None, Some(lifetime)) => lifetime.syntax().clone(),
+        (None, None) => return false,
+    };
+    idents(&bounded).any(|it| needed.contains(&it))
+}
+
+/// Names of types, values and lifetimes in `node`.
+fn idents(node: &SyntaxNode) -> impl Iterator<Item = String> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| matches!(it.kind(), SyntaxKind::IDENT | SyntaxKind::LIFETIME_IDENT))
+        .map(|it| it.text().to_string())
 }
 
 fn make_param_list(ctx: &AssistContext, module: hir::Module, fun: &Function) -> ast::ParamList {
@@ -1141,6 +1346,28 @@ fn make_ret_ty(ctx: &AssistContext, module: hir::Module, fun: &Function) -> Opti
                 vec![fun_ty.make_ty(ctx, module), handler_ty],
             )
         }
+        FlowHandler::TryControlFlow { kind, exit, control_flow, unit } => {
+            let exit_ty =
+                exit.expr_ty(ctx).map(|ty| make_ty(&ty, ctx, module)).unwrap_or_else(make::ty_unit);
+            let control_flow_ty = if *unit {
+                make::ty(&format!("{}<{}>", control_flow, exit_ty))
+            } else {
+                make::ty(&format!("{}<{}, {}>", control_flow, exit_ty, fun_ty.make_ty(ctx, module)))
+            };
+            match kind {
+                TryKind::Option => {
+                    make::ty_generic(make::name_ref("Option"), iter::once(control_flow_ty))
+                }
+                TryKind::Result { ty: parent_ret_ty } => {
+                    let handler_ty = parent_ret_ty
+                        .type_parameters()
+                        .nth(1)
+                        .map(|ty| make_ty(&ty, ctx, module))
+                        .unwrap_or_else(make::ty_unit);
+                    make::ty_generic(make::name_ref("Result"), vec![control_flow_ty, handler_ty])
+                }
+            }
+        }
         FlowHandler::If { .. } => make::ty("bool"),
         FlowHandler::IfOption { action } => {
             let handler_ty = action
@@ -1231,15 +1458,26 @@ fn make_body(
         FlowHandler::Try { kind } => {
             let block = with_default_tail_expr(block, make::expr_unit());
             map_tail_expr(block, |tail_expr| {
-                let constructor = match kind {
-                    TryKind::Option => "Some",
-                    TryKind::Result { .. } => "Ok",
-                };
-                let func = make::expr_path(make_path_from_text(constructor));
+                let func = make::expr_path(make_path_from_text(try_constructor(kind)));
                 let args = make::arg_list(iter::once(tail_expr));
                 make::expr_call(func, args)
             })
         }
+        FlowHandler::TryControlFlow { kind, control_flow, .. } => {
+            let block = with_default_tail_expr(block, make::expr_unit());
+            map_tail_expr(block, |tail_expr| {
+                let continue_ = make::path_qualified(
+                    control_flow.clone(),
+                    make::path_segment(make::name_ref("Continue")),
+                );
+                let value = make::expr_call(
+                    make::expr_path(continue_),
+                    make::arg_list(iter::once(tail_expr)),
+                );
+                let func = make::expr_path(make_path_from_text(try_constructor(kind)));
+                make::expr_call(func, make::arg_list(iter::once(value)))
+            })
+        }
         FlowHandler::If { .. } => {
             let lit_false = ast::Literal::cast(make::tokens::literal("false").parent()).unwrap();
             with_tail_expr(block, lit_false.into())
@@ -1263,6 +1501,14 @@ fn make_body(
     block.indent(new_indent)
 }
 
+/// The variant wrapping the successful result of a function returning `kind`.
+fn try_constructor(kind: &TryKind) -> &'static str {
+    match kind {
+        TryKind::Option => "Some",
+        TryKind::Result { .. } => "Ok",
+    }
+}
+
 fn map_tail_expr(block: ast::BlockExpr, f: impl FnOnce(ast::Expr) -> ast::Expr) -> ast::BlockExpr {
     let tail_expr = match block.tail_expr() {
         Some(tail_expr) => tail_expr,
@@ -1415,6 +1661,16 @@ fn update_external_control_flow(handler: &FlowHandler, syntax: &SyntaxNode) -> S
 fn make_rewritten_flow(handler: &FlowHandler, arg_expr: Option<ast::Expr>) -> Option<ast::Expr> {
     let value = match handler {
         FlowHandler::None | FlowHandler::Try { .. } => return None,
+        FlowHandler::TryControlFlow { kind, control_flow, .. } => {
+            let break_ = make::path_qualified(
+                control_flow.clone(),
+                make::path_segment(make::name_ref("Break")),
+            );
+            let expr = arg_expr.unwrap_or_else(|| make::expr_tuple(Vec::new()));
+            let value = make::expr_call(make::expr_path(break_), make::arg_list(iter::once(expr)));
+            let func = make::expr_path(make_path_from_text(try_constructor(kind)));
+            make::expr_call(func, make::arg_list(iter::once(value)))
+        }
         FlowHandler::If { .. } => {
             ast::Literal::cast(make::tokens::literal("true").parent()).unwrap().into()
         }
@@ -1806,6 +2062,38 @@ fn $0fun_name(n: u32, m: u32) -> u32 {
         )
     }
 
+    #[test]
+    fn long_signature_is_broken() {
+        check_assist(
+            extract_function,
+            r"
+fn foo() -> u32 {
+    let first_number = 1;
+    let second_number = 2;
+    let third_number = 3;
+    let fourth_big_number = 4;
+    $0first_number + second_number + third_number + fourth_big_number$0
+}",
+            r"
+fn foo() -> u32 {
+    let first_number = 1;
+    let second_number = 2;
+    let third_number = 3;
+    let fourth_big_number = 4;
+    fun_name(first_number, second_number, third_number, fourth_big_number)
+}
+
+fn $0fun_name(
+    first_number: u32,
+    second_number: u32,
+    third_number: u32,
+    fourth_big_number: u32,
+) -> u32 {
+    first_number + second_number + third_number + fourth_big_number
+}",
+        )
+    }
+
     #[test]
     fn argument_and_locals() {
         check_assist(
@@ -3335,44 +3623,195 @@ fn $0fun_name() -> Result<i32, i64> {
 
     #[test]
     fn try_and_break() {
-        mark::check!(external_control_flow_try_and_bc);
-        check_assist_not_applicable(
+        check_assist(
             extract_function,
             r##"
 enum Option<T> { None, Some(T) }
+enum ControlFlow<B, C = ()> { Continue(C), Break(B) }
 use Option::*;
 fn foo() -> Option<()> {
     loop {
         let n = Some(1);
         $0let m = n? + 1;
-        break;
-        let k = 2;
-        let k = k + 1;$0
-        let r = n + k;
+        if m > 2 {
+            break;
+        }
+        let k = m + 1;$0
+        let r = k + 1;
+    }
+    Some(())
+}"##,
+            r##"
+enum Option<T> { None, Some(T) }
+enum ControlFlow<B, C = ()> { Continue(C), Break(B) }
+use Option::*;
+fn foo() -> Option<()> {
+    loop {
+        let n = Some(1);
+        let k = match fun_name(n)? {
+            ControlFlow::Break(()) => break,
+            ControlFlow::Continue(value) => value,
+        };
+        let r = k + 1;
     }
     Some(())
+}
+
+fn $0fun_name(n: Option<i32>) -> Option<ControlFlow<(), i32>> {
+    let m = n? + 1;
+    if m > 2 {
+        return Some(ControlFlow::Break(()));
+    }
+    let k = m + 1;
+    Some(ControlFlow::Continue(k))
 }"##,
         );
     }
 
     #[test]
     fn try_and_return_ok() {
-        mark::check!(external_control_flow_try_and_return_non_err);
-        check_assist_not_applicable(
+        check_assist(
             extract_function,
-            r##"
-enum Result<T, E> { Ok(T), Err(E), }
-use Result::*;
-fn foo() -> Result<(), i64> {
+            &format!(
+                r##"//- /main.rs crate:main deps:core
+fn foo() -> Option<i64> {{
     let n = 1;
     $0let k = foo()?;
+    if k == 42 {{
+        return Some(1);
+    }}$0
+    Some(k)
+}}
+{}"##,
+                FamousDefs::FIXTURE
+            ),
+            r##"
+fn foo() -> Option<i64> {
+    let n = 1;
+    let k = match fun_name()? {
+        core::ops::ControlFlow::Break(value) => return value,
+        core::ops::ControlFlow::Continue(value) => value,
+    };
+    Some(k)
+}
+
+fn $0fun_name() -> Option<core::ops::ControlFlow<Option<i64>, i64>> {
+    let k = foo()?;
     if k == 42 {
-        return Ok(1);
+        return Some(core::ops::ControlFlow::Break(Some(1)));
     }
-    let m = k + 1;$0
-    let h = 1 + m;
-    Ok(())
+    Some(core::ops::ControlFlow::Continue(k))
+}
+"##,
+        );
+    }
+
+    #[test]
+    fn try_and_break_and_continue() {
+        mark::check!(external_control_flow_try_and_bc);
+        check_assist_not_applicable(
+            extract_function,
+            r##"
+enum Option<T> { None, Some(T) }
+use Option::*;
+fn foo() -> Option<()> {
+    loop {
+        let n = Some(1);
+        $0let m = n? + 1;
+        if m > 2 {
+            break;
+        }
+        continue;$0
+    }
+    Some(())
 }"##,
         );
     }
+
+    #[test]
+    fn extracts_async_fn() {
+        check_assist(
+            extract_function,
+            r#"
+async fn fetch() -> u32 { 0 }
+fn log() {}
+async fn foo() {
+    $0fetch().await;
+    log();$0
+}
+"#,
+            r#"
+async fn fetch() -> u32 { 0 }
+fn log() {}
+async fn foo() {
+    fun_name().await;
+}
+
+async fn $0fun_name() {
+    fetch().await;
+    log();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_async_fn_for_await_in_async_block() {
+        check_assist(
+            extract_function,
+            r#"
+async fn fetch() -> u32 { 0 }
+async fn foo() {
+    $0let run = async { fetch().await };
+    drop(run);$0
+}
+"#,
+            r#"
+async fn fetch() -> u32 { 0 }
+async fn foo() {
+    fun_name();
+}
+
+fn $0fun_name() {
+    let run = async { fetch().await };
+    drop(run);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn copies_generic_params_and_bounds() {
+        check_assist(
+            extract_function,
+            r#"
+trait Shape { fn area(&self) -> u32; }
+trait Named { fn name(&self) -> u32; }
+fn foo<'a, T: Shape, U, V>(shape: &'a T, other: U, v: V) -> u32
+where
+    U: Into<T>,
+    V: Named,
+{
+    let _ = other;
+    $0shape.area() + 1$0
+}
+"#,
+            r#"
+trait Shape { fn area(&self) -> u32; }
+trait Named { fn name(&self) -> u32; }
+fn foo<'a, T: Shape, U, V>(shape: &'a T, other: U, v: V) -> u32
+where
+    U: Into<T>,
+    V: Named,
+{
+    let _ = other;
+    fun_name(shape)
+}
+
+fn $0fun_name<T: Shape>(shape: &T) -> u32 {
+    shape.area() + 1
+}
+"#,
+        );
+    }
 }
@@ -0,0 +1,593 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use hir::{PathResolution, ScopeDef};
+use ide_db::{
+    base_db::AnchoredPathBuf,
+    defs::{Definition, NameClass},
+};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel},
+    AstNode, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use crate::{utils::vis_offset, AssistContext, AssistId, AssistKind, Assists};
+
+const MODULE_NAME: &str = "modname";
+
+// Assist: extract_module
+//
+// Wraps the selected items into a new inline module. Items used outside of the selection are made
+// visible to the old module and imported there, and the items of the old module which the
+// selection uses are imported into the new one.
+//
+// ```
+// struct Point(i32, i32);
+//
+// $0fn origin() -> Point {
+//     Point(0, 0)
+// }$0
+//
+// fn main() {
+//     let _ = origin();
+// }
+// ```
+// ->
+// ```
+// struct Point(i32, i32);
+//
+// mod modname {
+//     use super::Point;
+//
+//     pub(super) fn origin() -> Point {
+//         Point(0, 0)
+//     }
+// }
+//
+// use modname::origin;
+//
+// fn main() {
+//     let _ = origin();
+// }
+// ```
+pub(crate) fn extract_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let extraction = Extraction::new(ctx)?;
+    acc.add(
+        AssistId("extract_module", AssistKind::RefactorExtract),
+        "Extract into module",
+        extraction.range,
+        |builder| {
+            let indent = IndentLevel::from_node(extraction.items[0].syntax());
+            let mut buf = String::new();
+            if extraction.has_macro_rules {
+                format_to!(buf, "#[macro_use]\n{}", indent);
+            }
+            format_to!(buf, "mod {} {{\n", MODULE_NAME);
+            if let Some(import) = extraction.inward_import() {
+                format_to!(buf, "{}{}\n\n", indent + 1, import);
+            }
+            format_to!(buf, "{}{}\n{}}}", indent + 1, extraction.items_text(1), indent);
+            for import in extraction.outward_imports() {
+                format_to!(buf, "\n\n{}{}", indent, import);
+            }
+            builder.replace(extraction.range, buf);
+        },
+    )
+}
+
+// Assist: extract_module_to_file
+//
+// Moves the selected items into a new module file, fixing up visibility and imports like
+// `extract_module`.
+//
+// ```
+// $0fn double(x: i32) -> i32 {
+//     x * 2
+// }$0
+//
+// fn main() {
+//     let _ = double(2);
+// }
+// ```
+// ->
+// ```
+// mod modname;
+//
+// use modname::double;
+//
+// fn main() {
+//     let _ = double(2);
+// }
+// ```
+pub(crate) fn extract_module_to_file(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let extraction = Extraction::new(ctx)?;
+    if extraction.container.kind() != SyntaxKind::SOURCE_FILE {
+        return None;
+    }
+    acc.add(
+        AssistId("extract_module_to_file", AssistKind::RefactorExtract),
+        "Extract into module file",
+        extraction.range,
+        |builder| {
+            let path = {
+                let db = ctx.db();
+                let dir = match extraction.module.name(db) {
+                    Some(name) if !extraction.module.is_mod_rs(db) => format!("{}/", name),
+                    _ => String::new(),
+                };
+                format!("./{}{}.rs", dir, MODULE_NAME)
+            };
+            let mut contents = String::new();
+            if let Some(import) = extraction.inward_import() {
+                format_to!(contents, "{}\n\n", import);
+            }
+            format_to!(contents, "{}\n", extraction.items_text(0));
+
+            let mut buf = String::new();
+            if extraction.has_macro_rules {
+                buf.push_str("#[macro_use]\n");
+            }
+            format_to!(buf, "mod {};", MODULE_NAME);
+            for import in extraction.outward_imports() {
+                format_to!(buf, "\n\n{}", import);
+            }
+            builder.replace(extraction.range, buf);
+            builder.create_file(AnchoredPathBuf { anchor: ctx.frange.file_id, path }, contents);
+        },
+    )
+}
+
+struct Extraction {
+    /// The source file or item list containing the items.
+    container: SyntaxNode,
+    items: Vec<ast::Item>,
+    range: TextRange,
+    /// The module the items are extracted from.
+    module: hir::Module,
+    has_macro_rules: bool,
+    /// Items to import into the old module, by the visibility of the import.
+    outward: BTreeMap<String, Vec<String>>,
+    /// Names of the old module to import into the new one.
+    inward: Vec<String>,
+    /// Changes to the text of the items: visibilities raised for the old module to access the
+    /// definitions, and another `super` for paths starting with it.
+    edits: Vec<(TextRange, &'static str)>,
+}
+
+impl Extraction {
+    fn new(ctx: &AssistContext) -> Option<Extraction> {
+        let selection = ctx.frange.range;
+        if selection.is_empty() {
+            return None;
+        }
+        let container = match ctx.covering_element() {
+            syntax::NodeOrToken::Node(it) => it,
+            syntax::NodeOrToken::Token(it) => it.parent(),
+        }
+        .ancestors()
+        .find(|it| matches!(it.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST))?;
+        let items = container
+            .children()
+            .filter_map(ast::Item::cast)
+            .filter(|it| {
+                it.syntax().text_range().intersect(selection).map_or(false, |it| !it.is_empty())
+            })
+            .collect::<Vec<_>>();
+        if items.is_empty()
+            || items.iter().any(|it| !selection.contains_range(it.syntax().text_range()))
+        {
+            return None;
+        }
+        let range = items[0].syntax().text_range().cover(items.last()?.syntax().text_range());
+        let module = ctx.sema.scope(items[0].syntax()).module()?;
+
+        let mut extraction = Extraction {
+            container,
+            items,
+            range,
+            module,
+            has_macro_rules: false,
+            outward: BTreeMap::new(),
+            inward: Vec::new(),
+            edits: Vec::new(),
+        };
+        extraction.collect_definitions(ctx);
+        extraction.collect_inward_imports(ctx);
+        Some(extraction)
+    }
+
+    /// Finds the definitions of the items which are used outside of the selection, to make them
+    /// visible and import them.
+    fn collect_definitions(&mut self, ctx: &AssistContext) {
+        for item in self.items.clone() {
+            match &item {
+                ast::Item::MacroRules(_) => {
+                    self.has_macro_rules = true;
+                    continue;
+                }
+                ast::Item::Impl(imp) => {
+                    if imp.trait_().is_some() {
+                        continue;
+                    }
+                    let assoc_items =
+                        imp.assoc_item_list().into_iter().flat_map(|it| it.assoc_items());
+                    for assoc_item in assoc_items {
+                        let vis_owner = match &assoc_item {
+                            ast::AssocItem::Fn(it) => it.syntax(),
+                            ast::AssocItem::Const(it) => it.syntax(),
+                            ast::AssocItem::TypeAlias(it) => it.syntax(),
+                            ast::AssocItem::MacroCall(_) => continue,
+                        };
+                        if let Some(def) = definition(ctx, vis_owner) {
+                            if self.used_outside(ctx, def) {
+                                self.raise_visibility(vis_owner);
+                            }
+                        }
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let def = match definition(ctx, item.syntax()) {
+                Some(it) => it,
+                None => continue,
+            };
+            let visibility = item.syntax().children().find_map(ast::Visibility::cast);
+            let used_outside = self.used_outside(ctx, def);
+            if used_outside || visibility.is_some() {
+                self.raise_visibility(item.syntax());
+                let name = match item.syntax().children().find_map(ast::Name::cast) {
+                    Some(it) => it.text().to_string(),
+                    None => continue,
+                };
+                let import_vis = visibility.map_or(String::new(), |it| format!("{} ", it));
+                self.outward.entry(import_vis).or_default().push(name);
+            }
+            self.collect_fields(ctx, &item, used_outside);
+        }
+    }
+
+    fn collect_fields(&mut self, ctx: &AssistContext, item: &ast::Item, struct_used_outside: bool) {
+        let field_list = match item {
+            ast::Item::Struct(it) => it.field_list(),
+            ast::Item::Union(it) => it.record_field_list().map(ast::FieldList::RecordFieldList),
+            _ => None,
+        };
+        match field_list {
+            Some(ast::FieldList::RecordFieldList(fields)) => {
+                for field in fields.fields() {
+                    let used_outside = ctx
+                        .sema
+                        .to_def(&field)
+                        .map_or(false, |it| self.used_outside(ctx, Definition::Field(it)));
+                    if used_outside {
+                        self.raise_visibility(field.syntax());
+                    }
+                }
+            }
+            // Tuple fields are used by the constructor too, so they are made visible together
+            // with the struct.
+            Some(ast::FieldList::TupleFieldList(fields)) if struct_used_outside => {
+                for field in fields.fields() {
+                    self.raise_visibility(field.syntax());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn used_outside(&self, ctx: &AssistContext, def: Definition) -> bool {
+        let file_id = ctx.frange.file_id;
+        def.usages(&ctx.sema).all().into_iter().any(|(usage_file_id, references)| {
+            usage_file_id != file_id
+                || references.iter().any(|it| !self.range.contains_range(it.range))
+        })
+    }
+
+    /// Makes the item visible to the old module, as it becomes the parent of the new one.
+    fn raise_visibility(&mut self, node: &SyntaxNode) {
+        let visibility = node.children().find_map(ast::Visibility::cast);
+        let edit = match visibility {
+            None => (TextRange::empty(vis_offset(node)), "pub(super) "),
+            Some(vis) => match vis.kind() {
+                ast::VisibilityKind::PubSelf => (vis.syntax().text_range(), "pub(super)"),
+                ast::VisibilityKind::PubSuper => (vis.syntax().text_range(), "pub(crate)"),
+                _ => return,
+            },
+        };
+        self.edits.push(edit);
+    }
+
+    /// Finds the names which the selection uses from the scope of the old module.
+    fn collect_inward_imports(&mut self, ctx: &AssistContext) {
+        let db = ctx.db();
+        let defined = self
+            .items
+            .iter()
+            .filter_map(|it| it.syntax().children().find_map(ast::Name::cast))
+            .map(|it| it.text().to_string())
+            .collect::<FxHashSet<_>>();
+        let scope = self
+            .module
+            .scope(db, None)
+            .into_iter()
+            .map(|(name, def)| (name.to_string(), def))
+            .collect::<FxHashMap<_, _>>();
+
+        let mut inward = FxHashSet::default();
+        let nodes = self.items.iter().flat_map(|it| it.syntax().descendants());
+        for node in nodes {
+            let (name, def) = if let Some(path) = ast::Path::cast(node.clone()) {
+                if path.qualifier().is_some()
+                    || path.syntax().ancestors().any(|it| ast::Visibility::can_cast(it.kind()))
+                {
+                    continue;
+                }
+                if let Some(super_token) = path.segment().and_then(|it| it.super_token()) {
+                    let offset = super_token.text_range().start();
+                    self.edits.push((TextRange::empty(offset), "super::"));
+                    continue;
+                }
+                let name = match path.segment().and_then(|it| it.name_ref()) {
+                    Some(it) => it.text().to_string(),
+                    None => continue,
+                };
+                let def = match ctx.sema.resolve_path(&path) {
+                    Some(PathResolution::Def(it)) => ScopeDef::ModuleDef(it),
+                    Some(PathResolution::Macro(it)) => ScopeDef::MacroDef(it),
+                    _ => continue,
+                };
+                (name, def)
+            } else if let Some(call) = ast::MethodCallExpr::cast(node) {
+                // Traits need to be in scope for their methods to be called.
+                let trait_ = ctx
+                    .sema
+                    .resolve_method_call(&call)
+                    .and_then(|it| hir::AsAssocItem::as_assoc_item(it, db))
+                    .and_then(|it| it.containing_trait(db));
+                match trait_ {
+                    Some(it) => (it.name(db).to_string(), ScopeDef::ModuleDef(it.into())),
+                    None => continue,
+                }
+            } else {
+                continue;
+            };
+            if defined.contains(&name) || scope.get(&name) != Some(&def) {
+                continue;
+            }
+            inward.insert(name);
+        }
+        self.inward = inward.into_iter().sorted().collect();
+    }
+
+    fn inward_import(&self) -> Option<String> {
+        match self.inward.as_slice() {
+            [] => None,
+            [name] => Some(format!("use super::{};", name)),
+            names => Some(format!("use super::{{{}}};", names.join(", "))),
+        }
+    }
+
+    fn outward_imports(&self) -> Vec<String> {
+        self.outward
+            .iter()
+            .map(|(vis, names)| match names.as_slice() {
+                [name] => format!("{}use {}::{};", vis, MODULE_NAME, name),
+                names => format!("{}use {}::{{{}}};", vis, MODULE_NAME, names.join(", ")),
+            })
+            .collect()
+    }
+
+    /// The text of the items, indented by `levels` more.
+    fn items_text(&self, levels: u8) -> String {
+        let range = self.range - self.container.text_range().start();
+        let mut text = self.container.text().slice(range).to_string();
+        for (range, new) in self.edits.iter().sorted_by_key(|(range, _)| range.start()).rev() {
+            text.replace_range(Range::<usize>::from(*range - self.range.start()), new);
+        }
+        let indent = IndentLevel(levels).to_string();
+        text.split('\n')
+            .enumerate()
+            .map(|(idx, line)| match line.trim().is_empty() {
+                true => String::new(),
+                false if idx == 0 => line.to_string(),
+                false => format!("{}{}", indent, line),
+            })
+            .join("\n")
+    }
+}
+
+fn definition(ctx: &AssistContext, node: &SyntaxNode) -> Option<Definition> {
+    let name = node.children().find_map(ast::Name::cast)?;
+    NameClass::classify(&ctx.sema, &name).and_then(|it| it.defined(ctx.db()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn imports_in_both_directions() {
+        check_assist(
+            extract_module,
+            r#"
+trait Shape { fn area(&self) -> u32; }
+trait Named { fn name(&self) -> String; }
+impl Named for u32 { fn name(&self) -> String { String::new() } }
+
+$0struct Square { side: u32, name: String }
+
+impl Shape for Square {
+    fn area(&self) -> u32 { self.side * self.side }
+}
+
+impl Square {
+    fn new(side: u32) -> Square { Square { side, name: String::new() } }
+    fn describe(&self) -> String { self.side.name() }
+}$0
+
+fn main() {
+    let square = Square::new(2);
+    let _ = square.side;
+}
+"#,
+            r#"
+trait Shape { fn area(&self) -> u32; }
+trait Named { fn name(&self) -> String; }
+impl Named for u32 { fn name(&self) -> String { String::new() } }
+
+mod modname {
+    use super::{Named, Shape};
+
+    pub(super) struct Square { pub(super) side: u32, name: String }
+
+    impl Shape for Square {
+        fn area(&self) -> u32 { self.side * self.side }
+    }
+
+    impl Square {
+        pub(super) fn new(side: u32) -> Square { Square { side, name: String::new() } }
+        fn describe(&self) -> String { self.side.name() }
+    }
+}
+
+use modname::Square;
+
+fn main() {
+    let square = Square::new(2);
+    let _ = square.side;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reexports_visible_items() {
+        check_assist(
+            extract_module,
+            r#"
+mod outer {
+    $0pub fn api() { helper() }
+    pub(super) struct Handle(u32);
+    fn helper() {}
+    fn unused() { super::top() }$0
+
+    fn g() -> Handle { Handle(0) }
+}
+fn top() {}
+"#,
+            r#"
+mod outer {
+    mod modname {
+        pub fn api() { helper() }
+        pub(crate) struct Handle(pub(super) u32);
+        fn helper() {}
+        fn unused() { super::super::top() }
+    }
+
+    pub use modname::api;
+
+    pub(super) use modname::Handle;
+
+    fn g() -> Handle { Handle(0) }
+}
+fn top() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extracts_macro_rules() {
+        check_assist(
+            extract_module,
+            r#"
+$0macro_rules! m { () => {} }$0
+fn f() { m!(); }
+"#,
+            r#"
+#[macro_use]
+mod modname {
+    macro_rules! m { () => {} }
+}
+fn f() { m!(); }
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_to_file_from_root() {
+        check_assist(
+            extract_module_to_file,
+            r#"
+//- /main.rs
+struct S;
+$0fn f() -> S {
+    S
+}$0
+fn main() { f(); }
+"#,
+            r#"
+//- /main.rs
+struct S;
+mod modname;
+
+use modname::f;
+fn main() { f(); }
+//- /modname.rs
+use super::S;
+
+pub(super) fn f() -> S {
+    S
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_to_file_from_submodule() {
+        check_assist(
+            extract_module_to_file,
+            r#"
+//- /main.rs
+mod sub;
+//- /sub.rs
+$0fn f() {}$0
+"#,
+            r#"
+//- /sub.rs
+mod modname;
+//- /sub/modname.rs
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_partially_selected_items() {
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+fn f() { $0let x = 1; }
+fn g() {}$0
+"#,
+        );
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+fn f() { let x = $01; }
+"#,
+        );
+        check_assist_not_applicable(
+            extract_module_to_file,
+            r#"
+mod m {
+    $0fn f() {}$0
+}
+"#,
+        );
+    }
+}
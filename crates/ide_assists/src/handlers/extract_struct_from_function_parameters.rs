@@ -0,0 +1,543 @@
+use hir::{AsName, ModuleDef};
+use ide_db::{
+    defs::Definition,
+    helpers::{
+        insert_use::{insert_use, ImportScope},
+        mod_path_to_ast,
+    },
+};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::format_to;
+use syntax::{
+    algo::SyntaxRewriter,
+    ast::{
+        self, edit::IndentLevel, make, ArgListOwner, GenericParamsOwner, NameOwner, VisibilityOwner,
+    },
+    match_ast, AstNode, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: extract_struct_from_function_parameters
+//
+// Bundles the selected parameters of a function into a new struct, which the function
+// destructures and its callers construct.
+//
+// ```
+// fn draw_rect($0x: u32, y: u32$0, color: u32) {
+//     let _ = (x, y, color);
+// }
+//
+// fn main() {
+//     draw_rect(1, 2, 3);
+// }
+// ```
+// ->
+// ```
+// struct DrawRectParams {
+//     x: u32,
+//     y: u32,
+// }
+//
+// fn draw_rect(params: DrawRectParams, color: u32) {
+//     let DrawRectParams { x, y } = params;
+//     let _ = (x, y, color);
+// }
+//
+// fn main() {
+//     draw_rect(DrawRectParams { x: 1, y: 2 }, 3);
+// }
+// ```
+pub(crate) fn extract_struct_from_function_parameters(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let selection = ctx.frange.range;
+    if selection.is_empty() {
+        return None;
+    }
+    let param_list = ctx.find_node_at_offset::<ast::ParamList>()?;
+    let fn_ = param_list.syntax().parent().and_then(ast::Fn::cast)?;
+    let body = fn_.body()?;
+    let params = param_list.params().collect::<Vec<_>>();
+    let selected = params
+        .iter()
+        .enumerate()
+        .filter(|(_, it)| {
+            it.syntax().text_range().intersect(selection).map_or(false, |it| !it.is_empty())
+        })
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let (&first, &last) = (selected.first()?, selected.last()?);
+
+    let mut fields = Vec::new();
+    for param in &params[first..=last] {
+        let pat = match param.pat()? {
+            ast::Pat::IdentPat(it) if it.ref_token().is_none() && it.pat().is_none() => it,
+            _ => return None,
+        };
+        let ty = param.ty()?;
+        let unsupported = ty.syntax().descendants().any(|it| {
+            ast::ImplTraitType::can_cast(it.kind())
+                || ast::NameRef::cast(it).map_or(false, |it| it.text() == "Self")
+        });
+        if unsupported {
+            return None;
+        }
+        fields.push(Field {
+            name: pat.name()?.text().to_string(),
+            is_mut: pat.mut_token().is_some(),
+            ty,
+        });
+    }
+
+    let function = ctx.sema.to_def(&fn_)?;
+    let module = function.module(ctx.db());
+    let scope = module.scope(ctx.db(), None);
+    let base_name = format!("{}Params", to_pascal_case(fn_.name()?.text()));
+    let struct_name = (0..)
+        .map(|idx| match idx {
+            0 => base_name.clone(),
+            _ => format!("{}{}", base_name, idx),
+        })
+        .find(|name| scope.iter().all(|(it, _)| it.to_string() != *name))?;
+    let param_names = params
+        .iter()
+        .filter_map(|it| match it.pat()? {
+            ast::Pat::IdentPat(it) => Some(it.name()?.text().to_string()),
+            _ => None,
+        })
+        .collect::<FxHashSet<_>>();
+    let param_name = (0..)
+        .map(|idx| match idx {
+            0 => "params".to_string(),
+            _ => format!("params{}", idx),
+        })
+        .find(|name| !param_names.contains(name))?;
+
+    let target = params[first].syntax().text_range().cover(params[last].syntax().text_range());
+    acc.add(
+        AssistId("extract_struct_from_function_parameters", AssistKind::RefactorExtract),
+        "Extract struct from function parameters",
+        target,
+        |builder| {
+            let generics = Generics::new(&fn_, &fields);
+            let self_offset = usize::from(param_list.self_param().is_some());
+            let mut call_edits: FxHashMap<_, Vec<_>> = FxHashMap::default();
+            let mut imports: FxHashMap<_, SyntaxRewriter> = FxHashMap::default();
+            let mut imported_modules = FxHashSet::default();
+            let usages = Definition::ModuleDef(function.into()).usages(&ctx.sema).all();
+            for (file_id, references) in usages {
+                for reference in references {
+                    let name_ref = match reference.name {
+                        ast::NameLike::NameRef(it) => it,
+                        _ => continue,
+                    };
+                    let (args, offset) = match call_args(&name_ref) {
+                        Some(ArgsOf::Call(it)) => (it, self_offset),
+                        Some(ArgsOf::MethodCall(it)) => (it, 0),
+                        None => continue,
+                    };
+                    let args = args.args().collect::<Vec<_>>();
+                    if args.len() != params.len() + offset {
+                        continue;
+                    }
+                    let args = &args[first + offset..=last + offset];
+                    let range = args[0]
+                        .syntax()
+                        .text_range()
+                        .cover(args[args.len() - 1].syntax().text_range());
+                    let fields = fields
+                        .iter()
+                        .zip(args)
+                        .map(|(field, arg)| match arg.syntax().text() == field.name.as_str() {
+                            true => field.name.clone(),
+                            false => format!("{}: {}", field.name, arg.syntax().text()),
+                        })
+                        .join(", ");
+                    call_edits
+                        .entry(file_id)
+                        .or_default()
+                        .push((range, format!("{} {{ {} }}", struct_name, fields)));
+
+                    let call_module = match ctx.sema.scope(name_ref.syntax()).module() {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    if call_module == module || !imported_modules.insert(call_module) {
+                        continue;
+                    }
+                    let path = call_module.find_use_path_prefixed(
+                        ctx.db(),
+                        ModuleDef::Module(module),
+                        ctx.config.insert_use.prefix_kind,
+                    );
+                    let scope =
+                        ImportScope::find_insert_use_container(name_ref.syntax(), &ctx.sema);
+                    if let (Some(mut path), Some(scope)) = (path, scope) {
+                        path.push_segment(make::name(&struct_name).as_name());
+                        *imports.entry(file_id).or_default() +=
+                            insert_use(&scope, mod_path_to_ast(&path), ctx.config.insert_use.merge);
+                    }
+                }
+            }
+
+            let def_file_id = ctx.frange.file_id;
+            for file_id in call_edits.keys().chain(imports.keys()).copied().unique().sorted() {
+                if file_id == def_file_id {
+                    continue;
+                }
+                builder.edit_file(file_id);
+                if let Some(rewriter) = imports.remove(&file_id) {
+                    builder.rewrite(rewriter);
+                }
+                for (range, text) in call_edits.remove(&file_id).unwrap_or_default() {
+                    builder.replace(range, text);
+                }
+            }
+
+            builder.edit_file(def_file_id);
+            if let Some(rewriter) = imports.remove(&def_file_id) {
+                builder.rewrite(rewriter);
+            }
+            for (range, text) in call_edits.remove(&def_file_id).unwrap_or_default() {
+                builder.replace(range, text);
+            }
+
+            // The struct goes before the function, or the impl containing it.
+            let anchor = fn_
+                .syntax()
+                .ancestors()
+                .take_while(|it| {
+                    !matches!(it.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST)
+                })
+                .last()
+                .unwrap_or_else(|| fn_.syntax().clone());
+            let indent = IndentLevel::from_node(&anchor);
+            let visibility = fn_.visibility().map_or(String::new(), |it| format!("{} ", it));
+            let mut buf =
+                format!("{}struct {}{} {{\n", visibility, struct_name, generics.declaration());
+            for field in &fields {
+                format_to!(
+                    buf,
+                    "{}{}{}: {},\n",
+                    indent + 1,
+                    visibility,
+                    field.name,
+                    generics.field_type(&field.ty)
+                );
+            }
+            format_to!(buf, "{}}}\n\n{}", indent, indent);
+            builder.insert(anchor.text_range().start(), buf);
+
+            builder.replace(
+                target,
+                format!("{}: {}{}", param_name, struct_name, generics.arguments()),
+            );
+
+            let pattern = fields
+                .iter()
+                .map(|it| match it.is_mut {
+                    true => format!("mut {}", it.name),
+                    false => it.name.clone(),
+                })
+                .join(", ");
+            let destructure = format!("let {} {{ {} }} = {};", struct_name, pattern, param_name);
+            if let Some(l_curly) = body.l_curly_token() {
+                let text = match body.syntax().text().contains_char('\n') {
+                    true => {
+                        format!("\n{}{}", IndentLevel::from_node(fn_.syntax()) + 1, destructure)
+                    }
+                    false if body.statements().next().is_none() && body.tail_expr().is_none() => {
+                        format!(" {} ", destructure)
+                    }
+                    false => format!(" {}", destructure),
+                };
+                builder.insert(l_curly.text_range().end(), text);
+            }
+        },
+    )
+}
+
+struct Field {
+    name: String,
+    is_mut: bool,
+    ty: ast::Type,
+}
+
+enum ArgsOf {
+    Call(ast::ArgList),
+    MethodCall(ast::ArgList),
+}
+
+/// The arguments of the call, if `name_ref` is the called function.
+fn call_args(name_ref: &ast::NameRef) -> Option<ArgsOf> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return Some(ArgsOf::MethodCall(call.arg_list()?));
+    }
+    let path = ast::PathSegment::cast(parent)?.parent_path();
+    if path.parent_path().is_some() {
+        return None;
+    }
+    let call = path
+        .syntax()
+        .parent()
+        .and_then(ast::PathExpr::cast)?
+        .syntax()
+        .parent()
+        .and_then(ast::CallExpr::cast)?;
+    Some(ArgsOf::Call(call.arg_list()?))
+}
+
+/// The generic parameters of the function and its impl used by the fields, and a lifetime for
+/// the references whose lifetime is elided.
+struct Generics {
+    lifetimes: Vec<String>,
+    types: Vec<String>,
+    elided: Option<String>,
+}
+
+impl Generics {
+    fn new(fn_: &ast::Fn, fields: &[Field]) -> Generics {
+        let owners = fn_.syntax().ancestors().filter_map(generic_param_list);
+        let mut declared_lifetimes = Vec::new();
+        let mut declared_types = Vec::new();
+        for list in owners.collect::<Vec<_>>().into_iter().rev() {
+            for param in list.generic_params() {
+                match param {
+                    ast::GenericParam::LifetimeParam(it) => {
+                        declared_lifetimes.extend(it.lifetime().map(|it| it.text().to_string()))
+                    }
+                    ast::GenericParam::TypeParam(it) => {
+                        declared_types.extend(it.name().map(|it| it.text().to_string()))
+                    }
+                    ast::GenericParam::ConstParam(it) => {
+                        declared_types.extend(it.name().map(|it| it.text().to_string()))
+                    }
+                }
+            }
+        }
+
+        let mut used = FxHashSet::default();
+        let mut has_elided = false;
+        for field in fields {
+            for node in field.ty.syntax().descendants() {
+                if let Some(lifetime) = ast::Lifetime::cast(node.clone()) {
+                    match lifetime.text() {
+                        "'_" => has_elided = true,
+                        it => {
+                            used.insert(it.to_string());
+                        }
+                    }
+                } else if let Some(name_ref) = ast::NameRef::cast(node.clone()) {
+                    used.insert(name_ref.text().to_string());
+                } else if let Some(ref_type) = ast::RefType::cast(node) {
+                    has_elided |= ref_type.lifetime().is_none();
+                }
+            }
+        }
+        let elided = if has_elided {
+            (b'a'..=b'z')
+                .map(|c| format!("'{}", c as char))
+                .find(|it| !declared_lifetimes.contains(it))
+        } else {
+            None
+        };
+        Generics {
+            lifetimes: declared_lifetimes.into_iter().filter(|it| used.contains(it)).collect(),
+            types: declared_types.into_iter().filter(|it| used.contains(it)).collect(),
+            elided,
+        }
+    }
+
+    fn declaration(&self) -> String {
+        let params = self.elided.iter().chain(&self.lifetimes).chain(&self.types).join(", ");
+        match params.is_empty() {
+            true => String::new(),
+            false => format!("<{}>", params),
+        }
+    }
+
+    fn arguments(&self) -> String {
+        let elided = self.elided.as_ref().map(|_| "'_".to_string());
+        let args = elided.iter().chain(&self.lifetimes).chain(&self.types).join(", ");
+        match args.is_empty() {
+            true => String::new(),
+            false => format!("<{}>", args),
+        }
+    }
+
+    /// The type of a field, with elided lifetimes named.
+    fn field_type(&self, ty: &ast::Type) -> String {
+        let elided = match &self.elided {
+            Some(it) => it,
+            None => return ty.syntax().text().to_string(),
+        };
+        let start = ty.syntax().text_range().start();
+        let mut edits = Vec::new();
+        for node in ty.syntax().descendants() {
+            if let Some(lifetime) = ast::Lifetime::cast(node.clone()) {
+                if lifetime.text() == "'_" {
+                    edits.push((lifetime.syntax().text_range(), elided.clone()));
+                }
+            } else if let Some(ref_type) = ast::RefType::cast(node) {
+                if let (None, Some(amp)) = (ref_type.lifetime(), ref_type.amp_token()) {
+                    let offset = amp.text_range().end();
+                    edits.push((TextRange::empty(offset), format!("{} ", elided)));
+                }
+            }
+        }
+        let mut text = ty.syntax().text().to_string();
+        for (range, new) in edits.into_iter().sorted_by_key(|(range, _)| range.start()).rev() {
+            let range = range - start;
+            text.replace_range(usize::from(range.start())..usize::from(range.end()), &new);
+        }
+        text
+    }
+}
+
+fn generic_param_list(node: SyntaxNode) -> Option<ast::GenericParamList> {
+    match_ast! {
+        match node {
+            ast::Fn(it) => it.generic_param_list(),
+            ast::Impl(it) => it.generic_param_list(),
+            _ => None,
+        }
+    }
+}
+
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|it| !it.is_empty())
+        .map(|it| {
+            let mut chars = it.chars();
+            let first = chars.next().map(|it| it.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extracts_selected_parameters() {
+        check_assist(
+            extract_struct_from_function_parameters,
+            r#"
+fn connect(host: &str, $0port: u16, mut retries: u8$0, verbose: bool) {
+    retries += 1;
+}
+
+fn main() {
+    let port = 80;
+    connect("localhost", port, 3, false);
+}
+"#,
+            r#"
+struct ConnectParams {
+    port: u16,
+    retries: u8,
+}
+
+fn connect(host: &str, params: ConnectParams, verbose: bool) {
+    let ConnectParams { port, mut retries } = params;
+    retries += 1;
+}
+
+fn main() {
+    let port = 80;
+    connect("localhost", ConnectParams { port, retries: 3 }, false);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extracts_generics_and_lifetimes() {
+        check_assist(
+            extract_struct_from_function_parameters,
+            r#"
+pub fn render<'s, T: Clone, U>($0style: &'s str, item: T, label: &str$0, extra: U) {}
+"#,
+            r#"
+pub struct RenderParams<'a, 's, T> {
+    pub style: &'s str,
+    pub item: T,
+    pub label: &'a str,
+}
+
+pub fn render<'s, T: Clone, U>(params: RenderParams<'_, 's, T>, extra: U) { let RenderParams { style, item, label } = params; }
+"#,
+        );
+    }
+
+    #[test]
+    fn rewrites_method_calls_and_imports() {
+        check_assist(
+            extract_struct_from_function_parameters,
+            r#"
+mod shapes {
+    pub struct Canvas;
+
+    impl Canvas {
+        pub fn draw(&self, $0x: i32, y: i32$0) {}
+    }
+}
+
+mod app {
+    use crate::shapes::Canvas;
+
+    fn run(canvas: Canvas) {
+        canvas.draw(1, 2);
+        Canvas::draw(&canvas, 3, 4);
+    }
+}
+"#,
+            r#"
+mod shapes {
+    pub struct Canvas;
+
+    pub struct DrawParams {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    impl Canvas {
+        pub fn draw(&self, params: DrawParams) { let DrawParams { x, y } = params; }
+    }
+}
+
+mod app {
+    use crate::shapes::{Canvas, DrawParams};
+
+    fn run(canvas: Canvas) {
+        canvas.draw(DrawParams { x: 1, y: 2 });
+        Canvas::draw(&canvas, DrawParams { x: 3, y: 4 });
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_patterns() {
+        check_assist_not_applicable(
+            extract_struct_from_function_parameters,
+            r#"
+fn f($0(a, b): (u32, u32), c: u32$0) {}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_struct_from_function_parameters,
+            r#"
+fn f(a: u32$0, c: u32) {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use ide_db::helpers::{expected_type, term_search::term_search};
+use syntax::{ast, AstNode, SyntaxKind, T};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+/// How many functions or constructors the search applies on top of each other.
+const SEARCH_DEPTH: usize = 3;
+/// How many of the expressions found are offered.
+const MAX_FILLS: usize = 5;
+
+// Assist: fill_expression
+//
+// Fills a `todo!()` with an expression of the type it needs to have, built from the values,
+// constructors and functions in scope.
+//
+// ```
+// struct Point { x: i32, y: i32 }
+// fn origin() -> Point { Point { x: 0, y: 0 } }
+// fn main() {
+//     let p: Point = todo$0!();
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32 }
+// fn origin() -> Point { Point { x: 0, y: 0 } }
+// fn main() {
+//     let p: Point = origin();
+// }
+// ```
+pub(crate) fn fill_expression(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let name = call.path()?.segment()?.name_ref()?;
+    let has_args = call
+        .token_tree()?
+        .syntax()
+        .children_with_tokens()
+        .any(|it| !matches!(it.kind(), T!['('] | T![')'] | SyntaxKind::WHITESPACE));
+    if name.text() != "todo" || has_args {
+        return None;
+    }
+    let hole = ast::Expr::MacroCall(call);
+    let goal = expected_type(&ctx.sema, &hole)?;
+    if goal.is_unknown() || goal.is_unit() || goal.is_never() {
+        return None;
+    }
+
+    let terms = term_search(&ctx.sema, hole.syntax(), &goal, SEARCH_DEPTH);
+    let group = GroupLabel("Fill expression".into());
+    let target = hole.syntax().text_range();
+    for term in terms.into_iter().take(MAX_FILLS) {
+        acc.add_group(
+            &group,
+            AssistId("fill_expression", AssistKind::Generate),
+            format!("Fill with `{}`", term.text),
+            target,
+            |builder| builder.replace(target, term.text),
+        );
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn fills_with_nested_constructors() {
+        check_assist_by_label(
+            fill_expression,
+            r#"
+struct Id(u32);
+enum Event { Click { id: Id, double: bool }, Quit }
+fn handle(id: u32, double: bool) {
+    dispatch(todo!$0());
+}
+fn dispatch(event: Event) {}
+"#,
+            r#"
+struct Id(u32);
+enum Event { Click { id: Id, double: bool }, Quit }
+fn handle(id: u32, double: bool) {
+    dispatch(Event::Click { id: Id(id), double });
+}
+fn dispatch(event: Event) {}
+"#,
+            "Fill with `Event::Click { id: Id(id), double }`",
+        );
+    }
+
+    #[test]
+    fn fills_with_items_not_in_scope() {
+        check_assist_by_label(
+            fill_expression,
+            r#"
+mod net {
+    pub struct Addr;
+    pub struct Socket { pub addr: Addr }
+    impl Socket {
+        pub fn bind(addr: Addr) -> Socket { Socket { addr } }
+    }
+}
+fn connect(addr: net::Addr) -> net::Socket {
+    let default = net::Addr;
+    todo!($0)
+}
+"#,
+            r#"
+mod net {
+    pub struct Addr;
+    pub struct Socket { pub addr: Addr }
+    impl Socket {
+        pub fn bind(addr: Addr) -> Socket { Socket { addr } }
+    }
+}
+fn connect(addr: net::Addr) -> net::Socket {
+    let default = net::Addr;
+    net::Socket { addr }
+}
+"#,
+            "Fill with `net::Socket { addr }`",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_a_useful_type() {
+        check_assist_not_applicable(
+            fill_expression,
+            r#"
+fn f() {
+    todo!$0()
+}
+"#,
+        );
+        check_assist_not_applicable(
+            fill_expression,
+            r#"
+struct S;
+fn f() -> S {
+    todo!$0("later")
+}
+"#,
+        );
+    }
+}
@@ -1,14 +1,14 @@
 use std::iter;
 
-use hir::{Adt, HasSource, ModuleDef, Semantics};
-use ide_db::helpers::{mod_path_to_ast, FamousDefs};
+use hir::{Adt, HasAttrs, Semantics};
+use ide_db::helpers::FamousDefs;
 use ide_db::RootDatabase;
 use itertools::Itertools;
-use syntax::ast::{self, make, AstNode, MatchArm, NameOwner, Pat};
+use syntax::ast::{self, make, AstNode, MatchArm, Pat};
 use test_utils::mark;
 
 use crate::{
-    utils::{does_pat_match_variant, render_snippet, Cursor},
+    utils::{build_pat, does_pat_match_variant, render_snippet, Cursor},
     AssistContext, AssistId, AssistKind, Assists,
 };
 
@@ -65,6 +65,14 @@ pub(crate) fn fill_match_arms(acc: &mut Assists, ctx: &AssistContext) -> Option<
             mark::hit!(option_order);
             variants.reverse()
         }
+        let has_wildcard_arm =
+            arms.iter().any(|arm| matches!(arm.pat(), Some(Pat::WildcardPat(_))));
+        if is_foreign_non_exhaustive(ctx, module, enum_def) && !has_wildcard_arm {
+            // Other crates may add variants to the enum, which a `_` arm has to cover.
+            mark::hit!(fill_match_arms_non_exhaustive);
+            let wildcard = make::wildcard_pat().into();
+            variants.push(make::match_arm(iter::once(wildcard), make::expr_empty_block()));
+        }
         variants
     } else if let Some(enum_defs) = resolve_tuple_of_enum_def(&ctx.sema, &expr) {
         // Partial fill not currently supported for tuple of enums.
@@ -147,6 +155,15 @@ fn is_variant_missing(existing_arms: &mut Vec<MatchArm>, var: &Pat) -> bool {
     })
 }
 
+fn is_foreign_non_exhaustive(
+    ctx: &AssistContext,
+    module: hir::Module,
+    enum_def: hir::Enum,
+) -> bool {
+    enum_def.module(ctx.db()).krate() != module.krate()
+        && enum_def.attrs(ctx.db()).by_key("non_exhaustive").exists()
+}
+
 fn resolve_enum_def(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> Option<hir::Enum> {
     sema.type_of_expr(&expr)?.autoderef(sema.db).find_map(|ty| match ty.as_adt() {
         Some(Adt::Enum(e)) => Some(e),
@@ -173,25 +190,6 @@ fn resolve_tuple_of_enum_def(
         .collect()
 }
 
-fn build_pat(db: &RootDatabase, module: hir::Module, var: hir::Variant) -> Option<ast::Pat> {
-    let path = mod_path_to_ast(&module.find_use_path(db, ModuleDef::from(var))?);
-
-    // FIXME: use HIR for this; it doesn't currently expose struct vs. tuple vs. unit variants though
-    let pat: ast::Pat = match var.source(db)?.value.kind() {
-        ast::StructKind::Tuple(field_list) => {
-            let pats = iter::repeat(make::wildcard_pat().into()).take(field_list.fields().count());
-            make::tuple_struct_pat(path, pats).into()
-        }
-        ast::StructKind::Record(field_list) => {
-            let pats = field_list.fields().map(|f| make::ident_pat(f.name().unwrap()).into());
-            make::record_pat(path, pats).into()
-        }
-        ast::StructKind::Unit => make::path_pat(path),
-    };
-
-    Some(pat)
-}
-
 #[cfg(test)]
 mod tests {
     use ide_db::helpers::FamousDefs;
@@ -651,6 +649,60 @@ fn main() {
         );
     }
 
+    #[test]
+    fn fill_match_arms_foreign_non_exhaustive() {
+        mark::check!(fill_match_arms_non_exhaustive);
+        check_assist(
+            fill_match_arms,
+            r#"
+//- /main.rs crate:main deps:dep
+fn main(e: dep::E) {
+    match $0e {
+        dep::E::X => {}
+    }
+}
+//- /dep.rs crate:dep
+#[non_exhaustive]
+pub enum E { X, Y }
+"#,
+            r#"
+fn main(e: dep::E) {
+    match e {
+        dep::E::X => {}
+        $0dep::E::Y => {}
+        _ => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fill_match_arms_local_non_exhaustive() {
+        check_assist(
+            fill_match_arms,
+            r#"
+#[non_exhaustive]
+enum E { X, Y }
+fn main(e: E) {
+    match e {
+        $0
+    }
+}
+"#,
+            r#"
+#[non_exhaustive]
+enum E { X, Y }
+fn main(e: E) {
+    match e {
+        $0E::X => {}
+        E::Y => {}
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn fill_match_arms_preserves_comments() {
         check_assist(
@@ -1,6 +1,6 @@
 use syntax::ast::{AstNode, BinExpr, BinOp};
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{utils::reorder, AssistContext, AssistId, AssistKind, Assists};
 
 // Assist: flip_binexpr
 //
@@ -41,8 +41,7 @@ pub(crate) fn flip_binexpr(acc: &mut Assists, ctx: &AssistContext) -> Option<()>
             if let FlipAction::FlipAndReplaceOp(new_op) = action {
                 edit.replace(op_range, new_op);
             }
-            edit.replace(lhs.text_range(), rhs.text());
-            edit.replace(rhs.text_range(), lhs.text());
+            reorder::swap(edit, &lhs.into(), &rhs.into());
         },
     )
 }
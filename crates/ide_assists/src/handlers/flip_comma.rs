@@ -1,6 +1,16 @@
-use syntax::{algo::non_trivia_sibling, Direction, SyntaxKind, T};
+use hir::ModuleDef;
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, ArgListOwner},
+    AstNode, SyntaxElement, TextRange, T,
+};
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    assist_context::AssistBuilder,
+    utils::reorder::{ListKind, Siblings},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // Assist: flip_comma
 //
@@ -17,39 +27,151 @@ use crate::{AssistContext, AssistId, AssistKind, Assists};
 //     ((3, 4), (1, 2));
 // }
 // ```
+
+// Assist: flip_params_and_args
+//
+// Flips two function parameters, together with the corresponding arguments of all calls.
+//
+// ```
+// fn foo(x: i32,$0 y: bool) {}
+// fn main() {
+//     foo(1, true);
+// }
+// ```
+// ->
+// ```
+// fn foo(y: bool, x: i32) {}
+// fn main() {
+//     foo(true, 1);
+// }
+// ```
 pub(crate) fn flip_comma(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let comma = ctx.find_token_syntax_at_offset(T![,])?;
-    let prev = non_trivia_sibling(comma.clone().into(), Direction::Prev)?;
-    let next = non_trivia_sibling(comma.clone().into(), Direction::Next)?;
-
-    // Don't apply a "flip" in case of a last comma
-    // that typically comes before punctuation
-    if next.kind().is_punct() {
-        return None;
-    }
-
-    // Don't apply a "flip" inside the macro call
-    // since macro input are just mere tokens
-    if comma.ancestors().any(|it| it.kind() == SyntaxKind::MACRO_CALL) {
-        return None;
-    }
+    let siblings = Siblings::around(&comma)?;
 
     acc.add(
         AssistId("flip_comma", AssistKind::RefactorRewrite),
         "Flip comma",
         comma.text_range(),
-        |edit| {
-            edit.replace(prev.text_range(), next.to_string());
-            edit.replace(next.text_range(), prev.to_string());
-        },
-    )
+        |edit| siblings.swap(edit),
+    );
+
+    if siblings.kind == ListKind::Params {
+        let param = siblings.prev.as_node().cloned().and_then(ast::Param::cast)?;
+        let param_list = ast::ParamList::cast(comma.parent())?;
+        let func = param_list.syntax().parent().and_then(ast::Fn::cast)?;
+        let idx = param_list.params().position(|it| it == param)?;
+        let function = ctx.sema.to_def(&func)?;
+        let has_self = param_list.self_param().is_some();
+        acc.add(
+            AssistId("flip_params_and_args", AssistKind::RefactorRewrite),
+            "Flip parameters and update calls",
+            comma.text_range(),
+            |edit| {
+                siblings.swap(edit);
+                let def = Definition::ModuleDef(ModuleDef::Function(function));
+                for (file_id, references) in def.usages(&ctx.sema).all() {
+                    flip_args(ctx, edit, file_id, references, idx, has_self);
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+fn flip_args(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    file_id: FileId,
+    references: Vec<FileReference>,
+    idx: usize,
+    has_self: bool,
+) {
+    let source_file = ctx.sema.parse(file_id);
+    builder.edit_file(file_id);
+    let pairs = references
+        .into_iter()
+        .filter_map(|FileReference { range, .. }| {
+            let name_ref: ast::NameRef = find_node_at_range(source_file.syntax(), range)?;
+            let (first, second) = call_args(&name_ref, idx, has_self)?;
+            Some((first.text_range(), second.text_range()))
+        })
+        .collect::<Vec<_>>();
+    // Nested calls, like `foo(foo(a, b), c)`, are flipped as part of the outer call.
+    let text = source_file.syntax().to_string();
+    for (first, second) in outermost(&pairs) {
+        let range = first.cover(second);
+        builder.replace(range, render(&text, range, &pairs));
+    }
+}
+
+/// The text of `range`, with the pairs of arguments it contains flipped.
+fn render(text: &str, range: TextRange, pairs: &[(TextRange, TextRange)]) -> String {
+    let inner = pairs
+        .iter()
+        .filter(|&&(first, second)| range.contains_range(first.cover(second)))
+        .copied()
+        .collect::<Vec<_>>();
+    let mut res = String::new();
+    let mut offset = range.start();
+    for (first, second) in outermost(&inner) {
+        res.push_str(&text[TextRange::new(offset, first.start())]);
+        res.push_str(&render(text, second, pairs));
+        res.push_str(&text[TextRange::new(first.end(), second.start())]);
+        res.push_str(&render(text, first, pairs));
+        offset = second.end();
+    }
+    res.push_str(&text[TextRange::new(offset, range.end())]);
+    res
+}
+
+/// The pairs which aren't nested in another one, in order.
+fn outermost(pairs: &[(TextRange, TextRange)]) -> Vec<(TextRange, TextRange)> {
+    let mut res = pairs
+        .iter()
+        .filter(|&&(first, second)| {
+            let range = first.cover(second);
+            !pairs.iter().any(|&(f, s)| f.cover(s) != range && f.cover(s).contains_range(range))
+        })
+        .copied()
+        .collect::<Vec<_>>();
+    res.sort_by_key(|&(first, _)| first.start());
+    res.dedup();
+    res
+}
+
+/// Finds the arguments of the parameters `idx` and `idx + 1` in a call referring to the
+/// function by `name_ref`.
+fn call_args(
+    name_ref: &ast::NameRef,
+    idx: usize,
+    has_self: bool,
+) -> Option<(SyntaxElement, SyntaxElement)> {
+    let parent = name_ref.syntax().parent()?;
+    let (arg_list, idx) = if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        (call.arg_list()?, idx)
+    } else {
+        let path_expr = parent.ancestors().find_map(ast::PathExpr::cast)?;
+        if path_expr.path()?.segment()?.name_ref()? != *name_ref {
+            return None;
+        }
+        let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+        // With the path syntax, the receiver is passed as the first argument.
+        (call.arg_list()?, if has_self { idx + 1 } else { idx })
+    };
+    let mut args = arg_list.args().skip(idx);
+    let first = args.next()?;
+    let second = args.next()?;
+    Some((first.syntax().clone().into(), second.syntax().clone().into()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+    use crate::tests::{
+        check_assist, check_assist_by_label, check_assist_not_applicable, check_assist_target,
+    };
 
     #[test]
     fn flip_comma_works_for_function_parameters() {
@@ -103,4 +225,83 @@ mod tests {
         // See https://github.com/rust-analyzer/rust-analyzer/issues/7693
         check_assist_not_applicable(flip_comma, r#"bar!(a,$0 b)"#);
     }
+
+    #[test]
+    fn flip_comma_works_for_generic_params_and_where_predicates() {
+        check_assist(
+            flip_comma,
+            r#"fn foo<'a, T,$0 U>() where T: Clone, U: Copy {}"#,
+            r#"fn foo<'a, U, T>() where T: Clone, U: Copy {}"#,
+        );
+        check_assist(
+            flip_comma,
+            r#"fn foo<T, U>() where T: Clone,$0 U: Copy {}"#,
+            r#"fn foo<T, U>() where U: Copy, T: Clone {}"#,
+        );
+    }
+
+    #[test]
+    fn flip_comma_not_applicable_for_ordered_params() {
+        check_assist_not_applicable(flip_comma, r#"fn foo<'a,$0 T>() {}"#);
+        check_assist_not_applicable(
+            flip_comma,
+            r#"struct S; impl S { fn foo(&self,$0 x: i32) {} }"#,
+        );
+    }
+
+    #[test]
+    fn flip_params_and_args_updates_calls() {
+        check_assist_by_label(
+            flip_comma,
+            r#"
+struct S;
+impl S {
+    fn foo(&self, x: i32,$0 y: bool) {}
+}
+fn main() {
+    S.foo(1, true);
+    S::foo(&S, 2, false);
+    let f = S::foo;
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn foo(&self, y: bool, x: i32) {}
+}
+fn main() {
+    S.foo(true, 1);
+    S::foo(&S, false, 2);
+    let f = S::foo;
+}
+"#,
+            "Flip parameters and update calls",
+        );
+    }
+
+    #[test]
+    fn flip_params_and_args_flips_nested_calls() {
+        check_assist_by_label(
+            flip_comma,
+            r#"
+//- /main.rs
+mod other;
+fn foo(x: i32,$0 y: i32) -> i32 { x - y }
+//- /other.rs
+fn bar() {
+    crate::foo(crate::foo(1, crate::foo(2, 3)), 4);
+}
+"#,
+            r#"
+//- /main.rs
+mod other;
+fn foo(y: i32, x: i32) -> i32 { x - y }
+//- /other.rs
+fn bar() {
+    crate::foo(4, crate::foo(crate::foo(3, 2), 1));
+}
+"#,
+            "Flip parameters and update calls",
+        );
+    }
 }
@@ -1,10 +1,9 @@
-use syntax::{
-    algo::non_trivia_sibling,
-    ast::{self, AstNode},
-    Direction, T,
-};
+use syntax::T;
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    utils::reorder::{ListKind, Siblings},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // Assist: flip_trait_bound
 //
@@ -22,25 +21,17 @@ pub(crate) fn flip_trait_bound(acc: &mut Assists, ctx: &AssistContext) -> Option
     // the assist when the cursor is on a `+`
     let plus = ctx.find_token_syntax_at_offset(T![+])?;
 
-    // Make sure we're in a `TypeBoundList`
-    if ast::TypeBoundList::cast(plus.parent()).is_none() {
+    let siblings = Siblings::around(&plus)?;
+    if siblings.kind != ListKind::TypeBounds {
         return None;
     }
 
-    let (before, after) = (
-        non_trivia_sibling(plus.clone().into(), Direction::Prev)?,
-        non_trivia_sibling(plus.clone().into(), Direction::Next)?,
-    );
-
     let target = plus.text_range();
     acc.add(
         AssistId("flip_trait_bound", AssistKind::RefactorRewrite),
         "Flip trait bounds",
         target,
-        |edit| {
-            edit.replace(before.text_range(), after.to_string());
-            edit.replace(after.text_range(), before.to_string());
-        },
+        |edit| siblings.swap(edit),
     )
 }
 
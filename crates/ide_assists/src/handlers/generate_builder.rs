@@ -0,0 +1,302 @@
+use ide_db::helpers::FamousDefs;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::ast::{
+    self, edit::IndentLevel, AstNode, GenericParamsOwner, NameOwner, VisibilityOwner,
+};
+use test_utils::mark;
+
+use crate::{utils::adt_ty_text, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_builder
+//
+// Generates a builder for a struct with named fields, with a setter for each field and a
+// `build` method. Fields whose type doesn't implement `Default` must be set, and make `build`
+// return a `Result`.
+//
+// ```
+// pub struct Request$0 {
+//     url: String,
+//     retries: u32,
+// }
+// ```
+// ->
+// ```
+// pub struct Request {
+//     url: String,
+//     retries: u32,
+// }
+//
+// pub struct RequestBuilder {
+//     url: Option<String>,
+//     retries: Option<u32>,
+// }
+//
+// impl RequestBuilder {
+//     pub fn new() -> Self {
+//         Self { url: None, retries: None }
+//     }
+//
+//     pub fn url(mut self, url: String) -> Self {
+//         self.url = Some(url);
+//         self
+//     }
+//
+//     pub fn retries(mut self, retries: u32) -> Self {
+//         self.retries = Some(retries);
+//         self
+//     }
+//
+//     pub fn build(self) -> Result<Request, &'static str> {
+//         Ok(Request {
+//             url: self.url.ok_or("`url` is not set")?,
+//             retries: self.retries.ok_or("`retries` is not set")?,
+//         })
+//     }
+// }
+// ```
+pub(crate) fn generate_builder(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.kind() {
+        ast::StructKind::Record(it) => it,
+        _ => return None,
+    };
+    if let Some(list) = strukt.field_list() {
+        if list.syntax().text_range().contains(ctx.offset()) {
+            return None;
+        }
+    }
+    let name = strukt.name()?.text().to_string();
+    let builder_name = format!("{}Builder", name);
+    let mut taken = false;
+    ctx.sema.scope(strukt.syntax()).process_all_names(&mut |it, _| {
+        taken |= it.to_string() == builder_name;
+    });
+    if taken {
+        mark::hit!(generate_builder_name_taken);
+        return None;
+    }
+
+    let def = ctx.sema.to_def(&strukt)?;
+    let default_trait =
+        FamousDefs(&ctx.sema, Some(def.module(ctx.db()).krate())).core_default_Default();
+    let fields = field_list
+        .fields()
+        .zip(def.fields(ctx.db()))
+        .map(|(field, hir_field)| {
+            let has_default = default_trait.map_or(false, |default_trait| {
+                hir_field.signature_ty(ctx.db()).impls_trait(ctx.db(), default_trait, &[])
+            });
+            Some(BuilderField { name: field.name()?, ty: field.ty()?, has_default })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fields.is_empty() {
+        return None;
+    }
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("generate_builder", AssistKind::Generate),
+        format!("Generate `{}`", builder_name),
+        target,
+        |builder| {
+            let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+            let generic_params =
+                strukt.generic_param_list().map_or(String::new(), |it| it.to_string());
+            let where_clause =
+                strukt.where_clause().map_or(String::new(), |it| format!(" {}", it));
+            let struct_ty = adt_ty_text(&ast::Adt::Struct(strukt.clone()));
+            let builder_ty = format!("{}{}", builder_name, &struct_ty[name.len()..]);
+
+            let mut buf = String::new();
+            format_to!(buf, "{}struct {}{}{} {{\n", vis, builder_name, generic_params, where_clause);
+            for field in &fields {
+                format_to!(buf, "    {}: Option<{}>,\n", field.name, field.ty);
+            }
+            format_to!(buf, "}}\n\nimpl{} {}{} {{\n", generic_params, builder_ty, where_clause);
+            format_to!(
+                buf,
+                "    {}fn new() -> Self {{\n        Self {{ {} }}\n    }}\n",
+                vis,
+                fields.iter().map(|it| format!("{}: None", it.name)).format(", ")
+            );
+            for field in &fields {
+                format_to!(
+                    buf,
+                    "\n    {0}fn {1}(mut self, {1}: {2}) -> Self {{\n        self.{1} = Some({1});\n        self\n    }}\n",
+                    vis,
+                    field.name,
+                    field.ty
+                );
+            }
+            let fallible = fields.iter().any(|it| !it.has_default);
+            let (ret, open, close) = if fallible {
+                (format!("Result<{}, &'static str>", struct_ty), "Ok(", ")")
+            } else {
+                (struct_ty.clone(), "", "")
+            };
+            format_to!(buf, "\n    {}fn build(self) -> {} {{\n        {}{} {{\n", vis, ret, open, name);
+            for field in &fields {
+                if field.has_default {
+                    format_to!(buf, "            {0}: self.{0}.unwrap_or_default(),\n", field.name);
+                } else {
+                    format_to!(
+                        buf,
+                        "            {0}: self.{0}.ok_or(\"`{0}` is not set\")?,\n",
+                        field.name
+                    );
+                }
+            }
+            format_to!(buf, "        }}{}\n    }}\n}}", close);
+
+            let indent = IndentLevel::from_node(strukt.syntax());
+            let text = buf
+                .lines()
+                .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", indent, line) })
+                .join("\n");
+            builder.insert(strukt.syntax().text_range().end(), format!("\n\n{}", text));
+        },
+    )
+}
+
+struct BuilderField {
+    name: ast::Name,
+    ty: ast::Type,
+    has_default: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn defaults_fields_implementing_default() {
+        check_assist(
+            generate_builder,
+            &format!(
+                r#"//- /main.rs crate:main deps:core
+mod net {{
+    #[derive(Clone)]
+    pub struct Flags;
+    impl core::default::Default for Flags {{
+        fn default() -> Self {{ Flags }}
+    }}
+
+    pub(crate) struct $0Config<T> where T: Clone {{
+        pub flags: Flags,
+        payload: T,
+    }}
+}}
+{}"#,
+                FamousDefs::FIXTURE
+            ),
+            r#"
+mod net {
+    #[derive(Clone)]
+    pub struct Flags;
+    impl core::default::Default for Flags {
+        fn default() -> Self { Flags }
+    }
+
+    pub(crate) struct Config<T> where T: Clone {
+        pub flags: Flags,
+        payload: T,
+    }
+
+    pub(crate) struct ConfigBuilder<T> where T: Clone {
+        flags: Option<Flags>,
+        payload: Option<T>,
+    }
+
+    impl<T> ConfigBuilder<T> where T: Clone {
+        pub(crate) fn new() -> Self {
+            Self { flags: None, payload: None }
+        }
+
+        pub(crate) fn flags(mut self, flags: Flags) -> Self {
+            self.flags = Some(flags);
+            self
+        }
+
+        pub(crate) fn payload(mut self, payload: T) -> Self {
+            self.payload = Some(payload);
+            self
+        }
+
+        pub(crate) fn build(self) -> Result<Config<T>, &'static str> {
+            Ok(Config {
+                flags: self.flags.unwrap_or_default(),
+                payload: self.payload.ok_or("`payload` is not set")?,
+            })
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn infallible_build_when_all_fields_have_defaults() {
+        check_assist(
+            generate_builder,
+            &format!(
+                r#"//- /main.rs crate:main deps:core
+struct Flags;
+impl core::default::Default for Flags {{
+    fn default() -> Self {{ Flags }}
+}}
+struct $0Options {{ flags: Flags }}
+{}"#,
+                FamousDefs::FIXTURE
+            ),
+            r#"
+struct Flags;
+impl core::default::Default for Flags {
+    fn default() -> Self { Flags }
+}
+struct Options { flags: Flags }
+
+struct OptionsBuilder {
+    flags: Option<Flags>,
+}
+
+impl OptionsBuilder {
+    fn new() -> Self {
+        Self { flags: None }
+    }
+
+    fn flags(mut self, flags: Flags) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    fn build(self) -> Options {
+        Options {
+            flags: self.flags.unwrap_or_default(),
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_fields_or_to_tuple_structs() {
+        check_assist_not_applicable(generate_builder, "struct S { a: u32$0 }");
+        check_assist_not_applicable(generate_builder, "struct $0S(u32);");
+    }
+
+    #[test]
+    fn not_applicable_when_builder_exists() {
+        mark::check!(generate_builder_name_taken);
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+struct $0S { a: u32 }
+struct SBuilder;
+"#,
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use itertools::Itertools;
+use syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner},
+    TextRange,
+};
+
+use crate::{
+    utils::{gen_trait_fn_body::gen_debug_fn_body, generate_trait_impl_text},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_debug_impl
+//
+// Adds a `Debug` impl printing the fields of a type, with the fields under the cursor or
+// selection printed as `<redacted>`.
+//
+// ```
+// struct Login {
+//     user: String,
+//     pass$0word: String,
+// }
+// ```
+// ->
+// ```
+// struct Login {
+//     user: String,
+//     password: String,
+// }
+//
+// impl core::fmt::Debug for Login {
+//     $0fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+//         f.debug_struct("Login").field("user", &self.user).field("password", &"<redacted>").finish()
+//     }
+// }
+// ```
+pub(crate) fn generate_debug_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let adt = ctx.find_node_at_offset::<ast::Adt>()?;
+    if derives_debug(&adt) || implements_debug(ctx, &adt).unwrap_or(false) {
+        return None;
+    }
+
+    let redacted = redacted_fields(&adt, ctx.frange.range);
+    let fmt = if resolves_to_trait(ctx, &adt, "fmt::Debug") { "fmt" } else { "core::fmt" };
+    let body = gen_debug_fn_body(&adt, "f", &|owner, field| {
+        redacted.iter().any(|(it, name)| it == owner && name == field)
+    })?;
+
+    let label = if redacted.is_empty() {
+        "Generate `Debug` impl".to_string()
+    } else {
+        let fields = redacted.iter().map(|(_, field)| format!("`{}`", field)).format(", ");
+        format!("Generate `Debug` impl redacting {}", fields)
+    };
+    let target = adt.syntax().text_range();
+    acc.add(AssistId("generate_debug_impl", AssistKind::Generate), label, target, |builder| {
+        let cursor = if ctx.config.snippet_cap.is_some() { "$0" } else { "" };
+        let code = format!(
+            "    {}fn fmt(&self, f: &mut {fmt}::Formatter<'_>) -> {fmt}::Result {}",
+            cursor,
+            body,
+            fmt = fmt
+        );
+        let text = generate_trait_impl_text(&adt, &format!("{}::Debug", fmt), &code);
+        let offset = adt.syntax().text_range().end();
+        match ctx.config.snippet_cap {
+            Some(cap) => builder.insert_snippet(cap, offset, text),
+            None => builder.insert(offset, text),
+        }
+    })
+}
+
+/// The fields in `range`, with the name of their struct or variant.
+fn redacted_fields(adt: &ast::Adt, range: TextRange) -> Vec<(String, String)> {
+    let in_range = |field: &dyn AstNode| {
+        let field = field.syntax().text_range();
+        if range.is_empty() {
+            field.contains_range(range)
+        } else {
+            field.intersect(range).map_or(false, |it| !it.is_empty())
+        }
+    };
+    let owners = match adt {
+        ast::Adt::Struct(it) => vec![(it.name(), it.field_list())],
+        ast::Adt::Enum(it) => it
+            .variant_list()
+            .into_iter()
+            .flat_map(|it| it.variants())
+            .map(|it| (it.name(), it.field_list()))
+            .collect(),
+        ast::Adt::Union(_) => Vec::new(),
+    };
+
+    let mut res = Vec::new();
+    for (owner, field_list) in owners {
+        let owner = match owner {
+            Some(it) => it.to_string(),
+            None => continue,
+        };
+        match field_list {
+            Some(ast::FieldList::RecordFieldList(it)) => {
+                for field in it.fields().filter(|it| in_range(it)) {
+                    if let Some(name) = field.name() {
+                        res.push((owner.clone(), name.to_string()));
+                    }
+                }
+            }
+            Some(ast::FieldList::TupleFieldList(it)) => {
+                for (idx, _) in it.fields().enumerate().filter(|(_, it)| in_range(it)) {
+                    res.push((owner.clone(), idx.to_string()));
+                }
+            }
+            None => (),
+        }
+    }
+    res
+}
+
+fn derives_debug(adt: &ast::Adt) -> bool {
+    adt.attrs().filter(|attr| attr.simple_name().as_deref() == Some("derive")).any(|attr| {
+        attr.token_tree().map_or(false, |tt| {
+            tt.syntax()
+                .descendants_with_tokens()
+                .filter_map(|it| it.into_token())
+                .any(|it| it.text() == "Debug")
+        })
+    })
+}
+
+fn implements_debug(ctx: &AssistContext, adt: &ast::Adt) -> Option<bool> {
+    let path = ast::Path::parse("core::fmt::Debug").ok()?;
+    let debug = match ctx.sema.scope(adt.syntax()).speculative_resolve(&path)? {
+        hir::PathResolution::Def(hir::ModuleDef::Trait(it)) => it,
+        _ => return None,
+    };
+    let ty = match adt {
+        ast::Adt::Struct(it) => ctx.sema.to_def(it)?.ty(ctx.db()),
+        ast::Adt::Enum(it) => ctx.sema.to_def(it)?.ty(ctx.db()),
+        ast::Adt::Union(it) => ctx.sema.to_def(it)?.ty(ctx.db()),
+    };
+    Some(ty.impls_trait(ctx.db(), debug, &[]))
+}
+
+fn resolves_to_trait(ctx: &AssistContext, adt: &ast::Adt, path: &str) -> bool {
+    let path = match ast::Path::parse(path) {
+        Ok(it) => it,
+        Err(_) => return false,
+    };
+    matches!(
+        ctx.sema.scope(adt.syntax()).speculative_resolve(&path),
+        Some(hir::PathResolution::Def(hir::ModuleDef::Trait(_)))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn generate_debug_impl_target() {
+        check_assist_target(generate_debug_impl, "struct $0Unit;", "struct Unit;");
+    }
+
+    #[test]
+    fn generates_debug_struct() {
+        check_assist(
+            generate_debug_impl,
+            r#"
+struct Point<T> {
+    x: T,
+    $0y: T,
+}"#,
+            r#"
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+impl<T> core::fmt::Debug for Point<T> {
+    $0fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Point").field("x", &self.x).field("y", &"<redacted>").finish()
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn redacts_selected_fields_of_variants() {
+        check_assist(
+            generate_debug_impl,
+            r#"
+mod fmt { pub trait Debug {} }
+enum Credentials {
+    None,
+    Token($0String, u64$0),
+    Login { user: String, password: String },
+}"#,
+            r#"
+mod fmt { pub trait Debug {} }
+enum Credentials {
+    None,
+    Token(String, u64),
+    Login { user: String, password: String },
+}
+
+impl fmt::Debug for Credentials {
+    $0fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Token(_, _) => f.debug_tuple("Token").field(&"<redacted>").field(&"<redacted>").finish(),
+            Self::Login { user, password } => f.debug_struct("Login").field("user", user).field("password", password).finish(),
+        }
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn redacts_field_of_record_variant() {
+        check_assist(
+            generate_debug_impl,
+            r#"
+enum Credentials {
+    Login { user: String, pass$0word: String },
+}"#,
+            r#"
+enum Credentials {
+    Login { user: String, password: String },
+}
+
+impl core::fmt::Debug for Credentials {
+    $0fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Login { user, password: _ } => f.debug_struct("Login").field("user", user).field("password", &"<redacted>").finish(),
+        }
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_debug_is_derived() {
+        check_assist_not_applicable(
+            generate_debug_impl,
+            r#"
+#[derive(Clone, Debug)]
+struct S$0 { x: u32 }"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_unions() {
+        check_assist_not_applicable(generate_debug_impl, "union U$0 { x: u32 }");
+    }
+}
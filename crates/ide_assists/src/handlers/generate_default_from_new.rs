@@ -0,0 +1,191 @@
+use ide_db::helpers::FamousDefs;
+use stdx::format_to;
+use syntax::ast::{self, edit::IndentLevel, AstNode, GenericParamsOwner, NameOwner};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_default_from_new
+//
+// Generates a `Default` impl delegating to a `new` function without arguments.
+//
+// ```
+// struct Example { _inner: () }
+//
+// impl Example {
+//     pub fn n$0ew() -> Self {
+//         Self { _inner: () }
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Example { _inner: () }
+//
+// impl Example {
+//     pub fn new() -> Self {
+//         Self { _inner: () }
+//     }
+// }
+//
+// impl Default for Example {
+//     fn default() -> Self {
+//         Self::new()
+//     }
+// }
+// ```
+pub(crate) fn generate_default_from_new(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_node = ctx.find_node_at_offset::<ast::Fn>()?;
+    let fn_name = fn_node.name()?;
+    if fn_name.text() != "new" || fn_node.generic_param_list().is_some() {
+        return None;
+    }
+    let param_list = fn_node.param_list()?;
+    if param_list.self_param().is_some() || param_list.params().next().is_some() {
+        mark::hit!(default_from_new_fn_with_params);
+        return None;
+    }
+
+    let impl_ = fn_node.syntax().ancestors().find_map(ast::Impl::cast)?;
+    if impl_.trait_().is_some() {
+        return None;
+    }
+    let self_ty = impl_.self_ty()?;
+    let ret_ty = fn_node.ret_type()?.ty()?.syntax().text().to_string();
+    if ret_ty != "Self" && ret_ty != self_ty.syntax().text().to_string() {
+        return None;
+    }
+    if implements_default(ctx, &impl_) {
+        mark::hit!(default_from_new_already_implemented);
+        return None;
+    }
+
+    let target = fn_node.syntax().text_range();
+    acc.add(
+        AssistId("generate_default_from_new", AssistKind::Generate),
+        "Generate a Default impl from a new fn",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(impl_.syntax());
+            let mut buf = String::from("\n\n");
+            format_to!(buf, "{}impl", indent);
+            if let Some(generic_params) = impl_.generic_param_list() {
+                format_to!(buf, "{}", generic_params);
+            }
+            format_to!(buf, " Default for {}", self_ty);
+            if let Some(where_clause) = impl_.where_clause() {
+                format_to!(buf, " {}", where_clause);
+            }
+            format_to!(
+                buf,
+                " {{\n{0}    fn default() -> Self {{\n{0}        Self::new()\n{0}    }}\n{0}}}",
+                indent
+            );
+            builder.insert(impl_.syntax().text_range().end(), buf);
+        },
+    )
+}
+
+fn implements_default(ctx: &AssistContext, impl_: &ast::Impl) -> bool {
+    let db = ctx.db();
+    let impl_def = match ctx.sema.to_def(impl_) {
+        Some(it) => it,
+        None => return false,
+    };
+    let krate = impl_def.module(db).krate();
+    let default_trait = match FamousDefs(&ctx.sema, Some(krate)).core_default_Default() {
+        Some(it) => it,
+        None => return false,
+    };
+    impl_def.target_ty(db).impls_trait(db, default_trait, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generates_default_for_generic_impl() {
+        check_assist(
+            generate_default_from_new,
+            r#"
+struct Stack<T> { items: Vec<T> }
+
+mod imp {
+    impl<T> super::Stack<T> where T: Clone {
+        fn new$0() -> super::Stack<T> {
+            super::Stack { items: Vec::new() }
+        }
+    }
+}
+"#,
+            r#"
+struct Stack<T> { items: Vec<T> }
+
+mod imp {
+    impl<T> super::Stack<T> where T: Clone {
+        fn new() -> super::Stack<T> {
+            super::Stack { items: Vec::new() }
+        }
+    }
+
+    impl<T> Default for super::Stack<T> where T: Clone {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_new_takes_arguments() {
+        mark::check!(default_from_new_fn_with_params);
+        check_assist_not_applicable(
+            generate_default_from_new,
+            r#"
+struct S(u32);
+impl S {
+    fn $0new(value: u32) -> Self { S(value) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_default_exists() {
+        mark::check!(default_from_new_already_implemented);
+        check_assist_not_applicable(
+            generate_default_from_new,
+            &format!(
+                r#"//- /main.rs crate:main deps:core
+struct S;
+impl S {{
+    fn $0new() -> Self {{ S }}
+}}
+impl core::default::Default for S {{
+    fn default() -> Self {{ S }}
+}}
+{}"#,
+                FamousDefs::FIXTURE
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_trait_impls() {
+        check_assist_not_applicable(
+            generate_default_from_new,
+            r#"
+trait Make { fn new() -> Self; }
+struct S;
+impl Make for S {
+    fn $0new() -> Self { S }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use hir::{AsAssocItem, AssocItem, AssocItemContainer, HasSource, HasVisibility};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::format_to;
+use syntax::{
+    algo::SyntaxRewriter,
+    ast::{self, make, AstNode, GenericParamsOwner, NameOwner, VisibilityOwner},
+    SyntaxNode,
+};
+
+use crate::{
+    utils::{add_method_to_adt, find_struct_impl},
+    AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
+
+// Assist: generate_delegate_methods
+//
+// Generate a method on the struct which delegates to a method of one of its fields.
+//
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 {
+//         self.0
+//     }
+// }
+//
+// struct Person {
+//     ag$0e: Age,
+// }
+// ```
+// ->
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 {
+//         self.0
+//     }
+// }
+//
+// struct Person {
+//     age: Age,
+// }
+//
+// impl Person {
+//     fn age(&self) -> u8 {
+//         self.age.age()
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let (field_name, field_ty, field_def, target) =
+        if let Some(field) = ctx.find_node_at_offset::<ast::RecordField>() {
+            let def = ctx.sema.to_def(&field)?;
+            (field.name()?.to_string(), field.ty()?, def, field.syntax().text_range())
+        } else {
+            let field = ctx.find_node_at_offset::<ast::TupleField>()?;
+            let field_list = field.syntax().parent().and_then(ast::TupleFieldList::cast)?;
+            let idx = field_list.fields().position(|it| it == field)?;
+            let def = ctx.sema.to_def(&field)?;
+            (idx.to_string(), field.ty()?, def, field.syntax().text_range())
+        };
+
+    let db = ctx.db();
+    let module = ctx.sema.scope(strukt.syntax()).module()?;
+    let mut methods = Vec::new();
+    let mut seen_names = FxHashSet::default();
+    field_def.signature_ty(db).iterate_assoc_items(db, module.krate(), |item| {
+        if let AssocItem::Function(f) = item {
+            if f.self_param(db).is_some()
+                && f.is_visible_from(db, module)
+                && seen_names.insert(f.name(db))
+            {
+                methods.push(f);
+            }
+        }
+        None::<()>
+    });
+
+    let adt = ast::Adt::Struct(strukt.clone());
+    let group = GroupLabel("Generate delegate methods…".to_owned());
+    for method in methods {
+        let name = method.name(db).to_string();
+        let impl_def = match find_struct_impl(ctx, &adt, &name) {
+            Some(it) => it,
+            None => continue,
+        };
+        let source = match method.source(db) {
+            Some(it) => it.value,
+            None => continue,
+        };
+        let subst = match method.as_assoc_item(db).map(|it| it.container(db)) {
+            Some(AssocItemContainer::Impl(imp)) => match imp.source(db) {
+                Some(imp) => type_substitution(&imp.value, &field_ty),
+                None => continue,
+            },
+            _ => continue,
+        };
+        let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+        let method_text = match delegate_method(&source, &vis, &field_name, &subst) {
+            Some(it) => it,
+            None => continue,
+        };
+
+        acc.add_group(
+            &group,
+            AssistId("generate_delegate_methods", AssistKind::Generate),
+            format!("Generate delegate for `{}.{}()`", field_name, name),
+            target,
+            |builder| add_method_to_adt(builder, &adt, impl_def, &method_text),
+        )?;
+    }
+    Some(())
+}
+
+/// Maps the names of the type parameters of `imp` to the types `field_ty` passes for them, and
+/// `Self` to `field_ty` itself.
+fn type_substitution(imp: &ast::Impl, field_ty: &ast::Type) -> FxHashMap<String, String> {
+    let mut subst = FxHashMap::default();
+    subst.insert("Self".to_string(), field_ty.to_string());
+    let args = |ty: &ast::Type| match ty {
+        ast::Type::PathType(it) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.generic_arg_list())
+            .map(|it| it.generic_args().filter_map(type_arg).collect::<Vec<_>>()),
+        _ => None,
+    };
+    let (impl_args, field_args) = match (imp.self_ty().as_ref().and_then(args), args(field_ty)) {
+        (Some(impl_args), Some(field_args)) => (impl_args, field_args),
+        _ => return subst,
+    };
+    let params: Vec<_> = imp
+        .generic_param_list()
+        .map(|it| it.type_params().filter_map(|it| it.name()).map(|it| it.to_string()).collect())
+        .unwrap_or_default();
+    for (impl_arg, field_arg) in impl_args.iter().zip(field_args.iter()) {
+        let name = impl_arg.to_string();
+        if params.contains(&name) {
+            subst.insert(name, field_arg.to_string());
+        }
+    }
+    subst
+}
+
+fn type_arg(arg: ast::GenericArg) -> Option<ast::Type> {
+    match arg {
+        ast::GenericArg::TypeArg(it) => it.ty(),
+        _ => None,
+    }
+}
+
+/// Renders a method with the signature of `method` which calls it on the field.
+fn delegate_method(
+    method: &ast::Fn,
+    vis: &str,
+    field_name: &str,
+    subst: &FxHashMap<String, String>,
+) -> Option<String> {
+    let method = substitute_types(method, subst);
+    let param_list = method.param_list()?;
+    let self_param = param_list.self_param()?;
+    if self_param.colon_token().is_some() {
+        return None;
+    }
+    let self_param = match self_param.amp_token() {
+        Some(_) => self_param.to_string(),
+        None => "self".to_string(),
+    };
+
+    let mut params = vec![self_param];
+    let mut args = Vec::new();
+    for (idx, param) in param_list.params().enumerate() {
+        let arg = match param.pat() {
+            Some(ast::Pat::IdentPat(it)) if it.ref_token().is_none() => it.name()?.to_string(),
+            _ => format!("arg{}", idx),
+        };
+        params.push(format!("{}: {}", arg, param.ty()?));
+        args.push(arg);
+    }
+
+    let mut buf = format!("    {}", vis);
+    if method.async_token().is_some() {
+        buf.push_str("async ");
+    }
+    if method.unsafe_token().is_some() {
+        buf.push_str("unsafe ");
+    }
+    format_to!(buf, "fn {}", method.name()?);
+    if let Some(generic_params) = method.generic_param_list() {
+        format_to!(buf, "{}", generic_params);
+    }
+    format_to!(buf, "({})", params.iter().format(", "));
+    if let Some(ret_type) = method.ret_type() {
+        format_to!(buf, " {}", ret_type);
+    }
+    match method.where_clause() {
+        Some(where_clause) => {
+            buf.push_str("\n    where\n");
+            for pred in where_clause.predicates() {
+                format_to!(buf, "        {},\n", pred);
+            }
+            buf.push_str("    {");
+        }
+        None => buf.push_str(" {"),
+    }
+    format_to!(
+        buf,
+        "\n        self.{}.{}({}){}\n    }}",
+        field_name,
+        method.name()?,
+        args.iter().format(", "),
+        if method.async_token().is_some() { ".await" } else { "" },
+    );
+    Some(buf)
+}
+
+/// Replaces the type parameters of the impl, and `Self`, in the signature of `method`.
+fn substitute_types(method: &ast::Fn, subst: &FxHashMap<String, String>) -> ast::Fn {
+    let mut rewriter = SyntaxRewriter::default();
+    for path_type in method.syntax().descendants().filter_map(ast::PathType::cast) {
+        let path = match path_type.path() {
+            Some(it) => it,
+            None => continue,
+        };
+        if path.qualifier().is_some()
+            || path.segment().and_then(|it| it.generic_arg_list()).is_some()
+        {
+            continue;
+        }
+        if let Some(ty) = subst.get(&path.to_string()) {
+            let ty: SyntaxNode = make::ty(ty).syntax().clone();
+            rewriter.replace(path_type.syntax(), &ty);
+        }
+    }
+    rewriter.rewrite_ast(method)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn delegates_method_with_owning_and_mut_receivers() {
+        check_assist_by_label(
+            generate_delegate_methods,
+            r#"
+struct Stack { items: Vec<u8> }
+impl Stack {
+    fn push(&mut self, mut item: u8) {}
+    fn into_items(self) -> Vec<u8> { loop {} }
+}
+
+struct Machine {
+    st$0ack: Stack,
+}
+"#,
+            r#"
+struct Stack { items: Vec<u8> }
+impl Stack {
+    fn push(&mut self, mut item: u8) {}
+    fn into_items(self) -> Vec<u8> { loop {} }
+}
+
+struct Machine {
+    stack: Stack,
+}
+
+impl Machine {
+    fn push(&mut self, item: u8) {
+        self.stack.push(item)
+    }
+}
+"#,
+            "Generate delegate for `stack.push()`",
+        );
+        check_assist_by_label(
+            generate_delegate_methods,
+            r#"
+struct Stack { items: Vec<u8> }
+impl Stack {
+    fn push(&mut self, item: u8) {}
+    fn into_items(mut self) -> Vec<u8> { loop {} }
+}
+
+struct Machine(pub Stack$0);
+"#,
+            r#"
+struct Stack { items: Vec<u8> }
+impl Stack {
+    fn push(&mut self, item: u8) {}
+    fn into_items(mut self) -> Vec<u8> { loop {} }
+}
+
+struct Machine(pub Stack);
+
+impl Machine {
+    fn into_items(self) -> Vec<u8> {
+        self.0.into_items()
+    }
+}
+"#,
+            "Generate delegate for `0.into_items()`",
+        );
+    }
+
+    #[test]
+    fn substitutes_generics_of_the_field_type() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+struct Wrapper<T> { inner: T }
+impl<T: Clone> Wrapper<T> {
+    fn map<U, F>(&self, f: F) -> Wrapper<U>
+    where
+        F: Fn(T) -> U,
+    {
+        loop {}
+    }
+}
+
+pub struct Names<'a> {
+    na$0mes: Wrapper<&'a str>,
+}
+
+impl<'a> Names<'a> {
+    fn len(&self) -> usize { 0 }
+}
+"#,
+            r#"
+struct Wrapper<T> { inner: T }
+impl<T: Clone> Wrapper<T> {
+    fn map<U, F>(&self, f: F) -> Wrapper<U>
+    where
+        F: Fn(T) -> U,
+    {
+        loop {}
+    }
+}
+
+pub struct Names<'a> {
+    names: Wrapper<&'a str>,
+}
+
+impl<'a> Names<'a> {
+    fn len(&self) -> usize { 0 }
+
+    pub fn map<U, F>(&self, f: F) -> Wrapper<U>
+    where
+        F: Fn(&'a str) -> U,
+    {
+        self.names.map(f)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_existing_and_inaccessible_methods() {
+        check_assist_not_applicable(
+            generate_delegate_methods,
+            r#"
+mod inner {
+    pub struct Counter(u32);
+    impl Counter {
+        fn reset(&mut self) {}
+        pub fn get(&self) -> u32 { self.0 }
+        pub fn new() -> Counter { Counter(0) }
+    }
+}
+
+struct Stats { hi$0ts: inner::Counter }
+impl Stats {
+    fn get(&self) -> u32 { 0 }
+}
+"#,
+        );
+    }
+}
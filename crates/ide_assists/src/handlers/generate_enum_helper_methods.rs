@@ -0,0 +1,374 @@
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use stdx::{format_to, to_lower_snake_case};
+use syntax::ast::{self, AstNode, NameOwner, VisibilityOwner};
+
+use crate::{utils::add_method_to_adt, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_enum_helper_methods
+//
+// Generate `is_`, `as_` and `try_into_` methods for the variants of an enum that carry data.
+//
+// ```
+// enum Value$0 {
+//     Number(i32),
+//     Nothing,
+// }
+// ```
+// ->
+// ```
+// enum Value {
+//     Number(i32),
+//     Nothing,
+// }
+//
+// impl Value {
+//     /// Returns `true` if the value is [`Number`].
+//     fn is_number(&self) -> bool {
+//         matches!(self, Self::Number(..))
+//     }
+//
+//     fn as_number(&self) -> Option<&i32> {
+//         if let Self::Number(v) = self {
+//             Some(v)
+//         } else {
+//             None
+//         }
+//     }
+//
+//     fn try_into_number(self) -> Result<i32, Self> {
+//         if let Self::Number(v) = self {
+//             Ok(v)
+//         } else {
+//             Err(self)
+//         }
+//     }
+// }
+// ```
+pub(crate) fn generate_enum_helper_methods(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let enum_ = ctx.find_node_at_offset::<ast::Enum>()?;
+    let variant_list = enum_.variant_list()?;
+    // Only offered on the header of the enum, the variants have their own assists.
+    if variant_list.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    let enum_name = enum_.name()?;
+    let adt = ast::Adt::Enum(enum_.clone());
+    let (impl_def, existing) = inherent_impls(ctx, &adt)?;
+
+    let enum_lowercase_name = to_lower_snake_case(&enum_name.to_string());
+    let vis = enum_.visibility().map_or(String::new(), |v| format!("{} ", v));
+    let mut methods = Vec::new();
+    for variant in variant_list.variants() {
+        let variant_name = variant.name()?;
+        let fields = match Fields::new(&variant) {
+            Some(it) => it,
+            None => continue,
+        };
+        let snake_name = to_lower_snake_case(variant_name.text());
+        let mut push = |doc: String, prefix: &str, signature: String, body: String| {
+            let name = format!("{}_{}", prefix, snake_name);
+            if !existing.contains(&name) {
+                methods.push(format!(
+                    "{}    {}fn {}{} {{\n{}\n    }}",
+                    doc, vis, name, signature, body
+                ));
+            }
+        };
+        push(
+            format!(
+                "    /// Returns `true` if the {} is [`{}`].\n",
+                enum_lowercase_name, variant_name
+            ),
+            "is",
+            "(&self) -> bool".to_string(),
+            format!("        matches!(self, Self::{}{})", variant_name, fields.wildcard),
+        );
+        push(
+            String::new(),
+            "as",
+            format!("(&self) -> Option<{}>", fields.ty("&")),
+            fields.if_let(&variant_name, "Some", "None"),
+        );
+        push(
+            String::new(),
+            "try_into",
+            format!("(self) -> Result<{}, Self>", fields.ty("")),
+            fields.if_let(&variant_name, "Ok", "Err(self)"),
+        );
+    }
+    if methods.is_empty() {
+        return None;
+    }
+
+    let target = enum_.syntax().text_range();
+    acc.add(
+        AssistId("generate_enum_helper_methods", AssistKind::Generate),
+        "Generate `is_`, `as_` and `try_into_` methods",
+        target,
+        |builder| {
+            add_method_to_adt(builder, &adt, impl_def, &methods.join("\n\n"));
+        },
+    )
+}
+
+/// Returns the first inherent impl of `adt` in its module, and the names of the functions
+/// in all of them.
+fn inherent_impls(
+    ctx: &AssistContext,
+    adt: &ast::Adt,
+) -> Option<(Option<ast::Impl>, FxHashSet<String>)> {
+    let db = ctx.db();
+    let module = adt.syntax().ancestors().find(|node| {
+        ast::Module::can_cast(node.kind()) || ast::SourceFile::can_cast(node.kind())
+    })?;
+    let enum_def = match adt {
+        ast::Adt::Enum(it) => hir::Adt::Enum(ctx.sema.to_def(it)?),
+        _ => return None,
+    };
+
+    let impls = module.descendants().filter_map(ast::Impl::cast).filter(|impl_blk| {
+        ctx.sema.to_def(impl_blk).map_or(false, |blk| {
+            blk.target_trait(db).is_none() && blk.target_ty(db).as_adt() == Some(enum_def)
+        })
+    });
+    let mut first = None;
+    let mut names = FxHashSet::default();
+    for impl_blk in impls {
+        let fns = impl_blk.assoc_item_list().into_iter().flat_map(|it| it.assoc_items());
+        for item in fns {
+            if let ast::AssocItem::Fn(f) = item {
+                if let Some(name) = f.name() {
+                    names.insert(name.to_string());
+                }
+            }
+        }
+        first.get_or_insert(impl_blk);
+    }
+    Some((first, names))
+}
+
+/// How the fields of a data-carrying variant are matched and returned.
+struct Fields {
+    /// `(..)` or `{ .. }`.
+    wildcard: &'static str,
+    /// `(v)`, `(v0, v1)` or `{ a, b }`.
+    pattern: String,
+    /// `v` or `(v0, v1)`.
+    value: String,
+    types: Vec<ast::Type>,
+}
+
+impl Fields {
+    fn new(variant: &ast::Variant) -> Option<Fields> {
+        let (wildcard, names, types): (_, Vec<_>, Vec<_>) = match variant.kind() {
+            ast::StructKind::Record(record) => {
+                let fields = record.fields().collect::<Vec<_>>();
+                let names = fields.iter().map(|it| it.name().map(|it| it.to_string()));
+                let types = fields.iter().map(|it| it.ty());
+                (" { .. }", names.collect::<Option<_>>()?, types.collect::<Option<_>>()?)
+            }
+            ast::StructKind::Tuple(tuple) => {
+                let types = tuple.fields().map(|it| it.ty()).collect::<Option<Vec<_>>>()?;
+                let names = match types.len() {
+                    1 => vec!["v".to_string()],
+                    n => (0..n).map(|it| format!("v{}", it)).collect(),
+                };
+                ("(..)", names, types)
+            }
+            ast::StructKind::Unit => return None,
+        };
+        if types.is_empty() {
+            return None;
+        }
+
+        let list = names.iter().join(", ");
+        let pattern =
+            if wildcard == "(..)" { format!("({})", list) } else { format!(" {{ {} }}", list) };
+        let value = if names.len() == 1 { list } else { format!("({})", list) };
+        Some(Fields { wildcard, pattern, value, types })
+    }
+
+    fn if_let(&self, variant_name: &ast::Name, happy_case: &str, sad_case: &str) -> String {
+        format!(
+            "        if let Self::{}{} = self {{
+            {}({})
+        }} else {{
+            {}
+        }}",
+            variant_name, self.pattern, happy_case, self.value, sad_case
+        )
+    }
+
+    fn ty(&self, prefix: &str) -> String {
+        let mut buf = String::new();
+        let types = self.types.iter().map(|it| format!("{}{}", prefix, it)).join(", ");
+        if self.types.len() == 1 {
+            buf.push_str(&types);
+        } else {
+            format_to!(buf, "({})", types);
+        }
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generates_methods_for_data_variants() {
+        check_assist(
+            generate_enum_helper_methods,
+            r#"
+pub enum $0Shape {
+    Point,
+    Circle { radius: f64 },
+    Rect(f64, f64),
+}"#,
+            r#"
+pub enum Shape {
+    Point,
+    Circle { radius: f64 },
+    Rect(f64, f64),
+}
+
+impl Shape {
+    /// Returns `true` if the shape is [`Circle`].
+    pub fn is_circle(&self) -> bool {
+        matches!(self, Self::Circle { .. })
+    }
+
+    pub fn as_circle(&self) -> Option<&f64> {
+        if let Self::Circle { radius } = self {
+            Some(radius)
+        } else {
+            None
+        }
+    }
+
+    pub fn try_into_circle(self) -> Result<f64, Self> {
+        if let Self::Circle { radius } = self {
+            Ok(radius)
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Returns `true` if the shape is [`Rect`].
+    pub fn is_rect(&self) -> bool {
+        matches!(self, Self::Rect(..))
+    }
+
+    pub fn as_rect(&self) -> Option<(&f64, &f64)> {
+        if let Self::Rect(v0, v1) = self {
+            Some((v0, v1))
+        } else {
+            None
+        }
+    }
+
+    pub fn try_into_rect(self) -> Result<(f64, f64), Self> {
+        if let Self::Rect(v0, v1) = self {
+            Ok((v0, v1))
+        } else {
+            Err(self)
+        }
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn skips_existing_methods() {
+        check_assist(
+            generate_enum_helper_methods,
+            r#"
+enum Value$0 {
+    Text(String),
+}
+
+impl Value {
+    fn new() -> Self {
+        Value::Text(String::new())
+    }
+}
+
+impl Value {
+    fn as_text(&self) -> Option<&String> {
+        None
+    }
+}
+
+impl Clone for Value {
+    fn is_text(&self) -> bool {
+        false
+    }
+}"#,
+            r#"
+enum Value {
+    Text(String),
+}
+
+impl Value {
+    fn new() -> Self {
+        Value::Text(String::new())
+    }
+
+    /// Returns `true` if the value is [`Text`].
+    fn is_text(&self) -> bool {
+        matches!(self, Self::Text(..))
+    }
+
+    fn try_into_text(self) -> Result<String, Self> {
+        if let Self::Text(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Value {
+    fn as_text(&self) -> Option<&String> {
+        None
+    }
+}
+
+impl Clone for Value {
+    fn is_text(&self) -> bool {
+        false
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_data_variants() {
+        check_assist_not_applicable(
+            generate_enum_helper_methods,
+            "enum Version$0 { Minor, Major, Empty() }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_all_methods_exist() {
+        check_assist_not_applicable(
+            generate_enum_helper_methods,
+            r#"
+enum Value$0 { Text(String) }
+impl Value {
+    fn is_text(&self) -> bool { true }
+    fn as_text(&self) -> Option<&String> { None }
+    fn try_into_text(self) -> Result<String, Self> { Err(self) }
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_variants() {
+        check_assist_not_applicable(generate_enum_helper_methods, "enum Value { Text(String$0) }");
+    }
+}
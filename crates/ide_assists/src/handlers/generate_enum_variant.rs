@@ -0,0 +1,190 @@
+use hir::{Adt, HasSource, HirDisplay, ModuleDef, PathResolution};
+use itertools::Itertools;
+use syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{utils::append_to_braced_list, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_enum_variant
+//
+// Adds a variant to an enum from a path to it which doesn't resolve, with fields matching the
+// values it's constructed with.
+//
+// ```
+// enum Shape { Circle(f64) }
+//
+// fn main() {
+//     let square = Shape::Square$0(2.0);
+// }
+// ```
+// ->
+// ```
+// enum Shape { Circle(f64), Square(f64) }
+//
+// fn main() {
+//     let square = Shape::Square(2.0);
+// }
+// ```
+pub(crate) fn generate_enum_variant(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let path: ast::Path = ctx.find_node_at_offset()?;
+    if ctx.sema.resolve_path(&path).is_some() {
+        return None;
+    }
+    let name_ref = path.segment()?.name_ref()?;
+    let db = ctx.db();
+    let enum_ = match ctx.sema.resolve_path(&path.qualifier()?)? {
+        PathResolution::Def(ModuleDef::Adt(Adt::Enum(it))) => it,
+        PathResolution::SelfType(impl_) => match impl_.target_ty(db).as_adt()? {
+            Adt::Enum(it) => it,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let current_module = ctx.sema.scope(path.syntax()).module()?;
+    if enum_.module(db).krate() != current_module.krate() {
+        return None;
+    }
+    let src = enum_.source(db)?;
+    if src.file_id.call_node(db).is_some() {
+        return None;
+    }
+    let file_id = src.file_id.original_file(db);
+    let variant_list = src.value.variant_list()?;
+
+    let target_module = enum_.module(db);
+    let render_ty = |expr: Option<ast::Expr>| {
+        expr.and_then(|it| ctx.sema.type_of_expr(&it))
+            .filter(|ty| !ty.is_unknown())
+            .and_then(|ty| ty.display_source_code(db, target_module.into()).ok())
+            .unwrap_or_else(|| "()".to_string())
+    };
+    let parent = path.syntax().parent()?;
+    let fields = if let Some(record) = ast::RecordExpr::cast(parent.clone()) {
+        let fields = record
+            .record_expr_field_list()?
+            .fields()
+            .map(|field| Some(format!("{}: {}", field.field_name()?, render_ty(field.expr()))))
+            .collect::<Option<Vec<_>>>()?;
+        format!(" {{ {} }}", fields.iter().format(", "))
+    } else {
+        let path_expr = ast::PathExpr::cast(parent)?;
+        match path_expr.syntax().parent().and_then(ast::CallExpr::cast) {
+            Some(call) => {
+                let args = call.arg_list()?.args().map(|arg| render_ty(Some(arg)));
+                format!("({})", args.format(", "))
+            }
+            None => String::new(),
+        }
+    };
+    let variant = format!("{}{}", name_ref, fields);
+    let (offset, text) = append_to_braced_list(variant_list.syntax(), &variant)?;
+
+    acc.add(
+        AssistId("generate_enum_variant", AssistKind::Generate),
+        format!("Generate `{}` variant", name_ref),
+        path.syntax().text_range(),
+        |builder| {
+            builder.edit_file(file_id);
+            builder.insert(offset, text);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generates_variant_of_each_kind() {
+        check_assist(
+            generate_enum_variant,
+            r#"
+struct Point;
+enum Event {
+    Click(Point),
+}
+
+impl Event {
+    fn key(code: u32, point: Point) -> Event {
+        Self::Key$0 { code, at: point }
+    }
+}
+"#,
+            r#"
+struct Point;
+enum Event {
+    Click(Point),
+    Key { code: u32, at: Point },
+}
+
+impl Event {
+    fn key(code: u32, point: Point) -> Event {
+        Self::Key { code, at: point }
+    }
+}
+"#,
+        );
+        check_assist(
+            generate_enum_variant,
+            r#"
+enum Event {}
+fn main() {
+    let quit = Event::Quit$0;
+}
+"#,
+            r#"
+enum Event { Quit }
+fn main() {
+    let quit = Event::Quit;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generates_variant_in_other_file() {
+        check_assist(
+            generate_enum_variant,
+            r#"
+//- /main.rs
+mod event;
+fn main() {
+    event::Event::Resize$0(1u16, 2u16);
+}
+//- /event.rs
+pub enum Event {
+    Quit,
+}
+"#,
+            r#"
+pub enum Event {
+    Quit,
+    Resize(u16, u16),
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_existing_variants() {
+        check_assist_not_applicable(
+            generate_enum_variant,
+            r#"
+enum Event { Quit }
+fn main() {
+    let quit = Event::Quit$0;
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_enum_variant,
+            r#"
+mod event {}
+fn main() {
+    let quit = event::Quit$0;
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use hir::{Adt, HasAttrs, ModuleDef, PathResolution, Semantics};
+use ide_db::RootDatabase;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, make, GenericParamsOwner, NameOwner, VisibilityOwner},
+    AstNode, SyntaxKind,
+};
+use test_utils::mark;
+
+use crate::{utils::placeholders_to_tabstops, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_extern_c_wrapper
+//
+// Generates an `extern "C"` wrapper for a public function, passing string slices and
+// slices as a pointer and a length.
+//
+// ```
+// pub fn count_$0words(text: &str, limit: u32) -> u32 {
+//     0
+// }
+// ```
+// ->
+// ```
+// pub fn count_words(text: &str, limit: u32) -> u32 {
+//     0
+// }
+//
+// /// C-compatible wrapper for [`count_words`].
+// ///
+// /// # Safety
+// ///
+// /// `text_ptr` must point to `text_len` bytes of valid UTF-8.
+// #[no_mangle]
+// pub unsafe extern "C" fn $0count_words_ffi(text_ptr: *const u8, text_len: usize, limit: u32) -> u32 {
+//     // SAFETY: `text_ptr` points to `text_len` bytes of valid UTF-8, as required above.
+//     let text = std::str::from_utf8_unchecked(std::slice::from_raw_parts(text_ptr, text_len));
+//     count_words(text, limit)
+// }
+// ```
+pub(crate) fn generate_extern_c_wrapper(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let func = ctx.find_node_at_offset::<ast::Fn>()?;
+    let body = func.body()?;
+    if body.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    // Only free functions have a path which the wrapper can simply call.
+    if !matches!(func.syntax().parent()?.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST) {
+        return None;
+    }
+    if !func.visibility().map_or(false, |it| it.pub_token().is_some())
+        || func.generic_param_list().is_some()
+        || func.async_token().is_some()
+        || func.abi().is_some()
+    {
+        return None;
+    }
+    let name = func.name()?;
+
+    let mut params = Vec::new();
+    let mut conversions = Vec::new();
+    let mut safety = Vec::new();
+    let mut args = Vec::new();
+    for param in func.param_list()?.params() {
+        let pat = match param.pat()? {
+            ast::Pat::IdentPat(it) if it.pat().is_none() => it.name()?,
+            _ => return None,
+        };
+        let ty = param.ty()?;
+        let ptr_len = |elem: &str, mutable: bool| {
+            let ptr = if mutable { "*mut" } else { "*const" };
+            format!("{0}_ptr: {1} {2}, {0}_len: usize", pat, ptr, elem)
+        };
+        match FfiType::of(&ctx.sema, &ty) {
+            FfiType::Safe => params.push(format!("{}: {}", pat, ty)),
+            FfiType::Str { owned } => {
+                params.push(ptr_len("u8", false));
+                safety
+                    .push(format!("`{0}_ptr` must point to `{0}_len` bytes of valid UTF-8.", pat));
+                conversions.push(format!(
+                    "// SAFETY: `{0}_ptr` points to `{0}_len` bytes of valid UTF-8, as required above.\n\
+                     let {0} = std::str::from_utf8_unchecked(std::slice::from_raw_parts({0}_ptr, {0}_len)){1};",
+                    pat,
+                    if owned { ".to_owned()" } else { "" },
+                ));
+            }
+            FfiType::Slice { elem, mutable } => {
+                params.push(ptr_len(&elem.to_string(), mutable));
+                safety.push(format!(
+                    "`{0}_ptr` must point to `{0}_len` initialized elements{1}.",
+                    pat,
+                    if mutable { " which aren't accessed through other pointers" } else { "" },
+                ));
+                conversions.push(format!(
+                    "// SAFETY: `{0}_ptr` points to `{0}_len` elements, as required above.\n\
+                     let {0} = std::slice::{1}({0}_ptr, {0}_len);",
+                    pat,
+                    if mutable { "from_raw_parts_mut" } else { "from_raw_parts" },
+                ));
+            }
+            FfiType::Ambiguous => {
+                mark::hit!(extern_c_wrapper_ambiguous_param);
+                params.push(format!("{}: *const std::ffi::c_void", pat));
+                conversions.push(format!(
+                    "// Convert `{0}` from a C type.\nlet {0}: {1} = {2};",
+                    pat,
+                    ty,
+                    make::expr_todo()
+                ));
+            }
+        }
+        args.push(pat.to_string());
+    }
+
+    let call = format!("{}({})", name, args.iter().format(", "));
+    let (ret, tail) = match func.ret_type().and_then(|it| it.ty()) {
+        None => (String::new(), call),
+        Some(ty) => match FfiType::of(&ctx.sema, &ty) {
+            FfiType::Safe => (format!(" -> {}", ty), call),
+            _ => {
+                let tail = format!(
+                    "let result = {};\n// Return `{}` to C.\n{}",
+                    call,
+                    ty,
+                    make::expr_todo()
+                );
+                (String::new(), tail)
+            }
+        },
+    };
+    if func.unsafe_token().is_some() {
+        safety.push(format!("The safety requirements of [`{}`] must be upheld.", name));
+    }
+    let unsafe_ = if safety.is_empty() { "" } else { "unsafe " };
+
+    let target = func.syntax().text_range();
+    acc.add(
+        AssistId("generate_extern_c_wrapper", AssistKind::Generate),
+        "Generate `extern \"C\"` wrapper",
+        target,
+        |builder| {
+            let mut buf = String::new();
+            format_to!(buf, "/// C-compatible wrapper for [`{}`].\n", name);
+            if !safety.is_empty() {
+                buf.push_str("///\n/// # Safety\n");
+                for it in &safety {
+                    format_to!(buf, "///\n/// {}\n", it);
+                }
+            }
+            format_to!(
+                buf,
+                "#[no_mangle]\npub {}extern \"C\" fn {}_ffi({}){} {{\n",
+                unsafe_,
+                name,
+                params.iter().format(", "),
+                ret
+            );
+            for line in conversions.iter().chain(Some(&tail)).flat_map(|it| it.lines()) {
+                format_to!(buf, "    {}\n", line);
+            }
+            buf.push('}');
+
+            let indent = IndentLevel::from_node(func.syntax());
+            let buf = buf.lines().map(|it| format!("{}{}", indent, it)).join("\n");
+            let buf = format!("\n\n{}", buf);
+            let offset = func.syntax().text_range().end();
+            match ctx.config.snippet_cap {
+                Some(cap) => {
+                    let placeholder = make::expr_todo().to_string();
+                    let buf = if buf.contains(&placeholder) {
+                        placeholders_to_tabstops(cap, &buf, &placeholder)
+                    } else {
+                        buf.replace(&format!("fn {}_ffi", name), &format!("fn $0{}_ffi", name))
+                    };
+                    builder.insert_snippet(cap, offset, buf)
+                }
+                None => builder.insert(offset, buf),
+            }
+        },
+    )
+}
+
+enum FfiType {
+    /// Passed to C as it is.
+    Safe,
+    Str {
+        owned: bool,
+    },
+    Slice {
+        elem: ast::Type,
+        mutable: bool,
+    },
+    Ambiguous,
+}
+
+impl FfiType {
+    fn of(sema: &Semantics<RootDatabase>, ty: &ast::Type) -> FfiType {
+        match ty {
+            ast::Type::RefType(it) => match it.ty() {
+                Some(ast::Type::SliceType(slice)) => match slice.ty() {
+                    Some(elem) => FfiType::Slice { elem, mutable: it.mut_token().is_some() },
+                    None => FfiType::Ambiguous,
+                },
+                Some(ast::Type::PathType(path)) if is_builtin(sema, &path, &["str"]) => {
+                    if it.mut_token().is_some() {
+                        FfiType::Ambiguous
+                    } else {
+                        FfiType::Str { owned: false }
+                    }
+                }
+                Some(ast::Type::DynTraitType(_)) | None => FfiType::Ambiguous,
+                Some(_) => FfiType::Safe,
+            },
+            ast::Type::PtrType(_) | ast::Type::NeverType(_) => FfiType::Safe,
+            ast::Type::FnPtrType(it) if it.abi().is_some() => FfiType::Safe,
+            ast::Type::TupleType(it) if it.fields().next().is_none() => FfiType::Safe,
+            ast::Type::ParenType(it) => {
+                it.ty().map_or(FfiType::Ambiguous, |it| Self::of(sema, &it))
+            }
+            ast::Type::PathType(it) => {
+                let resolution = it.path().and_then(|it| sema.resolve_path(&it));
+                match resolution {
+                    Some(PathResolution::Def(ModuleDef::BuiltinType(_))) => {
+                        if is_builtin(sema, it, &["char", "str", "i128", "u128"]) {
+                            FfiType::Ambiguous
+                        } else {
+                            FfiType::Safe
+                        }
+                    }
+                    Some(PathResolution::Def(ModuleDef::TypeAlias(_))) => FfiType::Safe,
+                    Some(PathResolution::Def(ModuleDef::Adt(adt))) => {
+                        let krate = sema.scope(it.syntax()).module().map(|it| it.krate());
+                        if adt.name(sema.db).to_string() == "String"
+                            && Some(adt.module(sema.db).krate()) != krate
+                        {
+                            FfiType::Str { owned: true }
+                        } else if has_c_repr(sema.db, adt) {
+                            FfiType::Safe
+                        } else {
+                            FfiType::Ambiguous
+                        }
+                    }
+                    _ => FfiType::Ambiguous,
+                }
+            }
+            _ => FfiType::Ambiguous,
+        }
+    }
+}
+
+fn is_builtin(sema: &Semantics<RootDatabase>, ty: &ast::PathType, names: &[&str]) -> bool {
+    match ty.path().and_then(|it| sema.resolve_path(&it)) {
+        Some(PathResolution::Def(ModuleDef::BuiltinType(it))) => {
+            let name = it.name().to_string();
+            names.iter().any(|it| *it == name)
+        }
+        _ => false,
+    }
+}
+
+fn has_c_repr(db: &RootDatabase, adt: Adt) -> bool {
+    adt.attrs(db).by_key("repr").tt_values().any(|tt| {
+        let text = tt.to_string();
+        text.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|repr| repr == "C" || repr == "transparent")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn wraps_strings_and_slices() {
+        check_assist(
+            generate_extern_c_wrapper,
+            r#"
+//- /main.rs crate:main deps:std
+use std::string::String;
+#[repr(C)]
+pub struct Point { x: f64, y: f64 }
+pub fn $0plot(title: String, points: &[Point], out: &mut [u8], origin: &Point) -> bool {
+    true
+}
+//- /std.rs crate:std
+pub mod string {
+    pub struct String;
+}
+"#,
+            r#"
+use std::string::String;
+#[repr(C)]
+pub struct Point { x: f64, y: f64 }
+pub fn plot(title: String, points: &[Point], out: &mut [u8], origin: &Point) -> bool {
+    true
+}
+
+/// C-compatible wrapper for [`plot`].
+///
+/// # Safety
+///
+/// `title_ptr` must point to `title_len` bytes of valid UTF-8.
+///
+/// `points_ptr` must point to `points_len` initialized elements.
+///
+/// `out_ptr` must point to `out_len` initialized elements which aren't accessed through other pointers.
+#[no_mangle]
+pub unsafe extern "C" fn $0plot_ffi(title_ptr: *const u8, title_len: usize, points_ptr: *const Point, points_len: usize, out_ptr: *mut u8, out_len: usize, origin: &Point) -> bool {
+    // SAFETY: `title_ptr` points to `title_len` bytes of valid UTF-8, as required above.
+    let title = std::str::from_utf8_unchecked(std::slice::from_raw_parts(title_ptr, title_len)).to_owned();
+    // SAFETY: `points_ptr` points to `points_len` elements, as required above.
+    let points = std::slice::from_raw_parts(points_ptr, points_len);
+    // SAFETY: `out_ptr` points to `out_len` elements, as required above.
+    let out = std::slice::from_raw_parts_mut(out_ptr, out_len);
+    plot(title, points, out, origin)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn safe_signature_needs_no_unsafe() {
+        check_assist(
+            generate_extern_c_wrapper,
+            r#"
+mod api {
+    pub fn $0add(a: i32, b: i32) -> i32 { a + b }
+}
+"#,
+            r#"
+mod api {
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+    /// C-compatible wrapper for [`add`].
+    #[no_mangle]
+    pub extern "C" fn $0add_ffi(a: i32, b: i32) -> i32 {
+        add(a, b)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ambiguous_types_get_todo() {
+        mark::check!(extern_c_wrapper_ambiguous_param);
+        check_assist(
+            generate_extern_c_wrapper,
+            r#"
+pub struct Config { verbose: bool }
+pub unsafe fn $0run(config: Config, tag: char) -> Config { config }
+"#,
+            r#"
+pub struct Config { verbose: bool }
+pub unsafe fn run(config: Config, tag: char) -> Config { config }
+
+/// C-compatible wrapper for [`run`].
+///
+/// # Safety
+///
+/// The safety requirements of [`run`] must be upheld.
+#[no_mangle]
+pub unsafe extern "C" fn run_ffi(config: *const std::ffi::c_void, tag: *const std::ffi::c_void) {
+    // Convert `config` from a C type.
+    let config: Config = ${1:todo!()};
+    // Convert `tag` from a C type.
+    let tag: char = ${2:todo!()};
+    let result = run(config, tag);
+    // Return `Config` to C.
+    ${0:todo!()}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_extern_c_wrapper, "fn $0private() {}");
+        check_assist_not_applicable(generate_extern_c_wrapper, "pub fn $0generic<T>(t: T) {}");
+        check_assist_not_applicable(
+            generate_extern_c_wrapper,
+            r#"pub extern "C" fn $0already() {}"#,
+        );
+        check_assist_not_applicable(
+            generate_extern_c_wrapper,
+            "struct S; impl S { pub fn $0method(&self) {} }",
+        );
+        check_assist_not_applicable(generate_extern_c_wrapper, "pub fn body() { $0 }");
+    }
+}
@@ -1,9 +1,14 @@
-use ide_db::helpers::FamousDefs;
+use hir::ModuleDef;
+use ide_db::helpers::{mod_path_to_ast, FamousDefs};
 use ide_db::RootDatabase;
-use syntax::ast::{self, AstNode, NameOwner};
+use itertools::Itertools;
+use syntax::ast::{self, AstNode, GenericParamsOwner, NameOwner};
 use test_utils::mark;
 
-use crate::{utils::generate_trait_impl_text, AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    utils::{adt_ty_text, generate_trait_impl_text, generate_trait_impl_text_for},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // Assist: generate_from_impl_for_enum
 //
@@ -26,22 +31,7 @@ pub(crate) fn generate_from_impl_for_enum(acc: &mut Assists, ctx: &AssistContext
     let variant = ctx.find_node_at_offset::<ast::Variant>()?;
     let variant_name = variant.name()?;
     let enum_ = ast::Adt::Enum(variant.parent_enum());
-    let (field_name, field_type) = match variant.kind() {
-        ast::StructKind::Tuple(field_list) => {
-            if field_list.fields().count() != 1 {
-                return None;
-            }
-            (None, field_list.fields().next()?.ty()?)
-        }
-        ast::StructKind::Record(field_list) => {
-            if field_list.fields().count() != 1 {
-                return None;
-            }
-            let field = field_list.fields().next()?;
-            (Some(field.name()?), field.ty()?)
-        }
-        ast::StructKind::Unit => return None,
-    };
+    let (field_name, field_type) = single_field(&variant)?;
 
     if existing_from_impl(&ctx.sema, &variant).is_some() {
         mark::hit!(test_add_from_impl_already_exists);
@@ -80,6 +70,149 @@ pub(crate) fn generate_from_impl_for_enum(acc: &mut Assists, ctx: &AssistContext
     )
 }
 
+// Assist: generate_try_from_impls_for_enum
+//
+// Adds `TryFrom` impls extracting the field of each enum variant with one field.
+//
+// ```
+// enum Value$0 { Int(i64), Text(String) }
+// ```
+// ->
+// ```
+// enum Value { Int(i64), Text(String) }
+//
+// impl TryFrom<Value> for i64 {
+//     type Error = Value;
+//
+//     fn try_from(value: Value) -> Result<Self, Self::Error> {
+//         match value {
+//             Value::Int(v) => Ok(v),
+//             _ => Err(value),
+//         }
+//     }
+// }
+//
+// impl TryFrom<Value> for String {
+//     type Error = Value;
+//
+//     fn try_from(value: Value) -> Result<Self, Self::Error> {
+//         match value {
+//             Value::Text(v) => Ok(v),
+//             _ => Err(value),
+//         }
+//     }
+// }
+// ```
+pub(crate) fn generate_try_from_impls_for_enum(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let enum_ = ctx.find_node_at_offset::<ast::Enum>()?;
+    let variant_list = enum_.variant_list()?;
+    // Only offer the assist on the header of the enum.
+    if ctx.offset() > variant_list.syntax().text_range().start() {
+        return None;
+    }
+    let enum_name = enum_.name()?;
+    let adt = ast::Adt::Enum(enum_.clone());
+    let type_params = enum_
+        .generic_param_list()
+        .into_iter()
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.to_string())
+        .collect::<Vec<_>>();
+
+    let variants = variant_list
+        .variants()
+        .filter_map(|variant| {
+            let (field_name, field_type) = single_field(&variant)?;
+            Some((variant, field_name, field_type))
+        })
+        .collect::<Vec<_>>();
+    // A type wrapped by several variants can't tell which one to extract.
+    let counts = variants.iter().map(|(_, _, ty)| ty.to_string()).counts();
+    let variants = variants
+        .into_iter()
+        .filter(|(variant, _, ty)| {
+            let ty = ty.to_string();
+            counts[&ty] == 1
+                && !type_params.contains(&ty)
+                && existing_try_from_impl(&ctx.sema, variant).is_none()
+        })
+        .collect::<Vec<_>>();
+    if variants.is_empty() {
+        return None;
+    }
+
+    let module = ctx.sema.to_def(&enum_)?.module(ctx.db());
+    let try_from_path = FamousDefs(&ctx.sema, Some(module.krate()))
+        .core_convert_TryFrom()
+        .and_then(|it| module.find_use_path(ctx.db(), ModuleDef::Trait(it)))
+        .map_or_else(|| "TryFrom".to_string(), |it| mod_path_to_ast(&it).to_string());
+
+    let target = enum_name.syntax().text_range();
+    acc.add(
+        AssistId("generate_try_from_impls_for_enum", AssistKind::Generate),
+        "Generate `TryFrom` impls for the variants of this enum",
+        target,
+        |edit| {
+            let enum_ty = adt_ty_text(&adt);
+            let trait_text = format!("{}<{}>", try_from_path, enum_ty);
+            let mut buf = String::new();
+            for (variant, field_name, field_type) in variants {
+                let variant_name = match variant.name() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let pat = match &field_name {
+                    Some(name) => format!("{}::{} {{ {} }}", enum_name, variant_name, name),
+                    None => format!("{}::{}(v)", enum_name, variant_name),
+                };
+                let binding = field_name.map_or("v".to_string(), |it| it.to_string());
+                let code = format!(
+                    r#"    type Error = {0};
+
+    fn try_from(value: {0}) -> Result<Self, Self::Error> {{
+        match value {{
+            {1} => Ok({2}),
+            _ => Err(value),
+        }}
+    }}"#,
+                    enum_ty, pat, binding,
+                );
+                buf.push_str(&generate_trait_impl_text_for(
+                    &adt,
+                    &trait_text,
+                    &field_type.to_string(),
+                    &code,
+                ));
+            }
+            edit.insert(enum_.syntax().text_range().end(), buf);
+        },
+    )
+}
+
+/// The name, if any, and the type of the field of a variant with exactly one field.
+fn single_field(variant: &ast::Variant) -> Option<(Option<ast::Name>, ast::Type)> {
+    match variant.kind() {
+        ast::StructKind::Tuple(field_list) => {
+            if field_list.fields().count() != 1 {
+                return None;
+            }
+            Some((None, field_list.fields().next()?.ty()?))
+        }
+        ast::StructKind::Record(field_list) => {
+            if field_list.fields().count() != 1 {
+                return None;
+            }
+            let field = field_list.fields().next()?;
+            Some((Some(field.name()?), field.ty()?))
+        }
+        ast::StructKind::Unit => None,
+    }
+}
+
 fn existing_from_impl(
     sema: &'_ hir::Semantics<'_, RootDatabase>,
     variant: &ast::Variant,
@@ -101,6 +234,27 @@ fn existing_from_impl(
     }
 }
 
+fn existing_try_from_impl(
+    sema: &'_ hir::Semantics<'_, RootDatabase>,
+    variant: &ast::Variant,
+) -> Option<()> {
+    let variant = sema.to_def(variant)?;
+    let enum_ = variant.parent_enum(sema.db);
+    let krate = enum_.module(sema.db).krate();
+
+    let try_from_trait = FamousDefs(sema, Some(krate)).core_convert_TryFrom()?;
+
+    let enum_type = enum_.ty(sema.db);
+
+    let wrapped_type = variant.fields(sema.db).get(0)?.signature_ty(sema.db);
+
+    if wrapped_type.impls_trait(sema.db, try_from_trait, &[enum_type]) {
+        Some(())
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::mark;
@@ -265,4 +419,80 @@ impl<'a> From<&'a i32> for Generic<'a> {
 }"#,
         );
     }
+
+    #[test]
+    fn test_generate_try_from_impls_for_enum() {
+        check_assist(
+            generate_try_from_impls_for_enum,
+            r#"
+//- /main.rs crate:main deps:core
+enum Token<'a>$0 {
+    Ident { name: &'a str },
+    Int(i64),
+    Float(f64),
+    Eof,
+}
+
+impl core::convert::TryFrom<Token<'_>> for f64 {
+    type Error = ();
+    fn try_from(_: Token<'_>) -> Result<f64, ()> { loop {} }
+}
+//- /core.rs crate:core
+pub mod convert {
+    pub trait TryFrom<T>: Sized {
+        type Error;
+        fn try_from(value: T) -> Result<Self, Self::Error>;
+    }
+}
+"#,
+            r#"
+enum Token<'a> {
+    Ident { name: &'a str },
+    Int(i64),
+    Float(f64),
+    Eof,
+}
+
+impl<'a> core::convert::TryFrom<Token<'a>> for &'a str {
+    type Error = Token<'a>;
+
+    fn try_from(value: Token<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Token::Ident { name } => Ok(name),
+            _ => Err(value),
+        }
+    }
+}
+
+impl<'a> core::convert::TryFrom<Token<'a>> for i64 {
+    type Error = Token<'a>;
+
+    fn try_from(value: Token<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Token::Int(v) => Ok(v),
+            _ => Err(value),
+        }
+    }
+}
+
+impl core::convert::TryFrom<Token<'_>> for f64 {
+    type Error = ();
+    fn try_from(_: Token<'_>) -> Result<f64, ()> { loop {} }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_generate_try_from_impls_not_applicable() {
+        // Variants wrapping the same type, or a type parameter.
+        check_assist_not_applicable(
+            generate_try_from_impls_for_enum,
+            "enum Either<T>$0 { Left(u8), Right(u8), Other(T) }",
+        );
+        check_assist_not_applicable(
+            generate_try_from_impls_for_enum,
+            "enum Either<T> { Left(u$08), Right(T) }",
+        );
+    }
 }
@@ -1,7 +1,11 @@
-use hir::HirDisplay;
-use ide_db::{base_db::FileId, helpers::SnippetCap};
+use hir::{HasSource, HirDisplay, InFile};
+use ide_db::{
+    base_db::FileId,
+    helpers::{expected_type, SnippetCap},
+};
 use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::{
+    algo,
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
@@ -11,7 +15,7 @@ use syntax::{
 };
 
 use crate::{
-    utils::{render_snippet, Cursor},
+    utils::{find_struct_impl, generate_impl_text, render_snippet, Cursor},
     AssistContext, AssistId, AssistKind, Assists,
 };
 
@@ -77,12 +81,81 @@ pub(crate) fn generate_function(acc: &mut Assists, ctx: &AssistContext) -> Optio
     )
 }
 
+// Assist: generate_method
+//
+// Adds a stub method with a signature matching the method call under the cursor to the type of
+// the receiver.
+//
+// ```
+// struct Counter { count: u32 }
+// fn reset(counter: &mut Counter) {
+//     let previous: u32 = counter.take$0(0);
+// }
+// ```
+// ->
+// ```
+// struct Counter { count: u32 }
+//
+// impl Counter {
+//     fn take(&self, arg: i32) -> u32 {
+//         ${0:todo!()}
+//     }
+// }
+// fn reset(counter: &mut Counter) {
+//     let previous: u32 = counter.take(0);
+// }
+// ```
+pub(crate) fn generate_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name_ref: ast::NameRef = ctx.find_node_at_offset()?;
+    let call = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+    if ctx.sema.resolve_method_call(&call).is_some() {
+        return None;
+    }
+    let db = ctx.db();
+    let mut receiver_ty = ctx.sema.type_of_expr(&call.receiver()?)?;
+    while let Some(ty) = receiver_ty.remove_ref() {
+        receiver_ty = ty;
+    }
+    let adt = receiver_ty.as_adt()?;
+    let current_module = ctx.sema.scope(call.syntax()).module()?;
+    if adt.module(db).krate() != current_module.krate() {
+        return None;
+    }
+    let src = match adt {
+        hir::Adt::Struct(it) => it.source(db)?.map(ast::Adt::Struct),
+        hir::Adt::Enum(it) => it.source(db)?.map(ast::Adt::Enum),
+        hir::Adt::Union(it) => it.source(db)?.map(ast::Adt::Union),
+    };
+
+    let function_builder = FunctionBuilder::from_method_call(ctx, &call, &name_ref, src, adt)?;
+
+    let target = call.syntax().text_range();
+    acc.add(
+        AssistId("generate_method", AssistKind::Generate),
+        format!("Generate `{}` method", function_builder.fn_name),
+        target,
+        |builder| {
+            let function_template = function_builder.render();
+            builder.edit_file(function_template.file);
+            let new_fn = function_template.to_string(ctx.config.snippet_cap);
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, function_template.insert_offset, new_fn),
+                None => builder.insert(function_template.insert_offset, new_fn),
+            }
+        },
+    )
+}
+
 struct FunctionTemplate {
     insert_offset: TextSize,
     leading_ws: String,
     fn_def: ast::Fn,
-    ret_type: ast::RetType,
+    /// The node the cursor replaces: the return type, unless it was inferred from the call site,
+    /// and the body otherwise.
+    placeholder: SyntaxNode,
     trailing_ws: String,
+    /// The type the function is generated for, when it needs a new `impl` block.
+    new_impl_for: Option<ast::Adt>,
     file: FileId,
 }
 
@@ -90,11 +163,15 @@ impl FunctionTemplate {
     fn to_string(&self, cap: Option<SnippetCap>) -> String {
         let f = match cap {
             Some(cap) => {
-                render_snippet(cap, self.fn_def.syntax(), Cursor::Replace(self.ret_type.syntax()))
+                render_snippet(cap, self.fn_def.syntax(), Cursor::Replace(&self.placeholder))
             }
             None => self.fn_def.to_string(),
         };
-        format!("{}{}{}", self.leading_ws, f, self.trailing_ws)
+        let f = format!("{}{}{}", self.leading_ws, f, self.trailing_ws);
+        match &self.new_impl_for {
+            Some(adt) => generate_impl_text(adt, &f),
+            None => f,
+        }
     }
 }
 
@@ -103,6 +180,7 @@ struct FunctionBuilder {
     fn_name: ast::Name,
     type_params: Option<ast::GenericParamList>,
     params: ast::ParamList,
+    ret_type: Option<ast::RetType>,
     file: FileId,
     needs_pub: bool,
 }
@@ -129,25 +207,68 @@ impl FunctionBuilder {
         let needs_pub = target_module.is_some();
         let target_module = target_module.or_else(|| ctx.sema.scope(target.syntax()).module())?;
         let fn_name = fn_name(&path)?;
-        let (type_params, params) = fn_args(ctx, target_module, &call)?;
+        let (type_params, params) = fn_args(ctx, target_module, None, call.arg_list()?)?;
+        let ret_type = fn_ret_type(ctx, target_module, &ast::Expr::CallExpr(call.clone()));
+
+        Some(Self { target, fn_name, type_params, params, ret_type, file, needs_pub })
+    }
 
-        Some(Self { target, fn_name, type_params, params, file, needs_pub })
+    /// Prepares a generated method that matches `call`, in an inherent impl of `adt`.
+    fn from_method_call(
+        ctx: &AssistContext,
+        call: &ast::MethodCallExpr,
+        name_ref: &ast::NameRef,
+        adt_source: InFile<ast::Adt>,
+        adt: hir::Adt,
+    ) -> Option<Self> {
+        if adt_source.file_id.call_node(ctx.db()).is_some() {
+            return None;
+        }
+        let file = adt_source.file_id.original_file(ctx.db());
+        let adt_node = algo::find_node_at_range::<ast::Adt>(
+            ctx.sema.parse(file).syntax(),
+            adt_source.value.syntax().text_range(),
+        )?;
+        let fn_name = make::name(&name_ref.text());
+        let target = match find_struct_impl(ctx, &adt_node, &fn_name.text())? {
+            Some(impl_) => {
+                let item_list = impl_.assoc_item_list()?;
+                match item_list.assoc_items().last() {
+                    Some(last_item) => {
+                        GeneratedFunctionTarget::BehindItem(last_item.syntax().clone())
+                    }
+                    None => GeneratedFunctionTarget::InEmptyItemList(item_list.syntax().clone()),
+                }
+            }
+            None => GeneratedFunctionTarget::InNewImpl(adt_node),
+        };
+        let target_module = adt.module(ctx.db());
+        let needs_pub = ctx.sema.scope(call.syntax()).module()? != target_module;
+        let self_param = make::self_param_ref();
+        let (type_params, params) =
+            fn_args(ctx, target_module, Some(self_param), call.arg_list()?)?;
+        let ret_type = fn_ret_type(ctx, target_module, &ast::Expr::MethodCallExpr(call.clone()));
+
+        Some(Self { target, fn_name, type_params, params, ret_type, file, needs_pub })
     }
 
     fn render(self) -> FunctionTemplate {
         let placeholder_expr = make::expr_todo();
         let fn_body = make::block_expr(vec![], Some(placeholder_expr));
         let visibility = if self.needs_pub { Some(make::visibility_pub_crate()) } else { None };
+        let focus_body = self.ret_type.is_some();
+        let ret_type = self.ret_type.unwrap_or_else(|| make::ret_type(make::ty_unit()));
         let mut fn_def = make::fn_(
             visibility,
             self.fn_name,
             self.type_params,
             self.params,
             fn_body,
-            Some(make::ret_type(make::ty_unit())),
+            Some(ret_type),
         );
         let leading_ws;
         let trailing_ws;
+        let mut new_impl_for = None;
 
         let insert_offset = match self.target {
             GeneratedFunctionTarget::BehindItem(it) => {
@@ -164,14 +285,29 @@ impl FunctionBuilder {
                 trailing_ws = format!("\n{}", indent);
                 it.text_range().start() + TextSize::of('{')
             }
+            GeneratedFunctionTarget::InNewImpl(adt) => {
+                let indent = IndentLevel(1);
+                leading_ws = indent.to_string();
+                fn_def = fn_def.indent(indent);
+                trailing_ws = String::new();
+                let offset = adt.syntax().text_range().end();
+                new_impl_for = Some(adt);
+                offset
+            }
         };
 
+        let placeholder = if focus_body {
+            fn_def.body().and_then(|it| it.tail_expr()).unwrap().syntax().clone()
+        } else {
+            fn_def.ret_type().unwrap().syntax().clone()
+        };
         FunctionTemplate {
             insert_offset,
             leading_ws,
-            ret_type: fn_def.ret_type().unwrap(),
+            placeholder,
             fn_def,
             trailing_ws,
+            new_impl_for,
             file: self.file,
         }
     }
@@ -180,6 +316,7 @@ impl FunctionBuilder {
 enum GeneratedFunctionTarget {
     BehindItem(SyntaxNode),
     InEmptyItemList(SyntaxNode),
+    InNewImpl(ast::Adt),
 }
 
 impl GeneratedFunctionTarget {
@@ -187,6 +324,7 @@ impl GeneratedFunctionTarget {
         match self {
             GeneratedFunctionTarget::BehindItem(it) => it,
             GeneratedFunctionTarget::InEmptyItemList(it) => it,
+            GeneratedFunctionTarget::InNewImpl(it) => it.syntax(),
         }
     }
 }
@@ -200,11 +338,12 @@ fn fn_name(call: &ast::Path) -> Option<ast::Name> {
 fn fn_args(
     ctx: &AssistContext,
     target_module: hir::Module,
-    call: &ast::CallExpr,
+    self_param: Option<ast::SelfParam>,
+    arg_list: ast::ArgList,
 ) -> Option<(Option<ast::GenericParamList>, ast::ParamList)> {
     let mut arg_names = Vec::new();
     let mut arg_types = Vec::new();
-    for arg in call.arg_list()?.args() {
+    for arg in arg_list.args() {
         arg_names.push(match fn_arg_name(&arg) {
             Some(name) => name,
             None => String::from("arg"),
@@ -219,7 +358,21 @@ fn fn_args(
         .into_iter()
         .zip(arg_types)
         .map(|(name, ty)| make::param(make::ident_pat(make::name(&name)).into(), make::ty(&ty)));
-    Some((None, make::param_list(None, params)))
+    Some((None, make::param_list(self_param, params)))
+}
+
+/// The return type the call site expects the generated function to have, if it can tell.
+fn fn_ret_type(
+    ctx: &AssistContext,
+    target_module: hir::Module,
+    call: &ast::Expr,
+) -> Option<ast::RetType> {
+    let ty = expected_type(&ctx.sema, call)?;
+    if ty.is_unknown() || ty.is_unit() || ty.is_never() {
+        return None;
+    }
+    let rendered = ty.display_source_code(ctx.db(), target_module.into()).ok()?;
+    Some(make::ret_type(make::ty(&rendered)))
 }
 
 /// Makes duplicate argument names unique by appending incrementing numbers.
@@ -498,8 +651,8 @@ impl Baz {
     }
 }
 
-fn bar(baz: Baz) ${0:-> ()} {
-    todo!()
+fn bar(baz: Baz) -> Baz {
+    ${0:todo!()}
 }
 ",
         )
@@ -1043,29 +1196,162 @@ fn bar(baz: ()) {}
     }
 
     #[test]
-    #[ignore]
-    fn create_method_with_no_args() {
+    fn infers_return_type_from_call_site() {
+        check_assist(
+            generate_function,
+            r"
+struct Config;
+struct Text;
+fn load(path: &str) -> Config {
+    let contents: Text = read$0(path);
+    parse(contents)
+}
+fn parse(contents: Text) -> Config { Config }
+",
+            r"
+struct Config;
+struct Text;
+fn load(path: &str) -> Config {
+    let contents: Text = read(path);
+    parse(contents)
+}
+
+fn read(path: &str) -> Text {
+    ${0:todo!()}
+}
+fn parse(contents: Text) -> Config { Config }
+",
+        );
         check_assist(
             generate_function,
             r"
+struct Config;
+fn load(path: &str) -> Config {
+    parse$0(path)
+}
+",
+            r"
+struct Config;
+fn load(path: &str) -> Config {
+    parse(path)
+}
+
+fn parse(path: &str) -> Config {
+    ${0:todo!()}
+}
+",
+        );
+    }
+
+    #[test]
+    fn infers_return_type_of_closure() {
+        check_assist(
+            generate_function,
+            r"
+struct Config;
+struct Text;
+fn load() -> Config {
+    let read = || -> Text { return read_file$0(); };
+    Config
+}
+",
+            r"
+struct Config;
+struct Text;
+fn load() -> Config {
+    let read = || -> Text { return read_file(); };
+    Config
+}
+
+fn read_file() -> Text {
+    ${0:todo!()}
+}
+",
+        );
+    }
+
+    #[test]
+    fn create_method_with_no_args() {
+        check_assist(
+            generate_method,
+            r"
 struct Foo;
 impl Foo {
     fn foo(&self) {
-        self.bar()$0;
+        self.bar$0();
     }
 }
-        ",
+",
             r"
 struct Foo;
 impl Foo {
     fn foo(&self) {
         self.bar();
     }
-    fn bar(&self) {
-        todo!();
+
+    fn bar(&self) ${0:-> ()} {
+        todo!()
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn create_method_in_new_impl() {
+        check_assist(
+            generate_method,
+            r"
+mod shapes {
+    pub struct Rect { pub w: u32, pub h: u32 }
+}
+fn main() {
+    let rect = shapes::Rect { w: 1, h: 2 };
+    let scale = 2;
+    let area: u64 = rect.scaled_area$0(scale, &rect);
+}
+",
+            r"
+mod shapes {
+    pub struct Rect { pub w: u32, pub h: u32 }
+
+impl Rect {
+    pub(crate) fn scaled_area(&self, scale: i32, rect: &Rect) -> u64 {
+        ${0:todo!()}
     }
 }
-        ",
+}
+fn main() {
+    let rect = shapes::Rect { w: 1, h: 2 };
+    let scale = 2;
+    let area: u64 = rect.scaled_area(scale, &rect);
+}
+",
         )
     }
+
+    #[test]
+    fn create_method_not_applicable() {
+        check_assist_not_applicable(
+            generate_method,
+            r"
+struct Foo;
+impl Foo {
+    fn bar(&self) {}
+}
+fn main() {
+    Foo.bar$0();
+}
+",
+        );
+        check_assist_not_applicable(
+            generate_method,
+            r"
+fn main() {
+    let n = 1u32;
+    n.frobnicate$0();
+}
+",
+        );
+    }
 }
@@ -0,0 +1,233 @@
+use hir::{Adt, HasSource};
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, GenericParamsOwner, VisibilityOwner},
+    TextRange,
+};
+use test_utils::mark;
+
+use crate::{
+    utils::{find_impl_block_start, find_struct_impl},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_new_from_default
+//
+// Generates a `new` function delegating to the `Default` impl of a type.
+//
+// ```
+// pub struct Config { verbose: bool }
+//
+// impl Def$0ault for Config {
+//     fn default() -> Self {
+//         Config { verbose: false }
+//     }
+// }
+// ```
+// ->
+// ```
+// pub struct Config { verbose: bool }
+//
+// impl Default for Config {
+//     fn default() -> Self {
+//         Config { verbose: false }
+//     }
+// }
+//
+// impl Config {
+//     pub fn new() -> Self {
+//         Self::default()
+//     }
+// }
+// ```
+pub(crate) fn generate_new_from_default(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_ = ctx.find_node_at_offset::<ast::Impl>()?;
+    let trait_name = match impl_.trait_()? {
+        ast::Type::PathType(it) => it.path()?.segment()?.name_ref()?,
+        _ => return None,
+    };
+    if trait_name.text() != "Default" {
+        return None;
+    }
+    let items = impl_.assoc_item_list()?;
+    let header =
+        TextRange::new(impl_.syntax().text_range().start(), items.syntax().text_range().start());
+    if !header.contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    let self_ty = impl_.self_ty()?;
+
+    let adt = ctx.sema.to_def(&impl_)?.target_ty(ctx.db()).as_adt()?;
+    let source = match adt {
+        Adt::Struct(it) => it.source(ctx.db())?.map(ast::Adt::Struct),
+        Adt::Enum(it) => it.source(ctx.db())?.map(ast::Adt::Enum),
+        Adt::Union(it) => it.source(ctx.db())?.map(ast::Adt::Union),
+    };
+    if source.file_id != ctx.frange.file_id.into() {
+        return None;
+    }
+    let inherent_impl = match find_struct_impl(ctx, &source.value, "new") {
+        Some(it) => it,
+        None => {
+            mark::hit!(new_from_default_new_exists);
+            return None;
+        }
+    };
+    // An existing impl block may lack the bounds the `Default` impl relies on.
+    let inherent_impl = inherent_impl.filter(|_| impl_.generic_param_list().is_none());
+
+    let target = impl_.syntax().text_range();
+    acc.add(
+        AssistId("generate_new_from_default", AssistKind::Generate),
+        "Generate a new fn from the Default impl",
+        target,
+        |builder| {
+            let vis = source.value.visibility().map_or(String::new(), |v| format!("{} ", v));
+            match inherent_impl {
+                Some(inherent_impl) => {
+                    let indent = IndentLevel::from_node(inherent_impl.syntax()) + 1;
+                    let mut buf = String::new();
+                    format_to!(
+                        buf,
+                        "\n{0}{1}fn new() -> Self {{\n{0}    Self::default()\n{0}}}",
+                        indent,
+                        vis
+                    );
+                    if let Some(offset) = find_impl_block_start(inherent_impl, &mut buf) {
+                        builder.insert(offset, buf);
+                    }
+                }
+                None => {
+                    let indent = IndentLevel::from_node(impl_.syntax());
+                    let mut buf = String::from("\n\n");
+                    format_to!(buf, "{}impl", indent);
+                    if let Some(generic_params) = impl_.generic_param_list() {
+                        format_to!(buf, "{}", generic_params);
+                    }
+                    format_to!(buf, " {}", self_ty);
+                    if let Some(where_clause) = impl_.where_clause() {
+                        format_to!(buf, " {}", where_clause);
+                    }
+                    format_to!(
+                        buf,
+                        " {{\n{0}    {1}fn new() -> Self {{\n{0}        Self::default()\n{0}    }}\n{0}}}",
+                        indent,
+                        vis
+                    );
+                    builder.insert(impl_.syntax().text_range().end(), buf);
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn adds_new_to_existing_impl() {
+        check_assist(
+            generate_new_from_default,
+            r#"
+struct Counter { count: u32 }
+
+impl Counter {
+    fn bump(&mut self) { self.count += 1 }
+}
+
+impl $0Default for Counter {
+    fn default() -> Self { Counter { count: 0 } }
+}
+"#,
+            r#"
+struct Counter { count: u32 }
+
+impl Counter {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn bump(&mut self) { self.count += 1 }
+}
+
+impl Default for Counter {
+    fn default() -> Self { Counter { count: 0 } }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn copies_generics_of_default_impl() {
+        check_assist(
+            generate_new_from_default,
+            r#"
+pub(crate) struct Wrapper<T> { value: T }
+
+impl<T> Wrapper<T> {}
+
+impl<T: Default> Default for $0Wrapper<T> {
+    fn default() -> Self { Wrapper { value: T::default() } }
+}
+"#,
+            r#"
+pub(crate) struct Wrapper<T> { value: T }
+
+impl<T> Wrapper<T> {}
+
+impl<T: Default> Default for Wrapper<T> {
+    fn default() -> Self { Wrapper { value: T::default() } }
+}
+
+impl<T: Default> Wrapper<T> {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_new_exists() {
+        mark::check!(new_from_default_new_exists);
+        check_assist_not_applicable(
+            generate_new_from_default,
+            r#"
+struct S;
+impl S {
+    fn new() -> Self { S }
+}
+impl Default$0 for S {
+    fn default() -> Self { S }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body_or_other_traits() {
+        check_assist_not_applicable(
+            generate_new_from_default,
+            r#"
+struct S;
+impl Default for S {
+    fn default() -> Self { $0S }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_new_from_default,
+            r#"
+trait Make { fn make() -> Self; }
+struct S;
+impl Make$0 for S {
+    fn make() -> Self { S }
+}
+"#,
+        );
+    }
+}
@@ -1,8 +1,17 @@
-use ast::make;
-use hir::{HasSource, PathResolution};
+use hir::{AsAssocItem, AssocItemContainer, HasSource, PathResolution};
+use ide_db::{
+    base_db::FileId,
+    defs::Definition,
+    search::{FileReference, SearchScope},
+};
+use stdx::format_to;
 use syntax::{
-    ast::{self, edit::AstNodeEdit, ArgListOwner},
-    AstNode,
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        ArgListOwner, NameOwner, SelfParamKind,
+    },
+    AstNode, SourceFile, SyntaxKind, WalkEvent,
 };
 use test_utils::mark;
 
@@ -13,7 +22,7 @@ use crate::{
 
 // Assist: inline_function
 //
-// Inlines a function body.
+// Inlines a function or method body at the call under the cursor.
 //
 // ```
 // fn add(a: u32, b: u32) -> u32 { a + b }
@@ -25,70 +34,515 @@ use crate::{
 // ```
 // fn add(a: u32, b: u32) -> u32 { a + b }
 // fn main() {
-//     let x = {
-//         let a = 1;
-//         let b = 2;
-//         a + b
-//     };
+//     let x = 1 + 2;
 // }
 // ```
 pub(crate) fn inline_function(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
-    let path_expr: ast::PathExpr = ctx.find_node_at_offset()?;
-    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
-    let path = path_expr.path()?;
-
-    let function = match ctx.sema.resolve_path(&path)? {
-        PathResolution::Def(hir::ModuleDef::Function(f)) => f,
-        _ => return None,
-    };
-
-    let function_source = function.source(ctx.db())?;
-    let arguments: Vec<_> = call.arg_list()?.args().collect();
-    let parameters = function_parameter_patterns(&function_source.value)?;
+    let name_ref: ast::NameRef = ctx.find_node_at_offset()?;
+    let call = CallSite::from_name_ref(&name_ref)?;
+    let function = call.resolve(ctx)?;
+    let fn_ = InlinableFn::new(ctx, function)?;
 
-    if arguments.len() != parameters.len() {
+    if !fn_.accepts(&call) {
         // Can't inline the function because they've passed the wrong number of
         // arguments to this function
         mark::hit!(inline_function_incorrect_number_of_arguments);
         return None;
     }
 
-    let new_bindings = parameters.into_iter().zip(arguments);
-
-    let body = function_source.value.body()?;
-
     acc.add(
         AssistId("inline_function", AssistKind::RefactorInline),
-        format!("Inline `{}`", path),
+        format!("Inline `{}`", function.name(ctx.db())),
         call.syntax().text_range(),
-        |builder| {
-            let mut statements: Vec<ast::Stmt> = Vec::new();
-
-            for (pattern, value) in new_bindings {
-                statements.push(make::let_stmt(pattern, Some(value)).into());
-            }
+        |builder| builder.replace(call.syntax().text_range(), fn_.inline_into(ctx, &call)),
+    )
+}
 
-            statements.extend(body.statements());
+// Assist: inline_into_callers
+//
+// Inlines a function body at all of its call sites, removing the function when nothing else
+// refers to it.
+//
+// ```
+// fn $0double(x: u32) -> u32 { x * 2 }
+// fn main() {
+//     let a = double(1);
+//     let b = double(a);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let a = 1 * 2;
+//     let b = a * 2;
+// }
+// ```
+pub(crate) fn inline_into_callers(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name: ast::Name = ctx.find_node_at_offset()?;
+    let func = name.syntax().parent().and_then(ast::Fn::cast)?;
+    let function = ctx.sema.to_def(&func)?;
+    let fn_ = InlinableFn::new(ctx, function)?;
 
-            let original_indentation = call.indent_level();
-            let replacement = make::block_expr(statements, body.tail_expr())
-                .reset_indent()
-                .indent(original_indentation);
+    let usages = Definition::ModuleDef(function.into()).usages(&ctx.sema).all();
+    if usages.is_empty() {
+        return None;
+    }
+    let fn_range = func.syntax().text_range();
+    let fn_module = function.module(ctx.db());
+    let is_self_contained = fn_.is_self_contained(ctx);
+    let mut calls: Vec<(FileId, Vec<CallSite>)> = Vec::new();
+    let mut inlines_all = true;
+    for (&file_id, refs) in usages.iter() {
+        let mut sites: Vec<CallSite> = Vec::new();
+        for FileReference { range, name, .. } in refs {
+            if file_id == ctx.frange.file_id && fn_range.contains_range(*range) {
+                mark::hit!(inline_into_callers_recursive);
+                return None;
+            }
+            let call = match name {
+                ast::NameLike::NameRef(name_ref) => CallSite::from_name_ref(name_ref),
+                _ => None,
+            };
+            match call.filter(|it| fn_.accepts(it)) {
+                Some(call)
+                    if is_self_contained
+                        || ctx.sema.scope(call.syntax()).module() == Some(fn_module) =>
+                {
+                    sites.push(call)
+                }
+                Some(_) => {
+                    // The paths of the body aren't requalified for other modules.
+                    mark::hit!(inline_into_callers_other_module);
+                    inlines_all = false
+                }
+                None => inlines_all = false,
+            }
+        }
+        // Calls nested in the arguments of another call are left for a second pass.
+        sites.sort_by_key(|it| it.syntax().text_range().start());
+        let mut outer: Vec<CallSite> = Vec::new();
+        for site in sites {
+            match outer.last() {
+                Some(last)
+                    if last.syntax().text_range().contains_range(site.syntax().text_range()) =>
+                {
+                    inlines_all = false
+                }
+                _ => outer.push(site),
+            }
+        }
+        calls.push((file_id, outer));
+    }
+    if calls.iter().all(|(_, sites)| sites.is_empty()) {
+        return None;
+    }
 
-            builder.replace_ast(ast::Expr::CallExpr(call), ast::Expr::BlockExpr(replacement));
+    acc.add(
+        AssistId("inline_into_callers", AssistKind::RefactorInline),
+        "Inline into all callers",
+        name.syntax().text_range(),
+        |builder| {
+            for (file_id, sites) in calls {
+                builder.edit_file(file_id);
+                for call in sites {
+                    builder.replace(call.syntax().text_range(), fn_.inline_into(ctx, &call));
+                }
+            }
+            if inlines_all {
+                builder.edit_file(ctx.frange.file_id);
+                let ws = func
+                    .syntax()
+                    .prev_sibling_or_token()
+                    .or_else(|| func.syntax().next_sibling_or_token())
+                    .filter(|it| it.kind() == SyntaxKind::WHITESPACE);
+                let range = ws.map_or(fn_range, |ws| fn_range.cover(ws.text_range()));
+                builder.delete(range);
+            }
         },
     )
 }
 
-fn function_parameter_patterns(value: &ast::Fn) -> Option<Vec<ast::Pat>> {
-    let mut patterns = Vec::new();
+enum CallSite {
+    Call(ast::CallExpr),
+    MethodCall(ast::MethodCallExpr),
+}
+
+impl CallSite {
+    fn from_name_ref(name_ref: &ast::NameRef) -> Option<CallSite> {
+        if let Some(call) = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+            return Some(CallSite::MethodCall(call));
+        }
+        let path = name_ref.syntax().parent()?.parent().and_then(ast::Path::cast)?;
+        let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+        let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+        if call.expr()?.syntax() != path_expr.syntax() {
+            return None;
+        }
+        Some(CallSite::Call(call))
+    }
+
+    fn resolve(&self, ctx: &AssistContext) -> Option<hir::Function> {
+        match self {
+            CallSite::Call(call) => match call.expr()? {
+                ast::Expr::PathExpr(path_expr) => {
+                    match ctx.sema.resolve_path(&path_expr.path()?)? {
+                        PathResolution::Def(hir::ModuleDef::Function(f))
+                        | PathResolution::AssocItem(hir::AssocItem::Function(f)) => Some(f),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            CallSite::MethodCall(call) => ctx.sema.resolve_method_call(call),
+        }
+    }
+
+    fn syntax(&self) -> &syntax::SyntaxNode {
+        match self {
+            CallSite::Call(it) => it.syntax(),
+            CallSite::MethodCall(it) => it.syntax(),
+        }
+    }
+
+    /// The arguments of the call, starting with the receiver of a method call.
+    fn args(&self) -> Vec<ast::Expr> {
+        let (receiver, arg_list) = match self {
+            CallSite::Call(call) => (None, call.arg_list()),
+            CallSite::MethodCall(call) => (call.receiver(), call.arg_list()),
+        };
+        receiver.into_iter().chain(arg_list.into_iter().flat_map(|it| it.args())).collect()
+    }
+}
+
+struct InlinableFn {
+    file_id: FileId,
+    fn_: ast::Fn,
+    body: ast::BlockExpr,
+    self_param: Option<(ast::SelfParam, hir::Local)>,
+    params: Vec<(ast::Pat, Option<hir::Local>)>,
+    impl_: Option<(hir::Impl, ast::Type)>,
+}
+
+impl InlinableFn {
+    fn new(ctx: &AssistContext, function: hir::Function) -> Option<InlinableFn> {
+        let db = ctx.db();
+        if let Some(assoc) = function.as_assoc_item(db) {
+            // The implementation actually called may not be the one we see.
+            if let AssocItemContainer::Trait(_) = assoc.container(db) {
+                return None;
+            }
+        }
+        let source = function.source(db)?;
+        if source.file_id.call_node(db).is_some() {
+            return None;
+        }
+        let file_id = source.file_id.original_file(db);
+        let fn_ = ctx
+            .sema
+            .parse(file_id)
+            .syntax()
+            .descendants()
+            .filter_map(ast::Fn::cast)
+            .find(|it| it.syntax().text_range() == source.value.syntax().text_range())?;
+        let body = fn_.body()?;
+        if has_early_exit(&body) {
+            mark::hit!(inline_function_early_exit);
+            return None;
+        }
+
+        let param_list = fn_.param_list()?;
+        let self_param = match param_list.self_param() {
+            Some(self_param) => Some((self_param.clone(), ctx.sema.to_def(&self_param)?)),
+            None => None,
+        };
+        let params = param_list
+            .params()
+            .map(|param| {
+                let pat = param.pat()?;
+                let local = match &pat {
+                    ast::Pat::IdentPat(it) => ctx.sema.to_def(it),
+                    _ => None,
+                };
+                Some((pat, local))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let impl_ = match fn_.syntax().parent()?.parent().and_then(ast::Impl::cast) {
+            Some(impl_) => Some((ctx.sema.to_def(&impl_)?, impl_.self_ty()?)),
+            None => None,
+        };
+        Some(InlinableFn { file_id, fn_, body, self_param, params, impl_ })
+    }
+
+    fn accepts(&self, call: &CallSite) -> bool {
+        let n_params = self.params.len() + self.self_param.is_some() as usize;
+        match call {
+            CallSite::MethodCall(_) if self.self_param.is_none() => false,
+            _ => call.args().len() == n_params,
+        }
+    }
+
+    /// The text replacing `call`.
+    fn inline_into(&self, ctx: &AssistContext, call: &CallSite) -> String {
+        let mut args = call.args().into_iter();
+        let mut lets = Vec::new();
+        let mut edits = Vec::new();
+
+        if let Some((self_param, local, arg)) =
+            self.self_param.as_ref().and_then(|(param, local)| Some((param, local, args.next()?)))
+        {
+            let borrow = match call {
+                CallSite::MethodCall(_) => {
+                    let ty = ctx.sema.type_of_expr(&arg);
+                    let is_ref = ty.as_ref().map_or(false, |it| it.remove_ref().is_some());
+                    let is_mut_ref = ty.as_ref().map_or(false, |it| it.is_mutable_reference());
+                    match self_param.kind() {
+                        SelfParamKind::Ref if !is_ref => "&",
+                        SelfParamKind::MutRef if !is_mut_ref => "&mut ",
+                        _ => "",
+                    }
+                }
+                CallSite::Call(_) => "",
+            };
+            let usages = self.usages(ctx, *local);
+            let mut_ = match self_param.kind() {
+                SelfParamKind::Owned if self_param.mut_token().is_some() => "mut ",
+                _ => "",
+            };
+            if mut_.is_empty() && self.can_substitute(&arg) {
+                for FileReference { range, name, .. } in usages {
+                    // Autoref is still applied to receivers and field accesses.
+                    let is_receiver = name
+                        .syntax()
+                        .ancestors()
+                        .find_map(ast::PathExpr::cast)
+                        .and_then(|it| it.syntax().parent())
+                        .map_or(false, |it| {
+                            matches!(
+                                it.kind(),
+                                SyntaxKind::FIELD_EXPR | SyntaxKind::METHOD_CALL_EXPR
+                            )
+                        });
+                    let text =
+                        if is_receiver { arg.to_string() } else { format!("{}{}", borrow, arg) };
+                    edits.push((range, text));
+                }
+            } else {
+                lets.push(format!("let {}this = {}{};", mut_, borrow, arg));
+                edits.extend(usages.into_iter().map(|it| (it.range, "this".to_string())));
+            }
+        }
+
+        for ((pat, local), arg) in self.params.iter().zip(args) {
+            let substitutes = match (pat, local) {
+                (ast::Pat::IdentPat(ident), Some(_)) => {
+                    ident.mut_token().is_none()
+                        && ident.ref_token().is_none()
+                        && ident.pat().is_none()
+                        && self.can_substitute(&arg)
+                }
+                _ => false,
+            };
+            match local {
+                Some(local) if substitutes => {
+                    for FileReference { range, name, .. } in self.usages(ctx, *local) {
+                        let shorthand = match &name {
+                            ast::NameLike::NameRef(name_ref) => {
+                                ast::RecordExprField::for_field_name(name_ref)
+                                    .map_or(false, |field| field.name_ref().is_none())
+                            }
+                            _ => false,
+                        };
+                        let text = if shorthand {
+                            format!("{}: {}", name.syntax(), arg)
+                        } else {
+                            arg.to_string()
+                        };
+                        edits.push((range, text));
+                    }
+                }
+                _ => lets.push(format!("let {} = {};", pat, arg)),
+            }
+        }
+
+        if let Some((impl_, self_ty)) = &self.impl_ {
+            let call_impl = call
+                .syntax()
+                .ancestors()
+                .find_map(ast::Impl::cast)
+                .and_then(|it| ctx.sema.to_def(&it));
+            if call_impl.as_ref() != Some(impl_) {
+                for name_ref in self.body.syntax().descendants().filter_map(ast::NameRef::cast) {
+                    if name_ref.text() == "Self" {
+                        edits.push((name_ref.syntax().text_range(), self_ty.to_string()));
+                    }
+                }
+            }
+        }
+
+        let body_start = self.body.syntax().text_range().start();
+        let mut text = self.body.syntax().to_string();
+        edits.sort_by_key(|(range, _)| range.start());
+        for (range, replacement) in edits.into_iter().rev() {
+            let range = range - body_start;
+            text.replace_range(usize::from(range.start())..usize::from(range.end()), &replacement);
+        }
+        let body = block_from_text(&text).dedent(IndentLevel::from_node(self.fn_.syntax()));
+        let indent = IndentLevel::from_node(call.syntax());
+
+        if lets.is_empty() && body.statements().next().is_none() {
+            if let Some(tail) = body.tail_expr() {
+                let tail = tail.dedent(IndentLevel(1)).indent(indent);
+                return if needs_parens(&tail, call) {
+                    format!("({})", tail)
+                } else {
+                    tail.to_string()
+                };
+            }
+        }
+        let mut buf = "{\n".to_string();
+        for stmt in lets {
+            format_to!(buf, "    {}\n", stmt);
+        }
+        for stmt in body.statements() {
+            format_to!(buf, "    {}\n", stmt);
+        }
+        if let Some(tail) = body.tail_expr() {
+            format_to!(buf, "    {}\n", tail);
+        }
+        buf += "}";
+        block_from_text(&buf).indent(indent).to_string()
+    }
+
+    fn usages(&self, ctx: &AssistContext, local: hir::Local) -> Vec<FileReference> {
+        let usages = Definition::Local(local)
+            .usages(&ctx.sema)
+            .in_scope(SearchScope::single_file(self.file_id))
+            .all();
+        usages.iter().flat_map(|(_, refs)| refs.iter().cloned()).collect()
+    }
+
+    /// Whether the body only refers to its parameters, its locals and builtin types, so that it
+    /// means the same in any module.
+    fn is_self_contained(&self, ctx: &AssistContext) -> bool {
+        let db = ctx.db();
+        self.body.syntax().descendants().all(|node| {
+            if ast::MacroCall::can_cast(node.kind()) {
+                return false;
+            }
+            if let Some(path) = ast::Path::cast(node.clone()) {
+                return matches!(
+                    ctx.sema.resolve_path(&path),
+                    Some(PathResolution::Local(_))
+                        | Some(PathResolution::Def(hir::ModuleDef::BuiltinType(_)))
+                );
+            }
+            if let Some(call) = ast::MethodCallExpr::cast(node) {
+                // Trait methods need the trait to be in scope.
+                return ctx.sema.resolve_method_call(&call).map_or(false, |it| {
+                    !matches!(
+                        it.as_assoc_item(db).map(|it| it.container(db)),
+                        Some(AssocItemContainer::Trait(_))
+                    )
+                });
+            }
+            true
+        })
+    }
+
+    /// Whether `arg` can be pasted for each use of its parameter, instead of being bound to a
+    /// local first.
+    fn can_substitute(&self, arg: &ast::Expr) -> bool {
+        match arg {
+            ast::Expr::Literal(_) => true,
+            ast::Expr::PathExpr(path_expr) => {
+                // A local of the caller could be shadowed by a binding of the body.
+                let name = match path_expr
+                    .path()
+                    .filter(|it| it.qualifier().is_none())
+                    .and_then(|it| it.segment()?.name_ref())
+                {
+                    Some(it) => it,
+                    None => return true,
+                };
+                !self
+                    .body
+                    .syntax()
+                    .descendants()
+                    .filter_map(ast::IdentPat::cast)
+                    .filter_map(|it| it.name())
+                    .any(|it| it.text() == name.text())
+            }
+            ast::Expr::FieldExpr(field) => {
+                field.expr().map_or(false, |it| self.can_substitute(&it))
+            }
+            _ => false,
+        }
+    }
+}
 
-    for param in value.param_list()?.params() {
-        let pattern = param.pat()?;
-        patterns.push(pattern);
+/// Whether `body` leaves the function early, which can't be expressed in a block.
+fn has_early_exit(body: &ast::BlockExpr) -> bool {
+    let mut preorder = body.syntax().preorder();
+    while let Some(event) = preorder.next() {
+        let node = match event {
+            WalkEvent::Enter(node) => node,
+            WalkEvent::Leave(_) => continue,
+        };
+        match node.kind() {
+            SyntaxKind::RETURN_EXPR | SyntaxKind::TRY_EXPR => return true,
+            SyntaxKind::CLOSURE_EXPR | SyntaxKind::FN => preorder.skip_subtree(),
+            _ => {
+                if ast::EffectExpr::cast(node).map_or(false, |it| it.async_token().is_some()) {
+                    preorder.skip_subtree();
+                }
+            }
+        }
     }
+    false
+}
+
+fn needs_parens(expr: &ast::Expr, call: &CallSite) -> bool {
+    let atomic = matches!(
+        expr,
+        ast::Expr::PathExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::TupleExpr(_)
+            | ast::Expr::ArrayExpr(_)
+            | ast::Expr::Literal(_)
+            | ast::Expr::MacroCall(_)
+            | ast::Expr::TryExpr(_)
+            | ast::Expr::AwaitExpr(_)
+    );
+    !atomic
+        && call.syntax().parent().map_or(false, |parent| {
+            matches!(
+                parent.kind(),
+                SyntaxKind::BIN_EXPR
+                    | SyntaxKind::PREFIX_EXPR
+                    | SyntaxKind::REF_EXPR
+                    | SyntaxKind::CAST_EXPR
+                    | SyntaxKind::RANGE_EXPR
+                    | SyntaxKind::METHOD_CALL_EXPR
+                    | SyntaxKind::FIELD_EXPR
+                    | SyntaxKind::CALL_EXPR
+                    | SyntaxKind::INDEX_EXPR
+                    | SyntaxKind::TRY_EXPR
+                    | SyntaxKind::AWAIT_EXPR
+            )
+        })
+}
 
-    Some(patterns)
+fn block_from_text(text: &str) -> ast::BlockExpr {
+    SourceFile::parse(&format!("fn f() {}", text))
+        .tree()
+        .syntax()
+        .descendants()
+        .find_map(ast::BlockExpr::cast)
+        .unwrap()
 }
 
 #[cfg(test)]
@@ -141,15 +595,57 @@ fn main() {
     }
 
     #[test]
-    fn method_inlining_isnt_supported() {
-        check_assist_not_applicable(
+    fn method_with_autoref_receiver() {
+        check_assist(
             inline_function,
-            r"
-struct Foo;
-impl Foo { fn bar(&self) {} }
+            r#"
+struct Foo { n: u32 }
+impl Foo {
+    fn get(&self) -> u32 { self.n }
+    fn me(&self) -> &Foo { self }
+}
+
+fn main() {
+    let foo = Foo { n: 1 };
+    let n = foo.get$0() + 1;
+    let r = foo.me();
+}
+"#,
+            r#"
+struct Foo { n: u32 }
+impl Foo {
+    fn get(&self) -> u32 { self.n }
+    fn me(&self) -> &Foo { self }
+}
 
-fn main() { Foo.bar$0(); }
-",
+fn main() {
+    let foo = Foo { n: 1 };
+    let n = foo.n + 1;
+    let r = foo.me();
+}
+"#,
+        );
+        check_assist(
+            inline_function,
+            r#"
+struct Foo;
+impl Foo { fn me(&self) -> &Foo { self } }
+fn make() -> Foo { Foo }
+fn main() {
+    let r = make().me$0();
+}
+"#,
+            r#"
+struct Foo;
+impl Foo { fn me(&self) -> &Foo { self } }
+fn make() -> Foo { Foo }
+fn main() {
+    let r = {
+        let this = &make();
+        this
+    };
+}
+"#,
         );
     }
 
@@ -189,13 +685,272 @@ fn foo(a: u32, b: u32) -> u32 {
 
 fn main() {
     let x = {
-        let a = 1;
-        let b = 2;
-        let x = a + b;
-        let y = x - b;
+        let x = 1 + 2;
+        let y = x - 2;
         x * y
     };
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn substitutes_self_outside_of_impl() {
+        check_assist(
+            inline_function,
+            r#"
+struct Point { x: u32, y: u32 }
+impl Point {
+    fn new(x: u32) -> Self {
+        Self { x, y: x }
+    }
+}
+fn main() {
+    let p = Point::new$0(1 + 1);
+}
+"#,
+            r#"
+struct Point { x: u32, y: u32 }
+impl Point {
+    fn new(x: u32) -> Self {
+        Self { x, y: x }
+    }
+}
+fn main() {
+    let p = {
+        let x = 1 + 1;
+        Point { x, y: x }
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn expands_shorthand_fields_and_parenthesizes() {
+        check_assist(
+            inline_function,
+            r#"
+struct S { a: u32 }
+fn make(a: u32) -> S { S { a } }
+fn sum(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let s = make(1);
+    let x = 2 * sum$0(s.a, 3);
+}
+"#,
+            r#"
+struct S { a: u32 }
+fn make(a: u32) -> S { S { a } }
+fn sum(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let s = make(1);
+    let x = 2 * (s.a + 3);
+}
+"#,
+        );
+        check_assist(
+            inline_function,
+            r#"
+struct S { a: u32 }
+fn make(a: u32) -> S { S { a } }
+fn main() {
+    let s = make$0(1);
+}
+"#,
+            r#"
+struct S { a: u32 }
+fn make(a: u32) -> S { S { a } }
+fn main() {
+    let s = S { a: 1 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn binds_arguments_shadowed_by_the_body() {
+        check_assist(
+            inline_function,
+            r#"
+fn foo(a: u32) -> u32 {
+    let b = a + 1;
+    b * a
+}
+fn main() {
+    let b = 1;
+    foo$0(b);
+}
+"#,
+            r#"
+fn foo(a: u32) -> u32 {
+    let b = a + 1;
+    b * a
+}
+fn main() {
+    let b = 1;
+    {
+        let a = b;
+        let b = a + 1;
+        b * a
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_early_exit() {
+        mark::check!(inline_function_early_exit);
+        check_assist_not_applicable(
+            inline_function,
+            r#"
+fn foo(a: u32) -> u32 {
+    if a == 0 { return 1; }
+    a
+}
+fn main() { foo$0(1); }
+"#,
+        );
+    }
+
+    #[test]
+    fn inlines_into_all_callers() {
+        check_assist(
+            inline_into_callers,
+            r#"
+//- /main.rs
+mod other;
+struct Foo(u32);
+impl Foo {
+    fn $0twice(&mut self) {
+        self.0 *= 2;
+    }
+}
+fn main() {
+    let mut foo = Foo(1);
+    foo.twice();
+}
+//- /other.rs
+fn f(foo: &mut crate::Foo) {
+    foo.twice();
+}
+"#,
+            r#"
+//- /main.rs
+mod other;
+struct Foo(u32);
+impl Foo {
+}
+fn main() {
+    let mut foo = Foo(1);
+    {
+        foo.0 *= 2;
+    };
+}
+//- /other.rs
+fn f(foo: &mut crate::Foo) {
+    {
+        foo.0 *= 2;
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_keeps_fn_used_as_value() {
+        check_assist(
+            inline_into_callers,
+            r#"
+fn $0inc(x: u32) -> u32 { x + 1 }
+fn main() {
+    let a = inc(1);
+    let f = inc;
+}
+"#,
+            r#"
+fn inc(x: u32) -> u32 { x + 1 }
+fn main() {
+    let a = 1 + 1;
+    let f = inc;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_not_applicable_to_recursive_fn() {
+        mark::check!(inline_into_callers_recursive);
+        check_assist_not_applicable(
+            inline_into_callers,
+            r#"
+fn $0fact(n: u32) -> u32 { if n == 0 { 1 } else { n * fact(n - 1) } }
+fn main() { fact(3); }
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_removes_fn_called_from_other_files_only() {
+        check_assist(
+            inline_into_callers,
+            r#"
+//- /main.rs
+mod other;
+fn $0double(x: u32) -> u32 { x * 2 }
+fn main() {}
+//- /other.rs
+fn f() -> u32 {
+    crate::double(1)
+}
+"#,
+            r#"
+//- /main.rs
+mod other;
+fn main() {}
+//- /other.rs
+fn f() -> u32 {
+    1 * 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_callers_skips_other_modules_when_body_refers_to_items() {
+        mark::check!(inline_into_callers_other_module);
+        check_assist(
+            inline_into_callers,
+            r#"
+fn helper() -> u32 { 2 }
+fn $0double(x: u32) -> u32 { x * helper() }
+fn main() {
+    let a = double(1);
+}
+mod m {
+    fn f() -> u32 { super::double(1) }
+}
+"#,
+            r#"
+fn helper() -> u32 { 2 }
+fn double(x: u32) -> u32 { x * helper() }
+fn main() {
+    let a = 1 * helper();
+}
+mod m {
+    fn f() -> u32 { super::double(1) }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            inline_into_callers,
+            r#"
+fn helper() -> u32 { 2 }
+fn $0double(x: u32) -> u32 { x * helper() }
+mod m {
+    fn f() -> u32 { super::double(1) }
+}
 "#,
         );
     }
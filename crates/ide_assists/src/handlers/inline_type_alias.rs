@@ -0,0 +1,365 @@
+use hir::{AsAssocItem, HasSource, ModuleDef, PathResolution};
+use ide_db::defs::Definition;
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast::{self, AstNode, GenericParamsOwner, NameOwner},
+    SyntaxKind, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: inline_type_alias
+//
+// Replaces a usage of a type alias with the type it aliases.
+//
+// ```
+// type Pair<T> = (T, T);
+//
+// fn swap(pair: Pai$0r<u8>) {}
+// ```
+// ->
+// ```
+// type Pair<T> = (T, T);
+//
+// fn swap(pair: (u8, u8)) {}
+// ```
+pub(crate) fn inline_type_alias(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let path_type = ctx.find_node_at_offset::<ast::PathType>()?;
+    let alias = match ctx.sema.resolve_path(&path_type.path()?)? {
+        PathResolution::Def(ModuleDef::TypeAlias(it)) => it,
+        _ => return None,
+    };
+    if alias.as_assoc_item(ctx.db()).is_some() {
+        return None;
+    }
+    let replacement = Replacement::new(&alias.source(ctx.db())?.value)?;
+    let text = replacement.for_usage(&path_type)?;
+
+    let target = path_type.syntax().text_range();
+    acc.add(
+        AssistId("inline_type_alias", AssistKind::RefactorInline),
+        "Inline type alias",
+        target,
+        |builder| builder.replace(target, text),
+    )
+}
+
+// Assist: inline_type_alias_uses
+//
+// Replaces all usages of a type alias with the type it aliases, and removes the alias when
+// every usage could be replaced.
+//
+// ```
+// type $0Meters = f64;
+//
+// fn walk(distance: Meters) -> Meters { distance }
+// ```
+// ->
+// ```
+// fn walk(distance: f64) -> f64 { distance }
+// ```
+pub(crate) fn inline_type_alias_uses(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let alias = name.syntax().parent().and_then(ast::TypeAlias::cast)?;
+    let def = ctx.sema.to_def(&alias)?;
+    if def.as_assoc_item(ctx.db()).is_some() {
+        return None;
+    }
+    let replacement = Replacement::new(&alias)?;
+
+    let usages = Definition::ModuleDef(def.into()).usages(&ctx.sema).all();
+    if usages.is_empty() {
+        return None;
+    }
+    let mut edits = Vec::new();
+    let mut all_inlined = true;
+    for (file_id, references) in usages.references {
+        let mut file_edits = Vec::new();
+        for reference in references {
+            let text = match &reference.name {
+                ast::NameLike::NameRef(name_ref) => usage_path_type(name_ref)
+                    .and_then(|it| replacement.for_usage(&it).map(|text| (it, text))),
+                _ => None,
+            };
+            match text {
+                Some((path_type, text)) => file_edits.push((path_type.syntax().text_range(), text)),
+                None => all_inlined = false,
+            }
+        }
+        edits.push((file_id, file_edits));
+    }
+
+    acc.add(
+        AssistId("inline_type_alias_uses", AssistKind::RefactorInline),
+        "Inline type alias into all uses",
+        name.syntax().text_range(),
+        |builder| {
+            let alias_file_id = ctx.frange.file_id;
+            if all_inlined && !edits.iter().any(|(file_id, _)| *file_id == alias_file_id) {
+                edits.push((alias_file_id, Vec::new()));
+            }
+            for (file_id, file_edits) in edits {
+                builder.edit_file(file_id);
+                for (range, text) in file_edits {
+                    builder.replace(range, text);
+                }
+                if all_inlined && file_id == alias_file_id {
+                    builder.delete(alias_range(&alias));
+                }
+            }
+        },
+    )
+}
+
+/// The `PathType` whose last segment is `name_ref`.
+fn usage_path_type(name_ref: &ast::NameRef) -> Option<ast::PathType> {
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+    let path = segment.parent_path();
+    if path.segment()? != segment {
+        return None;
+    }
+    ast::PathType::cast(path.syntax().parent()?)
+}
+
+/// The range of the alias, including the whitespace separating it from the previous item, or
+/// from the next one if it is the first item.
+fn alias_range(alias: &ast::TypeAlias) -> TextRange {
+    let range = alias.syntax().text_range();
+    if let Some(ws) = alias.syntax().prev_sibling_or_token() {
+        if ws.kind() == SyntaxKind::WHITESPACE {
+            return TextRange::new(ws.text_range().start(), range.end());
+        }
+    }
+    match alias.syntax().next_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+            TextRange::new(range.start(), ws.text_range().end())
+        }
+        _ => range,
+    }
+}
+
+/// The aliased type of a type alias, and its generic parameters, which are substituted with the
+/// generic arguments of each usage.
+struct Replacement {
+    ty: ast::Type,
+    lifetime_params: Vec<String>,
+    /// Type and const parameters, with their defaults.
+    params: Vec<(String, Option<String>)>,
+}
+
+impl Replacement {
+    fn new(alias: &ast::TypeAlias) -> Option<Replacement> {
+        let ty = alias.ty()?;
+        let mut lifetime_params = Vec::new();
+        let mut params = Vec::new();
+        if let Some(generic_params) = alias.generic_param_list() {
+            for param in generic_params.generic_params() {
+                match param {
+                    ast::GenericParam::LifetimeParam(it) => {
+                        lifetime_params.push(it.lifetime()?.to_string())
+                    }
+                    ast::GenericParam::TypeParam(it) => params
+                        .push((it.name()?.to_string(), it.default_type().map(|it| it.to_string()))),
+                    ast::GenericParam::ConstParam(it) => params
+                        .push((it.name()?.to_string(), it.default_val().map(|it| it.to_string()))),
+                }
+            }
+        }
+        Some(Replacement { ty, lifetime_params, params })
+    }
+
+    /// The aliased type with the generic arguments of `usage` substituted, or `None` if they don't
+    /// fit the parameters of the alias.
+    fn for_usage(&self, usage: &ast::PathType) -> Option<String> {
+        let mut lifetime_args = Vec::new();
+        let mut args = Vec::new();
+        if let Some(arg_list) = usage.path()?.segment()?.generic_arg_list() {
+            for arg in arg_list.generic_args() {
+                match arg {
+                    ast::GenericArg::LifetimeArg(it) => lifetime_args.push(it.to_string()),
+                    ast::GenericArg::TypeArg(it) => args.push(it.to_string()),
+                    ast::GenericArg::ConstArg(it) => args.push(it.to_string()),
+                    ast::GenericArg::AssocTypeArg(_) => return None,
+                }
+            }
+        }
+        if args.len() > self.params.len()
+            || !(lifetime_args.is_empty() || lifetime_args.len() == self.lifetime_params.len())
+        {
+            return None;
+        }
+
+        let mut subst = FxHashMap::default();
+        for (idx, lifetime) in self.lifetime_params.iter().enumerate() {
+            let arg = lifetime_args.get(idx).cloned().unwrap_or_else(|| "'_".to_string());
+            subst.insert(lifetime.clone(), arg);
+        }
+        for (idx, (name, default)) in self.params.iter().enumerate() {
+            let arg = args.get(idx).cloned().or_else(|| default.clone())?;
+            subst.insert(name.clone(), arg);
+        }
+
+        let text = substitute(&self.ty, &subst);
+        let needs_parens =
+            usage.syntax().parent().map_or(false, |it| {
+                matches!(it.kind(), SyntaxKind::REF_TYPE | SyntaxKind::PTR_TYPE)
+            }) && has_many_bounds(&self.ty);
+        Some(if needs_parens { format!("({})", text) } else { text })
+    }
+}
+
+fn has_many_bounds(ty: &ast::Type) -> bool {
+    let bounds = match ty {
+        ast::Type::DynTraitType(it) => it.type_bound_list(),
+        ast::Type::ImplTraitType(it) => it.type_bound_list(),
+        _ => None,
+    };
+    bounds.map_or(false, |it| it.bounds().count() > 1)
+}
+
+/// Renders `ty`, replacing the generic parameters named in `subst`.
+fn substitute(ty: &ast::Type, subst: &FxHashMap<String, String>) -> String {
+    let start = ty.syntax().text_range().start();
+    let mut edits = Vec::new();
+    for node in ty.syntax().descendants() {
+        if let Some(lifetime) = ast::Lifetime::cast(node.clone()) {
+            if let Some(arg) = subst.get(&lifetime.to_string()) {
+                edits.push((node.text_range() - start, arg));
+            }
+            continue;
+        }
+        let path = match ast::Path::cast(node.clone()) {
+            Some(it) => it,
+            None => continue,
+        };
+        if path.qualifier().is_some()
+            || path.segment().and_then(|it| it.generic_arg_list()).is_some()
+        {
+            continue;
+        }
+        if let Some(arg) = subst.get(&path.to_string()) {
+            edits.push((node.text_range() - start, arg));
+        }
+    }
+
+    let mut text = ty.to_string();
+    edits.sort_by_key(|(range, _)| range.start());
+    for (range, arg) in edits.into_iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(range), arg);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inlines_generic_arguments_and_defaults() {
+        check_assist(
+            inline_type_alias,
+            r#"
+type Table<'a, K, const N: usize, V = u32> = [(&'a K, Vec<V>); N];
+fn lookup(table: &Tab$0le<'static, String, 8, u8>) {}
+"#,
+            r#"
+type Table<'a, K, const N: usize, V = u32> = [(&'a K, Vec<V>); N];
+fn lookup(table: &[(&'static String, Vec<u8>); 8]) {}
+"#,
+        );
+        check_assist(
+            inline_type_alias,
+            r#"
+type Table<'a, K, const N: usize, V = u32> = [(&'a K, Vec<V>); N];
+fn lookup(table: Option<Tab$0le<String, 4>>) {}
+"#,
+            r#"
+type Table<'a, K, const N: usize, V = u32> = [(&'a K, Vec<V>); N];
+fn lookup(table: Option<[(&'_ String, Vec<u32>); 4]>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn wraps_trait_objects_in_parens() {
+        check_assist(
+            inline_type_alias,
+            r#"
+trait Job {}
+type BoxedJob = dyn Job + Send;
+fn run(job: &Boxed$0Job) {}
+"#,
+            r#"
+trait Job {}
+type BoxedJob = dyn Job + Send;
+fn run(job: &(dyn Job + Send)) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_missing_arguments_or_assoc_types() {
+        check_assist_not_applicable(
+            inline_type_alias,
+            r#"
+type Pair<T> = (T, T);
+fn f(_: Pa$0ir) {}
+"#,
+        );
+        check_assist_not_applicable(
+            inline_type_alias,
+            r#"
+trait Tr { type Out; fn f() -> Self::Ou$0t; }
+"#,
+        );
+    }
+
+    #[test]
+    fn inlines_all_uses_across_files() {
+        check_assist(
+            inline_type_alias_uses,
+            r#"
+//- /main.rs
+mod units;
+pub type $0Id<T> = Option<Box<T>>;
+
+fn first(ids: Vec<Id<u8>>) -> Id<u8> { loop {} }
+//- /units.rs
+fn parse(s: &str) -> crate::Id<str> { loop {} }
+"#,
+            r#"
+//- /main.rs
+mod units;
+
+fn first(ids: Vec<Option<Box<u8>>>) -> Option<Box<u8>> { loop {} }
+//- /units.rs
+fn parse(s: &str) -> Option<Box<str>> { loop {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_alias_with_uses_which_cannot_be_inlined() {
+        check_assist(
+            inline_type_alias_uses,
+            r#"
+mod m {
+    pub type Bytes$0 = Vec<u8>;
+    pub fn read() -> Bytes { loop {} }
+}
+use m::Bytes;
+fn write(_: Bytes) {}
+"#,
+            r#"
+mod m {
+    pub type Bytes = Vec<u8>;
+    pub fn read() -> Vec<u8> { loop {} }
+}
+use m::Bytes;
+fn write(_: Vec<u8>) {}
+"#,
+        );
+    }
+}
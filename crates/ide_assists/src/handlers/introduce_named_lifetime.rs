@@ -1,7 +1,7 @@
 use rustc_hash::FxHashSet;
 use syntax::{
     ast::{self, GenericParamsOwner, NameOwner},
-    AstNode, TextRange, TextSize,
+    AstNode, SyntaxNode, TextRange, TextSize,
 };
 
 use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
@@ -11,7 +11,8 @@ static ASSIST_LABEL: &str = "Introduce named lifetime";
 
 // Assist: introduce_named_lifetime
 //
-// Change an anonymous lifetime to a named lifetime.
+// Change an anonymous or elided lifetime to a named lifetime, along with the elided lifetimes
+// of the signature that stand for the same lifetime.
 //
 // ```
 // impl Cursor<'_$0> {
@@ -33,71 +34,150 @@ static ASSIST_LABEL: &str = "Introduce named lifetime";
 // }
 // ```
 // FIXME: How can we handle renaming any one of multiple anonymous lifetimes?
-// FIXME: should also add support for the case fun(f: &Foo) -> &$0Foo
 pub(crate) fn introduce_named_lifetime(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
-    let lifetime =
-        ctx.find_node_at_offset::<ast::Lifetime>().filter(|lifetime| lifetime.text() == "'_")?;
-    if let Some(fn_def) = lifetime.syntax().ancestors().find_map(ast::Fn::cast) {
-        generate_fn_def_assist(acc, &fn_def, lifetime.lifetime_ident_token()?.text_range())
-    } else if let Some(impl_def) = lifetime.syntax().ancestors().find_map(ast::Impl::cast) {
-        generate_impl_def_assist(acc, &impl_def, lifetime.lifetime_ident_token()?.text_range())
+    let (node, target) = match ctx
+        .find_node_at_offset::<ast::Lifetime>()
+        .filter(|lifetime| lifetime.text() == "'_")
+    {
+        Some(lifetime) => (
+            lifetime.syntax().clone(),
+            Elided::Anonymous(lifetime.lifetime_ident_token()?.text_range()),
+        ),
+        None => {
+            let ref_type = ctx.find_node_at_offset::<ast::RefType>()?;
+            let amp = ref_type.amp_token()?;
+            if ref_type.lifetime().is_some() || !amp.text_range().contains_inclusive(ctx.offset()) {
+                return None;
+            }
+            (ref_type.syntax().clone(), Elided::Ref(amp.text_range().end()))
+        }
+    };
+    if let Some(fn_def) = node.ancestors().find_map(ast::Fn::cast) {
+        generate_fn_def_assist(acc, &fn_def, &node, target)
+    } else if let Some(impl_def) = node.ancestors().find_map(ast::Impl::cast) {
+        if impl_def
+            .assoc_item_list()
+            .map_or(false, |it| it.syntax().text_range().contains_range(node.text_range()))
+        {
+            return None;
+        }
+        generate_impl_def_assist(acc, &impl_def, target)
     } else {
         None
     }
 }
 
+/// A lifetime that isn't named in the source.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Elided {
+    /// The range of a `'_`.
+    Anonymous(TextRange),
+    /// The offset right after the `&` of a reference without a lifetime.
+    Ref(TextSize),
+}
+
+impl Elided {
+    fn name(self, builder: &mut AssistBuilder, lifetime: char) {
+        match self {
+            Elided::Anonymous(range) => builder.replace(range, format!("'{}", lifetime)),
+            Elided::Ref(offset) => builder.insert(offset, format!("'{} ", lifetime)),
+        }
+    }
+}
+
+/// The elided lifetimes in `node`, leaving out those of function pointer types, which aren't
+/// tied to the enclosing signature.
+fn elided_lifetimes(node: &SyntaxNode) -> Vec<Elided> {
+    node.descendants()
+        .filter(|it| {
+            !it.ancestors()
+                .take_while(|it| it != node)
+                .any(|it| ast::FnPtrType::can_cast(it.kind()))
+        })
+        .filter_map(|it| {
+            if let Some(lifetime) = ast::Lifetime::cast(it.clone()) {
+                if lifetime.text() == "'_" {
+                    return Some(Elided::Anonymous(lifetime.lifetime_ident_token()?.text_range()));
+                }
+            }
+            let ref_type = ast::RefType::cast(it)?;
+            if ref_type.lifetime().is_some() {
+                return None;
+            }
+            Some(Elided::Ref(ref_type.amp_token()?.text_range().end()))
+        })
+        .collect()
+}
+
 /// Generate the assist for the fn def case
 fn generate_fn_def_assist(
     acc: &mut Assists,
     fn_def: &ast::Fn,
-    lifetime_loc: TextRange,
+    node: &SyntaxNode,
+    target: Elided,
 ) -> Option<()> {
     let param_list: ast::ParamList = fn_def.param_list()?;
+    let ret_type = fn_def.ret_type();
+    let in_ret_type = ret_type
+        .as_ref()
+        .map_or(false, |it| it.syntax().text_range().contains_range(node.text_range()));
+    if !in_ret_type && !param_list.syntax().text_range().contains_range(node.text_range()) {
+        return None;
+    }
     let new_lifetime_param = generate_unique_lifetime_param_name(&fn_def.generic_param_list())?;
     let end_of_fn_ident = fn_def.name()?.ident_token()?.text_range().end();
+
+    // the elided lifetime of the inputs which the elided lifetimes of the output stand for
     let self_param =
         // use the self if it's a reference and has no explicit lifetime
         param_list.self_param().filter(|p| p.lifetime().is_none() && p.amp_token().is_some());
-    // compute the location which implicitly has the same lifetime as the anonymous lifetime
-    let loc_needing_lifetime = if let Some(self_param) = self_param {
-        // if we have a self reference, use that
-        Some(self_param.name()?.syntax().text_range().start())
-    } else {
-        // otherwise, if there's a single reference parameter without a named liftime, use that
-        let fn_params_without_lifetime: Vec<_> = param_list
-            .params()
-            .filter_map(|param| match param.ty() {
-                Some(ast::Type::RefType(ascribed_type)) if ascribed_type.lifetime().is_none() => {
-                    Some(ascribed_type.amp_token()?.text_range().end())
-                }
-                _ => None,
-            })
-            .collect();
-        match fn_params_without_lifetime.len() {
-            1 => Some(fn_params_without_lifetime.into_iter().nth(0)?),
-            0 => None,
-            // multiple unnnamed is invalid. assist is not applicable
-            _ => return None,
-        }
+    let param_lifetimes: Vec<_> = param_list
+        .params()
+        .filter_map(|param| param.ty())
+        .flat_map(|ty| elided_lifetimes(ty.syntax()))
+        .collect();
+    let output_source = match self_param {
+        Some(self_param) => Some(Elided::Ref(self_param.amp_token()?.text_range().end())),
+        None if param_lifetimes.len() == 1 => Some(param_lifetimes[0]),
+        None => None,
     };
-    acc.add(AssistId(ASSIST_NAME, AssistKind::Refactor), ASSIST_LABEL, lifetime_loc, |builder| {
-        add_lifetime_param(fn_def, builder, end_of_fn_ident, new_lifetime_param);
-        builder.replace(lifetime_loc, format!("'{}", new_lifetime_param));
-        loc_needing_lifetime.map(|loc| builder.insert(loc, format!("'{} ", new_lifetime_param)));
-    })
+    let ret_lifetimes = ret_type.map_or_else(Vec::new, |it| elided_lifetimes(it.syntax()));
+
+    let mut named = vec![target];
+    if in_ret_type {
+        // with several elided input lifetimes and no `&self`, the output can't elide its lifetime
+        if output_source.is_none() && !param_lifetimes.is_empty() {
+            return None;
+        }
+        named.extend(ret_lifetimes.into_iter().filter(|it| *it != target));
+        named.extend(output_source);
+    } else if output_source == Some(target) {
+        named.extend(ret_lifetimes);
+    }
+    acc.add(
+        AssistId(ASSIST_NAME, AssistKind::Refactor),
+        ASSIST_LABEL,
+        node.text_range(),
+        |builder| {
+            add_lifetime_param(fn_def, builder, end_of_fn_ident, new_lifetime_param);
+            for elided in named {
+                elided.name(builder, new_lifetime_param);
+            }
+        },
+    )
 }
 
 /// Generate the assist for the impl def case
-fn generate_impl_def_assist(
-    acc: &mut Assists,
-    impl_def: &ast::Impl,
-    lifetime_loc: TextRange,
-) -> Option<()> {
+fn generate_impl_def_assist(acc: &mut Assists, impl_def: &ast::Impl, target: Elided) -> Option<()> {
     let new_lifetime_param = generate_unique_lifetime_param_name(&impl_def.generic_param_list())?;
     let end_of_impl_kw = impl_def.impl_token()?.text_range().end();
-    acc.add(AssistId(ASSIST_NAME, AssistKind::Refactor), ASSIST_LABEL, lifetime_loc, |builder| {
+    let range = match target {
+        Elided::Anonymous(range) => range,
+        Elided::Ref(offset) => TextRange::empty(offset),
+    };
+    acc.add(AssistId(ASSIST_NAME, AssistKind::Refactor), ASSIST_LABEL, range, |builder| {
         add_lifetime_param(impl_def, builder, end_of_impl_kw, new_lifetime_param);
-        builder.replace(lifetime_loc, format!("'{}", new_lifetime_param));
+        target.name(builder, new_lifetime_param);
     })
 }
 
@@ -312,4 +392,54 @@ mod tests {
             r#"fn my_fun<'other, 'a>(self, f: &'a Foo, b: &'other Bar) -> X<'a>"#,
         );
     }
+
+    #[test]
+    fn test_function_elided_reference() {
+        check_assist(
+            introduce_named_lifetime,
+            r#"fn my_fun(f: &$0Foo, g: Bar<'_>) -> &Baz"#,
+            r#"fn my_fun<'a>(f: &'a Foo, g: Bar<'_>) -> &Baz"#,
+        );
+        check_assist(
+            introduce_named_lifetime,
+            r#"fn my_fun(f: $0&mut Foo) -> (&Baz, X<'_>, fn(&u8))"#,
+            r#"fn my_fun<'a>(f: &'a mut Foo) -> (&'a Baz, X<'a>, fn(&u8))"#,
+        );
+    }
+
+    #[test]
+    fn test_function_elided_reference_in_return_type() {
+        check_assist(
+            introduce_named_lifetime,
+            r#"fn my_fun(&mut self, f: &Foo) -> Option<&$0Baz<'_>>"#,
+            r#"fn my_fun<'a>(&'a mut self, f: &Foo) -> Option<&'a Baz<'a>>"#,
+        );
+    }
+
+    #[test]
+    fn test_function_param_does_not_name_self_lifetime() {
+        check_assist(
+            introduce_named_lifetime,
+            r#"fn my_fun(&self, f: Foo<'_$0>) -> &Baz"#,
+            r#"fn my_fun<'a>(&self, f: Foo<'a>) -> &Baz"#,
+        );
+    }
+
+    #[test]
+    fn test_impl_elided_reference() {
+        check_assist(
+            introduce_named_lifetime,
+            r#"impl<T> Trait for &$0Foo<T> { fn f(&self) -> &u8 {} }"#,
+            r#"impl<T, 'a> Trait for &'a Foo<T> { fn f(&self) -> &u8 {} }"#,
+        );
+    }
+
+    #[test]
+    fn test_not_applicable_in_bodies() {
+        check_assist_not_applicable(introduce_named_lifetime, r#"fn f() { let x: &$0u8; }"#);
+        check_assist_not_applicable(
+            introduce_named_lifetime,
+            r#"impl Foo { const C: &$0str = ""; }"#,
+        );
+    }
 }
@@ -0,0 +1,843 @@
+use std::{collections::BTreeSet, iter, ops::Range};
+
+use hir::{ModuleDef, ModuleSource, PathResolution};
+use ide_db::{
+    base_db::FileId,
+    defs::{Definition, NameClass},
+};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    ast::{self, edit::IndentLevel, ModuleItemOwner},
+    AstNode, SyntaxKind, SyntaxNode, TextRange, TextSize, T,
+};
+
+use crate::{
+    assist_context::{AssistBuilder, AssistContext, Assists},
+    utils::vis_offset,
+    AssistId, AssistKind, GroupLabel,
+};
+
+// Assist: move_to_module
+//
+// Moves the function, struct or impl under the cursor into a child, sibling or the parent of its
+// module. Paths referring to the item are rewritten, imports are added where it was used by its
+// name, and its visibility is raised where it is used outside of the new module.
+//
+// ```
+// mod shapes {}
+//
+// fn are$0a(width: u32, height: u32) -> u32 {
+//     width * height
+// }
+//
+// fn main() {
+//     let _ = area(1, 2);
+// }
+// ```
+// ->
+// ```
+// use shapes::area;
+//
+// mod shapes {
+//     pub(crate) fn area(width: u32, height: u32) -> u32 {
+//         width * height
+//     }
+// }
+//
+// fn main() {
+//     let _ = area(1, 2);
+// }
+// ```
+pub(crate) fn move_to_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let item = ctx.find_node_at_offset::<ast::Item>()?;
+    let header_end = match &item {
+        ast::Item::Fn(it) => it.body()?.syntax().text_range().start(),
+        ast::Item::Struct(it) => match it.field_list() {
+            Some(fields) => fields.syntax().text_range().start(),
+            None => it.syntax().text_range().end(),
+        },
+        ast::Item::Impl(it) => it.assoc_item_list()?.syntax().text_range().start(),
+        _ => return None,
+    };
+    if ctx.offset() > header_end
+        || !matches!(
+            item.syntax().parent()?.kind(),
+            SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST
+        )
+    {
+        return None;
+    }
+
+    let db = ctx.db();
+    let module = ctx.sema.scope(item.syntax()).module()?;
+    let name = item.syntax().children().find_map(ast::Name::cast).map(|it| it.text().to_string());
+    let def = definition(ctx, item.syntax());
+    let targets = nearby_modules(db, module)
+        .into_iter()
+        .filter(|it| {
+            let source = it.definition_source(db);
+            let inside_item = source.file_id.original_file(db) == ctx.frange.file_id
+                && item.syntax().text_range().contains_range(definition_range(&source.value));
+            !inside_item && !matches!(source.value, ModuleSource::BlockExpr(_))
+        })
+        .filter(|it| match &name {
+            // Imports of the item itself are removed.
+            Some(name) => it.scope(db, None).into_iter().all(|(it, scope_def)| {
+                it.to_string() != *name
+                    || Some(scope_def) == def.and_then(module_def).map(hir::ScopeDef::ModuleDef)
+            }),
+            None => true,
+        })
+        .map(|it| (display_path(ctx, it), it))
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .collect::<Vec<_>>();
+
+    let group = GroupLabel("Move to module...".to_owned());
+    let target_range = item.syntax().text_range();
+    for (path, target) in targets {
+        let label = match &name {
+            Some(name) => format!("Move `{}` to `{}`", name, path),
+            None => format!("Move impl to `{}`", path),
+        };
+        acc.add_group(
+            &group,
+            AssistId("move_to_module", AssistKind::RefactorRewrite),
+            label,
+            target_range,
+            |builder| {
+                let mut relocation = Relocation {
+                    ctx,
+                    item: item.clone(),
+                    target,
+                    edits: FxHashMap::default(),
+                    imports: FxHashMap::default(),
+                    item_edits: Vec::new(),
+                };
+                relocation.collect(def);
+                relocation.apply(builder);
+            },
+        );
+    }
+    Some(())
+}
+
+/// The edits moving the item to one of the target modules.
+struct Relocation<'a> {
+    ctx: &'a AssistContext<'a>,
+    item: ast::Item,
+    target: hir::Module,
+    /// Edits outside of the moved item, by file.
+    edits: FxHashMap<FileId, Vec<(TextRange, String)>>,
+    /// Use trees to import into modules, by module.
+    imports: FxHashMap<hir::Module, BTreeSet<String>>,
+    /// Changes to the text of the item, with ranges in the original file.
+    item_edits: Vec<(TextRange, String)>,
+}
+
+impl Relocation<'_> {
+    fn collect(&mut self, def: Option<Definition>) {
+        if let Some(def) = def {
+            if self.rewrite_references(def) {
+                let item = self.item.clone();
+                self.raise_visibility(item.syntax());
+            }
+        }
+        match self.item.clone() {
+            ast::Item::Struct(strukt) => self.collect_fields(&strukt, def),
+            ast::Item::Impl(imp) if imp.trait_().is_none() => {
+                let assoc_items = imp.assoc_item_list().into_iter().flat_map(|it| it.assoc_items());
+                for assoc_item in assoc_items {
+                    let vis_owner = match &assoc_item {
+                        ast::AssocItem::Fn(it) => it.syntax(),
+                        ast::AssocItem::Const(it) => it.syntax(),
+                        ast::AssocItem::TypeAlias(it) => it.syntax(),
+                        ast::AssocItem::MacroCall(_) => continue,
+                    };
+                    if let Some(def) = definition(self.ctx, vis_owner) {
+                        if self.used_outside_target(def) {
+                            self.raise_visibility(vis_owner);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.collect_item_paths();
+    }
+
+    /// Rewrites the paths referring to `def` from outside of the item, returning whether any of
+    /// them is outside of the target module.
+    fn rewrite_references(&mut self, def: Definition) -> bool {
+        let ctx = self.ctx;
+        let db = ctx.db();
+        let name = match def.name(db) {
+            Some(it) => it.to_string(),
+            None => return false,
+        };
+        let mut used_outside = false;
+        let mut imported = FxHashSet::default();
+        let mut used_by_name = FxHashSet::default();
+        for (file_id, references) in def.usages(&ctx.sema).all() {
+            for reference in references {
+                if self.is_in_item(file_id, reference.range) {
+                    continue;
+                }
+                let name_ref = match reference.name {
+                    ast::NameLike::NameRef(it) => it,
+                    _ => continue,
+                };
+                let module = match ctx.sema.scope(name_ref.syntax()).module() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if !self.is_within_target(module) {
+                    used_outside = true;
+                }
+                let path = match name_ref.syntax().parent().and_then(ast::PathSegment::cast) {
+                    Some(it) => it.parent_path(),
+                    None => continue,
+                };
+                if let Some(use_tree) = path.syntax().parent().and_then(ast::UseTree::cast) {
+                    imported.insert(module);
+                    self.rewrite_use_tree(file_id, module, &use_tree, &name);
+                } else if let Some(qualifier) = path.qualifier() {
+                    let segment_start = path.segment().map(|it| it.syntax().text_range().start());
+                    let edit = if module == self.target {
+                        let range = TextRange::new(
+                            qualifier.syntax().text_range().start(),
+                            segment_start.unwrap_or_else(|| qualifier.syntax().text_range().end()),
+                        );
+                        (range, String::new())
+                    } else {
+                        let module_path =
+                            match module.find_use_path(db, ModuleDef::from(self.target)) {
+                                Some(it) => it.to_string(),
+                                None => continue,
+                            };
+                        (qualifier.syntax().text_range(), module_path)
+                    };
+                    self.edits.entry(file_id).or_default().push(edit);
+                } else if module != self.target {
+                    used_by_name.insert(module);
+                }
+            }
+        }
+        for module in used_by_name {
+            if imported.contains(&module) {
+                continue;
+            }
+            if let Some(path) = self.target_path(module, ctx.config.insert_use.prefix_kind) {
+                self.imports.entry(module).or_default().insert(format!("{}::{}", path, name));
+            }
+        }
+        used_outside
+    }
+
+    /// Points an import of the item to the target module, or removes it from the target module
+    /// itself.
+    fn rewrite_use_tree(
+        &mut self,
+        file_id: FileId,
+        module: hir::Module,
+        use_tree: &ast::UseTree,
+        name: &str,
+    ) {
+        let nested =
+            use_tree.syntax().parent().map_or(false, |it| ast::UseTreeList::can_cast(it.kind()));
+        if module != self.target && !nested {
+            if let Some(path) = self.target_path(module, self.ctx.config.insert_use.prefix_kind) {
+                let range = use_tree
+                    .path()
+                    .map_or(use_tree.syntax().text_range(), |it| it.syntax().text_range());
+                self.edits.entry(file_id).or_default().push((range, format!("{}::{}", path, name)));
+            }
+            return;
+        }
+
+        let range = if nested {
+            nested_use_tree_range(use_tree)
+        } else {
+            match use_tree.syntax().parent().and_then(ast::Use::cast) {
+                Some(use_item) => range_with_whitespace(use_item.syntax()),
+                None => return,
+            }
+        };
+        self.edits.entry(file_id).or_default().push((range, String::new()));
+        if module != self.target {
+            if let Some(path) = self.target_path(module, self.ctx.config.insert_use.prefix_kind) {
+                let rename = use_tree.rename().map_or(String::new(), |it| format!(" {}", it));
+                self.imports
+                    .entry(module)
+                    .or_default()
+                    .insert(format!("{}::{}{}", path, name, rename));
+            }
+        }
+    }
+
+    fn collect_fields(&mut self, strukt: &ast::Struct, def: Option<Definition>) {
+        match strukt.field_list() {
+            Some(ast::FieldList::RecordFieldList(fields)) => {
+                for field in fields.fields() {
+                    let used_outside = self
+                        .ctx
+                        .sema
+                        .to_def(&field)
+                        .map_or(false, |it| self.used_outside_target(Definition::Field(it)));
+                    if used_outside {
+                        self.raise_visibility(field.syntax());
+                    }
+                }
+            }
+            // Tuple fields are used by the constructor too, so they are made visible together
+            // with the struct.
+            Some(ast::FieldList::TupleFieldList(fields))
+                if def.map_or(false, |it| self.used_outside_target(it)) =>
+            {
+                for field in fields.fields() {
+                    self.raise_visibility(field.syntax());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adjusts the paths of the item which are relative to the old module, and imports the names
+    /// it uses from the old module into the target one.
+    fn collect_item_paths(&mut self) {
+        let ctx = self.ctx;
+        let db = ctx.db();
+        let prefix_kind = ctx.config.insert_use.prefix_kind;
+        let item = self.item.clone();
+        for node in item.syntax().descendants() {
+            let def = if let Some(path) = ast::Path::cast(node.clone()) {
+                if path.qualifier().is_some()
+                    || path.syntax().ancestors().any(|it| ast::Visibility::can_cast(it.kind()))
+                {
+                    continue;
+                }
+                let segment = match path.segment() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if segment.self_token().is_some() || segment.super_token().is_some() {
+                    self.rewrite_relative_path(path);
+                    continue;
+                }
+                if segment.name_ref().is_none() {
+                    continue;
+                }
+                match ctx.sema.resolve_path(&path) {
+                    Some(PathResolution::Def(it)) => it,
+                    _ => continue,
+                }
+            } else if let Some(call) = ast::MethodCallExpr::cast(node) {
+                // Traits need to be in scope for their methods to be called.
+                let trait_ = ctx
+                    .sema
+                    .resolve_method_call(&call)
+                    .and_then(|it| hir::AsAssocItem::as_assoc_item(it, db))
+                    .and_then(|it| it.containing_trait(db));
+                match trait_ {
+                    Some(it) => it.into(),
+                    None => continue,
+                }
+            } else {
+                continue;
+            };
+            if Some(def) == definition(ctx, item.syntax()).and_then(module_def) {
+                continue;
+            }
+            let path = match self.target.find_use_path_prefixed(db, def, prefix_kind) {
+                Some(it) => it,
+                None => continue,
+            };
+            if path.kind == hir::PathKind::Plain && path.segments().len() == 1 {
+                continue;
+            }
+            self.imports.entry(self.target).or_default().insert(path.to_string());
+        }
+    }
+
+    /// Rewrites the longest prefix of a path starting with `self` or `super` which names a module
+    /// to be relative to the target module.
+    fn rewrite_relative_path(&mut self, path: ast::Path) {
+        let ctx = self.ctx;
+        let mut module_path = None;
+        for path in iter::successors(Some(path), |it| it.parent_path()) {
+            match ctx.sema.resolve_path(&path) {
+                Some(PathResolution::Def(ModuleDef::Module(module))) => {
+                    module_path = Some((path, module))
+                }
+                _ => break,
+            }
+        }
+        let (path, module) = match module_path {
+            Some(it) => it,
+            None => return,
+        };
+        let text = if module == self.target {
+            "self".to_string()
+        } else {
+            match self.target.find_use_path(ctx.db(), ModuleDef::from(module)) {
+                Some(it) => it.to_string(),
+                None => return,
+            }
+        };
+        self.item_edits.push((path.syntax().text_range(), text));
+    }
+
+    fn used_outside_target(&self, def: Definition) -> bool {
+        def.usages(&self.ctx.sema).all().into_iter().any(|(file_id, references)| {
+            references.iter().any(|reference| {
+                !self.is_in_item(file_id, reference.range)
+                    && self
+                        .ctx
+                        .sema
+                        .scope(reference.name.syntax())
+                        .module()
+                        .map_or(false, |it| !self.is_within_target(it))
+            })
+        })
+    }
+
+    /// Makes the item visible to the whole crate, as it was used outside of the target module.
+    fn raise_visibility(&mut self, node: &SyntaxNode) {
+        let visibility = node.children().find_map(ast::Visibility::cast);
+        let range = match visibility {
+            None => TextRange::empty(vis_offset(node)),
+            Some(vis) => match vis.kind() {
+                ast::VisibilityKind::Pub | ast::VisibilityKind::PubCrate => return,
+                _ => vis.syntax().text_range(),
+            },
+        };
+        let text = if range.is_empty() { "pub(crate) " } else { "pub(crate)" };
+        self.item_edits.push((range, text.to_string()));
+    }
+
+    fn apply(mut self, builder: &mut AssistBuilder) {
+        let ctx = self.ctx;
+        let file_id = ctx.frange.file_id;
+
+        for (module, imports) in self.imports.clone() {
+            if let Some((import_file_id, offset, text)) = import_insertion(ctx, module, &imports) {
+                self.edits
+                    .entry(import_file_id)
+                    .or_default()
+                    .push((TextRange::empty(offset), text));
+            }
+        }
+        if let Some((target_file_id, range, text)) = self.item_insertion() {
+            self.edits.entry(target_file_id).or_default().push((range, text));
+        }
+        self.edits
+            .entry(file_id)
+            .or_default()
+            .push((range_with_whitespace(self.item.syntax()), String::new()));
+
+        for (file_id, edits) in self.edits.into_iter().sorted_by_key(|(file_id, _)| *file_id) {
+            builder.edit_file(file_id);
+            for (range, text) in edits {
+                builder.replace(range, text);
+            }
+        }
+    }
+
+    /// Where and what to insert to define the item at the end of the target module.
+    fn item_insertion(&self) -> Option<(FileId, TextRange, String)> {
+        let db = self.ctx.db();
+        let source = self.target.definition_source(db);
+        let file_id = source.file_id.original_file(db);
+        let res = match source.value {
+            ModuleSource::SourceFile(file) => match file.items().last() {
+                Some(last) => (
+                    TextRange::empty(last.syntax().text_range().end()),
+                    format!("\n\n{}", self.item_text(IndentLevel(0))),
+                ),
+                None => (
+                    TextRange::empty(file.syntax().text_range().end()),
+                    format!("{}\n", self.item_text(IndentLevel(0))),
+                ),
+            },
+            ModuleSource::Module(module) => {
+                let indent = IndentLevel::from_node(module.syntax());
+                let item_list = module.item_list()?;
+                match item_list.items().last() {
+                    Some(last) => (
+                        TextRange::empty(last.syntax().text_range().end()),
+                        format!("\n\n{}{}", indent + 1, self.item_text(indent + 1)),
+                    ),
+                    None => (
+                        TextRange::new(
+                            item_list.l_curly_token()?.text_range().end(),
+                            item_list.r_curly_token()?.text_range().start(),
+                        ),
+                        format!("\n{}{}\n{}", indent + 1, self.item_text(indent + 1), indent),
+                    ),
+                }
+            }
+            ModuleSource::BlockExpr(_) => return None,
+        };
+        Some((file_id, res.0, res.1))
+    }
+
+    /// The text of the item with its edits applied, indented for `indent`.
+    fn item_text(&self, indent: IndentLevel) -> String {
+        let item_range = self.item.syntax().text_range();
+        let mut text = self.item.syntax().text().to_string();
+        for (range, new) in self.item_edits.iter().sorted_by_key(|(range, _)| range.start()).rev() {
+            text.replace_range(Range::<usize>::from(*range - item_range.start()), new);
+        }
+        let old_indent = IndentLevel::from_node(self.item.syntax()).to_string();
+        text.split('\n')
+            .enumerate()
+            .map(|(idx, line)| match line.trim().is_empty() {
+                true => String::new(),
+                false if idx == 0 => line.to_string(),
+                false => format!("{}{}", indent, line.strip_prefix(&old_indent).unwrap_or(line)),
+            })
+            .join("\n")
+    }
+
+    fn is_in_item(&self, file_id: FileId, range: TextRange) -> bool {
+        file_id == self.ctx.frange.file_id && self.item.syntax().text_range().contains_range(range)
+    }
+
+    fn is_within_target(&self, module: hir::Module) -> bool {
+        module.path_to_root(self.ctx.db()).contains(&self.target)
+    }
+
+    /// The path of the target module from `module`.
+    fn target_path(&self, module: hir::Module, prefix_kind: hir::PrefixKind) -> Option<String> {
+        let path = module.find_use_path_prefixed(
+            self.ctx.db(),
+            ModuleDef::from(self.target),
+            prefix_kind,
+        )?;
+        Some(path.to_string())
+    }
+}
+
+/// Where and what to insert to import the use trees into `module`: after its last import, or
+/// before its first item.
+fn import_insertion(
+    ctx: &AssistContext,
+    module: hir::Module,
+    imports: &BTreeSet<String>,
+) -> Option<(FileId, TextSize, String)> {
+    let db = ctx.db();
+    let source = module.definition_source(db);
+    let file_id = source.file_id.original_file(db);
+    let is_file = matches!(source.value, ModuleSource::SourceFile(_));
+    let (items, indent, start) = match source.value {
+        ModuleSource::SourceFile(file) => {
+            (file.items().collect::<Vec<_>>(), IndentLevel(0), file.syntax().text_range().start())
+        }
+        ModuleSource::Module(module) => {
+            let item_list = module.item_list()?;
+            (
+                item_list.items().collect(),
+                IndentLevel::from_node(module.syntax()) + 1,
+                item_list.l_curly_token()?.text_range().end(),
+            )
+        }
+        ModuleSource::BlockExpr(_) => return None,
+    };
+    let mut uses = imports.iter().map(|it| format!("use {};", it));
+    let last_use = items.iter().filter(|it| matches!(it, ast::Item::Use(_))).last();
+    let res = match (last_use, items.first()) {
+        (Some(last_use), _) => (
+            last_use.syntax().text_range().end(),
+            uses.map(|it| format!("\n{}{}", indent, it)).collect(),
+        ),
+        (None, Some(first)) => (
+            first.syntax().text_range().start(),
+            format!("{}\n\n{}", uses.join(&format!("\n{}", indent)), indent),
+        ),
+        (None, None) if is_file => (start, format!("{}\n\n", uses.join("\n"))),
+        (None, None) => {
+            (start, format!("{}\n", uses.map(|it| format!("\n{}{}", indent, it)).join("")))
+        }
+    };
+    Some((file_id, res.0, res.1))
+}
+
+/// The range of a use tree in a list, together with the separating comma.
+fn nested_use_tree_range(use_tree: &ast::UseTree) -> TextRange {
+    let range = use_tree.syntax().text_range();
+    let comma = |it: &syntax::SyntaxElement| it.kind() == T![,];
+    let is_trivia = |it: &syntax::SyntaxElement| it.kind() == SyntaxKind::WHITESPACE;
+    let mut next = use_tree.syntax().siblings_with_tokens(syntax::Direction::Next).skip(1);
+    if let Some(comma) = next.by_ref().find(|it| !is_trivia(it)).filter(comma) {
+        let end = next
+            .take_while(is_trivia)
+            .last()
+            .map_or(comma.text_range().end(), |it| it.text_range().end());
+        return TextRange::new(range.start(), end);
+    }
+    let mut prev = use_tree.syntax().siblings_with_tokens(syntax::Direction::Prev).skip(1);
+    match prev.find(|it| !is_trivia(it)).filter(comma) {
+        Some(comma) => TextRange::new(comma.text_range().start(), range.end()),
+        None => range,
+    }
+}
+
+/// The range of the node, together with the whitespace separating it from the next or, at the end
+/// of the list, the previous node.
+fn range_with_whitespace(node: &SyntaxNode) -> TextRange {
+    let range = node.text_range();
+    let whitespace = |it: Option<syntax::SyntaxElement>| {
+        it.filter(|it| it.kind() == SyntaxKind::WHITESPACE).map(|it| it.text_range())
+    };
+    match whitespace(node.next_sibling_or_token()) {
+        Some(next) if node.next_sibling().is_some() => range.cover(next),
+        _ => match whitespace(node.prev_sibling_or_token()) {
+            Some(prev) => range.cover(prev),
+            None => range,
+        },
+    }
+}
+
+fn definition(ctx: &AssistContext, node: &SyntaxNode) -> Option<Definition> {
+    let name = node.children().find_map(ast::Name::cast)?;
+    NameClass::classify(&ctx.sema, &name).and_then(|it| it.defined(ctx.db()))
+}
+
+fn module_def(def: Definition) -> Option<ModuleDef> {
+    match def {
+        Definition::ModuleDef(it) => Some(it),
+        _ => None,
+    }
+}
+
+/// The modules the item can be moved to: the children of `module`, its parent and its siblings.
+/// Offering every module of the crate would flood the list of assists on large crates.
+fn nearby_modules(db: &ide_db::RootDatabase, module: hir::Module) -> Vec<hir::Module> {
+    let mut res = module.children(db).collect::<Vec<_>>();
+    if let Some(parent) = module.parent(db) {
+        res.push(parent);
+        res.extend(parent.children(db).filter(|&it| it != module));
+    }
+    res
+}
+
+fn definition_range(source: &ModuleSource) -> TextRange {
+    match source {
+        ModuleSource::SourceFile(it) => it.syntax().text_range(),
+        ModuleSource::Module(it) => it.syntax().text_range(),
+        ModuleSource::BlockExpr(it) => it.syntax().text_range(),
+    }
+}
+
+/// The path of the module from the crate root, as shown to the user.
+fn display_path(ctx: &AssistContext, module: hir::Module) -> String {
+    let db = ctx.db();
+    iter::once("crate".to_string())
+        .chain(
+            module
+                .path_to_root(db)
+                .into_iter()
+                .rev()
+                .filter_map(|it| Some(it.name(db)?.to_string())),
+        )
+        .join("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn moves_fn_into_inline_module() {
+        check_assist_by_label(
+            move_to_module,
+            r#"
+mod geometry {
+    pub mod shapes {}
+
+    pub(super) fn are$0a(width: u32, height: u32) -> u32 {
+        width * height
+    }
+
+    pub fn square(side: u32) -> u32 {
+        area(side, side)
+    }
+}
+
+fn main() {
+    let _ = geometry::area(1, 2);
+}
+"#,
+            r#"
+mod geometry {
+    use self::shapes::area;
+
+    pub mod shapes {
+        pub(crate) fn area(width: u32, height: u32) -> u32 {
+            width * height
+        }
+    }
+
+    pub fn square(side: u32) -> u32 {
+        area(side, side)
+    }
+}
+
+fn main() {
+    let _ = geometry::shapes::area(1, 2);
+}
+"#,
+            "Move `area` to `crate::geometry::shapes`",
+        );
+    }
+
+    #[test]
+    fn moves_struct_and_rewrites_imports() {
+        check_assist_by_label(
+            move_to_module,
+            r#"
+mod model {
+    pub struct Po$0int { x: i32, pub y: i32 }
+
+    pub fn origin() -> Point { Point { x: 0, y: 0 } }
+}
+
+mod render {
+    use crate::model::{origin, Point};
+
+    fn draw(point: Point) -> i32 { point.y }
+}
+
+mod util {
+    use super::model::Point;
+
+    fn first() -> Point { super::model::origin() }
+}
+"#,
+            r#"
+mod model {
+    use crate::util::Point;
+
+    pub fn origin() -> Point { Point { x: 0, y: 0 } }
+}
+
+mod render {
+    use crate::model::{origin};
+    use crate::util::Point;
+
+    fn draw(point: Point) -> i32 { point.y }
+}
+
+mod util {
+    fn first() -> Point { super::model::origin() }
+
+    pub struct Point { pub(crate) x: i32, pub y: i32 }
+}
+"#,
+            "Move `Point` to `crate::util`",
+        );
+    }
+
+    #[test]
+    fn moves_impl_with_its_imports() {
+        check_assist_by_label(
+            move_to_module,
+            r#"
+mod a {
+    pub struct Meters(pub u32);
+    pub trait Describe { fn describe(&self) -> String; }
+
+    impl$0 Meters {
+        fn double(&self) -> Meters { Meters(self.0 * 2) }
+        fn halve(&self) -> Meters { Meters(self.0 / 2) }
+    }
+
+    fn f(m: Meters) -> Meters { m.halve() }
+}
+mod b {}
+"#,
+            r#"
+mod a {
+    pub struct Meters(pub u32);
+    pub trait Describe { fn describe(&self) -> String; }
+
+    fn f(m: Meters) -> Meters { m.halve() }
+}
+mod b {
+    use crate::a::Meters;
+
+    impl Meters {
+        fn double(&self) -> Meters { Meters(self.0 * 2) }
+        pub(crate) fn halve(&self) -> Meters { Meters(self.0 / 2) }
+    }
+}
+"#,
+            "Move impl to `crate::b`",
+        );
+    }
+
+    #[test]
+    fn moves_to_module_file() {
+        check_assist_by_label(
+            move_to_module,
+            r#"
+//- /main.rs
+mod net;
+
+fn conn$0ect() -> u8 { self::net::PORT }
+
+fn main() { connect(); }
+//- /net.rs
+pub const PORT: u8 = 80;
+"#,
+            r#"
+//- /main.rs
+use net::connect;
+
+mod net;
+
+fn main() { connect(); }
+//- /net.rs
+pub const PORT: u8 = 80;
+
+pub(crate) fn connect() -> u8 { self::PORT }
+"#,
+            "Move `connect` to `crate::net`",
+        );
+    }
+
+    #[test]
+    fn offers_only_modules_without_the_name() {
+        check_assist_not_applicable(
+            move_to_module,
+            r#"
+mod a { fn f() {} }
+fn f$0() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn offers_only_nearby_modules() {
+        check_assist_not_applicable(
+            move_to_module,
+            r#"
+mod a { pub mod b {} fn f() {} }
+fn f$0() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            move_to_module,
+            r#"
+mod a {}
+fn f() { $0 }
+"#,
+        );
+    }
+}
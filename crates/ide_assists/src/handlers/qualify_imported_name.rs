@@ -0,0 +1,265 @@
+use hir::PathResolution;
+use syntax::{
+    ast::{self, AstNode, ModuleItemOwner, NameOwner},
+    match_ast, SyntaxKind, SyntaxToken,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: qualify_imported_name
+//
+// Replaces a name brought into scope by a `use` with the full path it is imported from. This is
+// the inverse of `replace_qualified_name_with_use`.
+//
+// ```
+// mod geometry { pub struct Point; }
+// use geometry::Point;
+//
+// fn distance(a: Poi$0nt) {}
+// ```
+// ->
+// ```
+// mod geometry { pub struct Point; }
+// use geometry::Point;
+//
+// fn distance(a: geometry::Point) {}
+// ```
+pub(crate) fn qualify_imported_name(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let token = ctx.find_token_syntax_at_offset(SyntaxKind::IDENT)?;
+    // In macro arguments, look at the path the token ends up in.
+    let name_ref = ast::NameRef::cast(ctx.sema.descend_into_macros(token.clone()).parent())?;
+    let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+    if path.qualifier().is_some()
+        || path.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind()))
+        || token.ancestors().any(|it| ast::UseTree::can_cast(it.kind()))
+    {
+        return None;
+    }
+    let resolution = match ctx.sema.resolve_path(&path)? {
+        it @ PathResolution::Def(_) | it @ PathResolution::Macro(_) => it,
+        _ => return None,
+    };
+
+    let name = name_ref.text().to_string();
+    let qualified = import_candidates(&token, &name).into_iter().find(|candidate| {
+        let path = match ast::Path::parse(candidate) {
+            Ok(it) => it,
+            Err(_) => return false,
+        };
+        ctx.sema.scope(&token.parent()).speculative_resolve(&path).as_ref() == Some(&resolution)
+    })?;
+
+    let target = token.text_range();
+    acc.add(
+        AssistId("qualify_imported_name", AssistKind::RefactorRewrite),
+        format!("Qualify `{}` as `{}`", name, qualified),
+        target,
+        |builder| builder.replace(target, qualified),
+    )
+}
+
+/// The full paths `name` might be imported from, looking at the `use` items of the blocks and the
+/// module around `token`, innermost first.
+fn import_candidates(token: &SyntaxToken, name: &str) -> Vec<String> {
+    let mut res = Vec::new();
+    for node in token.parent().ancestors() {
+        let (items, is_module): (Vec<ast::Item>, _) = match_ast! {
+            match node {
+                ast::SourceFile(it) => (it.items().collect(), true),
+                ast::ItemList(it) => (it.items().collect(), true),
+                ast::BlockExpr(it) => {
+                    let items = it.statements().filter_map(|stmt| match stmt {
+                        ast::Stmt::Item(it) => Some(it),
+                        _ => None,
+                    });
+                    (items.collect(), false)
+                },
+                _ => continue,
+            }
+        };
+        let trees = items
+            .into_iter()
+            .filter_map(|it| match it {
+                ast::Item::Use(it) => it.use_tree(),
+                _ => None,
+            })
+            .flat_map(|it| {
+                it.syntax().descendants().filter_map(ast::UseTree::cast).collect::<Vec<_>>()
+            })
+            .filter(|it| it.use_tree_list().is_none())
+            .collect::<Vec<_>>();
+
+        // Names imported explicitly shadow glob imports.
+        for tree in trees.iter().filter(|it| it.star_token().is_none()) {
+            let segments = full_path(tree);
+            let imported_as = match tree.rename() {
+                Some(rename) => rename.name().map(|it| it.text().to_string()),
+                None => segments.last().cloned(),
+            };
+            if imported_as.as_deref() == Some(name) && !segments.is_empty() {
+                res.push(segments.join("::"));
+            }
+        }
+        for tree in trees.iter().filter(|it| it.star_token().is_some()) {
+            let mut segments = full_path(tree);
+            if !segments.is_empty() {
+                segments.push(name.to_string());
+                res.push(segments.join("::"));
+            }
+        }
+        if is_module {
+            break;
+        }
+    }
+    res
+}
+
+/// The segments of the path a `use` tree imports, including the prefixes of the trees it is nested
+/// in. `foo::{self}` imports `foo`.
+fn full_path(tree: &ast::UseTree) -> Vec<String> {
+    let mut res = Vec::new();
+    for tree in tree.syntax().ancestors().filter_map(ast::UseTree::cast) {
+        let path = match tree.path() {
+            Some(it) => it,
+            None => continue,
+        };
+        let mut segments = path
+            .syntax()
+            .to_string()
+            .split("::")
+            .map(|it| it.trim().to_string())
+            .collect::<Vec<_>>();
+        segments.extend(res);
+        res = segments;
+    }
+    if res.last().map(String::as_str) == Some("self") {
+        res.pop();
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn qualifies_type() {
+        check_assist(
+            qualify_imported_name,
+            r#"
+mod foo { pub mod bar { pub struct Baz; } }
+use foo::bar::Baz;
+fn f(_: Ba$0z) {}
+"#,
+            r#"
+mod foo { pub mod bar { pub struct Baz; } }
+use foo::bar::Baz;
+fn f(_: foo::bar::Baz) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualifies_renamed_function_from_nested_tree() {
+        check_assist(
+            qualify_imported_name,
+            r#"
+mod foo { pub mod bar { pub fn baz() {} } }
+use foo::{bar::{baz as quux}};
+fn f() { qu$0ux() }
+"#,
+            r#"
+mod foo { pub mod bar { pub fn baz() {} } }
+use foo::{bar::{baz as quux}};
+fn f() { foo::bar::baz() }
+"#,
+        );
+    }
+
+    #[test]
+    fn qualifies_trait_from_glob_import() {
+        check_assist(
+            qualify_imported_name,
+            r#"
+mod foo { pub trait Tr { fn f(); } }
+use foo::*;
+struct S;
+impl T$0r for S { fn f() {} }
+"#,
+            r#"
+mod foo { pub trait Tr { fn f(); } }
+use foo::*;
+struct S;
+impl foo::Tr for S { fn f() {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn qualifies_module_of_path() {
+        check_assist(
+            qualify_imported_name,
+            r#"
+mod foo { pub mod bar { pub struct Baz; } }
+use foo::{bar::{self}};
+fn f(_: b$0ar::Baz) {}
+"#,
+            r#"
+mod foo { pub mod bar { pub struct Baz; } }
+use foo::{bar::{self}};
+fn f(_: foo::bar::Baz) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualifies_name_in_macro_arguments() {
+        check_assist(
+            qualify_imported_name,
+            r#"
+macro_rules! id { ($($tt:tt)*) => { $($tt)* } }
+mod foo { pub fn bar() {} }
+use foo::bar;
+fn f() { id!(ba$0r()); }
+"#,
+            r#"
+macro_rules! id { ($($tt:tt)*) => { $($tt)* } }
+mod foo { pub fn bar() {} }
+use foo::bar;
+fn f() { id!(foo::bar()); }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_items_of_the_module() {
+        check_assist_not_applicable(
+            qualify_imported_name,
+            r#"
+struct Baz;
+fn f(_: Ba$0z) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_qualified_paths_and_imports() {
+        check_assist_not_applicable(
+            qualify_imported_name,
+            r#"
+mod foo { pub mod bar { pub struct Baz; } }
+use foo::bar;
+fn f(_: bar::Ba$0z) {}
+"#,
+        );
+        check_assist_not_applicable(
+            qualify_imported_name,
+            r#"
+mod foo { pub mod bar { pub struct Baz; } }
+use foo::bar::Ba$0z;
+"#,
+        );
+    }
+}
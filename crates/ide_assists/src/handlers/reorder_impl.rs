@@ -14,8 +14,8 @@ use crate::{AssistContext, AssistId, AssistKind, Assists};
 
 // Assist: reorder_impl
 //
-// Reorder the methods of an `impl Trait`. The methods will be ordered
-// in the same order as in the trait definition.
+// Reorder the items of an `impl Trait`. The methods, constants and types will be
+// ordered in the same order as in the trait definition.
 //
 // ```
 // trait Foo {
@@ -50,7 +50,7 @@ use crate::{AssistContext, AssistId, AssistKind, Assists};
 pub(crate) fn reorder_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let impl_ast = ctx.find_node_at_offset::<ast::Impl>()?;
     let items = impl_ast.assoc_item_list()?;
-    let methods = get_methods(&items);
+    let assoc_items = get_items(&items);
 
     let path = impl_ast
         .trait_()
@@ -61,28 +61,35 @@ pub(crate) fn reorder_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()>
         .path()?;
 
     let ranks = compute_method_ranks(&path, ctx)?;
-    let sorted: Vec<_> = methods
+    let sorted: Vec<_> = assoc_items
         .iter()
         .cloned()
         .sorted_by_key(|f| {
-            f.name().and_then(|n| ranks.get(&n.to_string()).copied()).unwrap_or(usize::max_value())
+            item_name(f)
+                .and_then(|n| ranks.get(&n.to_string()).copied())
+                .unwrap_or(usize::max_value())
         })
         .collect();
 
-    // Don't edit already sorted methods:
-    if methods == sorted {
+    // Don't edit already sorted items:
+    if assoc_items == sorted {
         mark::hit!(not_applicable_if_sorted);
         return None;
     }
 
     let target = items.syntax().text_range();
-    acc.add(AssistId("reorder_impl", AssistKind::RefactorRewrite), "Sort methods", target, |edit| {
-        let mut rewriter = algo::SyntaxRewriter::default();
-        for (old, new) in methods.iter().zip(&sorted) {
-            rewriter.replace(old.syntax(), new.syntax());
-        }
-        edit.rewrite(rewriter);
-    })
+    acc.add(
+        AssistId("reorder_impl", AssistKind::RefactorRewrite),
+        "Sort items by trait definition",
+        target,
+        |edit| {
+            let mut rewriter = algo::SyntaxRewriter::default();
+            for (old, new) in assoc_items.iter().zip(&sorted) {
+                rewriter.replace(old.syntax(), new.syntax());
+            }
+            edit.rewrite(rewriter);
+        },
+    )
 }
 
 fn compute_method_ranks(path: &ast::Path, ctx: &AssistContext) -> Option<FxHashMap<String, usize>> {
@@ -91,12 +98,9 @@ fn compute_method_ranks(path: &ast::Path, ctx: &AssistContext) -> Option<FxHashM
     Some(
         td.items(ctx.db())
             .iter()
-            .flat_map(|i| match i {
-                hir::AssocItem::Function(f) => Some(f),
-                _ => None,
-            })
+            .flat_map(|i| i.name(ctx.db()))
             .enumerate()
-            .map(|(idx, func)| ((func.name(ctx.db()).to_string(), idx)))
+            .map(|(idx, name)| (name.to_string(), idx))
             .collect(),
     )
 }
@@ -108,15 +112,17 @@ fn trait_definition(path: &ast::Path, sema: &Semantics<RootDatabase>) -> Option<
     }
 }
 
-fn get_methods(items: &ast::AssocItemList) -> Vec<ast::Fn> {
-    items
-        .assoc_items()
-        .flat_map(|i| match i {
-            ast::AssocItem::Fn(f) => Some(f),
-            _ => None,
-        })
-        .filter(|f| f.name().is_some())
-        .collect()
+fn get_items(items: &ast::AssocItemList) -> Vec<ast::AssocItem> {
+    items.assoc_items().filter(|i| item_name(i).is_some()).collect()
+}
+
+fn item_name(item: &ast::AssocItem) -> Option<ast::Name> {
+    match item {
+        ast::AssocItem::Fn(it) => it.name(),
+        ast::AssocItem::Const(it) => it.name(),
+        ast::AssocItem::TypeAlias(it) => it.name(),
+        ast::AssocItem::MacroCall(_) => None,
+    }
 }
 
 #[cfg(test)]
@@ -194,6 +200,45 @@ impl Bar for Foo {
     fn c() {}
     fn b() {}
     fn d() {}
+}
+        "#,
+        )
+    }
+
+    #[test]
+    fn reorder_impl_trait_consts_and_types() {
+        check_assist(
+            reorder_impl,
+            r#"
+trait Bar {
+    type Item;
+    const LEN: usize;
+    fn get() -> Self::Item;
+}
+
+struct Foo;
+$0impl Bar for Foo {
+    /// Always zero.
+    fn get() -> u8 { 0 }
+    const LEN: usize = 1;
+    #[allow(unused)]
+    type Item = u8;
+}
+        "#,
+            r#"
+trait Bar {
+    type Item;
+    const LEN: usize;
+    fn get() -> Self::Item;
+}
+
+struct Foo;
+impl Bar for Foo {
+    #[allow(unused)]
+    type Item = u8;
+    const LEN: usize = 1;
+    /// Always zero.
+    fn get() -> u8 { 0 }
 }
         "#,
         )
@@ -2,7 +2,8 @@ use ide_db::helpers::mod_path_to_ast;
 use ide_db::imports_locator;
 use itertools::Itertools;
 use syntax::{
-    ast::{self, make, AstNode, NameOwner},
+    algo::SyntaxRewriter,
+    ast::{self, edit::IndentLevel, make, AstNode, NameOwner},
     SyntaxKind::{IDENT, WHITESPACE},
     TextSize,
 };
@@ -10,30 +11,31 @@ use syntax::{
 use crate::{
     assist_context::{AssistBuilder, AssistContext, Assists},
     utils::{
-        add_trait_assoc_items_to_impl, filter_assoc_items, generate_trait_impl_text,
-        render_snippet, Cursor, DefaultMethods,
+        add_trait_assoc_items_to_impl, filter_assoc_items, gen_trait_fn_body::gen_trait_fn_body,
+        generate_trait_impl_text, pretty::reformat, render_snippet, Cursor, DefaultMethods,
     },
     AssistId, AssistKind,
 };
 
 // Assist: replace_derive_with_manual_impl
 //
-// Converts a `derive` impl into a manual one.
+// Converts a `derive` impl into a manual one, implemented the way the derive would for the
+// builtin derives.
 //
 // ```
 // # trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
 // #[derive(Deb$0ug, Display)]
-// struct S;
+// struct S { x: u32 }
 // ```
 // ->
 // ```
 // # trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
 // #[derive(Display)]
-// struct S;
+// struct S { x: u32 }
 //
 // impl Debug for S {
-//     fn fmt(&self, f: &mut Formatter) -> Result<()> {
-//         ${0:todo!()}
+//     $0fn fmt(&self, f: &mut Formatter) -> Result<()> {
+//         f.debug_struct("S").field("x", &self.x).finish()
 //     }
 // }
 // ```
@@ -112,17 +114,18 @@ fn add_assist(
         target,
         |builder| {
             let impl_def_with_items =
-                impl_def_from_trait(&ctx.sema, annotated_name, trait_, trait_path);
+                impl_def_from_trait(&ctx.sema, adt, annotated_name, trait_, trait_path);
             update_attribute(builder, &input, &trait_name, &attr);
             let trait_path = format!("{}", trait_path);
             match (ctx.config.snippet_cap, impl_def_with_items) {
-                (None, _) => {
-                    builder.insert(insert_pos, generate_trait_impl_text(adt, &trait_path, ""))
-                }
+                (None, _) => builder.insert(
+                    insert_pos,
+                    reformat(&generate_trait_impl_text(adt, &trait_path, ""), IndentLevel(0)),
+                ),
                 (Some(cap), None) => builder.insert_snippet(
                     cap,
                     insert_pos,
-                    generate_trait_impl_text(adt, &trait_path, "    $0"),
+                    reformat(&generate_trait_impl_text(adt, &trait_path, "    $0"), IndentLevel(0)),
                 ),
                 (Some(cap), Some((impl_def, first_assoc_item))) => {
                     let mut cursor = Cursor::Before(first_assoc_item.syntax());
@@ -140,7 +143,10 @@ fn add_assist(
                     builder.insert_snippet(
                         cap,
                         insert_pos,
-                        format!("\n\n{}", render_snippet(cap, impl_def.syntax(), cursor)),
+                        reformat(
+                            &format!("\n\n{}", render_snippet(cap, impl_def.syntax(), cursor)),
+                            IndentLevel(0),
+                        ),
                     )
                 }
             };
@@ -150,6 +156,7 @@ fn add_assist(
 
 fn impl_def_from_trait(
     sema: &hir::Semantics<ide_db::RootDatabase>,
+    adt: &ast::Adt,
     annotated_name: &ast::Name,
     trait_: Option<hir::Trait>,
     trait_path: &ast::Path,
@@ -166,6 +173,17 @@ fn impl_def_from_trait(
     );
     let (impl_def, first_assoc_item) =
         add_trait_assoc_items_to_impl(sema, trait_items, trait_, impl_def, target_scope);
+
+    // Fill in the body the derive would have generated, if we know it.
+    if let ast::AssocItem::Fn(func) = &first_assoc_item {
+        if let Some(body) = gen_trait_fn_body(func, trait_path, adt) {
+            let mut rewriter = SyntaxRewriter::default();
+            rewriter.replace(func.syntax(), func.with_body(body).syntax());
+            let impl_def = rewriter.rewrite_ast(&impl_def);
+            let first_assoc_item = impl_def.assoc_item_list()?.assoc_items().next()?;
+            return Some((impl_def, first_assoc_item));
+        }
+    }
     Some((impl_def, first_assoc_item))
 }
 
@@ -212,7 +230,7 @@ mod tests {
     fn add_custom_impl_debug() {
         check_assist(
             replace_derive_with_manual_impl,
-            "
+            r#"
 mod fmt {
     pub struct Error;
     pub type Result = Result<(), Error>;
@@ -226,8 +244,8 @@ mod fmt {
 struct Foo {
     bar: String,
 }
-",
-            "
+"#,
+            r#"
 mod fmt {
     pub struct Error;
     pub type Result = Result<(), Error>;
@@ -242,11 +260,273 @@ struct Foo {
 }
 
 impl fmt::Debug for Foo {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    $0fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Foo").field("bar", &self.bar).finish()
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_debug_enum() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+mod fmt {
+    pub struct Formatter;
+    pub trait Debug {
+        fn fmt(&self, f: &mut Formatter) -> ();
+    }
+}
+
+#[derive(Debu$0g)]
+enum Shape {
+    Circle { radius: f32 },
+    Rect(f32, f32),
+    Dot,
+}
+"#,
+            r#"
+mod fmt {
+    pub struct Formatter;
+    pub trait Debug {
+        fn fmt(&self, f: &mut Formatter) -> ();
+    }
+}
+
+enum Shape {
+    Circle { radius: f32 },
+    Rect(f32, f32),
+    Dot,
+}
+
+impl fmt::Debug for Shape {
+    $0fn fmt(&self, f: &mut fmt::Formatter) -> () {
+        match self {
+            Self::Circle { radius } => f.debug_struct("Circle").field("radius", radius).finish(),
+            Self::Rect(arg0, arg1) => f.debug_tuple("Rect").field(arg0).field(arg1).finish(),
+            Self::Dot => f.write_str("Dot"),
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_clone_and_default() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait Clone {
+    fn clone(&self) -> Self;
+    fn clone_from(&mut self, source: &Self) {}
+}
+
+#[derive(Clo$0ne)]
+enum Token {
+    Ident(String),
+    Eof,
+}
+"#,
+            r#"
+trait Clone {
+    fn clone(&self) -> Self;
+    fn clone_from(&mut self, source: &Self) {}
+}
+
+enum Token {
+    Ident(String),
+    Eof,
+}
+
+impl Clone for Token {
+    $0fn clone(&self) -> Self {
+        match self {
+            Self::Ident(arg0) => Self::Ident(arg0.clone()),
+            Self::Eof => Self::Eof,
+        }
+    }
+}
+"#,
+        );
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait Default {
+    fn default() -> Self;
+}
+
+#[derive(Defau$0lt)]
+struct Config(u32, bool);
+"#,
+            r#"
+trait Default {
+    fn default() -> Self;
+}
+
+struct Config(u32, bool);
+
+impl Default for Config {
+    $0fn default() -> Self {
+        Self(Default::default(), Default::default())
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_custom_impl_partial_eq_and_hash() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait PartialEq {
+    fn eq(&self, other: &Self) -> bool;
+    fn ne(&self, other: &Self) -> bool { !self.eq(other) }
+}
+
+#[derive(Partial$0Eq)]
+enum Value {
+    Int(i64),
+    Pair { key: u8, value: u8 },
+    Null,
+}
+"#,
+            r#"
+trait PartialEq {
+    fn eq(&self, other: &Self) -> bool;
+    fn ne(&self, other: &Self) -> bool { !self.eq(other) }
+}
+
+enum Value {
+    Int(i64),
+    Pair { key: u8, value: u8 },
+    Null,
+}
+
+impl PartialEq for Value {
+    $0fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Int(l_0), Self::Int(r_0)) => l_0 == r_0,
+            (
+                Self::Pair { key: l_key, value: l_value },
+                Self::Pair { key: r_key, value: r_value },
+            ) => l_key == r_key && l_value == r_value,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+"#,
+        );
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait Hasher {}
+trait Hash {
+    fn hash<H: Hasher>(&self, state: &mut H);
+}
+
+#[derive(Ha$0sh)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+"#,
+            r#"
+trait Hasher {}
+trait Hash {
+    fn hash<H: Hasher>(&self, state: &mut H);
+}
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Hash for Point {
+    $0fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.hash(state);
+        self.y.hash(state);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_custom_impl_ord() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+enum Ordering { Less, Equal, Greater }
+trait Ord {
+    fn cmp(&self, other: &Self) -> Ordering;
+}
+
+#[derive(O$0rd)]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+"#,
+            r#"
+enum Ordering { Less, Equal, Greater }
+trait Ord {
+    fn cmp(&self, other: &Self) -> Ordering;
+}
+
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+impl Ord for Version {
+    $0fn cmp(&self, other: &Self) -> Ordering {
+        match self.major.cmp(&other.major) {
+            core::cmp::Ordering::Equal => {}
+            ord => return ord,
+        }
+        self.minor.cmp(&other.minor)
+    }
+}
+"#,
+        );
+    }
+    #[test]
+    fn add_custom_impl_for_non_builtin_derive() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+mod ser {
+    pub trait Serialize {
+        fn serialize(&self) -> String;
+    }
+}
+
+#[derive(Serial$0ize)]
+struct Foo {
+    bar: String,
+}
+"#,
+            r#"
+mod ser {
+    pub trait Serialize {
+        fn serialize(&self) -> String;
+    }
+}
+
+struct Foo {
+    bar: String,
+}
+
+impl ser::Serialize for Foo {
+    fn serialize(&self) -> String {
         ${0:todo!()}
     }
 }
-",
+"#,
         )
     }
     #[test]
@@ -1,11 +1,12 @@
 use ast::LoopBodyOwner;
-use hir::known;
-use ide_db::helpers::FamousDefs;
 use stdx::format_to;
 use syntax::{ast, AstNode};
 use test_utils::mark;
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    utils::{impls_core_iter, is_ref_and_impls_iter_method},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // Assist: replace_for_loop_with_for_each
 //
@@ -68,60 +69,10 @@ pub(crate) fn replace_for_loop_with_for_each(acc: &mut Assists, ctx: &AssistCont
     )
 }
 
-/// If iterable is a reference where the expression behind the reference implements a method
-/// returning an Iterator called iter or iter_mut (depending on the type of reference) then return
-/// the expression behind the reference and the method name
-fn is_ref_and_impls_iter_method(
-    sema: &hir::Semantics<ide_db::RootDatabase>,
-    iterable: &ast::Expr,
-) -> Option<(ast::Expr, hir::Name)> {
-    let ref_expr = match iterable {
-        ast::Expr::RefExpr(r) => r,
-        _ => return None,
-    };
-    let wanted_method = if ref_expr.mut_token().is_some() { known::iter_mut } else { known::iter };
-    let expr_behind_ref = ref_expr.expr()?;
-    let typ = sema.type_of_expr(&expr_behind_ref)?;
-    let scope = sema.scope(iterable.syntax());
-    let krate = scope.module()?.krate();
-    let traits_in_scope = scope.traits_in_scope();
-    let iter_trait = FamousDefs(sema, Some(krate)).core_iter_Iterator()?;
-    let has_wanted_method = typ.iterate_method_candidates(
-        sema.db,
-        krate,
-        &traits_in_scope,
-        Some(&wanted_method),
-        |_, func| {
-            if func.ret_type(sema.db).impls_trait(sema.db, iter_trait, &[]) {
-                return Some(());
-            }
-            None
-        },
-    );
-    has_wanted_method.and(Some((expr_behind_ref, wanted_method)))
-}
-
-/// Whether iterable implements core::Iterator
-fn impls_core_iter(sema: &hir::Semantics<ide_db::RootDatabase>, iterable: &ast::Expr) -> bool {
-    let it_typ = if let Some(i) = sema.type_of_expr(iterable) {
-        i
-    } else {
-        return false;
-    };
-    let module = if let Some(m) = sema.scope(iterable.syntax()).module() {
-        m
-    } else {
-        return false;
-    };
-    let krate = module.krate();
-    if let Some(iter_trait) = FamousDefs(sema, Some(krate)).core_iter_Iterator() {
-        return it_typ.impls_trait(sema.db, iter_trait, &[]);
-    }
-    false
-}
-
 #[cfg(test)]
 mod tests {
+    use ide_db::helpers::FamousDefs;
+
     use crate::tests::{check_assist, check_assist_not_applicable};
 
     use super::*;
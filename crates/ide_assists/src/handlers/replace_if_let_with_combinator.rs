@@ -0,0 +1,770 @@
+use std::iter;
+
+use hir::{HirDisplay, PathResolution, Semantics};
+use ide_db::{
+    defs::{Definition, NameRefClass},
+    ty_filter::TryEnum,
+    RootDatabase,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, ArgListOwner,
+    },
+    AstNode, SyntaxKind, SyntaxNode,
+};
+use test_utils::mark;
+
+use crate::{utils::unwrap_trivial_block, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: replace_if_let_with_map_or_else
+//
+// Replaces an `if let Some(..) = .. { .. } else { .. }` over an `Option` or `Result`
+// with a call to `map_or_else`.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+//
+// fn describe(name: Option<&str>) -> usize {
+//     $0if let Some(name) = name {
+//         name.len()
+//     } else {
+//         0
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+//
+// fn describe(name: Option<&str>) -> usize {
+//     name.map_or_else(|| 0, |name| name.len())
+// }
+// ```
+pub(crate) fn replace_if_let_with_map_or_else(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let if_let = IfLet::new(ctx)?;
+    // Both closures are alive at the same time, so they can't both capture the same
+    // local unless it is `Copy`.
+    if if_let.branches_share_locals(&ctx.sema) {
+        mark::hit!(map_or_else_shared_local);
+        return None;
+    }
+    let target = if_let.if_expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_if_let_with_map_or_else", AssistKind::RefactorRewrite),
+        "Replace with `map_or_else`",
+        target,
+        |builder| {
+            let call = format!(
+                "{}.map_or_else({}, {})",
+                if_let.receiver(),
+                if_let.else_fn(&ctx.sema),
+                if_let.then_fn(&ctx.sema),
+            );
+            builder.replace(target, if_let.terminate(call));
+        },
+    )
+}
+
+// Assist: replace_if_let_with_map_unwrap_or_else
+//
+// Replaces an `if let Some(..) = .. { .. } else { .. }` over an `Option` or `Result`
+// with a call to `map` followed by `unwrap_or_else`.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+//
+// fn describe(name: Option<&str>) -> usize {
+//     $0if let Some(name) = name {
+//         name.len()
+//     } else {
+//         0
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+//
+// fn describe(name: Option<&str>) -> usize {
+//     name.map(|name| name.len()).unwrap_or_else(|| 0)
+// }
+// ```
+pub(crate) fn replace_if_let_with_map_unwrap_or_else(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let if_let = IfLet::new(ctx)?;
+    let target = if_let.if_expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_if_let_with_map_unwrap_or_else", AssistKind::RefactorRewrite),
+        "Replace with `map(..).unwrap_or_else(..)`",
+        target,
+        |builder| {
+            let call = format!(
+                "{}.map({}).unwrap_or_else({})",
+                if_let.receiver(),
+                if_let.then_fn(&ctx.sema),
+                if_let.else_fn(&ctx.sema),
+            );
+            builder.replace(target, if_let.terminate(call));
+        },
+    )
+}
+
+// Assist: replace_combinator_with_if_let
+//
+// Unfolds a `map_or_else` call, or a `map` call followed by `unwrap_or_else`, on an `Option`
+// or `Result` into an `if let` expression.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+// impl<T> Option<T> {
+//     fn map_or_else<U, D, F>(self, default: D, f: F) -> U { loop {} }
+// }
+//
+// fn describe(name: Option<&str>) -> usize {
+//     name.$0map_or_else(|| 0, |name| name.len())
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+// impl<T> Option<T> {
+//     fn map_or_else<U, D, F>(self, default: D, f: F) -> U { loop {} }
+// }
+//
+// fn describe(name: Option<&str>) -> usize {
+//     if let Some(name) = name {
+//         name.len()
+//     } else {
+//         0
+//     }
+// }
+// ```
+pub(crate) fn replace_combinator_with_if_let(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name_ref: ast::NameRef = ctx.find_node_at_offset()?;
+    let call = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+    let method = name_ref.text().to_string();
+    let (outer, receiver, then_fn, else_fn) = match method.as_str() {
+        "map_or_else" => {
+            let (else_fn, then_fn) = call.arg_list()?.args().collect_tuple()?;
+            (call.clone(), call.receiver()?, then_fn, else_fn)
+        }
+        "unwrap_or_else" | "map" => {
+            let (map, unwrap) = match method.as_str() {
+                "map" => {
+                    (call.clone(), call.syntax().parent().and_then(ast::MethodCallExpr::cast)?)
+                }
+                _ => match call.receiver()? {
+                    ast::Expr::MethodCallExpr(map) => (map, call.clone()),
+                    _ => return None,
+                },
+            };
+            if map.name_ref()?.text() != "map" || unwrap.name_ref()?.text() != "unwrap_or_else" {
+                return None;
+            }
+            let (then_fn,) = map.arg_list()?.args().collect_tuple()?;
+            let (else_fn,) = unwrap.arg_list()?.args().collect_tuple()?;
+            (unwrap, map.receiver()?, then_fn, else_fn)
+        }
+        _ => return None,
+    };
+    let try_enum = TryEnum::from_ty(&ctx.sema, &ctx.sema.type_of_expr(&receiver)?)?;
+
+    let (pat, then_body) = match &then_fn {
+        ast::Expr::ClosureExpr(closure) => {
+            let (param,) = closure.param_list()?.params().collect_tuple()?;
+            (param.pat()?, closure.body()?)
+        }
+        _ => {
+            let it =
+                make::expr_path(make::path_unqualified(make::path_segment(make::name_ref("it"))));
+            let pat = make::ident_pat(make::name("it")).into();
+            (pat, make::expr_call(callee(then_fn.clone()), make::arg_list(iter::once(it))))
+        }
+    };
+    let else_body = match (&else_fn, try_enum) {
+        (ast::Expr::ClosureExpr(closure), _) => {
+            let params = closure.param_list()?.params().collect::<Vec<_>>();
+            match (try_enum, params.as_slice()) {
+                (TryEnum::Option, []) => {}
+                (TryEnum::Result, [param]) if matches!(param.pat()?, ast::Pat::WildcardPat(_)) => {}
+                _ => return None,
+            }
+            closure.body()?
+        }
+        (_, TryEnum::Option) => make::expr_call(callee(else_fn.clone()), make::arg_list(None)),
+        // The error value can't be bound in an `else` branch.
+        (_, TryEnum::Result) => {
+            mark::hit!(combinator_to_if_let_uses_error);
+            return None;
+        }
+    };
+
+    let target = outer.syntax().text_range();
+    acc.add(
+        AssistId("replace_combinator_with_if_let", AssistKind::RefactorRewrite),
+        "Replace with `if let`",
+        target,
+        |builder| {
+            let happy_path =
+                make::path_unqualified(make::path_segment(make::name_ref(try_enum.happy_case())));
+            let pat = make::tuple_struct_pat(happy_path, iter::once(pat)).into();
+            let if_expr = make::expr_if(
+                make::condition(receiver, Some(pat)),
+                into_block(then_body),
+                Some(ast::ElseBranch::Block(into_block(else_body))),
+            )
+            .indent(IndentLevel::from_node(outer.syntax()));
+            let if_expr = match outer.syntax().parent() {
+                Some(parent) if !can_hold_if_expr(&parent) => make::expr_paren(if_expr),
+                _ => if_expr,
+            };
+            builder.replace(target, if_expr.to_string());
+        },
+    )
+}
+
+struct IfLet {
+    if_expr: ast::IfExpr,
+    try_enum: TryEnum,
+    expr: ast::Expr,
+    /// The pattern inside of `Some(..)` or `Ok(..)`.
+    pat: ast::Pat,
+    then_block: ast::BlockExpr,
+    else_block: ast::BlockExpr,
+}
+
+impl IfLet {
+    fn new(ctx: &AssistContext) -> Option<IfLet> {
+        let if_expr: ast::IfExpr = ctx.find_node_at_offset()?;
+        let cond = if_expr.condition()?;
+        let expr = cond.expr()?;
+        let try_enum = TryEnum::from_ty(&ctx.sema, &ctx.sema.type_of_expr(&expr)?)?;
+        let pat = match cond.pat()? {
+            ast::Pat::TupleStructPat(it) if it.path()?.to_string() == try_enum.happy_case() => {
+                let (pat,) = it.fields().collect_tuple()?;
+                pat
+            }
+            _ => return None,
+        };
+        if !is_closure_param(&pat) {
+            return None;
+        }
+        let then_block = if_expr.then_branch()?;
+        let else_block = match if_expr.else_branch()? {
+            ast::ElseBranch::Block(it) => it,
+            ast::ElseBranch::IfExpr(_) => return None,
+        };
+        if escapes_closure(then_block.syntax()) || escapes_closure(else_block.syntax()) {
+            mark::hit!(if_let_to_combinator_control_flow);
+            return None;
+        }
+        Some(IfLet { if_expr, try_enum, expr, pat, then_block, else_block })
+    }
+
+    fn receiver(&self) -> String {
+        match &self.expr {
+            ast::Expr::PathExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::TryExpr(_)
+            | ast::Expr::AwaitExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::MacroCall(_) => self.expr.to_string(),
+            expr => format!("({})", expr),
+        }
+    }
+
+    fn then_fn(&self, sema: &Semantics<RootDatabase>) -> String {
+        let body = unwrap_trivial_block(self.then_block.clone());
+        if let (ast::Pat::IdentPat(pat), ast::Expr::CallExpr(call)) = (&self.pat, &body) {
+            let arg = call.arg_list().and_then(|it| it.args().collect_tuple());
+            if arg.filter(|(arg,)| arg.to_string() == pat.to_string()).is_some() {
+                if let Some(f) = eta_reducible(sema, call, sema.type_of_pat(&self.pat)) {
+                    return f;
+                }
+            }
+        }
+        format!("|{}| {}", self.pat, body)
+    }
+
+    fn else_fn(&self, sema: &Semantics<RootDatabase>) -> String {
+        let body = unwrap_trivial_block(self.else_block.clone());
+        match self.try_enum {
+            TryEnum::Option => {
+                if let ast::Expr::CallExpr(call) = &body {
+                    if let Some(f) = eta_reducible(sema, call, None) {
+                        return f;
+                    }
+                }
+                format!("|| {}", body)
+            }
+            TryEnum::Result => format!("|_| {}", body),
+        }
+    }
+
+    /// Keeps an `if let` statement in the middle of a block a statement.
+    fn terminate(&self, call: String) -> String {
+        match self.if_expr.syntax().parent().and_then(ast::ExprStmt::cast) {
+            Some(stmt) if stmt.semicolon_token().is_none() => call + ";",
+            _ => call,
+        }
+    }
+
+    fn branches_share_locals(&self, sema: &Semantics<RootDatabase>) -> bool {
+        let then_locals = locals(sema, self.then_block.syntax());
+        locals(sema, self.else_block.syntax())
+            .into_iter()
+            .any(|it| then_locals.contains(&it) && !it.ty(sema.db).is_copy(sema.db))
+    }
+}
+
+/// Returns `f` when `call` is `f(x)` or `f()`, and `f` can be passed instead of the closure.
+fn eta_reducible(
+    sema: &Semantics<RootDatabase>,
+    call: &ast::CallExpr,
+    arg_ty: Option<hir::Type>,
+) -> Option<String> {
+    let path = match call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let func = match sema.resolve_path(&path)? {
+        PathResolution::Def(hir::ModuleDef::Function(it)) => it,
+        _ => return None,
+    };
+    if !hir::GenericDef::from(func).params(sema.db).is_empty() {
+        return None;
+    }
+    let params = func.assoc_fn_params(sema.db);
+    let matches = match (params.as_slice(), arg_ty) {
+        ([], None) => true,
+        // A closure would apply coercions, e.g. from `&String` to `&str`, a function can't.
+        ([param], Some(ty)) => {
+            param.ty().display(sema.db).to_string() == ty.display(sema.db).to_string()
+        }
+        _ => false,
+    };
+    if matches {
+        Some(path.to_string())
+    } else {
+        None
+    }
+}
+
+fn locals(sema: &Semantics<RootDatabase>, node: &SyntaxNode) -> FxHashSet<hir::Local> {
+    node.descendants()
+        .filter_map(ast::NameRef::cast)
+        .filter_map(|it| match NameRefClass::classify(sema, &it)? {
+            NameRefClass::Definition(Definition::Local(local)) => Some(local),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_closure_param(pat: &ast::Pat) -> bool {
+    match pat {
+        ast::Pat::IdentPat(it) => it.ref_token().is_none() && it.pat().is_none(),
+        ast::Pat::WildcardPat(_) => true,
+        ast::Pat::TuplePat(it) => it.fields().all(|it| is_closure_param(&it)),
+        _ => false,
+    }
+}
+
+/// Whether moving `body` into a closure changes what `return`, `?`, `break`, `continue`
+/// or `.await` in it refer to.
+fn escapes_closure(body: &SyntaxNode) -> bool {
+    body.descendants().any(|node| {
+        let is_loop_jump = match node.kind() {
+            SyntaxKind::BREAK_EXPR | SyntaxKind::CONTINUE_EXPR => true,
+            SyntaxKind::RETURN_EXPR | SyntaxKind::TRY_EXPR | SyntaxKind::AWAIT_EXPR => false,
+            _ => return false,
+        };
+        !node.ancestors().take_while(|it| it != body).any(|it| match it.kind() {
+            SyntaxKind::CLOSURE_EXPR | SyntaxKind::FN => true,
+            SyntaxKind::EFFECT_EXPR => {
+                ast::EffectExpr::cast(it).map_or(false, |it| it.async_token().is_some())
+            }
+            SyntaxKind::LOOP_EXPR | SyntaxKind::WHILE_EXPR | SyntaxKind::FOR_EXPR => is_loop_jump,
+            _ => false,
+        })
+    })
+}
+
+fn callee(expr: ast::Expr) -> ast::Expr {
+    match expr {
+        ast::Expr::PathExpr(_) | ast::Expr::FieldExpr(_) | ast::Expr::ParenExpr(_) => expr,
+        _ => make::expr_paren(expr),
+    }
+}
+
+fn into_block(expr: ast::Expr) -> ast::BlockExpr {
+    match expr.reset_indent() {
+        ast::Expr::BlockExpr(block) => block,
+        expr => make::block_expr(iter::empty(), Some(expr)),
+    }
+}
+
+fn can_hold_if_expr(parent: &SyntaxNode) -> bool {
+    matches!(
+        parent.kind(),
+        SyntaxKind::LET_STMT
+            | SyntaxKind::EXPR_STMT
+            | SyntaxKind::BLOCK_EXPR
+            | SyntaxKind::ARG_LIST
+            | SyntaxKind::MATCH_ARM
+            | SyntaxKind::RETURN_EXPR
+            | SyntaxKind::BREAK_EXPR
+            | SyntaxKind::PAREN_EXPR
+            | SyntaxKind::TUPLE_EXPR
+            | SyntaxKind::ARRAY_EXPR
+            | SyntaxKind::RECORD_EXPR_FIELD
+            | SyntaxKind::CLOSURE_EXPR
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const OPTION: &str = r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+impl<T> Option<T> {
+    fn map<U, F>(self, f: F) -> Option<U> { loop {} }
+    fn map_or_else<U, D, F>(self, default: D, f: F) -> U { loop {} }
+    fn unwrap_or_else<F>(self, f: F) -> T { loop {} }
+}
+"#;
+
+    const RESULT: &str = r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+impl<T, E> Result<T, E> {
+    fn map<U, F>(self, f: F) -> Result<U, E> { loop {} }
+    fn map_or_else<U, D, F>(self, default: D, f: F) -> U { loop {} }
+    fn unwrap_or_else<F>(self, f: F) -> T { loop {} }
+}
+"#;
+
+    fn with(prelude: &str, ra_fixture: &str) -> String {
+        format!("{}{}", prelude, ra_fixture)
+    }
+
+    #[test]
+    fn if_let_to_map_or_else() {
+        check_assist(
+            replace_if_let_with_map_or_else,
+            &with(
+                OPTION,
+                r#"
+fn f(x: Option<i32>) -> i32 {
+    i$0f let Some(x) = x { x + 1 } else { 0 }
+}
+"#,
+            ),
+            &with(
+                OPTION,
+                r#"
+fn f(x: Option<i32>) -> i32 {
+    x.map_or_else(|| 0, |x| x + 1)
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn if_let_to_map_unwrap_or_else_on_result() {
+        check_assist(
+            replace_if_let_with_map_unwrap_or_else,
+            &with(
+                RESULT,
+                r#"
+fn f(r: Result<(i32, i32), ()>) -> i32 {
+    $0if let Ok((a, b)) = r {
+        a + b
+    } else {
+        0
+    }
+}
+"#,
+            ),
+            &with(
+                RESULT,
+                r#"
+fn f(r: Result<(i32, i32), ()>) -> i32 {
+    r.map(|(a, b)| a + b).unwrap_or_else(|_| 0)
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn if_let_to_combinator_passes_functions() {
+        check_assist(
+            replace_if_let_with_map_or_else,
+            &with(
+                OPTION,
+                r#"
+fn double(x: i32) -> i32 { x * 2 }
+fn zero() -> i32 { 0 }
+fn f(x: Option<i32>) -> i32 {
+    $0if let Some(x) = x { double(x) } else { zero() }
+}
+"#,
+            ),
+            &with(
+                OPTION,
+                r#"
+fn double(x: i32) -> i32 { x * 2 }
+fn zero() -> i32 { 0 }
+fn f(x: Option<i32>) -> i32 {
+    x.map_or_else(zero, double)
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn if_let_to_combinator_keeps_closure_for_coercions() {
+        check_assist(
+            replace_if_let_with_map_or_else,
+            &with(
+                OPTION,
+                r#"
+struct S;
+fn len(s: &S) -> usize { 0 }
+fn f(s: Option<&&S>) -> usize {
+    $0if let Some(s) = s { len(s) } else { 0 }
+}
+"#,
+            ),
+            &with(
+                OPTION,
+                r#"
+struct S;
+fn len(s: &S) -> usize { 0 }
+fn f(s: Option<&&S>) -> usize {
+    s.map_or_else(|| 0, |s| len(s))
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn if_let_to_combinator_statement() {
+        check_assist(
+            replace_if_let_with_map_unwrap_or_else,
+            &with(
+                OPTION,
+                r#"
+fn log(x: i32) {}
+fn f(x: Option<i32>) {
+    $0if let Some(x) = x {
+        log(x);
+        log(x + 1);
+    } else {
+        log(0);
+    }
+    log(2);
+}
+"#,
+            ),
+            &with(
+                OPTION,
+                r#"
+fn log(x: i32) {}
+fn f(x: Option<i32>) {
+    x.map(|x| {
+        log(x);
+        log(x + 1);
+    }).unwrap_or_else(|| {
+        log(0);
+    });
+    log(2);
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn if_let_to_combinator_not_applicable() {
+        mark::check!(if_let_to_combinator_control_flow);
+        check_assist_not_applicable(
+            replace_if_let_with_map_or_else,
+            &with(
+                OPTION,
+                r#"
+fn f(x: Option<i32>) -> i32 {
+    $0if let Some(x) = x { x } else { return 0 }
+}
+"#,
+            ),
+        );
+        check_assist_not_applicable(
+            replace_if_let_with_map_or_else,
+            &with(
+                OPTION,
+                r#"
+fn f(x: Option<i32>) -> i32 {
+    $0if let Some(1) = x { 1 } else { 0 }
+}
+"#,
+            ),
+        );
+        check_assist_not_applicable(
+            replace_if_let_with_map_or_else,
+            &with(
+                OPTION,
+                r#"
+fn f(x: &Option<i32>) -> i32 {
+    $0if let Some(x) = x { *x } else { 0 }
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn if_let_to_map_or_else_shared_local() {
+        mark::check!(map_or_else_shared_local);
+        let fixture = with(
+            OPTION,
+            r#"
+struct V;
+impl V { fn push(&mut self, x: i32) {} }
+fn f(x: Option<i32>, mut v: V) {
+    $0if let Some(x) = x { v.push(x) } else { v.push(0) }
+}
+"#,
+        );
+        check_assist_not_applicable(replace_if_let_with_map_or_else, &fixture);
+        check_assist(
+            replace_if_let_with_map_unwrap_or_else,
+            &fixture,
+            &with(
+                OPTION,
+                r#"
+struct V;
+impl V { fn push(&mut self, x: i32) {} }
+fn f(x: Option<i32>, mut v: V) {
+    x.map(|x| v.push(x)).unwrap_or_else(|| v.push(0))
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn map_or_else_to_if_let() {
+        check_assist(
+            replace_combinator_with_if_let,
+            &with(
+                OPTION,
+                r#"
+fn f(x: Option<i32>) -> i32 {
+    let y = x.map_or_else$0(|| 0, |x| {
+        let y = x * 2;
+        y + 1
+    });
+    y
+}
+"#,
+            ),
+            &with(
+                OPTION,
+                r#"
+fn f(x: Option<i32>) -> i32 {
+    let y = if let Some(x) = x {
+        let y = x * 2;
+        y + 1
+    } else {
+        0
+    };
+    y
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn map_unwrap_or_else_to_if_let() {
+        check_assist(
+            replace_combinator_with_if_let,
+            &with(
+                RESULT,
+                r#"
+fn double(x: i32) -> i32 { x * 2 }
+fn f(r: Result<i32, ()>) -> i32 {
+    r.map(double).unwrap_or_else$0(|_| 0).pow(2)
+}
+"#,
+            ),
+            &with(
+                RESULT,
+                r#"
+fn double(x: i32) -> i32 { x * 2 }
+fn f(r: Result<i32, ()>) -> i32 {
+    (if let Ok(it) = r {
+        double(it)
+    } else {
+        0
+    }).pow(2)
+}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn combinator_to_if_let_needs_error() {
+        mark::check!(combinator_to_if_let_uses_error);
+        check_assist_not_applicable(
+            replace_combinator_with_if_let,
+            &with(
+                RESULT,
+                r#"
+fn handle(e: ()) -> i32 { 0 }
+fn f(r: Result<i32, ()>) -> i32 {
+    r.map_or_else$0(handle, |x| x)
+}
+"#,
+            ),
+        );
+        check_assist_not_applicable(
+            replace_combinator_with_if_let,
+            &with(
+                RESULT,
+                r#"
+fn f(r: Result<i32, i32>) -> i32 {
+    r.map_or_else$0(|e| e, |x| x)
+}
+"#,
+            ),
+        );
+    }
+}
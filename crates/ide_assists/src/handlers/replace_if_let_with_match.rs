@@ -596,4 +596,52 @@ fn main() {
 "#,
         )
     }
+
+    #[test]
+    fn keeps_or_patterns() {
+        check_assist(
+            replace_if_let_with_match,
+            r#"
+enum Token { Plus, Minus, Star }
+fn f(token: Token) {
+    $0if let Token::Plus | Token::Minus = token {
+        additive()
+    } else {
+        other()
+    }
+}
+"#,
+            r#"
+enum Token { Plus, Minus, Star }
+fn f(token: Token) {
+    match token {
+        Token::Plus | Token::Minus => additive(),
+        _ => other(),
+    }
+}
+"#,
+        );
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+enum Token { Plus, Minus, Star }
+fn f(token: Token) {
+    $0match token {
+        Token::Plus | Token::Minus => additive(),
+        _ => other(),
+    }
+}
+"#,
+            r#"
+enum Token { Plus, Minus, Star }
+fn f(token: Token) {
+    if let Token::Plus | Token::Minus = token {
+        additive()
+    } else {
+        other()
+    }
+}
+"#,
+        );
+    }
 }
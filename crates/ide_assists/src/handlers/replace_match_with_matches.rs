@@ -0,0 +1,269 @@
+use syntax::{
+    ast::{self, edit::IndentLevel},
+    AstNode, SyntaxKind, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: replace_match_with_matches
+//
+// Replaces a `match` with two arms evaluating to `true` and `false` with a `matches!` invocation.
+//
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn is_long_move(action: Action) -> bool {
+//     $0match action {
+//         Action::Move { distance } if distance > 10 => true,
+//         _ => false,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn is_long_move(action: Action) -> bool {
+//     matches!(action, Action::Move { distance } if distance > 10)
+// }
+// ```
+pub(crate) fn replace_match_with_matches(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let match_expr: ast::MatchExpr = ctx.find_node_at_offset()?;
+    let mut arms = match_expr.match_arm_list()?.arms();
+    let first_arm = arms.next()?;
+    let second_arm = arms.next()?;
+    if arms.next().is_some() {
+        return None;
+    }
+    let (first_value, second_value) = (bool_value(&first_arm)?, bool_value(&second_arm)?);
+    if first_value == second_value
+        || !matches!(second_arm.pat()?, ast::Pat::WildcardPat(_))
+        || second_arm.guard().is_some()
+    {
+        return None;
+    }
+    let scrutinee = match_expr.expr()?;
+    let mut pattern = first_arm.pat()?.syntax().text().to_string();
+    if let Some(guard) = first_arm.guard() {
+        pattern = format!("{} if {}", pattern, guard.expr()?.syntax().text());
+    }
+
+    let target = match_expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_match_with_matches", AssistKind::RefactorRewrite),
+        "Replace with matches!",
+        target,
+        |edit| {
+            let negation = if first_value { "" } else { "!" };
+            let replacement =
+                format!("{}matches!({}, {})", negation, scrutinee.syntax().text(), pattern);
+            edit.replace(target, replacement);
+        },
+    )
+}
+
+// Assist: replace_matches_with_match
+//
+// Replaces a `matches!` invocation with the equivalent `match` expression.
+//
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn is_long_move(action: Action) -> bool {
+//     $0matches!(action, Action::Move { distance } if distance > 10)
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn is_long_move(action: Action) -> bool {
+//     match action {
+//         Action::Move { distance } if distance > 10 => true,
+//         _ => false,
+//     }
+// }
+// ```
+pub(crate) fn replace_matches_with_match(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let macro_call: ast::MacroCall = ctx.find_node_at_offset()?;
+    let name = macro_call.path()?.segment()?.name_ref()?;
+    if name.text() != "matches" || !ast::Expr::can_cast(macro_call.syntax().kind()) {
+        return None;
+    }
+    let (scrutinee, pattern) = split_matches_args(&macro_call.token_tree()?)?;
+
+    let target = macro_call.syntax().text_range();
+    acc.add(
+        AssistId("replace_matches_with_match", AssistKind::RefactorRewrite),
+        "Replace with match",
+        target,
+        |edit| {
+            let indent = IndentLevel::from_node(macro_call.syntax());
+            let arm_indent = indent + 1;
+            let replacement = format!(
+                "match {} {{\n{2}{} => true,\n{2}_ => false,\n{3}}}",
+                scrutinee, pattern, arm_indent, indent
+            );
+            edit.replace(target, replacement);
+        },
+    )
+}
+
+/// The value of an arm which evaluates to a boolean literal.
+fn bool_value(arm: &ast::MatchArm) -> Option<bool> {
+    let expr = match arm.expr()? {
+        ast::Expr::BlockExpr(block) if block.statements().next().is_none() => block.tail_expr()?,
+        expr => expr,
+    };
+    let literal = match expr {
+        ast::Expr::Literal(it) => it,
+        _ => return None,
+    };
+    match literal.token().kind() {
+        T![true] => Some(true),
+        T![false] => Some(false),
+        _ => None,
+    }
+}
+
+/// Splits the arguments of `matches!(expr, pattern)` into the expression and the pattern,
+/// which includes the guard.
+fn split_matches_args(token_tree: &ast::TokenTree) -> Option<(String, String)> {
+    let tokens = token_tree.syntax().children_with_tokens().collect::<Vec<_>>();
+    // Skip the delimiters of the token tree.
+    let inner = tokens.get(1..tokens.len().checked_sub(1)?)?;
+    let comma = inner.iter().position(|it| it.kind() == T![,])?;
+    let text = |elements: &[syntax::SyntaxElement]| {
+        elements.iter().map(|it| it.to_string()).collect::<String>().trim().to_string()
+    };
+    let scrutinee = text(&inner[..comma]);
+    let mut pattern = &inner[comma + 1..];
+    // A trailing comma.
+    while let Some((last, rest)) = pattern.split_last() {
+        if last.kind() == T![,] || last.kind() == SyntaxKind::WHITESPACE {
+            pattern = rest;
+        } else {
+            break;
+        }
+    }
+    let pattern = text(pattern);
+    if scrutinee.is_empty() || pattern.is_empty() {
+        return None;
+    }
+    Some((scrutinee, pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn replaces_match_with_matches() {
+        check_assist(
+            replace_match_with_matches,
+            r#"
+fn is_digit_or_dot(c: Option<char>) -> bool {
+    $0match c {
+        Some('0'..='9') | Some('.') => { true }
+        _ => false,
+    }
+}
+"#,
+            r#"
+fn is_digit_or_dot(c: Option<char>) -> bool {
+    matches!(c, Some('0'..='9') | Some('.'))
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replaces_negated_match_with_matches() {
+        check_assist(
+            replace_match_with_matches,
+            r#"
+fn is_not_small(n: Option<u32>) -> bool {
+    $0match n {
+        Some(n) if n < 10 => false,
+        _ => true,
+    }
+}
+"#,
+            r#"
+fn is_not_small(n: Option<u32>) -> bool {
+    !matches!(n, Some(n) if n < 10)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_other_matches() {
+        check_assist_not_applicable(
+            replace_match_with_matches,
+            r#"
+fn f(n: Option<u32>) -> bool {
+    $0match n {
+        Some(_) => true,
+        None => false,
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            replace_match_with_matches,
+            r#"
+fn f(n: Option<u32>) -> u32 {
+    $0match n {
+        Some(_) => 1,
+        _ => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replaces_matches_with_match() {
+        check_assist(
+            replace_matches_with_match,
+            r#"
+fn f(items: &[Option<(u8, u8)>]) {
+    if items.iter().any(|it| $0matches!(it, Some((a, b)) | None if a == b,)) {}
+}
+"#,
+            r#"
+fn f(items: &[Option<(u8, u8)>]) {
+    if items.iter().any(|it| match it {
+        Some((a, b)) | None if a == b => true,
+        _ => false,
+    }) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replaces_matches_in_statement_position() {
+        check_assist(
+            replace_matches_with_match,
+            r#"
+fn f(n: u32) -> bool {
+    let small = $0matches!(n, 0..=9);
+    small
+}
+"#,
+            r#"
+fn f(n: u32) -> bool {
+    let small = match n {
+        0..=9 => true,
+        _ => false,
+    };
+    small
+}
+"#,
+        );
+    }
+}
@@ -21,7 +21,22 @@ pub(crate) fn replace_qualified_name_with_use(
     acc: &mut Assists,
     ctx: &AssistContext,
 ) -> Option<()> {
-    let path: ast::Path = ctx.find_node_at_offset()?;
+    let (path, anchor, target) = match ctx.find_node_at_offset::<ast::Path>() {
+        Some(path) => {
+            let target = path.syntax().text_range();
+            (path.clone(), path.syntax().clone(), target)
+        }
+        None => {
+            // In macro arguments, use the path the tokens end up in.
+            let tt = ctx.find_node_at_offset::<ast::TokenTree>()?;
+            let path = ctx.find_node_at_offset_with_descend::<ast::Path>()?;
+            let range = ctx.sema.original_range(path.syntax());
+            if range.file_id != ctx.frange.file_id {
+                return None;
+            }
+            (path, tt.syntax().clone(), range.range)
+        }
+    };
     // We don't want to mess with use statements
     if path.syntax().ancestors().find_map(ast::Use::cast).is_some() {
         return None;
@@ -31,8 +46,7 @@ pub(crate) fn replace_qualified_name_with_use(
         return None;
     }
 
-    let target = path.syntax().text_range();
-    let scope = ImportScope::find_insert_use_container(path.syntax(), &ctx.sema)?;
+    let scope = ImportScope::find_insert_use_container(&anchor, &ctx.sema)?;
     let syntax = scope.as_syntax_node();
     acc.add(
         AssistId("replace_qualified_name_with_use", AssistKind::RefactorRewrite),
@@ -62,6 +76,7 @@ fn shorten_paths(rewriter: &mut SyntaxRewriter<'static>, node: SyntaxNode, path:
                 // Don't descend into submodules, they don't have the same `use` items in scope.
                 ast::Module(_it) => continue,
 
+                ast::TokenTree(tt) => shorten_paths_in_token_tree(rewriter, &tt, path),
                 ast::Path(p) => {
                     match maybe_replace_path(rewriter, p.clone(), path.clone()) {
                         Some(()) => {},
@@ -74,6 +89,60 @@ fn shorten_paths(rewriter: &mut SyntaxRewriter<'static>, node: SyntaxNode, path:
     }
 }
 
+/// Like [`shorten_paths`], for the paths written in macro arguments, which are just tokens.
+fn shorten_paths_in_token_tree(
+    rewriter: &mut SyntaxRewriter<'static>,
+    tt: &ast::TokenTree,
+    path: &ast::Path,
+) {
+    // `::` is made of two tokens in token trees, so the texts are compared without trivia.
+    let path_text = path
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.kind().is_trivia())
+        .map(|it| it.text().to_string())
+        .collect::<String>();
+    let elements = tt.syntax().children_with_tokens().collect::<Vec<_>>();
+    let significant = elements
+        .iter()
+        .enumerate()
+        .filter(|(_, it)| !it.kind().is_trivia())
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let text =
+        |idx: usize| elements[idx].as_token().map_or(String::new(), |it| it.text().to_string());
+
+    let mut i = 0;
+    while i < significant.len() {
+        let preceding =
+            significant[i.saturating_sub(2)..i].iter().map(|&idx| text(idx)).collect::<String>();
+        let mut matched = String::new();
+        let mut end = i;
+        while end < significant.len() && matched.len() < path_text.len() {
+            let token = text(significant[end]);
+            if token.is_empty() {
+                break;
+            }
+            matched.push_str(&token);
+            end += 1;
+        }
+        if !preceding.ends_with("::") && matched == path_text {
+            // Delete everything up to the last segment.
+            for element in &elements[significant[i]..significant[end - 1]] {
+                rewriter.delete(element);
+            }
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+
+    for child in tt.syntax().children().filter_map(ast::TokenTree::cast) {
+        shorten_paths_in_token_tree(rewriter, &child, path);
+    }
+}
+
 fn maybe_replace_path(
     rewriter: &mut SyntaxRewriter<'static>,
     path: ast::Path,
@@ -120,6 +189,33 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn replaces_path_in_macro_arguments() {
+        check_assist(
+            replace_qualified_name_with_use,
+            r"
+macro_rules! id { ($($tt:tt)*) => { $($tt)* } }
+mod foo { pub mod bar { pub fn baz() {} } }
+
+fn main() {
+    id!(foo::bar::b$0az());
+    id!({ foo::bar::baz(); });
+    foo::bar::baz();
+}",
+            r"
+use foo::bar::baz;
+
+macro_rules! id { ($($tt:tt)*) => { $($tt)* } }
+mod foo { pub mod bar { pub fn baz() {} } }
+
+fn main() {
+    id!(baz());
+    id!({ baz(); });
+    baz();
+}",
+        );
+    }
+
     #[test]
     fn test_replace_already_imported() {
         check_assist(
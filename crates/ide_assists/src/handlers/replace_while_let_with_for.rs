@@ -0,0 +1,311 @@
+use hir::Local;
+use ide_db::{defs::Definition, search::FileReference};
+use syntax::{
+    ast::{self, ArgListOwner, LoopBodyOwner},
+    AstNode, SyntaxKind, SyntaxNode, TextRange,
+};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: replace_while_let_with_for
+//
+// Replaces a `while let` loop taking the items of an iterator one by one with a `for` loop.
+//
+// ```
+// fn sum(numbers: &[u32]) -> u32 {
+//     let mut total = 0;
+//     let mut iter = numbers.iter();
+//     $0while let Some(n) = iter.next() {
+//         total += n;
+//     }
+//     total
+// }
+// ```
+// ->
+// ```
+// fn sum(numbers: &[u32]) -> u32 {
+//     let mut total = 0;
+//     let mut iter = numbers.iter();
+//     for n in iter {
+//         total += n;
+//     }
+//     total
+// }
+// ```
+pub(crate) fn replace_while_let_with_for(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let while_expr = ctx.find_node_at_offset::<ast::WhileExpr>()?;
+    let body = while_expr.loop_body()?;
+    if body.syntax().text_range().start() < ctx.offset() {
+        return None;
+    }
+    let cond = while_expr.condition()?;
+    let pat = match cond.pat()? {
+        ast::Pat::TupleStructPat(it)
+            if it.path()?.segment()?.name_ref()?.text() == "Some" && it.fields().count() == 1 =>
+        {
+            it.fields().next()?
+        }
+        _ => return None,
+    };
+    let call = match cond.expr()? {
+        ast::Expr::MethodCallExpr(it) if it.arg_list()?.args().next().is_none() => it,
+        _ => return None,
+    };
+    let receiver = call.receiver()?;
+    let receiver_local = match &receiver {
+        ast::Expr::PathExpr(it) => match ctx.sema.resolve_path(&it.path()?)? {
+            hir::PathResolution::Local(local) => Some(local),
+            _ => return None,
+        },
+        // The loop calls anything but a place again on each iteration.
+        ast::Expr::FieldExpr(_) => None,
+        _ => return None,
+    };
+    let uses = Uses::new(ctx, &while_expr, &receiver, receiver_local);
+    if uses.in_body {
+        mark::hit!(replace_while_let_with_for_receiver_used_in_body);
+        return None;
+    }
+
+    let iterable = match &*call.name_ref()?.text() {
+        "next" if uses.after_loop => format!("{}.by_ref()", receiver),
+        "next" => receiver.to_string(),
+        // The loop takes the items from the back, and leaves the vector empty.
+        "pop" if is_vec(ctx, &receiver) => format!("{}.drain(..).rev()", receiver),
+        _ => return None,
+    };
+
+    let target = while_expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_while_let_with_for", AssistKind::RefactorRewrite),
+        "Replace with `for` loop",
+        target,
+        |builder| {
+            let header_start = match while_expr.label() {
+                Some(label) => label.syntax().text_range().end(),
+                None => target.start(),
+            };
+            let header = TextRange::new(header_start, body.syntax().text_range().start());
+            let label_ws = if while_expr.label().is_some() { " " } else { "" };
+            builder.replace(header, format!("{}for {} in {} ", label_ws, pat, iterable));
+        },
+    )
+}
+
+/// Where the receiver of the call in the condition is used besides in the condition.
+struct Uses {
+    in_body: bool,
+    /// Whether the receiver is used after the loop ends, so that the loop must not consume it.
+    after_loop: bool,
+}
+
+impl Uses {
+    fn new(
+        ctx: &AssistContext,
+        while_expr: &ast::WhileExpr,
+        receiver: &ast::Expr,
+        local: Option<Local>,
+    ) -> Uses {
+        let body_range = while_expr.loop_body().map(|it| it.syntax().text_range());
+        let loop_range = while_expr.syntax().text_range();
+        let local = match local {
+            Some(it) => it,
+            None => {
+                // Fields are always borrowed, as we can't move out of them.
+                let text = receiver.syntax().text().to_string();
+                let in_body = while_expr.loop_body().map_or(false, |body| {
+                    body.syntax()
+                        .descendants()
+                        .filter_map(ast::FieldExpr::cast)
+                        .any(|it| it.syntax().text() == text.as_str())
+                });
+                return Uses { in_body, after_loop: true };
+            }
+        };
+        // The condition of a loop within another loop runs again after the inner loop ends.
+        let mut uses = Uses { in_body: false, after_loop: is_in_loop(while_expr.syntax()) };
+        let usages = Definition::Local(local).usages(&ctx.sema).all();
+        for FileReference { range, .. } in usages.references.values().flatten() {
+            if body_range.map_or(false, |it| it.contains_range(*range)) {
+                uses.in_body = true;
+            } else if range.start() >= loop_range.end() {
+                uses.after_loop = true;
+            }
+        }
+        uses
+    }
+}
+
+/// Whether `node` is in the body of a loop, within its function or closure.
+fn is_in_loop(node: &SyntaxNode) -> bool {
+    node.ancestors()
+        .skip(1)
+        .take_while(|it| !matches!(it.kind(), SyntaxKind::FN | SyntaxKind::CLOSURE_EXPR))
+        .any(|it| {
+            matches!(
+                it.kind(),
+                SyntaxKind::LOOP_EXPR | SyntaxKind::WHILE_EXPR | SyntaxKind::FOR_EXPR
+            )
+        })
+}
+
+fn is_vec(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    let mut ty = match ctx.sema.type_of_expr(expr) {
+        Some(it) => it,
+        None => return false,
+    };
+    while let Some(it) = ty.remove_ref() {
+        ty = it;
+    }
+    ty.as_adt().map_or(false, |adt| adt.name(ctx.db()).to_string() == "Vec")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn replaces_while_let_over_next() {
+        check_assist(
+            replace_while_let_with_for,
+            r#"
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<(u32, u32)> { None } }
+fn main(mut iter: Iter) {
+    'outer: wh$0ile let Some((a, b)) = iter.next() {
+        if a == b { break 'outer; }
+    }
+}
+"#,
+            r#"
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<(u32, u32)> { None } }
+fn main(mut iter: Iter) {
+    'outer: for (a, b) in iter {
+        if a == b { break 'outer; }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn borrows_iterator_used_afterwards() {
+        check_assist(
+            replace_while_let_with_for,
+            r#"
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<u32> { None } }
+fn main(mut iter: Iter) {
+    loop {
+        $0while let Some(n) = iter.next() {
+            if n == 0 { break; }
+        }
+    }
+}
+"#,
+            r#"
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<u32> { None } }
+fn main(mut iter: Iter) {
+    loop {
+        for n in iter.by_ref() {
+            if n == 0 { break; }
+        }
+    }
+}
+"#,
+        );
+        check_assist(
+            replace_while_let_with_for,
+            r#"
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<u32> { None } }
+struct Parser { tokens: Iter }
+fn parse(p: &mut Parser) {
+    $0while let Some(n) = p.tokens.next() {}
+}
+"#,
+            r#"
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<u32> { None } }
+struct Parser { tokens: Iter }
+fn parse(p: &mut Parser) {
+    for n in p.tokens.by_ref() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replaces_while_let_over_pop() {
+        check_assist(
+            replace_while_let_with_for,
+            r#"
+struct Vec;
+impl Vec { fn pop(&mut self) -> Option<u32> { None } }
+fn main(mut stack: Vec) {
+    $0while let Some(top) = stack.pop() {
+        consume(top);
+    }
+    stack.pop();
+}
+"#,
+            r#"
+struct Vec;
+impl Vec { fn pop(&mut self) -> Option<u32> { None } }
+fn main(mut stack: Vec) {
+    for top in stack.drain(..).rev() {
+        consume(top);
+    }
+    stack.pop();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_receiver_is_used_in_body() {
+        mark::check!(replace_while_let_with_for_receiver_used_in_body);
+        check_assist_not_applicable(
+            replace_while_let_with_for,
+            r#"
+struct Vec;
+impl Vec { fn pop(&mut self) -> Option<u32> { None } fn push(&mut self, n: u32) {} }
+fn main(mut stack: Vec) {
+    $0while let Some(top) = stack.pop() {
+        if top > 0 { stack.push(top - 1); }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_other_loops() {
+        check_assist_not_applicable(
+            replace_while_let_with_for,
+            r#"
+fn make() -> Iter { Iter }
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<u32> { None } }
+fn main() {
+    $0while let Some(n) = make().next() {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            replace_while_let_with_for,
+            r#"
+struct Iter;
+impl Iter { fn next(&mut self) -> Option<u32> { None } }
+fn main(mut iter: Iter) {
+    $0while let None = iter.next() {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use itertools::Itertools;
+use syntax::{
+    algo,
+    ast::{self, AttrsOwner, NameOwner},
+    AstNode, TextRange,
+};
+use test_utils::mark;
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: sort_items
+//
+// Sorts the methods of an inherent impl or a trait, or the variants of an enum, alphabetically.
+// Comments and attributes move along with the item they belong to.
+//
+// ```
+// struct Bar;
+// $0impl Bar {
+//     fn c() {}
+//     /// Comes first.
+//     fn a() {}
+//     fn b() {}
+// }
+// ```
+// ->
+// ```
+// struct Bar;
+// impl Bar {
+//     /// Comes first.
+//     fn a() {}
+//     fn b() {}
+//     fn c() {}
+// }
+// ```
+pub(crate) fn sort_items(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if let Some(impl_) = ctx.find_node_at_offset::<ast::Impl>() {
+        let items = impl_.assoc_item_list()?;
+        if impl_.trait_().is_some() || !in_header(ctx, impl_.syntax(), items.syntax()) {
+            return None;
+        }
+        return sort_methods(acc, &items);
+    }
+    if let Some(trait_) = ctx.find_node_at_offset::<ast::Trait>() {
+        let items = trait_.assoc_item_list()?;
+        if !in_header(ctx, trait_.syntax(), items.syntax()) {
+            return None;
+        }
+        return sort_methods(acc, &items);
+    }
+    let enum_ = ctx.find_node_at_offset::<ast::Enum>()?;
+    let variants = enum_.variant_list()?;
+    if !in_header(ctx, enum_.syntax(), variants.syntax()) {
+        return None;
+    }
+    sort_variants(acc, &enum_, &variants)
+}
+
+/// Whether the cursor is on the item rather than in the list of its members.
+fn in_header(ctx: &AssistContext, item: &syntax::SyntaxNode, list: &syntax::SyntaxNode) -> bool {
+    let header = TextRange::new(item.text_range().start(), list.text_range().start());
+    header.contains_inclusive(ctx.offset())
+}
+
+fn sort_methods(acc: &mut Assists, items: &ast::AssocItemList) -> Option<()> {
+    let methods = items
+        .assoc_items()
+        .filter_map(|item| match item {
+            ast::AssocItem::Fn(it) => Some(it),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let sorted = methods
+        .iter()
+        .cloned()
+        .sorted_by_key(|it| it.name().map(|name| name.to_string()))
+        .collect::<Vec<_>>();
+    if methods == sorted {
+        mark::hit!(sort_items_already_sorted);
+        return None;
+    }
+
+    let target = items.syntax().text_range();
+    acc.add(
+        AssistId("sort_items", AssistKind::RefactorRewrite),
+        "Sort methods alphabetically",
+        target,
+        |edit| {
+            let mut rewriter = algo::SyntaxRewriter::default();
+            for (old, new) in methods.iter().zip(&sorted) {
+                rewriter.replace(old.syntax(), new.syntax());
+            }
+            edit.rewrite(rewriter);
+        },
+    )
+}
+
+fn sort_variants(acc: &mut Assists, enum_: &ast::Enum, list: &ast::VariantList) -> Option<()> {
+    let variants = list.variants().collect::<Vec<_>>();
+    // The order of the variants matters to derived comparisons, and to the implicit
+    // discriminants of the variants.
+    let explicit = variants.iter().filter(|it| it.expr().is_some()).count();
+    let orders_by_variants =
+        enum_.attrs().filter_map(|attr| attr.as_simple_call()).any(|(name, args)| {
+            name == "derive"
+                && args.syntax().children_with_tokens().any(|it| {
+                    let text = it.to_string();
+                    text == "PartialOrd" || text == "Ord"
+                })
+        });
+    if orders_by_variants || (explicit != 0 && explicit != variants.len()) {
+        mark::hit!(sort_items_order_matters);
+        return None;
+    }
+    let sorted = variants
+        .iter()
+        .cloned()
+        .sorted_by_key(|it| it.name().map(|name| name.to_string()))
+        .collect::<Vec<_>>();
+    if variants == sorted {
+        mark::hit!(sort_items_already_sorted);
+        return None;
+    }
+
+    let target = list.syntax().text_range();
+    acc.add(
+        AssistId("sort_items", AssistKind::RefactorRewrite),
+        "Sort variants alphabetically",
+        target,
+        |edit| {
+            let mut rewriter = algo::SyntaxRewriter::default();
+            for (old, new) in variants.iter().zip(&sorted) {
+                rewriter.replace(old.syntax(), new.syntax());
+            }
+            edit.rewrite(rewriter);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn sorts_trait_methods_around_other_items() {
+        check_assist(
+            sort_items,
+            r#"
+trait $0Shape {
+    fn perimeter(&self) -> f64;
+    const SIDES: u32;
+    // Defaults to zero.
+    #[inline]
+    fn area(&self) -> f64 { 0.0 }
+}
+"#,
+            r#"
+trait Shape {
+    // Defaults to zero.
+    #[inline]
+    fn area(&self) -> f64 { 0.0 }
+    const SIDES: u32;
+    fn perimeter(&self) -> f64;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_enum_variants() {
+        check_assist(
+            sort_items,
+            r#"
+#[derive(Debug)]
+enum $0Fruit {
+    /// Yellow.
+    Banana { ripe: bool },
+    Apple(u32),
+    Cherry,
+}
+"#,
+            r#"
+#[derive(Debug)]
+enum Fruit {
+    Apple(u32),
+    /// Yellow.
+    Banana { ripe: bool },
+    Cherry,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_sorted() {
+        mark::check!(sort_items_already_sorted);
+        check_assist_not_applicable(
+            sort_items,
+            r#"
+struct S;
+impl$0 S {
+    fn a() {}
+    fn b() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_variant_order_matters() {
+        mark::check!(sort_items_order_matters);
+        check_assist_not_applicable(
+            sort_items,
+            r#"
+#[derive(PartialEq, PartialOrd)]
+enum $0Level { High, Low }
+"#,
+        );
+        check_assist_not_applicable(
+            sort_items,
+            r#"
+enum $0Level { High = 2, Low }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_bodies_or_trait_impls() {
+        check_assist_not_applicable(
+            sort_items,
+            r#"
+struct S;
+impl S {
+    fn b() { $0 }
+    fn a() {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            sort_items,
+            r#"
+trait T { fn b(); fn a(); }
+struct S;
+$0impl T for S {
+    fn b() {}
+    fn a() {}
+}
+"#,
+        );
+    }
+}
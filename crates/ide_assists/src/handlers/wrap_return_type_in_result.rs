@@ -1,16 +1,21 @@
 use std::iter;
 
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference, ty_filter::TryEnum};
 use syntax::{
+    algo::find_node_at_range,
     ast::{self, make, BlockExpr, Expr, LoopBodyOwner},
     match_ast, AstNode, SyntaxNode,
 };
 use test_utils::mark;
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
 
 // Assist: wrap_return_type_in_result
 //
-// Wrap the function's return type into Result.
+// Wrap the function's return type into Result. For functions, there is also a variant
+// adding `?` or `.unwrap()` to its calls.
 //
 // ```
 // fn foo() -> i32$0 { 42i32 }
@@ -44,37 +49,139 @@ pub(crate) fn wrap_return_type_in_result(acc: &mut Assists, ctx: &AssistContext)
         }
     }
 
-    acc.add(
+    let group = GroupLabel("Wrap return type in Result".to_string());
+    let target = type_ref.syntax().text_range();
+    acc.add_group(
+        &group,
         AssistId("wrap_return_type_in_result", AssistKind::RefactorRewrite),
         "Wrap return type in Result",
-        type_ref.syntax().text_range(),
-        |builder| {
-            let mut tail_return_expr_collector = TailReturnCollector::new();
-            tail_return_expr_collector.collect_jump_exprs(&block_expr, false);
-            tail_return_expr_collector.collect_tail_exprs(&block_expr);
-
-            for ret_expr_arg in tail_return_expr_collector.exprs_to_wrap {
-                let ok_wrapped = make::expr_call(
-                    make::expr_path(make::path_unqualified(make::path_segment(make::name_ref(
-                        "Ok",
-                    )))),
-                    make::arg_list(iter::once(ret_expr_arg.clone())),
-                );
-                builder.replace_ast(ret_expr_arg, ok_wrapped);
-            }
+        target,
+        |builder| wrap_in_result(ctx, builder, &block_expr, type_ref),
+    );
 
-            match ctx.config.snippet_cap {
-                Some(cap) => {
-                    let snippet = format!("Result<{}, ${{0:_}}>", type_ref);
-                    builder.replace_snippet(cap, type_ref.syntax().text_range(), snippet)
+    // Call sites are only updated for functions, closures are rarely called in
+    // more than one place.
+    let func = ast::Fn::cast(parent)?;
+    let call_sites = call_sites(ctx, &func)?;
+    if call_sites.is_empty() {
+        return None;
+    }
+    acc.add_group(
+        &group,
+        AssistId("wrap_return_type_in_result", AssistKind::RefactorRewrite),
+        "Wrap return type in Result and update call sites",
+        target,
+        |builder| {
+            for (file_id, calls) in call_sites {
+                builder.edit_file(file_id);
+                for CallSite { call, propagate } in calls {
+                    let suffix = if propagate { "?" } else { ".unwrap()" };
+                    builder.insert(call.syntax().text_range().end(), suffix);
                 }
-                None => builder
-                    .replace(type_ref.syntax().text_range(), format!("Result<{}, _>", type_ref)),
             }
+            builder.edit_file(ctx.frange.file_id);
+            wrap_in_result(ctx, builder, &block_expr, type_ref);
         },
     )
 }
 
+fn wrap_in_result(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    block_expr: &BlockExpr,
+    type_ref: &ast::Type,
+) {
+    let mut tail_return_expr_collector = TailReturnCollector::new();
+    tail_return_expr_collector.collect_jump_exprs(block_expr, false);
+    tail_return_expr_collector.collect_tail_exprs(block_expr);
+
+    for ret_expr_arg in tail_return_expr_collector.exprs_to_wrap {
+        let ok_wrapped = make::expr_call(
+            make::expr_path(make::path_unqualified(make::path_segment(make::name_ref("Ok")))),
+            make::arg_list(iter::once(ret_expr_arg.clone())),
+        );
+        builder.replace_ast(ret_expr_arg, ok_wrapped);
+    }
+
+    match ctx.config.snippet_cap {
+        Some(cap) => {
+            let snippet = format!("Result<{}, ${{0:_}}>", type_ref);
+            builder.replace_snippet(cap, type_ref.syntax().text_range(), snippet)
+        }
+        None => builder.replace(type_ref.syntax().text_range(), format!("Result<{}, _>", type_ref)),
+    }
+}
+
+struct CallSite {
+    call: ast::Expr,
+    /// Whether the error can be propagated with `?`, as the call is in a function
+    /// returning a `Result`. Otherwise, the call gets an `.unwrap()`.
+    propagate: bool,
+}
+
+/// The direct calls of `func` outside of its own body. Other uses of the function,
+/// like passing it as a value, are left alone.
+fn call_sites(ctx: &AssistContext, func: &ast::Fn) -> Option<Vec<(FileId, Vec<CallSite>)>> {
+    let def = ctx.sema.to_def(func)?;
+    let body_range = func.body()?.syntax().text_range();
+    let mut res = Vec::new();
+    for (file_id, references) in Definition::ModuleDef(def.into()).usages(&ctx.sema).all() {
+        let source_file = ctx.sema.parse(file_id);
+        let mut calls = Vec::new();
+        for FileReference { range, .. } in references {
+            if file_id == ctx.frange.file_id && body_range.contains_range(range) {
+                mark::hit!(wrap_return_type_in_result_recursive_call);
+                continue;
+            }
+            let name_ref = match find_node_at_range::<ast::NameRef>(source_file.syntax(), range) {
+                Some(it) => it,
+                None => continue,
+            };
+            let call = match call_of(&name_ref) {
+                Some(it) => it,
+                None => continue,
+            };
+            let propagate = call
+                .syntax()
+                .ancestors()
+                .find_map(|node| {
+                    match_ast! {
+                        match node {
+                            ast::Fn(it) => Some(Some(it)),
+                            ast::ClosureExpr(_it) => Some(None),
+                            ast::EffectExpr(it) => it.async_token().map(|_| None),
+                            _ => None,
+                        }
+                    }
+                })
+                .flatten()
+                .and_then(|caller| ctx.sema.to_def(&caller))
+                .and_then(|caller| TryEnum::from_ty(&ctx.sema, &caller.ret_type(ctx.db())))
+                .map_or(false, |it| matches!(it, TryEnum::Result));
+            calls.push(CallSite { call, propagate });
+        }
+        if !calls.is_empty() {
+            res.push((file_id, calls));
+        }
+    }
+    Some(res)
+}
+
+/// The call expression `name_ref` is the callee of, as in `foo()`, `S::foo()` or `s.foo()`.
+fn call_of(name_ref: &ast::NameRef) -> Option<ast::Expr> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return Some(call.into());
+    }
+    let path = ast::PathSegment::cast(parent)?.parent_path();
+    let path_expr = ast::PathExpr::cast(path.syntax().parent()?)?;
+    let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+    if call.expr()?.syntax() != path_expr.syntax() {
+        return None;
+    }
+    Some(call.into())
+}
+
 struct TailReturnCollector {
     exprs_to_wrap: Vec<ast::Expr>,
 }
@@ -273,7 +380,7 @@ fn get_tail_expr_from_block(expr: &Expr) -> Option<Vec<NodeType>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
 
     use super::*;
 
@@ -1155,4 +1262,87 @@ fn foo(the_field: u32) -> Result<u32, ${0:_}> {
 "#,
         );
     }
+
+    #[test]
+    fn wrap_return_type_in_result_updates_call_sites() {
+        mark::check!(wrap_return_type_in_result_recursive_call);
+        check_assist_by_label(
+            wrap_return_type_in_result,
+            r#"
+//- /main.rs
+mod parser;
+use parser::parse;
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+struct S;
+impl S {
+    fn total(&self, text: &str) -> Result<u32, ()> {
+        let n = parse(text);
+        let double = |it| parse(it) * 2;
+        Ok(n + double(text))
+    }
+}
+fn main() {
+    let f = parse;
+    parser::parse("1");
+}
+//- /parser.rs
+pub fn parse(text: &str) -> u32$0 {
+    if text.is_empty() {
+        return parse("0");
+    }
+    42
+}
+"#,
+            r#"
+//- /main.rs
+mod parser;
+use parser::parse;
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+struct S;
+impl S {
+    fn total(&self, text: &str) -> Result<u32, ()> {
+        let n = parse(text)?;
+        let double = |it| parse(it).unwrap() * 2;
+        Ok(n + double(text))
+    }
+}
+fn main() {
+    let f = parse;
+    parser::parse("1").unwrap();
+}
+//- /parser.rs
+pub fn parse(text: &str) -> Result<u32, ${0:_}> {
+    if text.is_empty() {
+        return Ok(parse("0"));
+    }
+    Ok(42)
+}
+"#,
+            "Wrap return type in Result and update call sites",
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_result_updates_method_calls() {
+        check_assist_by_label(
+            wrap_return_type_in_result,
+            r#"
+struct S;
+impl S {
+    fn get(&self) -> u32$0 { 1 }
+}
+fn f(s: S) -> u32 { s.get() + S::get(&s) }
+"#,
+            r#"
+struct S;
+impl S {
+    fn get(&self) -> Result<u32, ${0:_}> { Ok(1) }
+}
+fn f(s: S) -> u32 { s.get().unwrap() + S::get(&s).unwrap() }
+"#,
+            "Wrap return type in Result and update call sites",
+        );
+    }
 }
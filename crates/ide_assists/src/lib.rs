@@ -107,38 +107,64 @@ mod handlers {
 
     pub(crate) type Handler = fn(&mut Assists, &AssistContext) -> Option<()>;
 
+    mod add_explicit_closure_types;
+    mod add_explicit_enum_discriminants;
     mod add_explicit_type;
     mod add_lifetime_to_type;
     mod add_missing_impl_members;
+    mod add_serde_rename;
     mod add_turbo_fish;
     mod apply_demorgan;
     mod auto_import;
     mod change_visibility;
+    mod convert_closure_to_fn;
+    mod convert_doctest_to_test;
+    mod convert_for_loop_to_iterator_chain;
+    mod convert_free_fn_to_method;
     mod convert_integer_literal;
+    mod convert_method_to_function;
+    mod convert_named_struct_to_tuple_struct;
+    mod convert_string_concat_to_format;
+    mod convert_tuple_struct_to_named_struct;
+    mod destructure_binding;
+    mod destructure_local;
     mod early_return;
     mod expand_glob_import;
+    mod expand_wildcard_arm;
     mod extract_function;
+    mod extract_module;
     mod extract_struct_from_enum_variant;
+    mod extract_struct_from_function_parameters;
     mod extract_variable;
+    mod fill_expression;
     mod fill_match_arms;
     mod fix_visibility;
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
+    mod generate_builder;
+    mod generate_debug_impl;
     mod generate_default_from_enum_variant;
+    mod generate_default_from_new;
+    mod generate_delegate_methods;
     mod generate_derive;
+    mod generate_enum_helper_methods;
     mod generate_enum_is_method;
+    mod generate_enum_variant;
     mod generate_enum_projection_method;
+    mod generate_extern_c_wrapper;
     mod generate_from_impl_for_enum;
     mod generate_function;
     mod generate_getter_mut;
     mod generate_getter;
     mod generate_impl;
     mod generate_new;
+    mod generate_new_from_default;
     mod generate_setter;
     mod infer_function_return_type;
     mod inline_function;
     mod inline_local_variable;
+    mod inline_type_alias;
     mod introduce_named_lifetime;
     mod invert_if;
     mod merge_imports;
@@ -146,7 +172,9 @@ mod handlers {
     mod move_bounds;
     mod move_guard;
     mod move_module_to_file;
+    mod move_to_module;
     mod pull_assignment_up;
+    mod qualify_imported_name;
     mod qualify_path;
     mod raw_string;
     mod remove_dbg;
@@ -156,12 +184,16 @@ mod handlers {
     mod reorder_impl;
     mod replace_derive_with_manual_impl;
     mod replace_for_loop_with_for_each;
+    mod replace_if_let_with_combinator;
     mod replace_if_let_with_match;
     mod replace_impl_trait_with_generic;
     mod replace_let_with_if_let;
+    mod replace_match_with_matches;
     mod replace_qualified_name_with_use;
     mod replace_string_with_char;
     mod replace_unwrap_with_match;
+    mod replace_while_let_with_for;
+    mod sort_items;
     mod split_import;
     mod toggle_ignore;
     mod unmerge_use;
@@ -171,36 +203,71 @@ mod handlers {
     pub(crate) fn all() -> &'static [Handler] {
         &[
             // These are alphabetic for the foolish consistency
+            add_explicit_closure_types::add_explicit_closure_types,
+            add_explicit_enum_discriminants::add_enum_repr,
+            add_explicit_enum_discriminants::add_explicit_enum_discriminants,
             add_explicit_type::add_explicit_type,
             add_lifetime_to_type::add_lifetime_to_type,
+            add_serde_rename::add_serde_field_renames,
+            add_serde_rename::add_serde_rename_all,
             add_turbo_fish::add_turbo_fish,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
             change_visibility::change_visibility,
+            convert_closure_to_fn::convert_closure_to_fn,
+            convert_closure_to_fn::convert_fn_to_closure,
+            convert_doctest_to_test::convert_doctest_to_test,
+            convert_for_loop_to_iterator_chain::convert_for_loop_to_iterator_chain,
+            convert_free_fn_to_method::convert_free_fn_to_method,
             convert_integer_literal::convert_integer_literal,
+            convert_method_to_function::convert_method_to_assoc_fn,
+            convert_method_to_function::convert_method_to_free_fn,
+            convert_named_struct_to_tuple_struct::convert_named_struct_to_tuple_struct,
+            convert_string_concat_to_format::convert_string_concat_to_format,
+            convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
+            destructure_binding::destructure_binding,
+            destructure_local::destructure_local,
             early_return::convert_to_guarded_return,
             expand_glob_import::expand_glob_import,
+            expand_wildcard_arm::expand_wildcard_arm,
+            expand_wildcard_arm::replace_wildcard_with_variant_arms,
+            expand_wildcard_arm::add_variant_arms_before_wildcard,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
+            extract_struct_from_function_parameters::extract_struct_from_function_parameters,
+            fill_expression::fill_expression,
             fill_match_arms::fill_match_arms,
             fix_visibility::fix_visibility,
             flip_binexpr::flip_binexpr,
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
+            generate_builder::generate_builder,
+            generate_debug_impl::generate_debug_impl,
             generate_default_from_enum_variant::generate_default_from_enum_variant,
+            generate_default_from_new::generate_default_from_new,
+            generate_delegate_methods::generate_delegate_methods,
             generate_derive::generate_derive,
+            generate_enum_helper_methods::generate_enum_helper_methods,
             generate_enum_is_method::generate_enum_is_method,
             generate_enum_projection_method::generate_enum_as_method,
             generate_enum_projection_method::generate_enum_try_into_method,
+            generate_enum_variant::generate_enum_variant,
+            generate_extern_c_wrapper::generate_extern_c_wrapper,
             generate_from_impl_for_enum::generate_from_impl_for_enum,
+            generate_from_impl_for_enum::generate_try_from_impls_for_enum,
             generate_function::generate_function,
+            generate_function::generate_method,
             generate_getter_mut::generate_getter_mut,
             generate_getter::generate_getter,
             generate_impl::generate_impl,
             generate_new::generate_new,
+            generate_new_from_default::generate_new_from_default,
             generate_setter::generate_setter,
             infer_function_return_type::infer_function_return_type,
             inline_function::inline_function,
+            inline_function::inline_into_callers,
             inline_local_variable::inline_local_variable,
+            inline_type_alias::inline_type_alias,
+            inline_type_alias::inline_type_alias_uses,
             introduce_named_lifetime::introduce_named_lifetime,
             invert_if::invert_if,
             merge_imports::merge_imports,
@@ -209,7 +276,9 @@ mod handlers {
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
             move_module_to_file::move_module_to_file,
+            move_to_module::move_to_module,
             pull_assignment_up::pull_assignment_up,
+            qualify_imported_name::qualify_imported_name,
             qualify_path::qualify_path,
             raw_string::add_hash,
             raw_string::make_usual_string,
@@ -221,12 +290,19 @@ mod handlers {
             reorder_impl::reorder_impl,
             replace_derive_with_manual_impl::replace_derive_with_manual_impl,
             replace_for_loop_with_for_each::replace_for_loop_with_for_each,
+            replace_if_let_with_combinator::replace_combinator_with_if_let,
+            replace_if_let_with_combinator::replace_if_let_with_map_or_else,
+            replace_if_let_with_combinator::replace_if_let_with_map_unwrap_or_else,
             replace_if_let_with_match::replace_if_let_with_match,
             replace_if_let_with_match::replace_match_with_if_let,
             replace_impl_trait_with_generic::replace_impl_trait_with_generic,
             replace_let_with_if_let::replace_let_with_if_let,
+            replace_match_with_matches::replace_match_with_matches,
+            replace_match_with_matches::replace_matches_with_match,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             replace_unwrap_with_match::replace_unwrap_with_match,
+            replace_while_let_with_for::replace_while_let_with_for,
+            sort_items::sort_items,
             split_import::split_import,
             toggle_ignore::toggle_ignore,
             unmerge_use::unmerge_use,
@@ -244,6 +320,8 @@ mod handlers {
             //
             extract_variable::extract_variable,
             extract_function::extract_function,
+            extract_module::extract_module,
+            extract_module::extract_module_to_file,
             // Are you sure you want to add new assist here, and not to the
             // sorted list above?
         ]
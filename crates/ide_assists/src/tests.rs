@@ -193,6 +193,10 @@ fn assist_order_field_struct() {
     assert_eq!(assists.next().expect("expected assist").label, "Generate a mut getter method");
     assert_eq!(assists.next().expect("expected assist").label, "Generate a getter method");
     assert_eq!(assists.next().expect("expected assist").label, "Generate a setter method");
+    assert_eq!(
+        assists.next().expect("expected assist").label,
+        "Generate `Debug` impl redacting `bar`"
+    );
     assert_eq!(assists.next().expect("expected assist").label, "Add `#[derive]`");
 }
 
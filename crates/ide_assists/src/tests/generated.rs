@@ -2,6 +2,66 @@
 
 use super::check_doc_test;
 
+#[test]
+fn doctest_add_enum_repr() {
+    check_doc_test(
+        "add_enum_repr",
+        r#####"
+enum Color$0 {
+    Red,
+    Green,
+}
+"#####,
+        r#####"
+#[repr(u8)]
+enum Color {
+    Red = 0,
+    Green = 1,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_explicit_closure_types() {
+    check_doc_test(
+        "add_explicit_closure_types",
+        r#####"
+fn main() {
+    let double = |$0x| x * 2;
+    double(21);
+}
+"#####,
+        r#####"
+fn main() {
+    let double = |x: i32| -> i32 { x * 2 };
+    double(21);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_explicit_enum_discriminants() {
+    check_doc_test(
+        "add_explicit_enum_discriminants",
+        r#####"
+enum Color$0 {
+    Red,
+    Green = 4,
+    Blue,
+}
+"#####,
+        r#####"
+enum Color {
+    Red = 0,
+    Green = 4,
+    Blue = 5,
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_explicit_type() {
     check_doc_test(
@@ -122,6 +182,53 @@ struct Point<'a> {
     )
 }
 
+#[test]
+fn doctest_add_serde_field_renames() {
+    check_doc_test(
+        "add_serde_field_renames",
+        r#####"
+#[derive(Deserialize)]
+struct Config$0 {
+    max_size: u32,
+    #[serde(rename = "minSize")]
+    min_size: u32,
+}
+"#####,
+        r#####"
+#[derive(Deserialize)]
+struct Config {
+    #[serde(rename = "maxSize")]
+    max_size: u32,
+    #[serde(rename = "minSize")]
+    min_size: u32,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_serde_rename_all() {
+    check_doc_test(
+        "add_serde_rename_all",
+        r#####"
+#[derive(Serialize)]
+struct Config$0 {
+    #[serde(rename = "maxSize")]
+    max_size: u32,
+    min_size: u32,
+}
+"#####,
+        r#####"
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Config {
+    max_size: u32,
+    min_size: u32,
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_turbo_fish() {
     check_doc_test(
@@ -141,6 +248,35 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_add_variant_arms_before_wildcard() {
+    check_doc_test(
+        "add_variant_arms_before_wildcard",
+        r#####"
+enum Action { Move { distance: u32 }, Jump, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Stop => {}
+        $0_ => {}
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Jump, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Stop => {}
+        Action::Move { distance } => {}
+        Action::Jump => {}
+        _ => {}
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_apply_demorgan() {
     check_doc_test(
@@ -192,6 +328,156 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_closure_to_fn() {
+    check_doc_test(
+        "convert_closure_to_fn",
+        r#####"
+struct Counter(u32);
+
+fn main() {
+    let counter = Counter(0);
+    let add = |$0x: u32| counter.0 + x;
+    add(1);
+}
+"#####,
+        r#####"
+struct Counter(u32);
+
+fn main() {
+    let counter = Counter(0);
+    add(1, &counter);
+}
+
+fn add(x: u32, counter: &Counter) -> u32 {
+    counter.0 + x
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_doctest_to_test() {
+    check_doc_test(
+        "convert_doctest_to_test",
+        r#####"
+/// ```
+/// let x = $0double(2);
+/// assert_eq!(x, 4);
+/// ```
+fn double(x: i32) -> i32 { x * 2 }
+"#####,
+        r#####"
+/// ```
+/// let x = double(2);
+/// assert_eq!(x, 4);
+/// ```
+fn double(x: i32) -> i32 { x * 2 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn double_doctest() {
+        let x = double(2);
+        assert_eq!(x, 4);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_fn_to_closure() {
+    check_doc_test(
+        "convert_fn_to_closure",
+        r#####"
+fn double(x: u32) -> u32 { x * 2 }
+
+fn apply(f: impl Fn(u32) -> u32) {}
+
+fn main() {
+    apply(double$0);
+}
+"#####,
+        r#####"
+fn apply(f: impl Fn(u32) -> u32) {}
+
+fn main() {
+    apply(|x| x * 2);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_for_loop_to_iterator_chain() {
+    check_doc_test(
+        "convert_for_loop_to_iterator_chain",
+        r#####"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Vec<T> { loop {} }
+    fn push(&mut self, item: T) {}
+}
+struct Order { price: u32 }
+fn prices(orders: Vec<Order>) -> Vec<u32> {
+    let mut prices = Vec::new();
+    for$0 order in orders {
+        prices.push(order.price);
+    }
+    prices
+}
+"#####,
+        r#####"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Vec<T> { loop {} }
+    fn push(&mut self, item: T) {}
+}
+struct Order { price: u32 }
+fn prices(orders: Vec<Order>) -> Vec<u32> {
+    let prices: Vec<_> = orders.into_iter().map(|order| order.price).collect();
+    prices
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_free_fn_to_method() {
+    check_doc_test(
+        "convert_free_fn_to_method",
+        r#####"
+struct Point { x: f64, y: f64 }
+
+fn $0norm(p: &Point) -> f64 {
+    (p.x * p.x + p.y * p.y).sqrt()
+}
+
+fn main() {
+    let p = Point { x: 3.0, y: 4.0 };
+    norm(&p);
+}
+"#####,
+        r#####"
+struct Point { x: f64, y: f64 }
+
+impl Point {
+    fn norm(&self) -> f64 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+}
+
+fn main() {
+    let p = Point { x: 3.0, y: 4.0 };
+    p.norm();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_integer_literal() {
     check_doc_test(
@@ -205,6 +491,121 @@ const _: i32 = 0b1010;
     )
 }
 
+#[test]
+fn doctest_convert_method_to_assoc_fn() {
+    check_doc_test(
+        "convert_method_to_assoc_fn",
+        r#####"
+struct Point { x: f64, y: f64 }
+
+impl Point {
+    fn $0dist(&self, other: &Point) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+}
+
+fn main() {
+    let p = Point { x: 0.0, y: 0.0 };
+    p.dist(&p);
+}
+"#####,
+        r#####"
+struct Point { x: f64, y: f64 }
+
+impl Point {
+    fn dist(point: &Point, other: &Point) -> f64 {
+        ((point.x - other.x).powi(2) + (point.y - other.y).powi(2)).sqrt()
+    }
+}
+
+fn main() {
+    let p = Point { x: 0.0, y: 0.0 };
+    Point::dist(&p, &p);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_method_to_free_fn() {
+    check_doc_test(
+        "convert_method_to_free_fn",
+        r#####"
+struct Point { x: f64, y: f64 }
+
+impl Point {
+    fn $0norm(&self) -> f64 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+}
+
+fn main() {
+    let p = Point { x: 3.0, y: 4.0 };
+    p.norm();
+}
+"#####,
+        r#####"
+struct Point { x: f64, y: f64 }
+
+impl Point {
+}
+
+fn norm(point: &Point) -> f64 {
+    (point.x * point.x + point.y * point.y).sqrt()
+}
+
+fn main() {
+    let p = Point { x: 3.0, y: 4.0 };
+    norm(&p);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check_doc_test(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct Poin$0t { x: f32, y: f32 }
+
+fn origin() -> Point { Point { x: 0.0, y: 0.0 } }
+fn x(point: &Point) -> f32 { point.x }
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+fn origin() -> Point { Point(0.0, 0.0) }
+fn x(point: &Point) -> f32 { point.0 }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_string_concat_to_format() {
+    check_doc_test(
+        "convert_string_concat_to_format",
+        r#####"
+#[lang = "add"]
+trait Add<Rhs> { type Output; }
+struct String;
+impl Add<&str> for String { type Output = String; }
+fn greet(name: String, punct: &str) -> String {
+    name $0+ ", hello" + punct
+}
+"#####,
+        r#####"
+#[lang = "add"]
+trait Add<Rhs> { type Output; }
+struct String;
+impl Add<&str> for String { type Output = String; }
+fn greet(name: String, punct: &str) -> String {
+    format!("{name}, hello{punct}")
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -229,6 +630,77 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_tuple_struct_to_named_struct() {
+    check_doc_test(
+        "convert_tuple_struct_to_named_struct",
+        r#####"
+struct Poin$0t(Meters, f32);
+struct Meters(f32);
+
+fn x(point: &Point) -> f32 {
+    let Point(x, _) = point;
+    (x.0 + point.1) / 2.0
+}
+"#####,
+        r#####"
+struct Point { meters: Meters, field2: f32 }
+struct Meters(f32);
+
+fn x(point: &Point) -> f32 {
+    let Point { meters: x, field2: _ } = point;
+    (x.0 + point.field2) / 2.0
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_binding() {
+    check_doc_test(
+        "destructure_binding",
+        r#####"
+struct Point { x: f64, y: f64 }
+
+fn norm(p: Point) -> f64 {
+    let $0q = p;
+    (q.x * q.x + q.y * q.y).sqrt()
+}
+"#####,
+        r#####"
+struct Point { x: f64, y: f64 }
+
+fn norm(p: Point) -> f64 {
+    let Point { x, y } = p;
+    (x * x + y * y).sqrt()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_local() {
+    check_doc_test(
+        "destructure_local",
+        r#####"
+struct Meters(f64);
+struct Segment { length: Meters, name: u32 }
+
+fn f(segment: Segment) {
+    segment$0;
+}
+"#####,
+        r#####"
+struct Meters(f64);
+struct Segment { length: Meters, name: u32 }
+
+fn f(segment: Segment) {
+    let Segment { length: Meters(length), name } = segment;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_expand_glob_import() {
     check_doc_test(
@@ -239,45 +711,132 @@ mod foo {
     pub struct Baz;
 }
 
-use foo::*$0;
+use foo::*$0;
+
+fn qux(bar: Bar, baz: Baz) {}
+"#####,
+        r#####"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+use foo::{Baz, Bar};
+
+fn qux(bar: Bar, baz: Baz) {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_expand_wildcard_arm() {
+    check_doc_test(
+        "expand_wildcard_arm",
+        r#####"
+enum Action { Move { distance: u32 }, Jump, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Stop => {}
+        $0_ => {}
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Jump, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Stop => {}
+        Action::Move { distance } | Action::Jump => {}
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_function() {
+    check_doc_test(
+        "extract_function",
+        r#####"
+fn main() {
+    let n = 1;
+    $0let m = n + 2;
+    let k = m + n;$0
+    let g = 3;
+}
+"#####,
+        r#####"
+fn main() {
+    let n = 1;
+    fun_name(n);
+    let g = 3;
+}
+
+fn $0fun_name(n: i32) {
+    let m = n + 2;
+    let k = m + n;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_module() {
+    check_doc_test(
+        "extract_module",
+        r#####"
+struct Point(i32, i32);
+
+$0fn origin() -> Point {
+    Point(0, 0)
+}$0
 
-fn qux(bar: Bar, baz: Baz) {}
+fn main() {
+    let _ = origin();
+}
 "#####,
         r#####"
-mod foo {
-    pub struct Bar;
-    pub struct Baz;
+struct Point(i32, i32);
+
+mod modname {
+    use super::Point;
+
+    pub(super) fn origin() -> Point {
+        Point(0, 0)
+    }
 }
 
-use foo::{Baz, Bar};
+use modname::origin;
 
-fn qux(bar: Bar, baz: Baz) {}
+fn main() {
+    let _ = origin();
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_extract_function() {
+fn doctest_extract_module_to_file() {
     check_doc_test(
-        "extract_function",
+        "extract_module_to_file",
         r#####"
+$0fn double(x: i32) -> i32 {
+    x * 2
+}$0
+
 fn main() {
-    let n = 1;
-    $0let m = n + 2;
-    let k = m + n;$0
-    let g = 3;
+    let _ = double(2);
 }
 "#####,
         r#####"
-fn main() {
-    let n = 1;
-    fun_name(n);
-    let g = 3;
-}
+mod modname;
 
-fn $0fun_name(n: i32) {
-    let m = n + 2;
-    let k = m + n;
+use modname::double;
+
+fn main() {
+    let _ = double(2);
 }
 "#####,
     )
@@ -298,6 +857,37 @@ enum A { One(One) }
     )
 }
 
+#[test]
+fn doctest_extract_struct_from_function_parameters() {
+    check_doc_test(
+        "extract_struct_from_function_parameters",
+        r#####"
+fn draw_rect($0x: u32, y: u32$0, color: u32) {
+    let _ = (x, y, color);
+}
+
+fn main() {
+    draw_rect(1, 2, 3);
+}
+"#####,
+        r#####"
+struct DrawRectParams {
+    x: u32,
+    y: u32,
+}
+
+fn draw_rect(params: DrawRectParams, color: u32) {
+    let DrawRectParams { x, y } = params;
+    let _ = (x, y, color);
+}
+
+fn main() {
+    draw_rect(DrawRectParams { x: 1, y: 2 }, 3);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_variable() {
     check_doc_test(
@@ -316,6 +906,27 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_fill_expression() {
+    check_doc_test(
+        "fill_expression",
+        r#####"
+struct Point { x: i32, y: i32 }
+fn origin() -> Point { Point { x: 0, y: 0 } }
+fn main() {
+    let p: Point = todo$0!();
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32 }
+fn origin() -> Point { Point { x: 0, y: 0 } }
+fn main() {
+    let p: Point = origin();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_fill_match_arms() {
     check_doc_test(
@@ -399,6 +1010,25 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_flip_params_and_args() {
+    check_doc_test(
+        "flip_params_and_args",
+        r#####"
+fn foo(x: i32,$0 y: bool) {}
+fn main() {
+    foo(1, true);
+}
+"#####,
+        r#####"
+fn foo(y: bool, x: i32) {}
+fn main() {
+    foo(true, 1);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_flip_trait_bound() {
     check_doc_test(
@@ -412,6 +1042,78 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_generate_builder() {
+    check_doc_test(
+        "generate_builder",
+        r#####"
+pub struct Request$0 {
+    url: String,
+    retries: u32,
+}
+"#####,
+        r#####"
+pub struct Request {
+    url: String,
+    retries: u32,
+}
+
+pub struct RequestBuilder {
+    url: Option<String>,
+    retries: Option<u32>,
+}
+
+impl RequestBuilder {
+    pub fn new() -> Self {
+        Self { url: None, retries: None }
+    }
+
+    pub fn url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    pub fn build(self) -> Result<Request, &'static str> {
+        Ok(Request {
+            url: self.url.ok_or("`url` is not set")?,
+            retries: self.retries.ok_or("`retries` is not set")?,
+        })
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_debug_impl() {
+    check_doc_test(
+        "generate_debug_impl",
+        r#####"
+struct Login {
+    user: String,
+    pass$0word: String,
+}
+"#####,
+        r#####"
+struct Login {
+    user: String,
+    password: String,
+}
+
+impl core::fmt::Debug for Login {
+    $0fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Login").field("user", &self.user).field("password", &"<redacted>").finish()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_default_from_enum_variant() {
     check_doc_test(
@@ -439,6 +1141,74 @@ impl Default for Version {
     )
 }
 
+#[test]
+fn doctest_generate_default_from_new() {
+    check_doc_test(
+        "generate_default_from_new",
+        r#####"
+struct Example { _inner: () }
+
+impl Example {
+    pub fn n$0ew() -> Self {
+        Self { _inner: () }
+    }
+}
+"#####,
+        r#####"
+struct Example { _inner: () }
+
+impl Example {
+    pub fn new() -> Self {
+        Self { _inner: () }
+    }
+}
+
+impl Default for Example {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_delegate_methods() {
+    check_doc_test(
+        "generate_delegate_methods",
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+struct Person {
+    ag$0e: Age,
+}
+"#####,
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn age(&self) -> u8 {
+        self.age.age()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_derive() {
     check_doc_test(
@@ -488,6 +1258,48 @@ impl Value {
     )
 }
 
+#[test]
+fn doctest_generate_enum_helper_methods() {
+    check_doc_test(
+        "generate_enum_helper_methods",
+        r#####"
+enum Value$0 {
+    Number(i32),
+    Nothing,
+}
+"#####,
+        r#####"
+enum Value {
+    Number(i32),
+    Nothing,
+}
+
+impl Value {
+    /// Returns `true` if the value is [`Number`].
+    fn is_number(&self) -> bool {
+        matches!(self, Self::Number(..))
+    }
+
+    fn as_number(&self) -> Option<&i32> {
+        if let Self::Number(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    fn try_into_number(self) -> Result<i32, Self> {
+        if let Self::Number(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_enum_is_method() {
     check_doc_test(
@@ -545,6 +1357,56 @@ impl Value {
     )
 }
 
+#[test]
+fn doctest_generate_enum_variant() {
+    check_doc_test(
+        "generate_enum_variant",
+        r#####"
+enum Shape { Circle(f64) }
+
+fn main() {
+    let square = Shape::Square$0(2.0);
+}
+"#####,
+        r#####"
+enum Shape { Circle(f64), Square(f64) }
+
+fn main() {
+    let square = Shape::Square(2.0);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_extern_c_wrapper() {
+    check_doc_test(
+        "generate_extern_c_wrapper",
+        r#####"
+pub fn count_$0words(text: &str, limit: u32) -> u32 {
+    0
+}
+"#####,
+        r#####"
+pub fn count_words(text: &str, limit: u32) -> u32 {
+    0
+}
+
+/// C-compatible wrapper for [`count_words`].
+///
+/// # Safety
+///
+/// `text_ptr` must point to `text_len` bytes of valid UTF-8.
+#[no_mangle]
+pub unsafe extern "C" fn $0count_words_ffi(text_ptr: *const u8, text_len: usize, limit: u32) -> u32 {
+    // SAFETY: `text_ptr` points to `text_len` bytes of valid UTF-8, as required above.
+    let text = std::str::from_utf8_unchecked(std::slice::from_raw_parts(text_ptr, text_len));
+    count_words(text, limit)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_from_impl_for_enum() {
     check_doc_test(
@@ -625,57 +1487,113 @@ struct Person {
 }
 "#####,
         r#####"
-struct Person {
-    name: String,
-}
+struct Person {
+    name: String,
+}
+
+impl Person {
+    /// Get a mutable reference to the person's name.
+    fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_impl() {
+    check_doc_test(
+        "generate_impl",
+        r#####"
+struct Ctx<T: Clone> {
+    data: T,$0
+}
+"#####,
+        r#####"
+struct Ctx<T: Clone> {
+    data: T,
+}
+
+impl<T: Clone> Ctx<T> {
+    $0
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_method() {
+    check_doc_test(
+        "generate_method",
+        r#####"
+struct Counter { count: u32 }
+fn reset(counter: &mut Counter) {
+    let previous: u32 = counter.take$0(0);
+}
+"#####,
+        r#####"
+struct Counter { count: u32 }
 
-impl Person {
-    /// Get a mutable reference to the person's name.
-    fn name_mut(&mut self) -> &mut String {
-        &mut self.name
+impl Counter {
+    fn take(&self, arg: i32) -> u32 {
+        ${0:todo!()}
     }
 }
+fn reset(counter: &mut Counter) {
+    let previous: u32 = counter.take(0);
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_generate_impl() {
+fn doctest_generate_new() {
     check_doc_test(
-        "generate_impl",
+        "generate_new",
         r#####"
 struct Ctx<T: Clone> {
-    data: T,$0
+     data: T,$0
 }
 "#####,
         r#####"
 struct Ctx<T: Clone> {
-    data: T,
+     data: T,
 }
 
 impl<T: Clone> Ctx<T> {
-    $0
+    fn $0new(data: T) -> Self { Self { data } }
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_generate_new() {
+fn doctest_generate_new_from_default() {
     check_doc_test(
-        "generate_new",
+        "generate_new_from_default",
         r#####"
-struct Ctx<T: Clone> {
-     data: T,$0
+pub struct Config { verbose: bool }
+
+impl Def$0ault for Config {
+    fn default() -> Self {
+        Config { verbose: false }
+    }
 }
 "#####,
         r#####"
-struct Ctx<T: Clone> {
-     data: T,
+pub struct Config { verbose: bool }
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { verbose: false }
+    }
 }
 
-impl<T: Clone> Ctx<T> {
-    fn $0new(data: T) -> Self { Self { data } }
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
 }
 "#####,
     )
@@ -705,6 +1623,41 @@ impl Person {
     )
 }
 
+#[test]
+fn doctest_generate_try_from_impls_for_enum() {
+    check_doc_test(
+        "generate_try_from_impls_for_enum",
+        r#####"
+enum Value$0 { Int(i64), Text(String) }
+"#####,
+        r#####"
+enum Value { Int(i64), Text(String) }
+
+impl TryFrom<Value> for i64 {
+    type Error = Value;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Int(v) => Ok(v),
+            _ => Err(value),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = Value;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(v) => Ok(v),
+            _ => Err(value),
+        }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_infer_function_return_type() {
     check_doc_test(
@@ -731,11 +1684,27 @@ fn main() {
         r#####"
 fn add(a: u32, b: u32) -> u32 { a + b }
 fn main() {
-    let x = {
-        let a = 1;
-        let b = 2;
-        a + b
-    };
+    let x = 1 + 2;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_into_callers() {
+    check_doc_test(
+        "inline_into_callers",
+        r#####"
+fn $0double(x: u32) -> u32 { x * 2 }
+fn main() {
+    let a = double(1);
+    let b = double(a);
+}
+"#####,
+        r#####"
+fn main() {
+    let a = 1 * 2;
+    let b = a * 2;
 }
 "#####,
     )
@@ -759,6 +1728,38 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_type_alias() {
+    check_doc_test(
+        "inline_type_alias",
+        r#####"
+type Pair<T> = (T, T);
+
+fn swap(pair: Pai$0r<u8>) {}
+"#####,
+        r#####"
+type Pair<T> = (T, T);
+
+fn swap(pair: (u8, u8)) {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_type_alias_uses() {
+    check_doc_test(
+        "inline_type_alias_uses",
+        r#####"
+type $0Meters = f64;
+
+fn walk(distance: Meters) -> Meters { distance }
+"#####,
+        r#####"
+fn walk(distance: f64) -> f64 { distance }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_lifetime() {
     check_doc_test(
@@ -963,6 +1964,37 @@ mod foo;
     )
 }
 
+#[test]
+fn doctest_move_to_module() {
+    check_doc_test(
+        "move_to_module",
+        r#####"
+mod shapes {}
+
+fn are$0a(width: u32, height: u32) -> u32 {
+    width * height
+}
+
+fn main() {
+    let _ = area(1, 2);
+}
+"#####,
+        r#####"
+use shapes::area;
+
+mod shapes {
+    pub(crate) fn area(width: u32, height: u32) -> u32 {
+        width * height
+    }
+}
+
+fn main() {
+    let _ = area(1, 2);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_pull_assignment_up() {
     check_doc_test(
@@ -992,6 +2024,25 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_qualify_imported_name() {
+    check_doc_test(
+        "qualify_imported_name",
+        r#####"
+mod geometry { pub struct Point; }
+use geometry::Point;
+
+fn distance(a: Poi$0nt) {}
+"#####,
+        r#####"
+mod geometry { pub struct Point; }
+use geometry::Point;
+
+fn distance(a: geometry::Point) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_qualify_path() {
     check_doc_test(
@@ -1133,6 +2184,39 @@ impl Foo for Bar {
     )
 }
 
+#[test]
+fn doctest_replace_combinator_with_if_let() {
+    check_doc_test(
+        "replace_combinator_with_if_let",
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+impl<T> Option<T> {
+    fn map_or_else<U, D, F>(self, default: D, f: F) -> U { loop {} }
+}
+
+fn describe(name: Option<&str>) -> usize {
+    name.$0map_or_else(|| 0, |name| name.len())
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+impl<T> Option<T> {
+    fn map_or_else<U, D, F>(self, default: D, f: F) -> U { loop {} }
+}
+
+fn describe(name: Option<&str>) -> usize {
+    if let Some(name) = name {
+        name.len()
+    } else {
+        0
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_derive_with_manual_impl() {
     check_doc_test(
@@ -1140,16 +2224,16 @@ fn doctest_replace_derive_with_manual_impl() {
         r#####"
 trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
 #[derive(Deb$0ug, Display)]
-struct S;
+struct S { x: u32 }
 "#####,
         r#####"
 trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
 #[derive(Display)]
-struct S;
+struct S { x: u32 }
 
 impl Debug for S {
-    fn fmt(&self, f: &mut Formatter) -> Result<()> {
-        ${0:todo!()}
+    $0fn fmt(&self, f: &mut Formatter) -> Result<()> {
+        f.debug_struct("S").field("x", &self.x).finish()
     }
 }
 "#####,
@@ -1179,6 +2263,60 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_replace_if_let_with_map_or_else() {
+    check_doc_test(
+        "replace_if_let_with_map_or_else",
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn describe(name: Option<&str>) -> usize {
+    $0if let Some(name) = name {
+        name.len()
+    } else {
+        0
+    }
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn describe(name: Option<&str>) -> usize {
+    name.map_or_else(|| 0, |name| name.len())
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_if_let_with_map_unwrap_or_else() {
+    check_doc_test(
+        "replace_if_let_with_map_unwrap_or_else",
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn describe(name: Option<&str>) -> usize {
+    $0if let Some(name) = name {
+        name.len()
+    } else {
+        0
+    }
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn describe(name: Option<&str>) -> usize {
+    name.map(|name| name.len()).unwrap_or_else(|| 0)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_if_let_with_match() {
     check_doc_test(
@@ -1274,6 +2412,54 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_replace_match_with_matches() {
+    check_doc_test(
+        "replace_match_with_matches",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn is_long_move(action: Action) -> bool {
+    $0match action {
+        Action::Move { distance } if distance > 10 => true,
+        _ => false,
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn is_long_move(action: Action) -> bool {
+    matches!(action, Action::Move { distance } if distance > 10)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_matches_with_match() {
+    check_doc_test(
+        "replace_matches_with_match",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn is_long_move(action: Action) -> bool {
+    $0matches!(action, Action::Move { distance } if distance > 10)
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn is_long_move(action: Action) -> bool {
+    match action {
+        Action::Move { distance } if distance > 10 => true,
+        _ => false,
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_qualified_name_with_use() {
     check_doc_test(
@@ -1330,6 +2516,86 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_replace_while_let_with_for() {
+    check_doc_test(
+        "replace_while_let_with_for",
+        r#####"
+fn sum(numbers: &[u32]) -> u32 {
+    let mut total = 0;
+    let mut iter = numbers.iter();
+    $0while let Some(n) = iter.next() {
+        total += n;
+    }
+    total
+}
+"#####,
+        r#####"
+fn sum(numbers: &[u32]) -> u32 {
+    let mut total = 0;
+    let mut iter = numbers.iter();
+    for n in iter {
+        total += n;
+    }
+    total
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_wildcard_with_variant_arms() {
+    check_doc_test(
+        "replace_wildcard_with_variant_arms",
+        r#####"
+enum Action { Move { distance: u32 }, Jump, Stop }
+
+fn handle(action: Action) -> u32 {
+    match action {
+        Action::Stop => 0,
+        $0_ => 1,
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Jump, Stop }
+
+fn handle(action: Action) -> u32 {
+    match action {
+        Action::Stop => 0,
+        Action::Move { distance } => 1,
+        Action::Jump => 1,
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_sort_items() {
+    check_doc_test(
+        "sort_items",
+        r#####"
+struct Bar;
+$0impl Bar {
+    fn c() {}
+    /// Comes first.
+    fn a() {}
+    fn b() {}
+}
+"#####,
+        r#####"
+struct Bar;
+impl Bar {
+    /// Comes first.
+    fn a() {}
+    fn b() {}
+    fn c() {}
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_split_import() {
     check_doc_test(
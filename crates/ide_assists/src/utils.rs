@@ -1,11 +1,15 @@
 //! Assorted functions shared by several assists.
 
-use std::ops;
+pub(crate) mod gen_trait_fn_body;
+pub(crate) mod pretty;
+pub(crate) mod reorder;
+
+use std::{iter, ops};
 
 use ast::TypeBoundsOwner;
-use hir::{Adt, HasSource, Semantics};
+use hir::{Adt, HasSource, ModuleDef, Semantics};
 use ide_db::{
-    helpers::{FamousDefs, SnippetCap},
+    helpers::{mod_path_to_ast, FamousDefs, SnippetCap},
     RootDatabase,
 };
 use itertools::Itertools;
@@ -201,6 +205,22 @@ pub(crate) fn render_snippet(_cap: SnippetCap, node: &SyntaxNode, cursor: Cursor
     }
 }
 
+/// Turns each occurrence of `placeholder` in `buf` into a tabstop, in order, with the cursor
+/// ending up at the last one.
+pub(crate) fn placeholders_to_tabstops(_cap: SnippetCap, buf: &str, placeholder: &str) -> String {
+    let parts = buf.split(placeholder).collect::<Vec<_>>();
+    let mut res = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        res.push_str(part);
+        if i + 1 == parts.len() {
+            break;
+        }
+        let tab_stop = if i + 2 == parts.len() { 0 } else { i + 1 };
+        format_to!(res, "${{{}:{}}}", tab_stop, placeholder);
+    }
+    res
+}
+
 pub(crate) fn vis_offset(node: &SyntaxNode) -> TextSize {
     node.children_with_tokens()
         .find(|it| !matches!(it.kind(), WHITESPACE | COMMENT | ATTR))
@@ -282,6 +302,90 @@ fn bin_impls_ord(sema: &Semantics<RootDatabase>, bin: &ast::BinExpr) -> bool {
     }
 }
 
+/// Returns the offset and the text to insert to append `item` to `list`, a `{ .. }` list of
+/// comma-separated items like the fields of a struct or the variants of an enum.
+pub fn append_to_braced_list(list: &SyntaxNode, item: &str) -> Option<(TextSize, String)> {
+    let last = match list.children().last() {
+        Some(it) => it,
+        None => {
+            let l_curly = list.first_token().filter(|it| it.kind() == T!['{'])?;
+            let text =
+                if list.text() == "{}" { format!(" {} ", item) } else { format!(" {}", item) };
+            return Some((l_curly.text_range().end(), text));
+        }
+    };
+    let comma = last
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .find(|it| !matches!(it.kind(), WHITESPACE | COMMENT))
+        .filter(|it| it.kind() == T![,]);
+    let multiline = list.text().contains_char('\n');
+    let indent = edit::IndentLevel::from_node(&last);
+    let insert = match (comma, multiline) {
+        (Some(comma), true) => (comma.text_range().end(), format!("\n{}{},", indent, item)),
+        (None, true) => (last.text_range().end(), format!(",\n{}{}", indent, item)),
+        (Some(comma), false) => (comma.text_range().end(), format!(" {},", item)),
+        (None, false) => (last.text_range().end(), format!(", {}", item)),
+    };
+    Some(insert)
+}
+
+/// If iterable is a reference where the expression behind the reference implements a method
+/// returning an Iterator called iter or iter_mut (depending on the type of reference) then return
+/// the expression behind the reference and the method name
+pub(crate) fn is_ref_and_impls_iter_method(
+    sema: &hir::Semantics<ide_db::RootDatabase>,
+    iterable: &ast::Expr,
+) -> Option<(ast::Expr, hir::Name)> {
+    let ref_expr = match iterable {
+        ast::Expr::RefExpr(r) => r,
+        _ => return None,
+    };
+    let wanted_method =
+        if ref_expr.mut_token().is_some() { hir::known::iter_mut } else { hir::known::iter };
+    let expr_behind_ref = ref_expr.expr()?;
+    let typ = sema.type_of_expr(&expr_behind_ref)?;
+    let scope = sema.scope(iterable.syntax());
+    let krate = scope.module()?.krate();
+    let traits_in_scope = scope.traits_in_scope();
+    let iter_trait = FamousDefs(sema, Some(krate)).core_iter_Iterator()?;
+    let has_wanted_method = typ.iterate_method_candidates(
+        sema.db,
+        krate,
+        &traits_in_scope,
+        Some(&wanted_method),
+        |_, func| {
+            if func.ret_type(sema.db).impls_trait(sema.db, iter_trait, &[]) {
+                return Some(());
+            }
+            None
+        },
+    );
+    has_wanted_method.and(Some((expr_behind_ref, wanted_method)))
+}
+
+/// Whether iterable implements core::Iterator
+pub(crate) fn impls_core_iter(
+    sema: &hir::Semantics<ide_db::RootDatabase>,
+    iterable: &ast::Expr,
+) -> bool {
+    let it_typ = if let Some(i) = sema.type_of_expr(iterable) {
+        i
+    } else {
+        return false;
+    };
+    let module = if let Some(m) = sema.scope(iterable.syntax()).module() {
+        m
+    } else {
+        return false;
+    };
+    let krate = module.krate();
+    if let Some(iter_trait) = FamousDefs(sema, Some(krate)).core_iter_Iterator() {
+        return it_typ.impls_trait(sema.db, iter_trait, &[]);
+    }
+    false
+}
+
 pub(crate) fn next_prev() -> impl Iterator<Item = Direction> {
     [Direction::Next, Direction::Prev].iter().copied()
 }
@@ -305,6 +409,30 @@ pub(crate) fn does_pat_match_variant(pat: &ast::Pat, var: &ast::Pat) -> bool {
     pat_head == var_head
 }
 
+/// A pattern matching any value of `var`, with the path to it from `module`.
+pub(crate) fn build_pat(
+    db: &RootDatabase,
+    module: hir::Module,
+    var: hir::Variant,
+) -> Option<ast::Pat> {
+    let path = mod_path_to_ast(&module.find_use_path(db, ModuleDef::from(var))?);
+
+    // FIXME: use HIR for this; it doesn't currently expose struct vs. tuple vs. unit variants though
+    let pat: ast::Pat = match var.source(db)?.value.kind() {
+        ast::StructKind::Tuple(field_list) => {
+            let pats = iter::repeat(make::wildcard_pat().into()).take(field_list.fields().count());
+            make::tuple_struct_pat(path, pats).into()
+        }
+        ast::StructKind::Record(field_list) => {
+            let pats = field_list.fields().map(|f| make::ident_pat(f.name().unwrap()).into());
+            make::record_pat(path, pats).into()
+        }
+        ast::StructKind::Unit => make::path_pat(path),
+    };
+
+    Some(pat)
+}
+
 // Uses a syntax-driven approach to find any impl blocks for the struct that
 // exist within the module/file
 //
@@ -400,16 +528,49 @@ pub(crate) fn find_impl_block_end(impl_def: ast::Impl, buf: &mut String) -> Opti
 // Generates the surrounding `impl Type { <code> }` including type and lifetime
 // parameters
 pub(crate) fn generate_impl_text(adt: &ast::Adt, code: &str) -> String {
-    generate_impl_text_inner(adt, None, code)
+    generate_impl_text_inner(adt, None, None, code)
 }
 
 // Generates the surrounding `impl <trait> for Type { <code> }` including type
 // and lifetime parameters
 pub(crate) fn generate_trait_impl_text(adt: &ast::Adt, trait_text: &str, code: &str) -> String {
-    generate_impl_text_inner(adt, Some(trait_text), code)
+    generate_impl_text_inner(adt, Some(trait_text), None, code)
+}
+
+// Generates the surrounding `impl <trait> for <self_ty> { <code> }` including the
+// type and lifetime parameters of `adt`, which the trait or `self_ty` mention
+pub(crate) fn generate_trait_impl_text_for(
+    adt: &ast::Adt,
+    trait_text: &str,
+    self_ty: &str,
+    code: &str,
+) -> String {
+    generate_impl_text_inner(adt, Some(trait_text), Some(self_ty), code)
 }
 
-fn generate_impl_text_inner(adt: &ast::Adt, trait_text: Option<&str>, code: &str) -> String {
+// The type of `adt`, with its type and lifetime parameters, like `Type<'a, T>`
+pub(crate) fn adt_ty_text(adt: &ast::Adt) -> String {
+    let mut buf = adt.name().unwrap().text().to_string();
+    if let Some(generic_params) = adt.generic_param_list() {
+        let lifetime_params = generic_params
+            .lifetime_params()
+            .filter_map(|it| it.lifetime())
+            .map(|it| SmolStr::from(it.text()));
+        let type_params = generic_params
+            .type_params()
+            .filter_map(|it| it.name())
+            .map(|it| SmolStr::from(it.text()));
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).format(", "))
+    }
+    buf
+}
+
+fn generate_impl_text_inner(
+    adt: &ast::Adt,
+    trait_text: Option<&str>,
+    self_ty: Option<&str>,
+    code: &str,
+) -> String {
     let generic_params = adt.generic_param_list();
     let mut buf = String::with_capacity(code.len());
     buf.push_str("\n\n");
@@ -440,17 +601,9 @@ fn generate_impl_text_inner(adt: &ast::Adt, trait_text: Option<&str>, code: &str
         buf.push_str(trait_text);
         buf.push_str(" for ");
     }
-    buf.push_str(adt.name().unwrap().text());
-    if let Some(generic_params) = generic_params {
-        let lifetime_params = generic_params
-            .lifetime_params()
-            .filter_map(|it| it.lifetime())
-            .map(|it| SmolStr::from(it.text()));
-        let type_params = generic_params
-            .type_params()
-            .filter_map(|it| it.name())
-            .map(|it| SmolStr::from(it.text()));
-        format_to!(buf, "<{}>", lifetime_params.chain(type_params).format(", "))
+    match self_ty {
+        Some(self_ty) => buf.push_str(self_ty),
+        None => buf.push_str(&adt_ty_text(adt)),
     }
 
     match adt.where_clause() {
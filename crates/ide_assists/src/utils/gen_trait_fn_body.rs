@@ -0,0 +1,386 @@
+//! Generates the bodies of the methods of the builtin derivable traits, the way the derives would
+//! implement them for an ADT.
+
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::ast::{
+    self,
+    edit::{AstNodeEdit, IndentLevel},
+    AstNode, NameOwner,
+};
+
+/// Generates the body of `func`, the required method of the trait `trait_path` in an impl for
+/// `adt`, or returns `None` if the trait isn't a derivable one or can't be derived for `adt`.
+pub(crate) fn gen_trait_fn_body(
+    func: &ast::Fn,
+    trait_path: &ast::Path,
+    adt: &ast::Adt,
+) -> Option<ast::BlockExpr> {
+    let trait_name = trait_path.segment()?.name_ref()?.to_string();
+    let params = func
+        .param_list()?
+        .params()
+        .map(|param| match param.pat()? {
+            ast::Pat::IdentPat(it) => Some(it.name()?.to_string()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let shape = Shape::new(adt)?;
+    let fn_name = func.name()?.to_string();
+    let body = match (trait_name.as_str(), fn_name.as_str(), params.as_slice()) {
+        ("Clone", "clone", []) => gen_clone(&shape),
+        ("Debug", "fmt", [f]) => gen_debug(&shape, f, &|_, _| false),
+        ("Default", "default", []) => gen_default(&shape)?,
+        ("Hash", "hash", [state]) => gen_hash(&shape, state),
+        ("PartialEq", "eq", [other]) => gen_eq(&shape, other),
+        ("PartialOrd", "partial_cmp", [other]) => gen_cmp(&shape, other, "partial_cmp")?,
+        ("Ord", "cmp", [other]) => gen_cmp(&shape, other, "cmp")?,
+        _ => return None,
+    };
+    parse_body(&body)
+}
+
+/// Generates the body of `Debug::fmt` for `adt`, with the fields for which `redact` returns
+/// `true` printed as `<redacted>`. `redact` is given the name of the struct or variant, and the
+/// name of the field.
+pub(crate) fn gen_debug_fn_body(
+    adt: &ast::Adt,
+    f: &str,
+    redact: &dyn Fn(&str, &str) -> bool,
+) -> Option<ast::BlockExpr> {
+    parse_body(&gen_debug(&Shape::new(adt)?, f, redact))
+}
+
+fn parse_body(body: &str) -> Option<ast::BlockExpr> {
+    let text = format!("fn f() {{\n{}}}", body);
+    let block = ast::SourceFile::parse(&text)
+        .tree()
+        .syntax()
+        .descendants()
+        .find_map(ast::BlockExpr::cast)?;
+    Some(block.indent(IndentLevel(1)))
+}
+
+enum Shape {
+    Struct(String, Fields),
+    Enum(Vec<(String, Fields)>),
+}
+
+enum Fields {
+    Record(Vec<String>),
+    Tuple(usize),
+    Unit,
+}
+
+impl Shape {
+    fn new(adt: &ast::Adt) -> Option<Shape> {
+        let shape = match adt {
+            ast::Adt::Struct(it) => {
+                Shape::Struct(it.name()?.to_string(), Fields::new(it.field_list())?)
+            }
+            ast::Adt::Enum(it) => Shape::Enum(
+                it.variant_list()?
+                    .variants()
+                    .map(|variant| {
+                        Some((variant.name()?.to_string(), Fields::new(variant.field_list())?))
+                    })
+                    .collect::<Option<_>>()?,
+            ),
+            ast::Adt::Union(_) => return None,
+        };
+        Some(shape)
+    }
+}
+
+impl Fields {
+    fn new(field_list: Option<ast::FieldList>) -> Option<Fields> {
+        let fields = match field_list {
+            Some(ast::FieldList::RecordFieldList(it)) => Fields::Record(
+                it.fields().map(|it| Some(it.name()?.to_string())).collect::<Option<_>>()?,
+            ),
+            Some(ast::FieldList::TupleFieldList(it)) => Fields::Tuple(it.fields().count()),
+            None => Fields::Unit,
+        };
+        Some(fields)
+    }
+
+    fn is_unit(&self) -> bool {
+        matches!(self, Fields::Unit)
+    }
+
+    /// The names of the fields, like `a` or `0`.
+    fn names(&self) -> Vec<String> {
+        match self {
+            Fields::Record(names) => names.clone(),
+            Fields::Tuple(n) => (0..*n).map(|idx| idx.to_string()).collect(),
+            Fields::Unit => Vec::new(),
+        }
+    }
+
+    /// A pattern for `path` binding the fields to their names prefixed with `prefix`, or to
+    /// `arg0`, `arg1`... for tuple fields without a prefix.
+    fn pattern(&self, path: &str, prefix: &str) -> String {
+        let bindings = self.bindings(prefix);
+        match self {
+            Fields::Record(names) if prefix.is_empty() => {
+                format!("{} {{ {} }}", path, names.iter().format(", "))
+            }
+            Fields::Record(names) => format!(
+                "{} {{ {} }}",
+                path,
+                names
+                    .iter()
+                    .zip(&bindings)
+                    .map(|(name, it)| format!("{}: {}", name, it))
+                    .format(", ")
+            ),
+            Fields::Tuple(_) => format!("{}({})", path, bindings.iter().format(", ")),
+            Fields::Unit => path.to_string(),
+        }
+    }
+
+    /// The names `pattern` binds the fields to.
+    fn bindings(&self, prefix: &str) -> Vec<String> {
+        match self {
+            Fields::Record(names) => names.iter().map(|it| format!("{}{}", prefix, it)).collect(),
+            Fields::Tuple(n) if prefix.is_empty() => {
+                (0..*n).map(|idx| format!("arg{}", idx)).collect()
+            }
+            Fields::Tuple(n) => (0..*n).map(|idx| format!("{}{}", prefix, idx)).collect(),
+            Fields::Unit => Vec::new(),
+        }
+    }
+
+    /// An expression constructing `path` from the values of the fields.
+    fn construct(&self, path: &str, values: &[String]) -> String {
+        match self {
+            Fields::Record(names) => format!(
+                "{} {{ {} }}",
+                path,
+                names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .format(", ")
+            ),
+            Fields::Tuple(_) => format!("{}({})", path, values.iter().format(", ")),
+            Fields::Unit => path.to_string(),
+        }
+    }
+}
+
+fn self_fields(fields: &Fields, receiver: &str) -> Vec<String> {
+    fields.names().iter().map(|name| format!("{}.{}", receiver, name)).collect()
+}
+
+fn gen_match(scrutinee: &str, arms: impl IntoIterator<Item = (String, String)>) -> String {
+    let mut buf = format!("    match {} {{\n", scrutinee);
+    for (pat, expr) in arms {
+        format_to!(buf, "        {} => {},\n", pat, expr);
+    }
+    buf.push_str("    }\n");
+    buf
+}
+
+fn gen_clone(shape: &Shape) -> String {
+    match shape {
+        Shape::Struct(_, fields) => {
+            let values = self_fields(fields, "self")
+                .iter()
+                .map(|it| format!("{}.clone()", it))
+                .collect::<Vec<_>>();
+            format!("    {}\n", fields.construct("Self", &values))
+        }
+        Shape::Enum(variants) if variants.is_empty() => "    match *self {}\n".to_string(),
+        Shape::Enum(variants) => gen_match(
+            "self",
+            variants.iter().map(|(name, fields)| {
+                let path = format!("Self::{}", name);
+                let values = fields
+                    .bindings("")
+                    .iter()
+                    .map(|it| format!("{}.clone()", it))
+                    .collect::<Vec<_>>();
+                (fields.pattern(&path, ""), fields.construct(&path, &values))
+            }),
+        ),
+    }
+}
+
+const REDACTED: &str = "&\"<redacted>\"";
+
+fn gen_debug(shape: &Shape, f: &str, redact: &dyn Fn(&str, &str) -> bool) -> String {
+    let debug = |name: &str, fields: &Fields, values: Vec<String>| match fields {
+        Fields::Record(names) => {
+            let mut buf = format!("{}.debug_struct(\"{}\")", f, name);
+            for (name, value) in names.iter().zip(values) {
+                format_to!(buf, ".field(\"{}\", {})", name, value);
+            }
+            buf + ".finish()"
+        }
+        Fields::Tuple(_) => {
+            let mut buf = format!("{}.debug_tuple(\"{}\")", f, name);
+            for value in values {
+                format_to!(buf, ".field({})", value);
+            }
+            buf + ".finish()"
+        }
+        Fields::Unit => format!("{}.write_str(\"{}\")", f, name),
+    };
+    match shape {
+        Shape::Struct(name, fields) => {
+            let values = fields
+                .names()
+                .iter()
+                .zip(self_fields(fields, "&self"))
+                .map(
+                    |(field, value)| if redact(name, field) { REDACTED.to_string() } else { value },
+                )
+                .collect();
+            format!("    {}\n", debug(name, fields, values))
+        }
+        Shape::Enum(variants) if variants.is_empty() => "    match *self {}\n".to_string(),
+        Shape::Enum(variants) => gen_match(
+            "self",
+            variants.iter().map(|(name, fields)| {
+                let path = format!("Self::{}", name);
+                let redacted =
+                    fields.names().iter().map(|field| redact(name, field)).collect::<Vec<_>>();
+                if !redacted.contains(&true) {
+                    return (fields.pattern(&path, ""), debug(name, fields, fields.bindings("")));
+                }
+                // Redacted fields aren't bound, so that they don't show up as unused.
+                let bindings = fields
+                    .bindings("")
+                    .into_iter()
+                    .zip(&redacted)
+                    .map(|(it, &redacted)| if redacted { "_".to_string() } else { it })
+                    .collect::<Vec<_>>();
+                let pat = match fields {
+                    Fields::Record(names) => format!(
+                        "{} {{ {} }}",
+                        path,
+                        names
+                            .iter()
+                            .zip(&bindings)
+                            .map(|(name, it)| {
+                                if it == "_" {
+                                    format!("{}: _", name)
+                                } else {
+                                    name.clone()
+                                }
+                            })
+                            .format(", ")
+                    ),
+                    _ => format!("{}({})", path, bindings.iter().format(", ")),
+                };
+                let values = bindings
+                    .into_iter()
+                    .map(|it| if it == "_" { REDACTED.to_string() } else { it })
+                    .collect();
+                (pat, debug(name, fields, values))
+            }),
+        ),
+    }
+}
+
+fn gen_default(shape: &Shape) -> Option<String> {
+    match shape {
+        Shape::Struct(_, fields) => {
+            let values = vec!["Default::default()".to_string(); fields.names().len()];
+            Some(format!("    {}\n", fields.construct("Self", &values)))
+        }
+        Shape::Enum(_) => None,
+    }
+}
+
+fn gen_hash(shape: &Shape, state: &str) -> String {
+    let hash = |values: Vec<String>, indent: &str| {
+        values.iter().map(|it| format!("{}{}.hash({});\n", indent, it, state)).collect::<String>()
+    };
+    match shape {
+        Shape::Struct(_, fields) => hash(self_fields(fields, "self"), "    "),
+        Shape::Enum(variants) => {
+            let mut buf = format!("    core::mem::discriminant(self).hash({});\n", state);
+            if variants.iter().any(|(_, fields)| !fields.is_unit()) {
+                buf.push_str("    match self {\n");
+                for (name, fields) in variants {
+                    let pat = fields.pattern(&format!("Self::{}", name), "");
+                    match fields {
+                        Fields::Unit => format_to!(buf, "        {} => {{}}\n", pat),
+                        _ => format_to!(
+                            buf,
+                            "        {} => {{\n{}        }}\n",
+                            pat,
+                            hash(fields.bindings(""), "            ")
+                        ),
+                    }
+                }
+                buf.push_str("    }\n");
+            }
+            buf
+        }
+    }
+}
+
+fn gen_eq(shape: &Shape, other: &str) -> String {
+    let eq = |lhs: Vec<String>, rhs: Vec<String>| {
+        if lhs.is_empty() {
+            return "true".to_string();
+        }
+        lhs.iter().zip(rhs).map(|(l, r)| format!("{} == {}", l, r)).join(" && ")
+    };
+    let discriminants =
+        format!("core::mem::discriminant(self) == core::mem::discriminant({})", other);
+    match shape {
+        Shape::Struct(_, fields) => {
+            format!("    {}\n", eq(self_fields(fields, "self"), self_fields(fields, other)))
+        }
+        Shape::Enum(variants) if variants.iter().all(|(_, fields)| fields.is_unit()) => {
+            format!("    {}\n", discriminants)
+        }
+        Shape::Enum(variants) => {
+            let arms =
+                variants.iter().filter(|(_, fields)| !fields.is_unit()).map(|(name, fields)| {
+                    let path = format!("Self::{}", name);
+                    let pat = format!(
+                        "({}, {})",
+                        fields.pattern(&path, "l_"),
+                        fields.pattern(&path, "r_")
+                    );
+                    (pat, eq(fields.bindings("l_"), fields.bindings("r_")))
+                });
+            let arms = arms.chain(std::iter::once(("_".to_string(), discriminants)));
+            gen_match(&format!("(self, {})", other), arms)
+        }
+    }
+}
+
+fn gen_cmp(shape: &Shape, other: &str, method: &str) -> Option<String> {
+    let fields = match shape {
+        Shape::Struct(_, it) => it,
+        Shape::Enum(_) => return None,
+    };
+    let equal = match method {
+        "partial_cmp" => "Some(core::cmp::Ordering::Equal)",
+        _ => "core::cmp::Ordering::Equal",
+    };
+    let names = fields.names();
+    let (last, rest) = match names.split_last() {
+        Some(it) => it,
+        None => return Some(format!("    {}\n", equal)),
+    };
+    let mut buf = String::new();
+    for name in rest {
+        format_to!(
+            buf,
+            "    match self.{0}.{1}(&{2}.{0}) {{\n        {3} => {{}}\n        ord => return ord,\n    }}\n",
+            name,
+            method,
+            other,
+            equal
+        );
+    }
+    format_to!(buf, "    self.{0}.{1}(&{2}.{0})\n", last, method, other);
+    Some(buf)
+}
@@ -0,0 +1,248 @@
+//! A small pretty printer for the code generated by assists, which breaks the lines longer than
+//! rustfmt's default `max_width` like rustfmt does, so that accepting an assist doesn't require
+//! reformatting the file right away.
+//!
+//! Only lists in parentheses, brackets and angle brackets are broken, into one element per line
+//! with a trailing comma. This covers the long lines assists generate: signatures, calls, and
+//! generic argument lists.
+
+use std::ops::Range;
+
+use syntax::{ast::edit::IndentLevel, tokenize, SyntaxKind, T};
+
+/// rustfmt's default `max_width`.
+const MAX_WIDTH: usize = 100;
+/// rustfmt's default indentation, four spaces.
+const INDENT: &str = "    ";
+
+/// Breaks the lines of `text` which are too long. `indent` is the indentation of the line the
+/// text is inserted at, which the first line of the text doesn't contain.
+///
+/// The text may contain snippet placeholders and escapes, which don't count towards the width.
+pub(crate) fn reformat(text: &str, indent: IndentLevel) -> String {
+    // Lines overlapping tokens spanning several lines, like string literals, are kept as is.
+    let mut multiline = Vec::new();
+    let mut offset = 0;
+    for token in tokenize(text).0 {
+        let len = usize::from(token.len);
+        if token.kind != SyntaxKind::WHITESPACE && text[offset..offset + len].contains('\n') {
+            multiline.push(offset..offset + len);
+        }
+        offset += len;
+    }
+
+    let mut res = String::with_capacity(text.len());
+    let mut line_start = 0;
+    for (idx, line) in text.split('\n').enumerate() {
+        let line_end = line_start + line.len();
+        if idx > 0 {
+            res.push('\n');
+        }
+        let column = if idx == 0 { usize::from(indent.0) * INDENT.len() } else { 0 };
+        if multiline.iter().any(|it| it.start < line_end && line_start < it.end) {
+            res.push_str(line);
+        } else {
+            push_line(line, column, &mut res);
+        }
+        line_start = line_end + 1;
+    }
+    res
+}
+
+/// Pushes the line, broken into several ones if it starts at `column` and is too long.
+fn push_line(line: &str, column: usize, acc: &mut String) {
+    let group = match column + width(line) > MAX_WIDTH {
+        true => widest_group(line),
+        false => None,
+    };
+    let group = match group {
+        Some(it) => it,
+        None => {
+            acc.push_str(line);
+            return;
+        }
+    };
+
+    let leading_whitespace = &line[..line.len() - line.trim_start().len()];
+    let indent = format!("{}{}", " ".repeat(column), leading_whitespace);
+    push_line(&line[..group.open + 1], column, acc);
+    for element in group.elements {
+        acc.push('\n');
+        push_line(&format!("{}{}{},", indent, INDENT, &line[element]), 0, acc);
+    }
+    acc.push('\n');
+    push_line(&format!("{}{}", indent, &line[group.close..]), 0, acc);
+}
+
+/// A bracketed, comma separated list in a line.
+struct Group {
+    /// The offset of the opening bracket.
+    open: usize,
+    /// The offset of the closing bracket.
+    close: usize,
+    /// The ranges of the elements, without the surrounding whitespace.
+    elements: Vec<Range<usize>>,
+}
+
+/// Returns the group with the longest contents among the ones not nested in other groups, which
+/// is what rustfmt breaks first.
+fn widest_group(line: &str) -> Option<Group> {
+    // The opening brackets with their offset and the offsets of the commas directly inside them.
+    let mut stack: Vec<(SyntaxKind, usize, Vec<usize>)> = Vec::new();
+    let mut best: Option<Group> = None;
+    let mut prev = None;
+    let mut offset = 0;
+    for token in tokenize(line).0 {
+        let start = offset;
+        offset += usize::from(token.len);
+        let kind = token.kind;
+        let closes = match kind {
+            T![')'] => Some(T!['(']),
+            T![']'] => Some(T!['[']),
+            T!['}'] => Some(T!['{']),
+            T![>] if !matches!(prev, Some(T![-]) | Some(T![=])) => Some(T![<]),
+            _ => None,
+        };
+        let opens_generics =
+            kind == T![<] && matches!(prev, Some(SyntaxKind::IDENT) | Some(T![:]) | Some(T![impl]));
+        prev = Some(kind);
+
+        if matches!(kind, T!['('] | T!['['] | T!['{']) || opens_generics {
+            stack.push((kind, start, Vec::new()));
+        } else if kind == T![,] {
+            if let Some((_, _, commas)) = stack.last_mut() {
+                commas.push(start);
+            }
+        } else if let Some(opening) = closes {
+            // Unmatched `<` are comparisons, not generics.
+            while stack.last().map_or(false, |(it, ..)| *it == T![<] && opening != T![<]) {
+                stack.pop();
+            }
+            match stack.last() {
+                Some((it, ..)) if *it == opening => (),
+                _ => continue,
+            }
+            let (_, open, commas) = stack.pop().unwrap();
+            if !stack.is_empty() || opening == T!['{'] {
+                continue;
+            }
+            let elements = elements(line, open + 1, start, &commas);
+            let is_wider = best.as_ref().map_or(true, |it| it.close - it.open < start - open);
+            if !elements.is_empty() && is_wider {
+                best = Some(Group { open, close: start, elements });
+            }
+        }
+    }
+    best
+}
+
+fn elements(line: &str, start: usize, end: usize, commas: &[usize]) -> Vec<Range<usize>> {
+    let mut res = Vec::new();
+    let mut element_start = start;
+    for &element_end in commas.iter().chain(Some(&end)) {
+        let text = &line[element_start..element_end];
+        let trimmed_start = element_start + text.len() - text.trim_start().len();
+        let trimmed_end = element_start + text.trim_end().len();
+        if trimmed_start < trimmed_end {
+            res.push(trimmed_start..trimmed_end);
+        }
+        element_start = element_end + 1;
+    }
+    res
+}
+
+/// The width of the line, without snippet placeholders and escapes.
+fn width(line: &str) -> usize {
+    let mut res = 0;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('{') | Some('}') | Some('$')) => (),
+            '$' if matches!(chars.peek(), Some('0'..='9') | Some('{')) => {
+                chars.next_if_eq(&'{');
+                while chars.next_if(|it| it.is_ascii_digit()).is_some() {}
+                chars.next_if_eq(&':');
+            }
+            _ => res += 1,
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::ast::edit::IndentLevel;
+    use test_utils::assert_eq_text;
+
+    use super::reformat;
+
+    fn check(indent: u8, before: &str, after: &str) {
+        let actual = reformat(before, IndentLevel(indent));
+        assert_eq_text!(after, &actual);
+    }
+
+    #[test]
+    fn breaks_long_signatures() {
+        check(
+            0,
+            r#"
+fn $0long_function_name(first_argument: &mut Vec<u32>, second_argument: HashMap<String, u32>) -> Option<u32> {
+    0
+}"#,
+            r#"
+fn $0long_function_name(
+    first_argument: &mut Vec<u32>,
+    second_argument: HashMap<String, u32>,
+) -> Option<u32> {
+    0
+}"#,
+        );
+    }
+
+    #[test]
+    fn breaks_nested_lists() {
+        check(
+            1,
+            r#"let value = function_with_long_name(first_argument, other_function(argument_one, argument_two, argument_three), last);"#,
+            r#"let value = function_with_long_name(
+        first_argument,
+        other_function(argument_one, argument_two, argument_three),
+        last,
+    );"#,
+        );
+        check(
+            0,
+            r#"
+    fn f(&self) -> Result<SomeVeryLongTypeName<WithGenericArguments>, AnotherVeryLongTypeName<WithGenericArguments>> {"#,
+            r#"
+    fn f(&self) -> Result<
+        SomeVeryLongTypeName<WithGenericArguments>,
+        AnotherVeryLongTypeName<WithGenericArguments>,
+    > {"#,
+        );
+    }
+
+    #[test]
+    fn keeps_lines_which_fit() {
+        check(
+            0,
+            r#"
+fn ${0:short}(a: u32, b: u32) -> bool {
+    a < b && b > a
+}"#,
+            r#"
+fn ${0:short}(a: u32, b: u32) -> bool {
+    a < b && b > a
+}"#,
+        );
+        check(
+            0,
+            r#"
+const S: &str = "a long string which isn't broken even though it is longer than the maximum width (of a line)
+";"#,
+            r#"
+const S: &str = "a long string which isn't broken even though it is longer than the maximum width (of a line)
+";"#,
+        );
+    }
+}
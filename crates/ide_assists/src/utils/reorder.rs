@@ -0,0 +1,71 @@
+//! Shared logic of the assists which swap two neighbouring elements of a list, like operands,
+//! comma-separated items or trait bounds.
+
+use syntax::{algo::non_trivia_sibling, Direction, SyntaxElement, SyntaxKind, SyntaxToken};
+
+use crate::assist_context::AssistBuilder;
+
+/// The kind of list the swapped elements are part of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListKind {
+    Params,
+    GenericParams,
+    WherePredicates,
+    TypeBounds,
+    Other,
+}
+
+/// Two neighbouring elements of a list, separated by a token like `,` or `+`.
+#[derive(Debug)]
+pub(crate) struct Siblings {
+    pub(crate) kind: ListKind,
+    pub(crate) prev: SyntaxElement,
+    pub(crate) next: SyntaxElement,
+}
+
+impl Siblings {
+    /// Finds the elements on both sides of `separator`, if they can swap places.
+    pub(crate) fn around(separator: &SyntaxToken) -> Option<Siblings> {
+        let prev = non_trivia_sibling(separator.clone().into(), Direction::Prev)?;
+        let next = non_trivia_sibling(separator.clone().into(), Direction::Next)?;
+
+        // A trailing separator typically comes before punctuation.
+        if next.kind().is_punct() {
+            return None;
+        }
+        // Macro inputs are just tokens, there's no list to reorder.
+        if separator.ancestors().any(|it| it.kind() == SyntaxKind::MACRO_CALL) {
+            return None;
+        }
+
+        let kind = match separator.parent().kind() {
+            SyntaxKind::PARAM_LIST => ListKind::Params,
+            SyntaxKind::GENERIC_PARAM_LIST => ListKind::GenericParams,
+            SyntaxKind::WHERE_CLAUSE => ListKind::WherePredicates,
+            SyntaxKind::TYPE_BOUND_LIST => ListKind::TypeBounds,
+            _ => ListKind::Other,
+        };
+        let is_ordered = |kind: SyntaxKind| match kind {
+            // `self` always comes first.
+            SyntaxKind::SELF_PARAM => true,
+            // Lifetimes have to come before type and const parameters.
+            SyntaxKind::LIFETIME_PARAM => prev.kind() != next.kind(),
+            _ => false,
+        };
+        if is_ordered(prev.kind()) || is_ordered(next.kind()) {
+            return None;
+        }
+
+        Some(Siblings { kind, prev, next })
+    }
+
+    pub(crate) fn swap(&self, builder: &mut AssistBuilder) {
+        swap(builder, &self.prev, &self.next)
+    }
+}
+
+/// Replaces each of the two, non-overlapping, elements with the text of the other one.
+pub(crate) fn swap(builder: &mut AssistBuilder, first: &SyntaxElement, second: &SyntaxElement) {
+    builder.replace(first.text_range(), second.to_string());
+    builder.replace(second.text_range(), first.to_string());
+}
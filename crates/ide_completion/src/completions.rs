@@ -1,6 +1,7 @@
 //! This module defines an accumulator for completions which are going to be presented to user.
 
 pub(crate) mod attribute;
+pub(crate) mod bound;
 pub(crate) mod dot;
 pub(crate) mod record;
 pub(crate) mod pattern;
@@ -38,6 +39,7 @@ use crate::{
 #[derive(Debug, Default)]
 pub struct Completions {
     buf: Vec<CompletionItem>,
+    is_incomplete: bool,
 }
 
 impl Into<Vec<CompletionItem>> for Completions {
@@ -46,6 +48,20 @@ impl Into<Vec<CompletionItem>> for Completions {
     }
 }
 
+/// The completions at a position, and whether some of them were left out because the time budget
+/// ran out, in which case the client should ask for the completions again.
+#[derive(Debug)]
+pub struct CompletionList {
+    pub items: Vec<CompletionItem>,
+    pub is_incomplete: bool,
+}
+
+impl From<Completions> for CompletionList {
+    fn from(acc: Completions) -> CompletionList {
+        CompletionList { items: acc.buf, is_incomplete: acc.is_incomplete }
+    }
+}
+
 impl Builder {
     /// Convenience method, which allows to add a freshly created completion into accumulator
     /// without binding it to the variable.
@@ -59,6 +75,11 @@ impl Completions {
         self.buf.push(item.into())
     }
 
+    /// Marks the completions as incomplete, because some were left out.
+    pub(crate) fn mark_incomplete(&mut self) {
+        self.is_incomplete = true;
+    }
+
     pub(crate) fn add_all<I>(&mut self, items: I)
     where
         I: IntoIterator,
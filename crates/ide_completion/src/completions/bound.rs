@@ -0,0 +1,252 @@
+//! Completion of bounds: lifetimes in `T: 'a`, `?Sized` and associated types in
+//! `Iterator<Item = u8>`. Traits and modules are completed by the path completions.
+
+use hir::{AssocItem, PathResolution, ScopeDef};
+use ide_db::SymbolKind;
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, GenericParamsOwner},
+    match_ast, AstNode, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use crate::{CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions};
+
+pub(crate) fn complete_bound(acc: &mut Completions, ctx: &CompletionContext) {
+    if let Some(lifetime) = &ctx.lifetime_bound {
+        complete_lifetimes(acc, ctx, lifetime);
+    }
+    if ctx.is_bound && ctx.is_trivial_path && !ctx.is_maybe_bound && ctx.is_type_param_bound {
+        CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), "?Sized")
+            .kind(CompletionItemKind::Keyword)
+            .lookup_by("Sized")
+            .add_to(acc);
+    }
+    if let Some(path) = &ctx.assoc_type_arg_trait {
+        complete_assoc_type_args(acc, ctx, path);
+    }
+}
+
+/// Whether `def` may start a path in a bound, which has to name a trait.
+pub(crate) fn is_bound_candidate(def: &ScopeDef) -> bool {
+    matches!(def, ScopeDef::ModuleDef(hir::ModuleDef::Trait(_) | hir::ModuleDef::Module(_)))
+}
+
+fn complete_lifetimes(acc: &mut Completions, ctx: &CompletionContext, lifetime: &ast::Lifetime) {
+    let source_range = lifetime_range(ctx);
+    let mut seen = FxHashSet::default();
+    for generic_param_list in lifetime.syntax().ancestors().filter_map(generic_param_list) {
+        for name in generic_param_list.lifetime_params().filter_map(|it| it.lifetime()) {
+            let name = name.text().to_string();
+            if seen.insert(name.clone()) {
+                CompletionItem::new(CompletionKind::Reference, source_range, name)
+                    .kind(CompletionItemKind::SymbolKind(SymbolKind::LifetimeParam))
+                    .add_to(acc);
+            }
+        }
+    }
+    CompletionItem::new(CompletionKind::Reference, source_range, "'static")
+        .kind(CompletionItemKind::Keyword)
+        .add_to(acc);
+}
+
+/// The generic parameters declared by `node`. The lifetime being completed is part of a file with
+/// a fake identifier, so they are collected syntactically rather than from the scope.
+fn generic_param_list(node: SyntaxNode) -> Option<ast::GenericParamList> {
+    match_ast! {
+        match node {
+            ast::Fn(it) => it.generic_param_list(),
+            ast::Struct(it) => it.generic_param_list(),
+            ast::Enum(it) => it.generic_param_list(),
+            ast::Union(it) => it.generic_param_list(),
+            ast::Trait(it) => it.generic_param_list(),
+            ast::Impl(it) => it.generic_param_list(),
+            ast::TypeAlias(it) => it.generic_param_list(),
+            ast::WherePred(it) => it.generic_param_list(),
+            ast::ForType(it) => it.generic_param_list(),
+            _ => None,
+        }
+    }
+}
+
+/// The range of the typed lifetime, including its `'`. In the original file, a lone `'` is often
+/// lexed as the start of a char literal.
+fn lifetime_range(ctx: &CompletionContext) -> TextRange {
+    let token = &ctx.original_token;
+    let offset = ctx.position.offset;
+    if token.kind() == SyntaxKind::LIFETIME_IDENT {
+        return token.text_range();
+    }
+    if token.text().starts_with('\'') && token.text_range().start() < offset {
+        return TextRange::new(token.text_range().start(), offset);
+    }
+    TextRange::empty(offset)
+}
+
+fn complete_assoc_type_args(acc: &mut Completions, ctx: &CompletionContext, path: &ast::Path) {
+    let trait_ = match ctx.sema.resolve_path(path) {
+        Some(PathResolution::Def(hir::ModuleDef::Trait(it))) => it,
+        _ => return,
+    };
+    let has_eq = ctx
+        .name_ref_syntax
+        .as_ref()
+        .and_then(|it| it.syntax().parent())
+        .and_then(ast::AssocTypeArg::cast)
+        .map_or(false, |it| it.eq_token().is_some());
+    for item in trait_.items(ctx.db) {
+        let type_alias = match item {
+            AssocItem::TypeAlias(it) => it,
+            _ => continue,
+        };
+        let name = type_alias.name(ctx.db).to_string();
+        let insert = if has_eq { name.clone() } else { format!("{} = ", name) };
+        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), format!("{} = …", name))
+            .kind(CompletionItemKind::SymbolKind(SymbolKind::TypeAlias))
+            .lookup_by(name)
+            .insert_text(insert)
+            .add_to(acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{
+        test_utils::{check_edit, completion_list},
+        CompletionKind,
+    };
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture, CompletionKind::Reference);
+        expect.assert_eq(&actual)
+    }
+
+    fn check_keyword(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture, CompletionKind::Keyword);
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn completes_only_traits_in_bounds() {
+        check(
+            r#"
+mod m { pub trait Inner {} pub struct S; }
+trait Tr {}
+struct S;
+fn f<T: $0>() {}
+"#,
+            expect![[r#"
+                md m
+                tt Tr
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_only_traits_in_where_clauses() {
+        check(
+            r#"
+mod m { pub trait Inner {} pub struct S; }
+trait Tr {}
+struct S;
+fn f<T>() where T: m::$0 {}
+"#,
+            expect![[r#"
+                tt Inner
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_maybe_sized() {
+        check_keyword(
+            r#"
+fn f<T: $0>() {}
+"#,
+            expect![[r#"
+                kw return
+                kw ?Sized
+            "#]],
+        );
+        check_keyword(
+            r#"
+fn f(_: impl $0) {}
+"#,
+            expect![[r#"
+                kw return
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_lifetime_bounds() {
+        check(
+            r#"
+struct S<'a, 'b, T: '$0>(&'a T, &'b T) where for<'c> &'c T: Copy;
+"#,
+            expect![[r#"
+                lt 'a
+                lt 'b
+                kw 'static
+            "#]],
+        );
+        check_edit(
+            "'a",
+            r#"
+fn f<'a, T>() where T: 'b$0 {}
+"#,
+            r#"
+fn f<'a, T>() where T: 'a {}
+"#,
+        );
+        check_edit(
+            "'a",
+            r#"
+struct S<'a, T: '$0>(&'a T);
+"#,
+            r#"
+struct S<'a, T: 'a>(&'a T);
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_assoc_type_args() {
+        check(
+            r#"
+trait Iter { type Item; type Error; fn next(&self); }
+fn f<I: Iter<$0>>() {}
+"#,
+            expect![[r#"
+                tp I
+                fn f()       -> ()
+                tt Iter
+                ta Item = …
+                ta Error = …
+            "#]],
+        );
+        check_edit(
+            "Item",
+            r#"
+trait Iter { type Item; }
+fn f(_: impl Iter<It$0>) {}
+"#,
+            r#"
+trait Iter { type Item; }
+fn f(_: impl Iter<Item = >) {}
+"#,
+        );
+        check_edit(
+            "Item",
+            r#"
+trait Iter { type Item; }
+fn f(_: impl Iter<It$0 = u8>) {}
+"#,
+            r#"
+trait Iter { type Item; }
+fn f(_: impl Iter<Item = u8>) {}
+"#,
+        );
+    }
+}
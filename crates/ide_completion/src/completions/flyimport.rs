@@ -42,6 +42,9 @@
 //! For clients with no such support, all edits have to be calculated on the completion request, including the fuzzy search completion ones,
 //! which might be slow ergo the feature is automatically disabled.
 //!
+//! The search is also cut short when the `rust-analyzer.completion.timeBudget` runs out: the imports found so far are
+//! proposed, and the completion list is marked as incomplete, so that the client asks for the completions again.
+//!
 //! .Feature toggle
 //!
 //! The feature can be forcefully turned off in the settings with the `rust-analyzer.completion.enableAutoimportCompletions` flag.
@@ -72,6 +75,7 @@ pub(crate) fn import_on_the_fly(acc: &mut Completions, ctx: &CompletionContext)
     if ctx.use_item_syntax.is_some()
         || ctx.attribute_under_caret.is_some()
         || ctx.mod_declaration_under_caret.is_some()
+        || ctx.is_maybe_bound
     {
         return None;
     }
@@ -86,6 +90,12 @@ pub(crate) fn import_on_the_fly(acc: &mut Completions, ctx: &CompletionContext)
 
     let _p = profile::span("import_on_the_fly").detail(|| potential_import_name.to_string());
 
+    if ctx.is_past_deadline() {
+        mark::hit!(flyimport_skipped_past_deadline);
+        acc.mark_incomplete();
+        return None;
+    }
+
     let user_input_lowercased = potential_import_name.to_lowercase();
     let import_assets = import_assets(ctx, potential_import_name)?;
     let import_scope = ImportScope::find_insert_use_container(
@@ -94,8 +104,15 @@ pub(crate) fn import_on_the_fly(acc: &mut Completions, ctx: &CompletionContext)
     )?;
 
     let scope_definitions = scope_definitions(ctx);
-    let mut all_mod_paths = import_assets
-        .search_for_imports(&ctx.sema, ctx.config.insert_use.prefix_kind)
+    let prefix_kind = ctx.config.insert_use.prefix_kind;
+    let mod_paths = match ctx.deadline {
+        Some(deadline) => import_assets.search_for_imports_until(&ctx.sema, prefix_kind, deadline),
+        None => import_assets.search_for_imports(&ctx.sema, prefix_kind),
+    };
+    if ctx.is_past_deadline() {
+        acc.mark_incomplete();
+    }
+    let mut all_mod_paths = mod_paths
         .into_iter()
         .map(|(mod_path, item_in_ns)| {
             let scope_item = match item_in_ns {
@@ -106,6 +123,7 @@ pub(crate) fn import_on_the_fly(acc: &mut Completions, ctx: &CompletionContext)
             (mod_path, scope_item)
         })
         .filter(|(_, proposed_def)| !scope_definitions.contains(proposed_def))
+        .filter(|(_, proposed_def)| !ctx.is_bound || super::bound::is_bound_candidate(proposed_def))
         .collect::<Vec<_>>();
     all_mod_paths.sort_by_cached_key(|(mod_path, _)| {
         compute_fuzzy_completion_order_key(mod_path, &user_input_lowercased)
@@ -199,12 +217,15 @@ fn compute_fuzzy_completion_order_key(
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use expect_test::{expect, Expect};
     use test_utils::mark;
 
     use crate::{
         item::CompletionKind,
-        test_utils::{check_edit, check_edit_with_config, completion_list, TEST_CONFIG},
+        test_utils::{check_edit, check_edit_with_config, completion_list, position, TEST_CONFIG},
+        CompletionConfig, CompletionList,
     };
 
     fn check(ra_fixture: &str, expect: Expect) {
@@ -293,6 +314,25 @@ fn main() {
         );
     }
 
+    #[test]
+    fn only_traits_are_imported_in_bounds() {
+        check(
+            r#"
+//- /lib.rs crate:dep
+pub mod fmt {
+    pub trait Display {}
+    pub struct Displayed;
+}
+
+//- /main.rs crate:main deps:dep
+fn show<T: Displ$0>(_: T) {}
+"#,
+            expect![[r#"
+                tt dep::fmt::Display
+            "#]],
+        );
+    }
+
     #[test]
     fn short_paths_are_ignored() {
         mark::check!(ignore_short_input_for_path);
@@ -775,4 +815,35 @@ fn main() {
 }"#,
         );
     }
+
+    // A budget of zero is used up by building the completion context, so the imports aren't
+    // searched at all, while the other completions are still proposed.
+    #[test]
+    fn flyimport_skipped_after_context_used_up_budget() {
+        mark::check!(flyimport_skipped_past_deadline);
+        let fixture = r#"
+//- /lib.rs crate:dep
+pub mod io {
+    pub fn stdin() {}
+};
+
+//- /main.rs crate:main deps:dep
+fn stdout() {}
+
+fn main() {
+    std$0
+}
+"#;
+        let config = CompletionConfig { time_budget: Some(Duration::from_secs(0)), ..TEST_CONFIG };
+        let (db, position) = position(fixture);
+        let list: CompletionList = crate::completions(&db, &config, position).unwrap().into();
+        assert!(list.is_incomplete);
+        let lookups = list.items.iter().map(|it| it.lookup()).collect::<Vec<_>>();
+        assert!(lookups.contains(&"stdout"), "{:?}", lookups);
+        assert!(!lookups.contains(&"stdin"), "{:?}", lookups);
+
+        let list: CompletionList = crate::completions(&db, &TEST_CONFIG, position).unwrap().into();
+        assert!(!list.is_incomplete);
+        assert!(list.items.iter().any(|it| it.lookup() == "stdin"));
+    }
 }
@@ -34,6 +34,9 @@ pub(crate) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionCon
         PathResolution::Def(hir::ModuleDef::Module(module)) => {
             let module_scope = module.scope(ctx.db, context_module);
             for (name, def) in module_scope {
+                if ctx.is_bound && !super::bound::is_bound_candidate(&def) {
+                    continue;
+                }
                 if ctx.use_item_syntax.is_some() {
                     if let ScopeDef::Unknown = def {
                         if let Some(name_ref) = ctx.name_ref_syntax.as_ref() {
@@ -46,7 +49,7 @@ pub(crate) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionCon
                     }
                 }
 
-                acc.add_resolution(ctx, name.to_string(), &def);
+                acc.add_resolution(ctx, ctx.escape(&name), &def);
             }
         }
         PathResolution::Def(def @ hir::ModuleDef::Adt(_))
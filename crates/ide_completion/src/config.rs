@@ -4,6 +4,8 @@
 //! module, and we use to statically check that we only produce snippet
 //! completions if we are allowed to.
 
+use std::time::Duration;
+
 use ide_db::helpers::{insert_use::InsertUseConfig, SnippetCap};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -14,4 +16,8 @@ pub struct CompletionConfig {
     pub add_call_argument_snippets: bool,
     pub snippet_cap: Option<SnippetCap>,
     pub insert_use: InsertUseConfig,
+    /// The time after which the expensive completions, like the imports on the fly, are cut
+    /// short, and the completions found so far are returned as an incomplete list. Ignored on
+    /// wasm32, which has no clock.
+    pub time_budget: Option<Duration>,
 }
@@ -1,7 +1,9 @@
 //! See `CompletionContext` structure.
 
+use std::time::Instant;
+
 use hir::{Local, ScopeDef, Semantics, SemanticsScope, Type};
-use ide_db::base_db::{FilePosition, SourceDatabase};
+use ide_db::base_db::{Edition, FilePosition, SourceDatabase};
 use ide_db::{call_info::ActiveParameter, RootDatabase};
 use syntax::{
     algo::find_node_at_offset, ast, match_ast, AstNode, NodeOrToken, SyntaxKind::*, SyntaxNode,
@@ -30,6 +32,9 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) db: &'a RootDatabase,
     pub(super) config: &'a CompletionConfig,
     pub(super) position: FilePosition,
+    /// When the time budget of the completion runs out. Only set where there is a clock, so
+    /// checking the deadline never calls `Instant::now()` on wasm32.
+    pub(super) deadline: Option<Instant>,
     /// The token before the cursor, in the original file.
     pub(super) original_token: SyntaxToken,
     /// The token before the cursor, in the macro-expanded file.
@@ -71,6 +76,17 @@ pub(crate) struct CompletionContext<'a> {
     /// If this is a macro call, i.e. the () are already there.
     pub(super) is_macro_call: bool,
     pub(super) is_path_type: bool,
+    /// If the path is a bound of a type parameter, trait object or `impl Trait`, like `T: Clone`.
+    pub(super) is_bound: bool,
+    /// Like `is_bound`, but for the relaxed `?Sized` bound.
+    pub(super) is_maybe_bound: bool,
+    /// If the bound restricts a type parameter, so that `?Sized` can be used.
+    pub(super) is_type_param_bound: bool,
+    /// The lifetime typed as a bound, like `T: 'a` or `'a: 'b`. Unlike most other fields, this
+    /// is a node of the file with the fake identifier, as a lone `'` doesn't parse as a lifetime.
+    pub(super) lifetime_bound: Option<ast::Lifetime>,
+    /// The path of the trait whose generic arguments are typed, for `Iterator<Item = u8>`.
+    pub(super) assoc_type_arg_trait: Option<ast::Path>,
     pub(super) has_type_args: bool,
     pub(super) attribute_under_caret: Option<ast::Attr>,
     pub(super) mod_declaration_under_caret: Option<ast::Module>,
@@ -100,6 +116,12 @@ impl<'a> CompletionContext<'a> {
         position: FilePosition,
         config: &'a CompletionConfig,
     ) -> Option<CompletionContext<'a>> {
+        // There is no clock on wasm32, where `Instant::now()` panics, so the budget is ignored.
+        let deadline = if cfg!(target_arch = "wasm32") {
+            None
+        } else {
+            config.time_budget.map(|budget| Instant::now() + budget)
+        };
         let sema = Semantics::new(db);
 
         let original_file = sema.parse(position.file_id);
@@ -132,6 +154,7 @@ impl<'a> CompletionContext<'a> {
             db,
             config,
             position,
+            deadline,
             original_token,
             token,
             krate,
@@ -159,6 +182,11 @@ impl<'a> CompletionContext<'a> {
             is_pattern_call: false,
             is_macro_call: false,
             is_path_type: false,
+            is_bound: false,
+            is_maybe_bound: false,
+            is_type_param_bound: false,
+            lifetime_bound: None,
+            assoc_type_arg_trait: None,
             has_type_args: false,
             attribute_under_caret: None,
             mod_declaration_under_caret: None,
@@ -235,11 +263,23 @@ impl<'a> CompletionContext<'a> {
         (self.fn_is_prev && !self.inside_impl_trait_block) || self.for_is_prev2
     }
 
+    /// Whether the time budget of the completion has run out.
+    pub(crate) fn is_past_deadline(&self) -> bool {
+        self.deadline.map_or(false, |deadline| Instant::now() >= deadline)
+    }
+
+    /// Returns the name as it has to be inserted, which is a raw identifier if the name is a
+    /// keyword in the edition of the completed crate.
+    pub(crate) fn escape(&self, name: &hir::Name) -> String {
+        let edition = self.krate.map_or(Edition::Edition2018, |it| it.edition(self.db));
+        name.escaped(edition)
+    }
+
     /// The range of the identifier that is being completed.
     pub(crate) fn source_range(&self) -> TextRange {
         // check kind of macro-expanded token, but use range of original token
         let kind = self.token.kind();
-        if kind == IDENT || kind == UNDERSCORE || kind.is_keyword() {
+        if kind == IDENT || kind == LIFETIME_IDENT || kind == UNDERSCORE || kind.is_keyword() {
             mark::hit!(completes_if_prefix_is_keyword);
             self.original_token.text_range()
         } else {
@@ -308,6 +348,12 @@ impl<'a> CompletionContext<'a> {
             })
             .flatten();
         self.attribute_under_caret = find_node_at_offset(&file_with_fake_ident, offset);
+        self.lifetime_bound = find_node_at_offset::<ast::Lifetime>(&file_with_fake_ident, offset)
+            .filter(|it| {
+                it.syntax().parent().map_or(false, |it| {
+                    ast::TypeBound::can_cast(it.kind()) || ast::WherePred::can_cast(it.kind())
+                })
+            });
 
         // First, let's try to complete a reference to some declaration.
         if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(&file_with_fake_ident, offset) {
@@ -421,6 +467,14 @@ impl<'a> CompletionContext<'a> {
             None => return,
         };
 
+        if let Some(assoc_type_arg) = ast::AssocTypeArg::cast(parent.clone()) {
+            self.assoc_type_arg_trait = assoc_type_arg
+                .syntax()
+                .parent()
+                .and_then(ast::GenericArgList::cast)
+                .and_then(|it| generic_args_owner(original_file, &it));
+        }
+
         if let Some(segment) = ast::PathSegment::cast(parent.clone()) {
             let path = segment.parent_path();
             self.is_call = path
@@ -434,7 +488,38 @@ impl<'a> CompletionContext<'a> {
                 path.syntax().parent().and_then(ast::TupleStructPat::cast).is_some();
 
             self.is_path_type = path.syntax().parent().and_then(ast::PathType::cast).is_some();
+            let type_bound = path
+                .syntax()
+                .parent()
+                .filter(|it| ast::PathType::can_cast(it.kind()))
+                .and_then(|it| it.parent())
+                .and_then(ast::TypeBound::cast);
+            if let Some(type_bound) = type_bound {
+                self.is_bound = true;
+                self.is_maybe_bound = type_bound.question_mark_token().is_some();
+                self.is_type_param_bound =
+                    type_bound.syntax().parent().and_then(|it| it.parent()).map_or(false, |it| {
+                        match_ast! {
+                            match it {
+                                ast::TypeParam(_it) => true,
+                                ast::WherePred(it) => it.lifetime().is_none(),
+                                _ => false,
+                            }
+                        }
+                    });
+            }
             self.has_type_args = segment.generic_arg_list().is_some();
+            if path.qualifier().is_none() {
+                self.assoc_type_arg_trait = path
+                    .syntax()
+                    .parent()
+                    .filter(|it| ast::PathType::can_cast(it.kind()))
+                    .and_then(|it| it.parent())
+                    .filter(|it| ast::TypeArg::can_cast(it.kind()))
+                    .and_then(|it| it.parent())
+                    .and_then(ast::GenericArgList::cast)
+                    .and_then(|it| generic_args_owner(original_file, &it));
+            }
 
             if let Some(path) = path_or_use_tree_qualifier(&path) {
                 self.path_qual = path
@@ -520,6 +605,16 @@ fn find_node_with_range<N: AstNode>(syntax: &SyntaxNode, range: TextRange) -> Op
     syntax.covering_element(range).ancestors().find_map(N::cast)
 }
 
+/// Finds the path in the original file to which the generic arguments are passed.
+fn generic_args_owner(
+    original_file: &SyntaxNode,
+    generic_arg_list: &ast::GenericArgList,
+) -> Option<ast::Path> {
+    let segment = generic_arg_list.syntax().parent().and_then(ast::PathSegment::cast)?;
+    let name_range = segment.name_ref()?.syntax().text_range();
+    find_node_with_range::<ast::PathSegment>(original_file, name_range).map(|it| it.parent_path())
+}
+
 fn is_node<N: AstNode>(node: &SyntaxNode) -> bool {
     match node.ancestors().find_map(N::cast) {
         None => false,
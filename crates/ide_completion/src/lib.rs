@@ -20,6 +20,7 @@ use text_edit::TextEdit;
 use crate::{completions::Completions, context::CompletionContext, item::CompletionKind};
 
 pub use crate::{
+    completions::CompletionList,
     config::CompletionConfig,
     item::{CompletionItem, CompletionItemKind, CompletionScore, ImportEdit, InsertTextFormat},
 };
@@ -127,6 +128,7 @@ pub fn completions(
     completions::macro_in_item_position::complete_macro_in_item_position(&mut acc, &ctx);
     completions::trait_impl::complete_trait_impl(&mut acc, &ctx);
     completions::mod_::complete_mod(&mut acc, &ctx);
+    completions::bound::complete_bound(&mut acc, &ctx);
     completions::flyimport::import_on_the_fly(&mut acc, &ctx);
 
     Some(acc)
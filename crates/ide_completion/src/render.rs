@@ -54,10 +54,10 @@ pub(crate) fn render_resolution_with_import<'a>(
     resolution: &ScopeDef,
 ) -> Option<CompletionItem> {
     let local_name = match resolution {
-        ScopeDef::ModuleDef(ModuleDef::Function(f)) => f.name(ctx.completion.db).to_string(),
-        ScopeDef::ModuleDef(ModuleDef::Const(c)) => c.name(ctx.completion.db)?.to_string(),
-        ScopeDef::ModuleDef(ModuleDef::TypeAlias(t)) => t.name(ctx.completion.db).to_string(),
-        _ => import_edit.import_path.segments().last()?.to_string(),
+        ScopeDef::ModuleDef(ModuleDef::Function(f)) => ctx.escape(&f.name(ctx.completion.db)),
+        ScopeDef::ModuleDef(ModuleDef::Const(c)) => ctx.escape(&c.name(ctx.completion.db)?),
+        ScopeDef::ModuleDef(ModuleDef::TypeAlias(t)) => ctx.escape(&t.name(ctx.completion.db)),
+        _ => ctx.escape(import_edit.import_path.segments().last()?),
     };
     Render::new(ctx).render_resolution(local_name, Some(import_edit), resolution).map(|mut item| {
         item.completion_kind = CompletionKind::Magic;
@@ -88,6 +88,10 @@ impl<'a> RenderContext<'a> {
         self.completion.source_range()
     }
 
+    fn escape(&self, name: &hir::Name) -> String {
+        self.completion.escape(name)
+    }
+
     fn is_deprecated(&self, node: impl HasAttrs) -> bool {
         let attrs = node.attrs(self.db());
         attrs.by_key("deprecated").exists() || attrs.by_key("rustc_deprecated").exists()
@@ -144,7 +148,7 @@ impl<'a> Render<'a> {
         let mut item = CompletionItem::new(
             CompletionKind::Reference,
             self.ctx.source_range(),
-            name.to_string(),
+            self.ctx.escape(&name),
         )
         .kind(SymbolKind::Field)
         .detail(ty.display(self.ctx.db()).to_string())
@@ -756,6 +760,45 @@ fn main() -> ManualVtable {
         );
     }
 
+    #[test]
+    fn escapes_keywords() {
+        check_edit(
+            "r#type",
+            r#"
+struct S { r#type: u32 }
+fn f(s: S) { s.$0 }
+"#,
+            r#"
+struct S { r#type: u32 }
+fn f(s: S) { s.r#type }
+"#,
+        );
+        check_edit(
+            "r#async",
+            r#"
+//- /main.rs crate:main deps:dep edition:2018
+fn main() { dep::$0 }
+//- /dep.rs crate:dep edition:2015
+pub fn r#async() {}
+"#,
+            r#"
+fn main() { dep::r#async()$0 }
+"#,
+        );
+        check_edit(
+            "async",
+            r#"
+//- /main.rs crate:main deps:dep edition:2015
+fn main() { dep::$0 }
+//- /dep.rs crate:dep edition:2018
+pub fn r#async() {}
+"#,
+            r#"
+fn main() { dep::async()$0 }
+"#,
+        );
+    }
+
     #[test]
     fn no_parens_in_use_item() {
         mark::check!(no_parens_in_use_item);
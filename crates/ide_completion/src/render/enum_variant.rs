@@ -39,7 +39,7 @@ impl<'a> EnumRender<'a> {
         variant: hir::Variant,
         path: Option<ModPath>,
     ) -> EnumRender<'a> {
-        let name = local_name.unwrap_or_else(|| variant.name(ctx.db()).to_string());
+        let name = local_name.unwrap_or_else(|| ctx.escape(&variant.name(ctx.db())));
         let variant_kind = variant.kind(ctx.db());
 
         let (qualified_name, short_qualified_name) = match &path {
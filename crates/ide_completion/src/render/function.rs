@@ -34,7 +34,7 @@ impl<'a> FunctionRender<'a> {
         local_name: Option<String>,
         fn_: hir::Function,
     ) -> Option<FunctionRender<'a>> {
-        let name = local_name.unwrap_or_else(|| fn_.name(ctx.db()).to_string());
+        let name = local_name.unwrap_or_else(|| ctx.escape(&fn_.name(ctx.db())));
         let ast_node = fn_.source(ctx.db())?.value;
 
         Some(FunctionRender { ctx, name, func: fn_, ast_node })
@@ -1,7 +1,6 @@
 //! Renderer for patterns.
 
-use hir::{db::HirDatabase, HasAttrs, HasVisibility, Name, StructKind};
-use ide_db::helpers::SnippetCap;
+use hir::{HasAttrs, HasVisibility, Name, StructKind};
 use itertools::Itertools;
 
 use crate::{item::CompletionKind, render::RenderContext, CompletionItem, CompletionItemKind};
@@ -39,7 +38,7 @@ pub(crate) fn render_struct_pat(
         return None;
     }
 
-    let name = local_name.unwrap_or_else(|| strukt.name(ctx.db())).to_string();
+    let name = ctx.escape(&local_name.unwrap_or_else(|| strukt.name(ctx.db())));
     let pat = render_pat(&ctx, &name, strukt.kind(ctx.db()), &visible_fields, fields_omitted)?;
 
     Some(build_completion(ctx, name, pat, strukt))
@@ -58,7 +57,7 @@ pub(crate) fn render_variant_pat(
 
     let name = match &path {
         Some(path) => path.to_string(),
-        None => local_name.unwrap_or_else(|| variant.name(ctx.db())).to_string(),
+        None => ctx.escape(&local_name.unwrap_or_else(|| variant.name(ctx.db()))),
     };
     let pat = render_pat(&ctx, &name, variant.kind(ctx.db()), &visible_fields, fields_omitted)?;
 
@@ -95,9 +94,7 @@ fn render_pat(
         StructKind::Tuple if ctx.snippet_cap().is_some() => {
             render_tuple_as_pat(&fields, &name, fields_omitted)
         }
-        StructKind::Record => {
-            render_record_as_pat(ctx.db(), ctx.snippet_cap(), &fields, &name, fields_omitted)
-        }
+        StructKind::Record => render_record_as_pat(ctx, fields, name, fields_omitted),
         _ => return None,
     };
 
@@ -113,27 +110,23 @@ fn render_pat(
 }
 
 fn render_record_as_pat(
-    db: &dyn HirDatabase,
-    snippet_cap: Option<SnippetCap>,
+    ctx: &RenderContext<'_>,
     fields: &[hir::Field],
     name: &str,
     fields_omitted: bool,
 ) -> String {
-    let fields = fields.iter();
-    if snippet_cap.is_some() {
+    let fields = fields.iter().map(|field| ctx.escape(&field.name(ctx.db())));
+    if ctx.snippet_cap().is_some() {
         format!(
             "{name} {{ {}{} }}",
-            fields
-                .enumerate()
-                .map(|(idx, field)| format!("{}${}", field.name(db), idx + 1))
-                .format(", "),
+            fields.enumerate().map(|(idx, field)| format!("{}${}", field, idx + 1)).format(", "),
             if fields_omitted { ", .." } else { "" },
             name = name
         )
     } else {
         format!(
             "{name} {{ {}{} }}",
-            fields.map(|field| field.name(db)).format(", "),
+            fields.format(", "),
             if fields_omitted { ", .." } else { "" },
             name = name
         )
@@ -26,6 +26,7 @@ pub(crate) const TEST_CONFIG: CompletionConfig = CompletionConfig {
         merge: Some(MergeBehavior::Full),
         prefix_kind: PrefixKind::Plain,
     },
+    time_budget: None,
 };
 
 /// Creates analysis from a multi-file fixture, returns positions marked with $0.
@@ -202,9 +202,13 @@ impl RootDatabase {
             crate::symbol_index::LibrarySymbolsQuery
             crate::symbol_index::LocalRootsQuery
             crate::symbol_index::LibraryRootsQuery
+            crate::symbol_index::ExcludedCodeQuery
+            crate::symbol_index::IsExcludedFileQuery
+            crate::symbol_index::UnresolvedIdentsQuery
 
             // LineIndexDatabase
             crate::LineIndexQuery
+            crate::MaxFnBodyLinesQuery
         ];
 
         // To collect interned data, we need to bump the revision counter by performing a synthetic
@@ -1,9 +1,14 @@
 //! A module with ide helpers for high-level ide features.
 pub mod insert_use;
 pub mod import_assets;
+pub mod term_search;
+pub mod wildcard_arm;
 
-use hir::{Crate, Enum, Module, ScopeDef, Semantics, Trait};
-use syntax::ast::{self, make};
+use hir::{Crate, Enum, Module, ScopeDef, Semantics, Trait, Type};
+use syntax::{
+    ast::{self, make, AstNode},
+    match_ast,
+};
 
 use crate::RootDatabase;
 
@@ -31,6 +36,64 @@ pub fn mod_path_to_ast(path: &hir::ModPath) -> ast::Path {
     make::path_from_segments(segments, is_abs)
 }
 
+/// The type `expr` has to have in its context, like the type of the `let` binding, the parameter
+/// or the field it initializes, or the return type of the function it returns from.
+pub fn expected_type(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> Option<Type> {
+    let db = sema.db;
+    let parent = expr.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::LetStmt(it) => sema.type_of_pat(&it.pat()?),
+            ast::ArgList(it) => {
+                let idx = it.args().position(|arg| arg == *expr)?;
+                let callable = match_ast! {
+                    match (it.syntax().parent()?) {
+                        ast::CallExpr(call) => sema.type_of_expr(&call.expr()?)?.as_callable(db),
+                        ast::MethodCallExpr(call) => sema.resolve_method_call_as_callable(&call),
+                        _ => None,
+                    }
+                }?;
+                callable.params(db).into_iter().nth(idx).map(|(_, ty)| ty)
+            },
+            ast::RecordExprField(it) => {
+                let name = it.field_name()?.to_string();
+                let record = it.syntax().ancestors().find_map(ast::RecordExpr::cast)?;
+                let fields = sema.type_of_expr(&ast::Expr::RecordExpr(record))?.fields(db);
+                fields.into_iter().find(|(field, _)| field.name(db).to_string() == name).map(|(_, ty)| ty)
+            },
+            ast::ReturnExpr(it) => {
+                // `return` returns from the innermost closure or function.
+                for node in it.syntax().ancestors() {
+                    if let Some(closure) = ast::ClosureExpr::cast(node.clone()) {
+                        let ty = sema.type_of_expr(&ast::Expr::ClosureExpr(closure))?;
+                        return Some(ty.as_callable(db)?.return_type());
+                    }
+                    if let Some(func) = ast::Fn::cast(node) {
+                        return Some(sema.to_def(&func)?.ret_type(db));
+                    }
+                }
+                None
+            },
+            ast::BlockExpr(it) => {
+                if it.tail_expr()? != *expr {
+                    return None;
+                }
+                match it.syntax().parent().and_then(ast::Fn::cast) {
+                    Some(func) => Some(sema.to_def(&func)?.ret_type(db)),
+                    None => sema.type_of_expr(&ast::Expr::BlockExpr(it)),
+                }
+            },
+            ast::BinExpr(it) => {
+                if it.op_kind()? != ast::BinOp::Assignment || it.rhs()? != *expr {
+                    return None;
+                }
+                sema.type_of_expr(&it.lhs()?)
+            },
+            _ => None,
+        }
+    }
+}
+
 /// Helps with finding well-know things inside the standard library. This is
 /// somewhat similar to the known paths infra inside hir, but it different; We
 /// want to make sure that IDE specific paths don't become interesting inside
@@ -53,10 +116,18 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:convert:From")
     }
 
+    pub fn core_convert_TryFrom(&self) -> Option<Trait> {
+        self.find_trait("core:convert:TryFrom")
+    }
+
     pub fn core_option_Option(&self) -> Option<Enum> {
         self.find_enum("core:option:Option")
     }
 
+    pub fn core_ops_ControlFlow(&self) -> Option<Enum> {
+        self.find_enum("core:ops:ControlFlow")
+    }
+
     pub fn core_default_Default(&self) -> Option<Trait> {
         self.find_trait("core:default:Default")
     }
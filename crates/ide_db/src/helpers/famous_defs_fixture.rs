@@ -14,6 +14,11 @@ pub mod convert {
     pub trait From<T> {
         fn from(t: T) -> Self;
     }
+
+    pub trait TryFrom<T>: Sized {
+        type Error;
+        fn try_from(value: T) -> crate::result::Result<Self, Self::Error>;
+    }
 }
 
 pub mod default {
@@ -108,6 +113,11 @@ pub mod ops {
         type Output;
         extern "rust-call" fn call_once(self, args: Args) -> Self::Output;
     }
+
+    pub enum ControlFlow<B, C = ()> {
+        Continue(C),
+        Break(B),
+    }
 }
 
 pub mod option {
@@ -115,6 +125,22 @@ pub mod option {
         None,
         Some(T),
     }
+
+    impl<T> Option<T> {
+        pub fn unwrap(self) -> T {
+            match self {
+                Some(val) => val,
+                None => panic!("called `Option::unwrap()` on a `None` value"),
+            }
+        }
+    }
+}
+
+pub mod result {
+    pub enum Result<T, E> {
+        Ok(T),
+        Err(E),
+    }
 }
 
 pub mod prelude {
@@ -1,4 +1,6 @@
 //! Look up accessible paths for items.
+use std::time::Instant;
+
 use either::Either;
 use hir::{AsAssocItem, AssocItem, Crate, MacroDef, Module, ModuleDef, PrefixKind, Semantics};
 use rustc_hash::FxHashSet;
@@ -151,7 +153,20 @@ impl ImportAssets {
         prefix_kind: PrefixKind,
     ) -> Vec<(hir::ModPath, hir::ItemInNs)> {
         let _p = profile::span("import_assets::search_for_imports");
-        self.search_for(sema, Some(prefix_kind))
+        self.search_for(sema, Some(prefix_kind), None)
+    }
+
+    /// Like `search_for_imports`, but stops looking for more imports once `deadline` has passed,
+    /// returning the ones found so far. As this reads the clock, callers must not pass a deadline
+    /// on wasm32.
+    pub fn search_for_imports_until(
+        &self,
+        sema: &Semantics<RootDatabase>,
+        prefix_kind: PrefixKind,
+        deadline: Instant,
+    ) -> Vec<(hir::ModPath, hir::ItemInNs)> {
+        let _p = profile::span("import_assets::search_for_imports_until");
+        self.search_for(sema, Some(prefix_kind), Some(deadline))
     }
 
     /// This may return non-absolute paths if a part of the returned path is already imported into scope.
@@ -160,13 +175,14 @@ impl ImportAssets {
         sema: &Semantics<RootDatabase>,
     ) -> Vec<(hir::ModPath, hir::ItemInNs)> {
         let _p = profile::span("import_assets::search_for_relative_paths");
-        self.search_for(sema, None)
+        self.search_for(sema, None, None)
     }
 
     fn search_for(
         &self,
         sema: &Semantics<RootDatabase>,
         prefixed: Option<hir::PrefixKind>,
+        deadline: Option<Instant>,
     ) -> Vec<(hir::ModPath, hir::ItemInNs)> {
         let current_crate = self.module_with_candidate.krate();
 
@@ -192,6 +208,7 @@ impl ImportAssets {
                     fuzzy_name.clone(),
                     assoc_item_search,
                     limit,
+                    deadline,
                 )
             }
         };
@@ -199,6 +216,7 @@ impl ImportAssets {
         let db = sema.db;
         let mut res =
             applicable_defs(self.import_candidate(), current_crate, db, unfiltered_imports)
+                .take_while(|_| deadline.map_or(true, |deadline| Instant::now() < deadline))
                 .filter_map(|candidate| {
                     let item: hir::ItemInNs = candidate.clone().either(Into::into, Into::into);
 
@@ -0,0 +1,403 @@
+//! Term search: synthesizes expressions of a given type from the values,
+//! constructors and functions in scope.
+//!
+//! The search runs in rounds. The first one collects the values which can be
+//! used as they are, like locals and constants, and each following one applies
+//! the functions, constructors, methods and fields it knows about to the terms
+//! found so far. Only the smallest few terms of each type are kept, which keeps
+//! the search cheap enough to run on request.
+
+use hir::{
+    Adt, AssocItem, Function, GenericDef, HasVisibility, Module, ModuleDef, Name, ScopeDef,
+    Semantics, StructKind, Type,
+};
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxNode};
+
+use crate::{helpers::mod_path_to_ast, RootDatabase};
+
+/// How many terms of each type the search keeps.
+const TERMS_PER_TYPE: usize = 4;
+
+/// An expression found by the term search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub text: String,
+    /// The number of values, functions and constructors the term is built from.
+    pub size: usize,
+}
+
+/// Finds expressions of type `goal` which are valid at `node`, applying at most
+/// `depth` functions or constructors on top of each other. The terms are sorted
+/// by size, smallest first.
+pub fn term_search(
+    sema: &Semantics<RootDatabase>,
+    node: &SyntaxNode,
+    goal: &Type,
+    depth: usize,
+) -> Vec<Term> {
+    let _p = profile::span("term_search");
+    let db = sema.db;
+    let scope = sema.scope(node);
+    let module = match scope.module() {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    // Calling the function the term is in would recurse endlessly.
+    let enclosing_fn = node.ancestors().find_map(ast::Fn::cast).and_then(|it| sema.to_def(&it));
+
+    let mut terms = Terms::default();
+    let mut rules = Vec::new();
+    let mut adts = Vec::new();
+    scope.process_all_names(&mut |name, def| match def {
+        ScopeDef::Local(it) => terms.add_value(it.ty(db), &name),
+        ScopeDef::ModuleDef(ModuleDef::Const(it)) => terms.add_value(it.ty(db), &name),
+        ScopeDef::ModuleDef(ModuleDef::Static(it)) => terms.add_value(it.ty(db), &name),
+        ScopeDef::ModuleDef(ModuleDef::Function(it))
+            if Some(it) != enclosing_fn && it.self_param(db).is_none() =>
+        {
+            rules.extend(Rule::call(db, module, it, name.to_string()))
+        }
+        ScopeDef::ModuleDef(ModuleDef::Adt(it)) if !it.has_non_default_type_params(db) => {
+            adts.push((it, Some(name.to_string())))
+        }
+        ScopeDef::ModuleDef(ModuleDef::Variant(it))
+            if !Adt::from(it.parent_enum(db)).has_non_default_type_params(db) =>
+        {
+            let ty = Adt::from(it.parent_enum(db)).ty(db);
+            rules.extend(Rule::constructor(
+                db,
+                module,
+                name.to_string(),
+                it.kind(db),
+                it.fields(db),
+                ty,
+            ))
+        }
+        _ => {}
+    });
+    // The type searched for and the types of the values in scope are worth a
+    // look even if they aren't in scope by name.
+    let reachable = terms.by_type.iter().map(|(ty, _)| ty).chain(Some(goal));
+    for adt in reachable.filter_map(|ty| ty.as_adt()).collect::<Vec<_>>() {
+        if !adt.has_non_default_type_params(db) && !adts.iter().any(|(it, _)| *it == adt) {
+            let path = module.find_use_path(db, ModuleDef::Adt(adt));
+            adts.push((adt, path.map(|it| mod_path_to_ast(&it).to_string())));
+        }
+    }
+    for (adt, path) in adts {
+        rules.extend(Rule::for_adt(db, module, adt, path, enclosing_fn));
+    }
+
+    for _ in 0..depth {
+        let mut found = Vec::new();
+        for rule in &rules {
+            // `multi_cartesian_product` has no items at all for no parameters.
+            let args: Vec<Vec<&Term>> = if rule.params.is_empty() {
+                vec![Vec::new()]
+            } else {
+                let args = rule.params.iter().map(|ty| terms.get(ty).iter());
+                args.multi_cartesian_product().take(TERMS_PER_TYPE).collect()
+            };
+            for args in args {
+                let size = 1 + args.iter().map(|it| it.size).sum::<usize>();
+                found.push((rule.ret.clone(), Term { text: rule.render(&args), size }));
+            }
+        }
+        let mut changed = false;
+        for (ty, term) in found {
+            changed |= terms.insert(ty, term);
+        }
+        if !changed {
+            break;
+        }
+    }
+    terms.get(goal).to_vec()
+}
+
+/// The terms found so far, grouped by their type.
+#[derive(Default)]
+struct Terms {
+    by_type: Vec<(Type, Vec<Term>)>,
+}
+
+impl Terms {
+    fn get(&self, ty: &Type) -> &[Term] {
+        self.by_type.iter().find(|(it, _)| it.is_same_type(ty)).map_or(&[], |(_, it)| it)
+    }
+
+    fn add_value(&mut self, ty: Type, name: &Name) {
+        self.insert(ty, Term { text: name.to_string(), size: 1 });
+    }
+
+    /// Adds a term, unless there are enough smaller ones of its type already.
+    fn insert(&mut self, ty: Type, term: Term) -> bool {
+        if ty.is_unknown() || ty.is_unit() || ty.is_never() {
+            return false;
+        }
+        let terms = match self.by_type.iter_mut().find(|(it, _)| it.is_same_type(&ty)) {
+            Some((_, terms)) => terms,
+            None => {
+                self.by_type.push((ty, vec![term]));
+                return true;
+            }
+        };
+        if terms.contains(&term)
+            || terms.iter().any(|it| it.text == term.text)
+            || terms.len() == TERMS_PER_TYPE && terms.iter().all(|it| it.size <= term.size)
+        {
+            return false;
+        }
+        let idx = terms.iter().position(|it| it.size > term.size).unwrap_or(terms.len());
+        terms.insert(idx, term);
+        terms.truncate(TERMS_PER_TYPE);
+        true
+    }
+}
+
+/// A way to build a term of type `ret` from terms of types `params`.
+struct Rule {
+    params: Vec<Type>,
+    ret: Type,
+    shape: Shape,
+}
+
+enum Shape {
+    /// `path(args)`, `path { fields }` or `path`, depending on the kind.
+    Constructor(String, StructKind, Vec<Name>),
+    /// `path(args)`.
+    Call(String),
+    /// `receiver.name(args)`, where the receiver is the first parameter.
+    Method(Name),
+    /// `receiver.name`.
+    Field(Name),
+}
+
+impl Rule {
+    fn call(db: &RootDatabase, module: Module, func: Function, path: String) -> Option<Rule> {
+        let params = func.assoc_fn_params(db).iter().map(|it| it.ty().clone()).collect();
+        Rule::new(db, module, func, params, Shape::Call(path))
+    }
+
+    fn constructor(
+        db: &RootDatabase,
+        module: Module,
+        path: String,
+        kind: StructKind,
+        fields: Vec<hir::Field>,
+        ret: Type,
+    ) -> Option<Rule> {
+        if !fields.iter().all(|it| it.is_visible_from(db, module)) {
+            return None;
+        }
+        let names = fields.iter().map(|it| it.name(db)).collect();
+        let params = fields.iter().map(|it| it.signature_ty(db)).collect();
+        Some(Rule { params, ret, shape: Shape::Constructor(path, kind, names) })
+    }
+
+    fn for_adt(
+        db: &RootDatabase,
+        module: Module,
+        adt: Adt,
+        path: Option<String>,
+        enclosing_fn: Option<Function>,
+    ) -> Vec<Rule> {
+        let ty = adt.ty(db);
+        let mut rules = Vec::new();
+        match adt {
+            Adt::Struct(it) => {
+                if let Some(path) = path.clone() {
+                    rules.extend(Rule::constructor(
+                        db,
+                        module,
+                        path,
+                        it.kind(db),
+                        it.fields(db),
+                        ty.clone(),
+                    ));
+                }
+                for field in it.fields(db) {
+                    if field.is_visible_from(db, module) {
+                        let shape = Shape::Field(field.name(db));
+                        rules.push(Rule {
+                            params: vec![ty.clone()],
+                            ret: field.signature_ty(db),
+                            shape,
+                        });
+                    }
+                }
+            }
+            Adt::Enum(it) => {
+                if let Some(path) = &path {
+                    for variant in it.variants(db) {
+                        let path = format!("{}::{}", path, variant.name(db));
+                        let (kind, fields) = (variant.kind(db), variant.fields(db));
+                        rules.extend(Rule::constructor(db, module, path, kind, fields, ty.clone()));
+                    }
+                }
+            }
+            Adt::Union(_) => {}
+        }
+        ty.clone().iterate_assoc_items(db, module.krate(), |item| {
+            let func = match item {
+                AssocItem::Function(it) if Some(it) != enclosing_fn => it,
+                _ => return None,
+            };
+            if !func.is_visible_from(db, module) {
+                return None;
+            }
+            let rule = match (func.method_params(db), &path) {
+                // The receiver is borrowed automatically, whatever kind of `self` the method takes.
+                (Some(params), _) => {
+                    let params = params.iter().map(|it| it.ty().clone());
+                    let params = Some(ty.clone()).into_iter().chain(params).collect();
+                    Rule::new(db, module, func, params, Shape::Method(func.name(db)))
+                }
+                (None, Some(path)) => {
+                    let params =
+                        func.assoc_fn_params(db).iter().map(|it| it.ty().clone()).collect();
+                    let path = format!("{}::{}", path, func.name(db));
+                    Rule::new(db, module, func, params, Shape::Call(path))
+                }
+                (None, None) => None,
+            };
+            rules.extend(rule);
+            None::<()>
+        });
+        rules
+    }
+
+    fn new(
+        db: &RootDatabase,
+        module: Module,
+        func: Function,
+        params: Vec<Type>,
+        shape: Shape,
+    ) -> Option<Rule> {
+        if func.is_unsafe(db)
+            || !GenericDef::from(func).type_params(db).is_empty()
+            || !func.is_visible_from(db, module)
+        {
+            return None;
+        }
+        let ret = func.ret_type(db);
+        // A function taking what it returns transforms values rather than
+        // building them, and would only make the terms of its type larger.
+        if params.iter().any(|it| it.is_unknown() || it.is_same_type(&ret)) {
+            return None;
+        }
+        Some(Rule { params, ret, shape })
+    }
+
+    fn render(&self, args: &[&Term]) -> String {
+        let args = args.iter().map(|it| it.text.as_str());
+        match &self.shape {
+            Shape::Constructor(path, StructKind::Record, names) => {
+                let fields = names.iter().zip(args).map(|(name, arg)| {
+                    if name.to_string() == arg {
+                        arg.to_string()
+                    } else {
+                        format!("{}: {}", name, arg)
+                    }
+                });
+                format!("{} {{ {} }}", path, fields.format(", "))
+            }
+            Shape::Constructor(path, StructKind::Tuple, _) | Shape::Call(path) => {
+                format!("{}({})", path, args.format(", "))
+            }
+            Shape::Constructor(path, StructKind::Unit, _) => path.clone(),
+            Shape::Method(name) => {
+                let mut args = args;
+                let receiver = args.next().unwrap_or_default();
+                format!("{}.{}({})", receiver, name, args.format(", "))
+            }
+            Shape::Field(name) => {
+                format!("{}.{}", args.format(""), name)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_db::{fixture::WithFixture, FileRange};
+    use expect_test::{expect, Expect};
+    use syntax::algo::find_node_at_range;
+
+    use crate::{helpers, RootDatabase};
+
+    use super::*;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (db, file_id, range_or_offset) = RootDatabase::with_range_or_offset(ra_fixture);
+        let range = FileRange { file_id, range: range_or_offset.into() };
+        let sema = Semantics::new(&db);
+        let file = sema.parse(range.file_id);
+        let hole: ast::Expr = find_node_at_range(file.syntax(), range.range).unwrap();
+        let goal = helpers::expected_type(&sema, &hole).unwrap();
+        let terms = term_search(&sema, hole.syntax(), &goal, 3);
+        let actual =
+            terms.iter().map(|it| format!("{} {}\n", it.size, it.text)).collect::<String>();
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn composes_constructors_and_functions() {
+        check(
+            r#"
+struct Point { x: i32, y: i32 }
+enum Shape { Circle(Point, u32), Dot(Point) }
+fn origin() -> Point { Point { x: 0, y: 0 } }
+fn make(x: i32) -> Shape {
+    let shape: Shape = $0todo!()$0;
+}
+"#,
+            expect![[r#"
+                2 Shape::Dot(origin())
+                4 Shape::Dot(Point { x, y: x })
+            "#]],
+        );
+    }
+
+    #[test]
+    fn uses_fields_and_methods() {
+        check(
+            r#"
+mod config {
+    pub struct Config { pub name: Name, secret: Name }
+    impl Config {
+        pub fn timeout(&self) -> Timeout { Timeout }
+        fn secret(&self) -> Name { self.secret }
+    }
+    pub struct Timeout;
+    pub struct Name(u32);
+}
+fn make(config: config::Config) -> config::Timeout {
+    let name: config::Name = $0todo!()$0;
+    todo!()
+}
+"#,
+            expect![[r#"
+                2 config.name
+            "#]],
+        );
+        check(
+            r#"
+mod config {
+    pub struct Config { retries: u32 }
+    impl Config {
+        pub fn timeout(&self, scale: u32) -> Timeout { Timeout }
+    }
+    pub struct Timeout;
+}
+fn make(config: config::Config, scale: u32) {
+    let timeout: config::Timeout = $0todo!()$0;
+}
+"#,
+            expect![[r#"
+                1 config::Timeout
+                3 config.timeout(scale)
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Finds the variants of an enum a wildcard arm of a `match` stands in for.
+use hir::{Adt, ModuleDef, PathResolution, Semantics, Variant};
+use rustc_hash::FxHashSet;
+use syntax::{ast, AstNode};
+
+use crate::RootDatabase;
+
+/// The variants of the matched enum that reach `arm`, if it's an unguarded `_` arm.
+///
+/// A variant is handled by an earlier arm only if that arm has no guard and its pattern matches
+/// every value of the variant, so variants that earlier arms match partially count as well.
+pub fn absorbed_variants(
+    sema: &Semantics<RootDatabase>,
+    arm: &ast::MatchArm,
+) -> Option<Vec<Variant>> {
+    if !matches!(arm.pat()?, ast::Pat::WildcardPat(_)) || arm.guard().is_some() {
+        return None;
+    }
+    let match_expr = arm.syntax().ancestors().find_map(ast::MatchExpr::cast)?;
+    let enum_ =
+        sema.type_of_expr(&match_expr.expr()?)?.autoderef(sema.db).find_map(|ty| {
+            match ty.as_adt() {
+                Some(Adt::Enum(it)) => Some(it),
+                _ => None,
+            }
+        })?;
+
+    let mut handled = FxHashSet::default();
+    for earlier in match_expr.match_arm_list()?.arms().take_while(|it| it != arm) {
+        if earlier.guard().is_some() {
+            continue;
+        }
+        if let Some(pat) = earlier.pat() {
+            handled_variants(sema, &pat, &mut handled);
+        }
+    }
+    Some(enum_.variants(sema.db).into_iter().filter(|it| !handled.contains(it)).collect())
+}
+
+fn handled_variants(sema: &Semantics<RootDatabase>, pat: &ast::Pat, acc: &mut FxHashSet<Variant>) {
+    let variant = match pat {
+        ast::Pat::OrPat(it) => {
+            it.pats().for_each(|pat| handled_variants(sema, &pat, acc));
+            return;
+        }
+        ast::Pat::ParenPat(it) => {
+            if let Some(pat) = it.pat() {
+                handled_variants(sema, &pat, acc);
+            }
+            return;
+        }
+        ast::Pat::RefPat(it) => {
+            if let Some(pat) = it.pat() {
+                handled_variants(sema, &pat, acc);
+            }
+            return;
+        }
+        ast::Pat::PathPat(it) => it.path().and_then(|path| resolve_variant(sema, &path)),
+        ast::Pat::IdentPat(it) if it.pat().is_none() => match sema.resolve_bind_pat_to_const(it) {
+            Some(ModuleDef::Variant(it)) => Some(it),
+            _ => None,
+        },
+        ast::Pat::TupleStructPat(it) if it.fields().all(|it| is_catch_all(sema, &it)) => {
+            it.path().and_then(|path| resolve_variant(sema, &path))
+        }
+        ast::Pat::RecordPat(it) => {
+            let catch_all = it.record_pat_field_list().map_or(true, |list| {
+                list.fields().filter_map(|it| it.pat()).all(|it| is_catch_all(sema, &it))
+            });
+            if catch_all {
+                it.path().and_then(|path| resolve_variant(sema, &path))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+    acc.extend(variant);
+}
+
+fn resolve_variant(sema: &Semantics<RootDatabase>, path: &ast::Path) -> Option<Variant> {
+    match sema.resolve_path(path)? {
+        PathResolution::Def(ModuleDef::Variant(it)) => Some(it),
+        _ => None,
+    }
+}
+
+/// Whether `pat` matches any value of its type.
+fn is_catch_all(sema: &Semantics<RootDatabase>, pat: &ast::Pat) -> bool {
+    match pat {
+        ast::Pat::WildcardPat(_) | ast::Pat::RestPat(_) => true,
+        ast::Pat::IdentPat(it) => match it.pat() {
+            Some(pat) => is_catch_all(sema, &pat),
+            None => sema.resolve_bind_pat_to_const(it).is_none(),
+        },
+        ast::Pat::TuplePat(it) => it.fields().all(|it| is_catch_all(sema, &it)),
+        ast::Pat::ParenPat(it) => it.pat().map_or(false, |it| is_catch_all(sema, &it)),
+        ast::Pat::RefPat(it) => it.pat().map_or(false, |it| is_catch_all(sema, &it)),
+        ast::Pat::BoxPat(it) => it.pat().map_or(false, |it| is_catch_all(sema, &it)),
+        _ => false,
+    }
+}
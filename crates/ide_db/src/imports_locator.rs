@@ -1,6 +1,8 @@
 //! This module contains an import search functionality that is provided to the assists module.
 //! Later, this should be moved away to a separate crate that is accessible from the assists module.
 
+use std::time::Instant;
+
 use hir::{
     import_map::{self, ImportKind},
     AsAssocItem, Crate, MacroDef, ModuleDef, Semantics,
@@ -46,12 +48,15 @@ pub enum AssocItemSearch {
     AssocItemsOnly,
 }
 
+/// Searches for the items whose name fuzzily matches `fuzzy_search_string`. The search stops once
+/// `deadline` has passed, returning the items found so far.
 pub fn find_similar_imports<'a>(
     sema: &Semantics<'a, RootDatabase>,
     krate: Crate,
     fuzzy_search_string: String,
     assoc_item_search: AssocItemSearch,
     limit: Option<usize>,
+    deadline: Option<Instant>,
 ) -> Box<dyn Iterator<Item = Either<ModuleDef, MacroDef>> + 'a> {
     let _p = profile::span("find_similar_imports");
 
@@ -75,6 +80,10 @@ pub fn find_similar_imports<'a>(
         external_query = external_query.limit(limit);
         local_query.limit(limit);
     }
+    if let Some(deadline) = deadline {
+        external_query = external_query.deadline(deadline);
+        local_query.deadline(deadline);
+    }
 
     let db = sema.db;
     Box::new(find_imports(sema, krate, local_query, external_query).filter(
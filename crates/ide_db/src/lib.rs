@@ -24,8 +24,12 @@ use base_db::{
 };
 use hir::db::{AstDatabase, DefDatabase, HirDatabase};
 use rustc_hash::FxHashSet;
+use syntax::ast::{self, AstNode};
 
-use crate::{line_index::LineIndex, symbol_index::SymbolsDatabase};
+use crate::{
+    line_index::LineIndex,
+    symbol_index::{ExcludedCode, SymbolsDatabase},
+};
 
 /// `base_db` is normally also needed in places where `ide_db` is used, so this re-export is for convenience.
 pub use base_db;
@@ -107,6 +111,7 @@ impl RootDatabase {
         db.set_crate_graph_with_durability(Default::default(), Durability::HIGH);
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
+        db.set_excluded_code_with_durability(Default::default(), Durability::HIGH);
         db.update_lru_capacity(lru_capacity);
         db
     }
@@ -117,6 +122,12 @@ impl RootDatabase {
         hir::db::ParseMacroExpansionQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
         hir::db::MacroExpandQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
     }
+
+    pub fn update_excluded_code(&mut self, excluded_code: ExcludedCode) {
+        if *self.excluded_code() != excluded_code {
+            self.set_excluded_code_with_durability(Arc::new(excluded_code), Durability::HIGH);
+        }
+    }
 }
 
 impl salsa::ParallelDatabase for RootDatabase {
@@ -128,6 +139,8 @@ impl salsa::ParallelDatabase for RootDatabase {
 #[salsa::query_group(LineIndexDatabaseStorage)]
 pub trait LineIndexDatabase: base_db::SourceDatabase + CheckCanceled {
     fn line_index(&self, file_id: FileId) -> Arc<LineIndex>;
+    /// The number of lines of the longest function body in the file.
+    fn max_fn_body_lines(&self, file_id: FileId) -> usize;
 }
 
 fn line_index(db: &dyn LineIndexDatabase, file_id: FileId) -> Arc<LineIndex> {
@@ -135,6 +148,24 @@ fn line_index(db: &dyn LineIndexDatabase, file_id: FileId) -> Arc<LineIndex> {
     Arc::new(LineIndex::new(&*text))
 }
 
+fn max_fn_body_lines(db: &dyn LineIndexDatabase, file_id: FileId) -> usize {
+    let line_index = db.line_index(file_id);
+    let source_file = db.parse(file_id).tree();
+    source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::Fn::cast)
+        .filter_map(|func| func.body())
+        .map(|body| {
+            let range = body.syntax().text_range();
+            let start = line_index.line_col(range.start()).line;
+            let end = line_index.line_col(range.end()).line;
+            (end - start) as usize + 1
+        })
+        .max()
+        .unwrap_or(0)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum SymbolKind {
     Const,
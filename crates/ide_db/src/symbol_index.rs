@@ -26,21 +26,20 @@ use std::{
     hash::{Hash, Hasher},
     mem,
     sync::Arc,
+    time::Instant,
 };
 
-use base_db::{
-    salsa::{self, ParallelDatabase},
-    CrateId, FileId, SourceDatabaseExt, SourceRootId,
-};
+use base_db::{salsa, CrateId, FileId, SourceDatabaseExt, SourceRootId, VfsPath};
 use fst::{self, Streamer};
 use hir::db::DefDatabase;
+#[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::{
     ast::{self, NameOwner},
-    match_ast, AstNode, Parse, SmolStr, SourceFile,
+    match_ast, AstNode, SmolStr, SourceFile,
     SyntaxKind::*,
-    SyntaxNode, SyntaxNodePtr, TextRange, WalkEvent,
+    SyntaxNode, SyntaxNodePtr, TextRange, TextSize, WalkEvent,
 };
 
 use crate::RootDatabase;
@@ -53,6 +52,7 @@ pub struct Query {
     libs: bool,
     exact: bool,
     limit: usize,
+    deadline: Option<Instant>,
 }
 
 impl Query {
@@ -65,6 +65,7 @@ impl Query {
             libs: false,
             exact: false,
             limit: usize::max_value(),
+            deadline: None,
         }
     }
 
@@ -83,6 +84,11 @@ impl Query {
     pub fn limit(&mut self, limit: usize) {
         self.limit = limit
     }
+
+    /// Stops the search once `deadline` has passed, returning the symbols found so far.
+    pub fn deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline)
+    }
 }
 
 #[salsa::query_group(SymbolsDatabaseStorage)]
@@ -97,6 +103,77 @@ pub trait SymbolsDatabase: hir::db::HirDatabase + SourceDatabaseExt {
     /// Files in libraries are assumed to never change.
     #[salsa::input]
     fn library_roots(&self) -> Arc<FxHashSet<SourceRootId>>;
+    /// Code which is neither indexed nor checked for diagnostics, like the output of code
+    /// generators. Its items still take part in name resolution.
+    #[salsa::input]
+    fn excluded_code(&self) -> Arc<ExcludedCode>;
+    fn is_excluded_file(&self, file_id: FileId) -> bool;
+    /// The identifiers of a file which name resolution doesn't see, in macro token trees and
+    /// `#[cfg]`-attributed code, with their offsets.
+    fn unresolved_idents(&self, file_id: FileId) -> Arc<FxHashMap<SmolStr, Vec<TextSize>>>;
+}
+
+/// Directories and modules whose files are excluded from analysis.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExcludedCode {
+    pub dirs: Vec<VfsPath>,
+    /// Paths like `my_crate::generated`, starting with the name of the crate. A path of only the
+    /// crate name excludes the whole crate.
+    pub modules: Vec<String>,
+}
+
+impl ExcludedCode {
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty() && self.modules.is_empty()
+    }
+
+    fn contains_module(&self, path: &str) -> bool {
+        self.modules.iter().any(|module| {
+            path.strip_prefix(module.as_str())
+                .map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+        })
+    }
+}
+
+fn is_excluded_file(db: &dyn SymbolsDatabase, file_id: FileId) -> bool {
+    let excluded = db.excluded_code();
+    if excluded.is_empty() {
+        return false;
+    }
+    let source_root = db.source_root(db.file_source_root(file_id));
+    if let Some(path) = source_root.path_for_file(&file_id) {
+        if excluded.dirs.iter().any(|dir| path.starts_with(dir)) {
+            return true;
+        }
+    }
+    if excluded.modules.is_empty() {
+        return false;
+    }
+
+    let crate_graph = db.crate_graph();
+    db.relevant_crates(file_id).iter().any(|&krate| {
+        let crate_name = match &crate_graph[krate].display_name {
+            Some(it) => it.to_string(),
+            None => return false,
+        };
+        let def_map = db.crate_def_map(krate);
+        let res = def_map.modules_for_file(file_id).any(|module| {
+            let mut segments = Vec::new();
+            let mut curr = module;
+            while let Some(parent) = def_map[curr].parent {
+                if let Some((name, _)) =
+                    def_map[parent].children.iter().find(|(_, &child)| child == curr)
+                {
+                    segments.push(name.to_string());
+                }
+                curr = parent;
+            }
+            segments.push(crate_name.clone());
+            segments.reverse();
+            excluded.contains_module(&segments.join("::"))
+        });
+        res
+    })
 }
 
 fn library_symbols(db: &dyn SymbolsDatabase) -> Arc<FxHashMap<SourceRootId, SymbolIndex>> {
@@ -111,10 +188,7 @@ fn library_symbols(db: &dyn SymbolsDatabase) -> Arc<FxHashMap<SourceRootId, Symb
                 .iter()
                 .map(|it| (it, SourceDatabaseExt::file_text(db, it)))
                 .collect::<Vec<_>>();
-            let symbol_index = SymbolIndex::for_files(
-                files.into_par_iter().map(|(file, text)| (file, SourceFile::parse(&text))),
-            );
-            (root_id, symbol_index)
+            (root_id, SymbolIndex::for_files(files))
         })
         .collect();
     Arc::new(res)
@@ -131,12 +205,55 @@ fn file_symbols(db: &dyn SymbolsDatabase, file_id: FileId) -> Arc<SymbolIndex> {
     Arc::new(SymbolIndex::new(symbols))
 }
 
-/// Need to wrap Snapshot to provide `Clone` impl for `map_with`
-struct Snap<DB>(DB);
-impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
-    fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
-        Snap(self.0.snapshot())
+fn unresolved_idents(
+    db: &dyn SymbolsDatabase,
+    file_id: FileId,
+) -> Arc<FxHashMap<SmolStr, Vec<TextSize>>> {
+    let mut res: FxHashMap<SmolStr, Vec<TextSize>> = FxHashMap::default();
+    let mut preorder = db.parse(file_id).tree().syntax().preorder();
+    while let Some(event) = preorder.next() {
+        let node = match event {
+            WalkEvent::Enter(it) => it,
+            WalkEvent::Leave(_) => continue,
+        };
+        let is_unresolved = ast::TokenTree::can_cast(node.kind())
+            || node
+                .children()
+                .filter_map(ast::Attr::cast)
+                .any(|attr| attr.simple_name().as_deref() == Some("cfg"));
+        if !is_unresolved {
+            continue;
+        }
+        preorder.skip_subtree();
+        for token in node.descendants_with_tokens().filter_map(|it| it.into_token()) {
+            if token.kind() == IDENT {
+                res.entry(SmolStr::new(token.text())).or_default().push(token.text_range().start());
+            }
+        }
+    }
+    Arc::new(res)
+}
+
+/// Computes the symbols of the files, on all cores where threads are available.
+#[cfg(not(target_arch = "wasm32"))]
+fn symbols_of_files(db: &RootDatabase, files: &[FileId]) -> Vec<Arc<SymbolIndex>> {
+    use base_db::salsa::ParallelDatabase;
+
+    /// Need to wrap Snapshot to provide `Clone` impl for `map_with`
+    struct Snap<DB>(DB);
+    impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
+        fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
+            Snap(self.0.snapshot())
+        }
     }
+
+    let snap = Snap(db.snapshot());
+    files.par_iter().map_with(snap, |db, &file_id| db.0.file_symbols(file_id)).collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn symbols_of_files(db: &RootDatabase, files: &[FileId]) -> Vec<Arc<SymbolIndex>> {
+    files.iter().map(|&file_id| db.file_symbols(file_id)).collect()
 }
 
 // Feature: Workspace Symbol
@@ -172,14 +289,10 @@ pub fn world_symbols(db: &RootDatabase, query: Query) -> Vec<FileSymbol> {
         let mut files = Vec::new();
         for &root in db.local_roots().iter() {
             let sr = db.source_root(root);
-            files.extend(sr.iter())
+            files.extend(sr.iter().filter(|&file_id| !db.is_excluded_file(file_id)))
         }
 
-        let snap = Snap(db.snapshot());
-        tmp2 = files
-            .par_iter()
-            .map_with(snap, |db, &file_id| db.0.file_symbols(file_id))
-            .collect::<Vec<_>>();
+        tmp2 = symbols_of_files(db, &files);
         tmp2.iter().map(|it| &**it).collect()
     };
     query.search(&buf)
@@ -198,12 +311,7 @@ pub fn crate_symbols(db: &RootDatabase, krate: CrateId, query: Query) -> Vec<Fil
         modules.extend(data.children.values());
     }
 
-    let snap = Snap(db.snapshot());
-
-    let buf = files
-        .par_iter()
-        .map_with(snap, |db, &file_id| db.0.file_symbols(file_id))
-        .collect::<Vec<_>>();
+    let buf = symbols_of_files(db, &files);
     let buf = buf.iter().map(|it| &**it).collect::<Vec<_>>();
 
     query.search(&buf)
@@ -250,7 +358,10 @@ impl SymbolIndex {
             lhs_chars.cmp(rhs_chars)
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
         symbols.par_sort_by(cmp);
+        #[cfg(target_arch = "wasm32")]
+        symbols.sort_by(cmp);
 
         let mut builder = fst::MapBuilder::memory();
 
@@ -285,11 +396,17 @@ impl SymbolIndex {
         self.map.as_fst().size() + self.symbols.len() * mem::size_of::<FileSymbol>()
     }
 
-    pub(crate) fn for_files(
-        files: impl ParallelIterator<Item = (FileId, Parse<ast::SourceFile>)>,
-    ) -> SymbolIndex {
+    /// Parses the files and indexes their symbols, on all cores where threads are available.
+    pub(crate) fn for_files(files: Vec<(FileId, Arc<String>)>) -> SymbolIndex {
+        #[cfg(not(target_arch = "wasm32"))]
+        let files = files.into_par_iter();
+        #[cfg(target_arch = "wasm32")]
+        let files = files.into_iter();
+
         let symbols = files
-            .flat_map(|(file_id, file)| source_file_to_file_symbols(&file.tree(), file_id))
+            .flat_map(|(file_id, text)| {
+                source_file_to_file_symbols(&SourceFile::parse(&text).tree(), file_id)
+            })
             .collect::<Vec<_>>();
         SymbolIndex::new(symbols)
     }
@@ -318,6 +435,9 @@ impl Query {
         let mut stream = op.union();
         let mut res = Vec::new();
         while let Some((_, indexed_values)) = stream.next() {
+            if self.deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                break;
+            }
             for indexed_value in indexed_values {
                 let symbol_index = &indices[indexed_value.index];
                 let (start, end) = SymbolIndex::map_value_to_range(indexed_value.value);
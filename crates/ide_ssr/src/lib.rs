@@ -81,13 +81,15 @@ use syntax::{ast, AstNode, SyntaxNode, TextRange};
 use text_edit::TextEdit;
 
 // A structured search replace rule. Create by calling `parse` on a str.
-#[derive(Debug)]
+//
+// Only the tokens of the rule are kept, so that it can be shared between threads. They are parsed
+// as Rust code again when the rule is added to a `MatchFinder`.
+#[derive(Debug, Clone)]
 pub struct SsrRule {
     /// A structured pattern that we're searching for.
     pattern: parsing::RawPattern,
     /// What we'll replace it with.
     template: parsing::RawPattern,
-    parsed_rules: Vec<parsing::ParsedRule>,
 }
 
 #[derive(Debug)]
@@ -148,7 +150,7 @@ impl<'db> MatchFinder<'db> {
     /// precedence. If a node is matched by an earlier rule, then later rules won't be permitted to
     /// match to it.
     pub fn add_rule(&mut self, rule: SsrRule) -> Result<(), SsrError> {
-        for parsed_rule in rule.parsed_rules {
+        for parsed_rule in parsing::ParsedRule::new(&rule.pattern, Some(&rule.template))? {
             self.rules.push(ResolvedRule::new(
                 parsed_rule,
                 &self.resolution_scope,
@@ -184,6 +186,22 @@ impl<'db> MatchFinder<'db> {
             .collect()
     }
 
+    /// Finds matches for all added rules and returns the range of each match together with the
+    /// edit that replaces just that match.
+    pub fn match_edits(&self) -> Vec<(FileRange, TextEdit)> {
+        use ide_db::base_db::SourceDatabaseExt;
+        self.matches()
+            .matches
+            .into_iter()
+            .map(|m| {
+                let range = m.range;
+                let file_text = self.sema.db.file_text(range.file_id);
+                let matches = SsrMatches { matches: vec![m] };
+                (range, replacing::matches_to_edit(&matches, &file_text, &self.rules))
+            })
+            .collect()
+    }
+
     /// Adds a search pattern. For use if you intend to only call `find_matches_in_file`. If you
     /// intend to do replacement, use `add_rule` instead.
     pub fn add_search_pattern(&mut self, pattern: SsrPattern) -> Result<(), SsrError> {
@@ -19,7 +19,7 @@ pub(crate) struct ParsedRule {
     pub(crate) template: Option<SyntaxNode>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RawPattern {
     tokens: Vec<PatternElement>,
 }
@@ -62,7 +62,7 @@ pub(crate) struct Token {
 }
 
 impl ParsedRule {
-    fn new(
+    pub(crate) fn new(
         pattern: &RawPattern,
         template: Option<&RawPattern>,
     ) -> Result<Vec<ParsedRule>, SsrError> {
@@ -160,8 +160,8 @@ impl FromStr for SsrRule {
         }
         let raw_pattern = pattern.parse()?;
         let raw_template = template.parse()?;
-        let parsed_rules = ParsedRule::new(&raw_pattern, Some(&raw_template))?;
-        let rule = SsrRule { pattern: raw_pattern, template: raw_template, parsed_rules };
+        ParsedRule::new(&raw_pattern, Some(&raw_template))?;
+        let rule = SsrRule { pattern: raw_pattern, template: raw_template };
         validate_rule(&rule)?;
         Ok(rule)
     }
@@ -1,20 +1,25 @@
 //! Loads a Cargo project into a static instance of analysis, without support
 //! for incorporating changes.
+//!
+//! The split of the project into the folders to load and the source roots is
+//! shared with the language server, which reloads it when the project changes.
 use std::{path::Path, sync::Arc};
 
 use anyhow::Result;
 use crossbeam_channel::{unbounded, Receiver};
 use ide::{AnalysisHost, Change};
-use ide_db::base_db::CrateGraph;
+use ide_db::base_db::{CrateGraph, SourceRoot, VfsPath};
 use project_model::{
-    BuildDataCollector, CargoConfig, ProcMacroClient, ProjectManifest, ProjectWorkspace,
+    BuildDataCollector, BuildDataResult, CargoConfig, ProcMacroClient, ProjectManifest,
+    ProjectWorkspace,
 };
-use vfs::{loader::Handle, AbsPath, AbsPathBuf};
-
-use crate::reload::{ProjectFolders, SourceRootConfig};
+use rustc_hash::FxHashMap;
+use vfs::{file_set::FileSetConfig, loader::Handle, AbsPath, AbsPathBuf};
 
 pub struct LoadCargoConfig {
     pub load_out_dirs_from_check: bool,
+    /// Expands procedural macros with the current executable, which has to be the
+    /// `rust-analyzer` binary.
     pub with_proc_macro: bool,
 }
 
@@ -126,6 +131,105 @@ fn load_crate_graph(
     host
 }
 
+#[derive(Default)]
+pub struct ProjectFolders {
+    pub load: Vec<vfs::loader::Entry>,
+    pub watch: Vec<usize>,
+    pub source_root_config: SourceRootConfig,
+    /// The directories with the sources of dependencies, which are read-only.
+    pub library_roots: Vec<AbsPathBuf>,
+}
+
+impl ProjectFolders {
+    pub fn new(
+        workspaces: &[ProjectWorkspace],
+        global_excludes: &[AbsPathBuf],
+        build_data: Option<&BuildDataResult>,
+    ) -> ProjectFolders {
+        let mut res = ProjectFolders::default();
+        let mut fsc = FileSetConfig::builder();
+        let mut local_filesets = vec![];
+        let mut loaded = FxHashMap::default();
+
+        for root in workspaces.iter().flat_map(|it| it.to_roots(build_data)) {
+            // Roots shared by several workspaces, like the sysroot and the dependencies from the
+            // registry, are loaded once, so that all the workspaces use the same files.
+            if let Some(&idx) = loaded.get(&root.include) {
+                if root.is_member && !local_filesets.contains(&idx) {
+                    res.watch.push(idx);
+                    local_filesets.push(idx);
+                }
+                continue;
+            }
+            loaded.insert(root.include.clone(), fsc.len());
+
+            let file_set_roots: Vec<VfsPath> =
+                root.include.iter().cloned().map(VfsPath::from).collect();
+
+            let entry = {
+                let mut dirs = vfs::loader::Directories::default();
+                dirs.extensions.push("rs".into());
+                dirs.include.extend(root.include);
+                dirs.exclude.extend(root.exclude);
+                for excl in global_excludes {
+                    if dirs.include.iter().any(|incl| incl.starts_with(excl)) {
+                        dirs.exclude.push(excl.clone());
+                    }
+                }
+
+                vfs::loader::Entry::Directories(dirs)
+            };
+
+            if root.is_member {
+                res.watch.push(res.load.len());
+            }
+            res.load.push(entry);
+
+            if root.is_member {
+                local_filesets.push(fsc.len());
+            }
+            fsc.add_file_set(file_set_roots)
+        }
+
+        res.library_roots = loaded
+            .into_iter()
+            .filter(|(_, idx)| !local_filesets.contains(idx))
+            .flat_map(|(include, _)| include)
+            .collect();
+        res.library_roots.sort();
+
+        let fsc = fsc.build();
+        res.source_root_config = SourceRootConfig { fsc, local_filesets };
+
+        res
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct SourceRootConfig {
+    pub fsc: FileSetConfig,
+    pub local_filesets: Vec<usize>,
+}
+
+impl SourceRootConfig {
+    pub fn partition(&self, vfs: &vfs::Vfs) -> Vec<SourceRoot> {
+        let _p = profile::span("SourceRootConfig::partition");
+        self.fsc
+            .partition(vfs)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, file_set)| {
+                let is_local = self.local_filesets.contains(&idx);
+                if is_local {
+                    SourceRoot::new_local(file_set)
+                } else {
+                    SourceRoot::new_library(file_set)
+                }
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,6 +58,7 @@ pub(super) const ATOM_EXPR_FIRST: TokenSet =
         T![const],
         T![loop],
         T![for],
+        T![_],
         LIFETIME_IDENT,
     ]));
 
@@ -144,6 +145,7 @@ pub(super) fn atom_expr(p: &mut Parser, r: Restrictions) -> Option<(CompletedMar
         }
         T![return] => return_expr(p),
         T![yield] => yield_expr(p),
+        T![_] => underscore_expr(p),
         T![continue] => continue_expr(p),
         T![break] => break_expr(p, r),
         _ => {
@@ -510,6 +512,19 @@ fn return_expr(p: &mut Parser) -> CompletedMarker {
     }
     m.complete(p, RETURN_EXPR)
 }
+// test underscore_expr
+// fn foo() {
+//     let x = _;
+//     _ = foo();
+//     (_, b) = (1, 2);
+// }
+fn underscore_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![_]));
+    let m = p.start();
+    p.bump(T![_]);
+    m.complete(p, UNDERSCORE_EXPR)
+}
+
 // test yield_expr
 // fn foo() {
 //     yield;
@@ -16,8 +16,13 @@ pub(super) fn opt_generic_arg_list(p: &mut Parser, colon_colon_required: bool) {
     }
 
     while !p.at(EOF) && !p.at(T![>]) {
+        // test_err generic_arg_list_recover
+        // fn f(v: Vec<i32, ) { let x: Vec<i32, = 1; let y: Option<u8; }
+        if p.at_ts(GENERIC_ARG_LIST_RECOVERY_SET) {
+            break;
+        }
         generic_arg(p);
-        if !p.at(T![>]) && !p.expect(T![,]) {
+        if !p.at(T![>]) && !p.at_ts(GENERIC_ARG_LIST_RECOVERY_SET) && !p.expect(T![,]) {
             break;
         }
     }
@@ -25,6 +30,11 @@ pub(super) fn opt_generic_arg_list(p: &mut Parser, colon_colon_required: bool) {
     m.complete(p, GENERIC_ARG_LIST);
 }
 
+/// Tokens which can't appear in a generic argument list, but are likely to follow one
+/// which isn't closed yet.
+const GENERIC_ARG_LIST_RECOVERY_SET: TokenSet =
+    TokenSet::new(&[T![;], T!['}'], T![')'], T![']'], T![=]]);
+
 // test type_arg
 // type A = B<'static, i32, 1, { 2 }, Item=u64, true, false>;
 fn generic_arg(p: &mut Parser) {
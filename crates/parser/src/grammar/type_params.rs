@@ -15,6 +15,13 @@ fn generic_param_list(p: &mut Parser) {
     p.bump(T![<]);
 
     while !p.at(EOF) && !p.at(T![>]) {
+        // test_err generic_param_list_recover
+        // struct S<T, ;
+        // fn f<T: Clone, () {}
+        // fn g<T {}
+        if p.at_ts(GENERIC_PARAM_LIST_RECOVERY_SET) {
+            break;
+        }
         let m = p.start();
 
         // test generic_lifetime_type_attribute
@@ -31,7 +38,7 @@ fn generic_param_list(p: &mut Parser) {
                 p.err_and_bump("expected type parameter")
             }
         }
-        if !p.at(T![>]) && !p.expect(T![,]) {
+        if !p.at(T![>]) && !p.at_ts(GENERIC_PARAM_LIST_RECOVERY_SET) && !p.expect(T![,]) {
             break;
         }
     }
@@ -39,6 +46,11 @@ fn generic_param_list(p: &mut Parser) {
     m.complete(p, GENERIC_PARAM_LIST);
 }
 
+/// Tokens which can't appear in a generic parameter list, but are likely to follow one
+/// which isn't closed yet.
+const GENERIC_PARAM_LIST_RECOVERY_SET: TokenSet =
+    TokenSet::new(&[T![;], T!['('], T!['{'], T!['}'], T![')'], T![=], T![where]]);
+
 fn lifetime_param(p: &mut Parser, m: Marker) {
     assert!(p.at(LIFETIME_IDENT));
     lifetime(p);
@@ -198,6 +198,7 @@ pub enum SyntaxKind {
     RECORD_EXPR_FIELD,
     EFFECT_EXPR,
     BOX_EXPR,
+    UNDERSCORE_EXPR,
     CALL_EXPR,
     INDEX_EXPR,
     METHOD_CALL_EXPR,
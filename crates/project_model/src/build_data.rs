@@ -106,6 +106,11 @@ fn collect_from_workspace(
     let mut cmd = Command::new(toolchain::cargo());
     cmd.args(&["check", "--workspace", "--message-format=json", "--manifest-path"])
         .arg(cargo_toml.as_ref());
+    // Cargo looks for `.cargo/config.toml` from the current directory, not from
+    // the manifest.
+    if let Some(parent) = cargo_toml.parent() {
+        cmd.current_dir(parent);
+    }
 
     // --all-targets includes tests, benches and examples in addition to the
     // default lib and bins. This is an independent concept from the --targets
@@ -0,0 +1,230 @@
+//! Reads the parts of cargo's `.cargo/config.toml` files which affect how
+//! crates are compiled: `build.target`, the rustflags and the `[env]` table.
+//!
+//! Like cargo, we look at the config files in the directory of the workspace
+//! and in all of its parents, and then at the one in `$CARGO_HOME`.
+//!
+//! `target.<cfg>` tables, like `[target.'cfg(unix)']`, are ignored: which of
+//! them apply depends on the cfgs of the target, which in turn depend on the
+//! rustflags they set.
+use std::{env, fs, path::Path};
+
+use rustc_hash::FxHashMap;
+use toml::{value::Table, Value};
+
+/// The merged config of all `.cargo/config.toml` files for a directory.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct CargoConfigFile {
+    /// `build.target`
+    pub(crate) target: Option<String>,
+    /// `build.rustflags`
+    build_rustflags: Option<Vec<String>>,
+    /// `target.<triple>.rustflags`
+    target_rustflags: FxHashMap<String, Vec<String>>,
+    /// The `[env]` table, with relative paths already resolved.
+    pub(crate) env: Vec<(String, String)>,
+}
+
+impl CargoConfigFile {
+    pub(crate) fn discover(dir: &Path) -> CargoConfigFile {
+        let _p = profile::span("CargoConfigFile::discover");
+        let mut dirs = dir.ancestors().map(|it| it.join(".cargo")).collect::<Vec<_>>();
+        if let Some(cargo_home) = toolchain::cargo_home() {
+            if !dirs.contains(&cargo_home) {
+                dirs.push(cargo_home);
+            }
+        }
+
+        // Files closer to the workspace take precedence, so they are merged last.
+        let mut res = CargoConfigFile::default();
+        for dir in dirs.iter().rev() {
+            let file =
+                ["config", "config.toml"].iter().map(|it| dir.join(it)).find(|it| it.is_file());
+            let file = match file {
+                Some(it) => it,
+                None => continue,
+            };
+            let text = match fs::read_to_string(&file) {
+                Ok(it) => it,
+                Err(e) => {
+                    log::warn!("failed to read {}: {}", file.display(), e);
+                    continue;
+                }
+            };
+            if let Err(e) = res.merge(&text, dir.parent().unwrap_or(dir)) {
+                log::warn!("failed to parse {}: {}", file.display(), e);
+            }
+        }
+        res
+    }
+
+    /// The flags cargo passes to rustc when compiling for `target`, in the same
+    /// order of precedence as cargo.
+    pub(crate) fn rustflags(&self, target: Option<&str>) -> Vec<String> {
+        if let Ok(flags) = env::var("CARGO_ENCODED_RUSTFLAGS") {
+            return flags.split('\x1f').filter(|it| !it.is_empty()).map(String::from).collect();
+        }
+        if let Ok(flags) = env::var("RUSTFLAGS") {
+            return flags.split_whitespace().map(String::from).collect();
+        }
+        if let Some(flags) = target.and_then(|it| self.target_rustflags.get(it)) {
+            return flags.clone();
+        }
+        self.build_rustflags.clone().unwrap_or_default()
+    }
+
+    /// Merges the config file `text` into `self`. `config_root` is the directory
+    /// containing the `.cargo` directory of the file.
+    fn merge(&mut self, text: &str, config_root: &Path) -> Result<(), toml::de::Error> {
+        let config: Table = toml::from_str(text)?;
+        let table = |table: &Table, key: &str| table.get(key).and_then(Value::as_table).cloned();
+
+        if let Some(build) = table(&config, "build") {
+            if let Some(target) = build.get("target").and_then(Value::as_str) {
+                self.target = Some(target.to_string());
+            }
+            if let Some(flags) = build.get("rustflags") {
+                merge_flags(flags, self.build_rustflags.get_or_insert_with(Vec::new));
+            }
+        }
+        for (triple, target) in table(&config, "target").unwrap_or_default() {
+            if triple.starts_with("cfg(") {
+                continue;
+            }
+            if let Some(flags) = target.get("rustflags") {
+                merge_flags(flags, self.target_rustflags.entry(triple).or_default());
+            }
+        }
+        for (name, value) in table(&config, "env").unwrap_or_default() {
+            let (value, relative, force) = match &value {
+                Value::String(it) => (it.clone(), false, false),
+                Value::Table(it) => {
+                    let flag = |key| it.get(key).and_then(Value::as_bool).unwrap_or(false);
+                    match it.get("value").and_then(Value::as_str) {
+                        Some(value) => (value.to_string(), flag("relative"), flag("force")),
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            // Like cargo, the variables of the environment win unless `force` is set.
+            if !force && env::var_os(&name).is_some() {
+                continue;
+            }
+            let value =
+                if relative { config_root.join(value).display().to_string() } else { value };
+            self.env.retain(|(it, _)| *it != name);
+            self.env.push((name, value));
+        }
+        Ok(())
+    }
+}
+
+/// rustflags are either a single string of space-separated flags, which
+/// replaces the flags of less specific files, or an array of flags, which
+/// is appended to them.
+fn merge_flags(value: &Value, flags: &mut Vec<String>) {
+    match value {
+        Value::String(it) => *flags = it.split_whitespace().map(String::from).collect(),
+        Value::Array(items) => {
+            flags.extend(items.iter().filter_map(Value::as_str).map(String::from))
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::CargoConfigFile;
+
+    fn merge(files: &[&str]) -> CargoConfigFile {
+        let mut res = CargoConfigFile::default();
+        for file in files {
+            res.merge(file, Path::new("/ws")).unwrap();
+        }
+        res
+    }
+
+    #[test]
+    fn reads_build_target_and_rustflags() {
+        let config = merge(&[r#"
+[build]
+target = "x86_64-unknown-linux-gnu"
+rustflags = "--cfg a"
+
+[target.wasm32-unknown-unknown]
+rustflags = ["--cfg", "wasm"]
+
+[target.'cfg(unix)']
+rustflags = ["--cfg", "unix_only"]
+"#]);
+        assert_eq!(config.target.as_deref(), Some("x86_64-unknown-linux-gnu"));
+        assert_eq!(config.build_rustflags, Some(vec!["--cfg".to_string(), "a".to_string()]));
+        assert_eq!(
+            config.target_rustflags.get("wasm32-unknown-unknown"),
+            Some(&vec!["--cfg".to_string(), "wasm".to_string()])
+        );
+        assert_eq!(config.target_rustflags.len(), 1);
+    }
+
+    #[test]
+    fn closer_files_take_precedence() {
+        let config = merge(&[
+            r#"
+build.target = "a"
+build.rustflags = ["--cfg", "outer"]
+target.a.rustflags = "--cfg replaced"
+"#,
+            r#"
+build.target = "b"
+build.rustflags = ["--cfg", "inner"]
+target.a.rustflags = "--cfg replacement"
+"#,
+        ]);
+        assert_eq!(config.target.as_deref(), Some("b"));
+        assert_eq!(
+            config.build_rustflags,
+            Some(vec!["--cfg", "outer", "--cfg", "inner"].into_iter().map(String::from).collect())
+        );
+        assert_eq!(
+            config.target_rustflags.get("a"),
+            Some(&vec!["--cfg".to_string(), "replacement".to_string()])
+        );
+    }
+
+    #[test]
+    fn reads_env() {
+        let config = merge(&[
+            r#"
+[env]
+RA_TEST_PLAIN = "outer"
+RA_TEST_RELATIVE = { value = "assets", relative = true }
+RA_TEST_NO_VALUE = { relative = true }
+"#,
+            r#"
+[env]
+RA_TEST_PLAIN = "inner"
+PATH = "not forced"
+CARGO = { value = "forced", force = true }
+"#,
+        ]);
+        let relative = Path::new("/ws").join("assets").display().to_string();
+        assert_eq!(
+            config.env,
+            vec![
+                ("RA_TEST_RELATIVE".to_string(), relative),
+                ("CARGO".to_string(), "forced".to_string()),
+                ("RA_TEST_PLAIN".to_string(), "inner".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_file_is_an_error() {
+        let mut config = CargoConfigFile::default();
+        assert!(config.merge("[build\ntarget = 1", Path::new("/ws")).is_err());
+        assert_eq!(config, CargoConfigFile::default());
+    }
+}
@@ -10,6 +10,7 @@ use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::FxHashMap;
 
 use crate::build_data::BuildDataConfig;
+use crate::cfg_flag::CfgFlag;
 use crate::utf8_stdout;
 
 /// `CargoWorkspace` represents the logical structure of, well, a Cargo
@@ -27,6 +28,7 @@ pub struct CargoWorkspace {
     packages: Arena<PackageData>,
     targets: Arena<TargetData>,
     workspace_root: AbsPathBuf,
+    target_directory: AbsPathBuf,
     build_data_config: BuildDataConfig,
 }
 
@@ -74,6 +76,10 @@ pub struct CargoConfig {
 
     /// rustc private crate source
     pub rustc_source: Option<RustcSource>,
+
+    /// Extra cfg flags for the crates of the workspace, like the `--cfg` flags
+    /// passed through `RUSTFLAGS`.
+    pub cfgs: Vec<CfgFlag>,
 }
 
 pub type Package = Idx<PackageData>;
@@ -109,6 +115,38 @@ pub struct PackageData {
 pub struct PackageDependency {
     pub pkg: Package,
     pub name: String,
+    /// The kinds of the dependency, as a package can be both a normal and a build-dependency.
+    pub kinds: Vec<DepKind>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum DepKind {
+    /// Available to all targets of the package, but not to its build script.
+    Normal,
+    /// Available to tests, examples and benches, and to the other targets under `cfg(test)`.
+    Dev,
+    /// Available only to the build script.
+    Build,
+}
+
+impl DepKind {
+    fn new(kinds: &[cargo_metadata::DepKindInfo]) -> Vec<DepKind> {
+        let mut res = kinds
+            .iter()
+            .map(|it| match it.kind {
+                cargo_metadata::DependencyKind::Development => DepKind::Dev,
+                cargo_metadata::DependencyKind::Build => DepKind::Build,
+                _ => DepKind::Normal,
+            })
+            .collect::<Vec<_>>();
+        // Cargo older than 1.41 doesn't report the kinds.
+        if res.is_empty() {
+            res.push(DepKind::Normal);
+        }
+        res.sort();
+        res.dedup();
+        res
+    }
 }
 
 /// Information associated with a package's target
@@ -134,6 +172,7 @@ pub enum TargetKind {
     Example,
     Test,
     Bench,
+    BuildScript,
     Other,
 }
 
@@ -145,6 +184,7 @@ impl TargetKind {
                 "test" => TargetKind::Test,
                 "bench" => TargetKind::Bench,
                 "example" => TargetKind::Example,
+                "custom-build" => TargetKind::BuildScript,
                 "proc-macro" => TargetKind::Lib,
                 _ if kind.contains("lib") => TargetKind::Lib,
                 _ => continue,
@@ -184,32 +224,9 @@ impl CargoWorkspace {
         if let Some(parent) = cargo_toml.parent() {
             meta.current_dir(parent.to_path_buf());
         }
-        let target = if let Some(target) = config.target.as_ref() {
-            Some(target.clone())
-        } else {
-            // cargo metadata defaults to giving information for _all_ targets.
-            // In the absence of a preference from the user, we use the host platform.
-            let mut rustc = Command::new(toolchain::rustc());
-            rustc.current_dir(cargo_toml.parent().unwrap()).arg("-vV");
-            log::debug!("Discovering host platform by {:?}", rustc);
-            match utf8_stdout(rustc) {
-                Ok(stdout) => {
-                    let field = "host: ";
-                    let target = stdout.lines().find_map(|l| l.strip_prefix(field));
-                    if let Some(target) = target {
-                        Some(target.to_string())
-                    } else {
-                        // If we fail to resolve the host platform, it's not the end of the world.
-                        log::info!("rustc -vV did not report host platform, got:\n{}", stdout);
-                        None
-                    }
-                }
-                Err(e) => {
-                    log::warn!("Failed to discover host platform: {}", e);
-                    None
-                }
-            }
-        };
+        // cargo metadata defaults to giving information for _all_ targets.
+        // In the absence of a preference from the user, we use the host platform.
+        let target = config.target.clone().or_else(|| rustc_host(cargo_toml));
         if let Some(target) = target {
             meta.other_options(vec![String::from("--filter-platform"), target]);
         }
@@ -217,7 +234,7 @@ impl CargoWorkspace {
         // FIXME: Currently MetadataCommand is not based on parse_stream,
         // So we just report it as a whole
         progress("metadata".to_string());
-        let mut meta = meta.exec().with_context(|| {
+        let meta = meta.exec().with_context(|| {
             let cwd: Option<AbsPathBuf> =
                 std::env::current_dir().ok().and_then(|p| p.try_into().ok());
 
@@ -235,6 +252,14 @@ impl CargoWorkspace {
             )
         })?;
 
+        CargoWorkspace::new(cargo_toml, config, meta)
+    }
+
+    pub(crate) fn new(
+        cargo_toml: &AbsPath,
+        config: &CargoConfig,
+        mut meta: cargo_metadata::Metadata,
+    ) -> Result<CargoWorkspace> {
         let mut pkg_by_id = FxHashMap::default();
         let mut packages = Arena::default();
         let mut targets = Arena::default();
@@ -299,20 +324,28 @@ impl CargoWorkspace {
                         continue;
                     }
                 };
-                let dep = PackageDependency { name: dep_node.name, pkg };
+                let kinds = DepKind::new(&dep_node.dep_kinds);
+                let dep = PackageDependency { name: dep_node.name, pkg, kinds };
                 packages[source].dependencies.push(dep);
             }
             packages[source].active_features.extend(node.features);
         }
 
         let workspace_root = AbsPathBuf::assert(meta.workspace_root);
+        let target_directory = AbsPathBuf::assert(meta.target_directory);
         let build_data_config = BuildDataConfig::new(
             cargo_toml.to_path_buf(),
             config.clone(),
             Arc::new(meta.packages.clone()),
         );
 
-        Ok(CargoWorkspace { packages, targets, workspace_root, build_data_config })
+        Ok(CargoWorkspace {
+            packages,
+            targets,
+            workspace_root,
+            target_directory,
+            build_data_config,
+        })
     }
 
     pub fn packages<'a>(&'a self) -> impl Iterator<Item = Package> + ExactSizeIterator + 'a {
@@ -330,6 +363,11 @@ impl CargoWorkspace {
         &self.workspace_root
     }
 
+    /// The directory build artifacts are placed in, `target` by default.
+    pub fn target_directory(&self) -> &AbsPath {
+        &self.target_directory
+    }
+
     pub fn package_flag(&self, package: &PackageData) -> String {
         if self.is_unique(&*package.name) {
             package.name.clone()
@@ -346,3 +384,27 @@ impl CargoWorkspace {
         self.packages.iter().filter(|(_, v)| v.name == name).count() == 1
     }
 }
+
+/// The target triple of the host platform, as reported by `rustc -vV`.
+pub(crate) fn rustc_host(cargo_toml: &AbsPath) -> Option<String> {
+    let mut rustc = Command::new(toolchain::rustc());
+    rustc.current_dir(cargo_toml.parent().unwrap()).arg("-vV");
+    log::debug!("Discovering host platform by {:?}", rustc);
+    match utf8_stdout(rustc) {
+        Ok(stdout) => {
+            let field = "host: ";
+            let target = stdout.lines().find_map(|l| l.strip_prefix(field));
+            if let Some(target) = target {
+                Some(target.to_string())
+            } else {
+                // If we fail to resolve the host platform, it's not the end of the world.
+                log::info!("rustc -vV did not report host platform, got:\n{}", stdout);
+                None
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to discover host platform: {}", e);
+            None
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! FIXME: write short doc here
 
+mod cargo_config_file;
 mod cargo_workspace;
 mod cfg_flag;
 mod project_json;
@@ -21,9 +22,10 @@ use rustc_hash::FxHashSet;
 pub use crate::{
     build_data::{BuildDataCollector, BuildDataResult},
     cargo_workspace::{
-        CargoConfig, CargoWorkspace, Package, PackageData, PackageDependency, RustcSource, Target,
-        TargetData, TargetKind,
+        CargoConfig, CargoWorkspace, DepKind, Package, PackageData, PackageDependency, RustcSource,
+        Target, TargetData, TargetKind,
     },
+    cfg_flag::CfgFlag,
     project_json::{ProjectJson, ProjectJsonData},
     sysroot::Sysroot,
     workspace::{PackageRoot, ProjectWorkspace},
@@ -4,7 +4,9 @@ use std::process::Command;
 
 use crate::{cfg_flag::CfgFlag, utf8_stdout};
 
-pub(crate) fn get(target: Option<&str>) -> Vec<CfgFlag> {
+/// `rustflags` are passed along, so that the `--cfg` and `-C target-feature`
+/// flags from `RUSTFLAGS` or `.cargo/config.toml` are reported too.
+pub(crate) fn get(target: Option<&str>, rustflags: &[String]) -> Vec<CfgFlag> {
     let _p = profile::span("rustc_cfg::get");
     let mut res = Vec::new();
 
@@ -22,6 +24,7 @@ pub(crate) fn get(target: Option<&str>) -> Vec<CfgFlag> {
         if let Some(target) = target {
             cmd.args(&["--target", target]);
         }
+        cmd.args(rustflags);
         utf8_stdout(cmd)
     };
 
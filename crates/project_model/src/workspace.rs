@@ -17,12 +17,13 @@ use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
     build_data::{BuildData, BuildDataMap, BuildDataResult},
-    cargo_workspace,
+    cargo_config_file::CargoConfigFile,
+    cargo_workspace::{self, rustc_host},
     cfg_flag::CfgFlag,
     rustc_cfg,
     sysroot::SysrootCrate,
-    utf8_stdout, BuildDataCollector, CargoConfig, CargoWorkspace, ProjectJson, ProjectManifest,
-    Sysroot, TargetKind,
+    utf8_stdout, BuildDataCollector, CargoConfig, CargoWorkspace, DepKind, ProjectJson,
+    ProjectManifest, Sysroot, Target, TargetKind,
 };
 
 /// `PackageRoot` describes a package root folder.
@@ -49,16 +50,26 @@ pub enum ProjectWorkspace {
         /// FIXME: make this a per-crate map, as, eg, build.rs might have a
         /// different target.
         rustc_cfg: Vec<CfgFlag>,
+        /// Extra cfg flags from the config, enabled for the crates of the
+        /// workspace, but not for the sysroot.
+        extra_cfg: Vec<CfgFlag>,
+        /// Environment variables from the `[env]` table of `.cargo/config.toml`.
+        extra_env: Vec<(String, String)>,
     },
     /// Project workspace was manually specified using a `rust-project.json` file.
-    Json { project: ProjectJson, sysroot: Option<Sysroot>, rustc_cfg: Vec<CfgFlag> },
+    Json {
+        project: ProjectJson,
+        sysroot: Option<Sysroot>,
+        rustc_cfg: Vec<CfgFlag>,
+        extra_cfg: Vec<CfgFlag>,
+    },
 }
 
 impl fmt::Debug for ProjectWorkspace {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Make sure this isn't too verbose.
         match self {
-            ProjectWorkspace::Cargo { cargo, sysroot, rustc, rustc_cfg } => f
+            ProjectWorkspace::Cargo { cargo, sysroot, rustc, rustc_cfg, extra_cfg, .. } => f
                 .debug_struct("Cargo")
                 .field("n_packages", &cargo.packages().len())
                 .field("n_sysroot_crates", &sysroot.crates().len())
@@ -67,14 +78,16 @@ impl fmt::Debug for ProjectWorkspace {
                     &rustc.as_ref().map_or(0, |rc| rc.packages().len()),
                 )
                 .field("n_rustc_cfg", &rustc_cfg.len())
+                .field("n_extra_cfg", &extra_cfg.len())
                 .finish(),
-            ProjectWorkspace::Json { project, sysroot, rustc_cfg } => {
+            ProjectWorkspace::Json { project, sysroot, rustc_cfg, extra_cfg } => {
                 let mut debug_struct = f.debug_struct("Json");
                 debug_struct.field("n_crates", &project.n_crates());
                 if let Some(sysroot) = sysroot {
                     debug_struct.field("n_sysroot_crates", &sysroot.crates().len());
                 }
                 debug_struct.field("n_rustc_cfg", &rustc_cfg.len());
+                debug_struct.field("n_extra_cfg", &extra_cfg.len());
                 debug_struct.finish()
             }
         }
@@ -97,7 +110,7 @@ impl ProjectWorkspace {
                 })?;
                 let project_location = project_json.parent().unwrap().to_path_buf();
                 let project_json = ProjectJson::new(&project_location, data);
-                ProjectWorkspace::load_inline(project_json, config.target.as_deref())?
+                ProjectWorkspace::load_inline(project_json, config)?
             }
             ProjectManifest::CargoToml(cargo_toml) => {
                 let cargo_version = utf8_stdout({
@@ -106,14 +119,28 @@ impl ProjectWorkspace {
                     cmd
                 })?;
 
-                let cargo = CargoWorkspace::from_cargo_metadata(&cargo_toml, config, progress)
-                    .with_context(|| {
-                        format!(
-                            "Failed to read Cargo metadata from Cargo.toml file {}, {}",
-                            cargo_toml.display(),
-                            cargo_version
-                        )
-                    })?;
+                // Cargo itself picks up `build.target` from `.cargo/config.toml`, but
+                // `cargo metadata --filter-platform` and `rustc --print cfg` don't.
+                let dot_cargo = CargoConfigFile::discover(cargo_toml.parent().unwrap().as_ref());
+                let mut config = config.clone();
+                if config.target.is_none() {
+                    config.target = dot_cargo.target.clone();
+                }
+                let config = &config;
+                // `cargo metadata` reports the dependencies of all platforms, unless it's
+                // filtered to the target, which defaults to the host.
+                let target = config.target.clone().or_else(|| rustc_host(&cargo_toml));
+                let metadata_config = &CargoConfig { target: target.clone(), ..config.clone() };
+
+                let cargo =
+                    CargoWorkspace::from_cargo_metadata(&cargo_toml, metadata_config, progress)
+                        .with_context(|| {
+                            format!(
+                                "Failed to read Cargo metadata from Cargo.toml file {}, {}",
+                                cargo_toml.display(),
+                                cargo_version
+                            )
+                        })?;
 
                 let sysroot = if config.no_sysroot {
                     Sysroot::default()
@@ -138,7 +165,7 @@ impl ProjectWorkspace {
 
                 let rustc = if let Some(rustc_dir) = rustc_dir {
                     Some(
-                        CargoWorkspace::from_cargo_metadata(&rustc_dir, config, progress)
+                        CargoWorkspace::from_cargo_metadata(&rustc_dir, metadata_config, progress)
                             .with_context(|| {
                                 format!("Failed to read Cargo metadata for Rust sources")
                             })?,
@@ -146,8 +173,11 @@ impl ProjectWorkspace {
                 } else {
                     None
                 };
-                let rustc_cfg = rustc_cfg::get(config.target.as_deref());
-                ProjectWorkspace::Cargo { cargo, sysroot, rustc, rustc_cfg }
+                let rustflags = dot_cargo.rustflags(target.as_deref());
+                let rustc_cfg = rustc_cfg::get(config.target.as_deref(), &rustflags);
+                let extra_cfg = config.cfgs.clone();
+                let extra_env = dot_cargo.env;
+                ProjectWorkspace::Cargo { cargo, sysroot, rustc, rustc_cfg, extra_cfg, extra_env }
             }
         };
 
@@ -156,14 +186,15 @@ impl ProjectWorkspace {
 
     pub fn load_inline(
         project_json: ProjectJson,
-        target: Option<&str>,
+        config: &CargoConfig,
     ) -> Result<ProjectWorkspace> {
         let sysroot = match &project_json.sysroot_src {
             Some(path) => Some(Sysroot::load(path)?),
             None => None,
         };
-        let rustc_cfg = rustc_cfg::get(target);
-        Ok(ProjectWorkspace::Json { project: project_json, sysroot, rustc_cfg })
+        let rustc_cfg = rustc_cfg::get(config.target.as_deref(), &[]);
+        let extra_cfg = config.cfgs.clone();
+        Ok(ProjectWorkspace::Json { project: project_json, sysroot, rustc_cfg, extra_cfg })
     }
 
     /// Returns the roots for the current `ProjectWorkspace`
@@ -171,7 +202,7 @@ impl ProjectWorkspace {
     /// the root is a member of the current workspace
     pub fn to_roots(&self, build_data: Option<&BuildDataResult>) -> Vec<PackageRoot> {
         match self {
-            ProjectWorkspace::Json { project, sysroot, rustc_cfg: _ } => project
+            ProjectWorkspace::Json { project, sysroot, .. } => project
                 .crates()
                 .map(|(_, krate)| PackageRoot {
                     is_member: krate.is_workspace_member,
@@ -188,7 +219,7 @@ impl ProjectWorkspace {
                     })
                 }))
                 .collect::<Vec<_>>(),
-            ProjectWorkspace::Cargo { cargo, sysroot, rustc, rustc_cfg: _ } => cargo
+            ProjectWorkspace::Cargo { cargo, sysroot, rustc, .. } => cargo
                 .packages()
                 .map(|pkg| {
                     let is_member = cargo[pkg].is_member;
@@ -251,23 +282,33 @@ impl ProjectWorkspace {
         };
 
         let mut crate_graph = match self {
-            ProjectWorkspace::Json { project, sysroot, rustc_cfg } => project_json_to_crate_graph(
-                rustc_cfg.clone(),
-                &proc_macro_loader,
-                load,
-                project,
-                sysroot,
-            ),
-            ProjectWorkspace::Cargo { cargo, sysroot, rustc, rustc_cfg } => cargo_to_crate_graph(
-                rustc_cfg.clone(),
-                &proc_macro_loader,
-                load,
-                cargo,
-                build_data.and_then(|it| it.get(cargo.workspace_root())),
-                sysroot,
-                rustc,
-                rustc.as_ref().zip(build_data).and_then(|(it, map)| map.get(it.workspace_root())),
-            ),
+            ProjectWorkspace::Json { project, sysroot, rustc_cfg, extra_cfg } => {
+                project_json_to_crate_graph(
+                    rustc_cfg.clone(),
+                    extra_cfg,
+                    &proc_macro_loader,
+                    load,
+                    project,
+                    sysroot,
+                )
+            }
+            ProjectWorkspace::Cargo { cargo, sysroot, rustc, rustc_cfg, extra_cfg, extra_env } => {
+                cargo_to_crate_graph(
+                    rustc_cfg.clone(),
+                    extra_cfg,
+                    extra_env,
+                    &proc_macro_loader,
+                    load,
+                    cargo,
+                    build_data.and_then(|it| it.get(cargo.workspace_root())),
+                    sysroot,
+                    rustc,
+                    rustc
+                        .as_ref()
+                        .zip(build_data)
+                        .and_then(|(it, map)| map.get(it.workspace_root())),
+                )
+            }
         };
         if crate_graph.patch_cfg_if() {
             log::debug!("Patched std to depend on cfg-if")
@@ -292,6 +333,7 @@ impl ProjectWorkspace {
 
 fn project_json_to_crate_graph(
     rustc_cfg: Vec<CfgFlag>,
+    extra_cfg: &[CfgFlag],
     proc_macro_loader: &dyn Fn(&Path) -> Vec<ProcMacro>,
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
     project: &ProjectJson,
@@ -316,13 +358,15 @@ fn project_json_to_crate_graph(
 
             let target_cfgs = match krate.target.as_deref() {
                 Some(target) => {
-                    cfg_cache.entry(target).or_insert_with(|| rustc_cfg::get(Some(target)))
+                    cfg_cache.entry(target).or_insert_with(|| rustc_cfg::get(Some(target), &[]))
                 }
                 None => &rustc_cfg,
             };
 
             let mut cfg_options = CfgOptions::default();
-            cfg_options.extend(target_cfgs.iter().chain(krate.cfg.iter()).cloned());
+            cfg_options.extend(
+                target_cfgs.iter().chain(krate.cfg.iter()).chain(extra_cfg.iter()).cloned(),
+            );
             (
                 crate_id,
                 crate_graph.add_crate_root(
@@ -357,6 +401,8 @@ fn project_json_to_crate_graph(
 
 fn cargo_to_crate_graph(
     rustc_cfg: Vec<CfgFlag>,
+    extra_cfg: &[CfgFlag],
+    extra_env: &[(String, String)],
     proc_macro_loader: &dyn Fn(&Path) -> Vec<ProcMacro>,
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
     cargo: &CargoWorkspace,
@@ -378,6 +424,7 @@ fn cargo_to_crate_graph(
     // Add test cfg for non-sysroot crates
     cfg_options.insert_atom("test".into());
     cfg_options.insert_atom("debug_assertions".into());
+    cfg_options.extend(extra_cfg.iter().cloned());
 
     let mut pkg_crates = FxHashMap::default();
 
@@ -385,12 +432,23 @@ fn cargo_to_crate_graph(
     for pkg in cargo.packages() {
         let mut lib_tgt = None;
         for &tgt in cargo[pkg].targets.iter() {
+            // The tests, examples and benches of dependencies are excluded from the roots.
+            if !cargo[pkg].is_member
+                && matches!(
+                    cargo[tgt].kind,
+                    TargetKind::Test | TargetKind::Example | TargetKind::Bench
+                )
+            {
+                continue;
+            }
             if let Some(file_id) = load(&cargo[tgt].root) {
                 let crate_id = add_target_crate_root(
                     &mut crate_graph,
-                    &cargo[pkg],
+                    cargo,
+                    tgt,
                     build_data_map.and_then(|it| it.get(&cargo[pkg].id)),
                     &cfg_options,
+                    extra_env,
                     proc_macro_loader,
                     file_id,
                 );
@@ -409,12 +467,12 @@ fn cargo_to_crate_graph(
                     }
                 }
 
-                pkg_crates.entry(pkg).or_insert_with(Vec::new).push(crate_id);
+                pkg_crates.entry(pkg).or_insert_with(Vec::new).push((crate_id, cargo[tgt].kind));
             }
         }
 
         // Set deps to the core, std and to the lib target of the current package
-        for &from in pkg_crates.get(&pkg).into_iter().flatten() {
+        for &(from, _) in pkg_crates.get(&pkg).into_iter().flatten() {
             if let Some((to, name)) = lib_tgt.clone() {
                 if to != from {
                     // For root projects with dashes in their name,
@@ -436,7 +494,18 @@ fn cargo_to_crate_graph(
         for dep in cargo[pkg].dependencies.iter() {
             let name = CrateName::new(&dep.name).unwrap();
             if let Some(&to) = pkg_to_lib_crate.get(&dep.pkg) {
-                for &from in pkg_crates.get(&pkg).into_iter().flatten() {
+                for &(from, kind) in pkg_crates.get(&pkg).into_iter().flatten() {
+                    // Build-dependencies are available to the build script, and only to it.
+                    // Dev-dependencies go to all the other targets, as even the library is
+                    // analyzed with `cfg(test)` enabled.
+                    let is_available = if kind == TargetKind::BuildScript {
+                        dep.kinds.contains(&DepKind::Build)
+                    } else {
+                        dep.kinds.iter().any(|&it| it != DepKind::Build)
+                    };
+                    if !is_available {
+                        continue;
+                    }
                     add_dep(&mut crate_graph, from, name.clone(), to)
                 }
             }
@@ -465,9 +534,11 @@ fn cargo_to_crate_graph(
                 if let Some(file_id) = load(&rustc_workspace[tgt].root) {
                     let crate_id = add_target_crate_root(
                         &mut crate_graph,
-                        &rustc_workspace[pkg],
+                        rustc_workspace,
+                        tgt,
                         rustc_build_data_map.and_then(|it| it.get(&rustc_workspace[pkg].id)),
                         &cfg_options,
+                        extra_env,
                         proc_macro_loader,
                         file_id,
                     );
@@ -502,7 +573,7 @@ fn cargo_to_crate_graph(
                     if !cargo[pkg].is_member {
                         continue;
                     }
-                    for &from in pkg_crates.get(&pkg).into_iter().flatten() {
+                    for &(from, _) in pkg_crates.get(&pkg).into_iter().flatten() {
                         add_dep(&mut crate_graph, from, name.clone(), to);
                     }
                 }
@@ -514,12 +585,16 @@ fn cargo_to_crate_graph(
 
 fn add_target_crate_root(
     crate_graph: &mut CrateGraph,
-    pkg: &cargo_workspace::PackageData,
+    cargo: &CargoWorkspace,
+    tgt: Target,
     build_data: Option<&BuildData>,
     cfg_options: &CfgOptions,
+    extra_env: &[(String, String)],
     proc_macro_loader: &dyn Fn(&Path) -> Vec<ProcMacro>,
     file_id: FileId,
 ) -> CrateId {
+    let target = &cargo[tgt];
+    let pkg = &cargo[target.package];
     let edition = pkg.edition;
     let cfg_options = {
         let mut opts = cfg_options.clone();
@@ -533,11 +608,31 @@ fn add_target_crate_root(
     };
 
     let mut env = Env::default();
+    for (k, v) in extra_env {
+        env.set(k, v.clone());
+    }
     if let Some(envs) = build_data.map(|it| &it.envs) {
         for (k, v) in envs {
             env.set(k, v.clone());
         }
     }
+    env.set("CARGO_CRATE_NAME", CrateName::normalize_dashes(&target.name).to_string());
+    match target.kind {
+        TargetKind::Bin => env.set("CARGO_BIN_NAME", target.name.clone()),
+        TargetKind::Test | TargetKind::Bench => {
+            let bins =
+                pkg.targets.iter().map(|&it| &cargo[it]).filter(|it| it.kind == TargetKind::Bin);
+            for bin in bins {
+                let exe = cargo.target_directory().join("debug").join(format!(
+                    "{}{}",
+                    bin.name,
+                    std::env::consts::EXE_SUFFIX
+                ));
+                env.set(&format!("CARGO_BIN_EXE_{}", bin.name), exe.display().to_string());
+            }
+        }
+        _ => (),
+    }
 
     let proc_macro = build_data
         .as_ref()
@@ -545,7 +640,9 @@ fn add_target_crate_root(
         .map(|it| proc_macro_loader(&it))
         .unwrap_or_default();
 
-    let display_name = CrateDisplayName::from_canonical_name(pkg.name.clone());
+    // Binaries, tests, examples and benches are told apart from the library by their own name.
+    let name = if target.kind == TargetKind::Lib { &pkg.name } else { &target.name };
+    let display_name = CrateDisplayName::from_canonical_name(name.clone());
     let crate_id = crate_graph.add_crate_root(
         file_id,
         edition,
@@ -610,3 +707,243 @@ fn add_dep(graph: &mut CrateGraph, from: CrateId, name: CrateName, to: CrateId)
         log::error!("{}", err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use cfg::{CfgAtom, CfgExpr};
+    use expect_test::{expect, Expect};
+    use serde_json::json;
+
+    use super::*;
+
+    fn crate_graph(
+        metadata: serde_json::Value,
+        extra_cfg: &[CfgFlag],
+        sysroot: &Sysroot,
+    ) -> (CrateGraph, Vec<AbsPathBuf>) {
+        let meta = serde_json::from_value(metadata).unwrap();
+        let cargo_toml = AbsPathBuf::assert("/ws/Cargo.toml".into());
+        let cargo = CargoWorkspace::new(&cargo_toml, &CargoConfig::default(), meta).unwrap();
+
+        let mut files = Vec::new();
+        let mut load = |path: &AbsPath| {
+            files.push(path.to_path_buf());
+            Some(FileId(files.len() as u32 - 1))
+        };
+        let crate_graph = cargo_to_crate_graph(
+            Vec::new(),
+            extra_cfg,
+            &[],
+            &|_| Vec::new(),
+            &mut load,
+            &cargo,
+            None,
+            sysroot,
+            &None,
+            None,
+        );
+        (crate_graph, files)
+    }
+
+    fn check_crate_graph(metadata: serde_json::Value, expect: Expect) {
+        let (crate_graph, files) = crate_graph(metadata, &[], &Sysroot::default());
+        let mut crates = crate_graph
+            .iter()
+            .map(|krate| {
+                let data = &crate_graph[krate];
+                let name = |krate: CrateId| crate_graph[krate].display_name.as_ref().unwrap();
+                let mut deps = data
+                    .dependencies
+                    .iter()
+                    .map(|dep| format!("{} ({})", dep.name, name(dep.crate_id)))
+                    .collect::<Vec<_>>();
+                deps.sort();
+                let mut env = data
+                    .env
+                    .iter()
+                    .filter(|(k, _)| k.starts_with("CARGO_CRATE") || k.starts_with("CARGO_BIN"))
+                    .map(|(k, v)| format!("    {}={}\n", k, v))
+                    .collect::<Vec<_>>();
+                env.sort();
+                format!(
+                    "{} {}: [{}]\n{}",
+                    name(krate),
+                    files[data.root_file_id.0 as usize].display(),
+                    deps.join(", "),
+                    env.concat()
+                )
+            })
+            .collect::<Vec<_>>();
+        crates.sort();
+        expect.assert_eq(&crates.concat());
+    }
+
+    fn package(name: &str, targets: &[(&str, &str, &str)]) -> serde_json::Value {
+        let dir = Path::new("/ws").join(name);
+        let targets = targets
+            .iter()
+            .map(|&(kind, name, src)| {
+                json!({ "name": name, "kind": [kind], "src_path": dir.join(src) })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "name": name,
+            "version": "0.1.0",
+            "id": name,
+            "dependencies": [],
+            "targets": targets,
+            "features": {},
+            "manifest_path": dir.join("Cargo.toml"),
+            "edition": "2018",
+        })
+    }
+
+    fn metadata() -> serde_json::Value {
+        json!({
+            "packages": [
+                package(
+                    "foo",
+                    &[
+                        ("lib", "foo", "src/lib.rs"),
+                        ("bin", "foo-cli", "src/main.rs"),
+                        ("test", "it", "tests/it.rs"),
+                        ("example", "demo", "examples/demo.rs"),
+                        ("bench", "perf", "benches/perf.rs"),
+                        ("custom-build", "build-script-build", "build.rs"),
+                    ],
+                ),
+                package(
+                    "bar",
+                    &[
+                        ("lib", "bar", "src/lib.rs"),
+                        ("test", "bar-tests", "tests/bar-tests.rs"),
+                        ("example", "bar-example", "examples/bar-example.rs"),
+                        ("bench", "bar-bench", "benches/bar-bench.rs"),
+                    ],
+                ),
+                package("build_dep", &[("lib", "build_dep", "src/lib.rs")]),
+                package("dev_dep", &[("lib", "dev_dep", "src/lib.rs")]),
+                package("both_dep", &[("lib", "both_dep", "src/lib.rs")]),
+            ],
+            "workspace_members": ["foo"],
+            "resolve": {
+                "nodes": [
+                    {
+                        "id": "foo",
+                        "dependencies": ["bar", "both_dep", "build_dep", "dev_dep"],
+                        "deps": [
+                            { "name": "bar", "pkg": "bar", "dep_kinds": [{ "kind": null }] },
+                            {
+                                "name": "build_dep",
+                                "pkg": "build_dep",
+                                "dep_kinds": [{ "kind": "build" }],
+                            },
+                            {
+                                "name": "dev_dep",
+                                "pkg": "dev_dep",
+                                "dep_kinds": [{ "kind": "dev" }],
+                            },
+                            {
+                                "name": "both_dep",
+                                "pkg": "both_dep",
+                                "dep_kinds": [{ "kind": null }, { "kind": "build" }],
+                            },
+                        ],
+                    },
+                    { "id": "bar", "dependencies": [] },
+                    { "id": "build_dep", "dependencies": [] },
+                    { "id": "dev_dep", "dependencies": [] },
+                    { "id": "both_dep", "dependencies": [] },
+                ],
+            },
+            "workspace_root": "/ws",
+            "target_directory": "/ws/target",
+            "version": 1,
+        })
+    }
+
+    // The tests, examples and benches of `bar` are skipped, as it isn't a member, and only the
+    // build script depends on `build_dep`. `both_dep` is both a normal and a build-dependency, so
+    // all the targets depend on it. Targets other than libraries are named after themselves.
+    #[test]
+    fn cargo_targets_crate_graph() {
+        check_crate_graph(
+            metadata(),
+            expect![[r#"
+                bar /ws/bar/src/lib.rs: []
+                    CARGO_CRATE_NAME=bar
+                both_dep /ws/both_dep/src/lib.rs: []
+                    CARGO_CRATE_NAME=both_dep
+                build_dep /ws/build_dep/src/lib.rs: []
+                    CARGO_CRATE_NAME=build_dep
+                build_script_build /ws/foo/build.rs: [both_dep (both_dep), build_dep (build_dep), foo (foo)]
+                    CARGO_CRATE_NAME=build_script_build
+                demo /ws/foo/examples/demo.rs: [bar (bar), both_dep (both_dep), dev_dep (dev_dep), foo (foo)]
+                    CARGO_CRATE_NAME=demo
+                dev_dep /ws/dev_dep/src/lib.rs: []
+                    CARGO_CRATE_NAME=dev_dep
+                foo /ws/foo/src/lib.rs: [bar (bar), both_dep (both_dep), dev_dep (dev_dep)]
+                    CARGO_CRATE_NAME=foo
+                foo_cli /ws/foo/src/main.rs: [bar (bar), both_dep (both_dep), dev_dep (dev_dep), foo (foo)]
+                    CARGO_BIN_NAME=foo-cli
+                    CARGO_CRATE_NAME=foo_cli
+                it /ws/foo/tests/it.rs: [bar (bar), both_dep (both_dep), dev_dep (dev_dep), foo (foo)]
+                    CARGO_BIN_EXE_foo-cli=/ws/target/debug/foo-cli
+                    CARGO_CRATE_NAME=it
+                perf /ws/foo/benches/perf.rs: [bar (bar), both_dep (both_dep), dev_dep (dev_dep), foo (foo)]
+                    CARGO_BIN_EXE_foo-cli=/ws/target/debug/foo-cli
+                    CARGO_CRATE_NAME=perf
+            "#]],
+        );
+    }
+
+    // The configured cfgs only apply to the crates of the workspace and its dependencies, the
+    // sysroot is compiled without them.
+    #[test]
+    fn extra_cfg_skips_sysroot() {
+        let sysroot_dir = std::env::temp_dir().join(format!("ra-sysroot-{}", std::process::id()));
+        for krate in ["core", "std"].iter() {
+            let src = sysroot_dir.join(krate).join("src");
+            std::fs::create_dir_all(&src).unwrap();
+            std::fs::write(src.join("lib.rs"), "").unwrap();
+        }
+        let sysroot = Sysroot::load(&AbsPathBuf::assert(sysroot_dir.clone())).unwrap();
+        std::fs::remove_dir_all(&sysroot_dir).unwrap();
+
+        let extra_cfg = ["my_flag".parse().unwrap(), r#"my_key="value""#.parse().unwrap()];
+        let (crate_graph, _) = crate_graph(metadata(), &extra_cfg, &sysroot);
+        let flag = CfgExpr::Atom(CfgAtom::Flag("my_flag".into()));
+        let key_value =
+            CfgExpr::Atom(CfgAtom::KeyValue { key: "my_key".into(), value: "value".into() });
+        let mut crates = crate_graph
+            .iter()
+            .map(|krate| {
+                let data = &crate_graph[krate];
+                format!(
+                    "{}: {:?} {:?}\n",
+                    data.display_name.as_ref().unwrap(),
+                    data.cfg_options.check(&flag),
+                    data.cfg_options.check(&key_value),
+                )
+            })
+            .collect::<Vec<_>>();
+        crates.sort();
+        expect![[r#"
+            bar: Some(true) Some(true)
+            both_dep: Some(true) Some(true)
+            build_dep: Some(true) Some(true)
+            build_script_build: Some(true) Some(true)
+            core: Some(false) Some(false)
+            demo: Some(true) Some(true)
+            dev_dep: Some(true) Some(true)
+            foo: Some(true) Some(true)
+            foo_cli: Some(true) Some(true)
+            it: Some(true) Some(true)
+            perf: Some(true) Some(true)
+            std: Some(false) Some(false)
+        "#]]
+        .assert_eq(&crates.concat());
+    }
+}
@@ -8,7 +8,9 @@ use std::{env, path::PathBuf};
 use anyhow::{bail, format_err, Result};
 use ide_ssr::{SsrPattern, SsrRule};
 use pico_args::Arguments;
-use rust_analyzer::cli::{AnalysisStatsCmd, BenchCmd, BenchWhat, Position, Verbosity};
+use rust_analyzer::cli::{
+    AnalysisStatsCmd, ApiReportCmd, BenchCmd, BenchWhat, Position, Verbosity,
+};
 use vfs::AbsPathBuf;
 
 pub(crate) struct Args {
@@ -24,9 +26,11 @@ pub(crate) enum Command {
     Parse { no_dump: bool },
     Symbols,
     Highlight { rainbow: bool },
+    CheckTypes,
     AnalysisStats(AnalysisStatsCmd),
     Bench(BenchCmd),
     Diagnostics { path: PathBuf, load_output_dirs: bool, with_proc_macro: bool },
+    ApiReport(ApiReportCmd),
     Ssr { rules: Vec<SsrRule> },
     StructuredSearch { debug_snippet: Option<String>, patterns: Vec<SsrPattern> },
     ProcMacro,
@@ -76,6 +80,9 @@ symbols < main.rs     Parse input an print the list of symbols
 highlight < main.rs   Highlight input as html
     --rainbow         Enable rainbow highlighting of identifiers
 
+check-types < main.rs Check the types of expressions annotated with `//^^^ type`
+                      comments, the input may be a multi-file fixture
+
 analysis-stats <PATH> Batch typecheck project and print summary statistics
     <PATH>            Directory with Cargo.toml
     --randomize       Randomize order in which crates, modules, and items are processed
@@ -106,6 +113,14 @@ diagnostics <PATH>
                       Load OUT_DIR values by running `cargo check` before analysis
     --with-proc-macro Use proc-macro-srv for proc-macro expanding
 
+api-report <PATH>     Print the public API of the workspace crates
+    <PATH>            Directory with Cargo.toml
+    --diff <PATH>     Compare with the API of this base revision of the workspace, and
+                      exit with a non-zero status code on semver-breaking changes
+    --load-output-dirs
+                      Load OUT_DIR values by running `cargo check` before analysis
+    --with-proc-macro Use proc-macro-srv for proc-macro expanding
+
 ssr [RULE...]
     <RULE>            A structured search replace rule (`$a.foo($b) ==> bar($a, $b)`)
 
@@ -184,6 +199,7 @@ impl Args {
             "parse" => Command::Parse { no_dump: matches.contains("--no-dump") },
             "symbols" => Command::Symbols,
             "highlight" => Command::Highlight { rainbow: matches.contains("--rainbow") },
+            "check-types" => Command::CheckTypes,
             "analysis-stats" => Command::AnalysisStats(AnalysisStatsCmd {
                 randomize: matches.contains("--randomize"),
                 parallel: matches.contains("--parallel"),
@@ -230,6 +246,14 @@ impl Args {
                     .opt_free_from_str()?
                     .ok_or_else(|| format_err!("expected positional argument"))?,
             },
+            "api-report" => Command::ApiReport(ApiReportCmd {
+                diff: matches.opt_value_from_str("--diff")?,
+                load_output_dirs: matches.contains("--load-output-dirs"),
+                with_proc_macro: matches.contains("--with-proc-macro"),
+                path: matches
+                    .opt_free_from_str()?
+                    .ok_or_else(|| format_err!("expected positional argument"))?,
+            }),
             "proc-macro" => Command::ProcMacro,
             "ssr" => Command::Ssr {
                 rules: {
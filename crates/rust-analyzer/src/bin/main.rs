@@ -50,11 +50,13 @@ fn try_main() -> Result<()> {
         args::Command::Parse { no_dump } => cli::parse(no_dump)?,
         args::Command::Symbols => cli::symbols()?,
         args::Command::Highlight { rainbow } => cli::highlight(rainbow)?,
+        args::Command::CheckTypes => cli::check_types()?,
         args::Command::AnalysisStats(cmd) => cmd.run(args.verbosity)?,
         args::Command::Bench(cmd) => cmd.run(args.verbosity)?,
         args::Command::Diagnostics { path, load_output_dirs, with_proc_macro } => {
             cli::diagnostics(path.as_ref(), load_output_dirs, with_proc_macro)?
         }
+        args::Command::ApiReport(cmd) => cmd.run()?,
         args::Command::Ssr { rules } => {
             cli::apply_ssr_rules(rules)?;
         }
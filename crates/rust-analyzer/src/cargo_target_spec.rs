@@ -49,7 +49,9 @@ impl CargoTargetSpec {
                 if let Some(spec) = spec {
                     spec.push_to(&mut args, kind);
                 }
-                extra_args.push(path.to_string());
+                // rustdoc matches the filter anywhere in the name of the doctests, so
+                // `foo` alone would also select the ones of `foobar`.
+                extra_args.push(format!("{}::", path));
                 extra_args.push("--nocapture".to_string());
             }
             RunnableKind::Bench { test_id } => {
@@ -72,6 +74,15 @@ impl CargoTargetSpec {
                 extra_args.push(test_id.to_string());
                 extra_args.push("--nocapture".to_string());
             }
+            RunnableKind::DocTestMod { path } => {
+                args.push("test".to_string());
+                args.push("--doc".to_string());
+                if let Some(spec) = spec {
+                    spec.push_to(&mut args, kind);
+                }
+                extra_args.push(path.to_string());
+                extra_args.push("--nocapture".to_string());
+            }
             RunnableKind::Bin => {
                 let subcommand = match spec {
                     Some(CargoTargetSpec { target_kind: TargetKind::Test, .. }) => "test",
@@ -136,7 +147,7 @@ impl CargoTargetSpec {
         buf.push(self.package);
 
         // Can't mix --doc with other target flags
-        if let RunnableKind::DocTest { .. } = kind {
+        if let RunnableKind::DocTest { .. } | RunnableKind::DocTestMod { .. } = kind {
             return;
         }
         match self.target_kind {
@@ -159,7 +170,7 @@ impl CargoTargetSpec {
             TargetKind::Lib => {
                 buf.push("--lib".to_string());
             }
-            TargetKind::Other => (),
+            TargetKind::BuildScript | TargetKind::Other => (),
         }
     }
 }
@@ -0,0 +1,802 @@
+//! Diagnostics and completion for the `Cargo.toml` files opened in the editor: unknown and
+//! duplicate keys, path dependencies which don't exist, and features of dependencies.
+//!
+//! Manifests aren't Rust sources, so `ide` doesn't know about them. They are parsed with the `toml`
+//! crate, keeping the spans of keys and values for the diagnostics. A manifest which isn't valid
+//! TOML only gets a diagnostic for the first error.
+
+use std::{fmt, fs, path::Path};
+
+use ide::{FileId, TextRange, TextSize};
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+use project_model::ProjectWorkspace;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use toml::Spanned;
+use vfs::AbsPathBuf;
+
+use crate::{global_state::GlobalStateSnapshot, to_proto, Result};
+
+/// Whether the document is a manifest, which only gets the requests of this module.
+pub(crate) fn is_manifest(path: &str) -> bool {
+    path == "Cargo.toml" || path.ends_with("/Cargo.toml")
+}
+
+pub(crate) fn diagnostics(snap: &GlobalStateSnapshot, file_id: FileId) -> Result<Vec<Diagnostic>> {
+    let text = snap.analysis.file_text(file_id)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let path = match snap.file_id_to_url(file_id).to_file_path() {
+        Ok(it) => it,
+        Err(()) => return Ok(Vec::new()),
+    };
+    let manifest_dir = match path.parent() {
+        Some(it) => it.to_path_buf(),
+        None => return Ok(Vec::new()),
+    };
+    let dep_manifests = DepManifests::new(snap, &path);
+    let res = check(&text, manifest_dir.as_ref(), &|dep| dep_manifests.text(dep))
+        .into_iter()
+        .map(|it| Diagnostic {
+            range: to_proto::range(&line_index, it.range),
+            severity: Some(it.severity),
+            code: None,
+            code_description: None,
+            source: Some("rust-analyzer".to_string()),
+            message: it.message,
+            related_information: None,
+            tags: None,
+            data: None,
+        })
+        .collect();
+    Ok(res)
+}
+
+pub(crate) fn completions(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    offset: TextSize,
+) -> Result<Option<Vec<lsp_types::CompletionItem>>> {
+    let text = snap.analysis.file_text(file_id)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let path = match snap.file_id_to_url(file_id).to_file_path() {
+        Ok(it) => it,
+        Err(()) => return Ok(None),
+    };
+    let manifest_dir = match path.parent() {
+        Some(it) => it.to_path_buf(),
+        None => return Ok(None),
+    };
+    let dep_manifests = DepManifests::new(snap, &path);
+    let completion = match feature_completion(&text, offset, manifest_dir.as_ref(), &|dep| {
+        dep_manifests.text(dep)
+    }) {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let range = to_proto::range(&line_index, completion.range);
+    let quote = completion.quote;
+    let items = completion
+        .features
+        .into_iter()
+        .map(|feature| {
+            let new_text = if quote { format!("\"{}\"", feature) } else { feature.clone() };
+            lsp_types::CompletionItem {
+                label: feature,
+                kind: Some(lsp_types::CompletionItemKind::Property),
+                text_edit: Some(lsp_types::CompletionTextEdit::Edit(lsp_types::TextEdit {
+                    range,
+                    new_text,
+                })),
+                ..Default::default()
+            }
+        })
+        .collect();
+    Ok(Some(items))
+}
+
+/// The manifests of the dependencies of a workspace package, as `cargo metadata` resolved them.
+struct DepManifests {
+    /// The manifests of the dependencies, by package name.
+    manifests: FxHashMap<String, AbsPathBuf>,
+}
+
+impl DepManifests {
+    fn new(snap: &GlobalStateSnapshot, manifest: &Path) -> DepManifests {
+        let mut manifests = FxHashMap::default();
+        for ws in snap.workspaces.iter() {
+            let cargo = match ws {
+                ProjectWorkspace::Cargo { cargo, .. } => cargo,
+                ProjectWorkspace::Json { .. } => continue,
+            };
+            let package =
+                cargo.packages().find(|&it| AsRef::<Path>::as_ref(&cargo[it].manifest) == manifest);
+            if let Some(package) = package {
+                for dep in &cargo[package].dependencies {
+                    let dep = &cargo[dep.pkg];
+                    manifests.insert(dep.name.clone(), dep.manifest.clone());
+                }
+            }
+        }
+        DepManifests { manifests }
+    }
+
+    fn text(&self, dep: &Dependency) -> Option<String> {
+        let manifest = self.manifests.get(dep.package_name())?;
+        fs::read_to_string(manifest).ok()
+    }
+}
+
+#[derive(Debug)]
+struct ManifestDiagnostic {
+    range: TextRange,
+    severity: DiagnosticSeverity,
+    message: String,
+}
+
+/// The tables of `[package]` and the top level, with the keys cargo knows about.
+const KNOWN_KEYS: &[(&str, &[&str])] = &[
+    (
+        "",
+        &[
+            "cargo-features",
+            "package",
+            "project",
+            "lib",
+            "bin",
+            "example",
+            "test",
+            "bench",
+            "dependencies",
+            "dev-dependencies",
+            "dev_dependencies",
+            "build-dependencies",
+            "build_dependencies",
+            "target",
+            "features",
+            "badges",
+            "patch",
+            "replace",
+            "profile",
+            "workspace",
+        ],
+    ),
+    (
+        "package",
+        &[
+            "name",
+            "version",
+            "authors",
+            "edition",
+            "rust-version",
+            "description",
+            "documentation",
+            "readme",
+            "homepage",
+            "repository",
+            "license",
+            "license-file",
+            "keywords",
+            "categories",
+            "workspace",
+            "build",
+            "links",
+            "exclude",
+            "include",
+            "publish",
+            "metadata",
+            "default-run",
+            "autobins",
+            "autoexamples",
+            "autotests",
+            "autobenches",
+            "resolver",
+            "im-a-teapot",
+        ],
+    ),
+];
+
+/// The keys of a detailed dependency, like `serde = { version = "1", features = ["derive"] }`.
+const DEPENDENCY_KEYS: &[&str] = &[
+    "version",
+    "path",
+    "git",
+    "branch",
+    "tag",
+    "rev",
+    "features",
+    "optional",
+    "default-features",
+    "default_features",
+    "package",
+    "registry",
+    "registry-index",
+    "public",
+];
+
+const DEPENDENCY_TABLES: &[&str] = &[
+    "dependencies",
+    "dev-dependencies",
+    "dev_dependencies",
+    "build-dependencies",
+    "build_dependencies",
+];
+
+/// Computes the diagnostics of the manifest in `manifest_dir`. `dep_manifest` returns the text
+/// of the manifest of a dependency which isn't a path dependency, if it's known.
+fn check(
+    text: &str,
+    manifest_dir: &Path,
+    dep_manifest: &dyn Fn(&Dependency) -> Option<String>,
+) -> Vec<ManifestDiagnostic> {
+    let root = match parse(text) {
+        Ok(it) => it,
+        Err(err) => return vec![invalid_manifest(text, &err)],
+    };
+    let mut res = Vec::new();
+    check_duplicates(&root, &mut Vec::new(), &mut res);
+    check_unknown_keys(&root, &mut res);
+
+    for dep in dependencies(&root) {
+        for (key, range) in &dep.unknown_keys {
+            res.push(ManifestDiagnostic {
+                range: *range,
+                severity: DiagnosticSeverity::Warning,
+                message: format!("unused manifest key: `{}.{}.{}`", dep.table, dep.name, key),
+            });
+        }
+        if let Some((path, range)) = &dep.path {
+            let dir = manifest_dir.join(path);
+            if !dir.is_dir() {
+                res.push(ManifestDiagnostic {
+                    range: *range,
+                    severity: DiagnosticSeverity::Error,
+                    message: format!("the path `{}` doesn't exist", path),
+                });
+                continue;
+            }
+            if !dir.join("Cargo.toml").is_file() {
+                res.push(ManifestDiagnostic {
+                    range: *range,
+                    severity: DiagnosticSeverity::Error,
+                    message: format!("there is no `Cargo.toml` in `{}`", path),
+                });
+                continue;
+            }
+        }
+        let features = match dep_features(&dep, manifest_dir, dep_manifest) {
+            Some(it) => it,
+            None => continue,
+        };
+        for (feature, range) in &dep.features {
+            if feature.contains('/') || features.contains(feature) {
+                continue;
+            }
+            res.push(ManifestDiagnostic {
+                range: *range,
+                severity: DiagnosticSeverity::Error,
+                message: format!("`{}` has no feature `{}`", dep.package_name(), feature),
+            });
+        }
+    }
+
+    res.sort_by_key(|it| it.range.start());
+    res
+}
+
+/// Reports a manifest which isn't valid TOML, like one redefining a table, on the line of the
+/// error.
+fn invalid_manifest(text: &str, err: &toml::de::Error) -> ManifestDiagnostic {
+    let range = match err.line_col() {
+        Some((line, _)) => {
+            let start = match line {
+                0 => 0,
+                _ => text.match_indices('\n').nth(line - 1).map_or(text.len(), |(idx, _)| idx + 1),
+            };
+            let len = text[start..].find('\n').unwrap_or_else(|| text.len() - start);
+            TextRange::at(TextSize::from(start as u32), TextSize::from(len as u32))
+        }
+        None => TextRange::empty(0.into()),
+    };
+    // The position is already shown by the range.
+    let message = err.to_string();
+    let message = match message.rfind(" at line ") {
+        Some(idx) => &message[..idx],
+        None => &message,
+    };
+    ManifestDiagnostic {
+        range,
+        severity: DiagnosticSeverity::Error,
+        message: format!("invalid manifest: {}", message),
+    }
+}
+
+/// The `toml` crate rejects redefined tables, but leaves duplicate keys to the types it
+/// deserializes into.
+fn check_duplicates(
+    table: &[(Spanned<String>, Spanned<Value>)],
+    path: &mut Vec<String>,
+    acc: &mut Vec<ManifestDiagnostic>,
+) {
+    let mut seen = FxHashSet::default();
+    for (key, value) in table {
+        path.push(key.get_ref().clone());
+        if !seen.insert(key.get_ref()) {
+            acc.push(ManifestDiagnostic {
+                range: range(key),
+                severity: DiagnosticSeverity::Error,
+                message: format!("duplicate key `{}`", path.join(".")),
+            });
+        }
+        let tables = match value.get_ref() {
+            Value::Table(it) => vec![it],
+            // Each table of an array of tables, like `[[bin]]`, has its own keys.
+            Value::Array(elements) => elements
+                .iter()
+                .filter_map(|it| match it.get_ref() {
+                    Value::Table(it) => Some(it),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        for table in tables {
+            check_duplicates(table, path, acc);
+        }
+        path.pop();
+    }
+}
+
+fn check_unknown_keys(
+    root: &[(Spanned<String>, Spanned<Value>)],
+    acc: &mut Vec<ManifestDiagnostic>,
+) {
+    for (key, value) in root {
+        check_key("", key, acc);
+        if matches!(key.get_ref().as_str(), "package" | "project") {
+            if let Value::Table(entries) = value.get_ref() {
+                for (key, _) in entries {
+                    check_key("package", key, acc);
+                }
+            }
+        }
+    }
+
+    fn check_key(table: &str, key: &Spanned<String>, acc: &mut Vec<ManifestDiagnostic>) {
+        let known = match KNOWN_KEYS.iter().find(|(it, _)| *it == table) {
+            Some((_, known)) => known,
+            None => return,
+        };
+        let name = key.get_ref().as_str();
+        if known.contains(&name) {
+            return;
+        }
+        let path = if table.is_empty() { name.to_string() } else { format!("{}.{}", table, name) };
+        acc.push(ManifestDiagnostic {
+            range: range(key),
+            severity: DiagnosticSeverity::Warning,
+            message: format!("unused manifest key: `{}`", path),
+        });
+    }
+}
+
+/// A dependency, with the keys of its declaration this module is interested in.
+#[derive(Debug, Default)]
+struct Dependency {
+    /// The table declaring the dependency, like `dependencies` or `target.'cfg(unix)'.dependencies`.
+    table: String,
+    name: String,
+    package: Option<String>,
+    path: Option<(String, TextRange)>,
+    features: Vec<(String, TextRange)>,
+    /// The range of the `features` array, between the brackets.
+    features_range: Option<TextRange>,
+    optional: bool,
+    unknown_keys: Vec<(String, TextRange)>,
+}
+
+impl Dependency {
+    fn package_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+
+    fn add_key(&mut self, key: &Spanned<String>, value: &Spanned<Value>) {
+        match (key.get_ref().as_str(), value.get_ref()) {
+            ("package", Value::String(it)) => self.package = Some(it.clone()),
+            ("path", Value::String(it)) => self.path = Some((it.clone(), range(value))),
+            ("optional", Value::Bool(it)) => self.optional = *it,
+            ("features", Value::Array(elements)) => {
+                let (start, end) = value.span();
+                self.features_range = Some(TextRange::new(
+                    TextSize::from(start as u32 + 1),
+                    TextSize::from(end as u32 - 1),
+                ));
+                self.features = elements
+                    .iter()
+                    .filter_map(|it| match it.get_ref() {
+                        Value::String(feature) => Some((feature.clone(), range(it))),
+                        _ => None,
+                    })
+                    .collect();
+            }
+            (key_text, _) if !DEPENDENCY_KEYS.contains(&key_text) => {
+                self.unknown_keys.push((key_text.to_string(), range(key)))
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Returns the dependencies declared in the manifest, in the dependency tables of the top level
+/// and of `[target.'cfg(..)']` tables.
+fn dependencies(root: &[(Spanned<String>, Spanned<Value>)]) -> Vec<Dependency> {
+    let mut res = Vec::new();
+    collect_dependencies(root, "", &mut res);
+    let targets = root.iter().find(|(key, _)| key.get_ref() == "target");
+    if let Some((_, Value::Table(targets))) = targets.map(|(key, value)| (key, value.get_ref())) {
+        for (cfg, table) in targets {
+            if let Value::Table(table) = table.get_ref() {
+                collect_dependencies(table, &format!("target.'{}'.", cfg.get_ref()), &mut res);
+            }
+        }
+    }
+    res
+}
+
+fn collect_dependencies(
+    table: &[(Spanned<String>, Spanned<Value>)],
+    prefix: &str,
+    acc: &mut Vec<Dependency>,
+) {
+    for (key, deps) in table {
+        let deps = match deps.get_ref() {
+            Value::Table(it) if DEPENDENCY_TABLES.contains(&key.get_ref().as_str()) => it,
+            _ => continue,
+        };
+        for (name, decl) in deps {
+            let mut dep = Dependency {
+                table: format!("{}{}", prefix, key.get_ref()),
+                name: name.get_ref().clone(),
+                ..Dependency::default()
+            };
+            if let Value::Table(entries) = decl.get_ref() {
+                for (key, value) in entries {
+                    dep.add_key(key, value);
+                }
+            }
+            acc.push(dep);
+        }
+    }
+}
+
+/// Returns the features of the dependency: the ones of its `[features]` table and its optional
+/// dependencies.
+fn dep_features(
+    dep: &Dependency,
+    manifest_dir: &Path,
+    dep_manifest: &dyn Fn(&Dependency) -> Option<String>,
+) -> Option<FxHashSet<String>> {
+    let text = match &dep.path {
+        Some((path, _)) => fs::read_to_string(manifest_dir.join(path).join("Cargo.toml")).ok()?,
+        None => dep_manifest(dep)?,
+    };
+    let root = parse(&text).ok()?;
+    let mut res = FxHashSet::default();
+    for (key, value) in &root {
+        if let (true, Value::Table(features)) = (key.get_ref() == "features", value.get_ref()) {
+            res.extend(features.iter().map(|(it, _)| it.get_ref().clone()));
+        }
+    }
+    res.extend(dependencies(&root).into_iter().filter(|it| it.optional).map(|it| it.name));
+    Some(res)
+}
+
+#[derive(Debug)]
+struct FeatureCompletion {
+    /// The range replaced by the completion.
+    range: TextRange,
+    /// Whether the completion is inserted outside of a string and so has to be quoted.
+    quote: bool,
+    features: Vec<String>,
+}
+
+/// Completes the features of a dependency in its `features` array.
+fn feature_completion(
+    text: &str,
+    offset: TextSize,
+    manifest_dir: &Path,
+    dep_manifest: &dyn Fn(&Dependency) -> Option<String>,
+) -> Option<FeatureCompletion> {
+    let root = parse(text).ok()?;
+    let dep = dependencies(&root)
+        .into_iter()
+        .find(|it| it.features_range.map_or(false, |range| range.contains_inclusive(offset)))?;
+    let (range, quote) = match dep.features.iter().find(|(_, it)| it.contains_inclusive(offset)) {
+        Some((_, range)) if range.len() >= TextSize::from(2) => {
+            // Inside of the quotes.
+            let start = range.start() + TextSize::from(1);
+            let end = range.end() - TextSize::from(1);
+            if offset < start || offset > end {
+                return None;
+            }
+            (TextRange::new(start, end), false)
+        }
+        Some(_) => return None,
+        None => (TextRange::empty(offset), true),
+    };
+    let mut features = dep_features(&dep, manifest_dir, dep_manifest)?
+        .into_iter()
+        .filter(|it| !dep.features.iter().any(|(feature, _)| feature == it))
+        .collect::<Vec<_>>();
+    features.sort();
+    Some(FeatureCompletion { range, quote, features })
+}
+
+/// Parses the manifest into its top level table.
+fn parse(text: &str) -> Result<Vec<(Spanned<String>, Spanned<Value>)>, toml::de::Error> {
+    match toml::from_str(text)? {
+        Value::Table(it) => Ok(it),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn range<T>(it: &Spanned<T>) -> TextRange {
+    let (start, end) = it.span();
+    TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+}
+
+/// A TOML value, with the spans of its parts. Strings span their quotes and arrays their
+/// brackets.
+#[derive(Debug)]
+enum Value {
+    String(String),
+    Bool(bool),
+    Array(Vec<Spanned<Value>>),
+    /// A table, with its entries in the order they were written in.
+    Table(Vec<(Spanned<String>, Spanned<Value>)>),
+    /// Any other value, like a number or a date.
+    Other,
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a TOML value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Value, E> {
+        Ok(Value::Other)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Value, E> {
+        Ok(Value::Other)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Value, E> {
+        Ok(Value::Other)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut res = Vec::new();
+        while let Some(it) = seq.next_element()? {
+            res.push(it);
+        }
+        Ok(Value::Array(res))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut res = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            res.push(entry);
+        }
+        Ok(Value::Table(res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use test_utils::extract_offset;
+
+    use super::{check, feature_completion, Dependency};
+
+    /// The manifest of every dependency which isn't a path dependency.
+    const SERDE: &str = r#"
+[package]
+name = "serde"
+
+[features]
+default = ["std"]
+std = []
+derive = ["serde_derive"]
+
+[dependencies]
+serde_derive = { version = "1", optional = true }
+itoa.version = "1"
+itoa.optional = true
+
+[dependencies.ryu]
+version = "1"
+optional = true
+"#;
+
+    fn dep_manifest(_: &Dependency) -> Option<String> {
+        Some(SERDE.to_string())
+    }
+
+    fn manifest_dir() -> &'static Path {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn check_diagnostics(ra_fixture: &str, expected: &[&str]) {
+        let actual = check(ra_fixture, manifest_dir(), &dep_manifest)
+            .into_iter()
+            .map(|it| format!("{:?} {}", &ra_fixture[it.range], it.message))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    fn check_completion(ra_fixture: &str, expected: &[&str], quote: bool) {
+        let (offset, text) = extract_offset(ra_fixture);
+        let completion = feature_completion(&text, offset, manifest_dir(), &dep_manifest).unwrap();
+        assert_eq!(completion.features, expected);
+        assert_eq!(completion.quote, quote);
+    }
+
+    #[test]
+    fn valid_manifest() {
+        check_diagnostics(
+            r#"
+cargo-features = ["x"]
+
+[package]
+name = "foo" # comment
+version = "0.1.0"
+authors = [
+    "a",
+    "b",
+]
+
+[dependencies]
+serde = { version = "1", features = ["derive", "itoa", "ryu"] }
+ide = { path = "../ide" }
+other.version = "1"
+other.features = ['std']
+
+[target.'cfg(unix)'.dependencies.serde]
+version = "1"
+features = ["std"]
+
+[[bin]]
+name = "a"
+
+[[bin]]
+name = "b"
+"#,
+            &[],
+        );
+    }
+
+    #[test]
+    fn unknown_keys() {
+        check_diagnostics(
+            r#"
+[package]
+name = "foo"
+verison = "0.1.0"
+
+[dependency]
+
+[dependencies]
+serde = { version = "1", feature = ["derive"] }
+"#,
+            &[
+                r#""verison" unused manifest key: `package.verison`"#,
+                r#""dependency" unused manifest key: `dependency`"#,
+                r#""feature" unused manifest key: `dependencies.serde.feature`"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        check_diagnostics(
+            r#"
+[package]
+name = "foo"
+name = "bar"
+"#,
+            &[r#""name" duplicate key `package.name`"#],
+        );
+        check_diagnostics(
+            r#"
+[dependencies]
+serde = "1"
+
+[dependencies]
+serde = "1"
+"#,
+            &[
+                r#""[dependencies]" invalid manifest: redefinition of table `dependencies` for key `dependencies`"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn missing_features_and_paths() {
+        check_diagnostics(
+            r#"
+[dependencies]
+serde = { version = "1", features = ["derive", "serde_json"] }
+renamed = { package = "serde", features = ["alloc"] }
+missing = { path = "../does-not-exist" }
+not-a-package = { path = ".." }
+
+[dev-dependencies.ide]
+path = "../ide"
+features = ["not_a_feature"]
+"#,
+            &[
+                r#""\"serde_json\"" `serde` has no feature `serde_json`"#,
+                r#""\"alloc\"" `serde` has no feature `alloc`"#,
+                r#""\"../does-not-exist\"" the path `../does-not-exist` doesn't exist"#,
+                r#""\"..\"" there is no `Cargo.toml` in `..`"#,
+                r#""\"not_a_feature\"" `ide` has no feature `not_a_feature`"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn completes_features() {
+        check_completion(
+            r#"
+[dependencies]
+serde = { version = "1", features = ["derive", $0] }
+"#,
+            &["default", "itoa", "ryu", "serde_derive", "std"],
+            true,
+        );
+        check_completion(
+            r#"
+[dependencies.serde]
+features = [
+    "st$0",
+]
+"#,
+            &["default", "derive", "itoa", "ryu", "serde_derive", "std"],
+            false,
+        );
+    }
+
+    #[test]
+    fn no_completion_outside_of_features() {
+        let (offset, text) = extract_offset(
+            r#"
+[dependencies]
+serde = { version = "1$0", features = [] }
+"#,
+        );
+        assert!(feature_completion(&text, offset, manifest_dir(), &dep_manifest).is_none());
+    }
+}
@@ -1,8 +1,9 @@
 //! Various batch processing tasks, intended primarily for debugging.
 
-mod load_cargo;
 mod analysis_stats;
 mod analysis_bench;
+mod api_report;
+mod check_types;
 mod diagnostics;
 mod progress_report;
 mod ssr;
@@ -17,10 +18,12 @@ use vfs::Vfs;
 pub use self::{
     analysis_bench::{BenchCmd, BenchWhat, Position},
     analysis_stats::AnalysisStatsCmd,
+    api_report::ApiReportCmd,
+    check_types::check_types,
     diagnostics::diagnostics,
-    load_cargo::{load_workspace, load_workspace_at, LoadCargoConfig},
     ssr::{apply_ssr_rules, search_for_patterns},
 };
+pub use load_cargo::{load_workspace, load_workspace_at, LoadCargoConfig};
 
 #[derive(Clone, Copy)]
 pub enum Verbosity {
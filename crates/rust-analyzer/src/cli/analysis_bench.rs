@@ -14,12 +14,10 @@ use ide_db::{
     },
     helpers::{insert_use::InsertUseConfig, SnippetCap},
 };
+use load_cargo::{load_workspace_at, LoadCargoConfig};
 use vfs::AbsPathBuf;
 
-use crate::cli::{
-    load_cargo::{load_workspace_at, LoadCargoConfig},
-    print_memory_usage, Verbosity,
-};
+use crate::cli::{print_memory_usage, Verbosity};
 
 pub struct BenchCmd {
     pub path: PathBuf,
@@ -108,6 +106,7 @@ impl BenchCmd {
                         add_call_argument_snippets: true,
                         snippet_cap: SnippetCap::new(true),
                         insert_use: InsertUseConfig { merge: None, prefix_kind: PrefixKind::Plain },
+                        time_budget: None,
                     };
                     let res = do_work(&mut host, file_id, |analysis| {
                         analysis.completions(&options, file_position)
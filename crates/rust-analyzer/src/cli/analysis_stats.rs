@@ -18,6 +18,7 @@ use ide_db::base_db::{
     SourceDatabaseExt,
 };
 use itertools::Itertools;
+use load_cargo::{load_workspace_at, LoadCargoConfig};
 use oorandom::Rand32;
 use rayon::prelude::*;
 use rustc_hash::FxHashSet;
@@ -25,10 +26,7 @@ use stdx::format_to;
 use syntax::AstNode;
 
 use crate::cli::{
-    load_cargo::{load_workspace_at, LoadCargoConfig},
-    print_memory_usage,
-    progress_report::ProgressReport,
-    report_metric, Result, Verbosity,
+    print_memory_usage, progress_report::ProgressReport, report_metric, Result, Verbosity,
 };
 use profile::StopWatch;
 
@@ -0,0 +1,625 @@
+//! Reports the public API of the crates of a workspace, and compares it with the API of a base
+//! revision of the workspace to find semver-breaking changes.
+//!
+//! The report lists every item reachable from outside of its crate: the public items of the
+//! crate root and of the public modules, following re-exports. Items marked `#[doc(hidden)]`
+//! are skipped, and so are items disabled by `cfg`s, as the def map doesn't contain them.
+//! Signatures are compared textually, after normalizing whitespace.
+
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+use anyhow::bail;
+use rustc_hash::FxHashMap;
+
+use hir::{
+    Adt, AssocItem, Attrs, Crate, FieldSource, HasAttrs, HasSource, HasVisibility, HirDisplay,
+    Module, ModuleDef, ScopeDef, Trait, Visibility,
+};
+use ide_db::{base_db::SourceDatabaseExt, RootDatabase};
+use load_cargo::{load_workspace_at, LoadCargoConfig};
+use syntax::{
+    ast::{self, AstNode},
+    NodeOrToken, SyntaxKind,
+    SyntaxKind::*,
+    SyntaxNode, T,
+};
+
+use crate::cli::Result;
+
+pub struct ApiReportCmd {
+    pub path: PathBuf,
+    /// The workspace of the base revision to compare the API with.
+    pub diff: Option<PathBuf>,
+    pub load_output_dirs: bool,
+    pub with_proc_macro: bool,
+}
+
+impl ApiReportCmd {
+    pub fn run(self) -> Result<()> {
+        let api = self.workspace_api(&self.path)?;
+        let base = match &self.diff {
+            Some(base) => self.workspace_api(base)?,
+            None => {
+                for item in &api {
+                    println!("{}", item);
+                }
+                return Ok(());
+            }
+        };
+
+        let changes = diff(&base, &api);
+        for change in &changes {
+            println!("{}", change);
+        }
+        let breaking = changes.iter().filter(|it| it.is_breaking()).count();
+        if breaking > 0 {
+            println!();
+            bail!("{} semver-breaking change(s) found", breaking);
+        }
+        Ok(())
+    }
+
+    fn workspace_api(&self, path: &PathBuf) -> Result<Vec<ApiItem>> {
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: self.load_output_dirs,
+            with_proc_macro: self.with_proc_macro,
+        };
+        let (host, _vfs) = load_workspace_at(path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let mut res = Vec::new();
+        for krate in Crate::all(db) {
+            let file_id = krate.root_file(db);
+            let source_root = db.source_root(db.file_source_root(file_id));
+            if !source_root.is_library {
+                res.extend(crate_api(db, krate));
+            }
+        }
+        res.sort();
+        res.dedup();
+        Ok(res)
+    }
+}
+
+/// An item of the public API.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ApiItem {
+    path: String,
+    /// The keyword the item is declared with, like `fn` or `struct`.
+    kind: &'static str,
+    /// The declaration following the name of the item, like the parameters of a function.
+    signature: String,
+    /// Whether adding the item can break code using the crate, like a new variant of an
+    /// exhaustive enum.
+    breaking_if_added: bool,
+}
+
+impl fmt::Display for ApiItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}{}", self.kind, self.path, self.signature)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ApiChange<'a> {
+    Removed(&'a ApiItem),
+    Changed { old: &'a ApiItem, new: &'a ApiItem },
+    Added(&'a ApiItem),
+}
+
+impl ApiChange<'_> {
+    fn is_breaking(&self) -> bool {
+        match self {
+            ApiChange::Removed(_) | ApiChange::Changed { .. } => true,
+            ApiChange::Added(item) => item.breaking_if_added,
+        }
+    }
+}
+
+impl fmt::Display for ApiChange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiChange::Removed(item) => write!(f, "- {}", item)?,
+            ApiChange::Changed { old, new } => write!(f, "~ {} => {}", old, new)?,
+            ApiChange::Added(item) => write!(f, "+ {}", item)?,
+        }
+        if self.is_breaking() {
+            write!(f, " (breaking)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two APIs, matching items by their kind and path.
+fn diff<'a>(base: &'a [ApiItem], api: &'a [ApiItem]) -> Vec<ApiChange<'a>> {
+    let by_key = |items: &'a [ApiItem]| {
+        items.iter().map(|it| ((it.path.as_str(), it.kind), it)).collect::<BTreeMap<_, _>>()
+    };
+    let base = by_key(base);
+    let api = by_key(api);
+    let mut res = Vec::new();
+    for (key, old) in &base {
+        match api.get(key) {
+            None => res.push(ApiChange::Removed(*old)),
+            Some(new) if old.signature != new.signature => {
+                res.push(ApiChange::Changed { old: *old, new: *new })
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, new) in &api {
+        if !base.contains_key(key) {
+            res.push(ApiChange::Added(*new));
+        }
+    }
+    res
+}
+
+fn crate_api(db: &RootDatabase, krate: Crate) -> Vec<ApiItem> {
+    let name = match krate.display_name(db) {
+        Some(it) => it.to_string(),
+        None => return Vec::new(),
+    };
+    let mut collector = ApiCollector {
+        db,
+        krate,
+        items: Vec::new(),
+        modules: Vec::new(),
+        adt_paths: FxHashMap::default(),
+        trait_paths: FxHashMap::default(),
+    };
+    collector.collect_module(krate.root_module(db), &name);
+    collector.collect_impls(krate.root_module(db));
+    collector.items
+}
+
+struct ApiCollector<'a> {
+    db: &'a RootDatabase,
+    krate: Crate,
+    items: Vec<ApiItem>,
+    /// The modules being collected, to stop at re-exports of an enclosing module.
+    modules: Vec<Module>,
+    /// The shortest paths of the public types and traits, to name them in impls.
+    adt_paths: FxHashMap<Adt, String>,
+    trait_paths: FxHashMap<Trait, String>,
+}
+
+impl ApiCollector<'_> {
+    fn push(&mut self, kind: &'static str, path: String, signature: String) {
+        self.items.push(ApiItem { path, kind, signature, breaking_if_added: false });
+    }
+
+    fn collect_module(&mut self, module: Module, path: &str) {
+        if self.modules.contains(&module) {
+            return;
+        }
+        self.modules.push(module);
+        let mut scope = module.exported_scope(self.db);
+        scope.sort_by_cached_key(|(name, _)| name.to_string());
+        for (name, def) in scope {
+            let path = format!("{}::{}", path, name);
+            match def {
+                ScopeDef::ModuleDef(def) => {
+                    if !def_attrs(self.db, def).map_or(false, |it| it.is_doc_hidden()) {
+                        self.collect_def(def, path);
+                    }
+                }
+                ScopeDef::MacroDef(mac) => {
+                    if !mac.attrs(self.db).is_doc_hidden() {
+                        self.push("macro", path + "!", String::new());
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.modules.pop();
+    }
+
+    fn collect_def(&mut self, def: ModuleDef, path: String) {
+        let db = self.db;
+        match def {
+            ModuleDef::Module(module) => {
+                self.push("mod", path.clone(), String::new());
+                // Modules of other crates are part of their crate's API.
+                if module.krate() == self.krate {
+                    self.collect_module(module, &path);
+                }
+            }
+            ModuleDef::Function(it) => self.push("fn", path, source_signature(db, it)),
+            ModuleDef::Adt(adt) => {
+                let (kind, fields) = match adt {
+                    Adt::Struct(it) => ("struct", it.fields(db)),
+                    Adt::Union(it) => ("union", it.fields(db)),
+                    Adt::Enum(_) => ("enum", Vec::new()),
+                };
+                let adt_signature = match adt {
+                    Adt::Struct(it) => source_signature(db, it),
+                    Adt::Union(it) => source_signature(db, it),
+                    Adt::Enum(it) => source_signature(db, it),
+                };
+                self.push(kind, path.clone(), adt_signature);
+
+                // New fields break struct literals and patterns unless there are private fields
+                // already, and new variants break exhaustive matches.
+                let non_exhaustive = adt.attrs(db).by_key("non_exhaustive").exists();
+                let all_fields_public =
+                    fields.iter().all(|it| it.visibility(db) == Visibility::Public);
+                for field in fields {
+                    if field.visibility(db) != Visibility::Public || field.attrs(db).is_doc_hidden()
+                    {
+                        continue;
+                    }
+                    let signature = match field.source(db).map(|it| it.value) {
+                        Some(FieldSource::Named(it)) => signature(it.syntax()),
+                        Some(FieldSource::Pos(it)) => it
+                            .ty()
+                            .map(|ty| format!(": {}", normalize(tokens(ty.syntax()))))
+                            .unwrap_or_default(),
+                        None => String::new(),
+                    };
+                    self.items.push(ApiItem {
+                        path: format!("{}::{}", path, field.name(db)),
+                        kind: "field",
+                        signature,
+                        breaking_if_added: all_fields_public && !non_exhaustive,
+                    });
+                }
+                if let Adt::Enum(it) = adt {
+                    for variant in it.variants(db) {
+                        if variant.attrs(db).is_doc_hidden() {
+                            continue;
+                        }
+                        self.items.push(ApiItem {
+                            path: format!("{}::{}", path, variant.name(db)),
+                            kind: "variant",
+                            signature: source_signature(db, variant),
+                            breaking_if_added: !non_exhaustive,
+                        });
+                    }
+                }
+                insert_shortest(&mut self.adt_paths, adt, path);
+            }
+            ModuleDef::Variant(it) => self.push("variant", path, source_signature(db, it)),
+            ModuleDef::Const(it) => self.push("const", path, source_signature(db, it)),
+            ModuleDef::Static(it) => self.push("static", path, source_signature(db, it)),
+            ModuleDef::TypeAlias(it) => self.push("type", path, source_signature(db, it)),
+            ModuleDef::Trait(tr) => {
+                self.push("trait", path.clone(), source_signature(db, tr));
+                // Implementations have to provide the new items without a default.
+                for item in tr.items(db) {
+                    let has_default = match item {
+                        AssocItem::Function(it) => {
+                            it.source(db).map_or(false, |it| it.value.body().is_some())
+                        }
+                        AssocItem::Const(it) => {
+                            it.source(db).map_or(false, |it| it.value.body().is_some())
+                        }
+                        AssocItem::TypeAlias(it) => {
+                            it.source(db).map_or(false, |it| it.value.ty().is_some())
+                        }
+                    };
+                    if let Some(mut item) = self.assoc_item(item, &path) {
+                        item.breaking_if_added = !has_default;
+                        self.items.push(item);
+                    }
+                }
+                insert_shortest(&mut self.trait_paths, tr, path);
+            }
+            ModuleDef::BuiltinType(_) => {}
+        }
+    }
+
+    /// Collects the implementations in all modules of the crate, as they are visible wherever
+    /// their self type and trait are, as well as their public items.
+    fn collect_impls(&mut self, module: Module) {
+        let db = self.db;
+        for imp in module.impl_defs(db) {
+            if imp.is_negative(db) {
+                continue;
+            }
+            let self_ty = imp.target_ty(db);
+            let self_path = self_ty.as_adt().and_then(|it| self.adt_paths.get(&it)).cloned();
+            match imp.trait_(db) {
+                Some(tr) => {
+                    let trait_path = self.trait_paths.get(&tr).cloned();
+                    if self_path.is_none() && trait_path.is_none() {
+                        continue;
+                    }
+                    let self_path = self_path.unwrap_or_else(|| self_ty.display(db).to_string());
+                    let trait_path = trait_path.unwrap_or_else(|| tr.name(db).to_string());
+                    self.push("impl", format!("{} for {}", trait_path, self_path), String::new());
+                }
+                None => {
+                    let self_path = match self_path {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    for item in imp.items(db) {
+                        if item.visibility(db) != Visibility::Public {
+                            continue;
+                        }
+                        if let Some(item) = self.assoc_item(item, &self_path) {
+                            self.items.push(item);
+                        }
+                    }
+                }
+            }
+        }
+        for child in module.children(db) {
+            self.collect_impls(child);
+        }
+    }
+
+    fn assoc_item(&self, item: AssocItem, parent: &str) -> Option<ApiItem> {
+        let db = self.db;
+        let (kind, name, attrs, signature) = match item {
+            AssocItem::Function(it) => ("fn", it.name(db), it.attrs(db), source_signature(db, it)),
+            AssocItem::Const(it) => ("const", it.name(db)?, it.attrs(db), source_signature(db, it)),
+            AssocItem::TypeAlias(it) => {
+                ("type", it.name(db), it.attrs(db), source_signature(db, it))
+            }
+        };
+        if attrs.is_doc_hidden() {
+            return None;
+        }
+        let path = format!("{}::{}", parent, name);
+        Some(ApiItem { path, kind, signature, breaking_if_added: false })
+    }
+}
+
+fn insert_shortest<T: std::hash::Hash + Eq>(map: &mut FxHashMap<T, String>, key: T, path: String) {
+    let shortest = map.entry(key).or_insert_with(|| path.clone());
+    if (path.len(), &path) < (shortest.len(), &*shortest) {
+        *shortest = path;
+    }
+}
+
+fn def_attrs(db: &RootDatabase, def: ModuleDef) -> Option<Attrs> {
+    let attrs = match def {
+        ModuleDef::Module(it) => it.attrs(db),
+        ModuleDef::Function(it) => it.attrs(db),
+        ModuleDef::Adt(it) => it.attrs(db),
+        ModuleDef::Variant(it) => it.attrs(db),
+        ModuleDef::Const(it) => it.attrs(db),
+        ModuleDef::Static(it) => it.attrs(db),
+        ModuleDef::Trait(it) => it.attrs(db),
+        ModuleDef::TypeAlias(it) => it.attrs(db),
+        ModuleDef::BuiltinType(_) => return None,
+    };
+    Some(attrs)
+}
+
+fn source_signature<D>(db: &RootDatabase, def: D) -> String
+where
+    D: HasSource,
+    D::Ast: AstNode,
+{
+    def.source(db).map(|it| signature(it.value.syntax())).unwrap_or_default()
+}
+
+/// Returns the source of the declaration after the name of the item, without its body. The
+/// fields of structs and unions are replaced by `{ .. }` or `(..)`, as public fields are reported
+/// on their own.
+fn signature(node: &SyntaxNode) -> String {
+    let name_end = match node.children().find_map(ast::Name::cast) {
+        Some(it) => it.syntax().text_range().end(),
+        None => return String::new(),
+    };
+    let has_fields_api = matches!(node.kind(), STRUCT | UNION);
+    let mut res = Vec::new();
+    for child in node.children_with_tokens() {
+        if child.text_range().start() < name_end {
+            continue;
+        }
+        match child {
+            NodeOrToken::Token(token) => {
+                // The values of constants and statics aren't part of their signature.
+                if token.kind() == T![=] && matches!(node.kind(), CONST | STATIC) {
+                    break;
+                }
+                res.push((token.kind(), token.text().to_string()));
+            }
+            NodeOrToken::Node(child) => match child.kind() {
+                BLOCK_EXPR | VARIANT_LIST | ASSOC_ITEM_LIST => {}
+                RECORD_FIELD_LIST if has_fields_api => res.extend(placeholder(&[
+                    WHITESPACE,
+                    T!['{'],
+                    WHITESPACE,
+                    T![..],
+                    WHITESPACE,
+                    T!['}'],
+                ])),
+                TUPLE_FIELD_LIST if has_fields_api => {
+                    res.extend(placeholder(&[T!['('], T![..], T![')']]))
+                }
+                _ => res.extend(tokens(&child)),
+            },
+        }
+    }
+    normalize(res)
+}
+
+fn placeholder(kinds: &[SyntaxKind]) -> Vec<(SyntaxKind, String)> {
+    kinds
+        .iter()
+        .map(|&kind| {
+            let text = match kind {
+                T!['{'] => "{",
+                T!['}'] => "}",
+                T!['('] => "(",
+                T![')'] => ")",
+                T![..] => "..",
+                _ => " ",
+            };
+            (kind, text.to_string())
+        })
+        .collect()
+}
+
+fn tokens(node: &SyntaxNode) -> Vec<(SyntaxKind, String)> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.ancestors().any(|it| it.kind() == ATTR))
+        .map(|it| (it.kind(), it.text().to_string()))
+        .collect()
+}
+
+/// Renders the tokens with single spaces where there was whitespace, except after opening and
+/// before closing delimiters, and without comments, semicolons and trailing commas, so that
+/// formatting doesn't matter.
+fn normalize(tokens: Vec<(SyntaxKind, String)>) -> String {
+    let is_opening = |kind| matches!(kind, T!['('] | T!['['] | T![<]);
+    let is_closing = |kind| matches!(kind, T![')'] | T![']'] | T![>]);
+    let tokens = tokens
+        .into_iter()
+        .map(|(kind, text)| if kind == COMMENT { (WHITESPACE, text) } else { (kind, text) })
+        .filter(|(kind, _)| *kind != T![;])
+        .collect::<Vec<_>>();
+    let mut res = String::new();
+    let mut space = false;
+    let mut prev = None;
+    for (idx, (kind, text)) in tokens.iter().enumerate() {
+        let kind = *kind;
+        if kind == WHITESPACE {
+            space = true;
+            continue;
+        }
+        let next = tokens[idx + 1..].iter().map(|(it, _)| *it).find(|it| *it != WHITESPACE);
+        if kind == T![,] && next.map_or(true, is_closing) {
+            continue;
+        }
+        if space && !prev.map_or(false, is_opening) && !is_closing(kind) {
+            res.push(' ');
+        }
+        space = false;
+        prev = Some(kind);
+        res.push_str(text);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::Crate;
+    use ide::AnalysisHost;
+    use ide_db::base_db::fixture::ChangeFixture;
+
+    use super::{crate_api, diff, ApiItem};
+
+    fn api(ra_fixture: &str) -> Vec<ApiItem> {
+        let fixture = ChangeFixture::parse(ra_fixture);
+        let mut host = AnalysisHost::default();
+        host.apply_change(fixture.change);
+        let db = host.raw_database();
+        let krate = Crate::all(db).into_iter().next().unwrap();
+        let mut res = crate_api(db, krate);
+        res.sort();
+        res
+    }
+
+    fn report(ra_fixture: &str) -> Vec<String> {
+        api(ra_fixture).iter().map(|it| it.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_public_items() {
+        let report = report(
+            r#"
+//- /lib.rs crate:foo cfg:feature=std
+pub mod m {
+    pub struct S<T> where T: Clone { pub a: T, b: u32 }
+    pub struct P(pub u32, u8);
+    impl<T: Clone> S<T> {
+        pub fn new(a: T) -> Self { loop {} }
+        fn private(&self) {}
+    }
+    pub(crate) fn hidden() {}
+}
+mod private {
+    pub fn reexported(
+        x: u32,
+        y: &str,
+    ) -> u32 { x }
+    pub fn not_reexported() {}
+}
+pub use private::reexported;
+pub enum E { A(u32), B { x: bool } }
+pub trait Tr { type Item; fn req(&self); fn provided(&self) {} }
+impl Tr for m::P { type Item = (); fn req(&self) {} }
+pub const C: u32 = 92;
+#[doc(hidden)]
+pub fn doc_hidden() {}
+#[cfg(feature = "std")]
+pub fn with_std() {}
+#[cfg(not(feature = "std"))]
+pub fn without_std() {}
+#[macro_export]
+macro_rules! mac { () => {} }
+"#,
+        );
+        assert_eq!(
+            report,
+            vec![
+                "const foo::C: u32",
+                "enum foo::E",
+                "variant foo::E::A(u32)",
+                "variant foo::E::B { x: bool }",
+                "trait foo::Tr",
+                "impl foo::Tr for foo::m::P",
+                "type foo::Tr::Item",
+                "fn foo::Tr::provided(&self)",
+                "fn foo::Tr::req(&self)",
+                "mod foo::m",
+                "struct foo::m::P(..)",
+                "field foo::m::P::0: u32",
+                "struct foo::m::S<T> where T: Clone { .. }",
+                "field foo::m::S::a: T",
+                "fn foo::m::S::new(a: T) -> Self",
+                "macro foo::mac!",
+                "fn foo::reexported(x: u32, y: &str) -> u32",
+                "fn foo::with_std()",
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_breaking_changes() {
+        let base = api(r#"
+//- /lib.rs crate:foo
+pub fn removed() {}
+pub fn changed(x: u32) {}
+pub struct S { pub a: u32 }
+pub struct Private { pub a: u32, b: u32 }
+pub enum E { A }
+#[non_exhaustive]
+pub enum NonExhaustive { A }
+pub trait Tr {}
+"#);
+        let api = api(r#"
+//- /lib.rs crate:foo
+pub fn changed(x: u64) {}
+pub fn added() {}
+pub struct S { pub a: u32, pub b: u32 }
+pub struct Private { pub a: u32, b: u32, pub c: u32 }
+pub enum E { A, B }
+#[non_exhaustive]
+pub enum NonExhaustive { A, B }
+pub trait Tr { fn required(&self); fn provided(&self) {} }
+"#);
+        let changes = diff(&base, &api).iter().map(|it| it.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                "~ fn foo::changed(x: u32) => fn foo::changed(x: u64) (breaking)",
+                "- fn foo::removed() (breaking)",
+                "+ variant foo::E::B (breaking)",
+                "+ variant foo::NonExhaustive::B",
+                "+ field foo::Private::c: u32",
+                "+ field foo::S::b: u32 (breaking)",
+                "+ fn foo::Tr::provided(&self)",
+                "+ fn foo::Tr::required(&self) (breaking)",
+                "+ fn foo::added()",
+            ]
+        );
+    }
+}
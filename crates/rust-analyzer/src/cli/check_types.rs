@@ -0,0 +1,105 @@
+//! Checks the types inferred for the expressions annotated with `//^^^ type` comments in a
+//! fixture, the same way the inference tests do. Exits with a non-zero status code if any
+//! of the annotations doesn't match.
+
+use anyhow::{anyhow, bail};
+use hir::{HirDisplay, Semantics};
+use ide::{AnalysisHost, LineIndex};
+use ide_db::{
+    base_db::{
+        fixture::{extract_annotations, ChangeFixture},
+        SourceDatabaseExt,
+    },
+    RootDatabase,
+};
+use syntax::{algo, ast, AstNode, SyntaxNode, TextRange};
+
+use crate::cli::{read_stdin, Result};
+
+pub fn check_types() -> Result<()> {
+    let text = read_stdin()?;
+    let mismatches = type_mismatches(&text)?;
+    for mismatch in &mismatches {
+        println!("{}", mismatch);
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        println!();
+        Err(anyhow!("{} type annotation(s) didn't match", mismatches.len()))
+    }
+}
+
+/// Returns a message for every annotation in `ra_fixture` which doesn't match the inferred type.
+fn type_mismatches(ra_fixture: &str) -> Result<Vec<String>> {
+    let fixture = ChangeFixture::parse(ra_fixture);
+    let mut host = AnalysisHost::default();
+    host.apply_change(fixture.change);
+    let db = host.raw_database();
+    let sema = Semantics::new(db);
+
+    let mut checked_one = false;
+    let mut res = Vec::new();
+    for file_id in fixture.files {
+        let source_root = db.source_root(db.file_source_root(file_id));
+        let path = source_root.path_for_file(&file_id).unwrap();
+        let text = db.file_text(file_id);
+        let line_index = LineIndex::new(&text);
+        let file = sema.parse(file_id);
+        for (range, expected) in extract_annotations(&text) {
+            checked_one = true;
+            let actual = type_at_range(&sema, file.syntax(), range);
+            if actual.as_deref() == Some(expected.as_str()) {
+                continue;
+            }
+            let line_col = line_index.line_col(range.start());
+            let location = format!("{}:{}:{}", path, line_col.line + 1, line_col.col + 1);
+            res.push(match actual {
+                Some(actual) => {
+                    format!("{}: expected `{}`, found `{}`", location, expected, actual)
+                }
+                None => format!("{}: no expression with a type found", location),
+            });
+        }
+    }
+    if !checked_one {
+        bail!("no `//^` annotations found");
+    }
+    Ok(res)
+}
+
+fn type_at_range(
+    sema: &Semantics<RootDatabase>,
+    file: &SyntaxNode,
+    range: TextRange,
+) -> Option<String> {
+    let expr = algo::find_node_at_range::<ast::Expr>(file, range)?;
+    let ty = sema.type_of_expr(&expr)?;
+    Some(ty.display_test(sema.db).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::type_mismatches;
+
+    #[test]
+    fn reports_mismatched_annotations() {
+        let mismatches = type_mismatches(
+            r#"
+//- /main.rs
+fn id<T>(t: T) -> T { t }
+fn main() {
+    let x = id(1u8);
+          //^^^^^^^ u8
+    let y = id("");
+    y;
+  //^ &str
+    x;
+  //^ u16
+}
+"#,
+        )
+        .unwrap();
+        assert_eq!(mismatches, vec!["/main.rs:8:5: expected `u16`, found `u8`".to_string()]);
+    }
+}
@@ -9,11 +9,9 @@ use rustc_hash::FxHashSet;
 use hir::{db::HirDatabase, Crate, Module};
 use ide::{DiagnosticsConfig, Severity};
 use ide_db::base_db::SourceDatabaseExt;
+use load_cargo::{load_workspace_at, LoadCargoConfig};
 
-use crate::cli::{
-    load_cargo::{load_workspace_at, LoadCargoConfig},
-    Result,
-};
+use crate::cli::Result;
 
 fn all_modules(db: &dyn HirDatabase) -> Vec<Module> {
     let mut worklist: Vec<_> =
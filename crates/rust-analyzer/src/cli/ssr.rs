@@ -1,10 +1,8 @@
 //! Applies structured search replace rules from the command line.
 
-use crate::cli::{
-    load_cargo::{load_workspace_at, LoadCargoConfig},
-    Result,
-};
+use crate::cli::Result;
 use ide_ssr::{MatchFinder, SsrPattern, SsrRule};
+use load_cargo::{load_workspace_at, LoadCargoConfig};
 
 pub fn apply_ssr_rules(rules: Vec<SsrRule>) -> Result<()> {
     use ide_db::base_db::SourceDatabaseExt;
@@ -7,29 +7,43 @@
 //! configure the server itself, feature flags are passed into analysis, and
 //! tweak things like automatic insertion of `()` in completions.
 
-use std::{ffi::OsString, iter, path::PathBuf};
+use std::{ffi::OsString, iter, path::PathBuf, time::Duration};
 
 use flycheck::FlycheckConfig;
 use hir::PrefixKind;
-use ide::{AssistConfig, CompletionConfig, DiagnosticsConfig, HoverConfig, InlayHintsConfig};
+use ide::{
+    AssistConfig, CompletionConfig, DiagnosticsConfig, ExcludedCode, HoverConfig, InlayHintsConfig,
+    LargeFileConfig,
+};
 use ide_db::helpers::{
     insert_use::{InsertUseConfig, MergeBehavior},
     SnippetCap,
 };
 use itertools::Itertools;
 use lsp_types::{ClientCapabilities, MarkupKind};
-use project_model::{CargoConfig, ProjectJson, ProjectJsonData, ProjectManifest, RustcSource};
+use project_model::{
+    CargoConfig, CfgFlag, ProjectJson, ProjectJsonData, ProjectManifest, RustcSource,
+};
 use rustc_hash::FxHashSet;
 use serde::{de::DeserializeOwned, Deserialize};
-use vfs::AbsPathBuf;
+use vfs::{AbsPathBuf, VfsPath};
 
 use crate::{
     caps::completion_item_edit_resolve, diagnostics::DiagnosticsMapConfig,
-    line_index::OffsetEncoding, lsp_ext::supports_utf8,
+    line_index::OffsetEncoding, lsp_ext::supports_utf8, project_config::ProjectConfig,
 };
 
 config_data! {
     struct ConfigData {
+        /// These directories are not indexed for workspace symbol search and
+        /// are not checked for native diagnostics, while their items can still
+        /// be referred to. This is useful for the output of code generators.
+        analysis_excludeDirs: Vec<PathBuf>   = "[]",
+        /// Modules like `my_crate::bindings`, or whole crates like `my_crate`,
+        /// whose files are excluded from analysis as with
+        /// `#rust-analyzer.analysis.excludeDirs#`.
+        analysis_excludeModules: Vec<String> = "[]",
+
         /// The strategy to use when inserting new imports or merging imports.
         assist_importMergeBehavior |
         assist_importMergeBehaviour: MergeBehaviorDef  = "\"full\"",
@@ -44,6 +58,10 @@ config_data! {
         cargo_autoreload: bool           = "true",
         /// Activate all available features (`--all-features`).
         cargo_allFeatures: bool          = "false",
+        /// Extra cfg atoms (`foo`) and key-value pairs (`key="value"`) to enable
+        /// for the crates of the workspace, like the `--cfg` flags in `RUSTFLAGS`.
+        /// `doctest` and `bench` can be enabled this way too.
+        cargo_cfgs: Vec<String>          = "[]",
         /// List of features to activate.
         cargo_features: Vec<String>      = "[]",
         /// Run `cargo check` on startup to get the correct value for package
@@ -89,6 +107,10 @@ config_data! {
         /// Toggles the additional completions that automatically add imports when completed.
         /// Note that your client must specify the `additionalTextEdits` LSP client capability to truly have this feature enabled.
         completion_autoimport_enable: bool       = "true",
+        /// Time in milliseconds after which the completion stops looking for
+        /// more items, like the ones adding imports, and returns the ones found
+        /// so far. `0` disables the limit.
+        completion_timeBudget: usize             = "100",
 
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = "true",
@@ -97,6 +119,22 @@ config_data! {
         diagnostics_enableExperimental: bool    = "true",
         /// List of rust-analyzer diagnostics to disable.
         diagnostics_disabled: FxHashSet<String> = "[]",
+        /// Whether to report private items which are never used. This searches
+        /// for the usages of every item in a file, which is slow in large crates.
+        diagnostics_deadCode: bool              = "false",
+        /// List of custom lint rules, such as
+        /// `call crate::db::blocking_query in async => "use query instead"`.
+        /// Calls matching a rule are reported as hints. A rule is `call <path>`,
+        /// optionally followed by `in <context>`, where the context is one of
+        /// `async`, `unsafe`, `loop` or `fn <path>`, and by `=> <message>`.
+        diagnostics_customLints: Vec<String>    = "[]",
+        /// List of blocking standard library functions (e.g.
+        /// `std::sync::Mutex::lock`) which should not be reported when called
+        /// in async code.
+        diagnostics_allowedBlockingCalls: Vec<String> = "[]",
+        /// List of types (e.g. `bindings::Opaque`) which are not reported
+        /// when used in `extern` blocks or `extern "C"` functions.
+        diagnostics_ffiSafeTypes: Vec<String>   = "[]",
         /// List of warnings that should be displayed with info severity.\n\nThe
         /// warnings will be indicated by a blue squiggly underline in code and
         /// a blue icon in the `Problems Panel`.
@@ -130,14 +168,30 @@ config_data! {
 
         /// Whether to show inlay type hints for method chains.
         inlayHints_chainingHints: bool      = "true",
+        /// Whether to show inlay hints for the discriminants of enum
+        /// variants which are not given as literals.
+        inlayHints_discriminantHints: bool  = "false",
         /// Maximum length for inlay hints. Default is unlimited.
         inlayHints_maxLength: Option<usize> = "null",
+        /// Whether to show inlay hints for expressions which may panic, like
+        /// `unwrap` calls, indexing and integer arithmetic.
+        inlayHints_panicHints: bool         = "false",
         /// Whether to show function parameter name inlay hints at the call
         /// site.
         inlayHints_parameterHints: bool     = "true",
         /// Whether to show inlay type hints for variables.
         inlayHints_typeHints: bool          = "true",
 
+        /// Size of a file in bytes above which rust-analyzer skips
+        /// expensive analyses, like native diagnostics and inlay hints, for
+        /// it. Syntax highlighting and navigation keep working. Set to `0`
+        /// to disable the limit.
+        largeFile_maxSize: usize          = "1000000",
+        /// Number of lines of a single function above which its file is
+        /// treated as large, like with `#rust-analyzer.largeFile.maxSize#`.
+        /// Set to `0` to disable the limit.
+        largeFile_maxFunctionLines: usize = "10000",
+
         /// Whether to show `Debug` lens. Only applies when
         /// `#rust-analyzer.lens.enable#` is set.
         lens_debug: bool            = "true",
@@ -155,6 +209,9 @@ config_data! {
         /// Whether to show `References` lens. Only applies when
         /// `#rust-analyzer.lens.enable#` is set.
         lens_references: bool = "false",
+        /// Whether to show `Unhandled Variants` lens above `_` arms of matches on
+        /// enums. Only applies when `#rust-analyzer.lens.enable#` is set.
+        lens_wildcardArms: bool = "false",
 
         /// Disable project auto-discovery in favor of explicitly specified set
         /// of projects.\n\nElements must be paths pointing to `Cargo.toml`,
@@ -187,8 +244,13 @@ config_data! {
         /// Additional arguments to `rustfmt`.
         rustfmt_extraArgs: Vec<String>               = "[]",
         /// Advanced option, fully override the command rust-analyzer uses for
-        /// formatting.
+        /// formatting. The command reads the code to format from stdin and writes
+        /// the formatted code to stdout, and runs in the directory of the file.
         rustfmt_overrideCommand: Option<Vec<String>> = "null",
+        /// Format only the selected lines with rustfmt's unstable `--file-lines`
+        /// option, which requires a nightly rustfmt. Otherwise, range formatting
+        /// formats the whole items containing the selection.
+        rustfmt_enableRangeFormatting: bool          = "false",
     }
 }
 
@@ -204,6 +266,7 @@ pub struct Config {
     data: ConfigData,
     pub discovered_projects: Option<Vec<ProjectManifest>>,
     pub root_path: AbsPathBuf,
+    project_config: ProjectConfig,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -231,11 +294,12 @@ pub struct LensConfig {
     pub implementations: bool,
     pub method_refs: bool,
     pub refs: bool, // for Struct, Enum, Union and Trait
+    pub wildcard_arms: bool,
 }
 
 impl LensConfig {
     pub fn any(&self) -> bool {
-        self.implementations || self.runnable() || self.references()
+        self.implementations || self.runnable() || self.references() || self.wildcard_arms
     }
 
     pub fn none(&self) -> bool {
@@ -270,7 +334,7 @@ pub struct NotificationsConfig {
 
 #[derive(Debug, Clone)]
 pub enum RustfmtConfig {
-    Rustfmt { extra_args: Vec<String> },
+    Rustfmt { extra_args: Vec<String>, enable_range_formatting: bool },
     CustomCommand { command: String, args: Vec<String> },
 }
 
@@ -285,7 +349,13 @@ pub struct RunnablesConfig {
 
 impl Config {
     pub fn new(root_path: AbsPathBuf, caps: ClientCapabilities) -> Self {
-        Config { caps, data: ConfigData::default(), discovered_projects: None, root_path }
+        Config {
+            caps,
+            data: ConfigData::default(),
+            discovered_projects: None,
+            root_path,
+            project_config: ProjectConfig::default(),
+        }
     }
     pub fn update(&mut self, json: serde_json::Value) {
         log::info!("updating config from JSON: {:#}", json);
@@ -294,6 +364,10 @@ impl Config {
         }
         self.data = ConfigData::from_json(json);
     }
+    /// Replaces the settings read from `rust-analyzer.toml`.
+    pub(crate) fn update_project_config(&mut self, project_config: ProjectConfig) {
+        self.project_config = project_config;
+    }
 
     pub fn json_schema() -> serde_json::Value {
         ConfigData::json_schema()
@@ -438,6 +512,12 @@ impl Config {
     pub fn status_notification(&self) -> bool {
         self.experimental("statusNotification")
     }
+    pub fn enable_cargo_features_command(&self) -> bool {
+        self.experimental("enableCargoFeatures")
+    }
+    pub fn dependency_sources_notification(&self) -> bool {
+        self.experimental("dependencySources")
+    }
 
     pub fn publish_diagnostics(&self) -> bool {
         self.data.diagnostics_enable
@@ -446,6 +526,12 @@ impl Config {
         DiagnosticsConfig {
             disable_experimental: !self.data.diagnostics_enableExperimental,
             disabled: self.data.diagnostics_disabled.clone(),
+            ssr_rules: self.project_config.ssr_rules.clone(),
+            custom_lints: self.data.diagnostics_customLints.clone(),
+            allowed_blocking_calls: self.data.diagnostics_allowedBlockingCalls.clone(),
+            ffi_safe_types: self.data.diagnostics_ffiSafeTypes.clone(),
+            snippet_cap: SnippetCap::new(self.experimental("snippetTextEdit")),
+            dead_code: self.data.diagnostics_deadCode,
         }
     }
     pub fn diagnostics_map(&self) -> DiagnosticsMapConfig {
@@ -454,6 +540,17 @@ impl Config {
             warnings_as_hint: self.data.diagnostics_warningsAsHint.clone(),
         }
     }
+    pub fn excluded_code(&self) -> ExcludedCode {
+        ExcludedCode {
+            dirs: self
+                .data
+                .analysis_excludeDirs
+                .iter()
+                .map(|it| VfsPath::from(self.root_path.join(it)))
+                .collect(),
+            modules: self.data.analysis_excludeModules.clone(),
+        }
+    }
     pub fn lru_capacity(&self) -> Option<usize> {
         self.data.lruCapacity
     }
@@ -492,6 +589,19 @@ impl Config {
             }
         });
 
+        let cfgs = self
+            .data
+            .cargo_cfgs
+            .iter()
+            .filter_map(|it| match it.parse::<CfgFlag>() {
+                Ok(it) => Some(it),
+                Err(err) => {
+                    log::error!("{}", err);
+                    None
+                }
+            })
+            .collect();
+
         CargoConfig {
             no_default_features: self.data.cargo_noDefaultFeatures,
             all_features: self.data.cargo_allFeatures,
@@ -499,6 +609,7 @@ impl Config {
             target: self.data.cargo_target.clone(),
             rustc_source,
             no_sysroot: self.data.cargo_noSysroot,
+            cfgs,
         }
     }
     pub fn rustfmt(&self) -> RustfmtConfig {
@@ -508,9 +619,10 @@ impl Config {
                 let command = args.remove(0);
                 RustfmtConfig::CustomCommand { command, args }
             }
-            Some(_) | None => {
-                RustfmtConfig::Rustfmt { extra_args: self.data.rustfmt_extraArgs.clone() }
-            }
+            Some(_) | None => RustfmtConfig::Rustfmt {
+                extra_args: self.data.rustfmt_extraArgs.clone(),
+                enable_range_formatting: self.data.rustfmt_enableRangeFormatting,
+            },
         }
     }
     pub fn flycheck(&self) -> Option<FlycheckConfig> {
@@ -560,9 +672,17 @@ impl Config {
             type_hints: self.data.inlayHints_typeHints,
             parameter_hints: self.data.inlayHints_parameterHints,
             chaining_hints: self.data.inlayHints_chainingHints,
+            panic_hints: self.data.inlayHints_panicHints,
+            discriminant_hints: self.data.inlayHints_discriminantHints,
             max_length: self.data.inlayHints_maxLength,
         }
     }
+    pub fn large_file(&self) -> LargeFileConfig {
+        LargeFileConfig {
+            max_file_size: self.data.largeFile_maxSize,
+            max_fn_lines: self.data.largeFile_maxFunctionLines,
+        }
+    }
     fn insert_use_config(&self) -> InsertUseConfig {
         InsertUseConfig {
             merge: match self.data.assist_importMergeBehavior {
@@ -585,6 +705,10 @@ impl Config {
             add_call_parenthesis: self.data.completion_addCallParenthesis,
             add_call_argument_snippets: self.data.completion_addCallArgumentSnippets,
             insert_use: self.insert_use_config(),
+            time_budget: match self.data.completion_timeBudget {
+                0 => None,
+                millis => Some(Duration::from_millis(millis as u64)),
+            },
             snippet_cap: SnippetCap::new(try_or!(
                 self.caps
                     .text_document
@@ -615,6 +739,7 @@ impl Config {
             implementations: self.data.lens_enable && self.data.lens_implementations,
             method_refs: self.data.lens_enable && self.data.lens_methodReferences,
             refs: self.data.lens_enable && self.data.lens_references,
+            wildcard_arms: self.data.lens_enable && self.data.lens_wildcardArms,
         }
     }
     pub fn hover(&self) -> HoverConfig {
@@ -793,6 +918,10 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
             "items": { "type": "string" },
             "uniqueItems": true,
         },
+        "usize" => set! {
+            "type": "integer",
+            "minimum": 0,
+        },
         "Option<usize>" => set! {
             "type": ["null", "integer"],
             "minimum": 0,
@@ -2,7 +2,7 @@
 //! `cargo check` json format to the LSP diagnostic format.
 use std::{collections::HashMap, path::Path};
 
-use flycheck::{DiagnosticLevel, DiagnosticSpan};
+use flycheck::{Applicability, DiagnosticLevel, DiagnosticSpan};
 use stdx::format_to;
 
 use crate::{lsp_ext, to_proto::url_from_abs_path};
@@ -39,8 +39,29 @@ fn is_from_macro(file_name: &str) -> bool {
     file_name.starts_with('<') && file_name.ends_with('>')
 }
 
+/// Maps the location of a span in the definition of a macro to the arguments of the macro call
+/// at the first location which were expanded within it.
+pub(crate) type MacroArgsMap<'a> =
+    dyn Fn(&lsp_types::Location, &lsp_types::Location) -> Option<lsp_types::Location> + 'a;
+
 /// Converts a Rust span to a LSP location, resolving macro expansion site if neccesary
-fn location(workspace_root: &Path, span: &DiagnosticSpan) -> lsp_types::Location {
+///
+/// Spans in the definition of a macro called from the source are mapped to the arguments of the
+/// call where possible, instead of the whole call.
+fn location(
+    workspace_root: &Path,
+    span: &DiagnosticSpan,
+    macro_args: &MacroArgsMap,
+) -> lsp_types::Location {
+    if let Some(expansion) = &span.expansion {
+        if expansion.span.expansion.is_none() && !is_from_macro(&span.file_name) {
+            let call_site = location_naive(workspace_root, &expansion.span);
+            let def_site = location_naive(workspace_root, span);
+            if let Some(location) = macro_args(&call_site, &def_site) {
+                return location;
+            }
+        }
+    }
     let mut span = span.clone();
     while let Some(expansion) = span.expansion {
         span = expansion.span;
@@ -68,9 +89,10 @@ fn location_naive(workspace_root: &Path, span: &DiagnosticSpan) -> lsp_types::Lo
 fn diagnostic_related_information(
     workspace_root: &Path,
     span: &DiagnosticSpan,
+    macro_args: &MacroArgsMap,
 ) -> Option<lsp_types::DiagnosticRelatedInformation> {
     let message = span.label.clone()?;
-    let location = location(workspace_root, span);
+    let location = location(workspace_root, span, macro_args);
     Some(lsp_types::DiagnosticRelatedInformation { location, message })
 }
 
@@ -87,6 +109,7 @@ enum MappedRustChildDiagnostic {
 fn map_rust_child_diagnostic(
     workspace_root: &Path,
     rd: &flycheck::Diagnostic,
+    macro_args: &MacroArgsMap,
 ) -> MappedRustChildDiagnostic {
     let spans: Vec<&DiagnosticSpan> = rd.spans.iter().filter(|s| s.is_primary).collect();
     if spans.is_empty() {
@@ -96,9 +119,12 @@ fn map_rust_child_diagnostic(
     }
 
     let mut edit_map: HashMap<lsp_types::Url, Vec<lsp_types::TextEdit>> = HashMap::new();
+    let mut machine_applicable = true;
     for &span in &spans {
         if let Some(suggested_replacement) = &span.suggested_replacement {
-            let location = location(workspace_root, span);
+            machine_applicable &=
+                matches!(span.suggestion_applicability, Some(Applicability::MachineApplicable));
+            let location = location(workspace_root, span, macro_args);
             let edit = lsp_types::TextEdit::new(location.range, suggested_replacement.clone());
             edit_map.entry(location.uri).or_default().push(edit);
         }
@@ -107,7 +133,7 @@ fn map_rust_child_diagnostic(
     if edit_map.is_empty() {
         MappedRustChildDiagnostic::SubDiagnostic(SubDiagnostic {
             related: lsp_types::DiagnosticRelatedInformation {
-                location: location(workspace_root, spans[0]),
+                location: location(workspace_root, spans[0], macro_args),
                 message: rd.message.clone(),
             },
             suggested_fix: None,
@@ -115,19 +141,20 @@ fn map_rust_child_diagnostic(
     } else {
         MappedRustChildDiagnostic::SubDiagnostic(SubDiagnostic {
             related: lsp_types::DiagnosticRelatedInformation {
-                location: location(workspace_root, spans[0]),
+                location: location(workspace_root, spans[0], macro_args),
                 message: rd.message.clone(),
             },
             suggested_fix: Some(lsp_ext::CodeAction {
                 title: rd.message.clone(),
                 group: None,
                 kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+                command: None,
                 edit: Some(lsp_ext::SnippetWorkspaceEdit {
                     // FIXME: there's no good reason to use edit_map here....
                     changes: Some(edit_map),
                     document_changes: None,
                 }),
-                is_preferred: Some(true),
+                is_preferred: Some(machine_applicable),
                 data: None,
             }),
         })
@@ -155,6 +182,7 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
     config: &DiagnosticsMapConfig,
     rd: &flycheck::Diagnostic,
     workspace_root: &Path,
+    macro_args: &MacroArgsMap,
 ) -> Vec<MappedRustDiagnostic> {
     let primary_spans: Vec<&DiagnosticSpan> = rd.spans.iter().filter(|s| s.is_primary).collect();
     if primary_spans.is_empty() {
@@ -179,7 +207,7 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
     let mut tags = Vec::new();
 
     for secondary_span in rd.spans.iter().filter(|s| !s.is_primary) {
-        let related = diagnostic_related_information(workspace_root, secondary_span);
+        let related = diagnostic_related_information(workspace_root, secondary_span, macro_args);
         if let Some(related) = related {
             subdiagnostics.push(SubDiagnostic { related, suggested_fix: None });
         }
@@ -187,7 +215,7 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
 
     let mut message = rd.message.clone();
     for child in &rd.children {
-        let child = map_rust_child_diagnostic(workspace_root, &child);
+        let child = map_rust_child_diagnostic(workspace_root, &child, macro_args);
         match child {
             MappedRustChildDiagnostic::SubDiagnostic(sub) => {
                 subdiagnostics.push(sub);
@@ -231,7 +259,7 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
     primary_spans
         .iter()
         .flat_map(|primary_span| {
-            let location = location(workspace_root, &primary_span);
+            let location = location(workspace_root, &primary_span, macro_args);
 
             let mut message = message.clone();
             if needs_primary_span_label {
@@ -284,7 +312,13 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
                 });
             }
 
-            // Emit the primary diagnostic.
+            // Emit the primary diagnostic, with the fixes which are safe to apply, so that they
+            // are available without going to the locations of the suggestions.
+            let fixes = subdiagnostics
+                .iter()
+                .filter_map(|sub| sub.suggested_fix.clone())
+                .filter(|fix| fix.is_preferred == Some(true))
+                .collect();
             diagnostics.push(MappedRustDiagnostic {
                 url: location.uri.clone(),
                 diagnostic: lsp_types::Diagnostic {
@@ -307,7 +341,7 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
                     tags: if tags.is_empty() { None } else { Some(tags.clone()) },
                     data: None,
                 },
-                fixes: Vec::new(),
+                fixes,
             });
 
             // Emit hint-level diagnostics for all `related_information` entries such as "help"s.
@@ -381,9 +415,18 @@ mod tests {
     }
 
     fn check_with_config(config: DiagnosticsMapConfig, diagnostics_json: &str, expect: ExpectFile) {
+        check_with_macro_args(config, &|_, _| None, diagnostics_json, expect)
+    }
+
+    fn check_with_macro_args(
+        config: DiagnosticsMapConfig,
+        macro_args: &MacroArgsMap,
+        diagnostics_json: &str,
+        expect: ExpectFile,
+    ) {
         let diagnostic: flycheck::Diagnostic = serde_json::from_str(diagnostics_json).unwrap();
         let workspace_root = Path::new("/test/");
-        let actual = map_rust_diagnostic_to_lsp(&config, &diagnostic, workspace_root);
+        let actual = map_rust_diagnostic_to_lsp(&config, &diagnostic, workspace_root, macro_args);
         expect.assert_debug_eq(&actual)
     }
 
@@ -1585,4 +1628,94 @@ mod tests {
             expect_file!["./test_data/snap_multi_line_fix.txt"],
         );
     }
+
+    #[test]
+    fn maps_macro_definition_spans_to_arguments() {
+        let macro_args = |call_site: &lsp_types::Location, def_site: &lsp_types::Location| {
+            // `1` in `m!(1)`, where the error occurs in `$e + ""`.
+            assert_eq!(call_site.range.start, lsp_types::Position::new(4, 4));
+            assert_eq!(def_site.range.start, lsp_types::Position::new(1, 19));
+            let range = lsp_types::Range::new(
+                lsp_types::Position::new(4, 7),
+                lsp_types::Position::new(4, 8),
+            );
+            Some(lsp_types::Location::new(call_site.uri.clone(), range))
+        };
+        check_with_macro_args(
+            DiagnosticsMapConfig::default(),
+            &macro_args,
+            r##"{
+                "rendered": "error[E0277]: cannot add `&str` to `{integer}`\n",
+                "children": [],
+                "code": {
+                    "code": "E0277",
+                    "explanation": null
+                },
+                "level": "error",
+                "message": "cannot add `&str` to `{integer}`",
+                "spans": [
+                    {
+                        "byte_end": 46,
+                        "byte_start": 39,
+                        "column_end": 27,
+                        "column_start": 20,
+                        "expansion": {
+                            "def_site_span": {
+                                "byte_end": 50,
+                                "byte_start": 0,
+                                "column_end": 2,
+                                "column_start": 1,
+                                "expansion": null,
+                                "file_name": "src/main.rs",
+                                "is_primary": false,
+                                "label": null,
+                                "line_end": 3,
+                                "line_start": 1,
+                                "suggested_replacement": null,
+                                "suggestion_applicability": null,
+                                "text": []
+                            },
+                            "macro_decl_name": "m!",
+                            "span": {
+                                "byte_end": 72,
+                                "byte_start": 67,
+                                "column_end": 10,
+                                "column_start": 5,
+                                "expansion": null,
+                                "file_name": "src/main.rs",
+                                "is_primary": false,
+                                "label": null,
+                                "line_end": 5,
+                                "line_start": 5,
+                                "suggested_replacement": null,
+                                "suggestion_applicability": null,
+                                "text": [
+                                    {
+                                        "highlight_end": 10,
+                                        "highlight_start": 5,
+                                        "text": "    m!(1);"
+                                    }
+                                ]
+                            }
+                        },
+                        "file_name": "src/main.rs",
+                        "is_primary": true,
+                        "label": "no implementation for `{integer} + &str`",
+                        "line_end": 2,
+                        "line_start": 2,
+                        "suggested_replacement": null,
+                        "suggestion_applicability": null,
+                        "text": [
+                            {
+                                "highlight_end": 27,
+                                "highlight_start": 20,
+                                "text": "    ($e:expr) => { $e + \"\" };"
+                            }
+                        ]
+                    }
+                ]
+            }"##,
+            expect_file!["./test_data/maps_macro_definition_spans_to_arguments.txt"],
+        );
+    }
 }
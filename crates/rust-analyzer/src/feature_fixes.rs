@@ -0,0 +1,290 @@
+//! Quick fixes for code disabled by `#[cfg(feature = "..")]`, which enable the missing features
+//! either in the settings or in the default features of the package.
+//!
+//! Unlike other fixes, these can't be computed by `ide`: the settings belong to the client, and
+//! `Cargo.toml` isn't part of the analyzed sources.
+
+use std::{collections::HashMap, fs, sync::Arc};
+
+use ide::{FileId, TextEdit, TextSize};
+use lsp_types::CodeActionKind;
+
+use crate::{
+    global_state::GlobalStateSnapshot,
+    line_index::{LineEndings, LineIndex},
+    lsp_ext, to_proto, Result,
+};
+
+pub(crate) fn enable_features_fixes(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    features: &[String],
+    acc: &mut Vec<lsp_ext::CodeAction>,
+) -> Result<()> {
+    let names = features.iter().map(|it| format!("`{}`", it)).collect::<Vec<_>>().join(", ");
+    let noun = if features.len() == 1 { "feature" } else { "features" };
+
+    if snap.config.enable_cargo_features_command() {
+        let title = format!("Enable {} {} in the settings", noun, names);
+        acc.push(lsp_ext::CodeAction {
+            title: title.clone(),
+            group: None,
+            kind: Some(CodeActionKind::QUICKFIX),
+            command: Some(lsp_types::Command {
+                title,
+                command: "rust-analyzer.enableCargoFeatures".to_string(),
+                arguments: Some(vec![serde_json::to_value(features)?]),
+            }),
+            edit: None,
+            is_preferred: Some(false),
+            data: None,
+        });
+    }
+
+    let crate_id = match snap.analysis.crate_for(file_id)?.first() {
+        Some(it) => *it,
+        None => return Ok(()),
+    };
+    let (cargo, target) = match snap.cargo_target_for_crate_root(crate_id) {
+        Some(it) => it,
+        None => return Ok(()),
+    };
+    let package = &cargo[cargo[target].package];
+    let default = package.features.get("default");
+    if !package.is_member
+        || features.iter().any(|feature| {
+            !package.features.contains_key(feature)
+                || default.map_or(false, |default| default.contains(feature))
+        })
+    {
+        return Ok(());
+    }
+    let text = match fs::read_to_string(&package.manifest) {
+        Ok(it) => it,
+        Err(_) => return Ok(()),
+    };
+    let (text, endings) = LineEndings::normalize(text);
+    let line_index = LineIndex {
+        index: Arc::new(ide::LineIndex::new(&text)),
+        endings,
+        encoding: snap.config.offset_encoding(),
+    };
+    let edit = add_default_features(&text, features);
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        to_proto::url_from_abs_path(&package.manifest),
+        to_proto::text_edit_vec(&line_index, edit),
+    );
+    acc.push(lsp_ext::CodeAction {
+        title: format!("Add {} {} to the default features of `{}`", noun, names, package.name),
+        group: None,
+        kind: Some(CodeActionKind::QUICKFIX),
+        command: None,
+        edit: Some(lsp_ext::SnippetWorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+        }),
+        is_preferred: Some(false),
+        data: None,
+    });
+    Ok(())
+}
+
+/// Returns the edit adding `features` to the `default` feature in the `[features]` table of the
+/// manifest, creating the feature and the table if they don't exist yet.
+fn add_default_features(manifest: &str, features: &[String]) -> TextEdit {
+    let features = features.iter().map(|it| format!("{:?}", it)).collect::<Vec<_>>();
+
+    let mut table_start = None;
+    let mut offset = 0;
+    while offset < manifest.len() {
+        let line_end = manifest[offset..].find('\n').map_or(manifest.len(), |it| offset + it + 1);
+        let line = manifest[offset..line_end].trim();
+        if line.starts_with('[') {
+            if table_start.is_some() {
+                break;
+            }
+            if line == "[features]" {
+                table_start = Some(line_end);
+            }
+        } else if table_start.is_some() && is_default_key(line) {
+            let array = manifest[offset..].find('[').map(|it| offset + it).and_then(|start| {
+                let end = start + manifest[start..].find(']')?;
+                Some((start, end))
+            });
+            if let Some((start, end)) = array {
+                return add_to_array(manifest, start, end, &features);
+            }
+        }
+        offset = line_end;
+    }
+
+    let default = format!("default = [{}]\n", features.join(", "));
+    let separator = if manifest.is_empty() || manifest.ends_with('\n') { "" } else { "\n" };
+    match table_start {
+        Some(start) if start < manifest.len() => {
+            TextEdit::insert(TextSize::from(start as u32), default)
+        }
+        Some(_) => TextEdit::insert(TextSize::of(manifest), format!("{}{}", separator, default)),
+        None => TextEdit::insert(
+            TextSize::of(manifest),
+            format!("{}\n[features]\n{}", separator, default),
+        ),
+    }
+}
+
+fn is_default_key(line: &str) -> bool {
+    line.strip_prefix("default").map_or(false, |rest| rest.trim_start().starts_with('='))
+}
+
+/// Adds the elements to the array between the brackets at `start` and `end`, keeping it on one
+/// line or one element per line, like it's written.
+fn add_to_array(manifest: &str, start: usize, end: usize, elements: &[String]) -> TextEdit {
+    let last = manifest[start + 1..end].trim_end();
+    let insert_at = start + 1 + last.len();
+    let text = if last.trim_start().is_empty() {
+        elements.join(", ")
+    } else if manifest[insert_at..end].contains('\n') {
+        let line_start = manifest[..insert_at].rfind('\n').map_or(0, |it| it + 1);
+        let line = &manifest[line_start..insert_at];
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let comma = if last.ends_with(',') { "" } else { "," };
+        let elements = elements.iter().map(|it| format!("\n{}{},", indent, it)).collect::<String>();
+        format!("{}{}", comma, elements)
+    } else if last.ends_with(',') {
+        format!(" {}", elements.join(", "))
+    } else {
+        format!(", {}", elements.join(", "))
+    };
+    TextEdit::insert(TextSize::from(insert_at as u32), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use super::add_default_features;
+
+    fn check(before: &str, features: &[&str], after: &str) {
+        let features = features.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+        let mut actual = trim_indent(before);
+        add_default_features(&actual.clone(), &features).apply(&mut actual);
+        assert_eq_text!(&trim_indent(after), &actual);
+    }
+
+    #[test]
+    fn adds_to_existing_default() {
+        check(
+            r#"
+[features]
+default = ["std"]
+serde = []
+"#,
+            &["serde"],
+            r#"
+[features]
+default = ["std", "serde"]
+serde = []
+"#,
+        );
+        check(
+            r#"
+[features]
+default = []
+a = []
+b = []
+"#,
+            &["a", "b"],
+            r#"
+[features]
+default = ["a", "b"]
+a = []
+b = []
+"#,
+        );
+    }
+
+    #[test]
+    fn adds_to_multiline_default() {
+        check(
+            r#"
+[features]
+default = [
+    "std",
+]
+serde = []
+"#,
+            &["serde"],
+            r#"
+[features]
+default = [
+    "std",
+    "serde",
+]
+serde = []
+"#,
+        );
+    }
+
+    #[test]
+    fn creates_default() {
+        check(
+            r#"
+[features]
+serde = []
+
+[dependencies]
+"#,
+            &["serde"],
+            r#"
+[features]
+default = ["serde"]
+serde = []
+
+[dependencies]
+"#,
+        );
+        check(
+            r#"
+[package]
+name = "foo"
+
+[dependencies]
+serde = { version = "1", optional = true }
+"#,
+            &["serde"],
+            r#"
+[package]
+name = "foo"
+
+[dependencies]
+serde = { version = "1", optional = true }
+
+[features]
+default = ["serde"]
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_default_of_other_tables() {
+        check(
+            r#"
+[profile]
+default = ["x"]
+
+[features]
+"#,
+            &["serde"],
+            r#"
+[profile]
+default = ["x"]
+
+[features]
+default = ["serde"]
+"#,
+        );
+    }
+}
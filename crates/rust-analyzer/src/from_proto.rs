@@ -114,5 +114,17 @@ pub(crate) fn annotation(
                 },
             })
         }
+        lsp_ext::CodeLensResolveData::AbsorbedVariants(params) => {
+            let file_id = world.url_to_file_id(&params.text_document.uri)?;
+            let line_index = world.file_line_index(file_id)?;
+
+            Ok(Annotation {
+                range: text_range(&line_index, code_lens.range),
+                kind: AnnotationKind::AbsorbedVariants {
+                    position: file_position(world, params)?,
+                    data: None,
+                },
+            })
+        }
     }
 }
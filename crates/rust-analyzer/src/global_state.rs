@@ -9,12 +9,13 @@ use crossbeam_channel::{unbounded, Receiver, Sender};
 use flycheck::FlycheckHandle;
 use ide::{Analysis, AnalysisHost, Cancelable, Change, FileId};
 use ide_db::base_db::{CrateId, VfsPath};
+use load_cargo::SourceRootConfig;
 use lsp_types::{SemanticTokens, Url};
 use parking_lot::{Mutex, RwLock};
 use project_model::{
     BuildDataCollector, BuildDataResult, CargoWorkspace, ProcMacroClient, ProjectWorkspace, Target,
 };
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use vfs::AnchoredPathBuf;
 
 use crate::{
@@ -25,10 +26,9 @@ use crate::{
     line_index::{LineEndings, LineIndex},
     main_loop::Task,
     op_queue::OpQueue,
-    reload::SourceRootConfig,
     request_metrics::{LatestRequests, RequestMetrics},
     thread_pool::TaskPool,
-    to_proto::url_from_abs_path,
+    to_proto::{self, url_from_abs_path},
     Result,
 };
 
@@ -74,6 +74,8 @@ pub(crate) struct GlobalState {
     pub(crate) config: Arc<Config>,
     pub(crate) analysis_host: AnalysisHost,
     pub(crate) diagnostics: DiagnosticCollection,
+    /// The large files the user was told about, see `Task::LargeFile`.
+    pub(crate) reported_large_files: FxHashSet<FileId>,
     pub(crate) mem_docs: FxHashMap<VfsPath, DocumentData>,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
     pub(crate) vfs: Arc<RwLock<(vfs::Vfs, FxHashMap<FileId, LineEndings>)>>,
@@ -116,7 +118,8 @@ impl GlobalState {
             Handle { handle, receiver }
         };
 
-        let analysis_host = AnalysisHost::new(config.lru_capacity());
+        let mut analysis_host = AnalysisHost::new(config.lru_capacity());
+        analysis_host.update_excluded_code(config.excluded_code());
         let (flycheck_sender, flycheck_receiver) = unbounded();
         GlobalState {
             sender,
@@ -130,6 +133,7 @@ impl GlobalState {
             config: Arc::new(config),
             analysis_host,
             diagnostics: Default::default(),
+            reported_large_files: FxHashSet::default(),
             mem_docs: FxHashMap::default(),
             semantic_tokens_cache: Arc::new(Default::default()),
             vfs: Arc::new(RwLock::new((vfs::Vfs::default(), FxHashMap::default()))),
@@ -291,6 +295,23 @@ impl GlobalStateSnapshot {
         url_from_abs_path(&path)
     }
 
+    /// Maps the location of a `cargo check` diagnostic in the definition of a macro to the
+    /// arguments of the macro call at `call_site` which were expanded there.
+    pub(crate) fn macro_args_location(
+        &self,
+        call_site: &lsp_types::Location,
+        def_site: &lsp_types::Location,
+    ) -> Option<lsp_types::Location> {
+        let document = |location: &lsp_types::Location| {
+            lsp_types::TextDocumentIdentifier::new(location.uri.clone())
+        };
+        let call = from_proto::file_range(self, document(call_site), call_site.range).ok()?;
+        let def = from_proto::file_range(self, document(def_site), def_site.range).ok()?;
+        let range = self.analysis.macro_call_range_from_def(call, def).ok()??;
+        let line_index = self.file_line_index(call.file_id).ok()?;
+        Some(lsp_types::Location::new(call_site.uri.clone(), to_proto::range(&line_index, range)))
+    }
+
     pub(crate) fn cargo_target_for_crate_root(
         &self,
         crate_id: CrateId,
@@ -28,13 +28,18 @@ use project_model::TargetKind;
 use serde::{Deserialize, Serialize};
 use serde_json::to_value;
 use stdx::{format_to, split_once};
-use syntax::{algo, ast, AstNode, TextRange, TextSize};
+use syntax::{
+    algo,
+    ast::{self, ModuleItemOwner},
+    AstNode, TextRange, TextSize,
+};
 
 use crate::{
     cargo_target_spec::CargoTargetSpec,
+    cargo_toml,
     config::RustfmtConfig,
     diff::diff,
-    from_proto,
+    feature_fixes, from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
     line_index::{LineEndings, LineIndex},
     lsp_ext::{self, InlayHint, InlayHintsParams},
@@ -133,6 +138,9 @@ pub(crate) fn handle_selection_range(
     params: lsp_types::SelectionRangeParams,
 ) -> Result<Option<Vec<lsp_types::SelectionRange>>> {
     let _p = profile::span("handle_selection_range");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let line_index = snap.file_line_index(file_id)?;
     let res: Result<Vec<lsp_types::SelectionRange>> = params
@@ -236,6 +244,9 @@ pub(crate) fn handle_on_type_formatting(
     params: lsp_types::DocumentOnTypeFormattingParams,
 ) -> Result<Option<Vec<lsp_types::TextEdit>>> {
     let _p = profile::span("handle_on_type_formatting");
+    if cargo_toml::is_manifest(params.text_document_position.text_document.uri.path()) {
+        return Ok(None);
+    }
     let mut position = from_proto::file_position(&snap, params.text_document_position)?;
     let line_index = snap.file_line_index(position.file_id)?;
 
@@ -274,6 +285,9 @@ pub(crate) fn handle_document_symbol(
     params: lsp_types::DocumentSymbolParams,
 ) -> Result<Option<lsp_types::DocumentSymbolResponse>> {
     let _p = profile::span("handle_document_symbol");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let line_index = snap.file_line_index(file_id)?;
 
@@ -614,6 +628,10 @@ pub(crate) fn handle_completion(
     let _p = profile::span("handle_completion");
     let text_document_position = params.text_document_position.clone();
     let position = from_proto::file_position(&snap, params.text_document_position)?;
+    if cargo_toml::is_manifest(text_document_position.text_document.uri.path()) {
+        let items = cargo_toml::completions(&snap, position.file_id, position.offset)?;
+        return Ok(items.map(lsp_types::CompletionResponse::Array));
+    }
     let completion_triggered_after_single_colon = {
         let mut res = false;
         if let Some(ctx) = params.context {
@@ -637,13 +655,14 @@ pub(crate) fn handle_completion(
     }
 
     let completion_config = &snap.config.completion();
-    let items = match snap.analysis.completions(completion_config, position)? {
+    let list = match snap.analysis.completions(completion_config, position)? {
         None => return Ok(None),
-        Some(items) => items,
+        Some(list) => list,
     };
     let line_index = snap.file_line_index(position.file_id)?;
 
-    let items: Vec<CompletionItem> = items
+    let items: Vec<CompletionItem> = list
+        .items
         .into_iter()
         .flat_map(|item| {
             let mut new_completion_items = to_proto::completion_item(&line_index, item.clone());
@@ -726,6 +745,9 @@ pub(crate) fn handle_folding_range(
     params: FoldingRangeParams,
 ) -> Result<Option<Vec<FoldingRange>>> {
     let _p = profile::span("handle_folding_range");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let folds = snap.analysis.folding_ranges(file_id)?;
     let text = snap.analysis.file_text(file_id)?;
@@ -743,6 +765,9 @@ pub(crate) fn handle_signature_help(
     params: lsp_types::SignatureHelpParams,
 ) -> Result<Option<lsp_types::SignatureHelp>> {
     let _p = profile::span("handle_signature_help");
+    if cargo_toml::is_manifest(params.text_document_position_params.text_document.uri.path()) {
+        return Ok(None);
+    }
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
     let call_info = match snap.analysis.call_info(position)? {
         Some(it) => it,
@@ -759,6 +784,9 @@ pub(crate) fn handle_hover(
     params: lsp_types::HoverParams,
 ) -> Result<Option<lsp_ext::Hover>> {
     let _p = profile::span("handle_hover");
+    if cargo_toml::is_manifest(params.text_document_position_params.text_document.uri.path()) {
+        return Ok(None);
+    }
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
     let hover_config = snap.config.hover();
     let info =
@@ -844,52 +872,108 @@ pub(crate) fn handle_formatting(
     params: DocumentFormattingParams,
 ) -> Result<Option<Vec<lsp_types::TextEdit>>> {
     let _p = profile::span("handle_formatting");
-    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    run_rustfmt(&snap, params.text_document, None)
+}
+
+pub(crate) fn handle_range_formatting(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::DocumentRangeFormattingParams,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    let _p = profile::span("handle_range_formatting");
+    run_rustfmt(&snap, params.text_document, Some(params.range))
+}
+
+fn run_rustfmt(
+    snap: &GlobalStateSnapshot,
+    text_document: TextDocumentIdentifier,
+    range: Option<lsp_types::Range>,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    if cargo_toml::is_manifest(text_document.uri.path()) {
+        return Ok(None);
+    }
+    let file_id = from_proto::file_id(&snap, &text_document.uri)?;
     let file = snap.analysis.file_text(file_id)?;
     let crate_ids = snap.analysis.crate_for(file_id)?;
 
     let line_index = snap.file_line_index(file_id)?;
 
+    // The part of the file which is formatted when rustfmt can't format just the given range.
+    let mut fragment = None;
+
     let mut rustfmt = match snap.config.rustfmt() {
-        RustfmtConfig::Rustfmt { extra_args } => {
+        RustfmtConfig::Rustfmt { extra_args, enable_range_formatting } => {
             let mut cmd = process::Command::new(toolchain::rustfmt());
             cmd.args(extra_args);
-            // try to chdir to the file so we can respect `rustfmt.toml`
-            // FIXME: use `rustfmt --config-path` once
-            // https://github.com/rust-lang/rustfmt/issues/4660 gets fixed
-            match params.text_document.uri.to_file_path() {
-                Ok(mut path) => {
-                    // pop off file name
-                    if path.pop() && path.is_dir() {
-                        cmd.current_dir(path);
-                    }
-                }
-                Err(_) => {
-                    log::error!(
-                        "Unable to get file path for {}, rustfmt.toml might be ignored",
-                        params.text_document.uri
-                    );
-                }
-            }
             if let Some(&crate_id) = crate_ids.first() {
                 // Assume all crates are in the same edition
                 let edition = snap.analysis.crate_edition(crate_id)?;
                 cmd.arg("--edition");
                 cmd.arg(edition.to_string());
             }
+            match range {
+                Some(range) if enable_range_formatting => {
+                    // Selections often end at the start of the line after the last selected one.
+                    let end_line = if range.end.character == 0 && range.end.line > range.start.line
+                    {
+                        range.end.line
+                    } else {
+                        range.end.line + 1
+                    };
+                    let file_lines = serde_json::json!([{
+                        "file": "stdin",
+                        "range": [range.start.line + 1, end_line],
+                    }]);
+                    cmd.arg("--unstable-features");
+                    cmd.arg("--file-lines");
+                    cmd.arg(file_lines.to_string());
+                }
+                Some(range) => fragment = Some(from_proto::text_range(&line_index, range)),
+                None => (),
+            }
             cmd
         }
         RustfmtConfig::CustomCommand { command, args } => {
             let mut cmd = process::Command::new(command);
             cmd.args(args);
+            fragment = range.map(|range| from_proto::text_range(&line_index, range));
             cmd
         }
     };
 
+    // try to chdir to the file so we can respect `rustfmt.toml`
+    // FIXME: use `rustfmt --config-path` once
+    // https://github.com/rust-lang/rustfmt/issues/4660 gets fixed
+    match text_document.uri.to_file_path() {
+        Ok(mut path) => {
+            // pop off file name
+            if path.pop() && path.is_dir() {
+                rustfmt.current_dir(path);
+            }
+        }
+        Err(_) => {
+            log::error!(
+                "Unable to get file path for {}, rustfmt.toml might be ignored",
+                text_document.uri
+            );
+        }
+    }
+
+    let fragment = match fragment {
+        Some(range) => match enclosing_items_range(&snap.analysis.parse(file_id)?, range) {
+            Some(it) => Some(it),
+            None => return Ok(None),
+        },
+        None => None,
+    };
+    let input = match fragment {
+        Some(range) => &file[range],
+        None => &file[..],
+    };
+
     let mut rustfmt =
         rustfmt.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
 
-    rustfmt.stdin.as_mut().unwrap().write_all(file.as_bytes())?;
+    rustfmt.stdin.as_mut().unwrap().write_all(input.as_bytes())?;
 
     let output = rustfmt.wait_with_output()?;
     let captured_stdout = String::from_utf8(output.stdout)?;
@@ -924,6 +1008,15 @@ pub(crate) fn handle_formatting(
     }
 
     let (new_text, new_line_endings) = LineEndings::normalize(captured_stdout);
+    let new_text = match fragment {
+        Some(range) => {
+            // rustfmt ends the fragment with a newline, like any file.
+            let mut text = file.to_string();
+            text.replace_range(std::ops::Range::<usize>::from(range), new_text.trim_end());
+            text
+        }
+        None => new_text,
+    };
 
     if line_index.endings != new_line_endings {
         // If line endings are different, send the entire file.
@@ -941,14 +1034,28 @@ pub(crate) fn handle_formatting(
     }
 }
 
+/// Returns the range of the top-level items overlapping with `range`, which are formatted
+/// instead of the range when rustfmt can't format ranges.
+fn enclosing_items_range(file: &ast::SourceFile, range: TextRange) -> Option<TextRange> {
+    let mut items =
+        file.items().map(|it| it.syntax().text_range()).filter(|it| it.intersect(range).is_some());
+    let first = items.next()?;
+    Some(items.fold(first, |acc, it| acc.cover(it)))
+}
+
 pub(crate) fn handle_code_action(
     snap: GlobalStateSnapshot,
     params: lsp_types::CodeActionParams,
 ) -> Result<Option<Vec<lsp_ext::CodeAction>>> {
     let _p = profile::span("handle_code_action");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
     // We intentionally don't support command-based actions, as those either
     // requires custom client-code anyway, or requires server-initiated edits.
     // Server initiated edits break causality, so we avoid those as well.
+    // The only exception are changes of the settings, which only the client
+    // can make.
     if !snap.config.code_action_literals() {
         return Ok(None);
     }
@@ -1000,9 +1107,23 @@ fn add_quick_fixes(
 ) -> Result<()> {
     let diagnostics = snap.analysis.diagnostics(&snap.config.diagnostics(), frange.file_id)?;
 
+    for diagnostic in &diagnostics {
+        if !diagnostic.features_to_enable.is_empty()
+            && diagnostic.range.intersect(frange.range).is_some()
+        {
+            feature_fixes::enable_features_fixes(
+                snap,
+                frange.file_id,
+                &diagnostic.features_to_enable,
+                acc,
+            )?;
+        }
+    }
+
     for fix in diagnostics
         .into_iter()
-        .filter_map(|d| d.fix)
+        .filter_map(|d| d.fixes)
+        .flatten()
         .filter(|fix| fix.fix_trigger_range.intersect(frange.range).is_some())
     {
         let edit = to_proto::snippet_workspace_edit(&snap, fix.source_change)?;
@@ -1010,6 +1131,7 @@ fn add_quick_fixes(
             title: fix.label.to_string(),
             group: None,
             kind: Some(CodeActionKind::QUICKFIX),
+            command: None,
             edit: Some(edit),
             is_preferred: Some(false),
             data: None,
@@ -1019,7 +1141,8 @@ fn add_quick_fixes(
 
     for fix in snap.check_fixes.get(&frange.file_id).into_iter().flatten() {
         let fix_range = from_proto::text_range(&line_index, fix.range);
-        if fix_range.intersect(frange.range).is_some() {
+        // The fixes of `cargo check` diagnostics are also attached to their hint diagnostics.
+        if fix_range.intersect(frange.range).is_some() && !acc.contains(&fix.action) {
             acc.push(fix.action.clone());
         }
     }
@@ -1063,6 +1186,9 @@ pub(crate) fn handle_code_lens(
     params: lsp_types::CodeLensParams,
 ) -> Result<Option<Vec<CodeLens>>> {
     let _p = profile::span("handle_code_lens");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
 
     let lens_config = snap.config.lens();
     if lens_config.none() {
@@ -1090,6 +1216,7 @@ pub(crate) fn handle_code_lens(
                 annotate_impls: lens_config.implementations,
                 annotate_references: lens_config.refs,
                 annotate_method_references: lens_config.method_refs,
+                annotate_wildcard_arms: lens_config.wildcard_arms,
                 run: lens_config.run,
                 debug: lens_config.debug,
             },
@@ -1115,6 +1242,9 @@ pub(crate) fn handle_document_highlight(
     params: lsp_types::DocumentHighlightParams,
 ) -> Result<Option<Vec<DocumentHighlight>>> {
     let _p = profile::span("handle_document_highlight");
+    if cargo_toml::is_manifest(params.text_document_position_params.text_document.uri.path()) {
+        return Ok(None);
+    }
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
     let line_index = snap.file_line_index(position.file_id)?;
 
@@ -1172,11 +1302,27 @@ pub(crate) fn handle_ssr(
     to_proto::workspace_edit(&snap, source_change)
 }
 
+pub(crate) fn handle_search_by_type(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::SearchByTypeParams,
+) -> Result<Vec<lsp_types::Location>> {
+    let _p = profile::span("handle_search_by_type");
+    let file_id = match params.text_document {
+        Some(text_document) => Some(from_proto::file_id(&snap, &text_document.uri)?),
+        None => None,
+    };
+    let ranges = snap.analysis.search_by_type(&params.query, file_id)??;
+    ranges.into_iter().map(|frange| to_proto::location(&snap, frange)).collect()
+}
+
 pub(crate) fn publish_diagnostics(
     snap: &GlobalStateSnapshot,
     file_id: FileId,
 ) -> Result<Vec<Diagnostic>> {
     let _p = profile::span("publish_diagnostics");
+    if cargo_toml::is_manifest(snap.file_id_to_url(file_id).path()) {
+        return cargo_toml::diagnostics(snap, file_id);
+    }
     let line_index = snap.file_line_index(file_id)?;
 
     let diagnostics: Vec<Diagnostic> = snap
@@ -1197,7 +1343,15 @@ pub(crate) fn publish_diagnostics(
             }),
             source: Some("rust-analyzer".to_string()),
             message: d.message,
-            related_information: None,
+            related_information: if d.related_information.is_empty() {
+                None
+            } else {
+                let related = d.related_information.into_iter().filter_map(|(frange, message)| {
+                    let location = to_proto::location(snap, frange).ok()?;
+                    Some(lsp_types::DiagnosticRelatedInformation { location, message })
+                });
+                Some(related.collect())
+            },
             tags: if d.unused { Some(vec![DiagnosticTag::Unnecessary]) } else { None },
             data: None,
         })
@@ -1211,6 +1365,9 @@ pub(crate) fn handle_inlay_hints(
 ) -> Result<Vec<InlayHint>> {
     let _p = profile::span("handle_inlay_hints");
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    if snap.analysis.is_large_file(file_id, &snap.config.large_file())? {
+        return Ok(Vec::new());
+    }
     let line_index = snap.file_line_index(file_id)?;
     Ok(snap
         .analysis
@@ -1317,6 +1474,9 @@ pub(crate) fn handle_semantic_tokens_full(
     params: SemanticTokensParams,
 ) -> Result<Option<SemanticTokensResult>> {
     let _p = profile::span("handle_semantic_tokens_full");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let text = snap.analysis.file_text(file_id)?;
@@ -1336,6 +1496,9 @@ pub(crate) fn handle_semantic_tokens_full_delta(
     params: SemanticTokensDeltaParams,
 ) -> Result<Option<SemanticTokensFullDeltaResult>> {
     let _p = profile::span("handle_semantic_tokens_full_delta");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let text = snap.analysis.file_text(file_id)?;
@@ -1366,6 +1529,9 @@ pub(crate) fn handle_semantic_tokens_range(
     params: SemanticTokensRangeParams,
 ) -> Result<Option<SemanticTokensRangeResult>> {
     let _p = profile::span("handle_semantic_tokens_range");
+    if cargo_toml::is_manifest(params.text_document.uri.path()) {
+        return Ok(None);
+    }
 
     let frange = from_proto::file_range(&snap, params.text_document, params.range)?;
     let text = snap.analysis.file_text(frange.file_id)?;
@@ -35,6 +35,9 @@ mod lsp_utils;
 mod thread_pool;
 mod document;
 mod diff;
+mod feature_fixes;
+mod cargo_toml;
+mod project_config;
 mod op_queue;
 pub mod lsp_ext;
 pub mod config;
@@ -196,6 +196,8 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    PanicHint,
+    DiscriminantHint,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -228,6 +230,23 @@ pub struct SsrParams {
     pub selections: Vec<lsp_types::Range>,
 }
 
+pub enum SearchByType {}
+
+impl Request for SearchByType {
+    type Params = SearchByTypeParams;
+    type Result = Vec<lsp_types::Location>;
+    const METHOD: &'static str = "experimental/searchByType";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchByTypeParams {
+    /// A function signature pattern like `fn(&str) -> _`, or a type pattern like `Vec<_>`.
+    pub query: String,
+    /// The file to search for expressions of a type in. Not needed for signature patterns.
+    pub text_document: Option<TextDocumentIdentifier>,
+}
+
 pub enum StatusNotification {}
 
 #[derive(Serialize, Deserialize)]
@@ -250,6 +269,18 @@ impl Notification for StatusNotification {
     const METHOD: &'static str = "rust-analyzer/status";
 }
 
+pub enum DependencySources {}
+
+#[derive(Deserialize, Serialize)]
+pub struct DependencySourcesParams {
+    pub roots: Vec<lsp_types::Url>,
+}
+
+impl Notification for DependencySources {
+    type Params = DependencySourcesParams;
+    const METHOD: &'static str = "rust-analyzer/dependencySources";
+}
+
 pub enum CodeActionRequest {}
 
 impl Request for CodeActionRequest {
@@ -273,9 +304,9 @@ pub struct CodeAction {
     pub group: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kind: Option<CodeActionKind>,
-    // We don't handle commands on the client-side
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub command: Option<lsp_types::Command>,
+    /// Only used for changing the settings, as that has to be done by the client.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<lsp_types::Command>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub edit: Option<SnippetWorkspaceEdit>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -384,6 +415,7 @@ pub struct OpenCargoTomlParams {
 pub(crate) enum CodeLensResolveData {
     Impls(lsp_types::request::GotoImplementationParams),
     References(lsp_types::TextDocumentPositionParams),
+    AbsorbedVariants(lsp_types::TextDocumentPositionParams),
 }
 
 pub fn supports_utf8(caps: &lsp_types::ClientCapabilities) -> bool {
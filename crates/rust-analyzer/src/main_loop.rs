@@ -22,6 +22,7 @@ use crate::{
     global_state::{file_id_to_url, url_to_file_id, GlobalState, Status},
     handlers, lsp_ext,
     lsp_utils::{apply_document_changes, is_canceled, notification_is, Progress},
+    project_config,
     reload::{BuildDataProgress, ProjectWorkspaceProgress},
     Result,
 };
@@ -62,6 +63,8 @@ enum Event {
 pub(crate) enum Task {
     Response(Response),
     Diagnostics(Vec<(FileId, Vec<lsp_types::Diagnostic>)>),
+    /// An open file is too large for diagnostics and inlay hints.
+    LargeFile(FileId),
     PrimeCaches(PrimeCachesProgress),
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
@@ -109,6 +112,7 @@ impl GlobalState {
                 "rust-analyzer failed to discover workspace".to_string(),
             );
         };
+        self.reload_project_config();
 
         if self.config.did_save_text_document_dynamic_registration() {
             let save_registration_options = lsp_types::TextDocumentSaveRegistrationOptions {
@@ -130,6 +134,21 @@ impl GlobalState {
                             scheme: None,
                             pattern: Some("**/Cargo.lock".into()),
                         },
+                        lsp_types::DocumentFilter {
+                            language: None,
+                            scheme: None,
+                            pattern: Some("**/.cargo/config".into()),
+                        },
+                        lsp_types::DocumentFilter {
+                            language: None,
+                            scheme: None,
+                            pattern: Some("**/.cargo/config.toml".into()),
+                        },
+                        lsp_types::DocumentFilter {
+                            language: None,
+                            scheme: None,
+                            pattern: Some(format!("**/{}", project_config::FILE_NAME)),
+                        },
                     ]),
                 },
             };
@@ -207,6 +226,20 @@ impl GlobalState {
                                 self.diagnostics.set_native_diagnostics(file_id, diagnostics)
                             }
                         }
+                        Task::LargeFile(file_id) => {
+                            // Tell the user why the file has no diagnostics, but only once.
+                            if self.reported_large_files.insert(file_id) {
+                                let path = self.vfs.read().0.file_path(file_id);
+                                self.show_message(
+                                    lsp_types::MessageType::Info,
+                                    format!(
+                                        "{} is too large for diagnostics and inlay hints, see the \
+                                         `rust-analyzer.largeFile` settings",
+                                        path
+                                    ),
+                                );
+                            }
+                        }
                         Task::PrimeCaches(progress) => match progress {
                             PrimeCachesProgress::Started => prime_caches_progress.push(progress),
                             PrimeCachesProgress::StartedOnCrate { .. } => {
@@ -339,11 +372,15 @@ impl GlobalState {
                 loop {
                     match task {
                         flycheck::Message::AddDiagnostic { workspace_root, diagnostic } => {
+                            let snap = self.snapshot();
                             let diagnostics =
                                 crate::diagnostics::to_proto::map_rust_diagnostic_to_lsp(
                                     &self.config.diagnostics_map(),
                                     &diagnostic,
                                     &workspace_root,
+                                    &|call_site, def_site| {
+                                        snap.macro_args_location(call_site, def_site)
+                                    },
                                 );
                             for diag in diagnostics {
                                 match url_to_file_id(&self.vfs.read().0, &diag.url) {
@@ -522,6 +559,7 @@ impl GlobalState {
             .on::<lsp_types::request::Rename>(handlers::handle_rename)
             .on::<lsp_types::request::References>(handlers::handle_references)
             .on::<lsp_types::request::Formatting>(handlers::handle_formatting)
+            .on::<lsp_types::request::RangeFormatting>(handlers::handle_range_formatting)
             .on::<lsp_types::request::DocumentHighlightRequest>(handlers::handle_document_highlight)
             .on::<lsp_types::request::CallHierarchyPrepare>(handlers::handle_call_hierarchy_prepare)
             .on::<lsp_types::request::CallHierarchyIncomingCalls>(
@@ -541,6 +579,7 @@ impl GlobalState {
             )
             .on::<lsp_types::request::WillRenameFiles>(handlers::handle_will_rename_files)
             .on::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on::<lsp_ext::SearchByType>(handlers::handle_search_by_type)
             .finish();
         Ok(())
     }
@@ -635,6 +674,9 @@ impl GlobalState {
                     flycheck.update();
                 }
                 if let Ok(abs_path) = from_proto::abs_path(&params.text_document.uri) {
+                    if abs_path == this.config.root_path.join(project_config::FILE_NAME) {
+                        this.reload_project_config();
+                    }
                     this.maybe_refresh(&[(abs_path, ChangeKind::Modify)]);
                 }
                 Ok(())
@@ -713,10 +755,15 @@ impl GlobalState {
         log::trace!("updating notifications for {:?}", subscriptions);
         if self.config.publish_diagnostics() {
             let snapshot = self.snapshot();
-            self.task_pool.handle.spawn(move || {
+            self.task_pool.handle.spawn_with_sender(move |sender| {
+                let large_file = snapshot.config.large_file();
                 let diagnostics = subscriptions
                     .into_iter()
                     .filter_map(|file_id| {
+                        if snapshot.analysis.is_large_file(file_id, &large_file).ok()? {
+                            sender.send(Task::LargeFile(file_id)).unwrap();
+                            return Some((file_id, Vec::new()));
+                        }
                         handlers::publish_diagnostics(&snapshot, file_id)
                             .map_err(|err| {
                                 if !is_canceled(&*err) {
@@ -728,7 +775,7 @@ impl GlobalState {
                             .map(|diags| (file_id, diags))
                     })
                     .collect::<Vec<_>>();
-                Task::Diagnostics(diagnostics)
+                sender.send(Task::Diagnostics(diagnostics)).unwrap();
             })
         }
     }
@@ -0,0 +1,84 @@
+//! Reads `rust-analyzer.toml`, the configuration which is checked into a project, as opposed to
+//! the settings of each user's editor.
+//!
+//! For now, it only holds the structural search replace rules whose matches are reported as
+//! diagnostics, like the uses of a deprecated internal API:
+//!
+//! ```toml
+//! [diagnostics]
+//! ssr-rules = ["crate::old_util($x) ==>> crate::new_util($x)"]
+//! ```
+use ide::SsrRule;
+use serde::Deserialize;
+
+/// The name of the file, at the root of the workspace.
+pub(crate) const FILE_NAME: &str = "rust-analyzer.toml";
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ProjectConfig {
+    /// The parsed rules, together with their text.
+    pub(crate) ssr_rules: Vec<(String, SsrRule)>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case")]
+struct ProjectConfigData {
+    diagnostics: DiagnosticsData,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case")]
+struct DiagnosticsData {
+    ssr_rules: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// Parses the text of the file. The errors are returned next to the valid part of the config,
+    /// so that one invalid rule doesn't disable the others.
+    pub(crate) fn parse(text: &str) -> (ProjectConfig, Vec<String>) {
+        let data: ProjectConfigData = match toml::from_str(text) {
+            Ok(it) => it,
+            Err(e) => return (ProjectConfig::default(), vec![e.to_string()]),
+        };
+        let mut res = ProjectConfig::default();
+        let mut errors = Vec::new();
+        for rule in data.diagnostics.ssr_rules {
+            match rule.parse() {
+                Ok(parsed) => res.ssr_rules.push((rule, parsed)),
+                Err(e) => errors.push(format!("invalid SSR rule `{}`: {}", rule, e)),
+            }
+        }
+        (res, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProjectConfig;
+
+    #[test]
+    fn parses_ssr_rules() {
+        let (config, errors) = ProjectConfig::parse(
+            r#"
+[diagnostics]
+ssr-rules = [
+    "crate::old_util($x) ==>> crate::new_util($x)",
+    "old($x)",
+]
+"#,
+        );
+        let rules = config.ssr_rules.iter().map(|(text, _)| text.as_str()).collect::<Vec<_>>();
+        assert_eq!(rules, vec!["crate::old_util($x) ==>> crate::new_util($x)"]);
+        assert_eq!(
+            errors,
+            vec!["invalid SSR rule `old($x)`: Parse error: Cannot find delimiter `==>>`"]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_files() {
+        let (config, errors) = ProjectConfig::parse("[diagnostics]\nssr-rules = 1\n");
+        assert!(config.ssr_rules.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+}
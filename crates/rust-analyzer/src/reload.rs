@@ -1,17 +1,20 @@
 //! Project loading & configuration updates
-use std::{mem, sync::Arc};
+use std::{fs, io, mem, sync::Arc};
 
 use flycheck::{FlycheckConfig, FlycheckHandle};
 use ide::Change;
-use ide_db::base_db::{CrateGraph, SourceRoot, VfsPath};
+use ide_db::base_db::CrateGraph;
+use load_cargo::ProjectFolders;
 use project_model::{BuildDataCollector, BuildDataResult, ProcMacroClient, ProjectWorkspace};
-use vfs::{file_set::FileSetConfig, AbsPath, AbsPathBuf, ChangeKind};
+use vfs::{AbsPath, AbsPathBuf, ChangeKind};
 
 use crate::{
     config::{Config, FilesWatcher, LinkedProject},
     global_state::{GlobalState, Status},
     lsp_ext,
     main_loop::Task,
+    project_config::{self, ProjectConfig},
+    to_proto,
 };
 use lsp_ext::StatusParams;
 
@@ -36,12 +39,35 @@ impl GlobalState {
         if self.config.lru_capacity() != old_config.lru_capacity() {
             self.analysis_host.update_lru_capacity(self.config.lru_capacity());
         }
+        self.analysis_host.update_excluded_code(self.config.excluded_code());
         if self.config.linked_projects() != old_config.linked_projects() {
             self.fetch_workspaces_request()
         } else if self.config.flycheck() != old_config.flycheck() {
             self.reload_flycheck();
         }
     }
+    /// Reads the `rust-analyzer.toml` at the root of the workspace, reporting the errors in it.
+    pub(crate) fn reload_project_config(&mut self) {
+        let path = self.config.root_path.join(project_config::FILE_NAME);
+        let text = match fs::read_to_string(&path) {
+            Ok(it) => it,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                log::error!("failed to read {}: {}", path.display(), e);
+                String::new()
+            }
+        };
+        let (project_config, errors) = ProjectConfig::parse(&text);
+        for error in errors {
+            self.show_message(
+                lsp_types::MessageType::Error,
+                format!("{}: {}", project_config::FILE_NAME, error),
+            );
+        }
+        let mut config = Config::clone(&*self.config);
+        config.update_project_config(project_config);
+        self.update_configuration(config);
+    }
     pub(crate) fn maybe_refresh(&mut self, changes: &[(AbsPathBuf, ChangeKind)]) {
         if !changes.iter().any(|(path, kind)| is_interesting(path, *kind)) {
             return;
@@ -73,6 +99,9 @@ impl GlobalState {
             if path.ends_with("Cargo.toml") || path.ends_with("Cargo.lock") {
                 return true;
             }
+            if path.ends_with(".cargo/config") || path.ends_with(".cargo/config.toml") {
+                return true;
+            }
             if change_kind == ChangeKind::Modify {
                 return false;
             }
@@ -179,10 +208,7 @@ impl GlobalState {
                             )
                         }
                         LinkedProject::InlineJsonProject(it) => {
-                            project_model::ProjectWorkspace::load_inline(
-                                it.clone(),
-                                cargo_config.target.as_deref(),
-                            )
+                            project_model::ProjectWorkspace::load_inline(it.clone(), &cargo_config)
                         }
                     })
                     .collect::<Vec<_>>();
@@ -278,6 +304,13 @@ impl GlobalState {
         let files_config = self.config.files();
         let project_folders =
             ProjectFolders::new(&workspaces, &files_config.exclude, workspace_build_data.as_ref());
+        if self.config.dependency_sources_notification() {
+            let roots =
+                project_folders.library_roots.iter().map(|it| to_proto::url_from_abs_path(it));
+            self.send_notification::<lsp_ext::DependencySources>(
+                lsp_ext::DependencySourcesParams { roots: roots.collect() },
+            );
+        }
 
         if self.proc_macro_client.is_none() {
             self.proc_macro_client = match self.config.proc_macro_srv() {
@@ -393,81 +426,3 @@ impl GlobalState {
             .collect();
     }
 }
-
-#[derive(Default)]
-pub(crate) struct ProjectFolders {
-    pub(crate) load: Vec<vfs::loader::Entry>,
-    pub(crate) watch: Vec<usize>,
-    pub(crate) source_root_config: SourceRootConfig,
-}
-
-impl ProjectFolders {
-    pub(crate) fn new(
-        workspaces: &[ProjectWorkspace],
-        global_excludes: &[AbsPathBuf],
-        build_data: Option<&BuildDataResult>,
-    ) -> ProjectFolders {
-        let mut res = ProjectFolders::default();
-        let mut fsc = FileSetConfig::builder();
-        let mut local_filesets = vec![];
-
-        for root in workspaces.iter().flat_map(|it| it.to_roots(build_data)) {
-            let file_set_roots: Vec<VfsPath> =
-                root.include.iter().cloned().map(VfsPath::from).collect();
-
-            let entry = {
-                let mut dirs = vfs::loader::Directories::default();
-                dirs.extensions.push("rs".into());
-                dirs.include.extend(root.include);
-                dirs.exclude.extend(root.exclude);
-                for excl in global_excludes {
-                    if dirs.include.iter().any(|incl| incl.starts_with(excl)) {
-                        dirs.exclude.push(excl.clone());
-                    }
-                }
-
-                vfs::loader::Entry::Directories(dirs)
-            };
-
-            if root.is_member {
-                res.watch.push(res.load.len());
-            }
-            res.load.push(entry);
-
-            if root.is_member {
-                local_filesets.push(fsc.len());
-            }
-            fsc.add_file_set(file_set_roots)
-        }
-
-        let fsc = fsc.build();
-        res.source_root_config = SourceRootConfig { fsc, local_filesets };
-
-        res
-    }
-}
-
-#[derive(Default, Debug)]
-pub(crate) struct SourceRootConfig {
-    pub(crate) fsc: FileSetConfig,
-    pub(crate) local_filesets: Vec<usize>,
-}
-
-impl SourceRootConfig {
-    pub(crate) fn partition(&self, vfs: &vfs::Vfs) -> Vec<SourceRoot> {
-        let _p = profile::span("SourceRootConfig::partition");
-        self.fsc
-            .partition(vfs)
-            .into_iter()
-            .enumerate()
-            .map(|(idx, file_set)| {
-                let is_local = self.local_filesets.contains(&idx);
-                if is_local {
-                    SourceRoot::new_local(file_set)
-                } else {
-                    SourceRoot::new_library(file_set)
-                }
-            })
-            .collect()
-    }
-}
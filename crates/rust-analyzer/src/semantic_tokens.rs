@@ -89,6 +89,7 @@ define_semantic_token_modifiers![
     (UNSAFE, "unsafe"),
     (ATTRIBUTE_MODIFIER, "attribute"),
     (CALLABLE, "callable"),
+    (INACTIVE, "inactive"),
 ];
 
 #[derive(Default)]
@@ -335,6 +335,8 @@ pub(crate) fn inlay_hint(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_e
             InlayKind::ParameterHint => lsp_ext::InlayKind::ParameterHint,
             InlayKind::TypeHint => lsp_ext::InlayKind::TypeHint,
             InlayKind::ChainingHint => lsp_ext::InlayKind::ChainingHint,
+            InlayKind::PanicHint => lsp_ext::InlayKind::PanicHint,
+            InlayKind::DiscriminantHint => lsp_ext::InlayKind::DiscriminantHint,
         },
     }
 }
@@ -457,6 +459,7 @@ fn semantic_token_type_and_modifiers(
             HlMod::Unsafe => semantic_tokens::UNSAFE,
             HlMod::Callable => semantic_tokens::CALLABLE,
             HlMod::Static => lsp_types::SemanticTokenModifier::STATIC,
+            HlMod::Inactive => semantic_tokens::INACTIVE,
             HlMod::Associated => continue,
         };
         mods |= modifier;
@@ -800,6 +803,7 @@ pub(crate) fn unresolved_code_action(
         title: assist.label.to_string(),
         group: assist.group.filter(|_| snap.config.code_action_group()).map(|gr| gr.0),
         kind: Some(code_action_kind(assist.id.1)),
+        command: None,
         edit: None,
         is_preferred: None,
         data: Some(lsp_ext::CodeActionData {
@@ -816,6 +820,7 @@ pub(crate) fn resolved_code_action(
 ) -> Result<lsp_ext::CodeAction> {
     let change = assist.source_change.unwrap();
     let res = lsp_ext::CodeAction {
+        command: None,
         edit: Some(snippet_workspace_edit(snap, change)?),
         title: assist.label.to_string(),
         group: assist.group.filter(|_| snap.config.code_action_group()).map(|gr| gr.0),
@@ -948,6 +953,45 @@ pub(crate) fn code_lens(
                 data: Some(to_value(lsp_ext::CodeLensResolveData::References(doc_pos)).unwrap()),
             })
         }
+        AnnotationKind::AbsorbedVariants { position: file_position, data } => {
+            let line_index = snap.file_line_index(file_position.file_id)?;
+            let annotation_range = range(&line_index, annotation.range);
+            let url = url(snap, file_position.file_id);
+
+            let position = position(&line_index, file_position.offset);
+
+            let id = lsp_types::TextDocumentIdentifier { uri: url.clone() };
+
+            let doc_pos = lsp_types::TextDocumentPositionParams::new(id, position);
+
+            let command = data.map(|variants| {
+                let locations: Vec<lsp_types::Location> = variants
+                    .into_iter()
+                    .filter_map(|target| {
+                        location(
+                            snap,
+                            FileRange { file_id: target.file_id, range: target.full_range },
+                        )
+                        .ok()
+                    })
+                    .collect();
+
+                command::show_references(
+                    unhandled_variants_title(locations.len()),
+                    &url,
+                    position,
+                    locations,
+                )
+            });
+
+            Ok(lsp_types::CodeLens {
+                range: annotation_range,
+                command,
+                data: Some(
+                    to_value(lsp_ext::CodeLensResolveData::AbsorbedVariants(doc_pos)).unwrap(),
+                ),
+            })
+        }
     }
 }
 
@@ -1043,6 +1087,14 @@ pub(crate) fn reference_title(count: usize) -> String {
     }
 }
 
+pub(crate) fn unhandled_variants_title(count: usize) -> String {
+    if count == 1 {
+        "1 variant unhandled here".into()
+    } else {
+        format!("{} variants unhandled here", count)
+    }
+}
+
 pub(crate) fn markup_content(markup: Markup) -> lsp_types::MarkupContent {
     let value = crate::markdown::format_docs(markup.as_str());
     lsp_types::MarkupContent { kind: lsp_types::MarkupKind::Markdown, value }
@@ -1088,11 +1140,13 @@ mod tests {
                     add_call_argument_snippets: true,
                     snippet_cap: SnippetCap::new(true),
                     insert_use: InsertUseConfig { merge: None, prefix_kind: PrefixKind::Plain },
+                    time_budget: None,
                 },
                 ide_db::base_db::FilePosition { file_id, offset },
             )
             .unwrap()
             .unwrap()
+            .items
             .into_iter()
             .filter(|c| c.label().ends_with("arg"))
             .map(|c| completion_item(&line_index, c))
@@ -18,12 +18,12 @@ use lsp_types::{
     notification::DidOpenTextDocument,
     request::{
         CodeActionRequest, Completion, Formatting, GotoTypeDefinition, HoverRequest,
-        WillRenameFiles,
+        RangeFormatting, WillRenameFiles,
     },
     CodeActionContext, CodeActionParams, CompletionParams, DidOpenTextDocumentParams,
-    DocumentFormattingParams, FileRename, FormattingOptions, GotoDefinitionParams, HoverParams,
-    PartialResultParams, Position, Range, RenameFilesParams, TextDocumentItem,
-    TextDocumentPositionParams, WorkDoneProgressParams,
+    DocumentFormattingParams, DocumentRangeFormattingParams, FileRename, FormattingOptions,
+    GotoDefinitionParams, HoverParams, PartialResultParams, Position, Range, RenameFilesParams,
+    TextDocumentItem, TextDocumentPositionParams, WorkDoneProgressParams,
 };
 use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
 use serde_json::json;
@@ -264,6 +264,57 @@ pub use std::collections::HashMap;
     );
 }
 
+#[test]
+fn test_format_range() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+fn foo() {
+}
+
+fn main() {
+    let x=1;
+}
+"#,
+    )
+    .wait_until_workspace_is_loaded();
+
+    // Without `--file-lines`, the whole `main` function is formatted.
+    server.request::<RangeFormatting>(
+        DocumentRangeFormattingParams {
+            text_document: server.doc_id("src/lib.rs"),
+            range: Range::new(Position::new(4, 4), Position::new(4, 12)),
+            options: FormattingOptions {
+                tab_size: 4,
+                insert_spaces: false,
+                insert_final_newline: None,
+                trim_final_newlines: None,
+                trim_trailing_whitespace: None,
+                properties: HashMap::new(),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        },
+        json!([
+            {
+                "newText": " = ",
+                "range": {
+                    "end": { "character": 10, "line": 4 },
+                    "start": { "character": 9, "line": 4 }
+                }
+            }
+        ]),
+    );
+}
+
 #[test]
 fn test_format_document_unchanged() {
     if skip_slow_tests() {
@@ -74,6 +74,29 @@ pub fn replace(buf: &mut String, from: char, to: &str) {
     *buf = buf.replace(from, to)
 }
 
+/// The number of single-character insertions, deletions and substitutions needed to turn `a`
+/// into `b`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+    let mut row = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = prev_row[j] + if ca == cb { 0 } else { 1 };
+            row[j + 1] = substitution.min(prev_row[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut prev_row, &mut row);
+    }
+    prev_row[b.len()]
+}
+
+/// Whether `candidate` is close enough to `query` to be a plausible correction of a typo in it.
+pub fn is_typo_of(candidate: &str, query: &str) -> bool {
+    let max_distance = (query.chars().count() / 3).max(1);
+    edit_distance(candidate, query) <= max_distance
+}
+
 // https://github.com/rust-lang/rust/issues/74773
 pub fn split_once(haystack: &str, delim: char) -> Option<(&str, &str)> {
     let mut split = haystack.splitn(2, delim);
@@ -236,4 +259,14 @@ mod tests {
             "fn main() {\n    return 92;\n}\n"
         );
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("HashMap", "HashMap"), 0);
+        assert_eq!(edit_distance("HashMap", "HashMpa"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert!(is_typo_of("HashMap", "HashMpa"));
+        assert!(!is_typo_of("Vec", "Box"));
+    }
 }
@@ -203,6 +203,9 @@ pub fn expr_return(expr: Option<ast::Expr>) -> ast::Expr {
 pub fn expr_try(expr: ast::Expr) -> ast::Expr {
     expr_from_text(&format!("{}?", expr))
 }
+pub fn expr_await(expr: ast::Expr) -> ast::Expr {
+    expr_from_text(&format!("{}.await", expr))
+}
 pub fn expr_match(expr: ast::Expr, match_arm_list: ast::MatchArmList) -> ast::Expr {
     expr_from_text(&format!("match {} {}", expr, match_arm_list))
 }
@@ -397,6 +400,10 @@ pub fn ret_type(ty: ast::Type) -> ast::RetType {
     ast_from_text(&format!("fn f() -> {} {{ }}", ty))
 }
 
+pub fn self_param_ref() -> ast::SelfParam {
+    ast_from_text("fn f(&self) { }")
+}
+
 pub fn param_list(
     self_param: Option<ast::SelfParam>,
     pats: impl IntoIterator<Item = ast::Param>,
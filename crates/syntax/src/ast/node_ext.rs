@@ -88,6 +88,18 @@ impl NameOwner for Macro {
 
 impl AttrsOwner for Macro {}
 
+impl ast::Abi {
+    /// The ABI string, like `C` in `extern "C"`. `None` for a bare `extern`, which means `C`.
+    pub fn abi_string(&self) -> Option<String> {
+        let string = self
+            .syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find_map(ast::String::cast)?;
+        string.value().map(|it| it.into_owned())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AttrKind {
     Inner,
@@ -40,10 +40,11 @@ pub fn tokenize(text: &str) -> (Vec<Token>, Vec<SyntaxError>) {
         None => 0,
     };
 
-    let text_without_shebang = &text[offset..];
-
-    for rustc_token in rustc_lexer::tokenize(text_without_shebang) {
-        let token_len: TextSize = rustc_token.len.try_into().unwrap();
+    while offset < text.len() {
+        let rustc_token =
+            recover_unclosed_string(rustc_lexer::first_token(&text[offset..]), &text[offset..]);
+        let len = rustc_token.len;
+        let token_len: TextSize = len.try_into().unwrap();
         let token_range = TextRange::at(offset.try_into().unwrap(), token_len);
 
         let (syntax_kind, err_message) =
@@ -55,12 +56,85 @@ pub fn tokenize(text: &str) -> (Vec<Token>, Vec<SyntaxError>) {
             errors.push(SyntaxError::new(err_message, token_range));
         }
 
-        offset += rustc_token.len;
+        offset += len;
     }
 
     (tokens, errors)
 }
 
+/// Cuts string literals which most likely miss their closing quote at the end of their first
+/// line, as they would otherwise turn the code after them into the literal. These are the
+/// literals which aren't terminated at all, and the ones spanning lines which are closed by a
+/// quote looking like it opens a literal, like the one of `let s = "` or `f(r"`.
+fn recover_unclosed_string(
+    rustc_token: rustc_lexer::Token,
+    token_text: &str,
+) -> rustc_lexer::Token {
+    use rustc_lexer::LiteralKind::*;
+
+    let (kind, suffix_start) = match rustc_token.kind {
+        rustc_lexer::TokenKind::Literal { kind, suffix_start } => (kind, suffix_start),
+        _ => return rustc_token,
+    };
+    let first_line_len = match token_text[..rustc_token.len].find('\n') {
+        Some(it) => it,
+        None => return rustc_token,
+    };
+    let (literal, rest) = token_text.split_at(suffix_start);
+    let kind = match kind {
+        Str { terminated } if !terminated || closes_like_it_opens(literal, rest) => {
+            Str { terminated: false }
+        }
+        ByteStr { terminated } if !terminated || closes_like_it_opens(literal, rest) => {
+            ByteStr { terminated: false }
+        }
+        RawStr { n_hashes, err } if is_unclosed_raw(err, literal, rest) => {
+            RawStr { n_hashes, err: Some(no_terminator(n_hashes)) }
+        }
+        RawByteStr { n_hashes, err } if is_unclosed_raw(err, literal, rest) => {
+            RawByteStr { n_hashes, err: Some(no_terminator(n_hashes)) }
+        }
+        _ => return rustc_token,
+    };
+    rustc_lexer::Token {
+        kind: rustc_lexer::TokenKind::Literal { kind, suffix_start: first_line_len },
+        len: first_line_len,
+    }
+}
+
+fn is_unclosed_raw(err: Option<RawStrError>, literal: &str, rest: &str) -> bool {
+    match err {
+        None => closes_like_it_opens(literal, rest),
+        Some(RawStrError::NoTerminator { .. }) => true,
+        Some(_) => false,
+    }
+}
+
+fn no_terminator(n_hashes: u16) -> RawStrError {
+    RawStrError::NoTerminator {
+        expected: n_hashes.into(),
+        found: 0,
+        possible_terminator_offset: None,
+    }
+}
+
+/// Whether the closing quote of `literal` follows a `(`, `[`, `{`, `,` or `=` on its line, maybe
+/// along with the prefix of a raw or byte string, and the text after the literal continues it
+/// rather than the code around it.
+fn closes_like_it_opens(literal: &str, rest: &str) -> bool {
+    let closing_quote = match literal.rfind('"') {
+        Some(it) => it,
+        None => return false,
+    };
+    let line_start = literal[..closing_quote].rfind('\n').map_or(0, |it| it + 1);
+    let before = literal[line_start..closing_quote].trim_end_matches('#');
+    let before = ["br", "r", "b"].iter().find_map(|it| before.strip_suffix(it)).unwrap_or(before);
+    let opens = matches!(before.trim_end().chars().last(), Some(c) if "([{,=".contains(c));
+    let continues =
+        matches!(rest.chars().next(), Some(c) if !c.is_whitespace() && !")]},;.".contains(c));
+    opens && continues
+}
+
 /// Returns `SyntaxKind` and `Option<SyntaxError>` if `text` parses as a single token.
 ///
 /// Returns `None` if the string contains zero *or two or more* tokens.
@@ -0,0 +1,7 @@
+let s = "abc;
+let t = "x";
+let r = r"raw;
+f(r"y");
+let h = br#"hashed;
+let u = "multi
+line";
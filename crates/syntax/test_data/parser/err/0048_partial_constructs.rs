@@ -0,0 +1,12 @@
+fn f() {
+    println!("abc);
+}
+
+fn g() {
+    let s = S;
+    s.
+}
+
+fn h()
+
+fn i() {}
@@ -0,0 +1,9 @@
+fn f() {
+    let s = "abc;
+    let t = "x";
+    let r = r"raw;
+    g(r"y", b"z");
+    let h = r#"hashed;
+}
+
+fn g() {}
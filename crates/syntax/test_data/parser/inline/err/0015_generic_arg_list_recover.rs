@@ -0,0 +1 @@
+fn f(v: Vec<i32, ) { let x: Vec<i32, = 1; let y: Option<u8; }
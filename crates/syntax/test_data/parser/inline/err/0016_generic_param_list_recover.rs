@@ -0,0 +1,3 @@
+struct S<T, ;
+fn f<T: Clone, () {}
+fn g<T {}
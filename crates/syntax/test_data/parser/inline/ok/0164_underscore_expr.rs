@@ -0,0 +1,5 @@
+fn foo() {
+    let x = _;
+    _ = foo();
+    (_, b) = (1, 2);
+}
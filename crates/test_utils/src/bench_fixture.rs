@@ -40,3 +40,36 @@ pub fn numerous_macro_rules() -> String {
     let path = project_dir().join("bench_data/numerous_macro_rules");
     fs::read_to_string(&path).unwrap()
 }
+
+pub fn deep_generic_chains() -> String {
+    let n_fns = 10;
+    let depth = 60;
+
+    let mut buf = "
+#[lang = \"fn_once\"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+struct Wrapper<T>(T);
+
+impl<T> Wrapper<T> {
+    fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Wrapper<U> { loop {} }
+    fn zip<U>(self, other: Wrapper<U>) -> Wrapper<(T, U)> { loop {} }
+}
+"
+    .to_string();
+    for i in 0..n_fns {
+        format_to!(buf, "\nfn f{}() {{\n    let w = Wrapper({}u32)", i, i);
+        for j in 0..depth {
+            if j % 2 == 0 {
+                buf.push_str("\n        .map(|x| x)");
+            } else {
+                format_to!(buf, "\n        .zip(Wrapper({}u8))", j);
+            }
+        }
+        buf.push_str(";\n}\n");
+    }
+
+    buf
+}
@@ -14,6 +14,8 @@ pub struct Fixture {
     pub cfg_key_values: Vec<(String, String)>,
     pub edition: Option<String>,
     pub env: FxHashMap<String, String>,
+    /// Whether the file belongs to a library source root, like the sources of dependencies.
+    pub library: bool,
 }
 
 impl Fixture {
@@ -55,7 +57,7 @@ impl Fixture {
         res
     }
 
-    //- /lib.rs crate:foo deps:bar,baz cfg:foo=a,bar=b env:OUTDIR=path/to,OTHER=foo
+    //- /lib.rs crate:foo deps:bar,baz cfg:foo=a,bar=b env:OUTDIR=path/to,OTHER=foo library
     fn parse_meta_line(meta: &str) -> Fixture {
         assert!(meta.starts_with("//-"));
         let meta = meta["//-".len()..].trim();
@@ -70,7 +72,12 @@ impl Fixture {
         let mut cfg_atoms = Vec::new();
         let mut cfg_key_values = Vec::new();
         let mut env = FxHashMap::default();
+        let mut library = false;
         for component in components[1..].iter() {
+            if *component == "library" {
+                library = true;
+                continue;
+            }
             let (key, value) = split_once(component, ':').unwrap();
             match key {
                 "crate" => krate = Some(value.to_string()),
@@ -95,7 +102,17 @@ impl Fixture {
             }
         }
 
-        Fixture { path, text: String::new(), krate, deps, cfg_atoms, cfg_key_values, edition, env }
+        Fixture {
+            path,
+            text: String::new(),
+            krate,
+            deps,
+            cfg_atoms,
+            cfg_key_values,
+            edition,
+            env,
+            library,
+        }
     }
 }
 
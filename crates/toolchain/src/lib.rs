@@ -17,6 +17,14 @@ pub fn rustfmt() -> PathBuf {
     get_path_for_executable("rustfmt")
 }
 
+/// The directory cargo keeps its global config and caches in, `~/.cargo` by default.
+pub fn cargo_home() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("CARGO_HOME") {
+        return Some(path.into());
+    }
+    home::home_dir().map(|it| it.join(".cargo"))
+}
+
 /// Return a `PathBuf` to use for the given executable.
 ///
 /// E.g., `get_path_for_executable("cargo")` may return just `cargo` if that
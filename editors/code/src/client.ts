@@ -4,6 +4,7 @@ import * as ra from '../src/lsp_ext';
 import * as Is from 'vscode-languageclient/lib/common/utils/is';
 import { assert } from './util';
 import { WorkspaceEdit } from 'vscode';
+import { DependencySources, DEPENDENCY_SCHEME, toFileUri } from './dependencies';
 
 export interface Env {
     [name: string]: string;
@@ -23,7 +24,7 @@ function renderHoverActions(actions: ra.CommandLinkGroup[]): vscode.MarkdownStri
     return result;
 }
 
-export function createClient(serverPath: string, cwd: string, extraEnv: Env): lc.LanguageClient {
+export function createClient(serverPath: string, cwd: string, extraEnv: Env, dependencies: DependencySources): lc.LanguageClient {
     // '.' Is the fallback if no folder is open
     // TODO?: Workspace folders support Uri's (eg: file://test.txt).
     // It might be a good idea to test if the uri points to a file.
@@ -44,10 +45,19 @@ export function createClient(serverPath: string, cwd: string, extraEnv: Env): lc
     );
 
     const clientOptions: lc.LanguageClientOptions = {
-        documentSelector: [{ scheme: 'file', language: 'rust' }],
+        documentSelector: [
+            { scheme: 'file', language: 'rust' },
+            { scheme: DEPENDENCY_SCHEME, language: 'rust' },
+            // Manifests only get diagnostics and the completion of dependency features.
+            { scheme: 'file', pattern: '**/Cargo.toml' },
+        ],
         initializationOptions: vscode.workspace.getConfiguration("rust-analyzer"),
         diagnosticCollectionName: "rustc",
         traceOutputChannel,
+        uriConverters: {
+            code2Protocol: (uri: vscode.Uri) => toFileUri(uri).toString(),
+            protocol2Code: (value: string) => dependencies.toDependencyUri(vscode.Uri.parse(value)),
+        },
         middleware: {
             async provideHover(document: vscode.TextDocument, position: vscode.Position, token: vscode.CancellationToken, _next: lc.ProvideHoverSignature) {
                 return client.sendRequest(lc.HoverRequest.type, client.code2ProtocolConverter.asTextDocumentPositionParams(document, position), token).then(
@@ -79,9 +89,9 @@ export function createClient(serverPath: string, cwd: string, extraEnv: Env): lc
                     const result: (vscode.CodeAction | vscode.Command)[] = [];
                     const groups = new Map<string, { index: number; items: vscode.CodeAction[] }>();
                     for (const item of values) {
-                        // In our case we expect to get code edits only from diagnostics
+                        // In our case we expect to get code edits only from diagnostics,
+                        // and commands only from diagnostics of inactive code
                         if (lc.CodeAction.is(item)) {
-                            assert(!item.command, "We don't expect to receive commands in CodeActions");
                             const action = client.protocol2CodeConverter.asCodeAction(item);
                             result.push(action);
                             continue;
@@ -160,6 +170,8 @@ class ExperimentalFeatures implements lc.StaticFeature {
         caps.codeActionGroup = true;
         caps.hoverActions = true;
         caps.statusNotification = true;
+        caps.enableCargoFeatures = true;
+        caps.dependencySources = true;
         capabilities.experimental = caps;
     }
     initialize(_capabilities: lc.ServerCapabilities<any>, _documentSelector: lc.DocumentSelector | undefined): void {
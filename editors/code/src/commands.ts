@@ -250,6 +250,35 @@ export function ssr(ctx: Ctx): Cmd {
     };
 }
 
+export function searchByType(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!client) return;
+
+        const query = await vscode.window.showInputBox({
+            prompt: "Enter a signature like 'fn(&str) -> Option<_>', or a type like 'Vec<_>' to search the current file",
+        });
+        if (!query) return;
+
+        const textDocument = editor && client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document);
+        const locations = await client.sendRequest(ra.searchByType, { query, textDocument });
+        if (locations.length === 0) {
+            await vscode.window.showInformationMessage(`Nothing matches \`${query}\``);
+            return;
+        }
+
+        const uri = editor ? editor.document.uri : client.protocol2CodeConverter.asUri(locations[0].uri);
+        const position = editor ? editor.selection.active : client.protocol2CodeConverter.asPosition(locations[0].range.start);
+        await vscode.commands.executeCommand(
+            'editor.action.showReferences',
+            uri,
+            position,
+            locations.map(client.protocol2CodeConverter.asLocation),
+        );
+    };
+}
+
 export function serverVersion(ctx: Ctx): Cmd {
     return async () => {
         const { stdout } = spawnSync(ctx.serverPath, ["--version"], { encoding: "utf8" });
@@ -271,6 +300,16 @@ export function toggleInlayHints(ctx: Ctx): Cmd {
     };
 }
 
+export function enableCargoFeatures(ctx: Ctx): Cmd {
+    return async (features: string[]) => {
+        const enabled = ctx.config.cargoFeatures.filter(it => !features.includes(it));
+        await vscode
+            .workspace
+            .getConfiguration(`${ctx.config.rootSection}.cargo`)
+            .update('features', [...enabled, ...features], vscode.ConfigurationTarget.Workspace);
+    };
+}
+
 // Opens the virtual file that will show the syntax tree
 //
 // The contents of the file come from the `TextDocumentContentProvider`
@@ -107,6 +107,8 @@ export class Config {
             typeHints: this.get<boolean>("inlayHints.typeHints"),
             parameterHints: this.get<boolean>("inlayHints.parameterHints"),
             chainingHints: this.get<boolean>("inlayHints.chainingHints"),
+            panicHints: this.get<boolean>("inlayHints.panicHints"),
+            discriminantHints: this.get<boolean>("inlayHints.discriminantHints"),
             maxLength: this.get<null | number>("inlayHints.maxLength"),
         };
     }
@@ -117,6 +119,10 @@ export class Config {
         };
     }
 
+    get cargoFeatures() {
+        return this.get<string[]>("cargo.features");
+    }
+
     get cargoRunner() {
         return this.get<string | undefined>("cargoRunner");
     }
@@ -145,6 +151,7 @@ export class Config {
             implementations: this.get<boolean>("lens.implementations"),
             methodReferences: this.get<boolean>("lens.methodReferences"),
             references: this.get<boolean>("lens.references"),
+            wildcardArms: this.get<boolean>("lens.wildcardArms"),
         };
     }
 
@@ -6,6 +6,7 @@ import { Config } from './config';
 import { createClient } from './client';
 import { isRustEditor, RustEditor } from './util';
 import { Status } from './lsp_ext';
+import { DependencySources, DEPENDENCY_SCHEME } from './dependencies';
 
 export class Ctx {
     private constructor(
@@ -24,7 +25,13 @@ export class Ctx {
         serverPath: string,
         cwd: string,
     ): Promise<Ctx> {
-        const client = createClient(serverPath, cwd, config.serverExtraEnv);
+        const dependencies = new DependencySources();
+        extCtx.subscriptions.push(vscode.workspace.registerFileSystemProvider(
+            DEPENDENCY_SCHEME,
+            dependencies,
+            { isReadonly: true },
+        ));
+        const client = createClient(serverPath, cwd, config.serverExtraEnv, dependencies);
 
         const statusBar = vscode.window.createStatusBarItem(vscode.StatusBarAlignment.Left);
         extCtx.subscriptions.push(statusBar);
@@ -37,6 +44,7 @@ export class Ctx {
         res.pushCleanup(client.start());
         await client.onReady();
         client.onNotification(ra.status, (params) => res.setStatus(params.status));
+        client.onNotification(ra.dependencySources, (params) => dependencies.setRoots(params.roots));
         return res;
     }
 
@@ -0,0 +1,68 @@
+import * as vscode from 'vscode';
+
+/**
+ * The scheme of the sources of dependencies, like the ones in the cargo registry or in the
+ * sysroot. They are opened through a read-only file system, rooted at the same paths as the
+ * actual files, so that they can't be edited by mistake when navigating into them.
+ *
+ * The server only knows about `file` URIs: the URIs are converted in both directions by the
+ * `uriConverters` of the client.
+ */
+export const DEPENDENCY_SCHEME = 'rust-analyzer-dependency';
+
+export class DependencySources implements vscode.FileSystemProvider {
+    private roots: string[] = [];
+    private readonly changeEmitter = new vscode.EventEmitter<vscode.FileChangeEvent[]>();
+    readonly onDidChangeFile = this.changeEmitter.event;
+
+    /** Sets the directories which contain the sources of dependencies, as `file` URIs. */
+    setRoots(roots: string[]) {
+        this.roots = roots.map(root => root.endsWith('/') ? root : root + '/');
+    }
+
+    /** Converts the `file` URI of a dependency source to its read-only URI. */
+    toDependencyUri(uri: vscode.Uri): vscode.Uri {
+        if (uri.scheme !== 'file') return uri;
+        const value = uri.toString();
+        return this.roots.some(root => value.startsWith(root))
+            ? uri.with({ scheme: DEPENDENCY_SCHEME })
+            : uri;
+    }
+
+    watch(): vscode.Disposable {
+        return new vscode.Disposable(() => { });
+    }
+
+    stat(uri: vscode.Uri): Thenable<vscode.FileStat> {
+        return vscode.workspace.fs.stat(toFileUri(uri));
+    }
+
+    readDirectory(uri: vscode.Uri): Thenable<[string, vscode.FileType][]> {
+        return vscode.workspace.fs.readDirectory(toFileUri(uri));
+    }
+
+    readFile(uri: vscode.Uri): Thenable<Uint8Array> {
+        return vscode.workspace.fs.readFile(toFileUri(uri));
+    }
+
+    createDirectory(uri: vscode.Uri): void {
+        throw vscode.FileSystemError.NoPermissions(uri);
+    }
+
+    writeFile(uri: vscode.Uri): void {
+        throw vscode.FileSystemError.NoPermissions(uri);
+    }
+
+    delete(uri: vscode.Uri): void {
+        throw vscode.FileSystemError.NoPermissions(uri);
+    }
+
+    rename(oldUri: vscode.Uri): void {
+        throw vscode.FileSystemError.NoPermissions(oldUri);
+    }
+}
+
+/** Converts the read-only URI of a dependency source back to the URI of the actual file. */
+export function toFileUri(uri: vscode.Uri): vscode.Uri {
+    return uri.scheme === DEPENDENCY_SCHEME ? uri.with({ scheme: 'file' }) : uri;
+}
@@ -12,7 +12,9 @@ export function activateInlayHints(ctx: Ctx) {
         async onConfigChange() {
             const anyEnabled = ctx.config.inlayHints.typeHints
                 || ctx.config.inlayHints.parameterHints
-                || ctx.config.inlayHints.chainingHints;
+                || ctx.config.inlayHints.chainingHints
+                || ctx.config.inlayHints.panicHints
+                || ctx.config.inlayHints.discriminantHints;
             const enabled = ctx.config.inlayHints.enable && anyEnabled;
 
             if (!enabled) return this.dispose();
@@ -42,14 +44,18 @@ export function activateInlayHints(ctx: Ctx) {
 const typeHints = createHintStyle("type");
 const paramHints = createHintStyle("parameter");
 const chainingHints = createHintStyle("chaining");
+const panicHints = createHintStyle("panic");
+const discriminantHints = createHintStyle("discriminant");
 
-function createHintStyle(hintKind: "type" | "parameter" | "chaining") {
+function createHintStyle(hintKind: "type" | "parameter" | "chaining" | "panic" | "discriminant") {
     // U+200C is a zero-width non-joiner to prevent the editor from forming a ligature
     // between code and type hints
     const [pos, render] = ({
         type: ["after", (label: string) => `\u{200c}: ${label}`],
         parameter: ["before", (label: string) => `${label}: `],
         chaining: ["after", (label: string) => `\u{200c}: ${label}`],
+        panic: ["after", (label: string) => `\u{200c} ${label}`],
+        discriminant: ["after", (label: string) => `\u{200c} ${label}`],
     } as const)[hintKind];
 
     const fg = new vscode.ThemeColor(`rust_analyzer.inlayHints.foreground.${hintKind}Hints`);
@@ -164,10 +170,12 @@ class HintsUpdater implements Disposable {
         editor.setDecorations(typeHints.decorationType, decorations.type);
         editor.setDecorations(paramHints.decorationType, decorations.param);
         editor.setDecorations(chainingHints.decorationType, decorations.chaining);
+        editor.setDecorations(panicHints.decorationType, decorations.panic);
+        editor.setDecorations(discriminantHints.decorationType, decorations.discriminant);
     }
 
     private hintsToDecorations(hints: ra.InlayHint[]): InlaysDecorations {
-        const decorations: InlaysDecorations = { type: [], param: [], chaining: [] };
+        const decorations: InlaysDecorations = { type: [], param: [], chaining: [], panic: [], discriminant: [] };
         const conv = this.ctx.client.protocol2CodeConverter;
 
         for (const hint of hints) {
@@ -184,6 +192,14 @@ class HintsUpdater implements Disposable {
                     decorations.chaining.push(chainingHints.toDecoration(hint, conv));
                     continue;
                 }
+                case ra.InlayHint.Kind.PanicHint: {
+                    decorations.panic.push(panicHints.toDecoration(hint, conv));
+                    continue;
+                }
+                case ra.InlayHint.Kind.DiscriminantHint: {
+                    decorations.discriminant.push(discriminantHints.toDecoration(hint, conv));
+                    continue;
+                }
             }
         }
         return decorations;
@@ -211,6 +227,8 @@ interface InlaysDecorations {
     type: vscode.DecorationOptions[];
     param: vscode.DecorationOptions[];
     chaining: vscode.DecorationOptions[];
+    panic: vscode.DecorationOptions[];
+    discriminant: vscode.DecorationOptions[];
 }
 
 interface RustSourceFile {
@@ -16,6 +16,11 @@ export interface StatusParams {
 }
 export const status = new lc.NotificationType<StatusParams>("rust-analyzer/status");
 
+export interface DependencySourcesParams {
+    roots: string[];
+}
+export const dependencySources = new lc.NotificationType<DependencySourcesParams>("rust-analyzer/dependencySources");
+
 export const reloadWorkspace = new lc.RequestType0<null, void>("rust-analyzer/reloadWorkspace");
 
 export interface SyntaxTreeParams {
@@ -72,13 +77,15 @@ export interface Runnable {
 }
 export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>("experimental/runnables");
 
-export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint;
+export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint | InlayHint.PanicHint | InlayHint.DiscriminantHint;
 
 export namespace InlayHint {
     export const enum Kind {
         TypeHint = "TypeHint",
         ParamHint = "ParameterHint",
         ChainingHint = "ChainingHint",
+        PanicHint = "PanicHint",
+        DiscriminantHint = "DiscriminantHint",
     }
     interface Common {
         range: lc.Range;
@@ -87,6 +94,8 @@ export namespace InlayHint {
     export type TypeHint = Common & { kind: Kind.TypeHint };
     export type ParamHint = Common & { kind: Kind.ParamHint };
     export type ChainingHint = Common & { kind: Kind.ChainingHint };
+    export type PanicHint = Common & { kind: Kind.PanicHint };
+    export type DiscriminantHint = Common & { kind: Kind.DiscriminantHint };
 }
 export interface InlayHintsParams {
     textDocument: lc.TextDocumentIdentifier;
@@ -102,6 +111,12 @@ export interface SsrParams {
 }
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>('experimental/ssr');
 
+export interface SearchByTypeParams {
+    query: string;
+    textDocument?: lc.TextDocumentIdentifier;
+}
+export const searchByType = new lc.RequestType<SearchByTypeParams, lc.Location[], void>('experimental/searchByType');
+
 export interface CommandLink extends lc.Command {
     /**
      * A tooltip for the command, when represented in the UI.
@@ -118,8 +118,10 @@ async function tryActivate(context: vscode.ExtensionContext) {
     ctx.registerCommand('onEnter', commands.onEnter);
 
     ctx.registerCommand('ssr', commands.ssr);
+    ctx.registerCommand('searchByType', commands.searchByType);
     ctx.registerCommand('serverVersion', commands.serverVersion);
     ctx.registerCommand('toggleInlayHints', commands.toggleInlayHints);
+    ctx.registerCommand('enableCargoFeatures', commands.enableCargoFeatures);
 
     // Internal commands which are invoked by the server.
     ctx.registerCommand('runSingle', commands.runSingle);
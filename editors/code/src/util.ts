@@ -3,6 +3,7 @@ import * as vscode from "vscode";
 import { strict as nativeAssert } from "assert";
 import { spawnSync } from "child_process";
 import { inspect } from "util";
+import { DEPENDENCY_SCHEME } from "./dependencies";
 
 export function assert(condition: boolean, explanation: string): asserts condition {
     try {
@@ -101,7 +102,8 @@ export function isRustDocument(document: vscode.TextDocument): document is RustD
     // by allowing only `file` schemes
     // unfortunately extensions that use diff views not always set this
     // to something different than 'file' (see ongoing bug: #4608)
-    return document.languageId === 'rust' && document.uri.scheme === 'file';
+    return document.languageId === 'rust' &&
+        (document.uri.scheme === 'file' || document.uri.scheme === DEPENDENCY_SCHEME);
 }
 
 export function isRustEditor(editor: vscode.TextEditor): editor is RustEditor {
@@ -160,6 +160,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "RECORD_EXPR_FIELD",
         "EFFECT_EXPR",
         "BOX_EXPR",
+        "UNDERSCORE_EXPR",
         // postfix
         "CALL_EXPR",
         "INDEX_EXPR",
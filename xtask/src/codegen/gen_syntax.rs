@@ -10,7 +10,7 @@ use std::{
 
 use proc_macro2::{Punct, Spacing};
 use quote::{format_ident, quote};
-use ungrammar::{rust_grammar, Grammar, Rule};
+use ungrammar::{Grammar, Rule};
 
 use crate::{
     ast_src::{AstEnumSrc, AstNodeSrc, AstSrc, Cardinality, Field, KindsSrc, KINDS_SRC},
@@ -19,7 +19,7 @@ use crate::{
 };
 
 pub fn generate_syntax(mode: Mode) -> Result<()> {
-    let grammar = rust_grammar();
+    let grammar = include_str!("rust.ungram").parse::<Grammar>()?;
     let ast = lower(&grammar);
 
     let syntax_kinds_file = project_root().join("crates/parser/src/syntax_kind/generated.rs");
@@ -254,6 +254,10 @@ fn check_todo(path: &Path, text: &str) {
         // Some of our assists generate `todo!()`.
         "handlers/add_turbo_fish.rs",
         "handlers/generate_function.rs",
+        // Typed holes are `todo!()`s, which are reported and filled in.
+        "diagnostics/typed_hole.rs",
+        "handlers/fill_expression.rs",
+        "helpers/term_search.rs",
         // To support generating `todo!()` in assists, we have `expr_todo()` in
         // `ast::make`.
         "ast/make.rs",